@@ -2,7 +2,12 @@
 //!
 //! This module defines all CLI arguments and subcommands using clap.
 
+use crate::cli::output::OutputFormat;
+use crate::core::config::Config;
+use crate::core::manifest::ManifestFormat;
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
+use clap_complete::Shell;
 use std::path::PathBuf;
 
 /// Parse a path string, normalizing separators for the current platform
@@ -19,6 +24,10 @@ fn parse_path(s: &str) -> Result<PathBuf, String> {
     }
 }
 
+/// Accepted values for `--duplicate-action` (see `DuplicateStrategy`)
+const DUPLICATE_STRATEGIES: [&str; 5] =
+    ["skip", "rename", "overwrite", "keep_newest", "keep_largest"];
+
 /// A fast, reliable tool to extract photos from iOS devices (iPhone/iPad) on Windows
 #[derive(Parser, Debug)]
 #[command(name = "photo_extraction_tool")]
@@ -53,6 +62,56 @@ pub struct Args {
     #[arg(short, long)]
     pub skip_existing: Option<bool>,
 
+    /// Only extract files dated on or after this day, YYYY-MM-DD (overrides config)
+    #[arg(long, value_name = "DATE")]
+    pub date_from: Option<NaiveDate>,
+
+    /// Only extract files dated on or before this day, YYYY-MM-DD (overrides config)
+    #[arg(long, value_name = "DATE")]
+    pub date_to: Option<NaiveDate>,
+
+    /// Limit copy speed in bytes per second, e.g. for background runs (overrides config)
+    #[arg(long, value_name = "BYTES")]
+    pub max_bytes_per_sec: Option<u64>,
+
+    /// Show what would be extracted without copying anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Keep a checkpoint in the output folder and resume an interrupted
+    /// extraction from it (overrides config)
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Record extracted files in this SQLite database instead of the tracking
+    /// file (overrides config)
+    #[arg(long, value_name = "FILE", value_parser = parse_path)]
+    pub tracking_db: Option<PathBuf>,
+
+    /// Write a manifest of every processed file to this path (overrides config)
+    #[arg(long, value_name = "FILE", value_parser = parse_path)]
+    pub manifest: Option<PathBuf>,
+
+    /// Manifest format: json or csv (overrides config)
+    #[arg(long, value_name = "FORMAT", value_parser = ["json", "csv"])]
+    pub manifest_format: Option<String>,
+
+    /// Append a line for every processed file to this JSON Lines log (overrides config)
+    #[arg(long, value_name = "FILE", value_parser = parse_path)]
+    pub append_to_log: Option<PathBuf>,
+
+    /// Write an HTML report of the extracted files to this path (overrides config)
+    #[arg(long, value_name = "FILE", value_parser = parse_path)]
+    pub report_html: Option<PathBuf>,
+
+    /// Extract into a ZIP archive at this path instead of the output folder (overrides config)
+    #[arg(long, value_name = "FILE", value_parser = parse_path)]
+    pub zip: Option<PathBuf>,
+
+    /// Open the output folder once extraction finishes (overrides config)
+    #[arg(long)]
+    pub open_folder: bool,
+
     /// Enable duplicate detection using SHA256 hashing
     #[arg(long)]
     pub detect_duplicates: bool,
@@ -61,10 +120,16 @@ pub struct Args {
     #[arg(long = "compare-to", value_name = "FOLDER", value_parser = parse_path)]
     pub compare_folders: Vec<PathBuf>,
 
-    /// Action to take when a duplicate is found: skip, rename, or overwrite
-    #[arg(long, value_name = "ACTION", value_parser = ["skip", "rename", "overwrite"])]
+    /// Action to take when a duplicate is found: skip, rename, overwrite,
+    /// keep_newest or keep_largest
+    #[arg(long, value_name = "ACTION", value_parser = DUPLICATE_STRATEGIES)]
     pub duplicate_action: Option<String>,
 
+    /// Write a report of every duplicate found to this path; a .csv file gets
+    /// CSV, anything else JSON (overrides config)
+    #[arg(long, value_name = "FILE", value_parser = parse_path)]
+    pub duplicate_report: Option<PathBuf>,
+
     /// List all MTP-compatible devices (not just Apple/iOS devices)
     #[arg(long)]
     pub all_devices: bool,
@@ -72,6 +137,90 @@ pub struct Args {
     /// Log level: error, warn, info, debug, trace (overrides config)
     #[arg(short, long)]
     pub log_level: Option<String>,
+
+    /// Output format for list, scan and extract: text or json
+    ///
+    /// JSON output is a single versioned document on stdout; logs stay on
+    /// stderr and progress bars are hidden.
+    #[arg(long, global = true, value_name = "FORMAT", default_value = "text")]
+    pub format: OutputFormat,
+}
+
+impl Args {
+    /// Apply the flags that override config values to `config`
+    pub fn apply_overrides(&self, config: &mut Config) {
+        if let Some(ref output) = self.output {
+            config.output.directory = output.clone();
+        }
+        if let Some(ref device_id) = self.device_id {
+            config.device.device_id = Some(device_id.clone());
+        }
+        if let Some(dcim_only) = self.dcim_only {
+            config.extraction.dcim_only = dcim_only;
+        }
+        if let Some(preserve) = self.preserve_structure {
+            config.output.preserve_structure = preserve;
+        }
+        if let Some(skip) = self.skip_existing {
+            config.output.skip_existing = skip;
+        }
+        if let Some(from) = self.date_from {
+            config.extraction.date_from = Some(from);
+        }
+        if let Some(to) = self.date_to {
+            config.extraction.date_to = Some(to);
+        }
+        if let Some(rate) = self.max_bytes_per_sec {
+            config.extraction.max_bytes_per_sec = Some(rate);
+        }
+        if self.dry_run {
+            config.extraction.dry_run = true;
+        }
+        if self.resume {
+            config.extraction.resume_from_checkpoint = true;
+        }
+        if let Some(ref path) = self.tracking_db {
+            config.tracking.database = Some(path.clone());
+        }
+        if let Some(ref path) = self.manifest {
+            config.output.manifest_path = Some(path.clone());
+        }
+        if let Some(ref format) = self.manifest_format {
+            config.output.manifest_format = match format.as_str() {
+                "csv" => ManifestFormat::Csv,
+                _ => ManifestFormat::Json,
+            };
+        }
+        if let Some(ref path) = self.append_to_log {
+            config.output.append_to_log = Some(path.clone());
+        }
+        if let Some(ref path) = self.report_html {
+            config.output.report_html = Some(path.clone());
+        }
+        if let Some(ref path) = self.zip {
+            config.output.zip_path = Some(path.clone());
+        }
+        if self.open_folder {
+            config.output.open_folder_after_extraction = true;
+        }
+        if let Some(ref path) = self.duplicate_report {
+            let is_csv = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+            config.duplicate_detection.report_path = Some(path.clone());
+            config.duplicate_detection.report_format = if is_csv {
+                ManifestFormat::Csv
+            } else {
+                ManifestFormat::Json
+            };
+        }
+        if let Some(ref level) = self.log_level {
+            config.logging.level = level.clone();
+        }
+        if self.all_devices {
+            config.device.apple_only = false;
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -86,8 +235,9 @@ pub enum Commands {
         #[arg(long = "compare-to", value_name = "FOLDER", value_parser = parse_path)]
         compare_folders: Vec<PathBuf>,
 
-        /// Action to take when a duplicate is found: skip, rename, or overwrite
-        #[arg(long, value_name = "ACTION", value_parser = ["skip", "rename", "overwrite"])]
+        /// Action to take when a duplicate is found: skip, rename, overwrite,
+        /// keep_newest or keep_largest
+        #[arg(long, value_name = "ACTION", value_parser = DUPLICATE_STRATEGIES)]
         duplicate_action: Option<String>,
     },
 
@@ -125,11 +275,42 @@ pub enum Commands {
     /// Show current configuration
     ShowConfig,
 
+    /// Write the configuration that actually takes effect to a TOML file
+    ///
+    /// The config file, PET_* environment variables, command-line flags and
+    /// the selected device's profile are merged in that order, and each value
+    /// one of them set is preceded by a comment naming it.
+    ExportEffectiveConfig {
+        /// File to write the merged configuration to
+        #[arg(short, long, value_parser = parse_path)]
+        output: PathBuf,
+    },
+
+    /// Print a shell completion script to stdout
+    ///
+    /// For example, in PowerShell:
+    /// `photo_extraction_tool completions powershell >> $PROFILE`
+    Completions {
+        /// Shell to generate completions for (bash, zsh, fish, powershell, elvish)
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
     /// Scan device and show folder structure (for debugging)
     Scan {
         /// Maximum depth to scan (0 = unlimited)
         #[arg(short, long, default_value = "3")]
         depth: usize,
+
+        /// Record the full device tree (names, sizes, dates, object IDs; no file
+        /// bytes) to a JSON fixture that the test database can replay
+        #[arg(long, value_name = "PATH")]
+        record: Option<PathBuf>,
+
+        /// Save the scanned tree (names, sizes, types, object IDs) to a file:
+        /// a nested list for .md files, JSON for anything else
+        #[arg(long, value_name = "PATH", value_parser = parse_path)]
+        export: Option<PathBuf>,
     },
 
     /// List all configured device profiles
@@ -153,6 +334,109 @@ pub enum Commands {
         name: String,
     },
 
+    /// Rename a device profile without changing its device association
+    RenameProfile {
+        /// Name or partial device ID of the profile to rename
+        #[arg(short, long)]
+        id: String,
+
+        /// New display name for the profile
+        #[arg(short, long)]
+        new_name: String,
+
+        /// Also move the profile to this output folder (renamed on disk)
+        #[arg(short, long)]
+        folder: Option<String>,
+    },
+
+    /// Export all device profiles to a file for use on another machine
+    ///
+    /// Files ending in .toml are written as TOML, anything else as JSON.
+    ExportProfiles {
+        /// File to write the profiles to
+        #[arg(short, long, value_parser = parse_path)]
+        output: PathBuf,
+    },
+
+    /// Import device profiles from a file written by export-profiles
+    ///
+    /// By default profiles are merged by device ID and existing profiles are
+    /// kept.
+    ImportProfiles {
+        /// File to read the profiles from
+        #[arg(short, long, value_parser = parse_path)]
+        input: PathBuf,
+
+        /// Replace all existing profiles instead of merging
+        #[arg(long, conflicts_with = "overwrite")]
+        replace: bool,
+
+        /// When merging, overwrite profiles for devices that already have one
+        #[arg(long)]
+        overwrite: bool,
+    },
+
+    /// Wait for devices and extract from each one automatically when it connects
+    ///
+    /// Runs until Ctrl+C. Each device is extracted to its profile folder, and a
+    /// device must stay connected for the debounce period before extraction
+    /// starts. Use --device-id to watch for one specific device.
+    Watch {
+        /// Exit after the first successful extraction
+        #[arg(long)]
+        once: bool,
+
+        /// Milliseconds a device must stay connected before extraction starts
+        #[arg(long, value_name = "MS", default_value = "2000")]
+        debounce_ms: u64,
+    },
+
+    /// Forget tracked files that have been deleted from the output folder
+    ///
+    /// Pruned files are no longer considered extracted, so they will be copied
+    /// again on the next extraction.
+    PruneTracking {
+        /// Folder holding the tracking file (defaults to configured output directory)
+        #[arg(short, long, value_parser = parse_path)]
+        directory: Option<PathBuf>,
+
+        /// Use this SQLite tracking database instead of the JSON tracking file
+        /// (defaults to tracking.database, if set)
+        #[arg(long, value_name = "FILE", value_parser = parse_path)]
+        database: Option<PathBuf>,
+    },
+
+    /// Re-hash extracted files and report any that are missing or changed
+    VerifyTracking {
+        /// Folder holding the tracking file (defaults to configured output directory)
+        #[arg(short, long, value_parser = parse_path)]
+        directory: Option<PathBuf>,
+
+        /// Use this SQLite tracking database instead of the JSON tracking file
+        /// (defaults to tracking.database, if set)
+        #[arg(long, value_name = "FILE", value_parser = parse_path)]
+        database: Option<PathBuf>,
+
+        /// Only check this many files, spread evenly across the tracking data
+        #[arg(long, value_name = "N")]
+        sample: Option<usize>,
+    },
+
+    /// Show what is new on the device since the last backup, without copying
+    ///
+    /// Every media file is compared with the tracking file in the device's
+    /// output folder and reported as new, already extracted, or changed.
+    Plan {
+        /// Also re-read and hash files that were already extracted, to catch
+        /// edits that kept the same size (slower)
+        #[arg(long)]
+        hash: bool,
+
+        /// List every new and changed file, not just the totals
+        #[arg(long)]
+        list: bool,
+    },
+
     /// Benchmark scan performance - shows detailed discovery statistics
     BenchmarkScan {
         /// Whether to only scan DCIM folder (faster)
@@ -199,11 +483,13 @@ pub enum TestCommands {
         verbose: bool,
     },
 
-    /// Run tests filtered by tag
+    /// Run tests carrying any of the given tags
     RunTag {
-        /// Tag to filter scenarios by
-        /// Available tags: device, error, duplicate, structure, tracking, profile, performance, stress-test, edge-case
-        tag: String,
+        /// Tags to filter scenarios by (comma-separated or multiple values)
+        /// Common tags: device, error, duplicate, structure, tracking, profile,
+        /// performance, stress-test, edge-case (see `test list-tags` for all of them)
+        #[arg(value_delimiter = ',', required = true)]
+        tags: Vec<String>,
 
         /// Verbose output
         #[arg(short, long)]
@@ -277,8 +563,8 @@ pub enum TestCommands {
         #[arg(short, long, value_parser = parse_path)]
         output: PathBuf,
 
-        /// Number of files to generate
-        #[arg(short, long, default_value = "100")]
+        /// Number of files to generate (`-c` is taken by the global --config)
+        #[arg(short = 'n', long, default_value = "100")]
         count: usize,
 
         /// Types of files to generate (comma-separated)
@@ -2,25 +2,44 @@
 //!
 //! This module contains the implementation of all CLI commands.
 
-use crate::cli::progress::{BenchmarkProgress, ScanProgressTracker};
+use crate::cli::output::{
+    build_scan_tree, write_json, DeviceEntry, DeviceExtraction, DeviceListDocument,
+    ExtractDocument, OutputFormat, ScanDocument, TreeExportFormat, TreeExporter,
+};
+use crate::cli::progress::{BenchmarkProgress, IndicatifSink, ScanProgressTracker};
+use crate::cli::watch::{run_watch, WatchOptions};
 use crate::cli::{Args, Commands, TestCommands};
+use crate::core::archive::Destination;
 use crate::core::config::{
-    get_config_path, init_config, open_config_in_editor, Config, TrackingConfig,
+    get_config_path, init_config, open_config_in_editor, Config, ConfigSource, EffectiveConfig,
+    TrackingConfig,
 };
+use crate::core::discovery::{discover_folders, DiscoveredFile, DiscoveryRoot, ScanOrder};
 use crate::core::extractor::{self, ExtractionStats};
+use crate::core::generic_extractor::{NullSink, ProgressSink};
+use crate::core::media;
+use crate::core::plan::PlanStatus;
+use crate::core::retry::{Retrier, RetryPolicy};
 use crate::core::setup::run_setup_wizard;
-use crate::core::tracking::scan_for_profiles;
+use crate::core::tracking::{
+    scan_for_profiles, SqliteStore, StateTracker, TrackingStoreTrait, VerifyIssue,
+};
+use crate::core::util::reveal_in_explorer;
 use crate::device::traits::{DeviceContentTrait, DeviceManagerTrait};
 use crate::device::{self, DeviceInfo, ProfileManager};
 use crate::testdb::{
-    self, InteractiveTestMode, MockDataGenerator, ScenarioLibrary, TestRunner, TestRunnerConfig,
+    self, DeviceFixture, InteractiveTestMode, MockDataGenerator, ScenarioLibrary, TestRunner,
+    TestRunnerConfig,
 };
+use crate::ui::device_monitor::{DeviceMonitor, MonitorConfig};
 use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::Shell;
 use indicatif::{ProgressBar, ProgressStyle};
 use log::{debug, error, info, warn};
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread;
@@ -29,6 +48,8 @@ use std::time::{Duration, Instant};
 /// Result of extraction from a single device (for parallel extraction reporting)
 #[derive(Debug, Clone)]
 struct DeviceExtractionResult {
+    /// Device ID, used to update its profile once every device is done
+    device_id: String,
     /// Device friendly name
     device_name: String,
     /// Profile name (if available)
@@ -39,6 +60,8 @@ struct DeviceExtractionResult {
     stats: Option<ExtractionStats>,
     /// Error message if extraction failed
     error: Option<String>,
+    /// Whether the extraction was cancelled before it finished
+    cancelled: bool,
     /// Duration of extraction
     duration: Duration,
 }
@@ -113,13 +136,30 @@ pub fn run_command(args: &Args, config: &Config, shutdown_flag: Arc<AtomicBool>)
         }
         Some(Commands::List { all }) => {
             let use_all = *all || !config.device.apple_only;
-            list_devices(use_all)?;
+            list_devices(use_all, args.format)?;
         }
         Some(Commands::ShowConfig) => {
             show_config(&config);
         }
-        Some(Commands::Scan { depth }) => {
-            scan_device(&config, *depth)?;
+        Some(Commands::ExportEffectiveConfig { output }) => {
+            let effective = effective_config(args, |name| std::env::var(name).ok())?;
+            export_effective_config(&effective, output)?;
+        }
+        Some(Commands::Completions { shell }) => {
+            write_completions(*shell, &mut io::stdout())?;
+        }
+        Some(Commands::Scan {
+            depth,
+            record,
+            export,
+        }) => {
+            scan_device(
+                &config,
+                *depth,
+                record.as_deref(),
+                export.as_deref(),
+                args.format,
+            )?;
         }
         Some(Commands::Extract {
             detect_duplicates,
@@ -141,6 +181,7 @@ pub fn run_command(args: &Args, config: &Config, shutdown_flag: Arc<AtomicBool>)
                 folders,
                 action,
                 args.all_devices,
+                args.format,
             )?;
         }
         None => {
@@ -152,6 +193,7 @@ pub fn run_command(args: &Args, config: &Config, shutdown_flag: Arc<AtomicBool>)
                 args.compare_folders.clone(),
                 args.duplicate_action.clone(),
                 args.all_devices,
+                args.format,
             )?;
         }
         Some(Commands::ListProfiles) => {
@@ -163,8 +205,49 @@ pub fn run_command(args: &Args, config: &Config, shutdown_flag: Arc<AtomicBool>)
         Some(Commands::RemoveProfile { name }) => {
             remove_profile(&config, name)?;
         }
+        Some(Commands::RenameProfile {
+            id,
+            new_name,
+            folder,
+        }) => {
+            rename_profile(&config, id, new_name, folder.as_deref())?;
+        }
+        Some(Commands::ExportProfiles { output }) => {
+            export_profiles(&config, output)?;
+        }
+        Some(Commands::ImportProfiles {
+            input,
+            replace,
+            overwrite,
+        }) => {
+            import_profiles(&config, input, !*replace, *overwrite)?;
+        }
+        Some(Commands::Watch { once, debounce_ms }) => {
+            watch_devices(
+                &config,
+                shutdown_flag,
+                *once,
+                Duration::from_millis(*debounce_ms),
+            )?;
+        }
+        Some(Commands::PruneTracking {
+            directory,
+            database,
+        }) => {
+            prune_tracking(&config, directory.clone(), database.clone())?;
+        }
+        Some(Commands::VerifyTracking {
+            directory,
+            database,
+            sample,
+        }) => {
+            verify_tracking(&config, directory.clone(), database.clone(), *sample)?;
+        }
+        Some(Commands::Plan { hash, list }) => {
+            plan_extraction(&config, *hash, *list, args.format)?;
+        }
         Some(Commands::BenchmarkScan { dcim_only }) => {
-            benchmark_scan(&config, *dcim_only)?;
+            benchmark_scan(&config, *dcim_only, shutdown_flag)?;
         }
         Some(Commands::Test { test_command }) => {
             handle_test_command(test_command)?;
@@ -181,7 +264,10 @@ fn check_and_run_setup_if_needed(args: &Args, config: &Config) -> Result<Config>
     // Setup is only needed for extraction commands
     let needs_extraction = matches!(
         &args.command,
-        None | Some(Commands::Extract { .. }) | Some(Commands::Scan { .. })
+        None | Some(Commands::Extract { .. })
+            | Some(Commands::Scan { .. })
+            | Some(Commands::Plan { .. })
+            | Some(Commands::Watch { .. })
     );
 
     if !needs_extraction {
@@ -202,6 +288,13 @@ fn check_and_run_setup_if_needed(args: &Args, config: &Config) -> Result<Config>
         return Ok(config);
     }
 
+    // The wizard is interactive, so scripted runs must configure up front
+    if args.format.is_json() {
+        anyhow::bail!(
+            "Initial setup required. Pass --output or run 'photo_extraction_tool config' first."
+        );
+    }
+
     // Need to run setup wizard
     info!("Initial setup required...");
 
@@ -233,8 +326,8 @@ pub fn handle_test_command(test_command: &TestCommands) -> Result<()> {
         TestCommands::RunQuick { verbose } => {
             test_run_quick(*verbose)?;
         }
-        TestCommands::RunTag { tag, verbose } => {
-            test_run_by_tag(tag, *verbose)?;
+        TestCommands::RunTag { tags, verbose } => {
+            test_run_tagged(tags, *verbose)?;
         }
         TestCommands::Run { scenarios, verbose } => {
             test_run_scenarios(scenarios, *verbose)?;
@@ -382,29 +475,264 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
-/// Remove a device profile by name or partial ID
-pub fn remove_profile(config: &Config, name: &str) -> Result<()> {
-    let mut manager = ProfileManager::new(&config.device_profiles);
-    manager.load()?;
+/// Open the tracking data for `directory`, preferring a SQLite database if
+/// given or configured
+fn open_tracking_store(
+    config: &Config,
+    directory: &Path,
+    database: Option<PathBuf>,
+) -> Result<Box<dyn TrackingStoreTrait>> {
+    let database = database.or_else(|| config.tracking.database_path(directory));
+    let store: Box<dyn TrackingStoreTrait> = match database {
+        Some(path) => {
+            if !path.exists() {
+                anyhow::bail!("Tracking database does not exist: {}", path.display());
+            }
+            Box::new(SqliteStore::open(&path)?)
+        }
+        None => Box::new(StateTracker::open_existing(&config.tracking, directory)?),
+    };
+    Ok(store)
+}
 
-    // Find profile by name or partial device ID
-    let profiles = manager.get_all_profiles().clone();
-    let mut found_id: Option<String> = None;
+/// Handle the `prune-tracking` command - forget files deleted from the output folder
+pub fn prune_tracking(
+    config: &Config,
+    directory: Option<PathBuf>,
+    database: Option<PathBuf>,
+) -> Result<()> {
+    let directory = directory.unwrap_or_else(|| config.output.directory.clone());
+    let mut store = open_tracking_store(config, &directory, database)?;
+
+    info!("Checking tracked files in: {}", directory.display());
+    let report = store.prune_missing(&directory)?;
+    for object_id in &report.removed {
+        debug!("Pruned: {}", object_id);
+    }
+
+    println!();
+    println!("  Checked:   {} files", report.checked);
+    println!(
+        "  Pruned:    {} files no longer on disk",
+        report.removed.len()
+    );
+    if report.unknown_destination > 0 {
+        println!(
+            "  Skipped:   {} files tracked without a destination",
+            report.unknown_destination
+        );
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Handle the `verify-tracking` command - re-hash extracted files
+pub fn verify_tracking(
+    config: &Config,
+    directory: Option<PathBuf>,
+    database: Option<PathBuf>,
+    sample: Option<usize>,
+) -> Result<()> {
+    let directory = directory.unwrap_or_else(|| config.output.directory.clone());
+    let store = open_tracking_store(config, &directory, database)?;
+
+    info!("Verifying tracked files in: {}", directory.display());
+    let report = store.verify(&directory, sample)?;
+
+    println!();
+    for issue in &report.issues {
+        match issue {
+            VerifyIssue::Missing { path, .. } => {
+                println!("  MISSING     {}", path.display());
+            }
+            VerifyIssue::Changed { path, .. } => {
+                println!("  CHANGED     {}", path.display());
+            }
+            VerifyIssue::Unreadable { path, error, .. } => {
+                println!("  UNREADABLE  {} ({})", path.display(), error);
+            }
+        }
+    }
+    if !report.issues.is_empty() {
+        println!();
+    }
+
+    println!("  Checked:   {} files", report.checked);
+    println!("  OK:        {}", report.ok);
+    println!("  Missing:   {}", report.missing().count());
+    println!("  Changed:   {}", report.changed().count());
+    if report.unhashed > 0 {
+        println!(
+            "  No hash:   {} (extracted before hashes were recorded)",
+            report.unhashed
+        );
+    }
+    if report.unknown_destination > 0 {
+        println!(
+            "  Skipped:   {} files tracked without a destination",
+            report.unknown_destination
+        );
+    }
+    println!();
+
+    if report.is_clean() {
+        info!("All checked files are intact");
+    } else {
+        warn!("{} tracked files need attention", report.issues.len());
+    }
+
+    Ok(())
+}
 
-    for (id, profile) in &profiles {
-        if profile.name.to_lowercase().contains(&name.to_lowercase())
-            || id.to_lowercase().contains(&name.to_lowercase())
+/// Handle the `plan` command - show what is new on the device since the last backup
+pub fn plan_extraction(
+    config: &Config,
+    compare_hashes: bool,
+    list: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    // Initialize COM library
+    let _com_guard = device::initialize_com()?;
+    let manager = device::DeviceManager::new()?;
+
+    let devices = if config.device.apple_only {
+        manager.enumerate_apple_devices()?
+    } else {
+        manager.enumerate_all_devices()?
+    };
+    if devices.is_empty() {
+        if format.is_json() {
+            anyhow::bail!("No devices found");
+        }
+        error!("No devices found.");
+        return Ok(());
+    }
+    let device = select_device(&devices, &config.device.device_id)?;
+
+    // Planning must not create a profile, so a device without one has no backup yet
+    let output_dir = if config.device_profiles.enabled {
+        let mut profiles = ProfileManager::new(&config.device_profiles);
+        profiles.load().ok();
+        profiles.get_profile(&device.device_id).map(|profile| {
+            config
+                .device_profiles
+                .backup_base_folder
+                .join(&profile.output_folder)
+        })
+    } else {
+        Some(config.output.directory.clone())
+    };
+
+    let defaults = extractor::ExtractionConfig::default();
+    let plan_config = extractor::ExtractionConfig {
+        output_dir: output_dir.clone().unwrap_or(defaults.output_dir.clone()),
+        dcim_only: config.extraction.dcim_only,
+        tracking: output_dir.as_ref().map(|_| config.tracking.clone()),
+        quiet: format.is_json(),
+        android_config: device
+            .device_type()
+            .is_android()
+            .then(|| config.android.clone()),
+        date_from: config.extraction.date_from,
+        date_to: config.extraction.date_to,
+        include_undated: config.extraction.include_undated,
+        min_size_bytes: config.extraction.min_size_bytes(),
+        max_size_bytes: config.extraction.max_size_bytes(),
+        include_photos: config.extraction.include_photos,
+        include_videos: config.extraction.include_videos,
+        include_globs: config.extraction.include_globs.clone(),
+        exclude_globs: config.extraction.exclude_globs.clone(),
+        scan_order: config.extraction.scan_order(),
+        max_retries: config.extraction.max_retries,
+        retry_base_delay_ms: config.extraction.retry_base_delay_ms,
+        ..defaults
+    };
+    let plan = extractor::plan(device, &plan_config, compare_hashes)?;
+
+    if format.is_json() {
+        return write_json(&mut io::stdout(), &plan);
+    }
+
+    println!();
+    if list {
+        for file in plan
+            .files
+            .iter()
+            .filter(|f| f.status != PlanStatus::AlreadyHave)
         {
-            found_id = Some(id.clone());
+            let label = match file.status {
+                PlanStatus::Changed => "CHANGED",
+                _ => "NEW    ",
+            };
+            println!("  {}  {} ({})", label, file.path, format_bytes(file.size));
+        }
+        println!();
+    }
+
+    let summary = &plan.summary;
+    println!("  📱 {}", device.friendly_name);
+    match output_dir {
+        Some(ref dir) => println!("  📁 Compared with: {}", dir.display()),
+        None => println!("  📁 No backup of this device yet"),
+    }
+    println!();
+    println!(
+        "  New:              {} files ({})",
+        summary.new.files,
+        format_bytes(summary.new.bytes)
+    );
+    println!(
+        "  Changed:          {} files ({})",
+        summary.changed.files,
+        format_bytes(summary.changed.bytes)
+    );
+    println!(
+        "  Already have:     {} files ({})",
+        summary.already_have.files,
+        format_bytes(summary.already_have.bytes)
+    );
+    println!();
+
+    let to_copy = summary.to_copy();
+    if to_copy.files == 0 {
+        info!("Nothing new since the last backup");
+    } else {
+        info!(
+            "An extraction would copy {} files ({})",
+            to_copy.files,
+            format_bytes(to_copy.bytes)
+        );
+    }
+
+    Ok(())
+}
+
+/// Find a profile's device ID by name or partial device ID
+fn find_profile_id(manager: &ProfileManager, name: &str) -> Option<String> {
+    let query = name.to_lowercase();
+    manager
+        .get_all_profiles()
+        .iter()
+        .find(|(id, profile)| {
+            profile.name.to_lowercase().contains(&query) || id.to_lowercase().contains(&query)
+        })
+        .map(|(id, profile)| {
             info!(
                 "Found profile: {} -> {}",
                 profile.name, profile.output_folder
             );
-            break;
-        }
-    }
+            id.clone()
+        })
+}
+
+/// Remove a device profile by name or partial ID
+pub fn remove_profile(config: &Config, name: &str) -> Result<()> {
+    let mut manager = ProfileManager::new(&config.device_profiles);
+    manager.load()?;
 
-    if let Some(id) = found_id {
+    // Find profile by name or partial device ID
+    if let Some(id) = find_profile_id(&manager, name) {
         if let Some(profile) = manager.remove_profile(&id) {
             manager.save()?;
             info!("Removed profile: {}", profile.name);
@@ -416,6 +744,69 @@ pub fn remove_profile(config: &Config, name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Rename a device profile found by name or partial ID, keeping its device ID
+pub fn rename_profile(
+    config: &Config,
+    id: &str,
+    new_name: &str,
+    folder: Option<&str>,
+) -> Result<()> {
+    let mut manager = ProfileManager::new(&config.device_profiles);
+    manager.load()?;
+
+    let Some(device_id) = find_profile_id(&manager, id) else {
+        error!("No profile found matching: {}", id);
+        return Ok(());
+    };
+
+    manager.rename_profile(&device_id, new_name, folder)?;
+    manager.save()?;
+
+    if let Some(path) = manager.get_output_path(&device_id) {
+        info!("Renamed profile to '{}' ({})", new_name, path.display());
+    }
+
+    Ok(())
+}
+
+/// Export all device profiles to a portable file
+pub fn export_profiles(config: &Config, output: &Path) -> Result<()> {
+    let mut manager = ProfileManager::new(&config.device_profiles);
+    manager.load()?;
+
+    manager.export(output)?;
+    info!(
+        "Exported {} profile(s) to {}",
+        manager.get_all_profiles().len(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+/// Import device profiles, merging with or replacing the existing ones
+pub fn import_profiles(config: &Config, input: &Path, merge: bool, overwrite: bool) -> Result<()> {
+    let mut manager = ProfileManager::new(&config.device_profiles);
+    manager.load()?;
+
+    let summary = manager.import(input, merge, overwrite)?;
+    manager.save()?;
+
+    if merge {
+        info!(
+            "Imported profiles: {} added, {} replaced, {} kept existing",
+            summary.added, summary.replaced, summary.skipped
+        );
+    } else {
+        info!(
+            "Replaced {} existing profile(s) with {} imported profile(s)",
+            summary.replaced, summary.added
+        );
+    }
+
+    Ok(())
+}
+
 /// Handle the `config` command - open, show path, or reset the config file
 pub fn handle_config_command(show_path: bool, reset: bool) -> Result<()> {
     if reset {
@@ -463,6 +854,14 @@ pub fn handle_config_command(show_path: bool, reset: bool) -> Result<()> {
     Ok(())
 }
 
+/// Write a completion script for `shell` covering every subcommand and flag
+pub fn write_completions(shell: Shell, out: &mut dyn Write) -> Result<()> {
+    let mut command = Args::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, out);
+    Ok(())
+}
+
 /// Generate a configuration file at the specified or default location
 pub fn generate_config_file(output: Option<PathBuf>) -> Result<()> {
     use std::fs;
@@ -490,6 +889,53 @@ pub fn generate_config_file(output: Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+/// Rebuild the configuration the way `main` does, recording which layer set
+/// each value
+///
+/// The config file is followed by `PET_*` variables read through `env`, the
+/// command-line flags in `args`, and finally the profile of the configured
+/// device when device profiles are enabled.
+pub fn effective_config(
+    args: &Args,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<EffectiveConfig> {
+    let mut effective = match &args.config {
+        Some(path) => EffectiveConfig::from_file(Config::load_strict(path)?, path),
+        None => {
+            let path = Config::get_active_config_path();
+            match Config::load(&path) {
+                Ok(config) => EffectiveConfig::from_file(config, path),
+                Err(_) => EffectiveConfig::new(),
+            }
+        }
+    };
+
+    for warning in effective.apply_env_overrides_from(env) {
+        warn!("{}", warning);
+    }
+    effective.apply(ConfigSource::CommandLine, |config| {
+        args.apply_overrides(config)
+    });
+
+    let config = effective.config();
+    if let (true, Some(device_id)) = (config.device_profiles.enabled, &config.device.device_id) {
+        let mut profiles = ProfileManager::new(&config.device_profiles);
+        profiles.load()?;
+        if let Some(profile) = profiles.get_profile(device_id).cloned() {
+            effective.apply_profile(&profile);
+        }
+    }
+
+    Ok(effective)
+}
+
+/// Write the merged configuration, annotated with where each value came from
+pub fn export_effective_config(effective: &EffectiveConfig, output: &Path) -> Result<()> {
+    fs::write(output, effective.to_toml_string()?)?;
+    info!("Wrote effective configuration to {}", output.display());
+    Ok(())
+}
+
 /// Show the current configuration settings
 pub fn show_config(config: &Config) {
     let config_path = Config::get_active_config_path();
@@ -517,7 +963,44 @@ pub fn show_config(config: &Config) {
         config.output.preserve_structure
     );
     info!("  skip_existing = {}", config.output.skip_existing);
+    info!(
+        "  skip_existing_mode = {}",
+        config.output.skip_existing_mode
+    );
+    info!(
+        "  long_path_strategy = {}",
+        config.output.long_path_strategy
+    );
     info!("  organize_by_date = {}", config.output.organize_by_date);
+    if let Some(ref template) = config.output.path_template {
+        info!("  path_template = {}", template);
+    }
+    info!(
+        "  preserve_live_photo_pairs = {}",
+        config.output.preserve_live_photo_pairs
+    );
+    info!("  group_bursts = {}", config.output.group_bursts);
+    info!(
+        "  space_safety_margin_mb = {}",
+        config.output.space_safety_margin_mb
+    );
+    if let Some(ref path) = config.output.manifest_path {
+        info!("  manifest_path = \"{}\"", path.display());
+    }
+    info!("  manifest_format = \"{}\"", config.output.manifest_format);
+    if let Some(ref path) = config.output.append_to_log {
+        info!("  append_to_log = \"{}\"", path.display());
+    }
+    if let Some(ref path) = config.output.report_html {
+        info!("  report_html = \"{}\"", path.display());
+    }
+    if let Some(ref path) = config.output.zip_path {
+        info!("  zip_path = \"{}\"", path.display());
+    }
+    info!(
+        "  open_folder_after_extraction = {}",
+        config.output.open_folder_after_extraction
+    );
     info!(
         "  subfolder_by_device = {}",
         config.output.subfolder_by_device
@@ -548,8 +1031,49 @@ pub fn show_config(config: &Config) {
         "  exclude_extensions = {:?}",
         config.extraction.exclude_extensions
     );
+    info!("  include_globs = {:?}", config.extraction.include_globs);
+    info!("  exclude_globs = {:?}", config.extraction.exclude_globs);
     info!("  include_photos = {}", config.extraction.include_photos);
     info!("  include_videos = {}", config.extraction.include_videos);
+    if let Some(from) = config.extraction.date_from {
+        info!("  date_from = {}", from);
+    }
+    if let Some(to) = config.extraction.date_to {
+        info!("  date_to = {}", to);
+    }
+    info!("  include_undated = {}", config.extraction.include_undated);
+    info!(
+        "  verify_after_copy = {}",
+        config.extraction.verify_after_copy
+    );
+    info!("  verify_retries = {}", config.extraction.verify_retries);
+    info!(
+        "  delete_after_extract = {}",
+        config.extraction.delete_after_extract
+    );
+    info!("  max_retries = {}", config.extraction.max_retries);
+    info!(
+        "  retry_base_delay_ms = {}",
+        config.extraction.retry_base_delay_ms
+    );
+    info!(
+        "  reconnect_timeout_secs = {}",
+        config.extraction.reconnect_timeout_secs
+    );
+    info!(
+        "  resume_from_checkpoint = {}",
+        config.extraction.resume_from_checkpoint
+    );
+    info!(
+        "  checkpoint_interval = {}",
+        config.extraction.checkpoint_interval
+    );
+    if let Some(rate) = config.extraction.max_bytes_per_sec {
+        info!("  max_bytes_per_sec = {}", rate);
+    }
+    info!("  scan_threads = {}", config.extraction.scan_threads);
+    info!("  sort_by = \"{}\"", config.extraction.sort_by.as_str());
+    info!("  sort_ascending = {}", config.extraction.sort_ascending);
     info!("");
     info!("[logging]");
     info!("  level = \"{}\"", config.logging.level);
@@ -569,11 +1093,22 @@ pub fn show_config(config: &Config) {
         config.duplicate_detection.cache_file.display()
     );
     info!(
-        "  duplicate_action = {:?}",
-        config.duplicate_detection.duplicate_action
+        "  duplicate_action = \"{}\"",
+        config.duplicate_detection.duplicate_action.as_str()
     );
     info!("  recursive = {}", config.duplicate_detection.recursive);
     info!("  media_only = {}", config.duplicate_detection.media_only);
+    info!(
+        "  quick_hash_bytes = {}",
+        config.duplicate_detection.quick_hash_bytes
+    );
+    if let Some(ref path) = config.duplicate_detection.report_path {
+        info!("  report_path = \"{}\"", path.display());
+    }
+    info!(
+        "  report_format = \"{}\"",
+        config.duplicate_detection.report_format
+    );
     info!("");
     info!("[tracking]");
     info!("  enabled = {}", config.tracking.enabled);
@@ -585,6 +1120,9 @@ pub fn show_config(config: &Config) {
         "  track_extracted_files = {}",
         config.tracking.track_extracted_files
     );
+    if let Some(ref path) = config.tracking.database {
+        info!("  database = \"{}\"", path.display());
+    }
     info!("");
     info!("[android]");
     info!(
@@ -621,20 +1159,37 @@ pub fn show_config(config: &Config) {
 }
 
 /// List connected devices
-pub fn list_devices(all_devices: bool) -> Result<()> {
+pub fn list_devices(all_devices: bool, format: OutputFormat) -> Result<()> {
     // Initialize COM library (required for WPD)
     let _com_guard = device::initialize_com()?;
 
     // Create device manager
     let manager = device::DeviceManager::new()?;
 
+    list_devices_with(&manager, all_devices, format, &mut io::stdout())
+}
+
+/// List the devices known to `manager`, writing JSON output to `out`
+pub fn list_devices_with<M: DeviceManagerTrait>(
+    manager: &M,
+    all_devices: bool,
+    format: OutputFormat,
+    out: &mut dyn Write,
+) -> Result<()> {
     info!("Scanning for connected devices...");
 
-    let devices = if all_devices {
+    let mut devices = if all_devices {
         manager.enumerate_all_devices()?
     } else {
         manager.enumerate_apple_devices()?
     };
+    for device in &mut devices {
+        device.storage = manager.get_device_storage(&device.device_id);
+    }
+
+    if format.is_json() {
+        return write_json(out, &DeviceListDocument::new(&devices));
+    }
 
     if devices.is_empty() {
         info!("No portable devices found.");
@@ -658,6 +1213,15 @@ pub fn list_devices(all_devices: bool) -> Result<()> {
         info!("    Type: {}", device_type.display_name());
         info!("    Manufacturer: {}", device.manufacturer);
         info!("    Model: {}", device.model);
+        if let Some(storage) = device.storage {
+            info!(
+                "    Storage: {} used of {} ({:.0}%), {} free",
+                format_bytes(storage.used_bytes()),
+                format_bytes(storage.capacity_bytes),
+                storage.used_percent(),
+                format_bytes(storage.free_bytes)
+            );
+        }
         info!("    Device ID: {}", device.device_id);
         info!("");
     }
@@ -666,13 +1230,44 @@ pub fn list_devices(all_devices: bool) -> Result<()> {
 }
 
 /// Scan device and show folder structure
-pub fn scan_device(config: &Config, max_depth: usize) -> Result<()> {
+pub fn scan_device(
+    config: &Config,
+    max_depth: usize,
+    record: Option<&Path>,
+    export: Option<&Path>,
+    format: OutputFormat,
+) -> Result<()> {
     // Initialize COM library
     let _com_guard = device::initialize_com()?;
 
     // Create device manager
     let manager = device::DeviceManager::new()?;
 
+    scan_device_with(
+        &manager,
+        config,
+        max_depth,
+        record,
+        export,
+        format,
+        &mut io::stdout(),
+    )
+}
+
+/// Scan a device from `manager`, writing JSON output to `out`
+///
+/// When `record` is set, the full device tree is also saved there as a
+/// replayable test fixture. When `export` is set, the tree down to
+/// `max_depth` is streamed there as JSON or Markdown.
+pub fn scan_device_with<M: DeviceManagerTrait>(
+    manager: &M,
+    config: &Config,
+    max_depth: usize,
+    record: Option<&Path>,
+    export: Option<&Path>,
+    format: OutputFormat,
+    out: &mut dyn Write,
+) -> Result<()> {
     info!("Scanning for connected devices...");
 
     let devices = if config.device.apple_only {
@@ -682,6 +1277,9 @@ pub fn scan_device(config: &Config, max_depth: usize) -> Result<()> {
     };
 
     if devices.is_empty() {
+        if format.is_json() {
+            anyhow::bail!("No devices found");
+        }
         error!("No devices found.");
         return Ok(());
     }
@@ -693,6 +1291,73 @@ pub fn scan_device(config: &Config, max_depth: usize) -> Result<()> {
     // Open device
     let content = manager.open_device(&target_device.device_id)?;
 
+    if let Some(path) = record {
+        let fixture = DeviceFixture::record(target_device, &content, false)?;
+        fixture.save(path)?;
+        info!(
+            "Recorded {} objects to fixture: {}",
+            fixture.objects.len(),
+            path.display()
+        );
+    }
+
+    // The export is written from the same walk that prints the tree
+    let mut export_file = export
+        .map(|path| fs::File::create(path).map(io::BufWriter::new))
+        .transpose()?;
+    let mut exporter = match (export, export_file.as_mut()) {
+        (Some(path), Some(file)) => Some(TreeExporter::begin(
+            TreeExportFormat::from_path(path),
+            target_device,
+            max_depth,
+            file,
+        )?),
+        _ => None,
+    };
+    let finish_export = |exporter: Option<TreeExporter>| -> Result<()> {
+        if let (Some(exporter), Some(path)) = (exporter, export) {
+            let (folders, files) = exporter.finish()?;
+            info!(
+                "Exported {} folders and {} files to {}",
+                folders,
+                files,
+                path.display()
+            );
+        }
+        Ok(())
+    };
+
+    let storages = content.enumerate_storages()?;
+
+    if format.is_json() {
+        let tree = build_scan_tree(
+            &content,
+            "DEVICE",
+            max_depth,
+            config.extraction.scan_order(),
+        )?;
+        if let Some(ref mut exporter) = exporter {
+            exporter.write_nodes(&tree, 0)?;
+        }
+        finish_export(exporter)?;
+        let document = ScanDocument::new(target_device, max_depth, tree).with_storages(storages);
+        return write_json(out, &document);
+    }
+
+    info!("Found {} storage(s):", storages.len());
+    for storage in &storages {
+        match storage.storage_info() {
+            Some(info) => info!(
+                "  💾 {} ({} free of {})",
+                storage.description,
+                format_bytes(info.free_bytes),
+                format_bytes(info.capacity_bytes)
+            ),
+            None => info!("  💾 {}", storage.description),
+        }
+    }
+    info!("");
+
     info!("Scanning device structure (max depth: {})...", max_depth);
     info!("");
 
@@ -700,15 +1365,32 @@ pub fn scan_device(config: &Config, max_depth: usize) -> Result<()> {
     let progress = ScanProgressTracker::new();
 
     // Scan and print structure
-    scan_recursive(&content, "DEVICE", "", 0, max_depth, &progress)?;
+    scan_recursive(
+        &content,
+        "DEVICE",
+        "",
+        0,
+        max_depth,
+        config.extraction.scan_order(),
+        &progress,
+        exporter.as_mut(),
+    )?;
 
     progress.finish();
+    finish_export(exporter)?;
 
     Ok(())
 }
 
 /// Benchmark scan performance
-pub fn benchmark_scan(config: &Config, dcim_only: bool) -> Result<()> {
+///
+/// Stops listing folders once `shutdown_flag` is set and reports what was
+/// found so far.
+pub fn benchmark_scan(
+    config: &Config,
+    dcim_only: bool,
+    shutdown_flag: Arc<AtomicBool>,
+) -> Result<()> {
     use std::time::Instant;
 
     // Initialize COM library
@@ -746,28 +1428,65 @@ pub fn benchmark_scan(config: &Config, dcim_only: bool) -> Result<()> {
     info!("Starting scan benchmark...");
     let start = Instant::now();
 
-    // Scan for files
-    let root_objects = content.enumerate_objects()?;
-    progress.log_event(&format!("Found {} root objects", root_objects.len()));
-
-    let mut total_folders = 0usize;
-    let mut total_files = 0usize;
-    let mut media_files = 0usize;
-
-    for obj in root_objects {
-        if obj.is_folder {
-            benchmark_scan_recursive(
-                &content,
-                &obj.object_id,
-                &obj.name,
-                dcim_only,
-                &progress,
-                &mut total_folders,
-                &mut total_files,
-                &mut media_files,
-            )?;
-        }
-    }
+    // Scan every storage (internal, SD card, ...) for files
+    let storages = content.enumerate_storages()?;
+    progress.log_event(&format!("Found {} storage(s)", storages.len()));
+
+    info!(
+        "Listing folders on {} thread(s)",
+        config.extraction.scan_threads
+    );
+    let roots: Vec<DiscoveryRoot> = storages
+        .iter()
+        .map(|storage| DiscoveryRoot::new(&storage.object_id, &storage.description))
+        .collect();
+    let retrier = Retrier::new(
+        RetryPolicy::new(
+            config.extraction.max_retries,
+            config.extraction.retry_base_delay_ms,
+        ),
+        shutdown_flag.clone(),
+    );
+    let is_media = |file: &DiscoveredFile| media::is_media_file(&file.object.name);
+    let media_seen = AtomicUsize::new(0);
+    let discovery = discover_folders(
+        &content,
+        &roots,
+        config.extraction.scan_threads,
+        config.extraction.scan_order(),
+        &retrier,
+        &shutdown_flag,
+        // If dcim_only, only descend into DCIM itself until inside it (scanning
+        // starts at each storage root)
+        |path, folder| {
+            !dcim_only
+                || folder.name.eq_ignore_ascii_case("DCIM")
+                || path
+                    .rsplit_once('/')
+                    .is_some_and(|(parent, _)| parent.to_uppercase().contains("DCIM"))
+        },
+        |folder| {
+            let media = folder.found.iter().filter(|&file| is_media(file)).count();
+            let media = media_seen.fetch_add(media, Ordering::Relaxed) + media;
+            progress.update(folder.folders, folder.files, media);
+        },
+    )?;
+
+    let total_folders = discovery.folders_scanned;
+    let total_files = discovery.file_count();
+    let media: Vec<DiscoveredFile> = discovery.into_files().filter(is_media).collect();
+    let media_files = media.len();
+    let excluded_by_date = media
+        .iter()
+        .filter(|file| {
+            !extractor::date_in_range(
+                file.object.date_modified.as_deref(),
+                config.extraction.date_from,
+                config.extraction.date_to,
+                config.extraction.include_undated,
+            )
+        })
+        .count();
 
     let elapsed = start.elapsed();
     progress.finish();
@@ -775,10 +1494,29 @@ pub fn benchmark_scan(config: &Config, dcim_only: bool) -> Result<()> {
     // Print summary
     info!("");
     info!("=== Benchmark Results ===");
+    if shutdown_flag.load(Ordering::SeqCst) {
+        info!("Interrupted: results cover the folders listed so far");
+    }
     info!("Total time: {:.2}s", elapsed.as_secs_f64());
     info!("Folders scanned: {}", total_folders);
     info!("Total files found: {}", total_files);
     info!("Media files found: {}", media_files);
+    if config.extraction.date_from.is_some() || config.extraction.date_to.is_some() {
+        info!(
+            "Excluded by date filter: {} ({} to {})",
+            excluded_by_date,
+            config
+                .extraction
+                .date_from
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| "any".to_string()),
+            config
+                .extraction
+                .date_to
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| "any".to_string())
+        );
+    }
     if elapsed.as_secs_f64() > 0.0 {
         info!(
             "Scan rate: {:.1} items/second",
@@ -799,6 +1537,7 @@ pub fn extract_photos_with_args(
     compare_folders: Vec<PathBuf>,
     duplicate_action: Option<String>,
     all_devices: bool,
+    format: OutputFormat,
 ) -> Result<()> {
     // Initialize COM library (required for WPD)
     let _com_guard = device::initialize_com()?;
@@ -817,6 +1556,12 @@ pub fn extract_photos_with_args(
         manager.enumerate_all_devices()?
     };
 
+    if format.is_json() {
+        let duplicate_detection =
+            build_duplicate_config(config, detect_duplicates, compare_folders, duplicate_action);
+        return extract_json(&devices, config, duplicate_detection, shutdown_flag);
+    }
+
     if devices.is_empty() {
         println!();
         println!("  ✗ No portable devices found.");
@@ -874,6 +1619,99 @@ pub fn extract_photos_with_args(
     Ok(())
 }
 
+/// Extract without prompts or progress bars, writing an [`ExtractDocument`]
+///
+/// Scripts cannot answer the interactive device menu, so every connected
+/// device is extracted (one after another) unless `--device-id` picks one.
+fn extract_json(
+    devices: &[DeviceInfo],
+    config: &Config,
+    duplicate_detection: Option<crate::core::config::DuplicateDetectionConfig>,
+    shutdown_flag: Arc<AtomicBool>,
+) -> Result<()> {
+    let selected = match config.device.device_id {
+        Some(_) => select_devices(devices, &config.device.device_id)?,
+        None => devices.to_vec(),
+    };
+
+    let mut document = ExtractDocument::new(config.extraction.dry_run);
+    for device in &selected {
+        if shutdown_flag.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let start = Instant::now();
+        let output_dir = get_output_dir_for_device(device, config)?;
+        let result = extract_from_single_device_impl(
+            device,
+            config,
+            duplicate_detection.clone(),
+            shutdown_flag.clone(),
+            true,
+        );
+        let (stats, error) = match result {
+            Ok(stats) => (Some(stats), None),
+            Err(e) => {
+                warn!("Extraction from {} failed: {}", device.friendly_name, e);
+                (None, Some(e.to_string()))
+            }
+        };
+        document.devices.push(DeviceExtraction {
+            device: DeviceEntry::from(device),
+            output_dir,
+            stats,
+            error,
+            duration_ms: start.elapsed().as_millis() as u64,
+        });
+    }
+
+    write_json(&mut io::stdout(), &document)
+}
+
+/// Handle the `watch` command - extract from each matching device as it connects
+pub fn watch_devices(
+    config: &Config,
+    shutdown_flag: Arc<AtomicBool>,
+    once: bool,
+    debounce: Duration,
+) -> Result<()> {
+    // Initialize COM library (required for WPD)
+    let _com_guard = device::initialize_com()?;
+
+    let manager = Arc::new(device::DeviceManager::new()?);
+    let monitor =
+        DeviceMonitor::with_config(MonitorConfig::fast().apple_only(config.device.apple_only));
+    monitor
+        .start(manager)
+        .map_err(|e| anyhow::anyhow!("Failed to start device monitor: {}", e))?;
+
+    println!();
+    println!("  👀 Watching for devices (press Ctrl+C to stop)...");
+    println!();
+
+    let options = WatchOptions {
+        debounce,
+        once,
+        device_filter: config.device.device_id.clone(),
+    };
+    let duplicate_detection = build_duplicate_config(config, false, Vec::new(), None);
+    let extract_config = config.clone();
+    let extract_shutdown = shutdown_flag.clone();
+
+    let completed = run_watch(&monitor, options, shutdown_flag, move |device| {
+        extract_from_single_device(
+            device,
+            &extract_config,
+            duplicate_detection.clone(),
+            extract_shutdown.clone(),
+        )
+    });
+    monitor.stop();
+
+    info!("Watch stopped after {} extraction(s)", completed);
+    Ok(())
+}
+
 /// Build duplicate detection configuration from CLI args and config
 fn build_duplicate_config(
     config: &Config,
@@ -882,12 +1720,10 @@ fn build_duplicate_config(
     duplicate_action: Option<String>,
 ) -> Option<crate::core::config::DuplicateDetectionConfig> {
     if detect_duplicates || !compare_folders.is_empty() {
-        let action = match duplicate_action.as_deref() {
-            Some("skip") => crate::core::config::DuplicateAction::Skip,
-            Some("rename") => crate::core::config::DuplicateAction::Rename,
-            Some("overwrite") => crate::core::config::DuplicateAction::Overwrite,
-            _ => config.duplicate_detection.duplicate_action.clone(),
-        };
+        let action = duplicate_action
+            .as_deref()
+            .and_then(crate::core::config::DuplicateStrategy::from_name)
+            .unwrap_or(config.duplicate_detection.duplicate_action);
 
         let folders = if compare_folders.is_empty() {
             config.duplicate_detection.comparison_folders.clone()
@@ -908,6 +1744,9 @@ fn build_duplicate_config(
                 duplicate_action: action,
                 recursive: config.duplicate_detection.recursive,
                 media_only: config.duplicate_detection.media_only,
+                quick_hash_bytes: config.duplicate_detection.quick_hash_bytes,
+                report_path: config.duplicate_detection.report_path.clone(),
+                report_format: config.duplicate_detection.report_format,
             })
         } else {
             debug!("Duplicate detection requested but no comparison folders specified");
@@ -942,7 +1781,7 @@ fn extract_from_single_device_impl(
     let output_dir = get_output_dir_for_device(device, config)?;
 
     // Create output directory if it doesn't exist
-    if !output_dir.exists() {
+    if !output_dir.exists() && !config.extraction.dry_run {
         std::fs::create_dir_all(&output_dir)?;
         debug!("Created output directory: {}", output_dir.display());
     }
@@ -957,9 +1796,20 @@ fn extract_from_single_device_impl(
 
     let extraction_config = extractor::ExtractionConfig {
         output_dir: output_dir.clone(),
+        destination: match config.output.zip_path {
+            Some(ref path) => Destination::Zip(path.clone()),
+            None => Destination::Directory(output_dir.clone()),
+        },
         dcim_only: config.extraction.dcim_only,
         preserve_structure: config.output.preserve_structure,
+        organize_by_date: config.output.organize_by_date,
+        path_template: Some(config.output.output_template()),
+        preserve_live_photo_pairs: config.output.preserve_live_photo_pairs,
+        group_bursts: config.output.group_bursts,
+        space_safety_margin_mb: config.output.space_safety_margin_mb,
         skip_existing: config.output.skip_existing,
+        skip_existing_mode: config.output.skip_existing_mode,
+        long_path_strategy: config.output.long_path_strategy,
         duplicate_detection,
         tracking: if config.tracking.enabled {
             Some(config.tracking.clone())
@@ -968,9 +1818,51 @@ fn extract_from_single_device_impl(
         },
         quiet,
         android_config,
+        date_from: config.extraction.date_from,
+        date_to: config.extraction.date_to,
+        include_undated: config.extraction.include_undated,
+        min_size_bytes: config.extraction.min_size_bytes(),
+        max_size_bytes: config.extraction.max_size_bytes(),
+        include_photos: config.extraction.include_photos,
+        include_videos: config.extraction.include_videos,
+        include_globs: config.extraction.include_globs.clone(),
+        exclude_globs: config.extraction.exclude_globs.clone(),
+        scan_order: config.extraction.scan_order(),
+        scan_threads: config.extraction.scan_threads,
+        verify_after_copy: config.extraction.verify_after_copy,
+        verify_retries: config.extraction.verify_retries,
+        delete_after_extract: config.extraction.delete_after_extract,
+        max_retries: config.extraction.max_retries,
+        retry_base_delay_ms: config.extraction.retry_base_delay_ms,
+        reconnect_timeout_secs: config.extraction.reconnect_timeout_secs,
+        checkpoint_path: config.extraction.checkpoint_path(&output_dir),
+        checkpoint_interval: config.extraction.checkpoint_interval,
+        max_bytes_per_sec: config.extraction.max_bytes_per_sec,
+        dry_run: config.extraction.dry_run,
+        manifest_path: config.output.manifest_path.clone(),
+        manifest_format: config.output.manifest_format,
+        append_to_log: config.output.append_to_log.clone(),
+        report_html: config.output.report_html.clone(),
     };
 
-    let stats = extractor::extract_photos(device, extraction_config, shutdown_flag)?;
+    let zip_path = config.output.zip_path.clone();
+    let mut sink: Box<dyn ProgressSink> = if quiet {
+        Box::new(NullSink)
+    } else {
+        Box::new(IndicatifSink::new())
+    };
+    let stats = extractor::extract_photos(
+        device,
+        extraction_config,
+        shutdown_flag.clone(),
+        sink.as_mut(),
+    )?;
+    record_extraction_in_profile(
+        &device.device_id,
+        config,
+        &stats,
+        shutdown_flag.load(Ordering::SeqCst),
+    );
 
     debug!(
         "Extraction finished: {} extracted, {} skipped, {} duplicates, {} errors, {} bytes",
@@ -981,9 +1873,34 @@ fn extract_from_single_device_impl(
         stats.total_bytes
     );
 
+    if !quiet {
+        let folder = match zip_path {
+            Some(ref path) => path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new(".")),
+            None => &output_dir,
+        };
+        open_output_folder(config, folder, &shutdown_flag);
+    }
+
     Ok(stats)
 }
 
+/// Open `folder` in Explorer if `output.open_folder_after_extraction` is on
+/// and the extraction that wrote to it really ran to the end
+fn open_output_folder(config: &Config, folder: &Path, shutdown_flag: &AtomicBool) {
+    if !config.output.open_folder_after_extraction
+        || config.extraction.dry_run
+        || shutdown_flag.load(Ordering::SeqCst)
+    {
+        return;
+    }
+    if let Err(e) = reveal_in_explorer(folder) {
+        warn!("Could not open the output folder: {}", e);
+    }
+}
+
 /// Extract from a single device with a pre-resolved output directory (for parallel extraction)
 fn extract_with_output_dir(
     device: &DeviceInfo,
@@ -994,7 +1911,7 @@ fn extract_with_output_dir(
     progress: Option<Arc<SharedProgress>>,
 ) -> Result<ExtractionStats> {
     // Create output directory if it doesn't exist
-    if !output_dir.exists() {
+    if !output_dir.exists() && !config.extraction.dry_run {
         std::fs::create_dir_all(&output_dir)?;
         debug!("Created output directory: {}", output_dir.display());
     }
@@ -1007,11 +1924,36 @@ fn extract_with_output_dir(
         None
     };
 
+    // Each device gets its own duplicate report inside its output folder
+    let duplicate_detection = duplicate_detection.map(|mut detection| {
+        detection.report_path = detection
+            .report_path
+            .as_ref()
+            .map(|path| output_dir.join(path.file_name().unwrap_or("duplicates.json".as_ref())));
+        detection
+    });
+
+    // Each device gets its own archive inside its output folder
+    let destination = match config.output.zip_path {
+        Some(ref path) => {
+            Destination::Zip(output_dir.join(path.file_name().unwrap_or("photos.zip".as_ref())))
+        }
+        None => Destination::Directory(output_dir.clone()),
+    };
+
     let extraction_config = extractor::ExtractionConfig {
         output_dir: output_dir.clone(),
+        destination,
         dcim_only: config.extraction.dcim_only,
         preserve_structure: config.output.preserve_structure,
+        organize_by_date: config.output.organize_by_date,
+        path_template: Some(config.output.output_template()),
+        preserve_live_photo_pairs: config.output.preserve_live_photo_pairs,
+        group_bursts: config.output.group_bursts,
+        space_safety_margin_mb: config.output.space_safety_margin_mb,
         skip_existing: config.output.skip_existing,
+        skip_existing_mode: config.output.skip_existing_mode,
+        long_path_strategy: config.output.long_path_strategy,
         duplicate_detection,
         tracking: if config.tracking.enabled {
             Some(config.tracking.clone())
@@ -1020,6 +1962,39 @@ fn extract_with_output_dir(
         },
         quiet: true, // Always quiet for parallel
         android_config,
+        date_from: config.extraction.date_from,
+        date_to: config.extraction.date_to,
+        include_undated: config.extraction.include_undated,
+        min_size_bytes: config.extraction.min_size_bytes(),
+        max_size_bytes: config.extraction.max_size_bytes(),
+        include_photos: config.extraction.include_photos,
+        include_videos: config.extraction.include_videos,
+        include_globs: config.extraction.include_globs.clone(),
+        exclude_globs: config.extraction.exclude_globs.clone(),
+        scan_order: config.extraction.scan_order(),
+        scan_threads: config.extraction.scan_threads,
+        verify_after_copy: config.extraction.verify_after_copy,
+        verify_retries: config.extraction.verify_retries,
+        delete_after_extract: config.extraction.delete_after_extract,
+        max_retries: config.extraction.max_retries,
+        retry_base_delay_ms: config.extraction.retry_base_delay_ms,
+        reconnect_timeout_secs: config.extraction.reconnect_timeout_secs,
+        checkpoint_path: config.extraction.checkpoint_path(&output_dir),
+        checkpoint_interval: config.extraction.checkpoint_interval,
+        max_bytes_per_sec: config.extraction.max_bytes_per_sec,
+        dry_run: config.extraction.dry_run,
+        manifest_path: config
+            .output
+            .manifest_path
+            .as_ref()
+            .map(|path| output_dir.join(path.file_name().unwrap_or("manifest.json".as_ref()))),
+        manifest_format: config.output.manifest_format,
+        append_to_log: config.output.append_to_log.clone(),
+        report_html: config
+            .output
+            .report_html
+            .as_ref()
+            .map(|path| output_dir.join(path.file_name().unwrap_or("report.html".as_ref()))),
     };
 
     // Create progress callback if we have shared progress
@@ -1040,11 +2015,15 @@ fn extract_with_output_dir(
     let stats = extractor::extract_photos_with_progress(
         device,
         extraction_config,
-        shutdown_flag,
+        shutdown_flag.clone(),
+        &mut NullSink,
         progress_callback,
         total_files_callback,
     )?;
 
+    // Each device's archive lives inside its output folder
+    open_output_folder(config, &output_dir, &shutdown_flag);
+
     Ok(stats)
 }
 
@@ -1071,6 +2050,38 @@ fn get_output_dir_for_device(device: &DeviceInfo, config: &Config) -> Result<Pat
     }
 }
 
+/// Remember a finished extraction in the device's profile, if profiles are on
+///
+/// Dry runs copy nothing and aren't recorded. Failing to update the profile
+/// only logs a warning; the extraction itself succeeded.
+fn record_extraction_in_profile(
+    device_id: &str,
+    config: &Config,
+    stats: &ExtractionStats,
+    cancelled: bool,
+) {
+    if !config.device_profiles.enabled || config.extraction.dry_run {
+        return;
+    }
+
+    let mut profiles = ProfileManager::new(&config.device_profiles);
+    let updated = profiles
+        .load()
+        .map(|_| profiles.record_extraction(device_id, stats, cancelled));
+    match updated {
+        Ok(true) => {
+            if let Err(e) = profiles.save() {
+                warn!(
+                    "Could not save the extraction summary to the profile: {}",
+                    e
+                );
+            }
+        }
+        Ok(false) => {}
+        Err(e) => warn!("Could not update the device profile: {}", e),
+    }
+}
+
 /// Extract from multiple devices with option for parallel extraction
 fn extract_from_multiple_devices(
     devices: &[DeviceInfo],
@@ -1137,6 +2148,7 @@ fn extract_from_multiple_devices(
                         total_stats.duplicates_skipped += stats.duplicates_skipped;
                         total_stats.errors += stats.errors;
                         total_stats.total_bytes += stats.total_bytes;
+                        total_stats.by_type.merge(&stats.by_type);
                     }
                     Err(e) => {
                         println!("  ✗ Error extracting from {}: {}", device.friendly_name, e);
@@ -1162,6 +2174,7 @@ fn extract_from_multiple_devices(
                     "     Total size:       {}",
                     format_bytes(total_stats.total_bytes)
                 );
+                extractor::print_stats_by_type(&total_stats.by_type);
                 println!();
             }
         }
@@ -1238,6 +2251,7 @@ fn extract_from_multiple_devices(
                     let cfg = config_clone.clone();
                     let dup = dup_config.clone();
                     let flag = shutdown_flag.clone();
+                    let device_id = device.device_id.clone();
                     let device_name = device.friendly_name.clone();
                     let output_dir_clone = output_dir.clone();
                     let progress = Arc::clone(&shared_progress);
@@ -1251,11 +2265,13 @@ fn extract_from_multiple_devices(
                             Err(e) => {
                                 progress.mark_device_complete();
                                 return DeviceExtractionResult {
+                                    device_id,
                                     device_name,
                                     profile_name: Some(profile_name),
                                     output_dir,
                                     stats: None,
                                     error: Some(format!("COM init failed: {}", e)),
+                                    cancelled: false,
                                     duration: start.elapsed(),
                                 };
                             }
@@ -1266,28 +2282,32 @@ fn extract_from_multiple_devices(
                             output_dir_clone,
                             &cfg,
                             dup,
-                            flag,
+                            flag.clone(),
                             Some(Arc::clone(&progress)),
                         ) {
                             Ok(stats) => {
                                 progress.mark_device_complete();
                                 DeviceExtractionResult {
+                                    device_id,
                                     device_name,
                                     profile_name: Some(profile_name),
                                     output_dir,
                                     stats: Some(stats),
                                     error: None,
+                                    cancelled: flag.load(Ordering::SeqCst),
                                     duration: start.elapsed(),
                                 }
                             }
                             Err(e) => {
                                 progress.mark_device_complete();
                                 DeviceExtractionResult {
+                                    device_id,
                                     device_name,
                                     profile_name: Some(profile_name),
                                     output_dir,
                                     stats: None,
                                     error: Some(format!("{}", e)),
+                                    cancelled: flag.load(Ordering::SeqCst),
                                     duration: start.elapsed(),
                                 }
                             }
@@ -1330,16 +2350,31 @@ fn extract_from_multiple_devices(
                 match handle.join() {
                     Ok(result) => results.push(result),
                     Err(_) => results.push(DeviceExtractionResult {
+                        device_id: String::new(),
                         device_name: "Unknown".to_string(),
                         profile_name: None,
                         output_dir: PathBuf::new(),
                         stats: None,
                         error: Some("Thread panicked".to_string()),
+                        cancelled: false,
                         duration: Duration::ZERO,
                     }),
                 }
             }
 
+            // Profiles are updated one device at a time now that no thread
+            // is writing the profiles file
+            for result in &results {
+                if let Some(ref stats) = result.stats {
+                    record_extraction_in_profile(
+                        &result.device_id,
+                        config,
+                        stats,
+                        result.cancelled,
+                    );
+                }
+            }
+
             let total_duration = parallel_start.elapsed();
 
             // Final progress update
@@ -1376,6 +2411,7 @@ fn extract_from_multiple_devices(
                     total_stats.duplicates_skipped += stats.duplicates_skipped;
                     total_stats.errors += stats.errors;
                     total_stats.total_bytes += stats.total_bytes;
+                    total_stats.by_type.merge(&stats.by_type);
 
                     println!("  │  📁 {}", result.output_dir.display());
                     println!(
@@ -1416,6 +2452,7 @@ fn extract_from_multiple_devices(
                 "     Total size:         {}",
                 format_bytes(total_stats.total_bytes)
             );
+            extractor::print_stats_by_type(&total_stats.by_type);
             println!(
                 "     Total time:         {:.1}s",
                 total_duration.as_secs_f64()
@@ -1610,32 +2647,36 @@ fn select_device<'a>(
     } else if devices.len() == 1 {
         Ok(&devices[0])
     } else {
-        // For non-extraction commands, just pick the first one with a note
-        println!();
-        println!(
+        // For non-extraction commands, just pick the first one with a note.
+        // This goes to stderr so it never mixes with JSON output.
+        eprintln!();
+        eprintln!(
             "  ℹ Multiple devices found, using first one: {}",
             devices[0].friendly_name
         );
-        println!("    Use --device-id to specify a different device.");
-        println!();
+        eprintln!("    Use --device-id to specify a different device.");
+        eprintln!();
         Ok(&devices[0])
     }
 }
 
 /// Recursively scan and print device structure
-fn scan_recursive(
-    content: &device::DeviceContent,
+fn scan_recursive<C: DeviceContentTrait>(
+    content: &C,
     object_id: &str,
     prefix: &str,
     depth: usize,
     max_depth: usize,
+    order: ScanOrder,
     progress: &ScanProgressTracker,
+    mut exporter: Option<&mut TreeExporter>,
 ) -> Result<()> {
     if max_depth > 0 && depth >= max_depth {
         return Ok(());
     }
 
-    let children = content.enumerate_children(object_id)?;
+    let mut children = content.enumerate_children(object_id)?;
+    order.sort(&mut children);
 
     let mut file_count = 0;
     for (i, child) in children.iter().enumerate() {
@@ -1655,6 +2696,15 @@ fn scan_recursive(
             "{}{}{} {}{}",
             prefix, connector, type_str, child.name, size_str
         );
+        if let Some(ref mut exporter) = exporter {
+            exporter.enter(
+                depth,
+                &child.object_id,
+                &child.name,
+                child.is_folder,
+                child.size,
+            )?;
+        }
 
         if child.is_folder {
             progress.increment_folders();
@@ -1665,11 +2715,16 @@ fn scan_recursive(
                 &new_prefix,
                 depth + 1,
                 max_depth,
+                order,
                 progress,
+                exporter.as_deref_mut(),
             )?;
         } else {
             file_count += 1;
         }
+        if let Some(ref mut exporter) = exporter {
+            exporter.leave()?;
+        }
     }
 
     if file_count > 0 {
@@ -1733,19 +2788,30 @@ fn test_run_quick(verbose: bool) -> Result<()> {
     Ok(())
 }
 
-/// Run tests filtered by tag
-fn test_run_by_tag(tag: &str, verbose: bool) -> Result<()> {
+/// Run tests carrying any of the given tags
+fn test_run_tagged(tags: &[String], verbose: bool) -> Result<()> {
     let config = TestRunnerConfig {
         verbose,
         ..Default::default()
     };
 
+    let known = ScenarioLibrary::tags();
+    for tag in tags.iter().filter(|tag| !known.contains(tag)) {
+        println!(
+            "⚠️  No scenarios are tagged '{}' (see: test list-tags)",
+            tag
+        );
+    }
+
+    let tags: Vec<&str> = tags.iter().map(|s| s.as_str()).collect();
     let mut runner = TestRunner::with_config(config);
-    let summary = runner.run_by_tag(tag);
+    let summary = runner.run_tagged(&tags);
 
     println!(
-        "\n✓ Tests with tag '{}' complete: {}/{} passed",
-        tag, summary.passed, summary.total
+        "\n✓ Tests tagged '{}' complete: {}/{} passed",
+        tags.join(", "),
+        summary.passed,
+        summary.total
     );
 
     if summary.failed > 0 {
@@ -1841,7 +2907,7 @@ fn test_list_tags() -> Result<()> {
         println!("  • {} ({} scenarios)", tag, count);
     }
     println!();
-    println!("Use: photo_extraction_tool test run-tag <TAG>");
+    println!("Use: photo_extraction_tool test run-tag <TAG>[,<TAG>...]");
     Ok(())
 }
 
@@ -2206,93 +3272,344 @@ fn test_benchmark_mock(file_count: usize, iterations: usize) -> Result<()> {
     Ok(())
 }
 
-/// Recursively scan for benchmark statistics
-#[allow(clippy::too_many_arguments)]
-fn benchmark_scan_recursive(
-    content: &device::DeviceContent,
-    object_id: &str,
-    path: &str,
-    dcim_only: bool,
-    progress: &BenchmarkProgress,
-    total_folders: &mut usize,
-    total_files: &mut usize,
-    media_files: &mut usize,
-) -> Result<()> {
-    *total_folders += 1;
-    progress.update(*total_folders, *total_files, *media_files);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::output::SCHEMA_VERSION;
+    use crate::testdb::{MockDeviceConfig, MockDeviceInfo, MockDeviceManager, MockFileSystem};
 
-    let children = match content.enumerate_children(object_id) {
-        Ok(c) => c,
-        Err(e) => {
-            warn!("Failed to enumerate '{}': {}", path, e);
-            return Ok(());
-        }
-    };
+    const GB: u64 = 1024 * 1024 * 1024;
 
-    // If dcim_only, skip non-DCIM paths (but always scan to find DCIM)
-    let is_dcim_path = path.to_uppercase().contains("DCIM");
+    fn mock_manager() -> MockDeviceManager {
+        let mut manager = MockDeviceManager::new();
 
-    for child in children {
-        let child_path = format!("{}/{}", path, child.name);
+        let mut fs = MockFileSystem::new();
+        fs.add_standard_dcim_structure(3, 2);
+        manager.add_device_with_config(
+            MockDeviceInfo::new("iphone-1", "Test iPhone", "Apple Inc.", "iPhone 15"),
+            fs,
+            MockDeviceConfig::new().with_storage(128 * GB, 32 * GB),
+        );
+        manager.add_device(
+            MockDeviceInfo::new("pixel-1", "Test Pixel", "Google", "Pixel 8"),
+            MockFileSystem::new(),
+        );
 
-        if child.is_folder {
-            // If dcim_only and we're not in DCIM yet, only recurse if this might lead to DCIM
-            if dcim_only && !is_dcim_path {
-                // Check if this is the DCIM folder or might contain it
-                let child_upper = child.name.to_uppercase();
-                if child_upper == "DCIM"
-                    || child_upper == "INTERNAL STORAGE"
-                    || child_upper.contains("STORAGE")
-                {
-                    benchmark_scan_recursive(
-                        content,
-                        &child.object_id,
-                        &child_path,
-                        dcim_only,
-                        progress,
-                        total_folders,
-                        total_files,
-                        media_files,
-                    )?;
-                }
-            } else {
-                benchmark_scan_recursive(
-                    content,
-                    &child.object_id,
-                    &child_path,
-                    dcim_only,
-                    progress,
-                    total_folders,
-                    total_files,
-                    media_files,
-                )?;
-            }
-        } else {
-            *total_files += 1;
-
-            // Check if it's a media file
-            let ext = std::path::Path::new(&child.name)
-                .extension()
-                .and_then(|e| e.to_str())
-                .map(|e| e.to_lowercase())
-                .unwrap_or_default();
-
-            let photo_exts = [
-                "jpg", "jpeg", "png", "heic", "heif", "gif", "webp", "raw", "dng", "tiff", "tif",
-                "bmp",
-            ];
-            let video_exts = ["mov", "mp4", "m4v", "avi", "3gp"];
-
-            if photo_exts.contains(&ext.as_str()) || video_exts.contains(&ext.as_str()) {
-                *media_files += 1;
-            }
+        manager
+    }
 
-            // Update progress every 100 files
-            if (*total_files).is_multiple_of(100) {
-                progress.update(*total_folders, *total_files, *media_files);
-            }
+    /// Run a command against a captured stdout and return what it wrote
+    fn capture<F>(command: F) -> String
+    where
+        F: FnOnce(&mut dyn Write) -> Result<()>,
+    {
+        let mut stdout = Vec::new();
+        command(&mut stdout).unwrap();
+        String::from_utf8(stdout).unwrap()
+    }
+
+    #[test]
+    fn test_list_json_output() {
+        let manager = mock_manager();
+        let stdout = capture(|out| list_devices_with(&manager, true, OutputFormat::Json, out));
+
+        let document: DeviceListDocument = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(document.schema_version, SCHEMA_VERSION);
+        assert_eq!(document.devices.len(), 2);
+        let iphone = document
+            .devices
+            .iter()
+            .find(|d| d.device_id == "iphone-1")
+            .unwrap();
+        assert_eq!(iphone.friendly_name, "Test iPhone");
+        assert!(iphone.device_type.is_apple());
+
+        // Apple-only listing filters the Android device out
+        let stdout = capture(|out| list_devices_with(&manager, false, OutputFormat::Json, out));
+        let document: DeviceListDocument = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(document.devices.len(), 1);
+    }
+
+    #[test]
+    fn test_list_json_reports_storage() {
+        let manager = mock_manager();
+        let stdout = capture(|out| list_devices_with(&manager, true, OutputFormat::Json, out));
+        let document: DeviceListDocument = serde_json::from_str(&stdout).unwrap();
+
+        let storage = |id: &str| {
+            document
+                .devices
+                .iter()
+                .find(|d| d.device_id == id)
+                .unwrap()
+                .storage
+        };
+        let iphone = storage("iphone-1").unwrap();
+        assert_eq!(iphone.capacity_bytes, 128 * GB);
+        assert_eq!(iphone.used_bytes, 96 * GB);
+        assert_eq!(iphone.free_bytes, 32 * GB);
+
+        // Devices that don't report storage list it as null
+        assert!(storage("pixel-1").is_none());
+        let value: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert!(value["devices"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|d| d["storage"].is_null()));
+    }
+
+    #[test]
+    fn test_list_text_output_leaves_json_writer_empty() {
+        let manager = mock_manager();
+        let stdout = capture(|out| list_devices_with(&manager, true, OutputFormat::Text, out));
+        assert!(stdout.is_empty());
+    }
+
+    #[test]
+    fn test_scan_json_output() {
+        let manager = mock_manager();
+        let mut config = Config::default();
+        config.device.device_id = Some("iphone-1".to_string());
+
+        let stdout = capture(|out| {
+            scan_device_with(&manager, &config, 0, None, None, OutputFormat::Json, out)
+        });
+        let value: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+
+        let document: ScanDocument = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(document.device.device_id, "iphone-1");
+        // Internal Storage, DCIM, 100APPLE, 101APPLE
+        assert_eq!(document.folders, 4);
+        assert_eq!(document.files, 6);
+        assert_eq!(document.tree[0].name, "Internal Storage");
+        assert_eq!(document.storages.len(), 1);
+        assert_eq!(document.storages[0].description, "Internal Storage");
+
+        // A depth limit trims the tree
+        let stdout = capture(|out| {
+            scan_device_with(&manager, &config, 2, None, None, OutputFormat::Json, out)
+        });
+        let document: ScanDocument = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(document.folders, 2);
+        assert_eq!(document.files, 0);
+    }
+
+    #[test]
+    fn test_scan_records_replayable_fixture() {
+        let manager = mock_manager();
+        let mut config = Config::default();
+        config.device.device_id = Some("iphone-1".to_string());
+
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("iphone.json");
+        // The recording covers the whole tree even when the scan is depth-limited
+        capture(|out| {
+            scan_device_with(
+                &manager,
+                &config,
+                1,
+                Some(&path),
+                None,
+                OutputFormat::Json,
+                out,
+            )
+        });
+
+        let replayed = MockDeviceManager::from_fixture(&path).unwrap();
+        let device = replayed.get_device_info("iphone-1").unwrap();
+        assert_eq!(device.friendly_name, "Test iPhone");
+
+        let stdout = capture(|out| {
+            scan_device_with(&replayed, &config, 0, None, None, OutputFormat::Json, out)
+        });
+        let document: ScanDocument = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(document.folders, 4);
+        assert_eq!(document.files, 6);
+        assert_eq!(document.tree[0].name, "Internal Storage");
+    }
+
+    #[test]
+    fn test_scan_export_matches_printed_tree() {
+        let manager = mock_manager();
+        let mut config = Config::default();
+        config.device.device_id = Some("iphone-1".to_string());
+
+        let temp = tempfile::tempdir().unwrap();
+        let json_path = temp.path().join("tree.json");
+        let stdout = capture(|out| {
+            scan_device_with(
+                &manager,
+                &config,
+                0,
+                None,
+                Some(&json_path),
+                OutputFormat::Json,
+                out,
+            )
+        });
+        let printed: ScanDocument = serde_json::from_str(&stdout).unwrap();
+        let exported: ScanDocument =
+            serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(
+            (exported.folders, exported.files),
+            (printed.folders, printed.files)
+        );
+        assert_eq!(exported.tree, printed.tree);
+        assert_eq!(exported.device, printed.device);
+
+        let markdown_path = temp.path().join("tree.md");
+        capture(|out| {
+            scan_device_with(
+                &manager,
+                &config,
+                0,
+                None,
+                Some(&markdown_path),
+                OutputFormat::Text,
+                out,
+            )
+        });
+        let markdown = fs::read_to_string(&markdown_path).unwrap();
+        let items = markdown
+            .lines()
+            .filter(|line| line.trim_start().starts_with("- "))
+            .count();
+        assert_eq!(items, printed.folders + printed.files);
+        assert!(markdown.contains("- `Internal Storage/`\n  - `DCIM/`\n"));
+    }
+
+    #[test]
+    fn test_extract_json_document() {
+        let device = MockDeviceInfo::new("iphone-1", "Test iPhone", "Apple Inc.", "iPhone 15");
+        let mut document = ExtractDocument::new(false);
+        document.devices.push(DeviceExtraction {
+            device: DeviceEntry::from(&device),
+            output_dir: PathBuf::from("photos"),
+            stats: Some(ExtractionStats {
+                files_extracted: 12,
+                total_bytes: 4096,
+                ..Default::default()
+            }),
+            error: None,
+            duration_ms: 1500,
+        });
+
+        let stdout = capture(|out| write_json(out, &document));
+        let value: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+        assert_eq!(value["dry_run"], false);
+        assert_eq!(value["devices"][0]["device"]["device_id"], "iphone-1");
+        assert_eq!(value["devices"][0]["stats"]["files_extracted"], 12);
+        assert_eq!(value["devices"][0]["stats"]["total_bytes"], 4096);
+        assert!(value["devices"][0]["error"].is_null());
+    }
+
+    #[test]
+    fn test_powershell_completions_list_subcommands() {
+        let script = capture(|out| write_completions(Shell::PowerShell, out));
+
+        assert!(script.contains("photo_extraction_tool"));
+        for subcommand in [
+            "extract",
+            "list-profiles",
+            "rename-profile",
+            "export-profiles",
+            "import-profiles",
+            "completions",
+            "watch",
+        ] {
+            assert!(
+                script.contains(&format!("'{}'", subcommand)),
+                "missing subcommand {}",
+                subcommand
+            );
         }
+        assert!(script.contains("--config"));
     }
 
-    Ok(())
+    #[test]
+    fn test_effective_config_applies_layers_in_order() {
+        use clap::Parser;
+
+        let temp = tempfile::tempdir().unwrap();
+        let backup = temp.path().join("backup");
+        let profiles_file = temp.path().join("profiles.json");
+        let config_path = temp.path().join("config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[output]\ndirectory = \"file_output\"\n\n\
+                 [device_profiles]\nenabled = true\nbackup_base_folder = {}\nprofiles_file = {}\n",
+                toml::Value::from(backup.display().to_string()),
+                toml::Value::from(profiles_file.display().to_string()),
+            ),
+        )
+        .unwrap();
+        fs::write(
+            &profiles_file,
+            r#"{"version": 1, "profiles": {"iphone-1": {"name": "Alice", "output_folder": "Alice_iPhone"}}}"#,
+        )
+        .unwrap();
+
+        let export = temp.path().join("effective.toml");
+        let args = Args::try_parse_from([
+            "photo_extraction_tool",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--output",
+            "cli_output",
+            "--device-id",
+            "iphone-1",
+            "--dcim-only",
+            "false",
+            "export-effective-config",
+            "--output",
+            export.to_str().unwrap(),
+        ])
+        .unwrap();
+        let env = |name: &str| match name {
+            "PET_OUTPUT_DIR" => Some("env_output".to_string()),
+            "PET_LOG_LEVEL" => Some("debug".to_string()),
+            _ => None,
+        };
+
+        let effective = effective_config(&args, env).unwrap();
+        let config = effective.config();
+
+        // The profile wins over the command line, which wins over the environment
+        assert_eq!(config.output.directory, backup.join("Alice_iPhone"));
+        assert_eq!(
+            effective.source("output.directory"),
+            Some(&ConfigSource::Profile("Alice".to_string()))
+        );
+        assert!(!config.extraction.dcim_only);
+        assert_eq!(
+            effective.source("extraction.dcim_only"),
+            Some(&ConfigSource::CommandLine)
+        );
+        assert_eq!(config.logging.level, "debug");
+        assert_eq!(
+            effective.source("logging.level"),
+            Some(&ConfigSource::Env("PET_LOG_LEVEL".to_string()))
+        );
+        assert_eq!(
+            effective.source("device_profiles.backup_base_folder"),
+            Some(&ConfigSource::File(config_path.clone()))
+        );
+        assert_eq!(effective.source("output.preserve_structure"), None);
+
+        export_effective_config(&effective, &export).unwrap();
+        let written = fs::read_to_string(&export).unwrap();
+        assert!(written.contains("# from device profile 'Alice'\ndirectory = "));
+        assert!(written.contains("# from command line\ndcim_only = false"));
+        assert!(written.contains("# from environment variable PET_LOG_LEVEL\nlevel = \"debug\""));
+
+        // The export is a loadable config with the same values
+        let reloaded = Config::load(&export).unwrap();
+        assert_eq!(reloaded.output.directory, config.output.directory);
+        assert!(!reloaded.extraction.dcim_only);
+    }
 }
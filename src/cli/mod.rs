@@ -7,6 +7,8 @@
 //!
 //! - `args` - Command-line argument definitions using clap
 //! - `commands` - Command handler implementations
+//! - `output` - JSON output documents for `--format json`
+//! - `watch` - Automatic extraction when devices connect
 //! - `progress` - Progress bars and CLI output utilities
 
 //! unused imports will be allowed to make sure a complete version of the API is available.
@@ -14,9 +16,12 @@
 
 pub mod args;
 pub mod commands;
+pub mod output;
 pub mod progress;
+pub mod watch;
 
 // Re-export commonly used types for convenience
 pub use args::{Args, Commands, TestCommands};
 pub use commands::run_command;
+pub use output::OutputFormat;
 pub use progress::DualWriter;
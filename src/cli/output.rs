@@ -0,0 +1,424 @@
+//! Machine-readable command output
+//!
+//! With `--format json`, the `list`, `scan` and `extract` commands write one
+//! JSON document to stdout instead of human-readable lines, so the tool can be
+//! driven from scripts. Logs still go to stderr and progress bars are hidden,
+//! leaving stdout as pure JSON.
+//!
+//! Every document has a top-level `schema_version`. New fields may be added
+//! within a version; removing or renaming a field bumps [`SCHEMA_VERSION`].
+
+use crate::cli::progress::format_bytes;
+use crate::core::discovery::ScanOrder;
+use crate::core::error::{ExtractionError, Result as ExtractionResult};
+use crate::core::extractor::ExtractionStats;
+use crate::device::traits::{
+    DeviceContentTrait, DeviceInfo, DeviceType, StorageInfo, StorageVolume,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Version of the JSON output schema
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// How commands format their results on stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human-readable lines (default)
+    #[default]
+    Text,
+    /// A single JSON document
+    Json,
+}
+
+impl OutputFormat {
+    /// Check if this is JSON output
+    pub fn is_json(&self) -> bool {
+        matches!(self, OutputFormat::Json)
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(format!(
+                "unknown output format '{}' (expected text or json)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// A connected device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceEntry {
+    pub device_id: String,
+    pub friendly_name: String,
+    pub manufacturer: String,
+    pub model: String,
+    pub device_type: DeviceType,
+    /// Storage usage, absent if the device doesn't report it
+    #[serde(default)]
+    pub storage: Option<StorageEntry>,
+}
+
+/// Storage usage of a device in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageEntry {
+    pub capacity_bytes: u64,
+    pub used_bytes: u64,
+    pub free_bytes: u64,
+}
+
+impl From<StorageInfo> for StorageEntry {
+    fn from(storage: StorageInfo) -> Self {
+        Self {
+            capacity_bytes: storage.capacity_bytes,
+            used_bytes: storage.used_bytes(),
+            free_bytes: storage.free_bytes,
+        }
+    }
+}
+
+impl From<&DeviceInfo> for DeviceEntry {
+    fn from(device: &DeviceInfo) -> Self {
+        Self {
+            device_id: device.device_id.clone(),
+            friendly_name: device.friendly_name.clone(),
+            manufacturer: device.manufacturer.clone(),
+            model: device.model.clone(),
+            device_type: device.device_type(),
+            storage: device.storage.map(StorageEntry::from),
+        }
+    }
+}
+
+/// Output of the `list` command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceListDocument {
+    pub schema_version: u32,
+    pub devices: Vec<DeviceEntry>,
+}
+
+impl DeviceListDocument {
+    pub fn new(devices: &[DeviceInfo]) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            devices: devices.iter().map(DeviceEntry::from).collect(),
+        }
+    }
+}
+
+/// A file or folder found while scanning a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanNode {
+    pub object_id: String,
+    pub name: String,
+    pub is_folder: bool,
+    pub size: u64,
+    /// Contents of a folder (empty for files and for folders below the depth limit)
+    #[serde(default)]
+    pub children: Vec<ScanNode>,
+}
+
+/// Output of the `scan` command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanDocument {
+    pub schema_version: u32,
+    pub device: DeviceEntry,
+    /// Storage volumes on the device (internal storage, SD card, ...)
+    #[serde(default)]
+    pub storages: Vec<StorageVolume>,
+    /// Depth limit used for the scan (0 = unlimited)
+    pub max_depth: usize,
+    pub folders: usize,
+    pub files: usize,
+    pub tree: Vec<ScanNode>,
+}
+
+impl ScanDocument {
+    pub fn new(device: &DeviceInfo, max_depth: usize, tree: Vec<ScanNode>) -> Self {
+        let (folders, files) = count_nodes(&tree);
+        Self {
+            schema_version: SCHEMA_VERSION,
+            device: DeviceEntry::from(device),
+            storages: Vec::new(),
+            max_depth,
+            folders,
+            files,
+            tree,
+        }
+    }
+}
+
+impl ScanDocument {
+    /// Set the storage volumes found on the device
+    pub fn with_storages(mut self, storages: Vec<StorageVolume>) -> Self {
+        self.storages = storages;
+        self
+    }
+}
+
+/// Result of extracting from one device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceExtraction {
+    pub device: DeviceEntry,
+    pub output_dir: PathBuf,
+    /// Final statistics, absent if the extraction failed
+    pub stats: Option<ExtractionStats>,
+    /// Why the extraction failed, if it did
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Output of the `extract` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractDocument {
+    pub schema_version: u32,
+    pub dry_run: bool,
+    pub devices: Vec<DeviceExtraction>,
+}
+
+impl ExtractDocument {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            dry_run,
+            devices: Vec::new(),
+        }
+    }
+}
+
+/// Write `document` as pretty-printed JSON followed by a newline
+pub fn write_json<T: Serialize>(out: &mut dyn Write, document: &T) -> anyhow::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, document)?;
+    writeln!(out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Walk the device tree below `object_id` (`max_depth` 0 = unlimited), each
+/// folder's children in `order`
+pub fn build_scan_tree<C: DeviceContentTrait + ?Sized>(
+    content: &C,
+    object_id: &str,
+    max_depth: usize,
+    order: ScanOrder,
+) -> ExtractionResult<Vec<ScanNode>> {
+    scan_level(content, object_id, 0, max_depth, order)
+}
+
+fn scan_level<C: DeviceContentTrait + ?Sized>(
+    content: &C,
+    object_id: &str,
+    depth: usize,
+    max_depth: usize,
+    order: ScanOrder,
+) -> ExtractionResult<Vec<ScanNode>> {
+    if max_depth > 0 && depth >= max_depth {
+        return Ok(Vec::new());
+    }
+
+    let mut children = content.enumerate_children(object_id)?;
+    order.sort(&mut children);
+    children
+        .into_iter()
+        .map(|child| {
+            let children = if child.is_folder {
+                scan_level(content, &child.object_id, depth + 1, max_depth, order)?
+            } else {
+                Vec::new()
+            };
+            Ok(ScanNode {
+                object_id: child.object_id,
+                name: child.name,
+                is_folder: child.is_folder,
+                size: child.size,
+                children,
+            })
+        })
+        .collect()
+}
+
+/// File format written by `scan --export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeExportFormat {
+    /// A [`ScanDocument`] with one tree node per line
+    Json,
+    /// A nested bullet list
+    Markdown,
+}
+
+impl TreeExportFormat {
+    /// Markdown for `.md` and `.markdown` files, JSON for anything else
+    pub fn from_path(path: &Path) -> Self {
+        let is_markdown = path.extension().is_some_and(|ext| {
+            ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+        });
+        if is_markdown {
+            TreeExportFormat::Markdown
+        } else {
+            TreeExportFormat::Json
+        }
+    }
+}
+
+/// Writes a scan tree to `out` node by node while it is being walked
+///
+/// The walk calls [`enter`](Self::enter) for each node and
+/// [`leave`](Self::leave) once the node's children have been entered, so a
+/// large device can be exported without building the whole tree first. JSON
+/// output parses as a [`ScanDocument`].
+pub struct TreeExporter<'a> {
+    format: TreeExportFormat,
+    out: &'a mut dyn Write,
+    /// Whether the next node entered is the first in its folder
+    first_in_folder: bool,
+    counts: (usize, usize),
+}
+
+impl<'a> TreeExporter<'a> {
+    /// Write the document header for `device`
+    pub fn begin(
+        format: TreeExportFormat,
+        device: &DeviceInfo,
+        max_depth: usize,
+        out: &'a mut dyn Write,
+    ) -> ExtractionResult<Self> {
+        match format {
+            TreeExportFormat::Json => write!(
+                out,
+                "{{\n\"schema_version\": {},\n\"device\": {},\n\"max_depth\": {},\n\"tree\": [",
+                SCHEMA_VERSION,
+                json_string(&DeviceEntry::from(device))?,
+                max_depth
+            )?,
+            TreeExportFormat::Markdown => writeln!(out, "# {}\n", device.friendly_name)?,
+        }
+        Ok(Self {
+            format,
+            out,
+            first_in_folder: true,
+            counts: (0, 0),
+        })
+    }
+
+    /// Write a node found `depth` levels below the root
+    pub fn enter(
+        &mut self,
+        depth: usize,
+        object_id: &str,
+        name: &str,
+        is_folder: bool,
+        size: u64,
+    ) -> ExtractionResult<()> {
+        if is_folder {
+            self.counts.0 += 1;
+        } else {
+            self.counts.1 += 1;
+        }
+        match self.format {
+            TreeExportFormat::Json => {
+                let separator = if self.first_in_folder { "" } else { "," };
+                write!(
+                    self.out,
+                    "{}\n{}{{\"object_id\": {}, \"name\": {}, \"is_folder\": {}, \"size\": {}, \"children\": [",
+                    separator,
+                    "  ".repeat(depth + 1),
+                    json_string(object_id)?,
+                    json_string(name)?,
+                    is_folder,
+                    size
+                )?;
+                self.first_in_folder = true;
+            }
+            TreeExportFormat::Markdown if is_folder => {
+                writeln!(self.out, "{}- `{}/`", "  ".repeat(depth), name)?;
+            }
+            TreeExportFormat::Markdown => {
+                writeln!(
+                    self.out,
+                    "{}- `{}` ({})",
+                    "  ".repeat(depth),
+                    name,
+                    format_bytes(size)
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Close the node most recently entered at this depth
+    pub fn leave(&mut self) -> ExtractionResult<()> {
+        if self.format == TreeExportFormat::Json {
+            write!(self.out, "]}}")?;
+        }
+        self.first_in_folder = false;
+        Ok(())
+    }
+
+    /// Write every node of an already built tree
+    pub fn write_nodes(&mut self, nodes: &[ScanNode], depth: usize) -> ExtractionResult<()> {
+        for node in nodes {
+            self.enter(
+                depth,
+                &node.object_id,
+                &node.name,
+                node.is_folder,
+                node.size,
+            )?;
+            self.write_nodes(&node.children, depth + 1)?;
+            self.leave()?;
+        }
+        Ok(())
+    }
+
+    /// Write the document footer and return the number of (folders, files)
+    pub fn finish(self) -> ExtractionResult<(usize, usize)> {
+        let (folders, files) = self.counts;
+        match self.format {
+            TreeExportFormat::Json => writeln!(
+                self.out,
+                "],\n\"folders\": {},\n\"files\": {}\n}}",
+                folders, files
+            )?,
+            TreeExportFormat::Markdown => {
+                writeln!(self.out, "\n{} folders, {} files", folders, files)?
+            }
+        }
+        self.out.flush()?;
+        Ok(self.counts)
+    }
+}
+
+fn json_string<T: Serialize + ?Sized>(value: &T) -> ExtractionResult<String> {
+    serde_json::to_string(value).map_err(|e| ExtractionError::IoError(e.to_string()))
+}
+
+/// Count (folders, files) in a scan tree
+fn count_nodes(nodes: &[ScanNode]) -> (usize, usize) {
+    nodes.iter().fold((0, 0), |(folders, files), node| {
+        let (sub_folders, sub_files) = count_nodes(&node.children);
+        if node.is_folder {
+            (folders + 1 + sub_folders, files + sub_files)
+        } else {
+            (folders + sub_folders, files + 1 + sub_files)
+        }
+    })
+}
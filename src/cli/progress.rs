@@ -10,6 +10,7 @@
 
 #![allow(dead_code)] // Many utilities here are for future use
 
+use crate::core::generic_extractor::{ExtractionStats, FileOutcome, ProgressSink};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::io::Write;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
@@ -384,6 +385,99 @@ impl ExtractionProgress {
     }
 }
 
+// ============================================================================
+// Progress sink for the generic extractor
+// ============================================================================
+
+/// [`ProgressSink`] that drives an [`ExtractionProgress`] bar
+///
+/// The bar is only drawn once the first file starts, and its length is taken
+/// from that file, so the sink can be created before the device has been
+/// scanned without getting in the way of the scan's own output.
+pub struct IndicatifSink {
+    progress: Option<ExtractionProgress>,
+    current_file: String,
+}
+
+impl IndicatifSink {
+    /// Create a sink; the progress bar appears with the first file
+    pub fn new() -> Self {
+        Self {
+            progress: None,
+            current_file: String::new(),
+        }
+    }
+
+    /// Get the underlying extraction progress tracker, once a file has started
+    pub fn progress(&self) -> Option<&ExtractionProgress> {
+        self.progress.as_ref()
+    }
+}
+
+impl Default for IndicatifSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressSink for IndicatifSink {
+    fn on_file_start(&mut self, name: &str, index: usize, total: usize) {
+        let bar = self
+            .progress
+            .get_or_insert_with(|| ExtractionProgress::new(total as u64))
+            .bar();
+        bar.set_length(total as u64);
+        bar.set_position(index.saturating_sub(1) as u64);
+        self.current_file = name.chars().take(25).collect();
+    }
+
+    fn on_bytes(&mut self, copied: u64, total: u64) {
+        let message = if total > 0 {
+            format!(
+                "{} {}/{}",
+                self.current_file,
+                format_bytes(copied),
+                format_bytes(total)
+            )
+        } else {
+            format!("{} {}", self.current_file, format_bytes(copied))
+        };
+        if let Some(ref progress) = self.progress {
+            progress.bar().set_message(message);
+        }
+    }
+
+    fn on_file_done(&mut self, outcome: &FileOutcome) {
+        let Some(ref progress) = self.progress else {
+            return;
+        };
+        match outcome {
+            FileOutcome::Extracted { bytes } | FileOutcome::Hashed { bytes } => {
+                progress.file_completed(&self.current_file, *bytes)
+            }
+            FileOutcome::Skipped | FileOutcome::Duplicate => {
+                progress.file_skipped(&self.current_file)
+            }
+            FileOutcome::Failed(error) => {
+                progress.log_warning(&format!("{}: {}", self.current_file, error));
+                progress.file_skipped(&self.current_file);
+            }
+            FileOutcome::Cancelled => {}
+        }
+    }
+
+    fn on_finished(&mut self, stats: &ExtractionStats) {
+        let Some(ref progress) = self.progress else {
+            return;
+        };
+        if stats.errors > 0 {
+            progress.finish_with_error(&format!("{} files failed", stats.errors));
+        } else {
+            progress.finish();
+        }
+    }
+}
+
 // ============================================================================
 // Multi-stage progress tracker
 // ============================================================================
@@ -0,0 +1,425 @@
+//! Watch mode - extract automatically whenever a device connects
+//!
+//! The `watch` command keeps a [`DeviceMonitor`] running and starts an
+//! extraction for each matching device shortly after it connects. The decision
+//! logic lives in [`Watcher`], a small state machine fed with monitor events,
+//! so it can be exercised with mock devices:
+//!
+//! - A connect only triggers an extraction once the device has stayed
+//!   connected for the debounce period; a disconnect before then cancels it,
+//!   so a flaky cable does not start (and abort) several extractions. The
+//!   timing is the monitor's own [`EventDebouncer`].
+//! - A device that is already being extracted is never started twice.
+//! - With `--once`, the watcher stops after the first successful extraction.
+
+use crate::core::extractor::ExtractionStats;
+use crate::device::DeviceInfo;
+use crate::ui::device_monitor::{DeviceMonitor, EventDebouncer};
+use crate::ui::events::{DeviceEvent, UiEvent};
+use log::{debug, info, warn};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often the watch loop wakes up to check debounce timers and shutdown
+const TICK: Duration = Duration::from_millis(100);
+
+/// Options for watch mode
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// How long a device must stay connected before extraction starts
+    pub debounce: Duration,
+    /// Stop after the first successful extraction
+    pub once: bool,
+    /// Only extract devices whose ID equals, or whose name contains, this value
+    pub device_filter: Option<String>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(2000),
+            once: false,
+            device_filter: None,
+        }
+    }
+}
+
+/// Watch-mode state machine
+///
+/// Feed it monitor events with [`handle_event`](Self::handle_event), poll
+/// [`ready`](Self::ready) for devices to extract, and report each extraction's
+/// outcome with [`finish`](Self::finish).
+#[derive(Debug)]
+pub struct Watcher {
+    options: WatchOptions,
+    /// Connects and disconnects waiting out the debounce period
+    debouncer: EventDebouncer,
+    /// Devices with an extraction in progress
+    busy: HashSet<String>,
+    /// Successful extractions so far
+    completed: usize,
+}
+
+impl Watcher {
+    /// Create a watcher with no devices seen yet
+    pub fn new(options: WatchOptions) -> Self {
+        Self {
+            debouncer: EventDebouncer::new(options.debounce),
+            options,
+            busy: HashSet::new(),
+            completed: 0,
+        }
+    }
+
+    /// Update state for a monitor event received at `now`
+    pub fn handle_event(&mut self, event: &UiEvent, now: Instant) {
+        match event {
+            UiEvent::Device(DeviceEvent::Connected { device, .. }) if !self.matches(device) => {
+                debug!(
+                    "Watch: ignoring non-matching device {}",
+                    device.friendly_name
+                );
+            }
+            UiEvent::Device(
+                event @ (DeviceEvent::Connected { .. } | DeviceEvent::Disconnected { .. }),
+            ) => self.debouncer.push(event.clone(), now),
+            _ => {}
+        }
+    }
+
+    /// Devices whose debounce period has passed, marked busy
+    ///
+    /// Returns nothing once the watcher [`is_done`](Self::is_done).
+    pub fn ready(&mut self, now: Instant) -> Vec<DeviceInfo> {
+        let mut ready = Vec::new();
+        for event in self.debouncer.poll(now) {
+            let DeviceEvent::Connected { device, .. } = event else {
+                continue;
+            };
+            if self.is_done() {
+                continue;
+            }
+            if self.busy.contains(&device.device_id) {
+                debug!("Watch: {} is already being extracted", device.friendly_name);
+                continue;
+            }
+            self.busy.insert(device.device_id.clone());
+            ready.push(device);
+        }
+        ready
+    }
+
+    /// Record that the extraction for `device_id` has ended
+    pub fn finish(&mut self, device_id: &str, success: bool) {
+        self.busy.remove(device_id);
+        if success {
+            self.completed += 1;
+        }
+    }
+
+    /// Check whether an extraction is running for this device
+    pub fn is_busy(&self, device_id: &str) -> bool {
+        self.busy.contains(device_id)
+    }
+
+    /// Check whether any extraction is running
+    pub fn has_busy(&self) -> bool {
+        !self.busy.is_empty()
+    }
+
+    /// Number of successful extractions
+    pub fn completed(&self) -> usize {
+        self.completed
+    }
+
+    /// Whether no further extractions should start (`--once` was satisfied)
+    pub fn is_done(&self) -> bool {
+        self.options.once && self.completed > 0
+    }
+
+    fn matches(&self, device: &DeviceInfo) -> bool {
+        match self.options.device_filter {
+            Some(ref filter) => {
+                device.device_id == *filter || device.friendly_name.contains(filter.as_str())
+            }
+            None => true,
+        }
+    }
+}
+
+/// Run watch mode until shutdown (or the first success with `--once`)
+///
+/// `monitor` must already be started. Each ready device is extracted on its
+/// own thread with `extract`. Returns the number of successful extractions.
+pub fn run_watch<F>(
+    monitor: &DeviceMonitor,
+    options: WatchOptions,
+    shutdown_flag: Arc<AtomicBool>,
+    extract: F,
+) -> usize
+where
+    F: Fn(&DeviceInfo) -> anyhow::Result<ExtractionStats> + Send + Sync + 'static,
+{
+    let extract = Arc::new(extract);
+    let (done_tx, done_rx) = mpsc::channel::<(String, bool)>();
+    let mut workers: HashMap<String, JoinHandle<()>> = HashMap::new();
+    let mut watcher = Watcher::new(options);
+
+    loop {
+        if shutdown_flag.load(Ordering::SeqCst) {
+            info!("Watch: shutdown requested");
+            break;
+        }
+
+        if let Some(event) = monitor.recv_event_timeout(TICK) {
+            watcher.handle_event(&event, Instant::now());
+        }
+
+        while let Ok((device_id, success)) = done_rx.try_recv() {
+            if let Some(worker) = workers.remove(&device_id) {
+                let _ = worker.join();
+            }
+            watcher.finish(&device_id, success);
+        }
+
+        if watcher.is_done() && !watcher.has_busy() {
+            break;
+        }
+
+        for device in watcher.ready(Instant::now()) {
+            info!("Watch: starting extraction for {}", device.friendly_name);
+            if let Some(previous) = workers.remove(&device.device_id) {
+                let _ = previous.join();
+            }
+            let device_id = device.device_id.clone();
+            let extract = Arc::clone(&extract);
+            let done_tx = done_tx.clone();
+            let worker = thread::spawn(move || {
+                let success = match extract(&device) {
+                    Ok(stats) => {
+                        info!(
+                            "Watch: {} done ({} extracted, {} skipped, {} errors)",
+                            device.friendly_name,
+                            stats.files_extracted,
+                            stats.files_skipped,
+                            stats.errors
+                        );
+                        true
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Watch: extraction from {} failed: {}",
+                            device.friendly_name, e
+                        );
+                        false
+                    }
+                };
+                let _ = done_tx.send((device.device_id.clone(), success));
+            });
+            workers.insert(device_id, worker);
+        }
+    }
+
+    // Running extractions see the shutdown flag and stop after their current file
+    for worker in workers.into_values() {
+        let _ = worker.join();
+    }
+    drop(done_tx);
+    for (device_id, success) in done_rx {
+        watcher.finish(&device_id, success);
+    }
+
+    watcher.completed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{DeviceContentTrait, DeviceManagerTrait};
+    use crate::testdb::{MockDeviceInfo, MockDeviceManager, MockFileSystem};
+    use crate::ui::device_monitor::MonitorConfig;
+    use std::sync::atomic::AtomicUsize;
+
+    fn iphone(id: &str) -> DeviceInfo {
+        MockDeviceInfo::new(id, &format!("iPhone {}", id), "Apple Inc.", "iPhone 15")
+    }
+
+    fn connected(device: &DeviceInfo) -> UiEvent {
+        UiEvent::Device(DeviceEvent::Connected {
+            device: device.clone(),
+            previously_known: false,
+        })
+    }
+
+    fn disconnected(device: &DeviceInfo) -> UiEvent {
+        UiEvent::Device(DeviceEvent::Disconnected {
+            device_id: device.device_id.clone(),
+            device_name: Some(device.friendly_name.clone()),
+        })
+    }
+
+    fn options(debounce_ms: u64, once: bool) -> WatchOptions {
+        WatchOptions {
+            debounce: Duration::from_millis(debounce_ms),
+            once,
+            device_filter: None,
+        }
+    }
+
+    #[test]
+    fn test_connect_waits_for_debounce() {
+        let mut watcher = Watcher::new(options(1000, false));
+        let device = iphone("a");
+        let t0 = Instant::now();
+
+        watcher.handle_event(&connected(&device), t0);
+        assert!(watcher.ready(t0 + Duration::from_millis(500)).is_empty());
+
+        let ready = watcher.ready(t0 + Duration::from_millis(1000));
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].device_id, "a");
+        assert!(watcher.is_busy("a"));
+    }
+
+    #[test]
+    fn test_bounce_cancels_pending_extraction() {
+        let mut watcher = Watcher::new(options(1000, false));
+        let device = iphone("a");
+        let t0 = Instant::now();
+
+        watcher.handle_event(&connected(&device), t0);
+        watcher.handle_event(&disconnected(&device), t0 + Duration::from_millis(200));
+        assert!(watcher.ready(t0 + Duration::from_secs(5)).is_empty());
+
+        // Reconnecting restarts the debounce timer
+        let t1 = t0 + Duration::from_millis(300);
+        watcher.handle_event(&connected(&device), t1);
+        assert!(watcher.ready(t1 + Duration::from_millis(900)).is_empty());
+        assert_eq!(watcher.ready(t1 + Duration::from_millis(1000)).len(), 1);
+    }
+
+    #[test]
+    fn test_busy_device_is_not_started_twice() {
+        let mut watcher = Watcher::new(options(0, false));
+        let device = iphone("a");
+        let t0 = Instant::now();
+
+        watcher.handle_event(&connected(&device), t0);
+        assert_eq!(watcher.ready(t0).len(), 1);
+
+        // A reconnect while extracting is ignored
+        watcher.handle_event(&disconnected(&device), t0);
+        assert!(watcher.ready(t0).is_empty());
+        watcher.handle_event(&connected(&device), t0);
+        assert!(watcher.ready(t0).is_empty());
+
+        watcher.finish("a", true);
+        assert!(!watcher.is_busy("a"));
+        watcher.handle_event(&disconnected(&device), t0);
+        assert!(watcher.ready(t0).is_empty());
+        watcher.handle_event(&connected(&device), t0);
+        assert_eq!(watcher.ready(t0).len(), 1);
+    }
+
+    #[test]
+    fn test_once_stops_after_first_success() {
+        let mut watcher = Watcher::new(options(0, true));
+        let (a, b) = (iphone("a"), iphone("b"));
+        let t0 = Instant::now();
+
+        watcher.handle_event(&connected(&a), t0);
+        assert_eq!(watcher.ready(t0).len(), 1);
+
+        // A failure does not count
+        watcher.finish("a", false);
+        assert!(!watcher.is_done());
+
+        watcher.handle_event(&disconnected(&a), t0);
+        assert!(watcher.ready(t0).is_empty());
+        watcher.handle_event(&connected(&a), t0);
+        assert_eq!(watcher.ready(t0).len(), 1);
+        watcher.finish("a", true);
+        assert!(watcher.is_done());
+
+        watcher.handle_event(&connected(&b), t0);
+        assert!(watcher.ready(t0).is_empty());
+        assert_eq!(watcher.completed(), 1);
+    }
+
+    #[test]
+    fn test_device_filter() {
+        let mut watcher = Watcher::new(WatchOptions {
+            device_filter: Some("iPhone b".to_string()),
+            ..options(0, false)
+        });
+        let t0 = Instant::now();
+
+        watcher.handle_event(&connected(&iphone("a")), t0);
+        watcher.handle_event(&connected(&iphone("b")), t0);
+
+        let ready = watcher.ready(t0);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].device_id, "b");
+    }
+
+    #[test]
+    fn test_run_watch_with_mock_monitor() {
+        let mut manager = MockDeviceManager::new();
+        let mut fs = MockFileSystem::new();
+        fs.add_standard_dcim_structure(5, 1);
+        manager.add_device(iphone("a"), fs);
+        let manager = Arc::new(manager);
+
+        let monitor = DeviceMonitor::with_config(MonitorConfig::default().with_poll_interval(10));
+        monitor.start(Arc::clone(&manager)).unwrap();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = Arc::clone(&calls);
+        let extract_manager = Arc::clone(&manager);
+        let completed = run_watch(
+            &monitor,
+            options(50, true),
+            Arc::new(AtomicBool::new(false)),
+            move |device| {
+                calls_clone.fetch_add(1, Ordering::SeqCst);
+                let content = extract_manager.open_device(&device.device_id)?;
+                let files = content.enumerate_children("100apple")?.len();
+                Ok(ExtractionStats {
+                    files_extracted: files,
+                    ..Default::default()
+                })
+            },
+        );
+        monitor.stop();
+
+        assert_eq!(completed, 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_run_watch_stops_on_shutdown() {
+        let monitor = DeviceMonitor::with_config(MonitorConfig::default().with_poll_interval(10));
+        monitor.start(Arc::new(MockDeviceManager::new())).unwrap();
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let shutdown_clone = Arc::clone(&shutdown);
+        let stopper = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(200));
+            shutdown_clone.store(true, Ordering::SeqCst);
+        });
+
+        let start = Instant::now();
+        let completed = run_watch(&monitor, options(0, false), shutdown, |_| {
+            Ok(ExtractionStats::default())
+        });
+        stopper.join().unwrap();
+        monitor.stop();
+
+        assert_eq!(completed, 0);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
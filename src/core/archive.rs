@@ -0,0 +1,395 @@
+//! Extraction destinations
+//!
+//! Extracted files normally land in a folder tree under the output directory.
+//! They can instead be streamed into a single ZIP archive whose entry names
+//! mirror the paths the folder tree would have used (device structure or
+//! date folders). Photos and videos are already compressed, so entries are
+//! stored as-is. Extracting into an existing archive appends to it, and its
+//! entries count as existing files for skip-existing. Two files that map to
+//! the same entry name are both kept, the later one with a numeric suffix.
+
+use crate::core::error::{ExtractionError, Result};
+use crate::core::extractor::{parse_device_datetime, CopyResult, WRITE_CHUNK_SIZE};
+use crate::core::throttle::Throttle;
+use chrono::{Datelike, Timelike};
+use log::warn;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Where extracted files are written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Destination {
+    /// A folder tree rooted at this directory
+    Directory(PathBuf),
+    /// Entries of a single ZIP archive at this path
+    Zip(PathBuf),
+}
+
+impl Destination {
+    /// Check if files go into a ZIP archive
+    pub fn is_zip(&self) -> bool {
+        matches!(self, Destination::Zip(_))
+    }
+
+    /// Folder on the drive that receives the extracted data
+    pub fn folder(&self) -> &Path {
+        match self {
+            Destination::Directory(dir) => dir,
+            Destination::Zip(path) => path
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new(".")),
+        }
+    }
+}
+
+/// A ZIP archive being written by an extraction
+pub struct ZipDestination {
+    path: PathBuf,
+    writer: ZipWriter<File>,
+    /// Size of every entry in the archive, by name
+    entries: HashMap<String, u64>,
+    /// Entries written since the archive was opened
+    written: HashSet<String>,
+}
+
+impl ZipDestination {
+    /// Open the archive at `path`, appending to it if it already exists
+    pub fn open(path: &Path) -> Result<Self> {
+        let archive_error = |e: &dyn std::fmt::Display| {
+            ExtractionError::IoError(format!(
+                "Failed to open archive '{}': {}",
+                path.display(),
+                e
+            ))
+        };
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to create directory '{}': {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+
+        let mut entries = HashMap::new();
+        let writer = if path.exists() {
+            let file = File::options()
+                .read(true)
+                .write(true)
+                .open(path)
+                .map_err(|e| archive_error(&e))?;
+            let mut archive = ZipArchive::new(&file).map_err(|e| archive_error(&e))?;
+            for i in 0..archive.len() {
+                let entry = archive.by_index(i).map_err(|e| archive_error(&e))?;
+                entries.insert(entry.name().to_string(), entry.size());
+            }
+            drop(archive);
+            ZipWriter::new_append(file).map_err(|e| archive_error(&e))?
+        } else {
+            ZipWriter::new(File::create(path).map_err(|e| archive_error(&e))?)
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer,
+            entries,
+            written: HashSet::new(),
+        })
+    }
+
+    /// Path of the archive file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of entries in the archive
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the archive has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check if an entry with this name (and size, when known) was in the
+    /// archive before it was opened
+    ///
+    /// Entries written since then came from other device files, which merely
+    /// share the name.
+    pub fn contains(&self, name: &str, size: u64) -> bool {
+        !self.written.contains(name)
+            && self
+                .entries
+                .get(name)
+                .is_some_and(|&existing| existing == size || size == 0)
+    }
+
+    /// `name`, or `name` with a numeric suffix if that entry is taken
+    pub fn unique_name(&self, name: &str) -> String {
+        if !self.entries.contains_key(name) {
+            return name.to_string();
+        }
+
+        let (folder, file_name) = match name.rsplit_once('/') {
+            Some((folder, file_name)) => (format!("{}/", folder), file_name),
+            None => (String::new(), name),
+        };
+        let (stem, extension) = match file_name.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+            _ => (file_name, String::new()),
+        };
+
+        (1..)
+            .map(|counter| format!("{}{}_{}{}", folder, stem, counter, extension))
+            .find(|candidate| !self.entries.contains_key(candidate))
+            .expect("unbounded counter always finds a free name")
+    }
+
+    /// Write a new entry from the chunks `produce` passes to the sink
+    ///
+    /// Returns the entry's name and the size and SHA256 of what was written.
+    /// The name is `name`, or [`unique_name`](Self::unique_name) if an entry
+    /// of that name already exists, so both copies are kept. `size` is the
+    /// size the device reports, which decides whether the entry needs ZIP64,
+    /// and `date_modified` the device timestamp stored as its modified time.
+    ///
+    /// Only one chunk is held in memory at a time. The shutdown flag is
+    /// checked before every [`WRITE_CHUNK_SIZE`] piece; if shutdown is
+    /// requested ([`ExtractionError::Cancelled`]) or anything else fails, the
+    /// unfinished entry is removed from the archive again.
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_entry<F>(
+        &mut self,
+        name: &str,
+        size: u64,
+        date_modified: Option<&str>,
+        throttle: &Throttle,
+        shutdown_flag: &AtomicBool,
+        on_bytes: &mut dyn FnMut(u64),
+        produce: F,
+    ) -> Result<(String, CopyResult)>
+    where
+        F: FnOnce(&mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()>,
+    {
+        let name = self.unique_name(name);
+        let archive_path = self.path.display().to_string();
+        let write_error = |e: &dyn std::fmt::Display| {
+            ExtractionError::IoError(format!(
+                "Failed to write '{}' to archive '{}': {}",
+                name, archive_path, e
+            ))
+        };
+
+        let mut options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Stored)
+            .large_file(size >= u32::MAX as u64);
+        if let Some(modified) = date_modified.and_then(zip_datetime) {
+            options = options.last_modified_time(modified);
+        }
+
+        self.writer
+            .start_file(name.as_str(), options)
+            .map_err(|e| write_error(&e))?;
+        let writer = &mut self.writer;
+        let mut hasher = Sha256::new();
+        let mut written = 0u64;
+        let result = produce(&mut |chunk| {
+            for piece in chunk.chunks(WRITE_CHUNK_SIZE) {
+                if shutdown_flag.load(Ordering::SeqCst) {
+                    return Err(ExtractionError::Cancelled);
+                }
+                hasher.update(piece);
+                writer.write_all(piece).map_err(|e| write_error(&e))?;
+                written += piece.len() as u64;
+                throttle.consume(piece.len() as u64);
+                on_bytes(written);
+            }
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            if let Err(abort_err) = self.writer.abort_file() {
+                warn!(
+                    "Could not remove unfinished entry '{}': {}",
+                    name, abort_err
+                );
+            }
+            return Err(e);
+        }
+
+        self.entries.insert(name.clone(), written);
+        self.written.insert(name.clone());
+        Ok((
+            name,
+            CopyResult {
+                bytes: written,
+                hash: hasher.finalize().into(),
+            },
+        ))
+    }
+
+    /// Write the archive's central directory and close it
+    pub fn finish(self) -> Result<()> {
+        let path = self.path;
+        self.writer.finish().map(|_| ()).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to finish archive '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Archive entry name for `path` relative to `root`, with `/` separators
+pub fn entry_name(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Convert a device timestamp into a ZIP (MS-DOS) timestamp
+fn zip_datetime(date_str: &str) -> Option<zip::DateTime> {
+    let dt = parse_device_datetime(date_str)?;
+    zip::DateTime::from_date_and_time(
+        u16::try_from(dt.year()).ok()?,
+        dt.month() as u8,
+        dt.day() as u8,
+        dt.hour() as u8,
+        dt.minute() as u8,
+        dt.second() as u8,
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Write `data` as an entry, returning the name it was stored under
+    fn write(zip: &mut ZipDestination, name: &str, data: &[u8], date: Option<&str>) -> String {
+        let (name, copy) = zip
+            .write_entry(
+                name,
+                data.len() as u64,
+                date,
+                &Throttle::unlimited(),
+                &AtomicBool::new(false),
+                &mut |_| {},
+                |sink| sink(data),
+            )
+            .unwrap();
+        assert_eq!(copy.bytes, data.len() as u64);
+        name
+    }
+
+    #[test]
+    fn test_entry_name_is_relative_with_forward_slashes() {
+        let root = Path::new("out");
+        assert_eq!(
+            entry_name(root, &root.join("2024").join("03").join("IMG_0001.JPG")),
+            "2024/03/IMG_0001.JPG"
+        );
+        assert_eq!(entry_name(root, Path::new("IMG_0002.JPG")), "IMG_0002.JPG");
+    }
+
+    #[test]
+    fn test_reopened_archive_appends_and_knows_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("photos.zip");
+
+        let mut zip = ZipDestination::open(&path).unwrap();
+        write(
+            &mut zip,
+            "DCIM/IMG_0001.JPG",
+            b"first",
+            Some("2024-03-01T10:00:00Z"),
+        );
+        zip.finish().unwrap();
+
+        let mut zip = ZipDestination::open(&path).unwrap();
+        assert!(zip.contains("DCIM/IMG_0001.JPG", 5));
+        assert!(!zip.contains("DCIM/IMG_0001.JPG", 6));
+        assert_eq!(zip.unique_name("DCIM/IMG_0001.JPG"), "DCIM/IMG_0001_1.JPG");
+        write(&mut zip, "DCIM/IMG_0002.JPG", b"second", None);
+        zip.finish().unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+        let first = archive.by_name("DCIM/IMG_0001.JPG").unwrap();
+        assert_eq!(first.size(), 5);
+        let modified = first.last_modified().unwrap();
+        assert_eq!(
+            (modified.year(), modified.month(), modified.day()),
+            (2024, 3, 1)
+        );
+    }
+
+    #[test]
+    fn test_entries_with_the_same_name_are_both_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("photos.zip");
+
+        let mut zip = ZipDestination::open(&path).unwrap();
+        assert_eq!(
+            write(&mut zip, "DCIM/IMG_0001.JPG", b"first", None),
+            "DCIM/IMG_0001.JPG"
+        );
+        assert!(!zip.contains("DCIM/IMG_0001.JPG", 5));
+        assert_eq!(
+            write(&mut zip, "DCIM/IMG_0001.JPG", b"second", None),
+            "DCIM/IMG_0001_1.JPG"
+        );
+        zip.finish().unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.by_name("DCIM/IMG_0001.JPG").unwrap().size(), 5);
+        assert_eq!(archive.by_name("DCIM/IMG_0001_1.JPG").unwrap().size(), 6);
+    }
+
+    #[test]
+    fn test_failed_entry_is_removed() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("photos.zip");
+
+        let mut zip = ZipDestination::open(&path).unwrap();
+        write(&mut zip, "DCIM/IMG_0001.JPG", b"first", None);
+        let result = zip.write_entry(
+            "DCIM/IMG_0002.MOV",
+            1 << 20,
+            None,
+            &Throttle::unlimited(),
+            &AtomicBool::new(false),
+            &mut |_| {},
+            |sink| {
+                sink(&[0; 1024])?;
+                Err(ExtractionError::DeviceDisconnected)
+            },
+        );
+        assert!(matches!(result, Err(ExtractionError::DeviceDisconnected)));
+        assert!(!zip.contains("DCIM/IMG_0002.MOV", 0));
+        zip.finish().unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 1);
+        assert!(archive.by_name("DCIM/IMG_0002.MOV").is_err());
+    }
+}
@@ -0,0 +1,328 @@
+//! Checkpoints for resuming an interrupted extraction
+//!
+//! While an extraction runs, the object IDs it has finished with and the
+//! folders whose media files are all done are saved every few files to a
+//! checkpoint next to the output. Starting again with the same checkpoint
+//! skips those objects, and completed folders are not enumerated at all, so
+//! resuming a large device doesn't start with a full re-scan.
+//!
+//! The file is written to a temporary name and renamed into place, so a crash
+//! while saving leaves the previous checkpoint intact. It is removed once an
+//! extraction finishes without errors.
+
+use crate::core::error::{ExtractionError, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Default checkpoint file name, created in the output directory
+pub const CHECKPOINT_FILE_NAME: &str = ".photo_extraction_checkpoint.json";
+
+/// Default number of finished files between checkpoint saves
+pub const DEFAULT_CHECKPOINT_INTERVAL: usize = 25;
+
+/// Progress of an extraction as saved to disk
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Object IDs that were copied or skipped
+    pub completed: BTreeSet<String>,
+    /// Device folders whose media files, including subfolders, are all completed
+    pub completed_folders: BTreeSet<String>,
+    /// Number of files handled so far in the last run, including resumed ones
+    pub position: usize,
+    /// Number of files the last run set out to handle
+    pub total: usize,
+}
+
+impl Checkpoint {
+    /// Default checkpoint location for an output directory
+    pub fn default_path(output_dir: &Path) -> PathBuf {
+        output_dir.join(CHECKPOINT_FILE_NAME)
+    }
+
+    /// Load a checkpoint, or return an empty one if `path` doesn't exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let file = File::open(path).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to open checkpoint '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        serde_json::from_reader(std::io::BufReader::new(file)).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to read checkpoint '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Save the checkpoint to `path` atomically
+    ///
+    /// The JSON is written and synced to `<path>.tmp`, then renamed over
+    /// `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to create checkpoint directory '{}': {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+
+        let temp_path = temp_path(path);
+        let write = || -> std::io::Result<()> {
+            let mut writer = BufWriter::new(File::create(&temp_path)?);
+            serde_json::to_writer(&mut writer, self)?;
+            writer.flush()?;
+            writer.get_ref().sync_all()?;
+            Ok(())
+        };
+        if let Err(e) = write() {
+            let _ = fs::remove_file(&temp_path);
+            return Err(ExtractionError::IoError(format!(
+                "Failed to write checkpoint '{}': {}",
+                temp_path.display(),
+                e
+            )));
+        }
+
+        fs::rename(&temp_path, path).map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            ExtractionError::IoError(format!(
+                "Failed to replace checkpoint '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Delete the checkpoint at `path`, if there is one
+    pub fn remove(path: &Path) -> Result<()> {
+        match fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(ExtractionError::IoError(format!(
+                "Failed to remove checkpoint '{}': {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Folders containing `path`, innermost first
+///
+/// `"Internal Storage/DCIM/100APPLE/IMG_0001.JPG"` yields
+/// `"Internal Storage/DCIM/100APPLE"`, `"Internal Storage/DCIM"` and
+/// `"Internal Storage"`.
+pub fn parent_folders(path: &str) -> impl Iterator<Item = &str> {
+    path.rmatch_indices('/')
+        .map(move |(index, _)| &path[..index])
+}
+
+/// Keeps a [`Checkpoint`] up to date during an extraction
+///
+/// Every media file found is registered with [`add_pending`] before copying
+/// starts. As files are finished, folders with nothing left pending are
+/// marked complete, and the checkpoint is saved every `interval` files.
+///
+/// [`add_pending`]: CheckpointTracker::add_pending
+#[derive(Debug)]
+pub struct CheckpointTracker {
+    path: PathBuf,
+    checkpoint: Checkpoint,
+    pending: HashMap<String, usize>,
+    interval: usize,
+    unsaved: usize,
+}
+
+impl CheckpointTracker {
+    /// Resume from the checkpoint at `path`, or start a new one
+    ///
+    /// A checkpoint that can't be read is discarded with a warning rather
+    /// than stopping the extraction.
+    pub fn open(path: impl Into<PathBuf>, interval: usize) -> Self {
+        let path = path.into();
+        let checkpoint = Checkpoint::load(&path).unwrap_or_else(|e| {
+            warn!("{}; starting without a checkpoint", e);
+            Checkpoint::default()
+        });
+        if !checkpoint.completed.is_empty() {
+            debug!(
+                "Resuming from checkpoint: {} files and {} folders already done",
+                checkpoint.completed.len(),
+                checkpoint.completed_folders.len()
+            );
+        }
+        Self {
+            path,
+            checkpoint,
+            pending: HashMap::new(),
+            interval: interval.max(1),
+            unsaved: 0,
+        }
+    }
+
+    /// Checkpoint as currently tracked
+    pub fn checkpoint(&self) -> &Checkpoint {
+        &self.checkpoint
+    }
+
+    /// Checkpoint file location
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Register a media file at device `path` that still has to be handled
+    pub fn add_pending(&mut self, path: &str) {
+        for folder in parent_folders(path) {
+            *self.pending.entry(folder.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Start counting the position in a run that will handle `total` files
+    pub fn begin_run(&mut self, total: usize) {
+        self.checkpoint.position = 0;
+        self.checkpoint.total = total;
+    }
+
+    /// Record a finished file, saving the checkpoint when the interval is reached
+    pub fn complete(&mut self, object_id: &str, path: &str) -> Result<()> {
+        self.checkpoint.completed.insert(object_id.to_string());
+        self.checkpoint.position += 1;
+        for folder in parent_folders(path) {
+            if let Some(count) = self.pending.get_mut(folder) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    self.pending.remove(folder);
+                    self.checkpoint.completed_folders.insert(folder.to_string());
+                }
+            }
+        }
+
+        self.unsaved += 1;
+        if self.unsaved >= self.interval {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Save the checkpoint now
+    pub fn save(&mut self) -> Result<()> {
+        self.checkpoint.save(&self.path)?;
+        self.unsaved = 0;
+        Ok(())
+    }
+
+    /// Delete the checkpoint file once the extraction no longer needs it
+    pub fn finish(self) -> Result<()> {
+        Checkpoint::remove(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_round_trip_atomically() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = Checkpoint::default_path(temp.path());
+        assert_eq!(Checkpoint::load(&path).unwrap(), Checkpoint::default());
+
+        let checkpoint = Checkpoint {
+            completed: ["a".to_string(), "b".to_string()].into(),
+            completed_folders: ["Internal Storage/DCIM/100APPLE".to_string()].into(),
+            position: 2,
+            total: 5,
+        };
+        checkpoint.save(&path).unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap(), checkpoint);
+        assert!(!temp_path(&path).exists());
+
+        // A leftover temp file from a crash mid-save is simply replaced
+        fs::write(temp_path(&path), "{ truncated").unwrap();
+        Checkpoint::default().save(&path).unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap(), Checkpoint::default());
+
+        Checkpoint::remove(&path).unwrap();
+        assert!(!path.exists());
+        Checkpoint::remove(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tracker_completes_folders_and_saves_on_interval() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("checkpoint.json");
+        let mut tracker = CheckpointTracker::open(&path, 2);
+        for file in [
+            "Internal Storage/DCIM/100APPLE/A.JPG",
+            "Internal Storage/DCIM/100APPLE/B.JPG",
+            "Internal Storage/DCIM/101APPLE/C.JPG",
+        ] {
+            tracker.add_pending(file);
+        }
+        tracker.begin_run(3);
+
+        tracker
+            .complete("a", "Internal Storage/DCIM/100APPLE/A.JPG")
+            .unwrap();
+        assert!(!path.exists());
+        assert!(tracker.checkpoint().completed_folders.is_empty());
+
+        tracker
+            .complete("b", "Internal Storage/DCIM/100APPLE/B.JPG")
+            .unwrap();
+        let saved = Checkpoint::load(&path).unwrap();
+        assert_eq!(saved.position, 2);
+        assert_eq!(saved.total, 3);
+        assert_eq!(
+            saved.completed_folders,
+            ["Internal Storage/DCIM/100APPLE".to_string()].into()
+        );
+
+        tracker
+            .complete("c", "Internal Storage/DCIM/101APPLE/C.JPG")
+            .unwrap();
+        assert!(tracker
+            .checkpoint()
+            .completed_folders
+            .contains("Internal Storage/DCIM"));
+
+        // Reopening picks up what was last saved
+        let reopened = CheckpointTracker::open(&path, 2);
+        assert_eq!(reopened.checkpoint(), &saved);
+        reopened.finish().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_parent_folders() {
+        let folders: Vec<_> = parent_folders("Internal Storage/DCIM/100APPLE/IMG.JPG").collect();
+        assert_eq!(
+            folders,
+            vec![
+                "Internal Storage/DCIM/100APPLE",
+                "Internal Storage/DCIM",
+                "Internal Storage"
+            ]
+        );
+        assert_eq!(parent_folders("IMG.JPG").count(), 0);
+    }
+}
@@ -5,6 +5,11 @@
 //! - Windows: %APPDATA%\photo_extraction_tool\config.toml
 //! - Linux/macOS: ~/.config/photo_extraction_tool/config.toml
 
+use crate::core::checkpoint::{Checkpoint, DEFAULT_CHECKPOINT_INTERVAL};
+use crate::core::discovery::{ScanOrder, DEFAULT_SCAN_THREADS, MAX_SCAN_THREADS};
+use crate::core::manifest::ManifestFormat;
+use crate::core::template::{PathTemplate, TemplateError, TEMPLATE_TOKENS};
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -16,6 +21,38 @@ const APP_NAME: &str = "photo_extraction_tool";
 /// Default config file name
 const CONFIG_FILE_NAME: &str = "config.toml";
 
+/// Current layout version written to `config_version`
+///
+/// No key has been renamed or moved since the first layout, so this is still
+/// 1. Bump it, and give [`migrate`] a step for the older versions, with the
+/// first change that would leave an older file's values unread.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Environment variables read by [`Config::apply_env_overrides`], with the
+/// setting each one overrides
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("PET_OUTPUT_DIR", "output.directory"),
+    ("PET_DEVICE_ID", "device.device_id"),
+    ("PET_APPLE_ONLY", "device.apple_only"),
+    ("PET_DCIM_ONLY", "extraction.dcim_only"),
+    ("PET_SKIP_EXISTING", "output.skip_existing"),
+    ("PET_PRESERVE_STRUCTURE", "output.preserve_structure"),
+    ("PET_MAX_BYTES_PER_SEC", "extraction.max_bytes_per_sec"),
+    ("PET_LOG_LEVEL", "logging.level"),
+];
+
+/// Accepted values for `logging.level`
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+/// Upper bound for `extraction.max_retries` and `extraction.verify_retries`
+const MAX_RETRY_COUNT: usize = 10;
+
+/// Upper bound for `extraction.retry_base_delay_ms`
+const MAX_RETRY_DELAY_MS: u64 = 60_000;
+
+/// Upper bound for `extraction.reconnect_timeout_secs`
+const MAX_RECONNECT_TIMEOUT_SECS: u64 = 3600;
+
 /// Get the standard configuration directory for the application.
 ///
 /// Returns:
@@ -111,10 +148,10 @@ pub fn open_config_in_editor() -> Result<PathBuf, ConfigError> {
     Ok(config_path)
 }
 
-/// Action to take when a duplicate is found
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum DuplicateAction {
+/// How to resolve an incoming file whose content matches an existing one
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateStrategy {
     /// Skip the duplicate file (don't extract)
     #[default]
     Skip,
@@ -122,12 +159,128 @@ pub enum DuplicateAction {
     Overwrite,
     /// Keep both by renaming the new file with a suffix
     Rename,
+    /// Overwrite only if the incoming file is newer than the existing one
+    KeepNewest,
+    /// Overwrite only if the incoming file is larger than the existing one
+    KeepLargest,
+}
+
+impl DuplicateStrategy {
+    /// Every strategy, in the order shown to users
+    pub const ALL: [DuplicateStrategy; 5] = [
+        DuplicateStrategy::Skip,
+        DuplicateStrategy::Overwrite,
+        DuplicateStrategy::Rename,
+        DuplicateStrategy::KeepNewest,
+        DuplicateStrategy::KeepLargest,
+    ];
+
+    /// Name used in config files and on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuplicateStrategy::Skip => "skip",
+            DuplicateStrategy::Overwrite => "overwrite",
+            DuplicateStrategy::Rename => "rename",
+            DuplicateStrategy::KeepNewest => "keep_newest",
+            DuplicateStrategy::KeepLargest => "keep_largest",
+        }
+    }
+
+    /// Parse a strategy name (`-` and `_` are interchangeable)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace('-', "_");
+        Self::ALL.into_iter().find(|s| s.as_str() == name)
+    }
+}
+
+/// How skip-existing decides a file was already extracted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipExistingMode {
+    /// A file of the same name and size exists at the destination
+    #[default]
+    ByName,
+    /// A file with identical content exists anywhere in the output folder
+    ByHash,
+}
+
+impl SkipExistingMode {
+    /// Name used in config files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipExistingMode::ByName => "by_name",
+            SkipExistingMode::ByHash => "by_hash",
+        }
+    }
+}
+
+impl std::fmt::Display for SkipExistingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What to do with a destination path longer than Windows' 260 character limit
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LongPathStrategy {
+    /// Write through a `\\?\` extended-length path, keeping the full name
+    #[default]
+    ExtendedPrefix,
+    /// Shorten the file name to fit, ending it with a hash of the full name
+    Truncate,
+}
+
+impl LongPathStrategy {
+    /// Name used in config files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LongPathStrategy::ExtendedPrefix => "extended_prefix",
+            LongPathStrategy::Truncate => "truncate",
+        }
+    }
+}
+
+impl std::fmt::Display for LongPathStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Sort options
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortBy {
+    /// Sort by name
+    Name,
+    /// Sort by date
+    #[default]
+    Date,
+    /// Sort by size
+    Size,
+    /// Sort by type/extension
+    Type,
+}
+
+impl SortBy {
+    /// Name used in config files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortBy::Name => "name",
+            SortBy::Date => "date",
+            SortBy::Size => "size",
+            SortBy::Type => "type",
+        }
+    }
 }
 
 /// Main configuration structure
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
+    /// Layout version of the file this was loaded from (see [`migrate`])
+    pub config_version: u32,
+
     /// Output settings
     pub output: OutputConfig,
 
@@ -154,6 +307,22 @@ pub struct Config {
     pub device_profiles: DeviceProfilesConfig,
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            config_version: CONFIG_VERSION,
+            output: OutputConfig::default(),
+            device: DeviceConfig::default(),
+            extraction: ExtractionConfig::default(),
+            android: AndroidConfig::default(),
+            logging: LoggingConfig::default(),
+            duplicate_detection: DuplicateDetectionConfig::default(),
+            tracking: TrackingConfig::default(),
+            device_profiles: DeviceProfilesConfig::default(),
+        }
+    }
+}
+
 /// Device profiles configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -196,6 +365,23 @@ pub struct DeviceProfile {
     /// Last time this device was used
     #[serde(default)]
     pub last_seen: Option<String>,
+
+    /// What the last completed extraction from this device brought over
+    #[serde(default)]
+    pub last_extraction: Option<ExtractionSummary>,
+}
+
+/// Outcome of an extraction, as remembered in a device profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractionSummary {
+    /// When the extraction finished
+    pub timestamp: String,
+
+    /// Number of files copied
+    pub files_extracted: usize,
+
+    /// Bytes copied
+    pub total_bytes: u64,
 }
 
 /// Output directory configuration
@@ -211,11 +397,55 @@ pub struct OutputConfig {
     /// Whether to skip existing files
     pub skip_existing: bool,
 
+    /// How an existing file is recognized: by_name (same name and size at
+    /// the destination) or by_hash (same content anywhere in the output)
+    pub skip_existing_mode: SkipExistingMode,
+
+    /// What to do when a destination path is longer than 260 characters:
+    /// extended_prefix (write it through `\\?\`) or truncate (shorten the
+    /// file name)
+    pub long_path_strategy: LongPathStrategy,
+
     /// Organize photos by date (YYYY/MM folders)
     pub organize_by_date: bool,
 
+    /// Keep Live Photo videos in the same folder as their still image
+    pub preserve_live_photo_pairs: bool,
+
+    /// Put the frames of each iPhone burst into their own `BURST<id>` folder
+    pub group_bursts: bool,
+
+    /// Free space (in MB) to leave on the output drive after extraction
+    pub space_safety_margin_mb: u64,
+
+    /// Write a manifest of every processed file to this path (unset = no manifest)
+    pub manifest_path: Option<PathBuf>,
+
+    /// Manifest format: json or csv
+    pub manifest_format: ManifestFormat,
+
+    /// Append a JSON line for every processed file to this log, keeping a
+    /// history across runs (unset = no log)
+    pub append_to_log: Option<PathBuf>,
+
+    /// Write a self-contained HTML report of the extracted files, grouped by
+    /// date, to this path (unset = no report)
+    pub report_html: Option<PathBuf>,
+
+    /// Write extracted files into a ZIP archive at this path instead of the
+    /// output folder (unset = plain folder tree)
+    pub zip_path: Option<PathBuf>,
+
+    /// Open the output folder in Explorer once an extraction finishes
+    pub open_folder_after_extraction: bool,
+
     /// Create subfolder with device name
     pub subfolder_by_device: bool,
+
+    /// Layout of output paths, such as `{device}/{year}/{month}/{original_name}`
+    /// (unset = built from `preserve_structure`, `organize_by_date` and
+    /// `subfolder_by_device`)
+    pub path_template: Option<String>,
 }
 
 /// Device configuration
@@ -245,6 +475,14 @@ pub struct ExtractionConfig {
     /// File extensions to exclude
     pub exclude_extensions: Vec<String>,
 
+    /// Only extract files whose device path matches one of these globs
+    /// (empty = all paths), e.g. `**/DCIM/**`
+    pub include_globs: Vec<String>,
+
+    /// Skip files whose device path matches one of these globs, e.g.
+    /// `**/DCIM/100CLOUD/**` (takes precedence over `include_globs`)
+    pub exclude_globs: Vec<String>,
+
     /// Minimum file size in bytes (0 = no minimum)
     pub min_file_size: u64,
 
@@ -256,6 +494,60 @@ pub struct ExtractionConfig {
 
     /// Include videos
     pub include_videos: bool,
+
+    /// Only extract files dated on or after this day (YYYY-MM-DD)
+    pub date_from: Option<NaiveDate>,
+
+    /// Only extract files dated on or before this day (YYYY-MM-DD)
+    pub date_to: Option<NaiveDate>,
+
+    /// Include files with no readable date when a date range is set
+    pub include_undated: bool,
+
+    /// Re-read and checksum each file after copying it
+    pub verify_after_copy: bool,
+
+    /// Number of times to re-copy a file that fails verification
+    pub verify_retries: usize,
+
+    /// Delete each file from the device after it is copied, verified and
+    /// tracked (requires `verify_after_copy`)
+    pub delete_after_extract: bool,
+
+    /// Maximum retries for transient device read errors (0 = no retries)
+    pub max_retries: usize,
+
+    /// Delay before the first retry in milliseconds (doubled each retry)
+    pub retry_base_delay_ms: u64,
+
+    /// Seconds to wait for a device that drops mid-extraction to come back
+    /// before giving up on the rest of the files (0 = don't wait)
+    pub reconnect_timeout_secs: u64,
+
+    /// Keep a checkpoint in the output folder and resume an interrupted
+    /// extraction from it, skipping the files and folders it already finished
+    pub resume_from_checkpoint: bool,
+
+    /// Number of finished files between checkpoint saves
+    pub checkpoint_interval: usize,
+
+    /// Cap on bytes read from the device per second (unset = unlimited)
+    pub max_bytes_per_sec: Option<u64>,
+
+    /// Number of device folders listed at once while scanning (1 = one at a time)
+    pub scan_threads: usize,
+
+    /// Order each folder's children are walked in, so scans and extraction
+    /// order don't change from run to run
+    pub sort_by: SortBy,
+
+    /// Walk children in ascending (rather than descending) `sort_by` order
+    pub sort_ascending: bool,
+
+    /// Preview the extraction without copying anything (set by `--dry-run`,
+    /// never read from or saved to the config file)
+    #[serde(skip)]
+    pub dry_run: bool,
 }
 
 /// Logging configuration
@@ -293,13 +585,23 @@ pub struct DuplicateDetectionConfig {
     pub cache_file: PathBuf,
 
     /// Action to take when a duplicate is found
-    pub duplicate_action: DuplicateAction,
+    pub duplicate_action: DuplicateStrategy,
 
     /// Scan comparison folders recursively (include subdirectories)
     pub recursive: bool,
 
     /// Only index media files (photos/videos) vs all files
     pub media_only: bool,
+
+    /// Leading bytes compared (by hash) before hashing a whole file
+    /// (0 = always hash the whole file)
+    pub quick_hash_bytes: usize,
+
+    /// Write a report of every duplicate found to this path (unset = no report)
+    pub report_path: Option<PathBuf>,
+
+    /// Duplicate report format: json or csv
+    pub report_format: ManifestFormat,
 }
 
 /// Tracking configuration for remembering device and extraction state
@@ -314,6 +616,10 @@ pub struct TrackingConfig {
 
     /// Track individual files that have been extracted
     pub track_extracted_files: bool,
+
+    /// SQLite database to record extracted files in, instead of the tracking
+    /// file; a relative path is taken from the output directory
+    pub database: Option<PathBuf>,
 }
 
 /// Android-specific extraction configuration
@@ -646,12 +952,41 @@ impl Default for OutputConfig {
             directory: PathBuf::new(), // Empty = needs setup
             preserve_structure: true,
             skip_existing: true,
+            skip_existing_mode: SkipExistingMode::ByName,
+            long_path_strategy: LongPathStrategy::ExtendedPrefix,
             organize_by_date: false,
+            preserve_live_photo_pairs: true,
+            group_bursts: false,
+            space_safety_margin_mb: 100,
+            manifest_path: None,
+            manifest_format: ManifestFormat::Json,
+            append_to_log: None,
+            report_html: None,
+            zip_path: None,
+            open_folder_after_extraction: false,
             subfolder_by_device: false,
+            path_template: None,
         }
     }
 }
 
+impl OutputConfig {
+    /// Layout of output paths: `path_template` when set and valid, otherwise
+    /// the one the boolean options describe
+    pub fn output_template(&self) -> PathTemplate {
+        self.path_template
+            .as_deref()
+            .and_then(|template| PathTemplate::parse(template).ok())
+            .unwrap_or_else(|| {
+                PathTemplate::from_options(
+                    self.preserve_structure,
+                    self.organize_by_date,
+                    self.subfolder_by_device,
+                )
+            })
+    }
+}
+
 impl Default for DeviceConfig {
     fn default() -> Self {
         Self {
@@ -668,14 +1003,56 @@ impl Default for ExtractionConfig {
             dcim_only: true,
             include_extensions: vec![],
             exclude_extensions: vec![],
+            include_globs: vec![],
+            exclude_globs: vec![],
             min_file_size: 0,
             max_file_size: 0,
             include_photos: true,
             include_videos: true,
+            date_from: None,
+            date_to: None,
+            include_undated: true,
+            verify_after_copy: false,
+            verify_retries: 2,
+            delete_after_extract: false,
+            max_retries: 3,
+            retry_base_delay_ms: 250,
+            reconnect_timeout_secs: 30,
+            resume_from_checkpoint: false,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            max_bytes_per_sec: None,
+            scan_threads: DEFAULT_SCAN_THREADS,
+            sort_by: SortBy::Name,
+            sort_ascending: true,
+            dry_run: false,
         }
     }
 }
 
+impl ExtractionConfig {
+    /// Minimum file size as an extractor bound (None when 0)
+    pub fn min_size_bytes(&self) -> Option<u64> {
+        (self.min_file_size > 0).then_some(self.min_file_size)
+    }
+
+    /// Maximum file size as an extractor bound (None when 0)
+    pub fn max_size_bytes(&self) -> Option<u64> {
+        (self.max_file_size > 0).then_some(self.max_file_size)
+    }
+
+    /// Order each folder's children are walked in
+    pub fn scan_order(&self) -> ScanOrder {
+        ScanOrder::new(self.sort_by, self.sort_ascending)
+    }
+
+    /// Checkpoint file for an extraction into `output_dir` (None when
+    /// `resume_from_checkpoint` is off)
+    pub fn checkpoint_path(&self, output_dir: &Path) -> Option<PathBuf> {
+        self.resume_from_checkpoint
+            .then(|| Checkpoint::default_path(output_dir))
+    }
+}
+
 impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
@@ -693,9 +1070,12 @@ impl Default for DuplicateDetectionConfig {
             comparison_folders: vec![],
             cache_enabled: true,
             cache_file: PathBuf::from("./.duplicate_cache.json"),
-            duplicate_action: DuplicateAction::Skip,
+            duplicate_action: DuplicateStrategy::Skip,
             recursive: true,
             media_only: true,
+            quick_hash_bytes: crate::duplicate::detector::DEFAULT_QUICK_HASH_BYTES,
+            report_path: None,
+            report_format: ManifestFormat::Json,
         }
     }
 }
@@ -707,11 +1087,13 @@ impl DuplicateDetectionConfig {
             comparison_folders: self.comparison_folders.clone(),
             cache_enabled: self.cache_enabled,
             cache_file: self.cache_file.clone(),
+            cache_read_only: false,
             recursive: self.recursive,
             follow_symlinks: false,
             min_file_size: 0,
             max_file_size: 0,
             media_only: self.media_only,
+            quick_hash_bytes: self.quick_hash_bytes,
         }
     }
 }
@@ -722,10 +1104,18 @@ impl Default for TrackingConfig {
             enabled: true,
             tracking_filename: ".photo_extraction_state.json".to_string(),
             track_extracted_files: true,
+            database: None,
         }
     }
 }
 
+impl TrackingConfig {
+    /// The tracking database for `output_dir`, if one is configured
+    pub fn database_path(&self, output_dir: &Path) -> Option<PathBuf> {
+        self.database.as_ref().map(|path| output_dir.join(path))
+    }
+}
+
 impl Config {
     /// Check if initial setup is required
     ///
@@ -767,6 +1157,9 @@ impl Config {
     }
 
     /// Load configuration from a TOML file
+    ///
+    /// Files written for an older layout are upgraded with [`migrate`]
+    /// before deserialization.
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
         let path = path.as_ref();
 
@@ -777,12 +1170,276 @@ impl Config {
         let content = fs::read_to_string(path)
             .map_err(|e| ConfigError::ReadError(path.to_path_buf(), e.to_string()))?;
 
-        let config: Config = toml::from_str(&content)
-            .map_err(|e| ConfigError::ParseError(path.to_path_buf(), e.to_string()))?;
+        Self::from_toml_str(&content).map_err(|e| match e {
+            ConfigError::SerializeError(message) => {
+                ConfigError::ParseError(path.to_path_buf(), message)
+            }
+            other => other,
+        })
+    }
+
+    /// Parse configuration from TOML text, migrating older layouts
+    ///
+    /// Parse failures are reported as [`ConfigError::SerializeError`]
+    /// since there is no file path to attach.
+    pub fn from_toml_str(content: &str) -> Result<Self, ConfigError> {
+        let mut table: toml::Table = content
+            .parse()
+            .map_err(|e: toml::de::Error| ConfigError::SerializeError(e.to_string()))?;
+
+        migrate(&mut table)?;
+
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::SerializeError(e.to_string()))
+    }
+
+    /// Load configuration from a TOML file, rejecting invalid values
+    ///
+    /// Unlike [`Config::load`], callers are expected to report the error
+    /// rather than fall back to defaults. Parse errors carry a hint for
+    /// common mistakes (such as quoting a boolean), and the parsed config
+    /// must pass [`Config::validate`].
+    pub fn load_strict<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+
+        let config = Self::load(path).map_err(|e| match e {
+            ConfigError::ParseError(path, message) => match parse_hint(&message) {
+                Some(hint) => ConfigError::ParseError(path, format!("{}\nhint: {}", message, hint)),
+                None => ConfigError::ParseError(path, message),
+            },
+            other => other,
+        })?;
 
+        config.validate()?;
         Ok(config)
     }
 
+    /// Apply overrides from `PET_*` environment variables
+    ///
+    /// These sit between the config file and CLI flags: call this after
+    /// loading the file and before applying command-line arguments. See
+    /// [`ENV_OVERRIDES`] for the supported variables. Empty variables are
+    /// ignored; variables that fail to parse are skipped and returned so
+    /// the caller can warn about them.
+    pub fn apply_env_overrides(&mut self) -> Vec<ConfigError> {
+        self.apply_overrides_from(|name| std::env::var(name).ok())
+    }
+
+    /// Apply `PET_*` overrides using `lookup` to read each variable
+    pub fn apply_overrides_from(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+
+        for (var, _) in ENV_OVERRIDES {
+            let Some(value) = lookup(var).filter(|v| !v.trim().is_empty()) else {
+                continue;
+            };
+            let value = value.trim();
+
+            let applied = match *var {
+                "PET_OUTPUT_DIR" => {
+                    self.output.directory = PathBuf::from(value);
+                    Ok(())
+                }
+                "PET_DEVICE_ID" => {
+                    self.device.device_id = Some(value.to_string());
+                    Ok(())
+                }
+                "PET_APPLE_ONLY" => parse_env_bool(value).map(|v| self.device.apple_only = v),
+                "PET_DCIM_ONLY" => parse_env_bool(value).map(|v| self.extraction.dcim_only = v),
+                "PET_SKIP_EXISTING" => parse_env_bool(value).map(|v| self.output.skip_existing = v),
+                "PET_PRESERVE_STRUCTURE" => {
+                    parse_env_bool(value).map(|v| self.output.preserve_structure = v)
+                }
+                "PET_MAX_BYTES_PER_SEC" => match value.parse::<u64>() {
+                    Ok(rate) if rate > 0 => {
+                        self.extraction.max_bytes_per_sec = Some(rate);
+                        Ok(())
+                    }
+                    _ => Err("a positive whole number of bytes"),
+                },
+                "PET_LOG_LEVEL" => {
+                    let level = value.to_lowercase();
+                    if LOG_LEVELS.contains(&level.as_str()) {
+                        self.logging.level = level;
+                        Ok(())
+                    } else {
+                        Err("one of error, warn, info, debug, trace")
+                    }
+                }
+                _ => Ok(()),
+            };
+
+            if let Err(expected) = applied {
+                errors.push(ConfigError::InvalidEnvVar {
+                    var: var.to_string(),
+                    value: value.to_string(),
+                    expected,
+                });
+            }
+        }
+
+        errors
+    }
+
+    /// Validate ranges and enum-like string fields
+    ///
+    /// Returns the first problem found, naming the offending key.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let level = self.logging.level.to_lowercase();
+        if !LOG_LEVELS.contains(&level.as_str()) {
+            return Err(ConfigError::UnknownValue {
+                key: "logging.level".to_string(),
+                value: self.logging.level.clone(),
+                expected: LOG_LEVELS,
+                suggestion: closest_match(&level, LOG_LEVELS),
+            });
+        }
+
+        if let Some(ref template) = self.output.path_template {
+            if let Err(e) = PathTemplate::parse(template) {
+                let key = "output.path_template".to_string();
+                return Err(match e {
+                    TemplateError::UnknownToken(token) => ConfigError::UnknownValue {
+                        key,
+                        value: format!("{{{}}}", token),
+                        expected: TEMPLATE_TOKENS,
+                        suggestion: closest_match(&token, TEMPLATE_TOKENS),
+                    },
+                    e => ConfigError::OutOfRange {
+                        key,
+                        value: template.clone(),
+                        expected: e.to_string(),
+                    },
+                });
+            }
+        }
+
+        let extraction = &self.extraction;
+        if extraction.max_file_size > 0 && extraction.min_file_size > extraction.max_file_size {
+            return Err(ConfigError::OutOfRange {
+                key: "extraction.min_file_size".to_string(),
+                value: extraction.min_file_size.to_string(),
+                expected: format!(
+                    "at most extraction.max_file_size ({})",
+                    extraction.max_file_size
+                ),
+            });
+        }
+
+        for (key, patterns) in [
+            ("extraction.include_globs", &extraction.include_globs),
+            ("extraction.exclude_globs", &extraction.exclude_globs),
+        ] {
+            for pattern in patterns {
+                if let Err(e) = crate::core::media::compile_glob(pattern) {
+                    return Err(ConfigError::InvalidPattern {
+                        key: key.to_string(),
+                        pattern: pattern.clone(),
+                        reason: e.kind().to_string(),
+                    });
+                }
+            }
+        }
+
+        if let (Some(from), Some(to)) = (extraction.date_from, extraction.date_to) {
+            if from > to {
+                return Err(ConfigError::OutOfRange {
+                    key: "extraction.date_from".to_string(),
+                    value: from.to_string(),
+                    expected: format!("on or before extraction.date_to ({})", to),
+                });
+            }
+        }
+
+        if extraction.max_retries > MAX_RETRY_COUNT {
+            return Err(ConfigError::OutOfRange {
+                key: "extraction.max_retries".to_string(),
+                value: extraction.max_retries.to_string(),
+                expected: format!("0 to {}", MAX_RETRY_COUNT),
+            });
+        }
+
+        if extraction.verify_retries > MAX_RETRY_COUNT {
+            return Err(ConfigError::OutOfRange {
+                key: "extraction.verify_retries".to_string(),
+                value: extraction.verify_retries.to_string(),
+                expected: format!("0 to {}", MAX_RETRY_COUNT),
+            });
+        }
+
+        if extraction.retry_base_delay_ms > MAX_RETRY_DELAY_MS {
+            return Err(ConfigError::OutOfRange {
+                key: "extraction.retry_base_delay_ms".to_string(),
+                value: extraction.retry_base_delay_ms.to_string(),
+                expected: format!("0 to {}", MAX_RETRY_DELAY_MS),
+            });
+        }
+
+        if extraction.reconnect_timeout_secs > MAX_RECONNECT_TIMEOUT_SECS {
+            return Err(ConfigError::OutOfRange {
+                key: "extraction.reconnect_timeout_secs".to_string(),
+                value: extraction.reconnect_timeout_secs.to_string(),
+                expected: format!("0 to {}", MAX_RECONNECT_TIMEOUT_SECS),
+            });
+        }
+
+        if extraction.checkpoint_interval == 0 {
+            return Err(ConfigError::OutOfRange {
+                key: "extraction.checkpoint_interval".to_string(),
+                value: "0".to_string(),
+                expected: "at least 1".to_string(),
+            });
+        }
+
+        if extraction.max_bytes_per_sec == Some(0) {
+            return Err(ConfigError::OutOfRange {
+                key: "extraction.max_bytes_per_sec".to_string(),
+                value: "0".to_string(),
+                expected: "at least 1 (remove the key for unlimited)".to_string(),
+            });
+        }
+
+        if extraction.scan_threads == 0 || extraction.scan_threads > MAX_SCAN_THREADS {
+            return Err(ConfigError::OutOfRange {
+                key: "extraction.scan_threads".to_string(),
+                value: extraction.scan_threads.to_string(),
+                expected: format!("1 to {}", MAX_SCAN_THREADS),
+            });
+        }
+
+        if !extraction.include_photos && !extraction.include_videos {
+            return Err(ConfigError::Conflict {
+                key: "extraction.include_photos".to_string(),
+                reason: "both photos and videos are excluded, so nothing would be extracted"
+                    .to_string(),
+                suggestion: "set extraction.include_photos or extraction.include_videos to true"
+                    .to_string(),
+            });
+        }
+
+        if extraction.delete_after_extract && !extraction.verify_after_copy {
+            return Err(ConfigError::Conflict {
+                key: "extraction.delete_after_extract".to_string(),
+                reason: "files are only deleted from the device after a verified copy".to_string(),
+                suggestion: "set extraction.verify_after_copy = true".to_string(),
+            });
+        }
+
+        if self.tracking.enabled && self.tracking.tracking_filename.trim().is_empty() {
+            return Err(ConfigError::OutOfRange {
+                key: "tracking.tracking_filename".to_string(),
+                value: String::new(),
+                expected: "a non-empty file name".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
     /// Load configuration from default locations
     ///
     /// Search order:
@@ -855,6 +1512,218 @@ impl Config {
     }
 }
 
+/// Where an effective configuration value was set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The config file at this path
+    File(PathBuf),
+    /// A `PET_*` environment variable
+    Env(String),
+    /// A command-line flag
+    CommandLine,
+    /// The device profile with this name
+    Profile(String),
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::File(path) => write!(f, "config file {}", path.display()),
+            ConfigSource::Env(var) => write!(f, "environment variable {}", var),
+            ConfigSource::CommandLine => write!(f, "command line"),
+            ConfigSource::Profile(name) => write!(f, "device profile '{}'", name),
+        }
+    }
+}
+
+/// A [`Config`] built up layer by layer, remembering which layer last set
+/// each key
+///
+/// Layers are applied in precedence order: the config file, `PET_*`
+/// environment variables, command-line flags, then the device profile.
+/// Keys no layer changed keep their defaults and have no source.
+#[derive(Debug, Clone)]
+pub struct EffectiveConfig {
+    config: Config,
+    sources: std::collections::BTreeMap<String, ConfigSource>,
+}
+
+impl EffectiveConfig {
+    /// Start from the built-in defaults
+    pub fn new() -> Self {
+        Self {
+            config: Config::default(),
+            sources: Default::default(),
+        }
+    }
+
+    /// Start from a config loaded from `path`
+    ///
+    /// Keys whose value differs from the default are attributed to the file.
+    pub fn from_file(config: Config, path: impl Into<PathBuf>) -> Self {
+        let mut effective = Self::new();
+        effective.apply(ConfigSource::File(path.into()), |c| *c = config);
+        effective
+    }
+
+    /// Apply one override layer, attributing every key it changes to `source`
+    pub fn apply<R>(&mut self, source: ConfigSource, f: impl FnOnce(&mut Config) -> R) -> R {
+        let before = toml::Value::try_from(&self.config).ok();
+        let result = f(&mut self.config);
+        let after = toml::Value::try_from(&self.config).ok();
+
+        let mut changed = Vec::new();
+        changed_keys("", before.as_ref(), after.as_ref(), &mut changed);
+        for key in changed {
+            self.sources.insert(key, source.clone());
+        }
+        result
+    }
+
+    /// Apply `PET_*` overrides read through `lookup`, one variable at a time
+    /// so each key is attributed to the variable that set it
+    pub fn apply_env_overrides_from(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        for (var, _) in ENV_OVERRIDES {
+            let Some(value) = lookup(var) else {
+                continue;
+            };
+            errors.extend(self.apply(ConfigSource::Env(var.to_string()), |config| {
+                config.apply_overrides_from(|name| (name == *var).then(|| value.clone()))
+            }));
+        }
+        errors
+    }
+
+    /// Point the output directory at `profile`'s folder, as extraction does
+    /// when device profiles are enabled
+    pub fn apply_profile(&mut self, profile: &DeviceProfile) {
+        let output = self
+            .config
+            .device_profiles
+            .backup_base_folder
+            .join(&profile.output_folder);
+        self.apply(ConfigSource::Profile(profile.name.clone()), |config| {
+            config.output.directory = output
+        });
+    }
+
+    /// The merged configuration
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Consume and return the merged configuration
+    pub fn into_config(self) -> Config {
+        self.config
+    }
+
+    /// The layer that last set the dotted `key`, if any did
+    pub fn source(&self, key: &str) -> Option<&ConfigSource> {
+        self.sources.get(key)
+    }
+
+    /// Render the merged configuration as TOML, with a comment above each
+    /// value naming the layer that set it
+    pub fn to_toml_string(&self) -> Result<String, ConfigError> {
+        let content = toml::to_string_pretty(&self.config)
+            .map_err(|e| ConfigError::SerializeError(e.to_string()))?;
+
+        let mut out = String::from(
+            "# Effective configuration after all overrides\n\
+             #\n\
+             # Values not commented on are defaults. Later layers win: config file,\n\
+             # environment variables, command line, device profile.\n\n",
+        );
+        let mut table = String::new();
+        for line in content.lines() {
+            if let Some(header) = line.strip_prefix('[') {
+                table = header.trim_matches(|c| c == '[' || c == ']').to_string();
+            } else if let Some((key, _)) = line.split_once(" = ") {
+                let key = if table.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", table, key)
+                };
+                if let Some(source) = self.sources.get(&key) {
+                    out.push_str(&format!("# from {}\n", source));
+                }
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+impl Default for EffectiveConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collect the dotted keys whose value differs between two TOML values
+fn changed_keys(
+    prefix: &str,
+    before: Option<&toml::Value>,
+    after: Option<&toml::Value>,
+    changed: &mut Vec<String>,
+) {
+    match (before, after) {
+        (Some(toml::Value::Table(before)), Some(toml::Value::Table(after))) => {
+            let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                changed_keys(&path, before.get(key), after.get(key), changed);
+            }
+        }
+        _ if before != after => changed.push(prefix.to_string()),
+        _ => {}
+    }
+}
+
+/// Upgrade a raw config table from an older layout to the current one
+///
+/// Files without a `config_version` are treated as version 1, and
+/// `config_version` is updated to [`CONFIG_VERSION`]. Returns the version
+/// the table was written for. Versions newer than this build understands
+/// are rejected rather than partially loaded.
+pub fn migrate(table: &mut toml::Table) -> Result<u32, ConfigError> {
+    let version = match table.get("config_version") {
+        None => 1,
+        Some(toml::Value::Integer(v)) if *v >= 1 && *v <= i64::from(u32::MAX) => *v as u32,
+        Some(value) => {
+            return Err(ConfigError::OutOfRange {
+                key: "config_version".to_string(),
+                value: value.to_string(),
+                expected: format!("a version from 1 to {}", CONFIG_VERSION),
+            })
+        }
+    };
+
+    if version > CONFIG_VERSION {
+        return Err(ConfigError::UnsupportedVersion {
+            found: version,
+            supported: CONFIG_VERSION,
+        });
+    }
+
+    table.insert(
+        "config_version".to_string(),
+        toml::Value::Integer(i64::from(CONFIG_VERSION)),
+    );
+    Ok(version)
+}
+
 /// Configuration error types
 ///
 /// Some variants are reserved for future use (save functionality).
@@ -875,6 +1744,102 @@ pub enum ConfigError {
     ConfigDirNotFound,
     /// Failed to open config file in editor
     OpenError(PathBuf, String),
+    /// A numeric or path setting is outside its allowed range
+    OutOfRange {
+        key: String,
+        value: String,
+        expected: String,
+    },
+    /// A path pattern setting is not a valid glob
+    InvalidPattern {
+        key: String,
+        pattern: String,
+        reason: String,
+    },
+    /// A string setting is not one of the accepted values
+    UnknownValue {
+        key: String,
+        value: String,
+        expected: &'static [&'static str],
+        suggestion: Option<&'static str>,
+    },
+    /// A setting is incompatible with the rest of the configuration
+    Conflict {
+        key: String,
+        reason: String,
+        suggestion: String,
+    },
+    /// The file was written by a newer version of the tool
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// An override environment variable could not be parsed
+    InvalidEnvVar {
+        var: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+impl ConfigError {
+    /// Dotted key of the offending setting, for validation errors
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            ConfigError::OutOfRange { key, .. }
+            | ConfigError::InvalidPattern { key, .. }
+            | ConfigError::UnknownValue { key, .. }
+            | ConfigError::Conflict { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a boolean environment value (true/false, yes/no, on/off, 1/0)
+fn parse_env_bool(value: &str) -> Result<bool, &'static str> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err("true or false"),
+    }
+}
+
+/// Suggest a fix for common TOML type mistakes
+fn parse_hint(message: &str) -> Option<&'static str> {
+    if message.contains("expected a boolean") {
+        Some("use true or false without quotes")
+    } else if message.contains("expected u64") || message.contains("expected usize") {
+        Some("use a whole number without quotes")
+    } else if message.contains("expected a string") {
+        Some("wrap the value in double quotes")
+    } else {
+        None
+    }
+}
+
+/// Find the accepted value closest to `value`, if any is a plausible typo
+fn closest_match(value: &str, candidates: &[&'static str]) -> Option<&'static str> {
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(value, candidate), *candidate))
+        .filter(|(distance, candidate)| *distance <= value.len().max(candidate.len()) / 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+
+    row[b.len()]
 }
 
 impl std::fmt::Display for ConfigError {
@@ -921,6 +1886,64 @@ impl std::fmt::Display for ConfigError {
                     err
                 )
             }
+            ConfigError::OutOfRange {
+                key,
+                value,
+                expected,
+            } => {
+                write!(
+                    f,
+                    "Invalid value for '{}': {:?} (expected {})",
+                    key, value, expected
+                )
+            }
+            ConfigError::InvalidPattern {
+                key,
+                pattern,
+                reason,
+            } => {
+                write!(f, "Invalid glob in '{}': {:?} ({})", key, pattern, reason)
+            }
+            ConfigError::UnknownValue {
+                key,
+                value,
+                expected,
+                suggestion,
+            } => {
+                write!(
+                    f,
+                    "Unknown value for '{}': {:?} (expected one of: {})",
+                    key,
+                    value,
+                    expected.join(", ")
+                )?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, "; did you mean {:?}?", suggestion)?;
+                }
+                Ok(())
+            }
+            ConfigError::Conflict {
+                key,
+                reason,
+                suggestion,
+            } => {
+                write!(f, "Invalid setting '{}': {} ({})", key, reason, suggestion)
+            }
+            ConfigError::UnsupportedVersion { found, supported } => {
+                write!(
+                    f,
+                    "Config file uses layout version {}, but this build only supports up to {}; \
+                     upgrade the tool or regenerate the file with `config --reset`",
+                    found, supported
+                )
+            }
+            ConfigError::InvalidEnvVar {
+                var,
+                value,
+                expected,
+            } => {
+                write!(f, "Ignoring {}={:?}: expected {}", var, value, expected)
+            }
         }
     }
 }
@@ -1064,4 +2087,399 @@ mod tests {
         assert_eq!(app_folders::SIGNAL, "Signal/Signal Photos");
         assert_eq!(app_folders::VIBER_IMAGES, "Viber/media/Viber Images");
     }
+
+    #[test]
+    fn test_extraction_date_range_from_toml() {
+        let config: Config = toml::from_str(
+            r#"
+            [extraction]
+            date_from = "2024-01-01"
+            date_to = "2024-01-31"
+            include_undated = false
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.extraction.date_from,
+            NaiveDate::from_ymd_opt(2024, 1, 1)
+        );
+        assert_eq!(
+            config.extraction.date_to,
+            NaiveDate::from_ymd_opt(2024, 1, 31)
+        );
+        assert!(!config.extraction.include_undated);
+    }
+
+    #[test]
+    fn test_extraction_date_range_defaults() {
+        let config = ExtractionConfig::default();
+        assert!(config.date_from.is_none());
+        assert!(config.date_to.is_none());
+        assert!(config.include_undated);
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_out_of_range() {
+        let mut config = Config::default();
+        config.extraction.max_retries = 50;
+
+        match config.validate() {
+            Err(ConfigError::OutOfRange { key, value, .. }) => {
+                assert_eq!(key, "extraction.max_retries");
+                assert_eq!(value, "50");
+            }
+            other => panic!("expected OutOfRange, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_unknown_log_level() {
+        let mut config = Config::default();
+        config.logging.level = "warning".to_string();
+
+        let err = config.validate().unwrap_err();
+        assert_eq!(err.key(), Some("logging.level"));
+        match err {
+            ConfigError::UnknownValue { suggestion, .. } => {
+                assert_eq!(suggestion, Some("warn"));
+            }
+            other => panic!("expected UnknownValue, got {:?}", other),
+        }
+
+        config.logging.level = "DEBUG".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_path_template() {
+        let mut config = Config::default();
+        config.output.path_template = Some("{device}/{yaer}/{original_name}".to_string());
+
+        let err = config.validate().unwrap_err();
+        assert_eq!(err.key(), Some("output.path_template"));
+        match err {
+            ConfigError::UnknownValue {
+                value, suggestion, ..
+            } => {
+                assert_eq!(value, "{yaer}");
+                assert_eq!(suggestion, Some("year"));
+            }
+            other => panic!("expected UnknownValue, got {:?}", other),
+        }
+
+        config.output.path_template = Some("{device}/{year}".to_string());
+        assert!(config.validate().is_err());
+
+        config.output.path_template = Some("{device}/{year}/{original_name}".to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.output.output_template().to_string(),
+            "{device}/{year}/{original_name}"
+        );
+    }
+
+    #[test]
+    fn test_validate_conflicting_delete_without_verify() {
+        let mut config = Config::default();
+        config.extraction.delete_after_extract = true;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::Conflict { .. })
+        ));
+
+        config.extraction.verify_after_copy = true;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_invalid_glob() {
+        let mut config = Config::default();
+        config.extraction.exclude_globs = vec!["**/100CLOUD/**".to_string()];
+        assert!(config.validate().is_ok());
+
+        config
+            .extraction
+            .exclude_globs
+            .push("DCIM/[100".to_string());
+        match config.validate() {
+            Err(ConfigError::InvalidPattern { key, pattern, .. }) => {
+                assert_eq!(key, "extraction.exclude_globs");
+                assert_eq!(pattern, "DCIM/[100");
+            }
+            other => panic!("expected InvalidPattern, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_reversed_date_range() {
+        let mut config = Config::default();
+        config.extraction.date_from = NaiveDate::from_ymd_opt(2024, 1, 1);
+        config.extraction.date_to = NaiveDate::from_ymd_opt(2024, 1, 1);
+        assert!(config.validate().is_ok());
+
+        config.extraction.date_from = NaiveDate::from_ymd_opt(2024, 2, 1);
+        match config.validate() {
+            Err(ConfigError::OutOfRange { key, value, .. }) => {
+                assert_eq!(key, "extraction.date_from");
+                assert_eq!(value, "2024-02-01");
+            }
+            other => panic!("expected OutOfRange, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load_strict_reports_type_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[extraction]\ndcim_only = \"yes\"\n").unwrap();
+
+        // Lenient loading reports the same parse error, without the hint
+        assert!(Config::load(&path).is_err());
+
+        match Config::load_strict(&path) {
+            Err(ConfigError::ParseError(_, message)) => {
+                assert!(message.contains("dcim_only"));
+                assert!(message.contains("hint: use true or false"));
+            }
+            other => panic!("expected ParseError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load_strict_rejects_invalid_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[logging]\nlevel = \"verbose\"\n").unwrap();
+
+        let err = Config::load_strict(&path).unwrap_err();
+        assert!(matches!(err, ConfigError::UnknownValue { .. }));
+        assert!(Config::load(&path).is_ok());
+    }
+
+    #[test]
+    fn test_migrate_unversioned_file() {
+        let mut table: toml::Table = "[extraction]\nverify_after_copy = true\n".parse().unwrap();
+
+        assert_eq!(migrate(&mut table).unwrap(), 1);
+        assert_eq!(
+            table["config_version"].as_integer(),
+            Some(i64::from(CONFIG_VERSION))
+        );
+
+        let config = Config::from_toml_str("[extraction]\nverify_after_copy = true\n").unwrap();
+        assert_eq!(config.config_version, CONFIG_VERSION);
+        assert!(config.extraction.verify_after_copy);
+    }
+
+    #[test]
+    fn test_migrate_current_version_is_untouched() {
+        let mut table: toml::Table = format!(
+            "config_version = {}\n[extraction]\nverify_after_copy = true\n",
+            CONFIG_VERSION
+        )
+        .parse()
+        .unwrap();
+
+        assert_eq!(migrate(&mut table).unwrap(), CONFIG_VERSION);
+        assert!(table["extraction"].get("verify_after_copy").is_some());
+    }
+
+    #[test]
+    fn test_migrate_rejects_future_version() {
+        let err = Config::from_toml_str("config_version = 99\n").unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::UnsupportedVersion {
+                found: 99,
+                supported: CONFIG_VERSION
+            }
+        ));
+    }
+
+    #[test]
+    fn test_default_config_round_trips_with_version() {
+        let content = toml::to_string_pretty(&Config::default()).unwrap();
+        let config = Config::from_toml_str(&content).unwrap();
+        assert_eq!(config.config_version, CONFIG_VERSION);
+    }
+
+    /// Serializes tests that modify the process environment
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn test_apply_env_overrides() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::set_var("PET_OUTPUT_DIR", "/tmp/pet-output");
+        std::env::set_var("PET_DEVICE_ID", "device-123");
+        std::env::set_var("PET_DCIM_ONLY", "maybe");
+        std::env::set_var("PET_LOG_LEVEL", "DEBUG");
+
+        let mut config = Config::default();
+        let errors = config.apply_env_overrides();
+
+        for (var, _) in ENV_OVERRIDES {
+            std::env::remove_var(var);
+        }
+
+        assert_eq!(config.output.directory, PathBuf::from("/tmp/pet-output"));
+        assert_eq!(config.device.device_id.as_deref(), Some("device-123"));
+        assert_eq!(config.logging.level, "debug");
+        // The unparsable bool is reported and leaves the file value alone
+        assert!(config.extraction.dcim_only);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ConfigError::InvalidEnvVar { var, .. } if var == "PET_DCIM_ONLY"
+        ));
+    }
+
+    #[test]
+    fn test_apply_overrides_from_lookup() {
+        let vars: HashMap<&str, &str> = [
+            ("PET_DCIM_ONLY", "no"),
+            ("PET_MAX_BYTES_PER_SEC", "0"),
+            ("PET_DEVICE_ID", "  "),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut config = Config::default();
+        let errors = config.apply_overrides_from(|name| vars.get(name).map(|v| v.to_string()));
+
+        assert!(!config.extraction.dcim_only);
+        assert!(config.device.device_id.is_none());
+        assert!(config.extraction.max_bytes_per_sec.is_none());
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_duplicate_strategy_names_in_toml() {
+        let config =
+            Config::from_toml_str("[duplicate_detection]\nduplicate_action = \"keep_newest\"\n")
+                .unwrap();
+        assert_eq!(
+            config.duplicate_detection.duplicate_action,
+            DuplicateStrategy::KeepNewest
+        );
+
+        let written = toml::to_string(&config).unwrap();
+        assert!(written.contains("duplicate_action = \"keep_newest\""));
+    }
+
+    #[test]
+    fn test_effective_config_tracks_sources() {
+        let file = Config::from_toml_str("[output]\nskip_existing = false\n").unwrap();
+        let mut effective = EffectiveConfig::from_file(file, "config.toml");
+        assert_eq!(
+            effective.source("output.skip_existing"),
+            Some(&ConfigSource::File(PathBuf::from("config.toml")))
+        );
+        assert_eq!(effective.source("output.directory"), None);
+
+        let errors = effective.apply_env_overrides_from(|name| match name {
+            "PET_SKIP_EXISTING" => Some("true".to_string()),
+            "PET_APPLE_ONLY" => Some("maybe".to_string()),
+            _ => None,
+        });
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            effective.source("output.skip_existing"),
+            Some(&ConfigSource::Env("PET_SKIP_EXISTING".to_string()))
+        );
+        assert_eq!(effective.source("device.apple_only"), None);
+
+        effective.apply(ConfigSource::CommandLine, |config| {
+            config.extraction.max_bytes_per_sec = Some(1000)
+        });
+        effective.apply_profile(&DeviceProfile {
+            name: "Alice".to_string(),
+            output_folder: "Alice_iPhone".to_string(),
+            manufacturer: String::new(),
+            model: String::new(),
+            first_seen: None,
+            last_seen: None,
+            last_extraction: None,
+        });
+        assert_eq!(
+            effective.source("extraction.max_bytes_per_sec"),
+            Some(&ConfigSource::CommandLine)
+        );
+        assert_eq!(
+            effective.source("output.directory"),
+            Some(&ConfigSource::Profile("Alice".to_string()))
+        );
+
+        let written = effective.to_toml_string().unwrap();
+        assert!(
+            written.contains("# from environment variable PET_SKIP_EXISTING\nskip_existing = true")
+        );
+        assert!(written.contains("# from command line\nmax_bytes_per_sec = 1000"));
+
+        let reloaded = Config::from_toml_str(&written).unwrap();
+        assert!(reloaded.output.skip_existing);
+        assert_eq!(
+            reloaded.output.directory,
+            effective.config().output.directory
+        );
+    }
+
+    #[test]
+    fn test_validate_scan_threads() {
+        let mut config = Config::default();
+        assert_eq!(config.extraction.scan_threads, DEFAULT_SCAN_THREADS);
+
+        config.extraction.scan_threads = 0;
+        assert_eq!(
+            config.validate().unwrap_err().key(),
+            Some("extraction.scan_threads")
+        );
+
+        config.extraction.scan_threads = MAX_SCAN_THREADS;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_checkpoint_options() {
+        let mut config = Config::default();
+        let output = Path::new("/photos");
+        assert_eq!(config.extraction.checkpoint_path(output), None);
+
+        config.extraction.resume_from_checkpoint = true;
+        assert_eq!(
+            config.extraction.checkpoint_path(output),
+            Some(Checkpoint::default_path(output))
+        );
+
+        config.extraction.checkpoint_interval = 0;
+        assert_eq!(
+            config.validate().unwrap_err().key(),
+            Some("extraction.checkpoint_interval")
+        );
+    }
+
+    #[test]
+    fn test_tracking_database_path() {
+        let mut config = Config::default();
+        let output = Path::new("/photos");
+        assert_eq!(config.tracking.database_path(output), None);
+
+        config.tracking.database = Some(PathBuf::from("tracking.db"));
+        assert_eq!(
+            config.tracking.database_path(output),
+            Some(PathBuf::from("/photos/tracking.db"))
+        );
+
+        let parsed: Config =
+            toml::from_str("[tracking]\ndatabase = \"D:/state/phone.db\"\n").unwrap();
+        assert_eq!(
+            parsed.tracking.database_path(output),
+            Some(PathBuf::from("D:/state/phone.db"))
+        );
+    }
 }
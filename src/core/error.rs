@@ -2,6 +2,22 @@
 //!
 //! This module defines the error types used throughout the application.
 //! Some variants are reserved for future use or provide a complete API surface.
+//!
+//! [`ExtractionError`] is the one typed error of the library: device access,
+//! configuration and extraction all return it, so it crosses those layers
+//! without wrapping and the UI can match on the failure kind. The kinds an
+//! extraction can end in are [`DeviceDisconnected`](ExtractionError::DeviceDisconnected),
+//! [`InsufficientSpace`](ExtractionError::InsufficientSpace),
+//! [`WindowsError`](ExtractionError::WindowsError) (with its HRESULT, see
+//! [`ExtractionError::hresult`]), [`Io`](ExtractionError::Io),
+//! [`Cancelled`](ExtractionError::Cancelled) and
+//! [`VerificationFailed`](ExtractionError::VerificationFailed). Failed WPD calls
+//! are converted through `From<windows::core::Error>`, which turns the codes of
+//! an unplugged device into `DeviceDisconnected`. I/O errors convert into `Io`
+//! unchanged, so their kind and source survive;
+//! [`IoError`](ExtractionError::IoError) is for file failures described with
+//! context, such as the path involved. Only the CLI converts to `anyhow`, at
+//! its edge.
 
 use thiserror::Error;
 
@@ -29,7 +45,11 @@ pub enum ExtractionError {
     #[error("Failed to access device content: {0}")]
     ContentError(String),
 
-    /// General I/O error
+    /// I/O error from the operating system
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// General I/O error, described with its context
     #[error("IO error: {0}")]
     IoError(String),
 
@@ -37,24 +57,207 @@ pub enum ExtractionError {
     #[error("Transfer failed for '{filename}': {message}")]
     TransferError { filename: String, message: String },
 
+    /// Written file did not match the data read from the device
+    #[error("Verification failed for '{filename}' after {attempts} attempts")]
+    VerificationFailed { filename: String, attempts: usize },
+
+    /// Not enough free space on the output drive for the planned extraction
+    #[error(
+        "Not enough free space: {} MB needed (including safety margin), {} MB available",
+        .needed / (1024 * 1024),
+        .available / (1024 * 1024)
+    )]
+    InsufficientSpace { needed: u64, available: u64 },
+
+    /// An include or exclude path pattern is not a valid glob
+    #[error("Invalid path pattern '{pattern}': {message}")]
+    InvalidPattern { pattern: String, message: String },
+
     /// Access to the device was denied
     #[error("Access denied. Please unlock your iOS device and tap 'Trust' when prompted.")]
     AccessDenied,
 
+    /// No device profile matched the given device ID or name
+    #[error("No device profile found for '{0}'")]
+    ProfileNotFound(String),
+
+    /// Another device profile already uses this name or output folder
+    #[error("A device profile named '{name}' already exists (device {device_id})")]
+    ProfileNameTaken { name: String, device_id: String },
+
     /// Device is not ready for communication
     #[error("Device not ready. Please ensure the device is unlocked.")]
     DeviceNotReady,
 
+    /// Device was unplugged or stopped responding
+    #[error("Device disconnected")]
+    DeviceDisconnected,
+
+    /// Shutdown was requested while a file was being copied
+    #[error("Extraction cancelled")]
+    Cancelled,
+
     /// Windows API error
     #[error("Windows API error: {0}")]
-    WindowsError(#[from] windows::core::Error),
+    WindowsError(#[source] windows::core::Error),
 }
 
 /// Result type alias for convenience
 pub type Result<T> = std::result::Result<T, ExtractionError>;
 
-impl From<std::io::Error> for ExtractionError {
-    fn from(err: std::io::Error) -> Self {
-        ExtractionError::IoError(err.to_string())
+/// HRESULTs that indicate a transient device condition worth retrying
+///
+/// Anything not listed (object not found, access denied, invalid argument,
+/// ...) is treated as permanent.
+const RETRYABLE_HRESULTS: &[u32] = &[
+    0x8000_000A, // E_PENDING
+    0x8001_0001, // RPC_E_CALL_REJECTED
+    0x8007_0015, // HRESULT_FROM_WIN32(ERROR_NOT_READY)
+    0x8007_0079, // HRESULT_FROM_WIN32(ERROR_SEM_TIMEOUT)
+    0x8007_00AA, // HRESULT_FROM_WIN32(ERROR_BUSY)
+    0x8007_045D, // HRESULT_FROM_WIN32(ERROR_IO_DEVICE)
+    0x8007_05B4, // HRESULT_FROM_WIN32(ERROR_TIMEOUT)
+    0x802A_0006, // E_WPD_DEVICE_IS_HUNG
+];
+
+/// HRESULTs that mean the device is gone rather than failing
+///
+/// An unplugged iPhone usually fails the transfer in progress with
+/// `ERROR_GEN_FAILURE` and every later call with `E_WPD_DEVICE_NOT_OPEN`,
+/// so both are treated as a disconnect and trigger a reconnect.
+const DISCONNECT_HRESULTS: &[u32] = &[
+    0x8007_001F, // HRESULT_FROM_WIN32(ERROR_GEN_FAILURE)
+    0x8007_0037, // HRESULT_FROM_WIN32(ERROR_DEV_NOT_EXIST)
+    0x8007_01B1, // HRESULT_FROM_WIN32(ERROR_NO_SUCH_DEVICE)
+    0x8007_048F, // HRESULT_FROM_WIN32(ERROR_DEVICE_NOT_CONNECTED)
+    0x802A_0002, // E_WPD_DEVICE_NOT_OPEN
+];
+
+/// Check whether an HRESULT indicates a transient failure
+pub fn is_retryable_hresult(code: i32) -> bool {
+    RETRYABLE_HRESULTS.contains(&(code as u32))
+}
+
+/// Check whether an HRESULT indicates the device was disconnected
+pub fn is_disconnect_hresult(code: i32) -> bool {
+    DISCONNECT_HRESULTS.contains(&(code as u32))
+}
+
+impl ExtractionError {
+    /// Check whether the operation that produced this error may succeed if retried
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ExtractionError::DeviceError(_)
+            | ExtractionError::TransferError { .. }
+            | ExtractionError::DeviceNotReady => true,
+            ExtractionError::WindowsError(e) => is_retryable_hresult(e.code().0),
+            _ => false,
+        }
+    }
+
+    /// HRESULT of a failed Windows API call, if that is what this error is
+    pub fn hresult(&self) -> Option<i32> {
+        match self {
+            ExtractionError::WindowsError(e) => Some(e.code().0),
+            _ => None,
+        }
+    }
+
+    /// Suggestion for the user on how to recover from this error
+    pub fn recovery_hint(&self) -> Option<&'static str> {
+        match self {
+            ExtractionError::DeviceDisconnected => {
+                Some("Reconnect the device and run the extraction again to continue.")
+            }
+            ExtractionError::InsufficientSpace { .. } => {
+                Some("Free up space on the output drive or choose another output folder.")
+            }
+            ExtractionError::AccessDenied | ExtractionError::DeviceNotReady => {
+                Some("Unlock the device and tap 'Trust' if prompted, then try again.")
+            }
+            ExtractionError::VerificationFailed { .. } => {
+                Some("Copies did not match the device; try another USB cable or port.")
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<windows::core::Error> for ExtractionError {
+    fn from(err: windows::core::Error) -> Self {
+        if is_disconnect_hresult(err.code().0) {
+            ExtractionError::DeviceDisconnected
+        } else {
+            ExtractionError::WindowsError(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable_hresults() {
+        assert!(is_retryable_hresult(0x8007_00AAu32 as i32)); // busy
+        assert!(is_retryable_hresult(0x8007_0079u32 as i32)); // semaphore timeout
+        assert!(!is_retryable_hresult(0x8007_0490u32 as i32)); // not found
+        assert!(!is_retryable_hresult(0x8007_0005u32 as i32)); // access denied
+    }
+
+    #[test]
+    fn test_error_retryability() {
+        assert!(ExtractionError::DeviceError("hiccup".to_string()).is_retryable());
+        assert!(ExtractionError::DeviceNotReady.is_retryable());
+        assert!(!ExtractionError::AccessDenied.is_retryable());
+        assert!(!ExtractionError::ContentError("Object not found".to_string()).is_retryable());
+        assert!(!ExtractionError::IoError("disk full".to_string()).is_retryable());
+        assert!(!ExtractionError::DeviceDisconnected.is_retryable());
+        assert!(!ExtractionError::Cancelled.is_retryable());
+    }
+
+    #[test]
+    fn test_disconnect_hresults_become_device_disconnected() {
+        use windows::core::HRESULT;
+
+        assert!(is_disconnect_hresult(0x8007_048Fu32 as i32));
+        assert!(!is_disconnect_hresult(0x8007_00AAu32 as i32));
+
+        for code in [
+            0x8007_048Fu32, // ERROR_DEVICE_NOT_CONNECTED
+            0x802A_0002,    // E_WPD_DEVICE_NOT_OPEN
+            0x8007_001F,    // ERROR_GEN_FAILURE
+        ] {
+            let gone = ExtractionError::from(windows::core::Error::from(HRESULT(code as i32)));
+            assert!(
+                matches!(gone, ExtractionError::DeviceDisconnected),
+                "{:#010x} should be a disconnect",
+                code
+            );
+            assert!(gone.recovery_hint().is_some());
+            assert!(!is_retryable_hresult(code as i32));
+        }
+
+        let busy =
+            ExtractionError::from(windows::core::Error::from(HRESULT(0x8007_00AAu32 as i32)));
+        assert_eq!(busy.hresult(), Some(0x8007_00AAu32 as i32));
+        assert!(busy.is_retryable());
+    }
+
+    #[test]
+    fn test_io_errors_keep_their_kind() {
+        fn open_missing(path: &std::path::Path) -> Result<std::fs::File> {
+            Ok(std::fs::File::open(path)?)
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let err = open_missing(&temp_dir.path().join("missing.jpg")).unwrap_err();
+        assert!(
+            matches!(&err, ExtractionError::Io(e) if e.kind() == std::io::ErrorKind::NotFound),
+            "{:?}",
+            err
+        );
+        assert!(!err.is_retryable());
+        assert_eq!(err.recovery_hint(), None);
     }
 }
@@ -8,37 +8,116 @@
 //! - State tracking for resume support
 //! - Android-specific folder structure handling
 
+use crate::core::archive::{entry_name, Destination, ZipDestination};
+use crate::core::checkpoint::{CheckpointTracker, DEFAULT_CHECKPOINT_INTERVAL};
 use crate::core::config::{
-    app_folders, AndroidConfig, DuplicateAction, DuplicateDetectionConfig, TrackingConfig,
+    app_folders, AndroidConfig, DuplicateDetectionConfig, LongPathStrategy, SkipExistingMode,
+    TrackingConfig,
 };
+use crate::core::discovery::{discover_folders, DiscoveryRoot, ScanOrder, DEFAULT_SCAN_THREADS};
 use crate::core::error::{ExtractionError, Result};
-use crate::core::tracking::StateTracker;
-use crate::device::traits::{DeviceContentTrait, DeviceInfo, DeviceManagerTrait, DeviceObject};
+use crate::core::generic_extractor::{self, FileOutcome, ProgressSink};
+use crate::core::long_paths::fit_path;
+use crate::core::manifest::{
+    ManifestEntry, ManifestFormat, ManifestOutcome, ManifestWriter, RunLogWriter,
+};
+use crate::core::media::{
+    burst_grouped_path, is_media_file, BurstFrame, MediaFilter, PathFilter, StatsByType,
+};
+use crate::core::metadata;
+use crate::core::names::{find_existing, normalize_name, WrittenPaths};
+use crate::core::plan::{classify, ExtractionPlan, PlannedFile};
+use crate::core::report::HtmlReport;
+use crate::core::retry::{wait_for_device, Retrier, RetryPolicy, MAX_RECONNECTS_PER_FILE};
+use crate::core::template::{PathTemplate, TemplateFile};
+use crate::core::throttle::Throttle;
+use crate::core::throughput::ThroughputEstimator;
+use crate::core::tracking::{self, StateTracker, TrackedFile, TrackingStoreTrait};
+use crate::device::traits::{
+    DeviceContentTrait, DeviceInfo, DeviceManagerTrait, DeviceObject, StorageVolume,
+};
 use crate::device::wpd::{DeviceContent, DeviceManager};
-use crate::duplicate::{compute_data_hash, DuplicateIndex};
+use crate::duplicate::{
+    compute_data_hash, compute_file_hash, hash_to_hex, resolve_duplicate, DuplicateCandidate,
+    DuplicateConfig, DuplicateIndex, DuplicateReport, DuplicateReportEntry, DuplicateResolution,
+    MatchType, Sha256Hash,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use indicatif::{ProgressBar, ProgressStyle};
 use log::{debug, info, trace, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
 use windows::core::PCWSTR;
-use windows::Win32::Devices::PortableDevices::WPD_RESOURCE_DEFAULT;
-use windows::Win32::System::Com::IStream;
 
 /// Configuration for photo extraction
 #[derive(Debug, Clone)]
 pub struct ExtractionConfig {
     /// Output directory for extracted photos
     pub output_dir: PathBuf,
+    /// Where extracted files are written (a `Zip` destination stores them as
+    /// entries named relative to `output_dir`, which still holds tracking state)
+    pub destination: Destination,
     /// Whether to only extract from DCIM folder
     pub dcim_only: bool,
     /// Whether to preserve folder structure from device
     pub preserve_structure: bool,
+    /// Whether to sort files into YYYY/MM folders by capture date
+    /// (overrides `preserve_structure`)
+    pub organize_by_date: bool,
+    /// Layout of output paths (None = built from `preserve_structure` and
+    /// `organize_by_date`)
+    pub path_template: Option<PathTemplate>,
+    /// Whether Live Photo videos are written next to their still image
+    pub preserve_live_photo_pairs: bool,
+    /// Whether the frames of each burst go into their own `BURST<id>` folder
+    pub group_bursts: bool,
+    /// Whether to re-read and checksum each file after writing it
+    pub verify_after_copy: bool,
+    /// How many times to re-copy a file that fails verification
+    pub verify_retries: usize,
+    /// Delete each file from the device once its copy is verified and tracked
+    /// (ignored unless `verify_after_copy` is set)
+    pub delete_after_extract: bool,
+    /// Free space (in MB) to leave on the output drive after extraction
+    pub space_safety_margin_mb: u64,
+    /// Maximum retries for transient device read/enumeration errors
+    pub max_retries: usize,
+    /// Plan the extraction without writing files or tracking state
+    pub dry_run: bool,
+    /// Where to write a manifest of every processed file (None = no manifest)
+    pub manifest_path: Option<PathBuf>,
+    /// Format of the manifest file
+    pub manifest_format: ManifestFormat,
+    /// Cumulative JSON Lines log every run appends its files to (None = no log)
+    pub append_to_log: Option<PathBuf>,
+    /// Where to write an HTML report of the extracted files (None = no report)
+    pub report_html: Option<PathBuf>,
+    /// Delay before the first retry in milliseconds (doubled each retry)
+    pub retry_base_delay_ms: u64,
+    /// Seconds to wait for a device that drops mid-extraction to come back
+    /// (0 = don't wait)
+    pub reconnect_timeout_secs: u64,
+    /// Checkpoint file to resume from and keep up to date (None = no checkpoint)
+    pub checkpoint_path: Option<PathBuf>,
+    /// Number of finished files between checkpoint saves
+    pub checkpoint_interval: usize,
     /// Whether to skip existing files
     pub skip_existing: bool,
+    /// How skip-existing recognizes a file that was already extracted
+    pub skip_existing_mode: SkipExistingMode,
+    /// What to do with a destination path beyond Windows' 260 character limit
+    pub long_path_strategy: LongPathStrategy,
+    /// Cap on bytes read from the device per second (None = unlimited)
+    pub max_bytes_per_sec: Option<u64>,
     /// Duplicate detection configuration
     pub duplicate_detection: Option<DuplicateDetectionConfig>,
     /// Tracking configuration
@@ -47,25 +126,127 @@ pub struct ExtractionConfig {
     pub quiet: bool,
     /// Android-specific configuration (None = use defaults)
     pub android_config: Option<AndroidConfig>,
+    /// Only extract files dated on or after this day (None = no lower bound)
+    pub date_from: Option<NaiveDate>,
+    /// Only extract files dated on or before this day (None = no upper bound)
+    pub date_to: Option<NaiveDate>,
+    /// Whether files without a readable date pass the date filter
+    pub include_undated: bool,
+    /// Skip files smaller than this many bytes (None = no minimum)
+    pub min_size_bytes: Option<u64>,
+    /// Skip files larger than this many bytes (None = no maximum)
+    pub max_size_bytes: Option<u64>,
+    /// Whether to extract photos
+    pub include_photos: bool,
+    /// Whether to extract videos
+    pub include_videos: bool,
+    /// Only extract files whose device path matches one of these globs
+    /// (empty = all paths)
+    pub include_globs: Vec<String>,
+    /// Skip files whose device path matches one of these globs
+    /// (takes precedence over `include_globs`)
+    pub exclude_globs: Vec<String>,
+    /// Order each folder's children are walked in
+    pub scan_order: ScanOrder,
+    /// Number of device folders listed at once while scanning
+    pub scan_threads: usize,
 }
 
 impl Default for ExtractionConfig {
     fn default() -> Self {
         Self {
             output_dir: PathBuf::from("./extracted_photos"),
+            destination: Destination::Directory(PathBuf::from("./extracted_photos")),
             dcim_only: true,
             preserve_structure: true,
+            organize_by_date: false,
+            path_template: None,
+            preserve_live_photo_pairs: true,
+            group_bursts: false,
+            verify_after_copy: false,
+            verify_retries: 2,
+            delete_after_extract: false,
+            space_safety_margin_mb: 100,
+            max_retries: 3,
+            retry_base_delay_ms: 250,
+            reconnect_timeout_secs: 30,
+            checkpoint_path: None,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            dry_run: false,
+            manifest_path: None,
+            manifest_format: ManifestFormat::Json,
+            append_to_log: None,
+            report_html: None,
             skip_existing: true,
+            skip_existing_mode: SkipExistingMode::ByName,
+            long_path_strategy: LongPathStrategy::ExtendedPrefix,
+            max_bytes_per_sec: None,
             duplicate_detection: None,
             tracking: None,
             quiet: false,
             android_config: None,
+            date_from: None,
+            date_to: None,
+            include_undated: true,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            include_photos: true,
+            include_videos: true,
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            scan_order: ScanOrder::default(),
+            scan_threads: DEFAULT_SCAN_THREADS,
+        }
+    }
+}
+
+impl ExtractionConfig {
+    /// Layout of output paths: `path_template`, or the one the boolean
+    /// options describe
+    pub fn output_template(&self) -> Cow<'_, PathTemplate> {
+        match self.path_template {
+            Some(ref template) => Cow::Borrowed(template),
+            None => Cow::Owned(PathTemplate::from_options(
+                self.preserve_structure,
+                self.organize_by_date,
+                false,
+            )),
+        }
+    }
+
+    /// Check whether a date range filter is configured
+    pub fn has_date_filter(&self) -> bool {
+        self.date_from.is_some() || self.date_to.is_some()
+    }
+
+    /// Check whether a file with the given device date passes the date filter
+    pub fn matches_date(&self, date_modified: Option<&str>) -> bool {
+        date_in_range(
+            date_modified,
+            self.date_from,
+            self.date_to,
+            self.include_undated,
+        )
+    }
+
+    /// Size band and media kinds to extract
+    pub fn media_filter(&self) -> MediaFilter {
+        MediaFilter {
+            include_photos: self.include_photos,
+            include_videos: self.include_videos,
+            min_size_bytes: self.min_size_bytes,
+            max_size_bytes: self.max_size_bytes,
         }
     }
+
+    /// Compiled include/exclude path globs
+    pub fn path_filter(&self) -> Result<PathFilter> {
+        PathFilter::new(&self.include_globs, &self.exclude_globs)
+    }
 }
 
 /// Statistics about the extraction process
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ExtractionStats {
     pub files_extracted: usize,
     pub files_skipped: usize,
@@ -74,6 +255,47 @@ pub struct ExtractionStats {
     pub duplicates_renamed: usize,
     pub errors: usize,
     pub total_bytes: u64,
+    pub files_excluded_by_date: usize,
+    pub files_filtered: usize,
+    pub live_photos_paired: usize,
+    pub burst_frames_grouped: usize,
+    pub verification_failures: usize,
+    pub retries: usize,
+    pub reconnects: usize,
+    pub deleted_from_device: usize,
+    pub renamed_on_collision: usize,
+    #[serde(default)]
+    pub by_type: StatsByType,
+}
+
+impl ExtractionStats {
+    /// The same counts in the form [`ProgressSink::on_finished`] takes
+    ///
+    /// Overwritten and renamed duplicates were written, so they count as
+    /// extracted; skipped duplicates count as both skipped and duplicates.
+    fn to_sink_stats(&self, duration: Duration) -> generic_extractor::ExtractionStats {
+        generic_extractor::ExtractionStats {
+            files_extracted: self.files_extracted
+                + self.duplicates_overwritten
+                + self.duplicates_renamed,
+            files_skipped: self.files_skipped + self.duplicates_skipped,
+            duplicates_found: self.duplicates_skipped,
+            errors: self.errors,
+            bytes_processed: self.total_bytes,
+            files_excluded_by_date: self.files_excluded_by_date,
+            files_filtered: self.files_filtered,
+            live_photos_paired: self.live_photos_paired,
+            burst_frames_grouped: self.burst_frames_grouped,
+            verification_failures: self.verification_failures,
+            retries: self.retries,
+            reconnects: self.reconnects,
+            deleted_from_device: self.deleted_from_device,
+            renamed_on_collision: self.renamed_on_collision,
+            by_type: self.by_type.clone(),
+            duration_ms: duration.as_millis() as u64,
+            ..Default::default()
+        }
+    }
 }
 
 /// Information about a photo on the device
@@ -161,12 +383,590 @@ impl ScanProgress {
     }
 }
 
-/// File extensions to extract
-const PHOTO_EXTENSIONS: &[&str] = &[
-    "jpg", "jpeg", "png", "heic", "heif", "gif", "webp", "raw", "dng", "tiff", "tif", "bmp",
-];
+/// Still image extensions that can carry a Live Photo motion clip
+const LIVE_PHOTO_IMAGE_EXTENSIONS: &[&str] = &["heic", "heif", "jpg", "jpeg"];
+
+/// Parse a device date string into a calendar date
+///
+/// Devices report dates as ISO 8601 (`2024-03-15T10:30:00Z`), but some MTP
+/// implementations return local timestamps without a zone or EXIF-style
+/// `2024:03:15 10:30:00` strings, so those are accepted too.
+pub fn parse_device_date(date_str: &str) -> Option<NaiveDate> {
+    // Keep the calendar day in the device's own zone
+    if let Ok(dt) = DateTime::parse_from_rfc3339(date_str.trim()) {
+        return Some(dt.date_naive());
+    }
+    if let Some(dt) = parse_device_datetime(date_str) {
+        return Some(dt.date());
+    }
+
+    date_str
+        .trim()
+        .get(..10)
+        .and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+}
+
+/// Parse a device date string into a timestamp
+///
+/// Accepts the same formats as [`parse_device_date`] except bare dates. Zoned
+/// timestamps are converted to UTC; unzoned ones are returned as-is.
+pub fn parse_device_datetime(date_str: &str) -> Option<NaiveDateTime> {
+    let trimmed = date_str.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(trimmed) {
+        return Some(dt.naive_utc());
+    }
+
+    [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y:%m:%d %H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(trimmed, format).ok())
+}
+
+/// Check whether a device date falls inside an inclusive date window
+///
+/// Files whose date is missing or unparseable pass only when `include_undated`
+/// is set. With no bounds configured every file passes.
+pub fn date_in_range(
+    date_modified: Option<&str>,
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+    include_undated: bool,
+) -> bool {
+    if date_from.is_none() && date_to.is_none() {
+        return true;
+    }
+
+    match date_modified.and_then(parse_device_date) {
+        Some(date) => {
+            date_from.is_none_or(|from| date >= from) && date_to.is_none_or(|to| date <= to)
+        }
+        None => include_undated,
+    }
+}
+
+/// Find Live Photo pairs among relative device paths
+///
+/// A Live Photo is stored as a still image and a `.MOV` sharing a folder and
+/// base name (`IMG_0001.HEIC` + `IMG_0001.MOV`). Names and extensions are
+/// compared case-insensitively. Returns a map from each paired video's index
+/// to its image's index; videos without a matching image are left out.
+pub fn find_live_photo_pairs<'a, I>(paths: I) -> HashMap<usize, usize>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut images: HashMap<String, usize> = HashMap::new();
+    let mut videos: Vec<(String, usize)> = Vec::new();
+
+    for (index, path) in paths.into_iter().enumerate() {
+        let path = Path::new(path);
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            continue;
+        };
+        let extension = extension.to_lowercase();
+        let base = path.with_extension("").to_string_lossy().to_lowercase();
+
+        if LIVE_PHOTO_IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+            images.entry(base).or_insert(index);
+        } else if extension == "mov" {
+            videos.push((base, index));
+        }
+    }
+
+    videos
+        .into_iter()
+        .filter_map(|(base, video)| images.get(&base).map(|&image| (video, image)))
+        .collect()
+}
+
+/// Reorder items so each Live Photo video directly follows its still image
+///
+/// Returns the reordered items and a map from each paired video's object ID to
+/// its image's object ID. Items that are not part of a pair keep their order.
+pub(crate) fn order_live_photo_pairs<T>(
+    items: Vec<T>,
+    path_of: impl Fn(&T) -> &str,
+    id_of: impl Fn(&T) -> &str,
+) -> (Vec<T>, HashMap<String, String>) {
+    let pairs = find_live_photo_pairs(items.iter().map(&path_of));
+    if pairs.is_empty() {
+        return (items, HashMap::new());
+    }
+    debug!("Found {} Live Photo pairs", pairs.len());
+
+    let mut partners = HashMap::with_capacity(pairs.len());
+    let mut videos_by_image: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (&video, &image) in &pairs {
+        partners.insert(
+            id_of(&items[video]).to_string(),
+            id_of(&items[image]).to_string(),
+        );
+        videos_by_image.entry(image).or_default().push(video);
+    }
+
+    let mut slots: Vec<Option<T>> = items.into_iter().map(Some).collect();
+    let mut ordered = Vec::with_capacity(slots.len());
+    for index in 0..slots.len() {
+        if pairs.contains_key(&index) {
+            continue;
+        }
+        ordered.extend(slots[index].take());
+        if let Some(videos) = videos_by_image.get_mut(&index) {
+            videos.sort_unstable();
+            for &video in videos.iter() {
+                ordered.extend(slots[video].take());
+            }
+        }
+    }
+
+    (ordered, partners)
+}
+
+/// Query the free space available to the current user on the volume containing `path`
+pub fn available_space(path: &Path) -> Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let path_wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut available: u64 = 0;
+    unsafe {
+        GetDiskFreeSpaceExW(
+            PCWSTR(path_wide.as_ptr()),
+            Some(&mut available as *mut u64),
+            None,
+            None,
+        )
+    }
+    .map_err(|e| {
+        ExtractionError::IoError(format!(
+            "Failed to query free space for '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    Ok(available)
+}
+
+/// Check that `needed` bytes plus a safety margin fit in `available` bytes
+pub fn check_free_space(needed: u64, available: u64, safety_margin_mb: u64) -> Result<()> {
+    let required = needed.saturating_add(safety_margin_mb.saturating_mul(1024 * 1024));
+    if required > available {
+        return Err(ExtractionError::InsufficientSpace {
+            needed: required,
+            available,
+        });
+    }
+    Ok(())
+}
+
+/// Check whether a file would actually be written, or skipped as already present
+///
+/// Mirrors the skip-existing check done during extraction: an existing file
+/// with the same size (or a device size of 0) is skipped, whatever case or
+/// normalization form its name was written with.
+pub(crate) fn will_be_written(output_path: &Path, size: u64, skip_existing: bool) -> bool {
+    if !skip_existing {
+        return true;
+    }
+    match find_existing(output_path).and_then(|existing| fs::metadata(existing).ok()) {
+        Some(metadata) => !(metadata.len() == size || size == 0),
+        None => true,
+    }
+}
+
+/// Find the copy an earlier run left of a file bound for a dated folder
+///
+/// `candidate` is where the file goes when dated by its device modified time,
+/// which is also where it goes with no EXIF date. A same-size file there (or
+/// archive entry, when extracting to `archive`) is skipped without reading the
+/// file for its capture date. `None` means the file has to be read first.
+pub(crate) fn existing_dated_copy(
+    candidate: PathBuf,
+    size: u64,
+    output_dir: &Path,
+    long_path_strategy: LongPathStrategy,
+    written_paths: &WrittenPaths,
+    archive: Option<&ZipDestination>,
+) -> Option<PathBuf> {
+    if let Some(zip) = archive {
+        return zip
+            .contains(&entry_name(output_dir, &candidate), size)
+            .then_some(candidate);
+    }
+    let candidate = fit_path(candidate, long_path_strategy).path;
+    if written_paths.contains(&candidate) {
+        return None;
+    }
+    let existing = find_existing(&candidate)?;
+    (!will_be_written(&existing, size, true)).then_some(existing)
+}
+
+/// Hash cache kept in the output folder for skip-existing by content
+pub const OUTPUT_INDEX_CACHE_FILE_NAME: &str = ".photo_extraction_hashes.json";
+
+/// Index the files already in `output_dir`, for skip-existing by content
+///
+/// Hashes are cached in [`OUTPUT_INDEX_CACHE_FILE_NAME`], so later runs only
+/// hash the files that changed since. A dry run (`read_only`) uses the cache
+/// without updating it. A folder that doesn't exist yet gives an empty index.
+/// Returns `None` if the folder can't be indexed, in which case files are
+/// skipped by name.
+pub(crate) fn build_output_index(
+    output_dir: &Path,
+    read_only: bool,
+    shutdown_flag: Arc<AtomicBool>,
+) -> Option<DuplicateIndex> {
+    let config = DuplicateConfig::new()
+        .with_folder(output_dir.to_path_buf())
+        .with_cache_file(output_dir.join(OUTPUT_INDEX_CACHE_FILE_NAME))
+        .with_cache_read_only(read_only);
+    if !output_dir.is_dir() {
+        return Some(DuplicateIndex::new(config));
+    }
+
+    match DuplicateIndex::build_from_folders(&config, shutdown_flag, |_| {}) {
+        Ok(index) => {
+            debug!(
+                "Indexed {} existing files in {}",
+                index.len(),
+                output_dir.display()
+            );
+            Some(index)
+        }
+        Err(e) => {
+            warn!(
+                "Could not index '{}', skipping existing files by name: {}",
+                output_dir.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// How a file already at a destination compares with the content bound for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Collision {
+    /// The destination already holds the same content
+    SameContent,
+    /// The destination holds a different file that happens to share the name
+    DifferentContent,
+}
+
+/// Compare the file at `output_path` with `data`
+///
+/// Sizes are compared first, so most clashes are told apart without hashing
+/// the existing file. A file that is missing or can't be read counts as
+/// different, so it is never overwritten.
+pub(crate) fn detect_collision(output_path: &Path, data: &[u8]) -> Collision {
+    let same_size = fs::metadata(output_path)
+        .map(|metadata| metadata.len() == data.len() as u64)
+        .unwrap_or(false);
+    let same_content = same_size
+        && compute_file_hash(output_path)
+            .map(|hash| hash == compute_data_hash(data))
+            .unwrap_or(false);
+    if same_content {
+        Collision::SameContent
+    } else {
+        Collision::DifferentContent
+    }
+}
+
+/// Chunk size used when streaming file content to disk
+pub(crate) const WRITE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Extension added to a file's name while it is being written
+///
+/// It is specific to this tool, so cleaning up after an interrupted run
+/// never touches `.partial` files that other programs left in the output.
+pub const PARTIAL_EXTENSION: &str = "pet-partial";
+
+/// Temporary path a file is written to before it is renamed to `output_path`
+pub(crate) fn partial_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(PARTIAL_EXTENSION);
+    output_path.with_file_name(name)
+}
+
+/// Delete the partial files an interrupted run left under `output_dir`
+///
+/// Returns how many were removed.
+pub(crate) fn remove_partial_files(output_dir: &Path) -> usize {
+    let mut removed = 0;
+    for entry in WalkDir::new(output_dir).into_iter().filter_map(|e| e.ok()) {
+        let is_partial = entry.file_type().is_file()
+            && entry
+                .path()
+                .extension()
+                .is_some_and(|ext| ext == PARTIAL_EXTENSION);
+        if !is_partial {
+            continue;
+        }
+        match fs::remove_file(entry.path()) {
+            Ok(()) => {
+                debug!("Removed stale partial file: {}", entry.path().display());
+                removed += 1;
+            }
+            Err(e) => warn!(
+                "Could not remove partial file '{}': {}",
+                entry.path().display(),
+                e
+            ),
+        }
+    }
+    removed
+}
+
+/// What a copy to disk wrote
+///
+/// The hash is computed from the same bytes as they are written, so neither
+/// verification nor tracking has to read the file (or the device) again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyResult {
+    /// Number of bytes written
+    pub bytes: u64,
+    /// SHA256 of the bytes written
+    pub hash: Sha256Hash,
+}
+
+/// Write file content to disk, hashing the bytes as they are written
+///
+/// Returns the size and SHA256 of the source bytes so the written file can be
+/// verified without reading the (one-shot) device stream again. `data` was
+/// charged to the rate cap when [`read_throttled`] read it, so writing it
+/// doesn't wait. `on_bytes` is called with the running total of bytes written.
+pub(crate) fn write_hashed(
+    output_path: &Path,
+    data: &[u8],
+    shutdown_flag: &AtomicBool,
+    on_bytes: &mut dyn FnMut(u64),
+) -> Result<CopyResult> {
+    write_stream(
+        output_path,
+        &Throttle::unlimited(),
+        shutdown_flag,
+        on_bytes,
+        |sink| sink(data),
+    )
+}
+
+/// Read a whole file from the device into memory, charging each chunk to
+/// `throttle`
+///
+/// For content needed before it is written (a capture date, a duplicate
+/// check), so those reads respect the rate cap like streamed copies do.
+pub(crate) fn read_throttled<C: DeviceContentTrait + ?Sized>(
+    content: &C,
+    object_id: &str,
+    throttle: &Throttle,
+) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    content.read_file_chunked(object_id, &mut |chunk| {
+        throttle.consume(chunk.len() as u64);
+        data.extend_from_slice(chunk);
+        Ok(())
+    })?;
+    Ok(data)
+}
+
+/// Stream a file from the device straight to disk, hashing it on the way
+///
+/// Only one chunk is held in memory at a time, so a large video can be
+/// cancelled part way through instead of after it has been read in full.
+/// The device is read exactly once: the returned hash is computed from the
+/// chunks as they are written.
+pub(crate) fn copy_streamed<C: DeviceContentTrait + ?Sized>(
+    content: &C,
+    object_id: &str,
+    output_path: &Path,
+    throttle: &Throttle,
+    shutdown_flag: &AtomicBool,
+    on_bytes: &mut dyn FnMut(u64),
+) -> Result<CopyResult> {
+    write_stream(output_path, throttle, shutdown_flag, on_bytes, |sink| {
+        content.read_file_chunked(object_id, sink)
+    })
+}
+
+/// Create `output_path` and write the chunks passed to the sink by `produce`
+///
+/// The shutdown flag is checked before every [`WRITE_CHUNK_SIZE`] piece is
+/// written. If shutdown is requested ([`ExtractionError::Cancelled`]) or
+/// anything else fails, the partially written file is deleted so no
+/// truncated copy is left behind. `on_bytes` receives the number of bytes
+/// written so far after every piece.
+fn write_stream<F>(
+    output_path: &Path,
+    throttle: &Throttle,
+    shutdown_flag: &AtomicBool,
+    on_bytes: &mut dyn FnMut(u64),
+    produce: F,
+) -> Result<CopyResult>
+where
+    F: FnOnce(&mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()>,
+{
+    let mut file = File::create(output_path).map_err(|e| {
+        ExtractionError::IoError(format!(
+            "Failed to create file '{}': {}",
+            output_path.display(),
+            e
+        ))
+    })?;
+
+    let mut hasher = Sha256::new();
+    let mut written = 0u64;
+    let result = produce(&mut |chunk| {
+        for piece in chunk.chunks(WRITE_CHUNK_SIZE) {
+            if shutdown_flag.load(Ordering::SeqCst) {
+                return Err(ExtractionError::Cancelled);
+            }
+            hasher.update(piece);
+            file.write_all(piece).map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to write file '{}': {}",
+                    output_path.display(),
+                    e
+                ))
+            })?;
+            written += piece.len() as u64;
+            throttle.consume(piece.len() as u64);
+            on_bytes(written);
+        }
+        Ok(())
+    });
+    drop(file);
+
+    if let Err(e) = result {
+        match fs::remove_file(output_path) {
+            Ok(()) => debug!(
+                "Removed partial file '{}' ({} bytes written): {}",
+                output_path.display(),
+                written,
+                e
+            ),
+            Err(remove_err) => warn!(
+                "Could not remove partial file '{}': {}",
+                output_path.display(),
+                remove_err
+            ),
+        }
+        return Err(e);
+    }
+
+    Ok(CopyResult {
+        bytes: written,
+        hash: hasher.finalize().into(),
+    })
+}
+
+/// Check a written file against the hash of the bytes streamed to it
+///
+/// A short WPD read hashes consistently, so the file size is also compared
+/// against the size reported by the device (when known). Returns a description
+/// of the mismatch on failure.
+pub(crate) fn verify_copy(
+    output_path: &Path,
+    source_hash: &Sha256Hash,
+    expected_size: u64,
+) -> std::result::Result<(), String> {
+    let written_size = fs::metadata(output_path)
+        .map_err(|e| format!("cannot stat written file: {}", e))?
+        .len();
+    if expected_size > 0 && written_size != expected_size {
+        return Err(format!(
+            "size mismatch (device reported {} bytes, wrote {})",
+            expected_size, written_size
+        ));
+    }
+
+    let written_hash =
+        compute_file_hash(output_path).map_err(|e| format!("cannot re-read file: {}", e))?;
+    if &written_hash != source_hash {
+        return Err("checksum mismatch".to_string());
+    }
+
+    Ok(())
+}
+
+/// Write a file with `write` and optionally verify it, writing again on failure
+///
+/// `write` creates the file at the path it is given, a [`partial_path`] next
+/// to `output_path`, and returns the [`CopyResult`] of the source bytes. When
+/// `verify` is set, a file that fails [`verify_copy`] is deleted and written
+/// again, up to `retries` more times. Only a complete (and verified) copy is
+/// renamed to `output_path`, so a crash or a dropped device never leaves a
+/// truncated file under the final name. Returns the result of the copy that
+/// was finally written.
+pub(crate) fn write_verified<F>(
+    output_path: &Path,
+    name: &str,
+    expected_size: u64,
+    verify: bool,
+    retries: usize,
+    mut write: F,
+) -> Result<CopyResult>
+where
+    F: FnMut(&Path) -> Result<CopyResult>,
+{
+    let partial = partial_path(output_path);
+    let mut attempt = 0;
+    loop {
+        let copy = write(&partial)?;
+        let verified = if verify {
+            verify_copy(&partial, &copy.hash, expected_size)
+        } else {
+            Ok(())
+        };
 
-const VIDEO_EXTENSIONS: &[&str] = &["mov", "mp4", "m4v", "avi", "3gp"];
+        match verified {
+            Ok(()) => {
+                if verify {
+                    trace!("Verified: {}", output_path.display());
+                }
+                fs::rename(&partial, output_path).map_err(|e| {
+                    let _ = fs::remove_file(&partial);
+                    ExtractionError::IoError(format!(
+                        "Failed to move '{}' into place: {}",
+                        output_path.display(),
+                        e
+                    ))
+                })?;
+                return Ok(copy);
+            }
+            Err(reason) => {
+                if let Err(e) = fs::remove_file(&partial) {
+                    debug!(
+                        "Could not remove unverified file '{}': {}",
+                        partial.display(),
+                        e
+                    );
+                }
+                attempt += 1;
+                if attempt > retries {
+                    return Err(ExtractionError::VerificationFailed {
+                        filename: name.to_string(),
+                        attempts: attempt,
+                    });
+                }
+                warn!(
+                    "Verification failed for '{}' ({}), retrying ({}/{})",
+                    name, reason, attempt, retries
+                );
+            }
+        }
+    }
+}
 
 /// Progress callback for extraction - receives (files_processed_this_update, bytes_this_update)
 pub type ProgressCallback = Box<dyn Fn(usize, u64) + Send>;
@@ -174,27 +974,42 @@ pub type ProgressCallback = Box<dyn Fn(usize, u64) + Send>;
 /// Callback to report total files to process
 pub type TotalFilesCallback = Box<dyn Fn(usize) + Send>;
 
-/// Extract photos from a device
+/// Extract photos from a device, reporting each file to `sink`
+///
+/// [`ProgressSink::on_finished`] is called once whenever the extraction
+/// returns successfully.
+///
+/// # Errors
+///
+/// Fails with [`ExtractionError::InsufficientSpace`] before copying when the
+/// output drive is too small, and with [`ExtractionError::DeviceDisconnected`]
+/// or [`ExtractionError::WindowsError`] when the device can't be opened or
+/// scanned. Once copying has started, a file that fails (even after waiting
+/// for the device to reconnect) is counted in the returned stats instead.
 pub fn extract_photos(
     device_info: &DeviceInfo,
     config: ExtractionConfig,
     shutdown_flag: Arc<AtomicBool>,
+    sink: &mut dyn ProgressSink,
 ) -> Result<ExtractionStats> {
-    extract_photos_with_progress(device_info, config, shutdown_flag, None, None)
+    extract_photos_with_progress(device_info, config, shutdown_flag, sink, None, None)
 }
 
 /// Extract photos from a device with progress callbacks
 ///
+/// Like [`extract_photos`], plus:
 /// - `progress_callback`: receives (files_processed, bytes_transferred) updates per file
 /// - `total_files_callback`: called once with total number of files to process
 pub fn extract_photos_with_progress(
     device_info: &DeviceInfo,
     config: ExtractionConfig,
     shutdown_flag: Arc<AtomicBool>,
+    sink: &mut dyn ProgressSink,
     progress_callback: Option<ProgressCallback>,
     total_files_callback: Option<TotalFilesCallback>,
 ) -> Result<ExtractionStats> {
     let quiet = config.quiet;
+    let path_filter = config.path_filter()?;
 
     // Print clean user-facing output
     if !quiet {
@@ -204,39 +1019,70 @@ pub fn extract_photos_with_progress(
 
     debug!("Opening device: {}", device_info.friendly_name);
 
+    let template = config.output_template().into_owned();
+    let device_name = device_info.friendly_name.as_str();
+
     // Create device manager and open device
     let manager = DeviceManager::new()?;
-    let content = manager.open_device(&device_info.device_id)?;
+    let mut content = manager.open_device(&device_info.device_id)?;
+    let mut reconnect_timeout =
+        Some(Duration::from_secs(config.reconnect_timeout_secs)).filter(|t| !t.is_zero());
 
-    // Create output directory
-    fs::create_dir_all(&config.output_dir).map_err(|e| {
-        ExtractionError::IoError(format!(
-            "Failed to create output directory '{}': {}",
-            config.output_dir.display(),
-            e
-        ))
-    })?;
+    // Create output directory, clearing out files an interrupted run was writing
+    if !config.dry_run {
+        fs::create_dir_all(&config.output_dir).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to create output directory '{}': {}",
+                config.output_dir.display(),
+                e
+            ))
+        })?;
+        remove_partial_files(&config.output_dir);
+    }
 
     if !quiet {
-        println!("  📁 Output: {}", config.output_dir.display());
+        match config.destination {
+            Destination::Zip(ref path) => println!("  🗜 Archive: {}", path.display()),
+            Destination::Directory(_) => println!("  📁 Output: {}", config.output_dir.display()),
+        }
+        if config.dry_run {
+            println!("  🧪 Dry run: nothing will be copied");
+        }
     }
     debug!("Output directory: {}", config.output_dir.display());
 
-    // Initialize state tracker if enabled
-    let mut tracker = if let Some(ref tracking_config) = config.tracking {
-        if tracking_config.enabled {
-            let mut tracker = StateTracker::new(tracking_config, &config.output_dir);
-            if let Err(e) = tracker.load_or_create(device_info) {
-                warn!("Failed to load tracking state: {}", e);
-            }
+    // Initialize state tracker if enabled. With a tracking database the
+    // extracted files are recorded there, and the tracker keeps the sessions
+    let tracking_config = config.tracking.as_ref().filter(|t| t.enabled);
+    let mut store: Option<Box<dyn TrackingStoreTrait>> = match tracking_config {
+        Some(t) => tracking::open_database(t, &config.output_dir)?
+            .map(|database| Box::new(database) as Box<dyn TrackingStoreTrait>),
+        None => None,
+    };
+    let mut tracker = tracking_config.map(|tracking_config| {
+        let mut tracking_config = tracking_config.clone();
+        tracking_config.track_extracted_files &= store.is_none();
+        let mut tracker = StateTracker::new(&tracking_config, &config.output_dir);
+        if let Err(e) = tracker.load_or_create(device_info) {
+            warn!("Failed to load tracking state: {}", e);
+        }
+        if !config.dry_run {
             tracker.start_session();
-            Some(tracker)
-        } else {
-            None
         }
-    } else {
-        None
+        tracker
+    });
+
+    // Pick up where an interrupted run left off
+    let mut checkpoint = match config.checkpoint_path {
+        Some(ref path) if !config.dry_run => {
+            Some(CheckpointTracker::open(path, config.checkpoint_interval))
+        }
+        _ => None,
     };
+    let completed_folders: HashSet<String> = checkpoint
+        .as_ref()
+        .map(|t| t.checkpoint().completed_folders.iter().cloned().collect())
+        .unwrap_or_default();
 
     // Build duplicate detection index if enabled
     let hash_index = if let Some(ref dup_config) = config.duplicate_detection {
@@ -245,7 +1091,9 @@ pub fn extract_photos_with_progress(
                 println!("  🔍 Building duplicate detection index...");
             }
             debug!("Building duplicate detection index...");
-            let detector_config = dup_config.to_detector_config();
+            let detector_config = dup_config
+                .to_detector_config()
+                .with_cache_read_only(config.dry_run);
             match DuplicateIndex::build_from_folders(
                 &detector_config,
                 shutdown_flag.clone(),
@@ -282,16 +1130,34 @@ pub fn extract_photos_with_progress(
         None
     };
 
+    // Index what is already in the output so skip-existing can match content
+    let output_index = if config.skip_existing
+        && config.skip_existing_mode == SkipExistingMode::ByHash
+        && !config.destination.is_zip()
+    {
+        if !quiet {
+            println!("  🔍 Indexing files already in the output folder...");
+        }
+        build_output_index(&config.output_dir, config.dry_run, shutdown_flag.clone())
+    } else {
+        None
+    };
+
     if !quiet {
         println!();
     }
 
     // Find all photos - progress is shown by ScanProgress (only in non-quiet mode)
+    let retrier = Retrier::new(
+        RetryPolicy::new(config.max_retries, config.retry_base_delay_ms),
+        shutdown_flag.clone(),
+    );
     let all_photos = find_all_photos_with_progress(
         &content,
-        config.dcim_only,
-        quiet,
-        config.android_config.as_ref(),
+        &config,
+        &retrier,
+        &shutdown_flag,
+        &completed_folders,
     )?;
     let total_on_device = all_photos.len();
 
@@ -309,12 +1175,67 @@ pub fn extract_photos_with_progress(
 
     debug!("Found {} photos/videos on device", total_on_device);
 
-    // Filter out already-extracted files using tracking state
-    let (photos, already_extracted_count) = if let Some(ref t) = tracker {
+    // Drop files outside the configured date window
+    let (all_photos, excluded_by_date) = if config.has_date_filter() {
+        let before = all_photos.len();
+        let in_range: Vec<PhotoInfo> = all_photos
+            .into_iter()
+            .filter(|photo| config.matches_date(photo.date_modified.as_deref()))
+            .collect();
+        let excluded = before - in_range.len();
+        debug!(
+            "Date filter ({:?} to {:?}) excluded {} files",
+            config.date_from, config.date_to, excluded
+        );
+        (in_range, excluded)
+    } else {
+        (all_photos, 0)
+    };
+
+    // Drop files outside the configured size band, of excluded kinds or on
+    // excluded paths
+    let media_filter = config.media_filter();
+    let (all_photos, filtered) = if media_filter.is_active() || path_filter.is_active() {
+        let before = all_photos.len();
+        let selected: Vec<PhotoInfo> = all_photos
+            .into_iter()
+            .filter(|photo| {
+                let exclusion = media_filter
+                    .exclusion(&photo.name, photo.size)
+                    .or_else(|| path_filter.exclusion(&photo.path));
+                match exclusion {
+                    Some(reason) => {
+                        debug!(
+                            "Filtered out {} ({} bytes): {}",
+                            photo.path, photo.size, reason
+                        );
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect();
+        let filtered = before - selected.len();
+        (selected, filtered)
+    } else {
+        (all_photos, 0)
+    };
+
+    // Filter out already-extracted files using tracking state and the checkpoint
+    let tracked: Option<&dyn TrackingStoreTrait> = match store {
+        Some(ref store) => Some(store.as_ref()),
+        None => tracker.as_ref().map(|t| t as &dyn TrackingStoreTrait),
+    };
+    let (photos, already_extracted_count) = if tracked.is_some() || checkpoint.is_some() {
+        let resumed = checkpoint.as_ref().map(|t| &t.checkpoint().completed);
         let mut new_photos = Vec::new();
         let mut skipped = 0u64;
         for photo in all_photos {
-            if t.is_file_extracted(&photo.object_id) {
+            let extracted = match tracked {
+                Some(t) => t.contains(&photo.object_id)?,
+                None => false,
+            } || resumed.is_some_and(|ids| ids.contains(&photo.object_id));
+            if extracted {
                 skipped += 1;
             } else {
                 new_photos.push(photo);
@@ -325,12 +1246,101 @@ pub fn extract_photos_with_progress(
         (all_photos, 0)
     };
 
+    // A dry run reads the tracking state but never updates it
+    if config.dry_run {
+        tracker = None;
+        store = None;
+    }
+
+    // Only delete from the device once a copy is verified and tracked
+    let delete_after_extract = if !config.delete_after_extract || config.dry_run {
+        false
+    } else if config.destination.is_zip() {
+        warn!("delete_after_extract is not supported for ZIP archives; nothing will be deleted");
+        false
+    } else if !config.verify_after_copy {
+        warn!("delete_after_extract requires verify_after_copy; nothing will be deleted");
+        false
+    } else if tracker.is_none() {
+        warn!("delete_after_extract requires tracking; nothing will be deleted");
+        false
+    } else {
+        if !quiet {
+            println!("  🗑 Verified copies will be deleted from the device");
+        }
+        true
+    };
+
+    // Keep Live Photo stills and their motion clips together
+    let (photos, live_photo_videos) = if config.preserve_live_photo_pairs {
+        order_live_photo_pairs(photos, |p| p.path.as_str(), |p| p.object_id.as_str())
+    } else {
+        (photos, HashMap::new())
+    };
+    let live_photo_images: HashSet<&String> = live_photo_videos.values().collect();
+    let mut live_photo_dirs: HashMap<String, PathBuf> = HashMap::new();
+    // Output paths written so far, so same-named files never replace each other
+    let mut written_paths = WrittenPaths::new();
+
     let total = photos.len();
 
+    // A folder is only complete once every file found in it is done
+    if let Some(ref mut t) = checkpoint {
+        for photo in &photos {
+            t.add_pending(&photo.path);
+        }
+        t.begin_run(total);
+    }
+
+    // Make sure the output drive can hold everything before copying anything
+    let needed: u64 = photos
+        .iter()
+        .filter(|photo| {
+            // With a dated layout the destination depends on the file content
+            template.uses_date()
+                || will_be_written(
+                    &fit_path(
+                        planned_output_path(&config, &template, device_name, photo, None),
+                        config.long_path_strategy,
+                    )
+                    .path,
+                    photo.size,
+                    config.skip_existing && output_index.is_none(),
+                )
+        })
+        .map(|photo| photo.size)
+        .sum();
+    if needed > 0 {
+        let space_root = match config.destination {
+            Destination::Zip(_) => config.destination.folder(),
+            Destination::Directory(_) => config.output_dir.as_path(),
+        };
+        match available_space(space_root) {
+            Ok(available) => {
+                debug!(
+                    "Free space: {} needed, {} available",
+                    format_size(needed),
+                    format_size(available)
+                );
+                match check_free_space(needed, available, config.space_safety_margin_mb) {
+                    Err(e) if config.dry_run => warn!("Dry run: {}", e),
+                    result => result?,
+                }
+            }
+            Err(e) => warn!("Skipping free space check: {}", e),
+        }
+    }
+
     // Show summary before extraction
     if !quiet {
         println!("  📊 Summary:");
         println!("     Total on device:    {}", total_on_device);
+        if excluded_by_date > 0 {
+            println!("     Outside date range: {}", excluded_by_date);
+        }
+        if filtered > 0 {
+            println!("     Filtered:           {}", filtered);
+        }
         if already_extracted_count > 0 {
             println!("     Already extracted:  {}", already_extracted_count);
         }
@@ -354,63 +1364,228 @@ pub fn extract_photos_with_progress(
                 debug!("Failed to save tracking state: {}", e);
             }
         }
-        return Ok(ExtractionStats {
+        if let Some(t) = checkpoint {
+            if let Err(e) = t.finish() {
+                warn!("{}", e);
+            }
+        }
+        let stats = ExtractionStats {
             files_skipped: already_extracted_count as usize,
+            files_excluded_by_date: excluded_by_date,
+            files_filtered: filtered,
             ..Default::default()
-        });
+        };
+        sink.on_finished(&stats.to_sink_stats(Duration::ZERO));
+        return Ok(stats);
     }
 
-    // Set up progress bar with clean style (hidden in quiet mode)
-    let progress = if quiet {
-        ProgressBar::hidden()
-    } else {
-        let pb = ProgressBar::new(total as u64);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("  {spinner:.green} [{bar:40.cyan/dim}] {pos}/{len} ({percent}%) {msg}")
-                .expect("Invalid progress template")
-                .progress_chars("━━╾─"),
-        );
-        pb.enable_steady_tick(std::time::Duration::from_millis(100));
-        pb
+    let mut stats = ExtractionStats {
+        files_excluded_by_date: excluded_by_date,
+        files_filtered: filtered,
+        ..Default::default()
+    };
+    let throttle = Throttle::new(config.max_bytes_per_sec, shutdown_flag.clone());
+    if let Some(rate) = config.max_bytes_per_sec {
+        debug!("Throttling transfers to {}/s", format_size(rate));
+    }
+    let mut manifest = match config.manifest_path {
+        Some(ref path) => Some(ManifestWriter::create(path, config.manifest_format)?),
+        None => None,
+    };
+    let mut run_log = open_run_log(config.append_to_log.as_deref(), !config.dry_run)?;
+    let mut report = config
+        .report_html
+        .as_deref()
+        .map(|path| HtmlReport::new(path, Some(device_name)));
+    let mut duplicate_report = DuplicateReport::new();
+    let mut archive = match config.destination {
+        Destination::Zip(ref path) if !config.dry_run => {
+            if config.verify_after_copy {
+                warn!(
+                    "verify_after_copy is not supported for ZIP archives; copies are not verified"
+                );
+            }
+            Some(ZipDestination::open(path)?)
+        }
+        _ => None,
     };
-
-    let mut stats = ExtractionStats::default();
     let extract_start = Instant::now();
 
+    // Bytes left is the size of every file not yet handled, whether it ends
+    // up copied, skipped or failed
+    let planned_bytes: u64 = photos.iter().map(|photo| photo.size).sum();
+    let mut handled_bytes = 0u64;
+    let mut transferred = 0u64;
+    let mut throughput = ThroughputEstimator::default();
+
     // Extract each photo
     for (index, photo) in photos.iter().enumerate() {
         // Check for shutdown request before processing each file
         if shutdown_flag.load(Ordering::SeqCst) {
+            stats.retries = retrier.retries();
+            sink.on_finished(&stats.to_sink_stats(extract_start.elapsed()));
             if !quiet {
-                progress.finish_with_message("⚠ Interrupted");
                 println!();
                 println!("  ⚠ Extraction interrupted by user");
             }
+            finish_manifest(manifest);
+            finish_report(report);
+            write_duplicate_report(&duplicate_report, &config);
+            if let Some(ref mut t) = checkpoint {
+                if let Err(e) = t.save() {
+                    warn!("{}", e);
+                }
+            }
+            // Leave a readable archive holding everything copied so far
+            finish_archive(archive)?;
             return Ok(stats);
         }
 
-        progress.set_position(index as u64);
+        // Live Photo videos follow their image into the same folder
+        let paired_dir = live_photo_videos
+            .get(&photo.object_id)
+            .and_then(|image_id| live_photo_dirs.get(image_id))
+            .cloned();
+
+        // Start the file again if the device drops and comes back
+        sink.on_file_start(&photo.name, index + 1, total);
+        let mut copied_this_file = 0;
+        let mut reconnects = 0;
+        let result = loop {
+            let result = retrier.run(&photo.name, || {
+                extract_single_photo(
+                    &content,
+                    photo,
+                    &config,
+                    &template,
+                    device_name,
+                    &hash_index,
+                    output_index.as_ref(),
+                    paired_dir.as_deref(),
+                    &written_paths,
+                    reconnects > 0,
+                    &throttle,
+                    &shutdown_flag,
+                    archive.as_mut(),
+                    &mut |copied| {
+                        sink.on_bytes(copied, photo.size);
+                        // A retry starts counting the file from zero again
+                        transferred += copied.checked_sub(copied_this_file).unwrap_or(copied);
+                        copied_this_file = copied;
+                        throughput.record(transferred);
+                        if let Some(rate) = throughput.bytes_per_sec() {
+                            let remaining = planned_bytes.saturating_sub(handled_bytes + copied);
+                            sink.on_throughput(rate, throughput.eta(remaining));
+                        }
+                    },
+                )
+            });
+            let Some(timeout) = reconnect_timeout else {
+                break result;
+            };
+            if !matches!(result, Err(ExtractionError::DeviceDisconnected))
+                || reconnects == MAX_RECONNECTS_PER_FILE
+            {
+                break result;
+            }
 
-        // Show current file (truncated) and transfer rate
-        let elapsed = extract_start.elapsed().as_secs_f64();
-        let rate = if elapsed > 0.0 && stats.total_bytes > 0 {
-            format!(
-                "{:.1} MB/s",
-                stats.total_bytes as f64 / elapsed / 1024.0 / 1024.0
-            )
-        } else {
-            String::new()
+            warn!(
+                "Device disconnected while copying {}; waiting up to {}s for it to come back",
+                photo.name,
+                timeout.as_secs()
+            );
+            match wait_for_device(&manager, &device_info.device_id, timeout, &shutdown_flag) {
+                Ok(device) => {
+                    content = device;
+                    reconnects += 1;
+                    stats.reconnects += 1;
+                }
+                Err(e) => {
+                    // Don't wait again for every remaining file
+                    reconnect_timeout = None;
+                    break Err(e);
+                }
+            }
+        };
+        handled_bytes += photo.size;
+        sink.on_file_done(&match result {
+            Ok(ref extracted) => match extracted.result {
+                ExtractResult::Extracted(bytes)
+                | ExtractResult::DuplicateOverwritten(bytes)
+                | ExtractResult::DuplicateRenamed(bytes) => FileOutcome::Extracted { bytes },
+                ExtractResult::Skipped => FileOutcome::Skipped,
+                ExtractResult::Duplicate(_) => FileOutcome::Duplicate,
+            },
+            Err(ExtractionError::Cancelled) => FileOutcome::Cancelled,
+            Err(ref e) => FileOutcome::Failed(e.to_string()),
+        });
+        if matches!(result, Err(ExtractionError::Cancelled)) {
+            // The partial copy is already gone; the shutdown check at the top
+            // of the loop reports the interruption
+            continue;
+        }
+        if manifest.is_some() || run_log.is_some() || report.is_some() {
+            let entry = manifest_entry(photo, &result);
+            record_manifest_entry(manifest.as_mut(), run_log.as_mut(), report.as_mut(), &entry);
+        }
+        if let Some(entry) = result
+            .as_ref()
+            .ok()
+            .and_then(|extracted| duplicate_report_entry(photo, extracted))
+        {
+            duplicate_report.record(entry);
+        }
+        if let (Some(t), Ok(_)) = (checkpoint.as_mut(), &result) {
+            if let Err(e) = t.complete(&photo.object_id, &photo.path) {
+                warn!("{}", e);
+            }
+        }
+        let (result, written) = match result {
+            Ok(extracted) => {
+                if extracted.renamed_on_collision {
+                    stats.renamed_on_collision += 1;
+                }
+                if !matches!(
+                    extracted.result,
+                    ExtractResult::Skipped | ExtractResult::Duplicate(_)
+                ) {
+                    written_paths.insert(&extracted.path);
+                }
+                if paired_dir.is_some() {
+                    stats.live_photos_paired += 1;
+                } else if live_photo_images.contains(&photo.object_id) {
+                    if let Some(parent) = extracted.path.parent() {
+                        live_photo_dirs.insert(photo.object_id.clone(), parent.to_path_buf());
+                    }
+                }
+                if paired_dir.is_none()
+                    && config.group_bursts
+                    && BurstFrame::from_name(&photo.name).is_some()
+                {
+                    stats.burst_frames_grouped += 1;
+                }
+                (Ok(extracted.result), Some((extracted.path, extracted.hash)))
+            }
+            Err(e) => (Err(e), None),
         };
-        let display_name: String = photo.name.chars().take(25).collect();
-        progress.set_message(format!("{} {}", display_name, rate));
 
-        match extract_single_photo(&content, photo, &config, &hash_index) {
+        match result {
             Ok(ExtractResult::Extracted(bytes)) => {
                 stats.files_extracted += 1;
                 stats.total_bytes += bytes;
+                stats.by_type.record(&photo.name, bytes);
                 if let Some(ref mut t) = tracker {
-                    t.record_extracted(&photo.object_id, bytes);
+                    record_extracted(
+                        t,
+                        &mut store,
+                        photo,
+                        bytes,
+                        written.as_ref(),
+                        &config.output_dir,
+                    );
+                    if delete_after_extract && delete_from_device(&content, photo) {
+                        stats.deleted_from_device += 1;
+                    }
                 }
                 if let Some(ref cb) = progress_callback {
                     cb(1, bytes);
@@ -439,8 +1614,19 @@ pub fn extract_photos_with_progress(
                 trace!("Overwrote duplicate: {}", photo.name);
                 stats.duplicates_overwritten += 1;
                 stats.total_bytes += bytes;
+                stats.by_type.record(&photo.name, bytes);
                 if let Some(ref mut t) = tracker {
-                    t.record_extracted(&photo.object_id, bytes);
+                    record_extracted(
+                        t,
+                        &mut store,
+                        photo,
+                        bytes,
+                        written.as_ref(),
+                        &config.output_dir,
+                    );
+                    if delete_after_extract && delete_from_device(&content, photo) {
+                        stats.deleted_from_device += 1;
+                    }
                 }
                 if let Some(ref cb) = progress_callback {
                     cb(1, bytes);
@@ -450,8 +1636,16 @@ pub fn extract_photos_with_progress(
                 trace!("Renamed duplicate: {}", photo.name);
                 stats.duplicates_renamed += 1;
                 stats.total_bytes += bytes;
+                stats.by_type.record(&photo.name, bytes);
                 if let Some(ref mut t) = tracker {
-                    t.record_extracted(&photo.object_id, bytes);
+                    record_extracted(
+                        t,
+                        &mut store,
+                        photo,
+                        bytes,
+                        written.as_ref(),
+                        &config.output_dir,
+                    );
                 }
                 if let Some(ref cb) = progress_callback {
                     cb(1, bytes);
@@ -460,6 +1654,9 @@ pub fn extract_photos_with_progress(
             Err(e) => {
                 // Only log errors at debug level to avoid cluttering output
                 debug!("Failed to extract '{}': {}", photo.name, e);
+                if matches!(e, ExtractionError::VerificationFailed { .. }) {
+                    stats.verification_failures += 1;
+                }
                 stats.errors += 1;
                 if let Some(ref mut t) = tracker {
                     t.record_error();
@@ -472,6 +1669,11 @@ pub fn extract_photos_with_progress(
     }
 
     // Calculate final stats
+    finish_manifest(manifest);
+    finish_report(report);
+    write_duplicate_report(&duplicate_report, &config);
+    finish_archive(archive)?;
+    stats.retries = retrier.retries();
     let elapsed = extract_start.elapsed();
     let rate = if elapsed.as_secs_f64() > 0.0 {
         stats.total_bytes as f64 / elapsed.as_secs_f64() / 1024.0 / 1024.0
@@ -479,16 +1681,7 @@ pub fn extract_photos_with_progress(
         0.0
     };
 
-    // Show completion with transfer rate
-    if !quiet {
-        progress.set_style(
-            ProgressStyle::default_bar()
-                .template("  ✓ [{bar:40.green/dim}] {pos}/{len} Complete")
-                .expect("Invalid progress template")
-                .progress_chars("━━━"),
-        );
-        progress.finish();
-    }
+    sink.on_finished(&stats.to_sink_stats(elapsed));
 
     // End tracking session
     let was_interrupted = shutdown_flag.load(Ordering::SeqCst);
@@ -499,11 +1692,27 @@ pub fn extract_photos_with_progress(
         }
     }
 
+    // Keep the checkpoint until every file has been handled
+    if let Some(mut t) = checkpoint {
+        let saved = if !was_interrupted && stats.errors == 0 {
+            t.finish()
+        } else {
+            t.save()
+        };
+        if let Err(e) = saved {
+            warn!("{}", e);
+        }
+    }
+
     // Print summary
     if !quiet {
         println!();
         println!("  ─────────────────────────────────────────");
-        println!("  📊 Extraction Results:");
+        if config.dry_run {
+            println!("  📊 Dry Run Results (nothing was copied):");
+        } else {
+            println!("  📊 Extraction Results:");
+        }
         println!("     Files extracted:  {}", stats.files_extracted);
         if stats.files_skipped > 0 {
             println!("     Files skipped:    {}", stats.files_skipped);
@@ -511,10 +1720,38 @@ pub fn extract_photos_with_progress(
         if stats.duplicates_skipped > 0 {
             println!("     Duplicates:       {}", stats.duplicates_skipped);
         }
+        if stats.files_excluded_by_date > 0 {
+            println!("     Out of range:     {}", stats.files_excluded_by_date);
+        }
+        if stats.files_filtered > 0 {
+            println!("     Filtered:         {}", stats.files_filtered);
+        }
+        if stats.live_photos_paired > 0 {
+            println!("     Live Photos:      {}", stats.live_photos_paired);
+        }
+        if stats.burst_frames_grouped > 0 {
+            println!("     Burst frames:     {}", stats.burst_frames_grouped);
+        }
+        if stats.renamed_on_collision > 0 {
+            println!("     Renamed (clash):  {}", stats.renamed_on_collision);
+        }
+        if stats.verification_failures > 0 {
+            println!("     Failed verify:    {}", stats.verification_failures);
+        }
+        if stats.retries > 0 {
+            println!("     Retries:          {}", stats.retries);
+        }
+        if stats.reconnects > 0 {
+            println!("     Reconnects:       {}", stats.reconnects);
+        }
+        if stats.deleted_from_device > 0 {
+            println!("     Deleted:          {}", stats.deleted_from_device);
+        }
         if stats.errors > 0 {
             println!("     Errors:           {}", stats.errors);
         }
         println!("     Total size:       {}", format_size(stats.total_bytes));
+        print_stats_by_type(&stats.by_type);
         println!(
             "     Duration:         {:.1}s ({:.1} MB/s)",
             elapsed.as_secs_f64(),
@@ -526,8 +1763,332 @@ pub fn extract_photos_with_progress(
     Ok(stats)
 }
 
+/// Record an extracted photo in the session stats, and in the tracking
+/// database instead of the tracking file if there is one
+fn record_extracted(
+    tracker: &mut StateTracker,
+    store: &mut Option<Box<dyn TrackingStoreTrait>>,
+    photo: &PhotoInfo,
+    bytes: u64,
+    written: Option<&(PathBuf, Option<Sha256Hash>)>,
+    output_dir: &Path,
+) {
+    let Some(store) = store else {
+        match written {
+            Some((path, hash)) => {
+                tracker.record_extracted_file(&photo.object_id, bytes, path, hash.as_ref())
+            }
+            None => tracker.record_extracted(&photo.object_id, bytes),
+        }
+        return;
+    };
+
+    tracker.record_extracted(&photo.object_id, bytes);
+    let hash = written.and_then(|(_, hash)| hash.as_ref()).map(hash_to_hex);
+    let mut file = TrackedFile::new(&photo.object_id, hash, bytes);
+    if let Some((path, _)) = written {
+        file = file.with_destination(path.strip_prefix(output_dir).unwrap_or(path));
+    }
+    if let Err(e) = store.insert(&file) {
+        warn!("Failed to record '{}': {}", photo.name, e);
+    }
+}
+
+/// Print the photo/video split and the per-extension breakdown of a summary
+pub fn print_stats_by_type(by_type: &StatsByType) {
+    if by_type.is_empty() {
+        return;
+    }
+    println!(
+        "     Photos:           {} ({})",
+        by_type.photos.files,
+        format_size(by_type.photos.bytes)
+    );
+    println!(
+        "     Videos:           {} ({})",
+        by_type.videos.files,
+        format_size(by_type.videos.bytes)
+    );
+    for (extension, tally) in by_type.extensions_by_size() {
+        let label = if extension.is_empty() {
+            "(none)".to_string()
+        } else {
+            extension.to_uppercase()
+        };
+        println!(
+            "       {:<8}        {} ({})",
+            label,
+            tally.files,
+            format_size(tally.bytes)
+        );
+    }
+}
+
+/// Compare the device's files with its tracking state without copying anything
+///
+/// Uses the same folder and date filters as [`extract_photos`] and the
+/// tracking file (or existing tracking database) in `config.output_dir`.
+/// Nothing is written: no files, no manifest and no tracking session. With
+/// `compare_hashes`, files that were already extracted are read from the
+/// device and re-hashed.
+pub fn plan(
+    device_info: &DeviceInfo,
+    config: &ExtractionConfig,
+    compare_hashes: bool,
+) -> Result<ExtractionPlan> {
+    let path_filter = config.path_filter()?;
+    let manager = DeviceManager::new()?;
+    let content = manager.open_device(&device_info.device_id)?;
+
+    let retrier = Retrier::new(
+        RetryPolicy::new(config.max_retries, config.retry_base_delay_ms),
+        Arc::new(AtomicBool::new(false)),
+    );
+    let photos = find_all_photos_with_progress(
+        &content,
+        config,
+        &retrier,
+        &AtomicBool::new(false),
+        &HashSet::new(),
+    )?;
+
+    // Loading the state never saves it; only an extraction session does that
+    let mut tracker = StateTracker::new(
+        &config.tracking.clone().unwrap_or_default(),
+        &config.output_dir,
+    );
+    if config.tracking.as_ref().is_some_and(|t| t.enabled) {
+        if let Err(e) = tracker.load_or_create(device_info) {
+            warn!("Failed to load tracking state: {}", e);
+        }
+    }
+    // An existing tracking database holds the extracted files instead
+    let database = match config.tracking.as_ref().filter(|t| t.enabled) {
+        Some(t)
+            if t.database_path(&config.output_dir)
+                .is_some_and(|p| p.exists()) =>
+        {
+            tracking::open_database(t, &config.output_dir)?
+        }
+        _ => None,
+    };
+    let tracked: &dyn TrackingStoreTrait = match database {
+        Some(ref database) => database,
+        None => &tracker,
+    };
+
+    let media_filter = config.media_filter();
+    let mut plan = ExtractionPlan::new();
+    for photo in photos {
+        if !config.matches_date(photo.date_modified.as_deref())
+            || !media_filter.accepts(&photo.name, photo.size)
+            || !path_filter.accepts(&photo.path)
+        {
+            continue;
+        }
+
+        let read = compare_hashes
+            .then_some(|| retrier.run(&photo.name, || content.read_file(&photo.object_id)));
+        let status = classify(tracked, &photo.object_id, photo.size, read)?;
+        plan.add(PlannedFile {
+            object_id: photo.object_id,
+            path: photo.path,
+            size: photo.size,
+            status,
+        });
+    }
+
+    debug!(
+        "Plan: {} new, {} already extracted, {} changed",
+        plan.summary.new.files, plan.summary.already_have.files, plan.summary.changed.files
+    );
+    Ok(plan)
+}
+
+/// Delete a photo from the device after its copy was verified and tracked
+///
+/// A failed delete is only logged; the photo stays on the device and the copy
+/// is kept.
+fn delete_from_device<C: DeviceContentTrait + ?Sized>(content: &C, photo: &PhotoInfo) -> bool {
+    match content.delete_object(&photo.object_id) {
+        Ok(()) => {
+            debug!("Deleted from device: {}", photo.path);
+            true
+        }
+        Err(e) => {
+            warn!("Failed to delete '{}' from device: {}", photo.path, e);
+            false
+        }
+    }
+}
+
+/// Build the manifest entry describing how a photo was handled
+fn manifest_entry(photo: &PhotoInfo, result: &Result<ExtractedPhoto>) -> ManifestEntry {
+    let burst = BurstFrame::from_name(&photo.name);
+    let entry = |outcome| {
+        ManifestEntry::new(&photo.object_id, &photo.path, photo.size, outcome)
+            .with_burst(burst.as_ref())
+    };
+    let date_modified = photo.date_modified.as_deref();
+
+    match result {
+        Ok(extracted) => {
+            let outcome = match extracted.result {
+                ExtractResult::Skipped => ManifestOutcome::Skipped,
+                ExtractResult::Duplicate(_) => ManifestOutcome::Duplicate,
+                _ => ManifestOutcome::Extracted,
+            };
+            entry(outcome)
+                .with_hash(extracted.hash.as_ref())
+                .with_destination(&extracted.path, date_modified)
+                .with_truncated_from(extracted.truncated_from.as_deref())
+        }
+        Err(e) => entry(ManifestOutcome::Error).with_error(e),
+    }
+}
+
+/// Describe a photo that matched an existing file, if it did
+fn duplicate_report_entry(
+    photo: &PhotoInfo,
+    extracted: &ExtractedPhoto,
+) -> Option<DuplicateReportEntry> {
+    let matched = extracted.duplicate_of.as_ref()?;
+    let entry = |resolution| {
+        DuplicateReportEntry::new(
+            &photo.path,
+            matched,
+            photo.size,
+            MatchType::Exact,
+            resolution,
+        )
+    };
+
+    match extracted.result {
+        ExtractResult::Duplicate(_) => Some(entry(DuplicateResolution::Skip)),
+        ExtractResult::DuplicateOverwritten(_) => {
+            Some(entry(DuplicateResolution::Overwrite).with_destination(&extracted.path))
+        }
+        ExtractResult::DuplicateRenamed(_) => {
+            Some(entry(DuplicateResolution::Rename).with_destination(&extracted.path))
+        }
+        _ => None,
+    }
+}
+
+/// Write the duplicate report if one is configured, logging (rather than
+/// failing the extraction) on error
+fn write_duplicate_report(report: &DuplicateReport, config: &ExtractionConfig) {
+    let Some(detection) = config.duplicate_detection.as_ref() else {
+        return;
+    };
+    if let Some(ref path) = detection.report_path {
+        finish_duplicate_report(report, path, detection.report_format);
+    }
+}
+
+/// Write a duplicate report, logging (rather than failing the extraction) on error
+pub(crate) fn finish_duplicate_report(
+    report: &DuplicateReport,
+    path: &Path,
+    format: ManifestFormat,
+) {
+    match report.write_report(path, format) {
+        Ok(()) => debug!(
+            "Duplicate report ({} entries) written to {}",
+            report.len(),
+            path.display()
+        ),
+        Err(e) => warn!("{}", e),
+    }
+}
+
+/// Write the archive's central directory, if extracting into a ZIP archive
+///
+/// Unlike the manifest this is an error: without a central directory none of
+/// the copied entries can be read back.
+pub(crate) fn finish_archive(archive: Option<ZipDestination>) -> Result<()> {
+    if let Some(zip) = archive {
+        let (path, entries) = (zip.path().to_path_buf(), zip.len());
+        zip.finish()?;
+        debug!(
+            "Archive ({} entries) written to {}",
+            entries,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Open the run log at `path` for this run, if one is configured
+///
+/// Nothing is logged unless files are actually written, so a dry run leaves
+/// the history untouched.
+pub(crate) fn open_run_log(path: Option<&Path>, write_files: bool) -> Result<Option<RunLogWriter>> {
+    match path {
+        Some(path) if write_files => {
+            let log = RunLogWriter::open(path)?;
+            debug!(
+                "Appending to run log {} as run {}",
+                path.display(),
+                log.run_id()
+            );
+            Ok(Some(log))
+        }
+        Some(_) => {
+            debug!("Dry run; not appending to the run log");
+            Ok(None)
+        }
+        None => Ok(None),
+    }
+}
+
+/// Record a file in the manifest, run log and HTML report, logging (rather
+/// than failing the extraction) on error
+pub(crate) fn record_manifest_entry(
+    manifest: Option<&mut ManifestWriter>,
+    run_log: Option<&mut RunLogWriter>,
+    report: Option<&mut HtmlReport>,
+    entry: &ManifestEntry,
+) {
+    if let Some(writer) = manifest {
+        if let Err(e) = writer.record(entry) {
+            warn!("{}", e);
+        }
+    }
+    if let Some(log) = run_log {
+        if let Err(e) = log.record(entry) {
+            warn!("{}", e);
+        }
+    }
+    if let Some(report) = report {
+        report.record(entry);
+    }
+}
+
+/// Close the manifest, logging (rather than failing the extraction) on error
+pub(crate) fn finish_manifest(manifest: Option<ManifestWriter>) {
+    if let Some(writer) = manifest {
+        let path = writer.path().to_path_buf();
+        match writer.finish() {
+            Ok(()) => debug!("Manifest written to {}", path.display()),
+            Err(e) => warn!("{}", e),
+        }
+    }
+}
+
+/// Write the HTML report, logging (rather than failing the extraction) on error
+pub(crate) fn finish_report(report: Option<HtmlReport>) {
+    if let Some(report) = report {
+        let path = report.path().to_path_buf();
+        match report.finish() {
+            Ok(()) => debug!("HTML report written to {}", path.display()),
+            Err(e) => warn!("{}", e),
+        }
+    }
+}
+
 /// Format bytes as human-readable size
-fn format_size(bytes: u64) -> String {
+pub(crate) fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -543,21 +2104,29 @@ fn format_size(bytes: u64) -> String {
     }
 }
 
-/// Find all photos on the device recursively with optional progress display
+/// Find all photos on the device with optional progress display
+///
+/// DCIM (or the iOS date folders, or every folder without `dcim_only`) is
+/// listed on `config.scan_threads` workers. Folders in `skip_folders` are
+/// not listed at all.
 fn find_all_photos_with_progress(
     content: &DeviceContent,
-    dcim_only: bool,
-    quiet: bool,
-    android_config: Option<&AndroidConfig>,
+    config: &ExtractionConfig,
+    retrier: &Retrier,
+    shutdown_flag: &AtomicBool,
+    skip_folders: &HashSet<String>,
 ) -> Result<Vec<PhotoInfo>> {
+    let dcim_only = config.dcim_only;
+    let order = config.scan_order;
     let mut photos = Vec::new();
-    let progress = if quiet {
+    let progress = if config.quiet {
         None
     } else {
         Some(ScanProgress::new())
     };
 
-    let root_objects = content.enumerate_objects()?;
+    let mut root_objects = retrier.run("Enumerate device root", || content.enumerate_objects())?;
+    order.sort(&mut root_objects);
 
     debug!("Found {} root objects on device", root_objects.len());
 
@@ -579,118 +2148,180 @@ fn find_all_photos_with_progress(
     }
 
     // If Android config is provided, use Android-specific scanning
-    if let Some(android_cfg) = android_config {
+    if let Some(ref android_cfg) = config.android_config {
         debug!("Using Android-specific folder scanning");
-        let android_photos = scan_android_device(content, android_cfg, &progress)?;
+        let android_photos = scan_android_device(content, android_cfg, &progress, retrier, order)?;
         if let Some(ref p) = progress {
             p.finish();
         }
         return Ok(android_photos);
     }
 
-    // Look for storage or DCIM folder
-    for obj in root_objects {
-        let name_upper = obj.name.to_uppercase();
+    // Each storage (internal, SD card, ...) has its own tree and DCIM may be
+    // on any of them
+    let storages = retrier.run("Enumerate storages", || content.enumerate_storages())?;
+    debug!("Found {} storage(s) on device", storages.len());
+
+    // Folders to list once every storage has been looked at, with where
+    // their photos go in `photos`
+    let mut roots: Vec<(usize, DiscoveryRoot)> = Vec::new();
+
+    // With several storages, prefix paths with the storage name so that their
+    // DCIM folders don't land in the same place
+    let storage_path = |storage: &StorageVolume, path: &str| {
+        if storages.len() > 1 {
+            format!("{}/{}", storage.description, path)
+        } else {
+            path.to_string()
+        }
+    };
+
+    for storage in &storages {
         debug!(
-            "Processing root object: '{}' (uppercase: '{}')",
-            obj.name, name_upper
+            "Scanning storage '{}' (id: {})",
+            storage.description, storage.object_id
         );
 
-        // Always scan the object if it's a folder - we need to find DCIM inside
-        if obj.is_folder {
-            debug!("'{}' is a folder, scanning its contents...", obj.name);
-
-            // Enumerate children of this object
-            match content.enumerate_children(&obj.object_id) {
-                Ok(children) => {
-                    debug!("Found {} children in '{}'", children.len(), obj.name);
+        let children = match retrier.run("Enumerate folder", || {
+            content.enumerate_children(&storage.object_id)
+        }) {
+            Ok(mut children) => {
+                order.sort(&mut children);
+                children
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to enumerate children of '{}': {}",
+                    storage.description, e
+                );
+                continue;
+            }
+        };
+        debug!(
+            "Found {} children in '{}'",
+            children.len(),
+            storage.description
+        );
 
-                    for child in &children {
-                        debug!(
-                            "  Child: '{}' (id: {}, folder: {}, size: {})",
-                            child.name, child.object_id, child.is_folder, child.size
-                        );
-                    }
+        for child in &children {
+            debug!(
+                "  Child: '{}' (id: {}, folder: {}, size: {})",
+                child.name, child.object_id, child.is_folder, child.size
+            );
+        }
 
-                    if dcim_only {
-                        // First, look for traditional DCIM folder
-                        let mut found_dcim = false;
-                        for child in &children {
-                            let child_name_upper = child.name.to_uppercase();
-                            if child_name_upper == "DCIM" && child.is_folder {
-                                debug!("Found DCIM folder inside '{}', scanning...", obj.name);
-                                found_dcim = true;
-                                scan_folder_recursive_quiet(
-                                    content,
-                                    child,
-                                    "DCIM",
-                                    &mut photos,
-                                    &progress,
-                                )?;
-                            }
-                        }
+        if dcim_only {
+            // First, look for traditional DCIM folder
+            let mut found_dcim = false;
+            for child in &children {
+                if child.name.to_uppercase() == "DCIM" && child.is_folder {
+                    debug!(
+                        "Found DCIM folder inside '{}', scanning...",
+                        storage.description
+                    );
+                    found_dcim = true;
+                    roots.push((
+                        photos.len(),
+                        DiscoveryRoot::new(&child.object_id, storage_path(storage, "DCIM")),
+                    ));
+                }
+            }
 
-                        // If no DCIM found, look for iOS date-based photo folders
-                        // These have patterns like "202511__", "201902__", "202506_a", etc.
-                        // (6 digits for YYYYMM followed by underscore or other chars)
-                        if !found_dcim {
-                            debug!(
-                                "No DCIM folder found, scanning for date-based photo folders..."
-                            );
-                            for child in children {
-                                if child.is_folder && is_ios_photo_folder(&child.name) {
-                                    debug!("Found photo folder '{}', scanning...", child.name);
-                                    let path = child.name.clone();
-                                    scan_folder_recursive_quiet(
-                                        content,
-                                        &child,
-                                        &path,
-                                        &mut photos,
-                                        &progress,
-                                    )?;
-                                }
-                            }
-                        }
-                    } else {
-                        // Scan everything inside
-                        for child in children {
-                            if child.is_folder {
-                                let path = format!("{}/{}", obj.name, child.name);
-                                scan_folder_recursive_quiet(
-                                    content,
-                                    &child,
-                                    &path,
-                                    &mut photos,
-                                    &progress,
-                                )?;
-                            } else if is_media_file(&child.name) {
-                                photos.push(PhotoInfo {
-                                    object_id: child.object_id.clone(),
-                                    name: child.name.clone(),
-                                    path: format!("{}/{}", obj.name, child.name),
-                                    size: child.size,
-                                    date_modified: child.date_modified.clone(),
-                                });
-                                if let Some(ref p) = progress {
-                                    p.add_files(1);
-                                }
-                            }
-                        }
+            // If no DCIM found, look for iOS date-based photo folders
+            // These have patterns like "202511__", "201902__", "202506_a", etc.
+            // (6 digits for YYYYMM followed by underscore or other chars)
+            if !found_dcim {
+                debug!("No DCIM folder found, scanning for date-based photo folders...");
+                for child in children {
+                    if child.is_folder && is_ios_photo_folder(&child.name) {
+                        debug!("Found photo folder '{}', scanning...", child.name);
+                        let path = storage_path(storage, &child.name);
+                        roots.push((photos.len(), DiscoveryRoot::new(child.object_id, path)));
                     }
                 }
-                Err(e) => {
-                    warn!("Failed to enumerate children of '{}': {}", obj.name, e);
+            }
+        } else {
+            // Scan everything inside
+            for child in children {
+                let path = format!("{}/{}", storage.description, child.name);
+                if child.is_folder {
+                    roots.push((photos.len(), DiscoveryRoot::new(child.object_id, path)));
+                } else if is_media_file(&child.name) {
+                    photos.push(PhotoInfo {
+                        object_id: child.object_id.clone(),
+                        name: child.name.clone(),
+                        path,
+                        size: child.size,
+                        date_modified: child.date_modified.clone(),
+                    });
+                    if let Some(ref p) = progress {
+                        p.add_files(1);
+                    }
                 }
             }
-        } else if !dcim_only && is_media_file(&obj.name) {
-            // It's a file at root level
-            photos.push(PhotoInfo {
-                object_id: obj.object_id.clone(),
-                name: obj.name.clone(),
-                path: obj.name.clone(),
-                size: obj.size,
-                date_modified: obj.date_modified.clone(),
+        }
+    }
+
+    // A checkpoint recorded everything in these folders as done
+    let skip = |path: &str| {
+        let completed = skip_folders.contains(path);
+        if completed {
+            debug!("Skipping completed folder: {}", path);
+        }
+        completed
+    };
+    let (positions, roots): (Vec<usize>, Vec<DiscoveryRoot>) = roots
+        .into_iter()
+        .filter(|(_, root)| !skip(&root.path))
+        .unzip();
+    let discovery = discover_folders(
+        content,
+        &roots,
+        config.scan_threads,
+        order,
+        retrier,
+        shutdown_flag,
+        |path, _| !skip(path),
+        |folder| {
+            if let Some(ref p) = progress {
+                p.increment_folders();
+                let media = folder
+                    .found
+                    .iter()
+                    .filter(|file| is_media_file(&file.object.name))
+                    .count();
+                p.add_files(media);
+            }
+        },
+    )?;
+
+    // Later positions first, so earlier ones stay valid
+    for (position, found) in positions.into_iter().zip(discovery.files).rev() {
+        let media = found
+            .into_iter()
+            .filter(|file| is_media_file(&file.object.name))
+            .map(|file| PhotoInfo {
+                object_id: file.object.object_id,
+                name: file.object.name,
+                path: file.path,
+                size: file.object.size,
+                date_modified: file.object.date_modified,
             });
+        photos.splice(position..position, media);
+    }
+
+    // Media files sitting at the device root, outside any storage
+    if !dcim_only {
+        for obj in root_objects {
+            if !obj.is_folder && is_media_file(&obj.name) {
+                photos.push(PhotoInfo {
+                    object_id: obj.object_id.clone(),
+                    name: obj.name.clone(),
+                    path: obj.name.clone(),
+                    size: obj.size,
+                    date_modified: obj.date_modified.clone(),
+                });
+            }
         }
     }
 
@@ -704,41 +2335,6 @@ fn find_all_photos_with_progress(
     Ok(photos)
 }
 
-/// Scan folder recursively with optional progress tracking (for quiet mode support)
-fn scan_folder_recursive_quiet(
-    content: &DeviceContent,
-    folder: &DeviceObject,
-    path: &str,
-    photos: &mut Vec<PhotoInfo>,
-    progress: &Option<ScanProgress>,
-) -> Result<()> {
-    if let Some(ref p) = progress {
-        p.increment_folders();
-    }
-
-    let children = content.enumerate_children(&folder.object_id)?;
-
-    for child in children {
-        if child.is_folder {
-            let child_path = format!("{}/{}", path, child.name);
-            scan_folder_recursive_quiet(content, &child, &child_path, photos, progress)?;
-        } else if is_media_file(&child.name) {
-            photos.push(PhotoInfo {
-                object_id: child.object_id.clone(),
-                name: child.name.clone(),
-                path: format!("{}/{}", path, child.name),
-                size: child.size,
-                date_modified: child.date_modified.clone(),
-            });
-            if let Some(ref p) = progress {
-                p.add_files(1);
-            }
-        }
-    }
-
-    Ok(())
-}
-
 /// Check if a folder name looks like an iOS photo folder
 /// iOS sometimes uses date-based folder names like "202511__", "201902__", "202506_a"
 /// Pattern: 6 digits (YYYYMM) followed by underscore or other suffix
@@ -827,36 +2423,32 @@ fn scan_android_device(
     content: &DeviceContent,
     config: &AndroidConfig,
     progress: &Option<ScanProgress>,
+    retrier: &Retrier,
+    order: ScanOrder,
 ) -> Result<Vec<PhotoInfo>> {
     let mut photos = Vec::new();
 
-    let root_objects = content.enumerate_objects()?;
+    let storages = retrier.run("Enumerate storages", || content.enumerate_storages())?;
     debug!(
-        "Scanning Android device, found {} root objects",
-        root_objects.len()
+        "Scanning Android device, found {} storage(s)",
+        storages.len()
     );
 
-    // Find Internal Storage or similar root
-    for root in root_objects {
-        if !root.is_folder {
-            continue;
-        }
-
-        let root_name_upper = root.name.to_uppercase();
-        let is_storage = root_name_upper.contains("STORAGE")
-            || root_name_upper.contains("INTERNAL")
-            || root_name_upper == "SDCARD"
-            || root_name_upper == "PHONE";
-
-        if !is_storage {
-            continue;
-        }
-
+    // Internal storage and SD card alike may hold DCIM and the app folders;
+    // with several storages, keep their DCIM paths apart
+    let multiple_storages = storages.len() > 1;
+    for storage in storages {
+        let root = DeviceObject::folder(&storage.object_id, "DEVICE", &storage.description);
         debug!("Found storage root: '{}'", root.name);
 
         // Enumerate storage contents
-        let storage_children = match content.enumerate_children(&root.object_id) {
-            Ok(children) => children,
+        let storage_children = match retrier.run("Enumerate folder", || {
+            content.enumerate_children(&root.object_id)
+        }) {
+            Ok(mut children) => {
+                order.sort(&mut children);
+                children
+            }
             Err(e) => {
                 warn!("Failed to enumerate storage '{}': {}", root.name, e);
                 continue;
@@ -873,7 +2465,21 @@ fn scan_android_device(
             // Handle DCIM folder (contains Camera, Screenshots on some devices)
             if child_name_upper == "DCIM" {
                 debug!("Found DCIM folder, scanning for Camera/Screenshots...");
-                scan_android_dcim(content, &child, config, &mut photos, progress)?;
+                let dcim_path = if multiple_storages {
+                    format!("{}/DCIM", root.name)
+                } else {
+                    "DCIM".to_string()
+                };
+                scan_android_dcim(
+                    content,
+                    &child,
+                    &dcim_path,
+                    config,
+                    &mut photos,
+                    progress,
+                    retrier,
+                    order,
+                )?;
             }
             // Handle Pictures folder
             else if child_name_upper == "PICTURES" && config.include_pictures {
@@ -886,6 +2492,8 @@ fn scan_android_device(
                     config,
                     &mut photos,
                     progress,
+                    retrier,
+                    order,
                 )?;
             }
             // Handle Download folder
@@ -899,6 +2507,8 @@ fn scan_android_device(
                     config,
                     &mut photos,
                     progress,
+                    retrier,
+                    order,
                 )?;
             }
             // Handle additional user-configured folders
@@ -912,6 +2522,8 @@ fn scan_android_device(
                     config,
                     &mut photos,
                     progress,
+                    retrier,
+                    order,
                 )?;
             }
         }
@@ -919,7 +2531,15 @@ fn scan_android_device(
         // Scan app-specific folders if enabled
         if config.has_app_folders_enabled() {
             debug!("Scanning app-specific folders...");
-            scan_android_app_folders(content, &root, config, &mut photos, progress)?;
+            scan_android_app_folders(
+                content,
+                &root,
+                config,
+                &mut photos,
+                progress,
+                retrier,
+                order,
+            )?;
         }
     }
 
@@ -933,6 +2553,8 @@ fn scan_android_app_folders(
     config: &AndroidConfig,
     photos: &mut Vec<PhotoInfo>,
     progress: &Option<ScanProgress>,
+    retrier: &Retrier,
+    order: ScanOrder,
 ) -> Result<()> {
     let enabled_folders = config.get_enabled_app_folders();
 
@@ -949,10 +2571,12 @@ fn scan_android_app_folders(
         debug!("Looking for app folder: {}", folder_path);
 
         // Try to navigate to the folder path
-        if let Some(folder) = find_folder_by_path(content, storage_root, folder_path) {
+        if let Some(folder) = find_folder_by_path(content, storage_root, folder_path, retrier) {
             debug!("Found app folder: {}", folder_path);
             let full_path = format!("{}/{}", storage_root.name, folder_path);
-            scan_android_folder_recursive(content, &folder, &full_path, config, photos, progress)?;
+            scan_android_folder_recursive(
+                content, &folder, &full_path, config, photos, progress, retrier, order,
+            )?;
         } else {
             trace!("App folder not found: {}", folder_path);
         }
@@ -962,10 +2586,12 @@ fn scan_android_app_folders(
     for folder_path in &config.additional_folders {
         debug!("Looking for custom folder: {}", folder_path);
 
-        if let Some(folder) = find_folder_by_path(content, storage_root, folder_path) {
+        if let Some(folder) = find_folder_by_path(content, storage_root, folder_path, retrier) {
             debug!("Found custom folder: {}", folder_path);
             let full_path = format!("{}/{}", storage_root.name, folder_path);
-            scan_android_folder_recursive(content, &folder, &full_path, config, photos, progress)?;
+            scan_android_folder_recursive(
+                content, &folder, &full_path, config, photos, progress, retrier, order,
+            )?;
         } else {
             trace!("Custom folder not found: {}", folder_path);
         }
@@ -979,6 +2605,7 @@ fn find_folder_by_path(
     content: &DeviceContent,
     storage_root: &DeviceObject,
     path: &str,
+    retrier: &Retrier,
 ) -> Option<DeviceObject> {
     let path_parts: Vec<&str> = path.split('/').collect();
 
@@ -991,7 +2618,9 @@ fn find_folder_by_path(
     let mut current_folder: Option<DeviceObject> = None;
 
     for (i, part) in path_parts.iter().enumerate() {
-        let children = match content.enumerate_children(&current_parent_id) {
+        let children = match retrier.run("Enumerate folder", || {
+            content.enumerate_children(&current_parent_id)
+        }) {
             Ok(c) => c,
             Err(_) => return None,
         };
@@ -1023,19 +2652,28 @@ fn find_folder_by_path(
 }
 
 /// Scan Android DCIM folder for Camera and Screenshots
+#[allow(clippy::too_many_arguments)]
 fn scan_android_dcim(
     content: &DeviceContent,
     dcim: &DeviceObject,
+    dcim_path: &str,
     config: &AndroidConfig,
     photos: &mut Vec<PhotoInfo>,
     progress: &Option<ScanProgress>,
+    retrier: &Retrier,
+    order: ScanOrder,
 ) -> Result<()> {
     if let Some(ref p) = progress {
         p.increment_folders();
     }
 
-    let children = match content.enumerate_children(&dcim.object_id) {
-        Ok(c) => c,
+    let children = match retrier.run("Enumerate folder", || {
+        content.enumerate_children(&dcim.object_id)
+    }) {
+        Ok(mut c) => {
+            order.sort(&mut c);
+            c
+        }
         Err(e) => {
             warn!("Failed to enumerate DCIM: {}", e);
             return Ok(());
@@ -1049,7 +2687,7 @@ fn scan_android_dcim(
                 photos.push(PhotoInfo {
                     object_id: child.object_id.clone(),
                     name: child.name.clone(),
-                    path: format!("DCIM/{}", child.name),
+                    path: format!("{}/{}", dcim_path, child.name),
                     size: child.size,
                     date_modified: child.date_modified.clone(),
                 });
@@ -1070,22 +2708,28 @@ fn scan_android_dcim(
 
         // Camera folder
         if child_name_upper == "CAMERA" && config.include_camera {
-            debug!("Scanning DCIM/Camera...");
-            let path = "DCIM/Camera".to_string();
-            scan_android_folder_recursive(content, &child, &path, config, photos, progress)?;
+            let path = format!("{}/Camera", dcim_path);
+            debug!("Scanning {}...", path);
+            scan_android_folder_recursive(
+                content, &child, &path, config, photos, progress, retrier, order,
+            )?;
         }
         // Screenshots folder (some devices have it under DCIM)
         else if child_name_upper == "SCREENSHOTS" && config.include_screenshots {
-            debug!("Scanning DCIM/Screenshots...");
-            let path = "DCIM/Screenshots".to_string();
-            scan_android_folder_recursive(content, &child, &path, config, photos, progress)?;
+            let path = format!("{}/Screenshots", dcim_path);
+            debug!("Scanning {}...", path);
+            scan_android_folder_recursive(
+                content, &child, &path, config, photos, progress, retrier, order,
+            )?;
         }
         // Other folders in DCIM (like 100ANDRO, manufacturer-specific folders)
         else if config.include_camera {
             // Include other DCIM subfolders as they often contain camera photos too
-            debug!("Scanning DCIM/{}...", child.name);
-            let path = format!("DCIM/{}", child.name);
-            scan_android_folder_recursive(content, &child, &path, config, photos, progress)?;
+            let path = format!("{}/{}", dcim_path, child.name);
+            debug!("Scanning {}...", path);
+            scan_android_folder_recursive(
+                content, &child, &path, config, photos, progress, retrier, order,
+            )?;
         }
     }
 
@@ -1093,6 +2737,7 @@ fn scan_android_dcim(
 }
 
 /// Recursively scan an Android folder for photos
+#[allow(clippy::too_many_arguments)]
 fn scan_android_folder_recursive(
     content: &DeviceContent,
     folder: &DeviceObject,
@@ -1100,13 +2745,20 @@ fn scan_android_folder_recursive(
     config: &AndroidConfig,
     photos: &mut Vec<PhotoInfo>,
     progress: &Option<ScanProgress>,
+    retrier: &Retrier,
+    order: ScanOrder,
 ) -> Result<()> {
     if let Some(ref p) = progress {
         p.increment_folders();
     }
 
-    let children = match content.enumerate_children(&folder.object_id) {
-        Ok(c) => c,
+    let children = match retrier.run("Enumerate folder", || {
+        content.enumerate_children(&folder.object_id)
+    }) {
+        Ok(mut c) => {
+            order.sort(&mut c);
+            c
+        }
         Err(e) => {
             warn!("Failed to enumerate '{}': {}", path, e);
             return Ok(());
@@ -1122,7 +2774,16 @@ fn scan_android_folder_recursive(
             }
 
             let child_path = format!("{}/{}", path, child.name);
-            scan_android_folder_recursive(content, &child, &child_path, config, photos, progress)?;
+            scan_android_folder_recursive(
+                content,
+                &child,
+                &child_path,
+                config,
+                photos,
+                progress,
+                retrier,
+                order,
+            )?;
         } else if is_media_file(&child.name) {
             photos.push(PhotoInfo {
                 object_id: child.object_id.clone(),
@@ -1140,74 +2801,6 @@ fn scan_android_folder_recursive(
     Ok(())
 }
 
-/// Recursively scan a folder for photos
-fn scan_folder_recursive(
-    content: &DeviceContent,
-    parent: &DeviceObject,
-    path_prefix: &str,
-    photos: &mut Vec<PhotoInfo>,
-    progress: &ScanProgress,
-) -> Result<()> {
-    debug!(
-        "Scanning folder: {} (id: {})",
-        path_prefix, parent.object_id
-    );
-
-    progress.increment_folders();
-
-    let children = match content.enumerate_children(&parent.object_id) {
-        Ok(c) => c,
-        Err(e) => {
-            warn!("Failed to enumerate '{}': {}", path_prefix, e);
-            return Ok(());
-        }
-    };
-
-    debug!("Found {} items in '{}'", children.len(), path_prefix);
-
-    let mut media_count = 0;
-    for child in children {
-        let child_path = if path_prefix.is_empty() {
-            child.name.clone()
-        } else {
-            format!("{}/{}", path_prefix, child.name)
-        };
-
-        if child.is_folder {
-            trace!("Recursing into folder: {}", child_path);
-            // Recurse into subfolders
-            scan_folder_recursive(content, &child, &child_path, photos, progress)?;
-        } else if is_media_file(&child.name) {
-            trace!("Found media file: {}", child_path);
-            photos.push(PhotoInfo {
-                object_id: child.object_id.clone(),
-                name: child.name.clone(),
-                path: child_path,
-                size: child.size,
-                date_modified: child.date_modified.clone(),
-            });
-            media_count += 1;
-        }
-    }
-
-    if media_count > 0 {
-        progress.add_files(media_count);
-    }
-
-    Ok(())
-}
-
-/// Check if a file is a photo or video based on extension
-fn is_media_file(name: &str) -> bool {
-    let extension = Path::new(name)
-        .extension()
-        .and_then(|e| e.to_str())
-        .map(|e| e.to_lowercase())
-        .unwrap_or_default();
-
-    PHOTO_EXTENSIONS.contains(&extension.as_str()) || VIDEO_EXTENSIONS.contains(&extension.as_str())
-}
-
 /// Result of extracting a single photo
 enum ExtractResult {
     /// Photo was extracted successfully, with the number of bytes
@@ -1222,67 +2815,406 @@ enum ExtractResult {
     DuplicateRenamed(u64),
 }
 
+/// Outcome of extracting a single photo
+struct ExtractedPhoto {
+    result: ExtractResult,
+    /// Where the photo was (or would be) written
+    path: PathBuf,
+    /// SHA256 of the content written, when it was written
+    hash: Option<Sha256Hash>,
+    /// Existing file whose content the photo matched
+    duplicate_of: Option<PathBuf>,
+    /// Whether `path` was renamed because a different file had the name
+    renamed_on_collision: bool,
+    /// The planned file name, if it was shortened to fit Windows' path limit
+    truncated_from: Option<String>,
+}
+
+impl ExtractedPhoto {
+    fn new(result: ExtractResult, path: PathBuf) -> Self {
+        Self {
+            result,
+            path,
+            hash: None,
+            duplicate_of: None,
+            renamed_on_collision: false,
+            truncated_from: None,
+        }
+    }
+
+    fn with_duplicate_of(mut self, path: Option<PathBuf>) -> Self {
+        self.duplicate_of = path;
+        self
+    }
+}
+
+/// Output path for a photo under `template`, given its capture date
+fn planned_output_path(
+    config: &ExtractionConfig,
+    template: &PathTemplate,
+    device_name: &str,
+    photo: &PhotoInfo,
+    date: Option<NaiveDate>,
+) -> PathBuf {
+    let path = config.output_dir.join(template.resolve(&TemplateFile {
+        device: device_name,
+        path: &photo.path,
+        name: &photo.name,
+        date,
+    }));
+    group_burst(config, path)
+}
+
+/// Move a burst frame's output path into its burst folder, when grouping
+fn group_burst(config: &ExtractionConfig, path: PathBuf) -> PathBuf {
+    if config.group_bursts {
+        burst_grouped_path(&path).unwrap_or(path)
+    } else {
+        path
+    }
+}
+
 /// Extract a single photo from the device
+///
+/// `dest_dir` overrides the computed destination folder (used to keep Live
+/// Photo pairs together). With an `archive` the file becomes an entry named
+/// after its output path instead of being written to disk. `written_paths`
+/// holds the output paths already used by other photos in this extraction.
+/// With an `output_index` (skip-existing by hash) a photo is skipped when its
+/// content is already in the output, not when its name is taken. `reverify`
+/// verifies the copy even without `verify_after_copy`, for a photo whose
+/// earlier attempt was cut off by the device dropping. A destination beyond
+/// Windows' path limit is handled according to `long_path_strategy` first.
+#[allow(clippy::too_many_arguments)]
 fn extract_single_photo(
     content: &DeviceContent,
     photo: &PhotoInfo,
     config: &ExtractionConfig,
+    template: &PathTemplate,
+    device_name: &str,
     hash_index: &Option<DuplicateIndex>,
-) -> Result<ExtractResult> {
+    output_index: Option<&DuplicateIndex>,
+    dest_dir: Option<&Path>,
+    written_paths: &WrittenPaths,
+    reverify: bool,
+    throttle: &Throttle,
+    shutdown_flag: &AtomicBool,
+    archive: Option<&mut ZipDestination>,
+    on_bytes: &mut dyn FnMut(u64),
+) -> Result<ExtractedPhoto> {
+    // The capture date lives inside the file, so a dated layout needs the
+    // content before the output path is known. A copy already filed under the
+    // device modified date settles it without the read
+    let dated = template.uses_date() && dest_dir.is_none();
+    if dated && config.skip_existing && output_index.is_none() {
+        let modified = photo.date_modified.as_deref().and_then(parse_device_date);
+        if let Some(existing) = existing_dated_copy(
+            planned_output_path(config, template, device_name, photo, modified),
+            photo.size,
+            &config.output_dir,
+            config.long_path_strategy,
+            written_paths,
+            archive.as_deref(),
+        ) {
+            debug!("Skipping existing file: {}", existing.display());
+            return Ok(ExtractedPhoto::new(ExtractResult::Skipped, existing));
+        }
+    }
+    let prefetched = if dated {
+        Some(read_throttled(content, &photo.object_id, throttle)?)
+    } else {
+        None
+    };
+
     // Determine output path
-    let output_path = if config.preserve_structure {
-        config.output_dir.join(&photo.path)
+    let output_path = if let Some(dir) = dest_dir {
+        dir.join(normalize_name(&photo.name))
+    } else {
+        let date = prefetched.as_deref().and_then(|data| {
+            metadata::resolve_capture_date(&photo.name, data, photo.date_modified.as_deref())
+                .map(|(date, _)| date)
+        });
+        planned_output_path(config, template, device_name, photo, date)
+    };
+
+    // Archive entry names have no length limit
+    let (output_path, truncated_from) = if archive.is_some() {
+        (output_path, None)
     } else {
-        config.output_dir.join(&photo.name)
+        let fitted = fit_path(output_path, config.long_path_strategy);
+        (fitted.path, fitted.truncated_from)
     };
+    if let Some(ref original) = truncated_from {
+        warn!(
+            "Destination for '{}' is too long, writing it as '{}'",
+            original,
+            output_path.display()
+        );
+    }
+
+    let extracted = extract_photo_to(
+        content,
+        photo,
+        config,
+        hash_index,
+        output_index,
+        written_paths,
+        output_path,
+        prefetched,
+        reverify,
+        throttle,
+        shutdown_flag,
+        archive,
+        on_bytes,
+    )?;
+    Ok(ExtractedPhoto {
+        truncated_from,
+        ..extracted
+    })
+}
 
-    // Check if file exists and skip if configured
-    if config.skip_existing && output_path.exists() {
-        // Also check file size matches
-        if let Ok(metadata) = fs::metadata(&output_path) {
-            if metadata.len() == photo.size || photo.size == 0 {
-                debug!("Skipping existing file: {}", output_path.display());
-                return Ok(ExtractResult::Skipped);
+/// Extract a single photo to `output_path`, once [`extract_single_photo`]
+/// has settled it
+///
+/// `prefetched` is the photo's content, if it was already read to find the
+/// capture date.
+#[allow(clippy::too_many_arguments)]
+fn extract_photo_to(
+    content: &DeviceContent,
+    photo: &PhotoInfo,
+    config: &ExtractionConfig,
+    hash_index: &Option<DuplicateIndex>,
+    output_index: Option<&DuplicateIndex>,
+    written_paths: &WrittenPaths,
+    mut output_path: PathBuf,
+    prefetched: Option<Vec<u8>>,
+    reverify: bool,
+    throttle: &Throttle,
+    shutdown_flag: &AtomicBool,
+    archive: Option<&mut ZipDestination>,
+    on_bytes: &mut dyn FnMut(u64),
+) -> Result<ExtractedPhoto> {
+    // Check if file exists and skip if configured. A file already on disk
+    // under another case or normalization form is the same file, so from here
+    // on its own spelling is used
+    let mut colliding = false;
+    if let Some(ref zip) = archive {
+        if config.skip_existing
+            && zip.contains(&entry_name(&config.output_dir, &output_path), photo.size)
+        {
+            debug!("Skipping existing archive entry: {}", output_path.display());
+            return Ok(ExtractedPhoto::new(ExtractResult::Skipped, output_path));
+        }
+    } else {
+        let existing = find_existing(&output_path);
+        if written_paths.contains(&output_path) {
+            // Another device file with the same name was written this run
+            colliding = true;
+        } else if let Some(ref existing) = existing {
+            // Also check file size matches; by content, the name alone proves nothing
+            if config.skip_existing && output_index.is_none() {
+                if let Ok(metadata) = fs::metadata(existing) {
+                    if metadata.len() == photo.size || photo.size == 0 {
+                        debug!("Skipping existing file: {}", existing.display());
+                        return Ok(ExtractedPhoto::new(
+                            ExtractResult::Skipped,
+                            existing.clone(),
+                        ));
+                    }
+                }
             }
+            colliding = true;
+        }
+        if let Some(existing) = existing {
+            output_path = existing;
+        }
+    }
+
+    // A dry run only needs the content for duplicate and name clash checks
+    if config.dry_run && hash_index.is_none() && output_index.is_none() && !colliding {
+        let bytes = prefetched.map_or(photo.size, |data| data.len() as u64);
+        trace!("Dry run: would extract {} ({} bytes)", photo.name, bytes);
+        return Ok(ExtractedPhoto::new(
+            ExtractResult::Extracted(bytes),
+            output_path,
+        ));
+    }
+
+    // Read file from device, unless nothing needs it before it is written.
+    // Skip-existing by hash checks a streamed file once it is written
+    let stream_to_disk = prefetched.is_none()
+        && !colliding
+        && hash_index.is_none()
+        && (output_index.is_none() || !config.dry_run);
+    let data = match prefetched {
+        Some(data) => Some(data),
+        None if stream_to_disk => None,
+        None => Some(read_throttled(content, &photo.object_id, throttle)?),
+    };
+    let bytes = data.as_ref().map_or(photo.size, |data| data.len() as u64);
+
+    // Skip content that is already somewhere in the output, whatever its name
+    if let (Some(index), Some(data)) = (output_index, data.as_deref()) {
+        if let Some(existing) = index.find_duplicate_with_size(data, bytes) {
+            debug!(
+                "Skipping {}, already extracted as {}",
+                photo.name,
+                existing.display()
+            );
+            return Ok(ExtractedPhoto::new(
+                ExtractResult::Skipped,
+                existing.to_path_buf(),
+            ));
         }
     }
 
-    // Read file from device
-    let data = read_file_from_device(content, &photo.object_id)?;
-    let bytes = data.len() as u64;
+    let strategy = config
+        .duplicate_detection
+        .as_ref()
+        .map(|d| d.duplicate_action)
+        .unwrap_or_default();
+
+    // Never overwrite a different file that happens to share the name
+    let mut overwriting = None;
+    let mut renamed_from = None;
+    let mut renamed_on_collision = false;
+    if let (true, Some(data)) = (colliding, data.as_deref()) {
+        match detect_collision(&output_path, data) {
+            Collision::DifferentContent => {
+                let renamed = generate_unique_path(&output_path);
+                debug!(
+                    "'{}' is taken by a different file, writing '{}'",
+                    output_path.display(),
+                    renamed.display()
+                );
+                output_path = renamed;
+                renamed_on_collision = true;
+            }
+            Collision::SameContent => {
+                let incoming = DuplicateCandidate::incoming(data, photo.date_modified.as_deref());
+                let existing = DuplicateCandidate::existing(&output_path).unwrap_or_default();
+                match resolve_duplicate(strategy, &incoming, &existing) {
+                    DuplicateResolution::Skip => {
+                        debug!("Skipping identical file: {}", output_path.display());
+                        return Ok(ExtractedPhoto::new(
+                            ExtractResult::Duplicate(output_path.clone()),
+                            output_path.clone(),
+                        )
+                        .with_duplicate_of(Some(output_path)));
+                    }
+                    DuplicateResolution::Overwrite => overwriting = Some(output_path.clone()),
+                    DuplicateResolution::Rename => {
+                        let renamed = generate_unique_path(&output_path);
+                        renamed_from = Some(std::mem::replace(&mut output_path, renamed));
+                    }
+                }
+            }
+        }
+    }
 
     // Check for duplicates using SHA256 hash
-    if let Some(ref index) = hash_index {
-        if let Some(duplicate_path) = index.find_duplicate_with_size(&data, bytes) {
-            // Determine action based on config
-            let action = config
-                .duplicate_detection
-                .as_ref()
-                .map(|d| d.duplicate_action.clone())
-                .unwrap_or(DuplicateAction::Skip);
-
-            match action {
-                DuplicateAction::Skip => {
+    let index = hash_index
+        .as_ref()
+        .filter(|_| overwriting.is_none() && renamed_from.is_none());
+    if let (Some(index), Some(data)) = (index, data.as_deref()) {
+        if let Some(duplicate_path) = index.find_duplicate_with_size(data, bytes) {
+            let incoming = DuplicateCandidate::incoming(data, photo.date_modified.as_deref());
+            // An indexed file that can no longer be read has no size or date to beat
+            let existing = DuplicateCandidate::existing(duplicate_path).unwrap_or_default();
+            let resolution = resolve_duplicate(strategy, &incoming, &existing);
+
+            match resolution {
+                DuplicateResolution::Skip => {
                     debug!(
                         "Skipping duplicate of {}: {}",
                         duplicate_path.display(),
                         photo.name
                     );
-                    return Ok(ExtractResult::Duplicate(duplicate_path.to_path_buf()));
+                    let duplicate = duplicate_path.to_path_buf();
+                    return Ok(ExtractedPhoto::new(
+                        ExtractResult::Duplicate(duplicate.clone()),
+                        duplicate.clone(),
+                    )
+                    .with_duplicate_of(Some(duplicate)));
                 }
-                DuplicateAction::Overwrite => {
+                DuplicateResolution::Overwrite => {
                     // Continue with extraction, will overwrite
-                    debug!("Overwriting duplicate: {}", output_path.display());
+                    debug!(
+                        "Overwriting duplicate ({}): {}",
+                        strategy.as_str(),
+                        output_path.display()
+                    );
+                    overwriting = Some(duplicate_path.to_path_buf());
                 }
-                DuplicateAction::Rename => {
-                    // Generate a unique filename
-                    let new_path = generate_unique_path(&output_path);
-                    return extract_to_path(&new_path, &data, true, photo.date_modified.as_deref());
+                DuplicateResolution::Rename => {
+                    // Written below like any other file, under a unique name
+                    output_path = match archive {
+                        Some(ref zip) => config
+                            .output_dir
+                            .join(zip.unique_name(&entry_name(&config.output_dir, &output_path))),
+                        None => generate_unique_path(&output_path),
+                    };
+                    renamed_from = Some(duplicate_path.to_path_buf());
                 }
             }
         }
     }
 
+    let extracted = |bytes| {
+        if overwriting.is_some() {
+            ExtractResult::DuplicateOverwritten(bytes)
+        } else if renamed_from.is_some() {
+            ExtractResult::DuplicateRenamed(bytes)
+        } else {
+            ExtractResult::Extracted(bytes)
+        }
+    };
+    let duplicate_of = overwriting.clone().or_else(|| renamed_from.clone());
+
+    if config.dry_run {
+        trace!("Dry run: would extract {} ({} bytes)", photo.name, bytes);
+        return Ok(ExtractedPhoto {
+            renamed_on_collision,
+            ..ExtractedPhoto::new(extracted(bytes), output_path).with_duplicate_of(duplicate_of)
+        });
+    }
+
+    if let Some(zip) = archive {
+        // An entry of the same name (from an earlier run, or another device
+        // file of this one) is kept and this one stored under a new name
+        let wanted = entry_name(&config.output_dir, &output_path);
+        // Content read up front was charged to the throttle already
+        let unthrottled = Throttle::unlimited();
+        let (name, copy) = zip.write_entry(
+            &wanted,
+            bytes,
+            photo.date_modified.as_deref(),
+            if data.is_some() {
+                &unthrottled
+            } else {
+                throttle
+            },
+            shutdown_flag,
+            on_bytes,
+            |sink| match data {
+                Some(data) => sink(&data),
+                None => content.read_file_chunked(&photo.object_id, sink),
+            },
+        )?;
+        debug!("Archived: {} ({} bytes)", name, copy.bytes);
+        if name != wanted {
+            output_path = config.output_dir.join(&name);
+        }
+        return Ok(ExtractedPhoto {
+            result: extracted(copy.bytes),
+            path: output_path,
+            hash: Some(copy.hash),
+            duplicate_of,
+            renamed_on_collision: renamed_on_collision || name != wanted,
+            truncated_from: None,
+        });
+    }
+
     // Ensure parent directory exists
     if let Some(parent) = output_path.parent() {
         fs::create_dir_all(parent).map_err(|e| {
@@ -1294,25 +3226,57 @@ fn extract_single_photo(
         })?;
     }
 
-    // Write to output file
-    let mut file = File::create(&output_path).map_err(|e| {
-        ExtractionError::IoError(format!(
-            "Failed to create file '{}': {}",
-            output_path.display(),
-            e
-        ))
-    })?;
-
-    file.write_all(&data).map_err(|e| {
-        ExtractionError::IoError(format!(
-            "Failed to write file '{}': {}",
-            output_path.display(),
-            e
-        ))
-    })?;
+    // A file streamed under skip-existing by hash is hashed as it is written,
+    // and given up before it is moved into place if the output has it already
+    let mut streamed = None;
+    if let (Some(index), None) = (output_index, data.as_ref()) {
+        let partial = partial_path(&output_path);
+        let copy = copy_streamed(
+            content,
+            &photo.object_id,
+            &partial,
+            throttle,
+            shutdown_flag,
+            on_bytes,
+        )?;
+        if let Some(existing) = index.find_duplicate_by_hash(&copy.hash) {
+            if let Err(e) = fs::remove_file(&partial) {
+                warn!("Could not remove '{}': {}", partial.display(), e);
+            }
+            debug!(
+                "Skipping {}, already extracted as {}",
+                photo.name,
+                existing.display()
+            );
+            return Ok(ExtractedPhoto::new(
+                ExtractResult::Skipped,
+                existing.to_path_buf(),
+            ));
+        }
+        streamed = Some(copy);
+    }
 
-    // Ensure file handle is closed before setting timestamps
-    drop(file);
+    // Write to output file, streaming a fresh copy if verification fails
+    let mut data = data;
+    let CopyResult { bytes, hash } = write_verified(
+        &output_path,
+        &photo.name,
+        photo.size,
+        config.verify_after_copy || reverify,
+        config.verify_retries,
+        |path| match (streamed.take(), data.take()) {
+            (Some(copy), _) => Ok(copy),
+            (None, Some(data)) => write_hashed(path, &data, shutdown_flag, on_bytes),
+            (None, None) => copy_streamed(
+                content,
+                &photo.object_id,
+                path,
+                throttle,
+                shutdown_flag,
+                on_bytes,
+            ),
+        },
+    )?;
 
     // Preserve file timestamps from device metadata
     if let Some(ref date_str) = photo.date_modified {
@@ -1327,7 +3291,14 @@ fn extract_single_photo(
 
     debug!("Extracted: {} ({} bytes)", output_path.display(), bytes);
 
-    Ok(ExtractResult::Extracted(bytes))
+    Ok(ExtractedPhoto {
+        result: extracted(bytes),
+        path: output_path,
+        hash: Some(hash),
+        duplicate_of,
+        renamed_on_collision,
+        truncated_from: None,
+    })
 }
 
 /// Set file modification timestamp from ISO 8601 date string
@@ -1390,7 +3361,7 @@ fn set_file_timestamp(path: &Path, date_str: &str) -> Result<()> {
 }
 
 /// Generate a unique path by adding a numeric suffix
-fn generate_unique_path(original_path: &Path) -> PathBuf {
+pub(crate) fn generate_unique_path(original_path: &Path) -> PathBuf {
     let stem = original_path
         .file_stem()
         .and_then(|s| s.to_str())
@@ -1409,138 +3380,13 @@ fn generate_unique_path(original_path: &Path) -> PathBuf {
             format!("{}_{}.{}", stem, counter, extension)
         };
         let new_path = parent.join(new_name);
-        if !new_path.exists() {
+        if find_existing(&new_path).is_none() {
             return new_path;
         }
         counter += 1;
     }
 }
 
-/// Extract data to a specific path
-fn extract_to_path(
-    output_path: &Path,
-    data: &[u8],
-    is_renamed: bool,
-    date_modified: Option<&str>,
-) -> Result<ExtractResult> {
-    let bytes = data.len() as u64;
-
-    // Ensure parent directory exists
-    if let Some(parent) = output_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| {
-            ExtractionError::IoError(format!(
-                "Failed to create directory '{}': {}",
-                parent.display(),
-                e
-            ))
-        })?;
-    }
-
-    // Write to output file
-    let mut file = File::create(output_path).map_err(|e| {
-        ExtractionError::IoError(format!(
-            "Failed to create file '{}': {}",
-            output_path.display(),
-            e
-        ))
-    })?;
-
-    file.write_all(data).map_err(|e| {
-        ExtractionError::IoError(format!(
-            "Failed to write file '{}': {}",
-            output_path.display(),
-            e
-        ))
-    })?;
-
-    // Ensure file handle is closed before setting timestamps
-    drop(file);
-
-    // Preserve file timestamps from device metadata
-    if let Some(date_str) = date_modified {
-        if let Err(e) = set_file_timestamp(output_path, date_str) {
-            debug!(
-                "Could not set timestamp for '{}': {}",
-                output_path.display(),
-                e
-            );
-        }
-    }
-
-    debug!("Extracted: {} ({} bytes)", output_path.display(), bytes);
-
-    if is_renamed {
-        Ok(ExtractResult::DuplicateRenamed(bytes))
-    } else {
-        Ok(ExtractResult::DuplicateOverwritten(bytes))
-    }
-}
-
-/// Read a file from the device using WPD resources API
-fn read_file_from_device(content: &DeviceContent, object_id: &str) -> Result<Vec<u8>> {
-    unsafe {
-        // Get the resources interface
-        let resources = content.inner().Transfer().map_err(|e| {
-            ExtractionError::ContentError(format!("Failed to get transfer interface: {}", e))
-        })?;
-
-        // Convert object ID to wide string
-        let object_id_wide: Vec<u16> = object_id.encode_utf16().chain(std::iter::once(0)).collect();
-
-        // Get the stream for reading - STGM_READ = 0
-        let mut optimal_buffer_size: u32 = 0;
-        let mut stream_opt: Option<IStream> = None;
-
-        resources
-            .GetStream(
-                PCWSTR(object_id_wide.as_ptr()),
-                &WPD_RESOURCE_DEFAULT,
-                0, // STGM_READ
-                &mut optimal_buffer_size,
-                &mut stream_opt,
-            )
-            .map_err(|e| {
-                ExtractionError::ContentError(format!("Failed to get file stream: {}", e))
-            })?;
-
-        let stream = stream_opt.ok_or_else(|| {
-            ExtractionError::ContentError("Failed to get stream: stream is None".to_string())
-        })?;
-
-        // Use a reasonable buffer size
-        let buffer_size = if optimal_buffer_size > 0 && optimal_buffer_size <= 1048576 {
-            optimal_buffer_size as usize
-        } else {
-            262144 // 256KB default
-        };
-
-        let mut data = Vec::new();
-        let mut buffer = vec![0u8; buffer_size];
-
-        loop {
-            let mut bytes_read: u32 = 0;
-            let result = stream.Read(
-                buffer.as_mut_ptr() as *mut _,
-                buffer_size as u32,
-                Some(&mut bytes_read),
-            );
-
-            if bytes_read == 0 {
-                break;
-            }
-
-            data.extend_from_slice(&buffer[..bytes_read as usize]);
-
-            // Check if we've read all data or encountered an error
-            if result.is_err() || bytes_read < buffer_size as u32 {
-                break;
-            }
-        }
-
-        Ok(data)
-    }
-}
-
 impl std::fmt::Display for ExtractionStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let size_mb = self.total_bytes as f64 / 1_048_576.0;
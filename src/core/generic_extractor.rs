@@ -31,17 +31,46 @@
 
 #![allow(unused)]
 
+use crate::core::archive::{entry_name, Destination, ZipDestination};
+use crate::core::checkpoint::{CheckpointTracker, DEFAULT_CHECKPOINT_INTERVAL};
+use crate::core::config::{DuplicateStrategy, LongPathStrategy, SkipExistingMode};
+use crate::core::discovery::{discover_folders, DiscoveryRoot, ScanOrder};
 use crate::core::error::{ExtractionError, Result};
-use crate::device::traits::{DeviceContentTrait, DeviceInfo, DeviceObject};
+use crate::core::extractor::{
+    available_space, build_output_index, check_free_space, copy_streamed, date_in_range,
+    detect_collision, existing_dated_copy, finish_archive, finish_duplicate_report,
+    finish_manifest, finish_report, generate_unique_path, open_run_log, order_live_photo_pairs,
+    parse_device_date, partial_path, read_throttled, record_manifest_entry, remove_partial_files,
+    will_be_written, write_hashed, write_verified, Collision,
+};
+use crate::core::long_paths::fit_path;
+use crate::core::manifest::{ManifestEntry, ManifestFormat, ManifestOutcome, ManifestWriter};
+use crate::core::media::{self, MediaFilter, PathFilter, StatsByType};
+use crate::core::metadata;
+use crate::core::names::{find_existing, normalize_name, WrittenPaths};
+use crate::core::plan::{classify, ExtractionPlan, PlannedFile};
+use crate::core::report::HtmlReport;
+use crate::core::retry::{wait_for_device, Retrier, RetryPolicy, MAX_RECONNECTS_PER_FILE};
+use crate::core::template::{PathTemplate, TemplateFile};
+use crate::core::throttle::Throttle;
+use crate::core::throughput::ThroughputEstimator;
+use crate::core::tracking::TrackingStoreTrait;
+use crate::device::traits::{DeviceContentTrait, DeviceInfo, DeviceManagerTrait, DeviceObject};
+use crate::duplicate::{
+    compute_data_hash, resolve_duplicate, DuplicateCandidate, DuplicateIndex, DuplicateReport,
+    DuplicateReportEntry, DuplicateResolution, DuplicateStore, MatchType, Sha256Hash,
+};
+use chrono::NaiveDate;
 use log::{debug, info, trace, warn};
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::Write;
 use std::os::windows::fs::OpenOptionsExt;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 // =============================================================================
 // Configuration
@@ -51,33 +80,159 @@ use std::time::Instant;
 pub struct GenericExtractionConfig {
     /// Output directory for extracted files
     pub output_dir: PathBuf,
+    /// Where extracted files are written (`Zip` entries are named relative to `output_dir`)
+    pub destination: Destination,
     /// Only extract from DCIM folder
     pub dcim_only: bool,
     /// Preserve folder structure from device
     pub preserve_structure: bool,
+    /// Sort files into YYYY/MM folders by capture date (overrides `preserve_structure`)
+    pub organize_by_date: bool,
+    /// Layout of output paths (None = built from `preserve_structure` and
+    /// `organize_by_date`)
+    pub path_template: Option<PathTemplate>,
+    /// Device name used for `{device}` in the path template (None = looked up
+    /// by `extract_from_device`, else "Unknown Device")
+    pub device_name: Option<String>,
+    /// Write Live Photo videos next to their still image
+    pub preserve_live_photo_pairs: bool,
+    /// Put the frames of each iPhone burst into their own `BURST<id>` folder
+    pub group_bursts: bool,
+    /// Re-read and checksum each file after writing it
+    pub verify_after_copy: bool,
+    /// Number of times to re-copy a file that fails verification
+    pub verify_retries: usize,
+    /// Delete each file from the device once its copy is verified and recorded
+    /// (ignored unless `verify_after_copy` is set)
+    pub delete_after_extract: bool,
+    /// Free space (in MB) to leave on the output drive after extraction
+    pub space_safety_margin_mb: u64,
+    /// Maximum retries for transient device read/enumeration errors
+    pub max_retries: usize,
+    /// Delay before the first retry in milliseconds (doubled each retry)
+    pub retry_base_delay_ms: u64,
+    /// Seconds to wait for a device that drops mid-extraction to come back
+    /// (0 = don't wait; only used by `extract_from_device`)
+    pub reconnect_timeout_secs: u64,
+    /// Cap on bytes read from the device per second (None = unlimited)
+    pub max_bytes_per_sec: Option<u64>,
+    /// Number of folders listed at once while scanning (1 = serial walk)
+    pub scan_threads: usize,
+    /// Order each folder's children are walked in
+    pub scan_order: ScanOrder,
+    /// Where to write a manifest of every processed file (None = no manifest)
+    pub manifest_path: Option<PathBuf>,
+    /// Format of the manifest file
+    pub manifest_format: ManifestFormat,
+    /// Cumulative JSON Lines log every run appends its files to (None = no log)
+    pub append_to_log: Option<PathBuf>,
+    /// Where to write an HTML report of the extracted files (None = no report)
+    pub report_html: Option<PathBuf>,
+    /// Index of existing files to check incoming content against (None = no duplicate detection)
+    pub duplicate_index: Option<Arc<DuplicateIndex>>,
+    /// What to do with a file whose content is already in `duplicate_index`
+    pub duplicate_strategy: DuplicateStrategy,
+    /// Where to write a report of every duplicate found (None = no report)
+    pub duplicate_report_path: Option<PathBuf>,
+    /// Format of the duplicate report
+    pub duplicate_report_format: ManifestFormat,
     /// Skip files that already exist
     pub skip_existing: bool,
+    /// How skip-existing recognizes a file that was already extracted
+    pub skip_existing_mode: SkipExistingMode,
+    /// What to do with a destination path beyond Windows' 260 character limit
+    pub long_path_strategy: LongPathStrategy,
     /// Write files to disk (false for dry-run/testing)
     pub write_files: bool,
     /// Maximum number of files to extract (0 = unlimited)
     pub max_files: usize,
+    /// Only extract files dated on or after this day (None = no lower bound)
+    pub date_from: Option<NaiveDate>,
+    /// Only extract files dated on or before this day (None = no upper bound)
+    pub date_to: Option<NaiveDate>,
+    /// Whether files without a readable date pass the date filter
+    pub include_undated: bool,
+    /// Skip files smaller than this many bytes (None = no minimum)
+    pub min_size_bytes: Option<u64>,
+    /// Skip files larger than this many bytes (None = no maximum)
+    pub max_size_bytes: Option<u64>,
+    /// Whether to extract photos
+    pub include_photos: bool,
+    /// Whether to extract videos
+    pub include_videos: bool,
+    /// Only extract files whose device path matches one of these globs
+    /// (empty = all paths)
+    pub include_globs: Vec<String>,
+    /// Skip files whose device path matches one of these globs
+    /// (takes precedence over `include_globs`)
+    pub exclude_globs: Vec<String>,
+    /// Checkpoint file to resume from and keep up to date (None = no checkpoint)
+    pub checkpoint_path: Option<PathBuf>,
+    /// Number of finished files between checkpoint saves
+    pub checkpoint_interval: usize,
     /// Callback for progress updates
     pub progress_callback: Option<Arc<dyn Fn(ProgressUpdate) + Send + Sync>>,
+    /// Override for querying free space on the output drive (None = query the OS)
+    pub space_probe: Option<SpaceProbe>,
 }
 
+/// Function returning the free bytes available at a path
+pub type SpaceProbe = Arc<dyn Fn(&Path) -> Result<u64> + Send + Sync>;
+
 impl std::fmt::Debug for GenericExtractionConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("GenericExtractionConfig")
             .field("output_dir", &self.output_dir)
+            .field("destination", &self.destination)
             .field("dcim_only", &self.dcim_only)
             .field("preserve_structure", &self.preserve_structure)
+            .field("organize_by_date", &self.organize_by_date)
+            .field("path_template", &self.path_template)
+            .field("device_name", &self.device_name)
+            .field("preserve_live_photo_pairs", &self.preserve_live_photo_pairs)
+            .field("group_bursts", &self.group_bursts)
+            .field("verify_after_copy", &self.verify_after_copy)
+            .field("verify_retries", &self.verify_retries)
+            .field("delete_after_extract", &self.delete_after_extract)
+            .field("space_safety_margin_mb", &self.space_safety_margin_mb)
+            .field("max_retries", &self.max_retries)
+            .field("retry_base_delay_ms", &self.retry_base_delay_ms)
+            .field("reconnect_timeout_secs", &self.reconnect_timeout_secs)
+            .field("max_bytes_per_sec", &self.max_bytes_per_sec)
+            .field("scan_threads", &self.scan_threads)
+            .field("scan_order", &self.scan_order)
+            .field("manifest_path", &self.manifest_path)
+            .field("manifest_format", &self.manifest_format)
+            .field("append_to_log", &self.append_to_log)
+            .field("report_html", &self.report_html)
+            .field(
+                "duplicate_index",
+                &self.duplicate_index.as_ref().map(|index| index.len()),
+            )
+            .field("duplicate_strategy", &self.duplicate_strategy)
+            .field("duplicate_report_path", &self.duplicate_report_path)
+            .field("duplicate_report_format", &self.duplicate_report_format)
             .field("skip_existing", &self.skip_existing)
+            .field("skip_existing_mode", &self.skip_existing_mode)
+            .field("long_path_strategy", &self.long_path_strategy)
             .field("write_files", &self.write_files)
             .field("max_files", &self.max_files)
+            .field("date_from", &self.date_from)
+            .field("date_to", &self.date_to)
+            .field("include_undated", &self.include_undated)
+            .field("min_size_bytes", &self.min_size_bytes)
+            .field("max_size_bytes", &self.max_size_bytes)
+            .field("include_photos", &self.include_photos)
+            .field("include_videos", &self.include_videos)
+            .field("include_globs", &self.include_globs)
+            .field("exclude_globs", &self.exclude_globs)
+            .field("checkpoint_path", &self.checkpoint_path)
+            .field("checkpoint_interval", &self.checkpoint_interval)
             .field(
                 "progress_callback",
                 &self.progress_callback.as_ref().map(|_| "<callback>"),
             )
+            .field("space_probe", &self.space_probe.as_ref().map(|_| "<probe>"))
             .finish()
     }
 }
@@ -86,12 +241,50 @@ impl Clone for GenericExtractionConfig {
     fn clone(&self) -> Self {
         Self {
             output_dir: self.output_dir.clone(),
+            destination: self.destination.clone(),
             dcim_only: self.dcim_only,
             preserve_structure: self.preserve_structure,
+            organize_by_date: self.organize_by_date,
+            path_template: self.path_template.clone(),
+            device_name: self.device_name.clone(),
+            preserve_live_photo_pairs: self.preserve_live_photo_pairs,
+            group_bursts: self.group_bursts,
+            verify_after_copy: self.verify_after_copy,
+            verify_retries: self.verify_retries,
+            delete_after_extract: self.delete_after_extract,
+            space_safety_margin_mb: self.space_safety_margin_mb,
+            max_retries: self.max_retries,
+            retry_base_delay_ms: self.retry_base_delay_ms,
+            reconnect_timeout_secs: self.reconnect_timeout_secs,
+            max_bytes_per_sec: self.max_bytes_per_sec,
+            scan_threads: self.scan_threads,
+            scan_order: self.scan_order,
+            manifest_path: self.manifest_path.clone(),
+            manifest_format: self.manifest_format,
+            append_to_log: self.append_to_log.clone(),
+            report_html: self.report_html.clone(),
+            duplicate_index: self.duplicate_index.clone(),
+            duplicate_strategy: self.duplicate_strategy,
+            duplicate_report_path: self.duplicate_report_path.clone(),
+            duplicate_report_format: self.duplicate_report_format,
             skip_existing: self.skip_existing,
+            skip_existing_mode: self.skip_existing_mode,
+            long_path_strategy: self.long_path_strategy,
             write_files: self.write_files,
             max_files: self.max_files,
+            date_from: self.date_from,
+            date_to: self.date_to,
+            include_undated: self.include_undated,
+            min_size_bytes: self.min_size_bytes,
+            max_size_bytes: self.max_size_bytes,
+            include_photos: self.include_photos,
+            include_videos: self.include_videos,
+            include_globs: self.include_globs.clone(),
+            exclude_globs: self.exclude_globs.clone(),
+            checkpoint_path: self.checkpoint_path.clone(),
+            checkpoint_interval: self.checkpoint_interval,
             progress_callback: self.progress_callback.clone(),
+            space_probe: self.space_probe.clone(),
         }
     }
 }
@@ -100,12 +293,50 @@ impl Default for GenericExtractionConfig {
     fn default() -> Self {
         Self {
             output_dir: PathBuf::from("./extracted_photos"),
+            destination: Destination::Directory(PathBuf::from("./extracted_photos")),
             dcim_only: true,
             preserve_structure: true,
+            organize_by_date: false,
+            path_template: None,
+            device_name: None,
+            preserve_live_photo_pairs: true,
+            group_bursts: false,
+            verify_after_copy: false,
+            verify_retries: 2,
+            delete_after_extract: false,
+            space_safety_margin_mb: 100,
+            max_retries: 3,
+            retry_base_delay_ms: 250,
+            reconnect_timeout_secs: 30,
+            max_bytes_per_sec: None,
+            scan_threads: 1,
+            scan_order: ScanOrder::default(),
+            manifest_path: None,
+            manifest_format: ManifestFormat::Json,
+            append_to_log: None,
+            report_html: None,
+            duplicate_index: None,
+            duplicate_strategy: DuplicateStrategy::Skip,
+            duplicate_report_path: None,
+            duplicate_report_format: ManifestFormat::Json,
             skip_existing: true,
+            skip_existing_mode: SkipExistingMode::ByName,
+            long_path_strategy: LongPathStrategy::ExtendedPrefix,
             write_files: true,
             max_files: 0,
+            date_from: None,
+            date_to: None,
+            include_undated: true,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            include_photos: true,
+            include_videos: true,
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            checkpoint_path: None,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
             progress_callback: None,
+            space_probe: None,
         }
     }
 }
@@ -115,6 +346,7 @@ impl GenericExtractionConfig {
     pub fn with_output_dir<P: AsRef<Path>>(output_dir: P) -> Self {
         Self {
             output_dir: output_dir.as_ref().to_path_buf(),
+            destination: Destination::Directory(output_dir.as_ref().to_path_buf()),
             ..Default::default()
         }
     }
@@ -123,7 +355,9 @@ impl GenericExtractionConfig {
     pub fn for_testing() -> Self {
         Self {
             output_dir: PathBuf::from("/dev/null"),
+            destination: Destination::Directory(PathBuf::from("/dev/null")),
             write_files: false,
+            retry_base_delay_ms: 0,
             ..Default::default()
         }
     }
@@ -140,24 +374,267 @@ impl GenericExtractionConfig {
         self
     }
 
+    /// Set organize-by-date mode
+    pub fn organize_by_date(mut self, value: bool) -> Self {
+        self.organize_by_date = value;
+        self
+    }
+
+    /// Lay out output paths with `template` instead of the boolean options
+    pub fn path_template(mut self, template: PathTemplate) -> Self {
+        self.path_template = Some(template);
+        self
+    }
+
+    /// Set the device name used for `{device}` in the path template
+    pub fn device_name(mut self, name: impl Into<String>) -> Self {
+        self.device_name = Some(name.into());
+        self
+    }
+
+    /// Layout of output paths: `path_template`, or the one the boolean
+    /// options describe
+    pub fn output_template(&self) -> Cow<'_, PathTemplate> {
+        match self.path_template {
+            Some(ref template) => Cow::Borrowed(template),
+            None => Cow::Owned(PathTemplate::from_options(
+                self.preserve_structure,
+                self.organize_by_date,
+                false,
+            )),
+        }
+    }
+
+    /// Set whether Live Photo pairs are kept together
+    pub fn preserve_live_photo_pairs(mut self, value: bool) -> Self {
+        self.preserve_live_photo_pairs = value;
+        self
+    }
+
+    /// Set whether burst frames are grouped into one folder per burst
+    pub fn group_bursts(mut self, value: bool) -> Self {
+        self.group_bursts = value;
+        self
+    }
+
+    /// Enable checksum verification with the given number of retries
+    pub fn verify_after_copy(mut self, value: bool, retries: usize) -> Self {
+        self.verify_after_copy = value;
+        self.verify_retries = retries;
+        self
+    }
+
+    /// Delete files from the device after a verified copy
+    pub fn delete_after_extract(mut self, value: bool) -> Self {
+        self.delete_after_extract = value;
+        self
+    }
+
     /// Set skip existing mode
     pub fn skip_existing(mut self, value: bool) -> Self {
         self.skip_existing = value;
         self
     }
 
+    /// Set how skip-existing recognizes a file that was already extracted
+    pub fn skip_existing_mode(mut self, mode: SkipExistingMode) -> Self {
+        self.skip_existing_mode = mode;
+        self
+    }
+
+    /// Set what to do with a destination path beyond Windows' path limit
+    pub fn long_path_strategy(mut self, strategy: LongPathStrategy) -> Self {
+        self.long_path_strategy = strategy;
+        self
+    }
+
     /// Set write files mode
     pub fn write_files(mut self, value: bool) -> Self {
         self.write_files = value;
         self
     }
 
+    /// Set dry-run mode (plan everything, write nothing)
+    pub fn dry_run(mut self, value: bool) -> Self {
+        self.write_files = !value;
+        self
+    }
+
     /// Set max files limit
     pub fn max_files(mut self, value: usize) -> Self {
         self.max_files = value;
         self
     }
 
+    /// Set the inclusive date window for extracted files
+    pub fn date_range(mut self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Self {
+        self.date_from = from;
+        self.date_to = to;
+        self
+    }
+
+    /// Set whether undated files pass the date filter
+    pub fn include_undated(mut self, value: bool) -> Self {
+        self.include_undated = value;
+        self
+    }
+
+    /// Set the inclusive size band for extracted files, in bytes
+    pub fn size_range(mut self, min: Option<u64>, max: Option<u64>) -> Self {
+        self.min_size_bytes = min;
+        self.max_size_bytes = max;
+        self
+    }
+
+    /// Set which kinds of media are extracted
+    pub fn media_kinds(mut self, photos: bool, videos: bool) -> Self {
+        self.include_photos = photos;
+        self.include_videos = videos;
+        self
+    }
+
+    /// Size band and media kinds to extract
+    pub fn media_filter(&self) -> MediaFilter {
+        MediaFilter {
+            include_photos: self.include_photos,
+            include_videos: self.include_videos,
+            min_size_bytes: self.min_size_bytes,
+            max_size_bytes: self.max_size_bytes,
+        }
+    }
+
+    /// Set the device path globs to include and exclude
+    ///
+    /// See [`PathFilter`] for how patterns are matched.
+    pub fn path_globs<I, E>(mut self, include: I, exclude: E) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+        E: IntoIterator,
+        E::Item: Into<String>,
+    {
+        self.include_globs = include.into_iter().map(Into::into).collect();
+        self.exclude_globs = exclude.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Compiled include/exclude path globs
+    pub fn path_filter(&self) -> Result<PathFilter> {
+        PathFilter::new(&self.include_globs, &self.exclude_globs)
+    }
+
+    /// Resume from and keep a checkpoint at `path`, saving every `interval` files
+    ///
+    /// See [`Checkpoint::default_path`](crate::core::checkpoint::Checkpoint::default_path)
+    /// for the usual location next to the output.
+    pub fn checkpoint<P: AsRef<Path>>(mut self, path: P, interval: usize) -> Self {
+        self.checkpoint_path = Some(path.as_ref().to_path_buf());
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// Set the retry count and base backoff delay for transient device errors
+    pub fn retries(mut self, max_retries: usize, base_delay_ms: u64) -> Self {
+        self.max_retries = max_retries;
+        self.retry_base_delay_ms = base_delay_ms;
+        self
+    }
+
+    /// Set how long to wait for a dropped device to come back (0 = don't wait)
+    pub fn reconnect_timeout_secs(mut self, value: u64) -> Self {
+        self.reconnect_timeout_secs = value;
+        self
+    }
+
+    /// Cap the rate at which file content is written (None = unlimited)
+    pub fn max_bytes_per_sec(mut self, value: Option<u64>) -> Self {
+        self.max_bytes_per_sec = value;
+        self
+    }
+
+    /// List up to `threads` folders at once while scanning
+    ///
+    /// The files found, and the order they are extracted in, are the same as
+    /// with a serial walk.
+    pub fn scan_threads(mut self, threads: usize) -> Self {
+        self.scan_threads = threads;
+        self
+    }
+
+    /// Walk each folder's children in `order`
+    pub fn scan_order(mut self, order: ScanOrder) -> Self {
+        self.scan_order = order;
+        self
+    }
+
+    /// Set where extracted files are written
+    ///
+    /// A `Directory` destination also becomes the output directory; a `Zip`
+    /// destination keeps it as the root that entry names are relative to.
+    pub fn destination(mut self, destination: Destination) -> Self {
+        if let Destination::Directory(ref dir) = destination {
+            self.output_dir = dir.clone();
+        }
+        self.destination = destination;
+        self
+    }
+
+    /// Write a manifest of every processed file to `path`
+    pub fn manifest<P: AsRef<Path>>(mut self, path: P, format: ManifestFormat) -> Self {
+        self.manifest_path = Some(path.as_ref().to_path_buf());
+        self.manifest_format = format;
+        self
+    }
+
+    /// Append a line for every processed file to the run log at `path`
+    ///
+    /// Unlike the manifest, the log is never truncated: each run adds its
+    /// files under a new run ID.
+    pub fn append_to_log<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.append_to_log = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Write an HTML report of the extracted files, grouped by date, to `path`
+    /// once the extraction finishes
+    pub fn report_html<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.report_html = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Check incoming files against `index`, handling matches with `strategy`
+    pub fn duplicate_detection(
+        mut self,
+        index: DuplicateIndex,
+        strategy: DuplicateStrategy,
+    ) -> Self {
+        self.duplicate_index = Some(Arc::new(index));
+        self.duplicate_strategy = strategy;
+        self
+    }
+
+    /// Write a report of every duplicate found to `path`
+    pub fn duplicate_report<P: AsRef<Path>>(mut self, path: P, format: ManifestFormat) -> Self {
+        self.duplicate_report_path = Some(path.as_ref().to_path_buf());
+        self.duplicate_report_format = format;
+        self
+    }
+
+    /// Set the free space (in MB) to leave on the output drive
+    pub fn space_safety_margin_mb(mut self, value: u64) -> Self {
+        self.space_safety_margin_mb = value;
+        self
+    }
+
+    /// Override how free space on the output drive is queried
+    pub fn with_space_probe<F>(mut self, probe: F) -> Self
+    where
+        F: Fn(&Path) -> Result<u64> + Send + Sync + 'static,
+    {
+        self.space_probe = Some(Arc::new(probe));
+        self
+    }
+
     /// Set progress callback
     pub fn with_progress<F>(mut self, callback: F) -> Self
     where
@@ -198,6 +675,61 @@ pub enum ExtractionPhase {
     Complete,
 }
 
+// =============================================================================
+// Progress sink
+// =============================================================================
+
+/// How one file ended, as reported to [`ProgressSink::on_file_done`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileOutcome {
+    /// Copied (or, in a dry run, would be copied)
+    Extracted { bytes: u64 },
+    /// Already present at the destination or extracted before
+    Skipped,
+    /// Matched a file that already exists elsewhere
+    Duplicate,
+    /// Read and hashed without being copied (a duplicate scan of a folder)
+    Hashed { bytes: u64 },
+    /// Shutdown was requested part way through the copy
+    Cancelled,
+    /// Failed after any retries; holds the error message
+    Failed(String),
+}
+
+/// Receiver for extraction progress events
+///
+/// Unlike the [`ProgressUpdate`] callback, a sink sees each file start and
+/// finish and the bytes written in between, which is what a progress bar
+/// needs. Every method does nothing by default.
+pub trait ProgressSink {
+    /// A file is about to be copied; `index` is 1-based
+    fn on_file_start(&mut self, _name: &str, _index: usize, _total: usize) {}
+
+    /// `copied` of the current file's `total` bytes have been written
+    ///
+    /// `total` is the size reported by the device, which can be 0 if unknown.
+    fn on_bytes(&mut self, _copied: u64, _total: u64) {}
+
+    /// Transfer speed averaged over the last few seconds, and the time left
+    /// to copy the remaining files at that speed
+    ///
+    /// Sent along with [`on_bytes`](Self::on_bytes) once enough has been
+    /// copied to estimate a speed.
+    fn on_throughput(&mut self, _bytes_per_sec: f64, _eta: Option<Duration>) {}
+
+    /// The file started last has been handled
+    fn on_file_done(&mut self, _outcome: &FileOutcome) {}
+
+    /// The extraction has finished, including after a shutdown request
+    fn on_finished(&mut self, _stats: &ExtractionStats) {}
+}
+
+/// Sink that ignores all progress events
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullSink;
+
+impl ProgressSink for NullSink {}
+
 // =============================================================================
 // Extraction statistics
 // =============================================================================
@@ -219,6 +751,26 @@ pub struct ExtractionStats {
     pub folders_scanned: usize,
     /// Total files found on device
     pub files_found: usize,
+    /// Number of files excluded by the date filter
+    pub files_excluded_by_date: usize,
+    /// Number of files left out by the size or media kind filter
+    pub files_filtered: usize,
+    /// Number of Live Photo videos written next to their still image
+    pub live_photos_paired: usize,
+    /// Number of burst frames written into a burst folder
+    pub burst_frames_grouped: usize,
+    /// Number of files that still failed verification after all retries
+    pub verification_failures: usize,
+    /// Number of retries made after transient device errors
+    pub retries: usize,
+    /// Number of times the device was reopened after dropping mid-extraction
+    pub reconnects: usize,
+    /// Number of files deleted from the device after a verified copy
+    pub deleted_from_device: usize,
+    /// Number of files given a new name because a different file had theirs
+    pub renamed_on_collision: usize,
+    /// Extracted files and bytes per extension and per photo or video
+    pub by_type: StatsByType,
     /// Time taken in milliseconds
     pub duration_ms: u64,
 }
@@ -285,6 +837,16 @@ pub struct GenericExtractor {
     shutdown_flag: Arc<AtomicBool>,
     /// Set of already extracted file IDs (for resume support)
     extracted_ids: HashSet<String>,
+    /// Duplicates found by the last extraction
+    duplicate_report: DuplicateReport,
+    /// Folders a checkpoint marked as done, skipped while scanning
+    completed_folders: HashSet<String>,
+    /// Output paths written (or planned, in a dry run) by the current extraction
+    written_paths: WrittenPaths,
+    /// Content written by the current extraction, checked with `duplicate_index`
+    extracted_hashes: DuplicateStore,
+    /// Files already in the output when skipping existing files by hash
+    output_index: Option<DuplicateIndex>,
 }
 
 impl GenericExtractor {
@@ -294,6 +856,11 @@ impl GenericExtractor {
             config,
             shutdown_flag: Arc::new(AtomicBool::new(false)),
             extracted_ids: HashSet::new(),
+            duplicate_report: DuplicateReport::new(),
+            completed_folders: HashSet::new(),
+            written_paths: WrittenPaths::new(),
+            extracted_hashes: DuplicateStore::new(),
+            output_index: None,
         }
     }
 
@@ -303,6 +870,11 @@ impl GenericExtractor {
             config,
             shutdown_flag: flag,
             extracted_ids: HashSet::new(),
+            duplicate_report: DuplicateReport::new(),
+            completed_folders: HashSet::new(),
+            written_paths: WrittenPaths::new(),
+            extracted_hashes: DuplicateStore::new(),
+            output_index: None,
         }
     }
 
@@ -322,15 +894,74 @@ impl GenericExtractor {
     }
 
     /// Extract photos from a device content interface
-    pub fn extract_from_content<C: DeviceContentTrait>(
+    pub fn extract_from_content<C: DeviceContentTrait + Sync>(
+        &mut self,
+        content: &C,
+    ) -> Result<ExtractionStats> {
+        self.extract_with_sink(content, &mut NullSink)
+    }
+
+    /// Extract photos, reporting per-file progress to `sink`
+    ///
+    /// [`ProgressSink::on_finished`] is called once whenever the extraction
+    /// returns successfully.
+    pub fn extract_with_sink<C: DeviceContentTrait + Sync>(
+        &mut self,
+        content: &C,
+        sink: &mut dyn ProgressSink,
+    ) -> Result<ExtractionStats> {
+        let stats = self.run_extraction(content, None, sink)?;
+        sink.on_finished(&stats);
+        Ok(stats)
+    }
+
+    /// Open `device_id` through `manager` and extract from it, reporting to `sink`
+    ///
+    /// If the device drops part way through, this waits up to
+    /// `reconnect_timeout_secs` for it to come back, reopens it and carries on
+    /// with the file that was being copied. That file is copied again from the
+    /// start and verified, whatever `verify_after_copy` says.
+    pub fn extract_from_device<M: DeviceManagerTrait>(
+        &mut self,
+        manager: &M,
+        device_id: &str,
+        sink: &mut dyn ProgressSink,
+    ) -> Result<ExtractionStats> {
+        let content = manager.open_device(device_id)?;
+        if self.config.device_name.is_none() {
+            self.config.device_name = manager
+                .get_device_info(device_id)
+                .map(|info| info.friendly_name);
+        }
+        let timeout = Duration::from_secs(self.config.reconnect_timeout_secs);
+        let shutdown_flag = Arc::clone(&self.shutdown_flag);
+        let reopen = || wait_for_device(manager, device_id, timeout, &shutdown_flag);
+        let reopen: Option<&dyn Fn() -> Result<M::Content>> =
+            (!timeout.is_zero()).then_some(&reopen);
+
+        let stats = self.run_extraction(&content, reopen, sink)?;
+        sink.on_finished(&stats);
+        Ok(stats)
+    }
+
+    /// Run an extraction from `content`, reopening the device with `reopen`
+    /// (when given) if it drops part way through
+    fn run_extraction<C: DeviceContentTrait + Sync>(
         &mut self,
         content: &C,
+        mut reopen: Option<&dyn Fn() -> Result<C>>,
+        sink: &mut dyn ProgressSink,
     ) -> Result<ExtractionStats> {
         let start_time = Instant::now();
         let mut stats = ExtractionStats::default();
+        let path_filter = self.config.path_filter()?;
+        self.duplicate_report = DuplicateReport::new();
+        self.written_paths.clear();
+        self.extracted_hashes.clear();
 
-        // Create output directory if writing files
-        if self.config.write_files {
+        // Create output directory if writing files, clearing out files an
+        // interrupted run was writing
+        if self.config.write_files && !self.config.destination.is_zip() {
             fs::create_dir_all(&self.config.output_dir).map_err(|e| {
                 ExtractionError::IoError(format!(
                     "Failed to create output directory '{}': {}",
@@ -338,6 +969,7 @@ impl GenericExtractor {
                     e
                 ))
             })?;
+            remove_partial_files(&self.config.output_dir);
         }
 
         info!("Scanning device for media files...");
@@ -349,10 +981,75 @@ impl GenericExtractor {
             phase: ExtractionPhase::Scanning,
         });
 
+        let retrier = Retrier::new(
+            RetryPolicy::new(self.config.max_retries, self.config.retry_base_delay_ms),
+            Arc::clone(&self.shutdown_flag),
+        );
+        let throttle = Throttle::new(
+            self.config.max_bytes_per_sec,
+            Arc::clone(&self.shutdown_flag),
+        );
+
+        // Pick up where an interrupted run left off
+        let mut checkpoint = match self.config.checkpoint_path {
+            Some(ref path) if self.config.write_files => Some(CheckpointTracker::open(
+                path,
+                self.config.checkpoint_interval,
+            )),
+            _ => None,
+        };
+        if let Some(ref tracker) = checkpoint {
+            let resumed = tracker.checkpoint();
+            self.extracted_ids.extend(resumed.completed.iter().cloned());
+            self.completed_folders = resumed.completed_folders.iter().cloned().collect();
+        }
+
         // Find all media files
-        let files = self.find_media_files(content, &mut stats)?;
+        let files = self.find_media_files(content, &mut stats, &retrier);
+        self.completed_folders.clear();
+        let mut files = files?;
         stats.files_found = files.len();
 
+        // Drop files outside the configured date window
+        if self.config.date_from.is_some() || self.config.date_to.is_some() {
+            files.retain(|file| {
+                date_in_range(
+                    file.date_modified.as_deref(),
+                    self.config.date_from,
+                    self.config.date_to,
+                    self.config.include_undated,
+                )
+            });
+            stats.files_excluded_by_date = stats.files_found - files.len();
+            debug!(
+                "Date filter excluded {} files",
+                stats.files_excluded_by_date
+            );
+        }
+
+        // Drop files outside the configured size band, of excluded kinds or on
+        // excluded paths
+        let media_filter = self.config.media_filter();
+        if media_filter.is_active() || path_filter.is_active() {
+            let before = files.len();
+            files.retain(|file| {
+                let exclusion = media_filter
+                    .exclusion(&file.name, file.size)
+                    .or_else(|| path_filter.exclusion(&file.path));
+                match exclusion {
+                    Some(reason) => {
+                        debug!(
+                            "Filtered out {} ({} bytes): {}",
+                            file.path, file.size, reason
+                        );
+                        false
+                    }
+                    None => true,
+                }
+            });
+            stats.files_filtered = before - files.len();
+        }
+
         if files.is_empty() {
             warn!("No media files found on device");
             stats.duration_ms = start_time.elapsed().as_millis() as u64;
@@ -361,6 +1058,29 @@ impl GenericExtractor {
 
         info!("Found {} media files to extract", files.len());
 
+        // Index what is already in the output so skip-existing can match content
+        self.output_index = if self.config.skip_existing
+            && self.config.skip_existing_mode == SkipExistingMode::ByHash
+            && !self.config.destination.is_zip()
+        {
+            build_output_index(
+                &self.config.output_dir,
+                !self.config.write_files,
+                Arc::clone(&self.shutdown_flag),
+            )
+        } else {
+            None
+        };
+
+        // Keep Live Photo stills and their motion clips together
+        let (files, live_photo_videos) = if self.config.preserve_live_photo_pairs {
+            order_live_photo_pairs(files, |f| f.path.as_str(), |f| f.object_id.as_str())
+        } else {
+            (files, HashMap::new())
+        };
+        let live_photo_images: HashSet<&String> = live_photo_videos.values().collect();
+        let mut live_photo_dirs: HashMap<String, PathBuf> = HashMap::new();
+
         // Extract each file
         let total = if self.config.max_files > 0 {
             files.len().min(self.config.max_files)
@@ -368,6 +1088,67 @@ impl GenericExtractor {
             files.len()
         };
 
+        let mut archive = match self.config.destination {
+            Destination::Zip(ref path) if self.config.write_files => {
+                if self.config.verify_after_copy {
+                    warn!("verify_after_copy is not supported for ZIP archives; copies are not verified");
+                }
+                Some(ZipDestination::open(path)?)
+            }
+            _ => None,
+        };
+
+        // Make sure the output drive can hold everything before copying anything
+        if self.config.write_files {
+            self.check_free_space(&files[..total], archive.as_ref())?;
+        }
+
+        let mut manifest = match self.config.manifest_path {
+            Some(ref path) => Some(ManifestWriter::create(path, self.config.manifest_format)?),
+            None => None,
+        };
+        let mut run_log = open_run_log(
+            self.config.append_to_log.as_deref(),
+            self.config.write_files,
+        )?;
+        let mut report = self
+            .config
+            .report_html
+            .as_deref()
+            .map(|path| HtmlReport::new(path, self.config.device_name.as_deref()));
+
+        // A folder is only complete once every file found in it is done,
+        // including those past `max_files`
+        if let Some(ref mut tracker) = checkpoint {
+            for file in &files {
+                tracker.add_pending(&file.path);
+            }
+            tracker.begin_run(total);
+        }
+
+        // Only delete from the device once a copy is verified and recorded
+        let delete_after_extract = self.config.delete_after_extract
+            && self.config.write_files
+            && self.config.verify_after_copy
+            && archive.is_none();
+        if self.config.delete_after_extract && !self.config.verify_after_copy {
+            warn!("delete_after_extract requires verify_after_copy; nothing will be deleted");
+        } else if self.config.delete_after_extract && archive.is_some() {
+            warn!(
+                "delete_after_extract is not supported for ZIP archives; nothing will be deleted"
+            );
+        }
+
+        // Replaces `content` once the device has been reopened
+        let mut reopened: Option<C> = None;
+
+        // Bytes left is the size of every file not yet handled, whether it
+        // ends up copied, skipped or failed
+        let planned_bytes: u64 = files[..total].iter().map(|file| file.size).sum();
+        let mut handled_bytes = 0u64;
+        let mut transferred = 0u64;
+        let mut throughput = ThroughputEstimator::default();
+
         for (index, file) in files.iter().take(total).enumerate() {
             // Check for shutdown
             if self.is_shutdown_requested() {
@@ -377,7 +1158,26 @@ impl GenericExtractor {
 
             // Check if already extracted (for resume)
             if self.extracted_ids.contains(&file.object_id) {
+                handled_bytes += file.size;
+                sink.on_file_start(&file.name, index + 1, total);
+                sink.on_file_done(&FileOutcome::Skipped);
+                Self::record_checkpoint(checkpoint.as_mut(), file);
                 stats.files_skipped += 1;
+                if manifest.is_some() || run_log.is_some() || report.is_some() {
+                    let entry = ManifestEntry::new(
+                        &file.object_id,
+                        &file.path,
+                        file.size,
+                        ManifestOutcome::Skipped,
+                    )
+                    .with_burst(media::BurstFrame::from_name(&file.name).as_ref());
+                    record_manifest_entry(
+                        manifest.as_mut(),
+                        run_log.as_mut(),
+                        report.as_mut(),
+                        &entry,
+                    );
+                }
                 continue;
             }
 
@@ -390,12 +1190,131 @@ impl GenericExtractor {
                 phase: ExtractionPhase::Extracting,
             });
 
-            // Extract the file
-            match self.extract_single_file(content, file) {
+            // Live Photo videos follow their image into the same folder
+            let paired_dir = live_photo_videos
+                .get(&file.object_id)
+                .and_then(|image_id| live_photo_dirs.get(image_id))
+                .cloned();
+
+            // Extract the file, starting it again if the device drops and comes back
+            sink.on_file_start(&file.name, index + 1, total);
+            let mut copied_this_file = 0;
+            let mut reconnects = 0;
+            let result = loop {
+                let current = reopened.as_ref().unwrap_or(content);
+                let result = retrier.run(&file.name, || {
+                    self.extract_single_file(
+                        current,
+                        file,
+                        paired_dir.as_deref(),
+                        reconnects > 0,
+                        &throttle,
+                        archive.as_mut(),
+                        &mut |copied| {
+                            sink.on_bytes(copied, file.size);
+                            // A retry starts counting the file from zero again
+                            transferred += copied.checked_sub(copied_this_file).unwrap_or(copied);
+                            copied_this_file = copied;
+                            throughput.record(transferred);
+                            if let Some(rate) = throughput.bytes_per_sec() {
+                                let remaining =
+                                    planned_bytes.saturating_sub(handled_bytes + copied);
+                                sink.on_throughput(rate, throughput.eta(remaining));
+                            }
+                        },
+                    )
+                });
+                let Some(reopen_device) = reopen else {
+                    break result;
+                };
+                if !matches!(result, Err(ExtractionError::DeviceDisconnected))
+                    || reconnects == MAX_RECONNECTS_PER_FILE
+                {
+                    break result;
+                }
+
+                warn!(
+                    "Device disconnected while copying {}; waiting for it to come back",
+                    file.name
+                );
+                match reopen_device() {
+                    Ok(device) => {
+                        reopened = Some(device);
+                        reconnects += 1;
+                        stats.reconnects += 1;
+                    }
+                    Err(e) => {
+                        // Don't wait again for every remaining file
+                        reopen = None;
+                        break Err(e);
+                    }
+                }
+            };
+            handled_bytes += file.size;
+            sink.on_file_done(&match result {
+                Ok(ref extracted) => match extracted.result {
+                    ExtractResult::Extracted(bytes) => FileOutcome::Extracted { bytes },
+                    ExtractResult::Skipped => FileOutcome::Skipped,
+                    ExtractResult::Duplicate(_) => FileOutcome::Duplicate,
+                },
+                Err(ExtractionError::Cancelled) => FileOutcome::Cancelled,
+                Err(ref e) => FileOutcome::Failed(e.to_string()),
+            });
+            if matches!(result, Err(ExtractionError::Cancelled)) {
+                info!(
+                    "Extraction cancelled by user; discarded partial copy of {}",
+                    file.name
+                );
+                break;
+            }
+            if result.is_ok() {
+                Self::record_checkpoint(checkpoint.as_mut(), file);
+            }
+            if manifest.is_some() || run_log.is_some() || report.is_some() {
+                let entry = self.manifest_entry(file, &result);
+                record_manifest_entry(manifest.as_mut(), run_log.as_mut(), report.as_mut(), &entry);
+            }
+            if let Ok(ref extracted) = result {
+                if let ExtractResult::Extracted(_) = extracted.result {
+                    self.written_paths.insert(&extracted.path);
+                }
+                if extracted.renamed_on_collision {
+                    stats.renamed_on_collision += 1;
+                }
+                if paired_dir.is_some() {
+                    stats.live_photos_paired += 1;
+                } else if live_photo_images.contains(&file.object_id) {
+                    if let Some(parent) = extracted.path.parent() {
+                        live_photo_dirs.insert(file.object_id.clone(), parent.to_path_buf());
+                    }
+                }
+                if paired_dir.is_none()
+                    && self.config.group_bursts
+                    && media::BurstFrame::from_name(&file.name).is_some()
+                {
+                    stats.burst_frames_grouped += 1;
+                }
+                if let Some(entry) = Self::duplicate_report_entry(file, extracted) {
+                    self.duplicate_report.record(entry);
+                }
+            }
+
+            match result.map(|extracted| extracted.result) {
                 Ok(ExtractResult::Extracted(bytes)) => {
                     stats.files_extracted += 1;
                     stats.bytes_processed += bytes;
+                    stats.by_type.record(&file.name, bytes);
                     self.extracted_ids.insert(file.object_id.clone());
+                    if delete_after_extract {
+                        let current = reopened.as_ref().unwrap_or(content);
+                        match current.delete_object(&file.object_id) {
+                            Ok(()) => {
+                                debug!("Deleted from device: {}", file.path);
+                                stats.deleted_from_device += 1;
+                            }
+                            Err(e) => warn!("Failed to delete '{}' from device: {}", file.path, e),
+                        }
+                    }
                 }
                 Ok(ExtractResult::Skipped) => {
                     stats.files_skipped += 1;
@@ -406,11 +1325,40 @@ impl GenericExtractor {
                 }
                 Err(e) => {
                     debug!("Failed to extract '{}': {}", file.name, e);
+                    if matches!(e, ExtractionError::VerificationFailed { .. }) {
+                        stats.verification_failures += 1;
+                    }
                     stats.errors += 1;
                 }
             }
         }
 
+        // Keep the checkpoint until every file has been handled
+        if let Some(mut tracker) = checkpoint {
+            let finished =
+                !self.is_shutdown_requested() && stats.errors == 0 && total == files.len();
+            let saved = if finished {
+                tracker.finish()
+            } else {
+                tracker.save()
+            };
+            if let Err(e) = saved {
+                warn!("{}", e);
+            }
+        }
+
+        // Also reached on shutdown, so the archive stays readable
+        finish_archive(archive)?;
+        finish_manifest(manifest);
+        finish_report(report);
+        if let Some(ref path) = self.config.duplicate_report_path {
+            finish_duplicate_report(
+                &self.duplicate_report,
+                path,
+                self.config.duplicate_report_format,
+            );
+        }
+        stats.retries = retrier.retries();
         stats.duration_ms = start_time.elapsed().as_millis() as u64;
 
         // Report completion
@@ -426,53 +1374,116 @@ impl GenericExtractor {
         Ok(stats)
     }
 
+    /// Compare the device's media files with `store` without copying anything
+    ///
+    /// The same folder and date filters as an extraction apply. With
+    /// `compare_hashes`, files already in the store are read and re-hashed.
+    pub fn plan<C: DeviceContentTrait + Sync, S: TrackingStoreTrait + ?Sized>(
+        &self,
+        content: &C,
+        store: &S,
+        compare_hashes: bool,
+    ) -> Result<ExtractionPlan> {
+        let retrier = Retrier::new(
+            RetryPolicy::new(self.config.max_retries, self.config.retry_base_delay_ms),
+            Arc::clone(&self.shutdown_flag),
+        );
+        let path_filter = self.config.path_filter()?;
+        let mut stats = ExtractionStats::default();
+        let files = self.find_media_files(content, &mut stats, &retrier)?;
+
+        let media_filter = self.config.media_filter();
+        let mut plan = ExtractionPlan::new();
+        for file in files {
+            if !date_in_range(
+                file.date_modified.as_deref(),
+                self.config.date_from,
+                self.config.date_to,
+                self.config.include_undated,
+            ) || !media_filter.accepts(&file.name, file.size)
+                || !path_filter.accepts(&file.path)
+            {
+                continue;
+            }
+
+            let read = compare_hashes
+                .then_some(|| retrier.run(&file.name, || content.read_file(&file.object_id)));
+            let status = classify(store, &file.object_id, file.size, read)?;
+            plan.add(PlannedFile {
+                object_id: file.object_id,
+                path: file.path,
+                size: file.size,
+                status,
+            });
+        }
+
+        debug!(
+            "Plan: {} new, {} already extracted, {} changed",
+            plan.summary.new.files, plan.summary.already_have.files, plan.summary.changed.files
+        );
+        Ok(plan)
+    }
+
     /// Find all media files on the device
-    fn find_media_files<C: DeviceContentTrait>(
+    ///
+    /// With more than one scan thread the folders below each storage are
+    /// listed concurrently once every storage has been listed; the files found
+    /// and their order are the same either way.
+    fn find_media_files<C: DeviceContentTrait + Sync>(
         &self,
         content: &C,
         stats: &mut ExtractionStats,
+        retrier: &Retrier,
     ) -> Result<Vec<FileInfo>> {
         let mut files = Vec::new();
+        let concurrent = self.config.scan_threads > 1;
+        // Folders left for concurrent discovery, with where their files go
+        let mut roots: Vec<(usize, DiscoveryRoot)> = Vec::new();
 
-        // Get root objects
-        let root_objects = content.enumerate_objects()?;
+        // Each storage (internal, SD card, ...) is scanned on its own
+        let storages = retrier.run("Enumerate storages", || content.enumerate_storages())?;
 
-        if root_objects.is_empty() {
-            warn!("No objects found at device root");
+        if storages.is_empty() {
+            warn!("No storages found on device");
             return Ok(files);
         }
+        debug!("Found {} storage(s) on device", storages.len());
 
-        // Process each root object
-        for root in &root_objects {
-            if !root.is_folder {
-                continue;
-            }
-
-            // Get children of root (typically "Internal Storage")
-            let children = content.enumerate_children(&root.object_id)?;
+        for storage in &storages {
+            let mut children = retrier.run(&storage.description, || {
+                content.enumerate_children(&storage.object_id)
+            })?;
+            self.config.scan_order.sort(&mut children);
 
             for child in children {
                 if self.config.dcim_only {
                     // Only scan DCIM folder
                     if child.is_folder && child.name.to_uppercase() == "DCIM" {
-                        self.scan_folder_recursive(
-                            content,
-                            &child,
-                            &format!("{}/DCIM", root.name),
-                            &mut files,
-                            stats,
-                        )?;
+                        let path = format!("{}/DCIM", storage.description);
+                        if concurrent {
+                            roots.push((files.len(), DiscoveryRoot::new(child.object_id, path)));
+                        } else {
+                            self.scan_folder_recursive(
+                                content, &child, &path, &mut files, stats, retrier,
+                            )?;
+                        }
                     }
                 } else {
                     // Scan all folders
                     if child.is_folder {
-                        let path = format!("{}/{}", root.name, child.name);
-                        self.scan_folder_recursive(content, &child, &path, &mut files, stats)?;
+                        let path = format!("{}/{}", storage.description, child.name);
+                        if concurrent {
+                            roots.push((files.len(), DiscoveryRoot::new(child.object_id, path)));
+                        } else {
+                            self.scan_folder_recursive(
+                                content, &child, &path, &mut files, stats, retrier,
+                            )?;
+                        }
                     } else if Self::is_media_file(&child.name) {
                         files.push(FileInfo {
                             object_id: child.object_id.clone(),
                             name: child.name.clone(),
-                            path: format!("{}/{}", root.name, child.name),
+                            path: format!("{}/{}", storage.description, child.name),
                             size: child.size,
                             date_modified: child.date_modified.clone(),
                         });
@@ -481,9 +1492,77 @@ impl GenericExtractor {
             }
         }
 
+        if !roots.is_empty() {
+            self.discover_media_files(content, roots, &mut files, stats, retrier)?;
+        }
+
         Ok(files)
     }
 
+    /// List `roots` concurrently and insert the media files found below each
+    /// at its position in `files`
+    fn discover_media_files<C: DeviceContentTrait + Sync>(
+        &self,
+        content: &C,
+        roots: Vec<(usize, DiscoveryRoot)>,
+        files: &mut Vec<FileInfo>,
+        stats: &mut ExtractionStats,
+        retrier: &Retrier,
+    ) -> Result<()> {
+        let completed_folders = &self.completed_folders;
+        let skip = |path: &str| {
+            let completed = completed_folders.contains(path);
+            if completed {
+                trace!("Skipping folder completed before: {}", path);
+            }
+            completed
+        };
+        let (positions, roots): (Vec<usize>, Vec<DiscoveryRoot>) = roots
+            .into_iter()
+            .filter(|(_, root)| !skip(&root.path))
+            .unzip();
+
+        let progress_callback = self.config.progress_callback.as_ref();
+        let discovery = discover_folders(
+            content,
+            &roots,
+            self.config.scan_threads,
+            self.config.scan_order,
+            retrier,
+            &self.shutdown_flag,
+            |path, _| !skip(path),
+            |progress| {
+                if let Some(callback) = progress_callback {
+                    callback(ProgressUpdate {
+                        current_file: progress.path.to_string(),
+                        current_index: progress.folders,
+                        total_files: progress.files,
+                        bytes_processed: 0,
+                        phase: ExtractionPhase::Scanning,
+                    });
+                }
+            },
+        )?;
+        stats.folders_scanned += discovery.folders_scanned;
+
+        // Later positions first, so earlier ones stay valid
+        for (position, found) in positions.into_iter().zip(discovery.files).rev() {
+            let media = found
+                .into_iter()
+                .filter(|file| Self::is_media_file(&file.object.name))
+                .map(|file| FileInfo {
+                    object_id: file.object.object_id,
+                    name: file.object.name,
+                    path: file.path,
+                    size: file.object.size,
+                    date_modified: file.object.date_modified,
+                });
+            files.splice(position..position, media);
+        }
+
+        Ok(())
+    }
+
     /// Recursively scan a folder for media files
     fn scan_folder_recursive<C: DeviceContentTrait>(
         &self,
@@ -492,7 +1571,12 @@ impl GenericExtractor {
         path_prefix: &str,
         files: &mut Vec<FileInfo>,
         stats: &mut ExtractionStats,
+        retrier: &Retrier,
     ) -> Result<()> {
+        if self.completed_folders.contains(path_prefix) {
+            trace!("Skipping folder completed before: {}", path_prefix);
+            return Ok(());
+        }
         trace!("Scanning folder: {}", path_prefix);
         stats.folders_scanned += 1;
 
@@ -501,8 +1585,17 @@ impl GenericExtractor {
             return Ok(());
         }
 
-        let children = match content.enumerate_children(&folder.object_id) {
-            Ok(c) => c,
+        let children = match retrier.run(path_prefix, || {
+            content.enumerate_children(&folder.object_id)
+        }) {
+            Ok(mut c) => {
+                self.config.scan_order.sort(&mut c);
+                c
+            }
+            // Nothing else on the device can be read either
+            Err(ExtractionError::DeviceDisconnected) => {
+                return Err(ExtractionError::DeviceDisconnected)
+            }
             Err(e) => {
                 warn!("Failed to enumerate '{}': {}", path_prefix, e);
                 return Ok(());
@@ -514,7 +1607,7 @@ impl GenericExtractor {
 
             if child.is_folder {
                 // Recurse into subfolder
-                self.scan_folder_recursive(content, &child, &child_path, files, stats)?;
+                self.scan_folder_recursive(content, &child, &child_path, files, stats, retrier)?;
             } else if Self::is_media_file(&child.name) {
                 files.push(FileInfo {
                     object_id: child.object_id.clone(),
@@ -531,50 +1624,406 @@ impl GenericExtractor {
 
     /// Check if a file is a media file based on extension
     fn is_media_file(name: &str) -> bool {
-        let photo_extensions = [
-            "jpg", "jpeg", "png", "heic", "heif", "gif", "webp", "raw", "dng", "tiff", "tif", "bmp",
-        ];
-        let video_extensions = ["mov", "mp4", "m4v", "avi", "3gp"];
+        media::is_media_file(name)
+    }
+
+    /// Output path for a file under `template`, given its capture date
+    fn planned_output_path(
+        &self,
+        template: &PathTemplate,
+        file: &FileInfo,
+        date: Option<NaiveDate>,
+    ) -> PathBuf {
+        let path = self.config.output_dir.join(template.resolve(&TemplateFile {
+            device: self.config.device_name.as_deref().unwrap_or_default(),
+            path: &file.path,
+            name: &file.name,
+            date,
+        }));
+        self.group_burst(path)
+    }
+
+    /// Move a burst frame's output path into its burst folder, when grouping
+    fn group_burst(&self, path: PathBuf) -> PathBuf {
+        if self.config.group_bursts {
+            media::burst_grouped_path(&path).unwrap_or(path)
+        } else {
+            path
+        }
+    }
 
-        let extension = Path::new(name)
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.to_lowercase())
-            .unwrap_or_default();
+    /// Fail early if the files that will be written don't fit on the output drive
+    ///
+    /// Files already extracted in a previous session, or that skip-existing
+    /// would skip (on disk or in `archive`), are not counted.
+    fn check_free_space(&self, files: &[FileInfo], archive: Option<&ZipDestination>) -> Result<()> {
+        let template = self.config.output_template();
+        let needed: u64 = files
+            .iter()
+            .filter(|file| !self.extracted_ids.contains(&file.object_id))
+            .filter(|file| {
+                // With a dated layout the destination depends on the file content
+                let path = self.planned_output_path(&template, file, None);
+                template.uses_date()
+                    || match archive {
+                        Some(zip) => {
+                            !self.config.skip_existing
+                                || !zip.contains(
+                                    &entry_name(&self.config.output_dir, &path),
+                                    file.size,
+                                )
+                        }
+                        None => will_be_written(
+                            &fit_path(path, self.config.long_path_strategy).path,
+                            file.size,
+                            self.config.skip_existing && self.output_index.is_none(),
+                        ),
+                    }
+            })
+            .map(|file| file.size)
+            .sum();
+        if needed == 0 {
+            return Ok(());
+        }
 
-        photo_extensions.contains(&extension.as_str())
-            || video_extensions.contains(&extension.as_str())
+        let space_root = match self.config.destination {
+            Destination::Zip(_) => self.config.destination.folder(),
+            Destination::Directory(_) => self.config.output_dir.as_path(),
+        };
+        let available = match self.config.space_probe {
+            Some(ref probe) => probe(space_root),
+            None => available_space(space_root),
+        };
+        match available {
+            Ok(available) => {
+                debug!(
+                    "Free space: {} bytes needed, {} bytes available",
+                    needed, available
+                );
+                check_free_space(needed, available, self.config.space_safety_margin_mb)
+            }
+            Err(e) => {
+                warn!("Skipping free space check: {}", e);
+                Ok(())
+            }
+        }
     }
 
     /// Extract a single file from the device
+    ///
+    /// `dest_dir` overrides the computed destination folder (used to keep Live
+    /// Photo pairs together). With an `archive` the file becomes an entry named
+    /// after its output path. `reverify` verifies the copy even without
+    /// `verify_after_copy`, for a file whose earlier attempt was cut off by
+    /// the device dropping. When skipping existing files by hash, a file is
+    /// skipped if its content is already in the output rather than if its
+    /// name is taken. `on_bytes` receives the running total of bytes
+    /// written. Returns the result along with the output path.
+    ///
+    /// A destination beyond Windows' path limit is handled according to
+    /// `long_path_strategy` before anything is checked or written.
+    #[allow(clippy::too_many_arguments)]
     fn extract_single_file<C: DeviceContentTrait>(
         &self,
         content: &C,
         file: &FileInfo,
-    ) -> Result<ExtractResult> {
+        dest_dir: Option<&Path>,
+        reverify: bool,
+        throttle: &Throttle,
+        archive: Option<&mut ZipDestination>,
+        on_bytes: &mut dyn FnMut(u64),
+    ) -> Result<ExtractedFile> {
+        // The capture date lives inside the file, so a dated layout needs the
+        // content before the output path is known. A copy already filed under
+        // the device modified date settles it without the read
+        let template = self.config.output_template();
+        let dated = template.uses_date() && dest_dir.is_none();
+        if dated && self.config.skip_existing && self.output_index.is_none() {
+            let modified = file.date_modified.as_deref().and_then(parse_device_date);
+            if let Some(existing) = existing_dated_copy(
+                self.planned_output_path(&template, file, modified),
+                file.size,
+                &self.config.output_dir,
+                self.config.long_path_strategy,
+                &self.written_paths,
+                archive.as_deref(),
+            ) {
+                debug!("Skipping existing file: {}", existing.display());
+                return Ok(ExtractedFile::new(ExtractResult::Skipped, existing));
+            }
+        }
+        let prefetched = if dated {
+            Some(read_throttled(content, &file.object_id, throttle)?)
+        } else {
+            None
+        };
+
         // Determine output path
-        let output_path = if self.config.preserve_structure {
-            self.config.output_dir.join(&file.path)
+        let output_path = if let Some(dir) = dest_dir {
+            dir.join(normalize_name(&file.name))
+        } else {
+            let date = prefetched.as_deref().and_then(|data| {
+                metadata::resolve_capture_date(&file.name, data, file.date_modified.as_deref())
+                    .map(|(date, _)| date)
+            });
+            self.planned_output_path(&template, file, date)
+        };
+
+        // Archive entry names have no length limit
+        let (output_path, truncated_from) = if archive.is_some() {
+            (output_path, None)
+        } else {
+            let fitted = fit_path(output_path, self.config.long_path_strategy);
+            (fitted.path, fitted.truncated_from)
+        };
+        if let Some(ref original) = truncated_from {
+            warn!(
+                "Destination for '{}' is too long, writing it as '{}'",
+                original,
+                output_path.display()
+            );
+        }
+
+        let extracted = self.extract_file_to(
+            content,
+            file,
+            output_path,
+            prefetched,
+            reverify,
+            throttle,
+            archive,
+            on_bytes,
+        )?;
+        Ok(ExtractedFile {
+            truncated_from,
+            ..extracted
+        })
+    }
+
+    /// Extract a single file to `output_path`, once
+    /// [`extract_single_file`](Self::extract_single_file) has settled it
+    ///
+    /// `prefetched` is the file content, if it was already read to find the
+    /// capture date.
+    #[allow(clippy::too_many_arguments)]
+    fn extract_file_to<C: DeviceContentTrait>(
+        &self,
+        content: &C,
+        file: &FileInfo,
+        mut output_path: PathBuf,
+        prefetched: Option<Vec<u8>>,
+        reverify: bool,
+        throttle: &Throttle,
+        archive: Option<&mut ZipDestination>,
+        on_bytes: &mut dyn FnMut(u64),
+    ) -> Result<ExtractedFile> {
+        // Check if file exists. A file already on disk under another case or
+        // normalization form is the same file, so from here on its own
+        // spelling is used
+        let mut colliding = false;
+        if let Some(ref zip) = archive {
+            if self.config.skip_existing
+                && zip.contains(
+                    &entry_name(&self.config.output_dir, &output_path),
+                    file.size,
+                )
+            {
+                trace!("Skipping existing archive entry: {}", output_path.display());
+                return Ok(ExtractedFile::new(ExtractResult::Skipped, output_path));
+            }
         } else {
-            self.config.output_dir.join(&file.name)
+            let existing = find_existing(&output_path);
+            if self.written_paths.contains(&output_path) {
+                // Another device file with the same name was written this run
+                colliding = true;
+            } else if let Some(ref existing) = existing {
+                // By content, the name alone proves nothing
+                if self.config.skip_existing && self.output_index.is_none() {
+                    if let Ok(metadata) = fs::metadata(existing) {
+                        if metadata.len() == file.size || file.size == 0 {
+                            trace!("Skipping existing file: {}", existing.display());
+                            return Ok(ExtractedFile::new(
+                                ExtractResult::Skipped,
+                                existing.clone(),
+                            ));
+                        }
+                    }
+                }
+                colliding = true;
+            }
+            if let Some(existing) = existing {
+                output_path = existing;
+            }
+        }
+
+        // Read file content from device, unless nothing needs it before it is
+        // written. Skip-existing by hash checks a streamed file once it is written
+        let stream_to_disk = prefetched.is_none()
+            && !colliding
+            && self.config.duplicate_index.is_none()
+            && (archive.is_some() || self.config.write_files);
+        let data = match prefetched {
+            Some(data) => Some(data),
+            None if stream_to_disk => None,
+            None => Some(read_throttled(content, &file.object_id, throttle)?),
         };
+        let mut bytes = data.as_ref().map_or(file.size, |data| data.len() as u64);
+
+        // Skip content that is already somewhere in the output, whatever its name
+        if let (Some(index), Some(data)) = (self.output_index.as_ref(), data.as_deref()) {
+            if let Some(existing) = index.find_duplicate_with_size(data, bytes) {
+                trace!(
+                    "Skipping {}, already extracted as {}",
+                    file.name,
+                    existing.display()
+                );
+                return Ok(ExtractedFile::new(
+                    ExtractResult::Skipped,
+                    existing.to_path_buf(),
+                ));
+            }
+        }
 
-        // Check if file exists
-        if self.config.skip_existing && output_path.exists() {
-            if let Ok(metadata) = fs::metadata(&output_path) {
-                if metadata.len() == file.size || file.size == 0 {
-                    trace!("Skipping existing file: {}", output_path.display());
-                    return Ok(ExtractResult::Skipped);
+        // Never overwrite a different file that happens to share the name
+        let mut duplicate = None;
+        let mut renamed_on_collision = false;
+        if let (true, Some(data)) = (colliding, data.as_deref()) {
+            match detect_collision(&output_path, data) {
+                Collision::DifferentContent => {
+                    let renamed = generate_unique_path(&output_path);
+                    debug!(
+                        "'{}' is taken by a different file, writing '{}'",
+                        output_path.display(),
+                        renamed.display()
+                    );
+                    output_path = renamed;
+                    renamed_on_collision = true;
+                }
+                Collision::SameContent => {
+                    let incoming =
+                        DuplicateCandidate::incoming(data, file.date_modified.as_deref());
+                    let existing = DuplicateCandidate::existing(&output_path).unwrap_or_default();
+                    let resolution =
+                        resolve_duplicate(self.config.duplicate_strategy, &incoming, &existing);
+                    let matched = output_path.clone();
+                    match resolution {
+                        DuplicateResolution::Skip => {
+                            trace!("Skipping identical file: {}", matched.display());
+                            return Ok(ExtractedFile {
+                                result: ExtractResult::Duplicate(bytes),
+                                path: matched.clone(),
+                                hash: None,
+                                duplicate: Some((matched, resolution)),
+                                renamed_on_collision: false,
+                                truncated_from: None,
+                            });
+                        }
+                        DuplicateResolution::Overwrite => {}
+                        DuplicateResolution::Rename => {
+                            output_path = generate_unique_path(&output_path);
+                        }
+                    }
+                    duplicate = Some((matched, resolution));
                 }
             }
         }
 
-        // Read file content from device
-        let data = content.read_file(&file.object_id)?;
-        let bytes = data.len() as u64;
+        // Check the content against files that already exist elsewhere, then
+        // against files this extraction already wrote. Claiming the hash is
+        // atomic, so of two identical files only one is the original; the
+        // claim is given up again if this file isn't written.
+        let index = self
+            .config
+            .duplicate_index
+            .as_ref()
+            .filter(|_| duplicate.is_none());
+        let mut claim = None;
+        if let (Some(index), Some(data)) = (index, data.as_deref()) {
+            let matched = match index.find_duplicate_with_size(data, bytes) {
+                Some(matched) => Some(matched.to_path_buf()),
+                None => match self
+                    .extracted_hashes
+                    .claim(compute_data_hash(data), &output_path)
+                {
+                    Ok(guard) => {
+                        claim = Some(guard);
+                        None
+                    }
+                    Err(original) => Some(original),
+                },
+            };
+            if let Some(matched) = matched {
+                let incoming = DuplicateCandidate::incoming(data, file.date_modified.as_deref());
+                // An indexed file that can no longer be read has no size or date to beat
+                let existing = DuplicateCandidate::existing(&matched).unwrap_or_default();
+                let resolution =
+                    resolve_duplicate(self.config.duplicate_strategy, &incoming, &existing);
+
+                match resolution {
+                    DuplicateResolution::Skip => {
+                        trace!("Skipping duplicate of {}: {}", matched.display(), file.name);
+                        return Ok(ExtractedFile {
+                            result: ExtractResult::Duplicate(bytes),
+                            path: matched.clone(),
+                            hash: None,
+                            duplicate: Some((matched, resolution)),
+                            renamed_on_collision,
+                            truncated_from: None,
+                        });
+                    }
+                    DuplicateResolution::Overwrite => {
+                        debug!(
+                            "Overwriting duplicate ({}): {}",
+                            self.config.duplicate_strategy.as_str(),
+                            output_path.display()
+                        );
+                    }
+                    DuplicateResolution::Rename => {
+                        output_path = match archive {
+                            Some(ref zip) => self.config.output_dir.join(
+                                zip.unique_name(&entry_name(&self.config.output_dir, &output_path)),
+                            ),
+                            None => generate_unique_path(&output_path),
+                        };
+                    }
+                }
+                duplicate = Some((matched, resolution));
+            }
+        }
 
         // Write to disk if configured
-        if self.config.write_files {
+        let mut hash = None;
+        if let Some(zip) = archive {
+            // An entry of the same name (from an earlier run, or another
+            // device file of this one) is kept and this one stored under a
+            // new name
+            let wanted = entry_name(&self.config.output_dir, &output_path);
+            // Content read up front was charged to the throttle already
+            let unthrottled = Throttle::unlimited();
+            let (name, copy) = zip.write_entry(
+                &wanted,
+                bytes,
+                file.date_modified.as_deref(),
+                if data.is_some() {
+                    &unthrottled
+                } else {
+                    throttle
+                },
+                &self.shutdown_flag,
+                on_bytes,
+                |sink| match data {
+                    Some(data) => sink(&data),
+                    None => content.read_file_chunked(&file.object_id, sink),
+                },
+            )?;
+            debug!("Archived: {} ({} bytes)", name, copy.bytes);
+            if name != wanted {
+                output_path = self.config.output_dir.join(&name);
+                renamed_on_collision = true;
+            }
+            bytes = copy.bytes;
+            hash = Some(copy.hash);
+        } else if self.config.write_files {
             // Ensure parent directory exists
             if let Some(parent) = output_path.parent() {
                 fs::create_dir_all(parent).map_err(|e| {
@@ -586,25 +2035,60 @@ impl GenericExtractor {
                 })?;
             }
 
-            // Write file
-            let mut output_file = File::create(&output_path).map_err(|e| {
-                ExtractionError::IoError(format!(
-                    "Failed to create file '{}': {}",
-                    output_path.display(),
-                    e
-                ))
-            })?;
-
-            output_file.write_all(&data).map_err(|e| {
-                ExtractionError::IoError(format!(
-                    "Failed to write file '{}': {}",
-                    output_path.display(),
-                    e
-                ))
-            })?;
+            // A file streamed under skip-existing by hash is hashed as it is
+            // written, and given up before it is moved into place if the
+            // output has it already
+            let mut streamed = None;
+            if let (Some(index), None) = (self.output_index.as_ref(), data.as_ref()) {
+                let partial = partial_path(&output_path);
+                let copy = copy_streamed(
+                    content,
+                    &file.object_id,
+                    &partial,
+                    throttle,
+                    &self.shutdown_flag,
+                    on_bytes,
+                )?;
+                if let Some(existing) = index.find_duplicate_by_hash(&copy.hash) {
+                    if let Err(e) = fs::remove_file(&partial) {
+                        warn!("Could not remove '{}': {}", partial.display(), e);
+                    }
+                    trace!(
+                        "Skipping {}, already extracted as {}",
+                        file.name,
+                        existing.display()
+                    );
+                    return Ok(ExtractedFile::new(
+                        ExtractResult::Skipped,
+                        existing.to_path_buf(),
+                    ));
+                }
+                streamed = Some(copy);
+            }
 
-            // Ensure file handle is closed before setting timestamps
-            drop(output_file);
+            // Write file, streaming a fresh copy if verification fails
+            let mut data = data;
+            let copy = write_verified(
+                &output_path,
+                &file.name,
+                file.size,
+                self.config.verify_after_copy || reverify,
+                self.config.verify_retries,
+                |path| match (streamed.take(), data.take()) {
+                    (Some(copy), _) => Ok(copy),
+                    (None, Some(data)) => write_hashed(path, &data, &self.shutdown_flag, on_bytes),
+                    (None, None) => copy_streamed(
+                        content,
+                        &file.object_id,
+                        path,
+                        throttle,
+                        &self.shutdown_flag,
+                        on_bytes,
+                    ),
+                },
+            )?;
+            bytes = copy.bytes;
+            hash = Some(copy.hash);
 
             // Preserve file timestamps from device metadata
             if let Some(ref date_str) = file.date_modified {
@@ -622,7 +2106,69 @@ impl GenericExtractor {
             trace!("Dry run: would extract {} ({} bytes)", file.name, bytes);
         }
 
-        Ok(ExtractResult::Extracted(bytes))
+        if let Some(claim) = claim {
+            claim.keep();
+        }
+        Ok(ExtractedFile {
+            result: ExtractResult::Extracted(bytes),
+            path: output_path,
+            hash,
+            duplicate,
+            renamed_on_collision,
+            truncated_from: None,
+        })
+    }
+
+    /// Build the manifest entry describing how a file was handled
+    fn manifest_entry(&self, file: &FileInfo, result: &Result<ExtractedFile>) -> ManifestEntry {
+        let burst = media::BurstFrame::from_name(&file.name);
+        let entry = |outcome| {
+            ManifestEntry::new(&file.object_id, &file.path, file.size, outcome)
+                .with_burst(burst.as_ref())
+        };
+        let extracted = match result {
+            Ok(extracted) => extracted,
+            Err(e) => return entry(ManifestOutcome::Error).with_error(e),
+        };
+        let outcome = match extracted.result {
+            ExtractResult::Extracted(_) => ManifestOutcome::Extracted,
+            ExtractResult::Skipped => ManifestOutcome::Skipped,
+            ExtractResult::Duplicate(_) => ManifestOutcome::Duplicate,
+        };
+
+        // In a dry run this is the planned destination, which doesn't exist yet
+        entry(outcome)
+            .with_hash(extracted.hash.as_ref())
+            .with_destination(&extracted.path, file.date_modified.as_deref())
+            .with_truncated_from(extracted.truncated_from.as_deref())
+    }
+
+    /// Describe a file that matched an existing file, if it did
+    fn duplicate_report_entry(
+        file: &FileInfo,
+        extracted: &ExtractedFile,
+    ) -> Option<DuplicateReportEntry> {
+        let (matched, resolution) = extracted.duplicate.as_ref()?;
+        let entry = DuplicateReportEntry::new(
+            &file.path,
+            matched,
+            file.size,
+            MatchType::Exact,
+            *resolution,
+        );
+        Some(match resolution {
+            DuplicateResolution::Skip => entry,
+            _ => entry.with_destination(&extracted.path),
+        })
+    }
+
+    /// Mark `file` as done in the checkpoint, if one is kept
+    fn record_checkpoint(checkpoint: Option<&mut CheckpointTracker>, file: &FileInfo) {
+        if let Some(tracker) = checkpoint {
+            if let Err(e) = tracker.complete(&file.object_id, &file.path) {
+                warn!("{}", e);
+            }
+        }
     }
 
     /// Report progress to callback if configured
@@ -641,6 +2187,11 @@ impl GenericExtractor {
     pub fn extracted_ids(&self) -> &HashSet<String> {
         &self.extracted_ids
     }
+
+    /// Get the duplicates found by the last extraction
+    pub fn duplicate_report(&self) -> &DuplicateReport {
+        &self.duplicate_report
+    }
 }
 
 /// Result of extracting a single file
@@ -654,6 +2205,35 @@ enum ExtractResult {
     Duplicate(u64),
 }
 
+/// Outcome of extracting a single file
+#[derive(Debug, Clone)]
+struct ExtractedFile {
+    result: ExtractResult,
+    /// Where the file was (or would be) written, or the file it duplicated
+    path: PathBuf,
+    /// SHA256 of the content written, when it was written
+    hash: Option<Sha256Hash>,
+    /// Existing file whose content matched, and how the match was resolved
+    duplicate: Option<(PathBuf, DuplicateResolution)>,
+    /// Whether `path` was renamed because a different file had the name
+    renamed_on_collision: bool,
+    /// The planned file name, if it was shortened to fit Windows' path limit
+    truncated_from: Option<String>,
+}
+
+impl ExtractedFile {
+    fn new(result: ExtractResult, path: PathBuf) -> Self {
+        Self {
+            result,
+            path,
+            hash: None,
+            duplicate: None,
+            renamed_on_collision: false,
+            truncated_from: None,
+        }
+    }
+}
+
 /// Set file modification timestamp from ISO 8601 date string
 fn set_file_timestamp(path: &std::path::Path, date_str: &str) -> Result<()> {
     use chrono::{DateTime, Utc};
@@ -717,14 +2297,14 @@ fn set_file_timestamp(path: &std::path::Path, date_str: &str) -> Result<()> {
 // =============================================================================
 
 /// Extract photos from a device content interface with default configuration
-pub fn extract_photos<C: DeviceContentTrait>(content: &C) -> Result<ExtractionStats> {
+pub fn extract_photos<C: DeviceContentTrait + Sync>(content: &C) -> Result<ExtractionStats> {
     let config = GenericExtractionConfig::for_testing();
     let mut extractor = GenericExtractor::new(config);
     extractor.extract_from_content(content)
 }
 
 /// Extract photos to a specific directory
-pub fn extract_photos_to<C: DeviceContentTrait, P: AsRef<Path>>(
+pub fn extract_photos_to<C: DeviceContentTrait + Sync, P: AsRef<Path>>(
     content: &C,
     output_dir: P,
 ) -> Result<ExtractionStats> {
@@ -734,7 +2314,7 @@ pub fn extract_photos_to<C: DeviceContentTrait, P: AsRef<Path>>(
 }
 
 /// Count media files on a device (dry run)
-pub fn count_media_files<C: DeviceContentTrait>(content: &C) -> Result<usize> {
+pub fn count_media_files<C: DeviceContentTrait + Sync>(content: &C) -> Result<usize> {
     let config = GenericExtractionConfig::for_testing();
     let mut extractor = GenericExtractor::new(config);
     let stats = extractor.extract_from_content(content)?;
@@ -749,7 +2329,8 @@ pub fn count_media_files<C: DeviceContentTrait>(content: &C) -> Result<usize> {
 mod tests {
     use super::*;
     use crate::device::traits::DeviceManagerTrait;
-    use crate::testdb::{MockDeviceInfo, MockDeviceManager, MockFileSystem};
+    use crate::testdb::{MockDeviceInfo, MockDeviceManager, MockFileSystem, MockObject};
+    use tempfile::TempDir;
 
     fn create_test_device() -> MockDeviceManager {
         let mut manager = MockDeviceManager::new();
@@ -762,6 +2343,29 @@ mod tests {
         manager
     }
 
+    #[test]
+    fn test_extracts_from_every_storage() {
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("sd-device", "Galaxy", "Samsung", "SM-A546B");
+        let mut fs = MockFileSystem::new();
+        fs.add_android_dcim_structure(3);
+        fs.add_sd_card_structure(2);
+        manager.add_device(device, fs);
+        let content = manager.open_device("sd-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path());
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_found, 5);
+        assert_eq!(stats.files_extracted, 5);
+        // Same file name on both storages, kept apart by the storage folder
+        let name = "DCIM/Camera/IMG_20240115_120000.jpg";
+        assert!(temp_dir.path().join("Internal Storage").join(name).exists());
+        assert!(temp_dir.path().join("SD Card").join(name).exists());
+    }
+
     #[test]
     fn test_config_defaults() {
         let config = GenericExtractionConfig::default();
@@ -926,4 +2530,1923 @@ mod tests {
         assert_eq!(stats2.files_skipped, stats1.files_extracted);
         assert_eq!(stats2.files_extracted, 0);
     }
+
+    fn create_dated_device() -> MockDeviceManager {
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("dated-device", "Test iPhone", "Apple Inc.", "iPhone 15");
+
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::folder("dcim", "internal", "DCIM"));
+        fs.add_object(MockObject::folder("100apple", "dcim", "100APPLE"));
+
+        let dates = [
+            ("jan", "IMG_0001.JPG", "2024-01-15T10:00:00Z"),
+            ("feb", "IMG_0002.JPG", "2024-02-01T00:00:00Z"),
+            ("mar", "IMG_0003.JPG", "2024-03-31T23:59:59Z"),
+            ("apr", "IMG_0004.JPG", "2024-04-02T08:30:00Z"),
+        ];
+        for (id, name, date) in dates {
+            fs.add_object(MockObject::file_with_date(
+                id,
+                "100apple",
+                name,
+                vec![0xFF, 0xD8, 0xFF, 0xE0],
+                date,
+            ));
+        }
+        fs.add_object(MockObject::file(
+            "undated",
+            "100apple",
+            "IMG_0005.JPG",
+            vec![0xFF, 0xD8, 0xFF, 0xE0],
+        ));
+
+        manager.add_device(device, fs);
+        manager
+    }
+
+    #[test]
+    fn test_parse_device_date_formats() {
+        use crate::core::extractor::parse_device_date;
+
+        let expected = NaiveDate::from_ymd_opt(2024, 3, 15);
+        assert_eq!(parse_device_date("2024-03-15T10:30:00Z"), expected);
+        assert_eq!(parse_device_date("2024-03-15T10:30:00"), expected);
+        assert_eq!(parse_device_date("2024:03:15 10:30:00"), expected);
+        assert_eq!(parse_device_date("2024-03-15"), expected);
+        assert_eq!(parse_device_date("not a date"), None);
+    }
+
+    #[test]
+    fn test_date_range_extracts_only_in_range_files() {
+        let manager = create_dated_device();
+        let content = manager.open_device("dated-device").unwrap();
+
+        let config = GenericExtractionConfig::for_testing()
+            .date_range(
+                NaiveDate::from_ymd_opt(2024, 2, 1),
+                NaiveDate::from_ymd_opt(2024, 3, 31),
+            )
+            .include_undated(false);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_found, 5);
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(stats.files_excluded_by_date, 3);
+
+        let extracted = extractor.extracted_ids();
+        assert!(extracted.contains("feb"));
+        assert!(extracted.contains("mar"));
+        assert!(!extracted.contains("jan"));
+        assert!(!extracted.contains("apr"));
+        assert!(!extracted.contains("undated"));
+    }
+
+    #[test]
+    fn test_date_range_include_undated() {
+        let manager = create_dated_device();
+        let content = manager.open_device("dated-device").unwrap();
+
+        let config = GenericExtractionConfig::for_testing()
+            .date_range(NaiveDate::from_ymd_opt(2024, 3, 1), None)
+            .include_undated(true);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        // March, April and the undated file
+        assert_eq!(stats.files_extracted, 3);
+        assert_eq!(stats.files_excluded_by_date, 2);
+        assert!(extractor.extracted_ids().contains("undated"));
+    }
+
+    #[test]
+    fn test_no_date_range_extracts_everything() {
+        let manager = create_dated_device();
+        let content = manager.open_device("dated-device").unwrap();
+
+        let config = GenericExtractionConfig::for_testing().include_undated(false);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 5);
+        assert_eq!(stats.files_excluded_by_date, 0);
+    }
+
+    #[test]
+    fn test_organize_by_date_prefers_exif_date() {
+        use crate::testdb::MockDataGenerator;
+
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("exif-device", "Test iPhone", "Apple Inc.", "iPhone 15");
+
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::folder("dcim", "internal", "DCIM"));
+        fs.add_object(MockObject::folder("100apple", "dcim", "100APPLE"));
+        // Taken in July 2019 but copied onto the device in February 2024
+        fs.add_object(MockObject::file_with_date(
+            "exif",
+            "100apple",
+            "IMG_0001.JPG",
+            MockDataGenerator::generate_jpeg_with_exif(1024, "2019:07:04 18:30:00", "iPhone 8"),
+            "2024-02-10T08:00:00Z",
+        ));
+        // No EXIF, so the device date is used
+        fs.add_object(MockObject::file_with_date(
+            "plain",
+            "100apple",
+            "IMG_0002.JPG",
+            MockDataGenerator::generate_jpeg_header(1024),
+            "2024-02-10T08:00:00Z",
+        ));
+        manager.add_device(device, fs);
+        let content = manager.open_device("exif-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config =
+            GenericExtractionConfig::with_output_dir(temp_dir.path()).organize_by_date(true);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 2);
+        assert!(temp_dir.path().join("2019/07/IMG_0001.JPG").exists());
+        assert!(temp_dir.path().join("2024/02/IMG_0002.JPG").exists());
+        assert!(!temp_dir.path().join("2024/02/IMG_0001.JPG").exists());
+    }
+
+    fn create_live_photo_device() -> MockDeviceManager {
+        use crate::testdb::MockDataGenerator;
+
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("live-device", "Test iPhone", "Apple Inc.", "iPhone 15");
+
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::folder("dcim", "internal", "DCIM"));
+        fs.add_object(MockObject::folder("100apple", "dcim", "100APPLE"));
+        // Motion clip enumerated before its still, with a different date and
+        // lowercase extension
+        fs.add_object(MockObject::file_with_date(
+            "live-mov",
+            "100apple",
+            "IMG_0001.mov",
+            vec![0x00, 0x00, 0x00, 0x14, b'f', b't', b'y', b'p'],
+            "2024-02-10T08:00:00Z",
+        ));
+        fs.add_object(MockObject::file_with_date(
+            "live-heic",
+            "100apple",
+            "IMG_0001.HEIC",
+            MockDataGenerator::generate_jpeg_with_exif(1024, "2019:07:04 18:30:00", "iPhone 8"),
+            "2024-02-10T08:00:00Z",
+        ));
+        // A video with no matching still is just a normal video
+        fs.add_object(MockObject::file_with_date(
+            "orphan-mov",
+            "100apple",
+            "IMG_0002.MOV",
+            vec![0x00, 0x00, 0x00, 0x14, b'f', b't', b'y', b'p'],
+            "2024-03-05T12:00:00Z",
+        ));
+
+        manager.add_device(device, fs);
+        manager
+    }
+
+    #[test]
+    fn test_find_live_photo_pairs() {
+        use crate::core::extractor::find_live_photo_pairs;
+
+        let paths = [
+            "DCIM/100APPLE/IMG_0001.MOV",
+            "DCIM/100APPLE/IMG_0001.heic",
+            "DCIM/100APPLE/IMG_0002.MOV",
+            "DCIM/101APPLE/IMG_0003.JPG",
+            "DCIM/100APPLE/IMG_0003.MOV",
+            "DCIM/100APPLE/IMG_0004.jpeg",
+            "DCIM/100APPLE/img_0004.mov",
+        ];
+        let pairs = find_live_photo_pairs(paths);
+
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs.get(&0), Some(&1));
+        assert_eq!(pairs.get(&6), Some(&5));
+        // No still image, or the still is in another folder
+        assert!(!pairs.contains_key(&2));
+        assert!(!pairs.contains_key(&4));
+    }
+
+    #[test]
+    fn test_live_photo_pair_lands_with_still() {
+        let manager = create_live_photo_device();
+        let content = manager.open_device("live-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config =
+            GenericExtractionConfig::with_output_dir(temp_dir.path()).organize_by_date(true);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 3);
+        assert_eq!(stats.live_photos_paired, 1);
+        assert!(temp_dir.path().join("2019/07/IMG_0001.HEIC").exists());
+        assert!(temp_dir.path().join("2019/07/IMG_0001.mov").exists());
+        assert!(temp_dir.path().join("2024/03/IMG_0002.MOV").exists());
+    }
+
+    #[test]
+    fn test_live_photo_pairing_disabled() {
+        let manager = create_live_photo_device();
+        let content = manager.open_device("live-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .organize_by_date(true)
+            .preserve_live_photo_pairs(false);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 3);
+        assert_eq!(stats.live_photos_paired, 0);
+        assert!(temp_dir.path().join("2019/07/IMG_0001.HEIC").exists());
+        assert!(temp_dir.path().join("2024/02/IMG_0001.mov").exists());
+    }
+
+    fn create_corrupting_device(truncated_reads: usize) -> MockDeviceManager {
+        use crate::testdb::MockDeviceConfig;
+
+        create_flaky_device(MockDeviceConfig::new().with_truncated_reads(truncated_reads))
+    }
+
+    fn create_failing_device(failed_reads: usize) -> MockDeviceManager {
+        use crate::testdb::MockDeviceConfig;
+
+        create_flaky_device(MockDeviceConfig::new().with_failed_reads(failed_reads))
+    }
+
+    fn create_flaky_device(device_config: crate::testdb::MockDeviceConfig) -> MockDeviceManager {
+        use crate::testdb::MockDataGenerator;
+
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("flaky-device", "Test iPhone", "Apple Inc.", "iPhone 15");
+
+        let mut fs = MockFileSystem::with_config(device_config);
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::folder("dcim", "internal", "DCIM"));
+        fs.add_object(MockObject::folder("100apple", "dcim", "100APPLE"));
+        fs.add_object(MockObject::file(
+            "photo",
+            "100apple",
+            "IMG_0001.JPG",
+            MockDataGenerator::generate_jpeg_with_seed(4096, 7),
+        ));
+
+        manager.add_device(device, fs);
+        manager
+    }
+
+    #[test]
+    fn test_verify_after_copy_retries_corrupt_transfer() {
+        use crate::testdb::MockDataGenerator;
+
+        let manager = create_corrupting_device(1);
+        let content = manager.open_device("flaky-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config =
+            GenericExtractionConfig::with_output_dir(temp_dir.path()).verify_after_copy(true, 2);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 1);
+        assert_eq!(stats.verification_failures, 0);
+        assert_eq!(stats.bytes_processed, 4096);
+
+        let written = fs::read(
+            temp_dir
+                .path()
+                .join("Internal Storage/DCIM/100APPLE/IMG_0001.JPG"),
+        )
+        .unwrap();
+        assert_eq!(written, MockDataGenerator::generate_jpeg_with_seed(4096, 7));
+    }
+
+    #[test]
+    fn test_verify_after_copy_gives_up_after_retries() {
+        let manager = create_corrupting_device(10);
+        let content = manager.open_device("flaky-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config =
+            GenericExtractionConfig::with_output_dir(temp_dir.path()).verify_after_copy(true, 2);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 0);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.verification_failures, 1);
+        assert!(!temp_dir
+            .path()
+            .join("Internal Storage/DCIM/100APPLE/IMG_0001.JPG")
+            .exists());
+    }
+
+    #[test]
+    fn test_corrupt_transfer_undetected_without_verification() {
+        let manager = create_corrupting_device(1);
+        let content = manager.open_device("flaky-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path());
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 1);
+        assert_eq!(stats.bytes_processed, 2048);
+    }
+
+    #[test]
+    fn test_delete_after_extract_removes_verified_files() {
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+        let device_fs = manager.get_file_system("test-device").unwrap();
+        let files_before = device_fs.read().unwrap().file_count();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .verify_after_copy(true, 2)
+            .delete_after_extract(true);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, files_before);
+        assert_eq!(stats.deleted_from_device, files_before);
+        assert_eq!(device_fs.read().unwrap().file_count(), 0);
+        for id in extractor.extracted_ids() {
+            assert!(content.get_object(id).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_delete_after_extract_keeps_failed_files() {
+        let manager = create_corrupting_device(10);
+        let content = manager.open_device("flaky-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .verify_after_copy(true, 2)
+            .delete_after_extract(true);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.verification_failures, 1);
+        assert_eq!(stats.deleted_from_device, 0);
+        assert!(content.get_object("photo").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_delete_after_extract_requires_verification() {
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+        let device_fs = manager.get_file_system("test-device").unwrap();
+        let files_before = device_fs.read().unwrap().file_count();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config =
+            GenericExtractionConfig::with_output_dir(temp_dir.path()).delete_after_extract(true);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, files_before);
+        assert_eq!(stats.deleted_from_device, 0);
+        assert_eq!(device_fs.read().unwrap().file_count(), files_before);
+    }
+
+    #[test]
+    fn test_delete_after_extract_never_deletes_in_dry_run() {
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+        let device_fs = manager.get_file_system("test-device").unwrap();
+        let files_before = device_fs.read().unwrap().file_count();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .verify_after_copy(true, 2)
+            .delete_after_extract(true)
+            .dry_run(true);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert!(stats.files_extracted > 0);
+        assert_eq!(stats.deleted_from_device, 0);
+        assert_eq!(device_fs.read().unwrap().file_count(), files_before);
+    }
+
+    #[test]
+    fn test_transient_read_errors_are_retried() {
+        let manager = create_failing_device(2);
+        let content = manager.open_device("flaky-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path()).retries(3, 0);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 1);
+        assert_eq!(stats.errors, 0);
+        assert_eq!(stats.retries, 2);
+        assert!(temp_dir
+            .path()
+            .join("Internal Storage/DCIM/100APPLE/IMG_0001.JPG")
+            .exists());
+    }
+
+    #[test]
+    fn test_read_errors_fail_after_max_retries() {
+        let manager = create_failing_device(5);
+        let content = manager.open_device("flaky-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path()).retries(1, 0);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 0);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.retries, 1);
+    }
+
+    #[test]
+    fn test_throttle_caps_transfer_rate() {
+        use crate::testdb::MockDataGenerator;
+        use std::time::{Duration, Instant};
+
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("test-device", "Test iPhone", "Apple Inc.", "iPhone 15");
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::folder("dcim", "internal", "DCIM"));
+        fs.add_object(MockObject::folder("100apple", "dcim", "100APPLE"));
+        fs.add_object(MockObject::file(
+            "video",
+            "100apple",
+            "IMG_0001.MOV",
+            MockDataGenerator::generate_mov_header(200_000),
+        ));
+        manager.add_device(device, fs);
+        let content = manager.open_device("test-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .max_bytes_per_sec(Some(400_000));
+        let mut extractor = GenericExtractor::new(config);
+
+        let start = Instant::now();
+        let stats = extractor.extract_from_content(&content).unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(stats.files_extracted, 1);
+        assert_eq!(stats.bytes_processed, 200_000);
+        // 200 KB at 400 KB/s, less the 40 KB burst allowance
+        assert!(
+            elapsed >= Duration::from_millis(380),
+            "copy finished too fast: {:?}",
+            elapsed
+        );
+    }
+
+    #[test]
+    fn test_json_manifest_matches_stats() {
+        use crate::core::manifest::read_manifest;
+
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let manifest_path = temp_dir.path().join("manifest.json");
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path().join("out"))
+            .manifest(&manifest_path, ManifestFormat::Json);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        // A finished JSON manifest is a closed array
+        let text = fs::read_to_string(&manifest_path).unwrap();
+        let entries: Vec<ManifestEntry> = serde_json::from_str(&text).unwrap();
+        assert_eq!(
+            entries,
+            read_manifest(&manifest_path, ManifestFormat::Json).unwrap()
+        );
+
+        assert_eq!(
+            entries.len(),
+            stats.files_extracted + stats.files_skipped + stats.errors
+        );
+        let extracted: Vec<_> = entries
+            .iter()
+            .filter(|e| e.outcome == ManifestOutcome::Extracted)
+            .collect();
+        assert_eq!(extracted.len(), stats.files_extracted);
+        for entry in extracted {
+            let destination = entry.destination_path.as_ref().unwrap();
+            assert!(destination.exists());
+            assert_eq!(entry.hash.as_ref().unwrap().len(), 64);
+            assert!(entry.source_path.contains("DCIM"));
+        }
+    }
+
+    #[test]
+    fn test_csv_manifest_records_skipped_files() {
+        use crate::core::manifest::read_manifest;
+
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("out");
+        let manifest_path = temp_dir.path().join("manifest.csv");
+        GenericExtractor::new(GenericExtractionConfig::with_output_dir(&output_dir))
+            .extract_from_content(&content)
+            .unwrap();
+
+        // Second run finds everything already on disk
+        let config = GenericExtractionConfig::with_output_dir(&output_dir)
+            .manifest(&manifest_path, ManifestFormat::Csv);
+        let stats = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+
+        let entries = read_manifest(&manifest_path, ManifestFormat::Csv).unwrap();
+        assert_eq!(stats.files_extracted, 0);
+        assert_eq!(entries.len(), stats.files_skipped);
+        assert!(entries
+            .iter()
+            .all(|e| e.outcome == ManifestOutcome::Skipped && e.hash.is_some()));
+    }
+
+    #[test]
+    fn test_manifest_records_errors() {
+        use crate::core::manifest::read_manifest;
+
+        let manager = create_failing_device(5);
+        let content = manager.open_device("flaky-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let manifest_path = temp_dir.path().join("manifest.json");
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path().join("out"))
+            .retries(0, 0)
+            .manifest(&manifest_path, ManifestFormat::Json);
+        let stats = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+
+        let entries = read_manifest(&manifest_path, ManifestFormat::Json).unwrap();
+        assert_eq!(stats.errors, 1);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].outcome, ManifestOutcome::Error);
+        assert!(entries[0].error.as_ref().unwrap().contains("transient"));
+        assert!(entries[0].destination_path.is_none());
+    }
+
+    #[test]
+    fn test_dry_run_writes_nothing() {
+        use crate::core::manifest::read_manifest;
+
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("out");
+        let manifest_dir = TempDir::new().unwrap();
+        let manifest_path = manifest_dir.path().join("plan.json");
+        let config = GenericExtractionConfig::with_output_dir(&output_dir)
+            .dry_run(true)
+            .manifest(&manifest_path, ManifestFormat::Json);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert!(stats.files_extracted > 0);
+        assert_eq!(stats.files_extracted, stats.files_found);
+        assert_eq!(stats.errors, 0);
+        assert!(!output_dir.exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+        // The planned manifest lists where each file would go
+        let entries = read_manifest(&manifest_path, ManifestFormat::Json).unwrap();
+        assert_eq!(entries.len(), stats.files_extracted);
+        for entry in &entries {
+            let destination = entry.destination_path.as_ref().unwrap();
+            assert!(destination.starts_with(&output_dir));
+            assert!(!destination.exists());
+            assert!(entry.hash.is_none());
+        }
+    }
+
+    #[test]
+    fn test_free_space_preflight_fails_when_drive_is_full() {
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .space_safety_margin_mb(0)
+            .with_space_probe(|_| Ok(1024));
+        let mut extractor = GenericExtractor::new(config);
+        let result = extractor.extract_from_content(&content);
+
+        match result {
+            Err(ExtractionError::InsufficientSpace { needed, available }) => {
+                assert!(needed > 1024);
+                assert_eq!(available, 1024);
+            }
+            other => panic!("Expected InsufficientSpace, got {:?}", other),
+        }
+        // Nothing was copied
+        assert!(extractor.extracted_ids().is_empty());
+    }
+
+    #[test]
+    fn test_free_space_preflight_includes_safety_margin() {
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+
+        // Plenty of room for the files, but not for the 1 MB margin on top
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .space_safety_margin_mb(1)
+            .with_space_probe(|_| Ok(1024 * 1024));
+        let mut extractor = GenericExtractor::new(config);
+        assert!(matches!(
+            extractor.extract_from_content(&content),
+            Err(ExtractionError::InsufficientSpace { .. })
+        ));
+
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .space_safety_margin_mb(1)
+            .with_space_probe(|_| Ok(100 * 1024 * 1024));
+        let mut extractor = GenericExtractor::new(config);
+        assert!(extractor.extract_from_content(&content).is_ok());
+    }
+
+    #[test]
+    fn test_free_space_preflight_ignores_existing_files() {
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+        let temp_dir = TempDir::new().unwrap();
+
+        // First run copies everything
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .with_space_probe(|_| Ok(u64::MAX));
+        let mut extractor = GenericExtractor::new(config);
+        let first = extractor.extract_from_content(&content).unwrap();
+        assert!(first.files_extracted > 0);
+
+        // Second run has nothing new to write, so a full drive is fine
+        let config =
+            GenericExtractionConfig::with_output_dir(temp_dir.path()).with_space_probe(|_| Ok(0));
+        let mut extractor = GenericExtractor::new(config);
+        let second = extractor.extract_from_content(&content).unwrap();
+        assert_eq!(second.files_skipped, first.files_extracted);
+    }
+
+    #[test]
+    fn test_duplicate_report_lists_matched_pair() {
+        use crate::duplicate::{DuplicateConfig, DuplicateReportEntry};
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let temp_dir = TempDir::new().unwrap();
+        let library = temp_dir.path().join("library");
+        fs::create_dir_all(&library).unwrap();
+        let existing = library.join("OLD_0001.JPG");
+        fs::write(&existing, b"already backed up").unwrap();
+
+        let manager = ScenarioBuilder::new("duplicates")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::bytes(b"already backed up".to_vec()),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+                FileSpec::bytes(b"brand new photo".to_vec()),
+            )
+            .build_manager();
+        let content = manager.open_device("custom-duplicates").unwrap();
+
+        let index = DuplicateIndex::build_from_folders(
+            &DuplicateConfig::new()
+                .with_folder(library)
+                .with_cache(false),
+            Arc::new(AtomicBool::new(false)),
+            |_| {},
+        )
+        .unwrap();
+        let report_path = temp_dir.path().join("duplicates.json");
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path().join("out"))
+            .preserve_structure(false)
+            .duplicate_detection(index, DuplicateStrategy::Skip)
+            .duplicate_report(&report_path, ManifestFormat::Json);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 1);
+        assert_eq!(stats.duplicates_found, 1);
+        assert!(!temp_dir.path().join("out").join("IMG_0001.JPG").exists());
+
+        let report = extractor.duplicate_report().entries();
+        assert_eq!(report.len(), 1);
+        assert!(report[0]
+            .source_path
+            .ends_with("DCIM/100APPLE/IMG_0001.JPG"));
+        assert_eq!(report[0].matched_path, existing);
+        assert_eq!(report[0].match_type, MatchType::Exact);
+        assert_eq!(report[0].resolution, DuplicateResolution::Skip);
+        assert!(report[0].destination_path.is_none());
+
+        let written: Vec<DuplicateReportEntry> =
+            serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+        assert_eq!(written, report);
+    }
+
+    #[test]
+    fn test_extract_into_zip_archive() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+        use zip::ZipArchive;
+
+        let manager = ScenarioBuilder::new("zipped")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::sized(2048).modified("2024-01-15T10:00:00Z"),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.MOV",
+                FileSpec::sized(4096),
+            )
+            .build_manager();
+        let content = manager.open_device("custom-zipped").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("out");
+        let zip_path = temp_dir.path().join("photos.zip");
+        let config = GenericExtractionConfig::with_output_dir(&output_dir)
+            .destination(Destination::Zip(zip_path.clone()));
+        let stats = GenericExtractor::new(config.clone())
+            .extract_from_content(&content)
+            .unwrap();
+
+        assert_eq!(stats.files_extracted, 2);
+        assert!(!output_dir.exists());
+
+        let mut archive = ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        let mut entries: Vec<(String, u64)> = (0..archive.len())
+            .map(|i| {
+                let entry = archive.by_index(i).unwrap();
+                (entry.name().to_string(), entry.size())
+            })
+            .collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    "Internal Storage/DCIM/100APPLE/IMG_0001.JPG".to_string(),
+                    2048
+                ),
+                (
+                    "Internal Storage/DCIM/100APPLE/IMG_0002.MOV".to_string(),
+                    4096
+                ),
+            ]
+        );
+
+        // Entries already in the archive count as existing files
+        let stats = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+        assert_eq!(stats.files_extracted, 0);
+        assert_eq!(stats.files_skipped, 2);
+        assert_eq!(
+            ZipArchive::new(File::open(&zip_path).unwrap())
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_zip_archive_keeps_files_with_the_same_name() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+        use zip::ZipArchive;
+
+        let manager = ScenarioBuilder::new("zipped_clash")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::sized(2048),
+            )
+            .file(
+                "Internal Storage/DCIM/101APPLE/IMG_0001.JPG",
+                FileSpec::sized(2048),
+            )
+            .build_manager();
+        let content = manager.open_device("custom-zipped_clash").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let zip_path = temp_dir.path().join("photos.zip");
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path().join("out"))
+            .preserve_structure(false)
+            .destination(Destination::Zip(zip_path.clone()));
+        let stats = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+
+        // Same name and size, but a different file: not skipped as existing
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(stats.files_skipped, 0);
+        assert_eq!(stats.renamed_on_collision, 1);
+
+        let mut archive = ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+        for name in ["IMG_0001.JPG", "IMG_0001_1.JPG"] {
+            assert_eq!(archive.by_name(name).unwrap().size(), 2048);
+        }
+    }
+
+    #[test]
+    fn test_plan_classifies_against_tracking_store() {
+        use crate::core::config::TrackingConfig;
+        use crate::core::plan::PlanStatus;
+        use crate::core::tracking::{StateTracker, TrackedFile};
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let manager = ScenarioBuilder::new("planned")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::sized(1000),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+                FileSpec::sized(2000),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0003.JPG",
+                FileSpec::sized(3000),
+            )
+            .build_manager();
+        let content = manager.open_device("custom-planned").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = StateTracker::new(&TrackingConfig::default(), temp_dir.path());
+        store
+            .insert(&TrackedFile::new(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                None,
+                1000,
+            ))
+            .unwrap();
+        store
+            .insert(&TrackedFile::new(
+                "Internal Storage/DCIM/100APPLE/IMG_0003.JPG",
+                None,
+                2500,
+            ))
+            .unwrap();
+
+        let output_dir = temp_dir.path().join("out");
+        let extractor =
+            GenericExtractor::new(GenericExtractionConfig::with_output_dir(&output_dir));
+        let plan = extractor.plan(&content, &store, false).unwrap();
+
+        let status = |name: &str| {
+            plan.files
+                .iter()
+                .find(|file| file.path.ends_with(name))
+                .map(|file| file.status)
+        };
+        assert_eq!(status("IMG_0001.JPG"), Some(PlanStatus::AlreadyHave));
+        assert_eq!(status("IMG_0002.JPG"), Some(PlanStatus::New));
+        assert_eq!(status("IMG_0003.JPG"), Some(PlanStatus::Changed));
+        assert_eq!(plan.summary.new.bytes, 2000);
+        assert_eq!(plan.summary.to_copy().files, 2);
+
+        // Planning copies and records nothing
+        assert!(!output_dir.exists());
+        assert_eq!(store.len().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_shutdown_mid_copy_removes_partial_file() {
+        use crate::testdb::mock_device::MOCK_STREAM_CHUNK_SIZE;
+        use crate::testdb::{FileSpec, RecordingDevice, ScenarioBuilder};
+
+        let path = "Internal Storage/DCIM/100APPLE/IMG_0001.MOV";
+        let manager = ScenarioBuilder::new("large_video")
+            .file(path, FileSpec::sized(4 * MOCK_STREAM_CHUNK_SIZE))
+            .build_manager();
+        let shutdown_flag = Arc::new(AtomicBool::new(false));
+        let content = RecordingDevice::new(manager.open_device("custom-large_video").unwrap())
+            .raise_after_chunk(Arc::clone(&shutdown_flag));
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path());
+        let mut extractor = GenericExtractor::with_shutdown_flag(config, shutdown_flag.clone());
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert!(shutdown_flag.load(Ordering::SeqCst));
+        assert_eq!(stats.files_extracted, 0);
+        assert_eq!(stats.errors, 0);
+        assert!(!temp_dir.path().join(path).exists());
+        assert!(extractor.extracted_ids().is_empty());
+    }
+
+    #[test]
+    fn test_mock_failures_surface_typed_errors() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let scenario = |name: &str| {
+            ScenarioBuilder::new(name)
+                .file(
+                    "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                    FileSpec::sized(2048),
+                )
+                .file(
+                    "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+                    FileSpec::sized(2048),
+                )
+        };
+        let temp_dir = TempDir::new().unwrap();
+
+        // Losing the device while scanning aborts the extraction
+        let manager = scenario("unplugged")
+            .disconnect_during_enumeration(2)
+            .build_manager();
+        let content = manager.open_device("custom-unplugged").unwrap();
+        let error =
+            GenericExtractor::new(GenericExtractionConfig::with_output_dir(temp_dir.path()))
+                .extract_from_content(&content)
+                .unwrap_err();
+        assert!(matches!(error, ExtractionError::DeviceDisconnected));
+        assert!(error.recovery_hint().is_some());
+
+        // Not enough room on the output drive is reported before copying
+        let manager = scenario("full_drive").build_manager();
+        let content = manager.open_device("custom-full_drive").unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .space_safety_margin_mb(0)
+            .with_space_probe(|_| Ok(1024));
+        let error = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ExtractionError::InsufficientSpace {
+                needed: 4096,
+                available: 1024
+            }
+        ));
+
+        // Copies that keep failing verification are counted, not fatal
+        let manager = scenario("bad_cable")
+            .truncate_reads_of("Internal Storage/DCIM/100APPLE/IMG_0001.JPG", 10)
+            .build_manager();
+        let content = manager.open_device("custom-bad_cable").unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path().join("verified"))
+            .verify_after_copy(true, 1);
+        let stats = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+        assert_eq!(stats.verification_failures, 1);
+        assert_eq!(stats.files_extracted, 1);
+    }
+
+    #[test]
+    fn test_progress_sink_sees_each_file_in_order() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        #[derive(Debug, PartialEq)]
+        enum Event {
+            Start(String, usize, usize),
+            Bytes(u64, u64),
+            Done(FileOutcome),
+            Finished(usize),
+        }
+
+        #[derive(Default)]
+        struct RecordingSink(Vec<Event>);
+
+        impl ProgressSink for RecordingSink {
+            fn on_file_start(&mut self, name: &str, index: usize, total: usize) {
+                self.0.push(Event::Start(name.to_string(), index, total));
+            }
+            fn on_bytes(&mut self, copied: u64, total: u64) {
+                self.0.push(Event::Bytes(copied, total));
+            }
+            fn on_file_done(&mut self, outcome: &FileOutcome) {
+                self.0.push(Event::Done(outcome.clone()));
+            }
+            fn on_finished(&mut self, stats: &ExtractionStats) {
+                self.0.push(Event::Finished(stats.files_extracted));
+            }
+        }
+
+        let manager = ScenarioBuilder::new("two_files")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::sized(2048),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+                FileSpec::sized(1024),
+            )
+            .build_manager();
+        let content = manager.open_device("custom-two_files").unwrap();
+        let temp_dir = TempDir::new().unwrap();
+
+        let mut sink = RecordingSink::default();
+        let stats =
+            GenericExtractor::new(GenericExtractionConfig::with_output_dir(temp_dir.path()))
+                .extract_with_sink(&content, &mut sink)
+                .unwrap();
+
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(
+            sink.0,
+            vec![
+                Event::Start("IMG_0001.JPG".to_string(), 1, 2),
+                Event::Bytes(2048, 2048),
+                Event::Done(FileOutcome::Extracted { bytes: 2048 }),
+                Event::Start("IMG_0002.JPG".to_string(), 2, 2),
+                Event::Bytes(1024, 1024),
+                Event::Done(FileOutcome::Extracted { bytes: 1024 }),
+                Event::Finished(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_resumes_after_crash() {
+        use crate::core::checkpoint::Checkpoint;
+        use crate::testdb::{FileSpec, RecordingDevice, ScenarioBuilder};
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let first_folder = "Internal Storage/DCIM/100APPLE";
+        let paths = [
+            "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+            "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+            "Internal Storage/DCIM/101APPLE/IMG_0003.JPG",
+            "Internal Storage/DCIM/101APPLE/IMG_0004.JPG",
+        ];
+        let manager = paths
+            .iter()
+            .fold(ScenarioBuilder::new("crash"), |builder, path| {
+                builder.file(*path, FileSpec::sized(1024))
+            })
+            .build_manager();
+        let device = || RecordingDevice::new(manager.open_device("custom-crash").unwrap());
+
+        let temp_dir = TempDir::new().unwrap();
+        let checkpoint_path = Checkpoint::default_path(temp_dir.path());
+        let config = || {
+            GenericExtractionConfig::with_output_dir(temp_dir.path())
+                .checkpoint(&checkpoint_path, 1)
+        };
+
+        // The process dies while copying the third file
+        let crashing = device().panic_on_read(3);
+        let crashed = catch_unwind(AssertUnwindSafe(|| {
+            GenericExtractor::new(config()).extract_from_content(&crashing)
+        }));
+        assert!(crashed.is_err());
+
+        let saved = Checkpoint::load(&checkpoint_path).unwrap();
+        assert_eq!(
+            saved.completed,
+            paths[..2].iter().map(|p| p.to_string()).collect()
+        );
+        assert!(saved.completed_folders.contains(first_folder));
+        assert_eq!((saved.position, saved.total), (2, 4));
+
+        // A fresh run copies only what is left, without listing the finished folder
+        let resumed = device();
+        let stats = GenericExtractor::new(config())
+            .extract_from_content(&resumed)
+            .unwrap();
+
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(resumed.reads(), &paths[2..]);
+        assert!(!resumed.listed_folders().iter().any(|id| id == first_folder));
+        for path in paths {
+            assert!(temp_dir.path().join(path).exists());
+        }
+        assert!(!checkpoint_path.exists());
+    }
+
+    #[test]
+    fn test_same_named_files_do_not_overwrite_each_other() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        // Same name and size, different content
+        let first = b"first photo".to_vec();
+        let second = b"other photo".to_vec();
+        let content = ScenarioBuilder::new("name_clash")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::bytes(first.clone()),
+            )
+            .file(
+                "Internal Storage/DCIM/101APPLE/IMG_0001.JPG",
+                FileSpec::bytes(second.clone()),
+            )
+            .file(
+                "Internal Storage/DCIM/102APPLE/IMG_0001.JPG",
+                FileSpec::bytes(first.clone()),
+            )
+            .build_content();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config =
+            GenericExtractionConfig::with_output_dir(temp_dir.path()).preserve_structure(false);
+        let stats = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(stats.renamed_on_collision, 1);
+        assert_eq!(
+            fs::read(temp_dir.path().join("IMG_0001.JPG")).unwrap(),
+            first
+        );
+        assert_eq!(
+            fs::read(temp_dir.path().join("IMG_0001_1.JPG")).unwrap(),
+            second
+        );
+
+        // Identical content under the same name follows the duplicate strategy
+        assert_eq!(stats.duplicates_found, 1);
+        assert!(!temp_dir.path().join("IMG_0001_2.JPG").exists());
+    }
+
+    #[test]
+    fn test_size_band_and_media_kinds_select_files() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let dir = "Internal Storage/DCIM/100APPLE";
+        let scenario = || {
+            ScenarioBuilder::new("sizes")
+                .file(&format!("{dir}/TINY.JPG"), FileSpec::sized(100))
+                .file(&format!("{dir}/MID.JPG"), FileSpec::sized(5_000))
+                .file(&format!("{dir}/MID.MOV"), FileSpec::sized(8_000))
+                .file(&format!("{dir}/HUGE.MOV"), FileSpec::sized(50_000))
+                .build_content()
+        };
+        let extract = |config: GenericExtractionConfig| {
+            let mut extractor = GenericExtractor::new(config);
+            let stats = extractor.extract_from_content(&scenario()).unwrap();
+            let mut names: Vec<_> = extractor
+                .extracted_ids()
+                .iter()
+                .map(|id| id.rsplit('/').next().unwrap().to_string())
+                .collect();
+            names.sort();
+            (stats, names)
+        };
+
+        let (stats, names) =
+            extract(GenericExtractionConfig::for_testing().size_range(Some(1_000), Some(10_000)));
+        assert_eq!(names, ["MID.JPG", "MID.MOV"]);
+        assert_eq!(stats.files_found, 4);
+        assert_eq!(stats.files_filtered, 2);
+
+        let (stats, names) = extract(
+            GenericExtractionConfig::for_testing()
+                .size_range(Some(1_000), None)
+                .media_kinds(true, false),
+        );
+        assert_eq!(names, ["MID.JPG"]);
+        assert_eq!(stats.files_filtered, 3);
+
+        let (stats, names) = extract(GenericExtractionConfig::for_testing());
+        assert_eq!(names.len(), 4);
+        assert_eq!(stats.files_filtered, 0);
+    }
+
+    #[test]
+    fn test_exclude_glob_skips_folder_but_not_sibling() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let content = ScenarioBuilder::new("cloud")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::sized(1024),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.MOV",
+                FileSpec::sized(2048),
+            )
+            .file(
+                "Internal Storage/DCIM/100CLOUD/IMG_0003.JPG",
+                FileSpec::sized(1024),
+            )
+            .file(
+                "Internal Storage/DCIM/100CLOUD/IMG_0004.JPG",
+                FileSpec::sized(1024),
+            )
+            .build_content();
+
+        let config = GenericExtractionConfig::for_testing()
+            .path_globs(Vec::<String>::new(), ["**/DCIM/100CLOUD/**"]);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_found, 4);
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(stats.files_filtered, 2);
+        let extracted = extractor.extracted_ids();
+        assert!(extracted.contains("Internal Storage/DCIM/100APPLE/IMG_0001.JPG"));
+        assert!(extracted.contains("Internal Storage/DCIM/100APPLE/IMG_0002.MOV"));
+
+        // Excludes win over an include that also matches
+        let config = GenericExtractionConfig::for_testing()
+            .path_globs(["**/DCIM/**"], ["**/100CLOUD/**", "**/*.mov"]);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+        assert_eq!(stats.files_extracted, 1);
+        assert!(extractor
+            .extracted_ids()
+            .contains("Internal Storage/DCIM/100APPLE/IMG_0001.JPG"));
+
+        // An invalid pattern fails before anything is extracted
+        let config = GenericExtractionConfig::for_testing().path_globs(["DCIM/[100"], ["**/x"]);
+        assert!(matches!(
+            GenericExtractor::new(config).extract_from_content(&content),
+            Err(ExtractionError::InvalidPattern { .. })
+        ));
+    }
+
+    #[test]
+    fn test_identical_files_in_one_run_are_duplicates() {
+        use crate::duplicate::DuplicateConfig;
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let content = ScenarioBuilder::new("twins")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::bytes(b"same photo".to_vec()),
+            )
+            .file(
+                "Internal Storage/DCIM/101APPLE/IMG_0900.JPG",
+                FileSpec::bytes(b"same photo".to_vec()),
+            )
+            .file(
+                "Internal Storage/DCIM/101APPLE/IMG_0901.JPG",
+                FileSpec::bytes(b"other photo".to_vec()),
+            )
+            .build_content();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .preserve_structure(false)
+            .duplicate_detection(
+                DuplicateIndex::new(DuplicateConfig::new()),
+                DuplicateStrategy::Skip,
+            );
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(stats.duplicates_found, 1);
+        assert!(temp_dir.path().join("IMG_0001.JPG").exists());
+        assert!(!temp_dir.path().join("IMG_0900.JPG").exists());
+        assert_eq!(
+            extractor.duplicate_report().entries()[0].matched_path,
+            temp_dir.path().join("IMG_0001.JPG")
+        );
+    }
+
+    #[test]
+    fn test_streamed_copy_hash_matches_written_file() {
+        use crate::duplicate::compute_file_hash;
+        use crate::testdb::mock_device::MOCK_STREAM_CHUNK_SIZE;
+        use crate::testdb::{FileSpec, RecordingDevice, ScenarioBuilder};
+
+        let path = "Internal Storage/DCIM/100APPLE/IMG_0001.MOV";
+        let size = 3 * MOCK_STREAM_CHUNK_SIZE + 17;
+        let manager = ScenarioBuilder::new("single_read")
+            .file(path, FileSpec::sized(size))
+            .build_manager();
+        let content = RecordingDevice::new(manager.open_device("custom-single_read").unwrap());
+        let object_id = content
+            .enumerate_objects()
+            .unwrap()
+            .into_iter()
+            .find(|obj| !obj.is_folder)
+            .unwrap()
+            .object_id;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("IMG_0001.MOV");
+        let copy = copy_streamed(
+            &content,
+            &object_id,
+            &output_path,
+            &Throttle::unlimited(),
+            &AtomicBool::new(false),
+            &mut |_| {},
+        )
+        .unwrap();
+
+        assert_eq!(content.reads(), vec![object_id]);
+        assert_eq!(copy.bytes, size as u64);
+        assert_eq!(copy.hash, compute_file_hash(&output_path).unwrap());
+    }
+
+    #[test]
+    fn test_concurrent_scan_matches_serial_scan() {
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("sd-device", "Galaxy", "Samsung", "SM-A546B");
+        let mut fs = MockFileSystem::new();
+        fs.add_android_dcim_structure(3);
+        fs.add_sd_card_structure(2);
+        fs.add_nested_structure(4, 2);
+        manager.add_device(device, fs);
+        let content = manager.open_device("sd-device").unwrap();
+
+        let scan = |threads| {
+            let config = GenericExtractionConfig {
+                dcim_only: false,
+                ..GenericExtractionConfig::for_testing()
+            }
+            .scan_threads(threads);
+            let extractor = GenericExtractor::new(config);
+            let retrier = Retrier::new(RetryPolicy::none(), Arc::new(AtomicBool::new(false)));
+            let mut stats = ExtractionStats::default();
+            let files = extractor
+                .find_media_files(&content, &mut stats, &retrier)
+                .unwrap();
+            let paths: Vec<String> = files.into_iter().map(|file| file.path).collect();
+            (paths, stats.folders_scanned)
+        };
+
+        let serial = scan(1);
+        assert!(!serial.0.is_empty());
+        assert_eq!(scan(4), serial);
+    }
+
+    #[test]
+    fn test_group_bursts_into_burst_folders() {
+        use crate::core::manifest::read_manifest;
+
+        let mut manager = MockDeviceManager::new();
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::folder("dcim", "internal", "DCIM"));
+        fs.add_object(MockObject::folder("100apple", "dcim", "100APPLE"));
+        let names = [
+            "IMG_0010_BURST20240315120000123.JPG",
+            "IMG_0011_BURST20240315120000123_COVER.JPG",
+            "IMG_0012_BURST20240315120000123.JPG",
+            "IMG_0013_BURST20240401090000456_COVER.JPG",
+            "IMG_0014.JPG",
+        ];
+        for (i, name) in names.iter().enumerate() {
+            fs.add_object(MockObject::file_with_size(
+                &format!("file{}", i),
+                "100apple",
+                name,
+                512,
+            ));
+        }
+        manager.add_device(
+            MockDeviceInfo::new("burst", "Test iPhone", "Apple Inc.", "iPhone 15"),
+            fs,
+        );
+        let content = manager.open_device("burst").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let manifest_path = temp_dir.path().join("manifest.json");
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .group_bursts(true)
+            .manifest(&manifest_path, ManifestFormat::Json);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 5);
+        assert_eq!(stats.burst_frames_grouped, 4);
+        let dir = temp_dir.path().join("Internal Storage/DCIM/100APPLE");
+        let first = dir.join("BURST20240315120000123");
+        assert!(first.join("IMG_0010_BURST20240315120000123.JPG").exists());
+        assert!(first.join("IMG_0012_BURST20240315120000123.JPG").exists());
+        assert!(first
+            .join("IMG_0011_BURST20240315120000123_COVER.JPG")
+            .exists());
+        assert!(dir
+            .join("BURST20240401090000456/IMG_0013_BURST20240401090000456_COVER.JPG")
+            .exists());
+        // Not part of a burst
+        assert!(dir.join("IMG_0014.JPG").exists());
+
+        // The manifest marks each frame's burst and which frame is the cover
+        let entries = read_manifest(&manifest_path, ManifestFormat::Json).unwrap();
+        let burst_of = |id: &str| {
+            let entry = entries.iter().find(|e| e.object_id == id).unwrap();
+            (entry.burst_id.clone(), entry.burst_cover)
+        };
+        let first_id = Some("20240315120000123".to_string());
+        assert_eq!(burst_of("file0"), (first_id.clone(), false));
+        assert_eq!(burst_of("file1"), (first_id, true));
+        assert_eq!(
+            burst_of("file3"),
+            (Some("20240401090000456".to_string()), true)
+        );
+        assert_eq!(burst_of("file4"), (None, false));
+    }
+
+    #[test]
+    fn test_bursts_stay_flat_by_default() {
+        let mut manager = MockDeviceManager::new();
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("dcim", "DEVICE", "DCIM"));
+        fs.add_object(MockObject::file_with_size(
+            "frame",
+            "dcim",
+            "IMG_0010_BURST20240315120000123_COVER.JPG",
+            512,
+        ));
+        manager.add_device(
+            MockDeviceInfo::new("burst", "Test iPhone", "Apple Inc.", "iPhone 15"),
+            fs,
+        );
+        let content = manager.open_device("burst").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path()).dcim_only(false);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.burst_frames_grouped, 0);
+        assert!(temp_dir
+            .path()
+            .join("DCIM/IMG_0010_BURST20240315120000123_COVER.JPG")
+            .exists());
+    }
+
+    #[test]
+    fn test_extract_from_device_continues_after_reconnect() {
+        use crate::testdb::{MockDeviceConfig, MockFault};
+
+        let mut manager = MockDeviceManager::new();
+        let mut fs = MockFileSystem::with_config(MockDeviceConfig::new().with_fault(
+            MockFault::DropConnection {
+                after_reads: 1,
+                offline_listings: 1,
+            },
+        ));
+        fs.add_object(MockObject::folder("dcim", "DEVICE", "DCIM"));
+        for i in 1..=3 {
+            fs.add_object(MockObject::file_with_size(
+                &format!("photo{}", i),
+                "dcim",
+                &format!("IMG_000{}.JPG", i),
+                4096,
+            ));
+        }
+        manager.add_device(
+            MockDeviceInfo::new("usb", "Test iPhone", "Apple Inc.", "iPhone 15"),
+            fs,
+        );
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .dcim_only(false)
+            .reconnect_timeout_secs(5);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor
+            .extract_from_device(&manager, "usb", &mut NullSink)
+            .unwrap();
+
+        // The second file is cut off half way, then copied again in full
+        assert_eq!(stats.files_extracted, 3);
+        assert_eq!(stats.errors, 0);
+        assert_eq!(stats.reconnects, 1);
+        for i in 1..=3 {
+            let path = temp_dir.path().join(format!("DCIM/IMG_000{}.JPG", i));
+            assert_eq!(fs::metadata(path).unwrap().len(), 4096);
+        }
+    }
+
+    #[test]
+    fn test_stats_by_type_sum_to_totals() {
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("mixed-device", "Test iPhone", "Apple Inc.", "iPhone 15");
+
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::folder("dcim", "internal", "DCIM"));
+        fs.add_object(MockObject::folder("100apple", "dcim", "100APPLE"));
+        let files = [
+            ("a", "IMG_0001.JPG", 100),
+            ("b", "IMG_0002.jpg", 200),
+            ("c", "IMG_0003.HEIC", 300),
+            ("d", "IMG_0004.PNG", 50),
+            ("e", "IMG_0005.MOV", 1000),
+            ("f", "IMG_0006.MP4", 700),
+        ];
+        for (id, name, size) in files {
+            fs.add_object(MockObject::file(id, "100apple", name, vec![0xAB; size]));
+        }
+        manager.add_device(device, fs);
+        let content = manager.open_device("mixed-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path());
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 6);
+        let by_type = &stats.by_type;
+        assert_eq!(by_type.total().files, stats.files_extracted);
+        assert_eq!(by_type.total().bytes, stats.bytes_processed);
+        assert_eq!(
+            by_type.photos.files + by_type.videos.files + by_type.other.files,
+            stats.files_extracted
+        );
+        assert_eq!(
+            by_type.photos.bytes + by_type.videos.bytes + by_type.other.bytes,
+            stats.bytes_processed
+        );
+
+        // Extensions are grouped case-insensitively
+        assert_eq!(by_type.by_extension["jpg"].files, 2);
+        assert_eq!(by_type.by_extension["jpg"].bytes, 300);
+        assert_eq!(by_type.photos.files, 4);
+        assert_eq!(by_type.photos.bytes, 650);
+        assert_eq!(by_type.videos.files, 2);
+        assert_eq!(by_type.videos.bytes, 1700);
+        assert_eq!(by_type.extensions_by_size()[0].0, "mov");
+    }
+
+    #[test]
+    fn test_skip_existing_by_hash_matches_content_not_names() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let content = ScenarioBuilder::new("skip_by_hash")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::bytes(b"new photo 1".to_vec()),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+                FileSpec::bytes(b"same photo!".to_vec()),
+            )
+            .build_content();
+
+        // A different photo under a reused name, and a copy of IMG_0002
+        // filed under another name by an earlier run
+        let extract = |mode: SkipExistingMode| {
+            let temp_dir = TempDir::new().unwrap();
+            fs::write(temp_dir.path().join("IMG_0001.JPG"), b"old photo 1").unwrap();
+            fs::create_dir(temp_dir.path().join("Saved")).unwrap();
+            fs::write(temp_dir.path().join("Saved/holiday.jpg"), b"same photo!").unwrap();
+
+            let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+                .preserve_structure(false)
+                .skip_existing_mode(mode);
+            let stats = GenericExtractor::new(config)
+                .extract_from_content(&content)
+                .unwrap();
+            (stats, temp_dir)
+        };
+
+        // By name, the reused name is skipped and the copy extracted again
+        let (stats, temp_dir) = extract(SkipExistingMode::ByName);
+        assert_eq!(stats.files_skipped, 1);
+        assert_eq!(stats.files_extracted, 1);
+        assert_eq!(
+            fs::read(temp_dir.path().join("IMG_0001.JPG")).unwrap(),
+            b"old photo 1"
+        );
+        assert!(temp_dir.path().join("IMG_0002.JPG").exists());
+
+        // By hash, the different photo is kept under a new name and the copy skipped
+        let (stats, temp_dir) = extract(SkipExistingMode::ByHash);
+        assert_eq!(stats.files_skipped, 1);
+        assert_eq!(stats.files_extracted, 1);
+        assert_eq!(stats.renamed_on_collision, 1);
+        assert_eq!(
+            fs::read(temp_dir.path().join("IMG_0001_1.JPG")).unwrap(),
+            b"new photo 1"
+        );
+        assert!(!temp_dir.path().join("IMG_0002.JPG").exists());
+    }
+
+    #[test]
+    fn test_failed_copy_leaves_no_file_under_final_name() {
+        use crate::core::extractor::{partial_path, PARTIAL_EXTENSION};
+        use crate::testdb::{MockDeviceConfig, MockFault};
+
+        let mut manager = MockDeviceManager::new();
+        let mut fs = MockFileSystem::with_config(MockDeviceConfig::new().with_fault(
+            MockFault::DropConnection {
+                after_reads: 0,
+                offline_listings: 1,
+            },
+        ));
+        fs.add_object(MockObject::folder("dcim", "DEVICE", "DCIM"));
+        fs.add_object(MockObject::file_with_size(
+            "photo1",
+            "dcim",
+            "IMG_0001.MOV",
+            4 * 1024 * 1024,
+        ));
+        manager.add_device(
+            MockDeviceInfo::new("usb", "Test iPhone", "Apple Inc.", "iPhone 15"),
+            fs,
+        );
+        let content = manager.open_device("usb").unwrap();
+
+        // Left behind by an earlier run that was cut off
+        let temp_dir = TempDir::new().unwrap();
+        let stale = partial_path(&temp_dir.path().join("DCIM/IMG_0000.JPG"));
+        assert_eq!(stale.extension().unwrap(), PARTIAL_EXTENSION);
+        fs::create_dir_all(stale.parent().unwrap()).unwrap();
+        fs::write(&stale, b"half a photo").unwrap();
+        // ... and one another program left, which isn't ours to delete
+        let foreign = temp_dir.path().join("DCIM/download.partial");
+        fs::write(&foreign, b"half a download").unwrap();
+
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path()).dcim_only(false);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        // The device drops half way through the copy
+        assert_eq!(stats.files_extracted, 0);
+        assert_eq!(stats.errors, 1);
+        let output = temp_dir.path().join("DCIM/IMG_0001.MOV");
+        assert!(!output.exists());
+        assert!(!partial_path(&output).exists());
+        assert!(!stale.exists());
+        assert!(foreign.exists());
+    }
+
+    #[test]
+    fn test_html_report_lists_extracted_files() {
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("out");
+        let report_path = output_dir.join("report.html");
+        let config = GenericExtractionConfig::with_output_dir(&output_dir)
+            .device_name("Test iPhone")
+            .report_html(&report_path);
+
+        let stats = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+        assert!(stats.files_extracted > 0);
+
+        let html = fs::read_to_string(&report_path).unwrap();
+        assert!(html.contains("Photos extracted from Test iPhone"));
+        assert!(html.contains(&format!("{} files extracted", stats.files_extracted)));
+        assert_eq!(html.matches("<figure").count(), stats.files_extracted);
+    }
+
+    #[test]
+    fn test_run_log_accumulates_across_extractions() {
+        use crate::core::manifest::read_run_log;
+
+        let manager = create_test_device();
+        let content = manager.open_device("test-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("out");
+        let log_path = output_dir.join("backup_log.jsonl");
+        let config = GenericExtractionConfig::with_output_dir(&output_dir).append_to_log(&log_path);
+
+        let first = GenericExtractor::new(config.clone())
+            .extract_from_content(&content)
+            .unwrap();
+        let first_len = fs::metadata(&log_path).unwrap().len();
+        let after_first = read_run_log(&log_path).unwrap();
+        assert_eq!(after_first.len(), first.files_extracted);
+
+        // The second run skips everything, and says so under its own run ID
+        let second = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+        assert!(fs::metadata(&log_path).unwrap().len() > first_len);
+        let records = read_run_log(&log_path).unwrap();
+        assert_eq!(records.len(), first.files_extracted + second.files_skipped);
+        assert_eq!(records[..after_first.len()], after_first[..]);
+
+        let mut run_ids: Vec<&str> = records.iter().map(|r| r.run_id.as_str()).collect();
+        run_ids.dedup();
+        assert_eq!(run_ids.len(), 2);
+        assert!(records[after_first.len()..]
+            .iter()
+            .all(|r| r.entry.outcome == ManifestOutcome::Skipped));
+
+        // Every line is a complete JSON object
+        for line in fs::read_to_string(&log_path).unwrap().lines() {
+            assert!(serde_json::from_str::<serde_json::Value>(line)
+                .unwrap()
+                .is_object());
+        }
+    }
+
+    #[test]
+    fn test_names_differing_by_case_or_normalization_clash() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let content = ScenarioBuilder::new("name_spelling")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::bytes(b"first photo".to_vec()),
+            )
+            .file(
+                "Internal Storage/DCIM/101APPLE/img_0001.jpg",
+                FileSpec::bytes(b"other photo".to_vec()),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/Caf\u{e9}.JPG",
+                FileSpec::bytes(b"cafe photo".to_vec()),
+            )
+            .build_content();
+
+        // An earlier run wrote the same photo under its NFD spelling
+        let temp_dir = TempDir::new().unwrap();
+        let nfd_path = temp_dir.path().join("Cafe\u{301}.JPG");
+        fs::write(&nfd_path, b"cafe photo").unwrap();
+
+        let config =
+            GenericExtractionConfig::with_output_dir(temp_dir.path()).preserve_structure(false);
+        let stats = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(stats.files_skipped, 1);
+        assert_eq!(stats.renamed_on_collision, 1);
+        assert_eq!(
+            fs::read(find_existing(&temp_dir.path().join("IMG_0001.JPG")).unwrap()).unwrap(),
+            b"first photo"
+        );
+        assert_eq!(
+            fs::read(find_existing(&temp_dir.path().join("IMG_0001_1.JPG")).unwrap()).unwrap(),
+            b"other photo"
+        );
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_over_long_destination_is_truncated_and_recorded() {
+        use crate::core::long_paths::MAX_PATH_LEN;
+        use crate::core::manifest::read_manifest;
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let long_name = format!("IMG_0001 {}.JPG", "at the top of the mountain ".repeat(4));
+        let content = ScenarioBuilder::new("long_name")
+            .file(
+                &format!("Internal Storage/DCIM/100APPLE/{}", long_name),
+                FileSpec::bytes(b"summit photo".to_vec()),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+                FileSpec::bytes(b"short photo".to_vec()),
+            )
+            .build_content();
+
+        // An output folder nested deep enough that the long name can't fit
+        let temp_dir = TempDir::new().unwrap();
+        let mut output_dir = temp_dir.path().to_path_buf();
+        while output_dir.join(&long_name).as_os_str().len() <= MAX_PATH_LEN {
+            output_dir.push("Backups of the family phones");
+        }
+        let manifest_path = temp_dir.path().join("manifest.json");
+        let config = GenericExtractionConfig::with_output_dir(&output_dir)
+            .preserve_structure(false)
+            .long_path_strategy(LongPathStrategy::Truncate)
+            .manifest(&manifest_path, ManifestFormat::Json);
+        let stats = GenericExtractor::new(config)
+            .extract_from_content(&content)
+            .unwrap();
+        assert_eq!(stats.files_extracted, 2);
+
+        let entries = read_manifest(&manifest_path, ManifestFormat::Json).unwrap();
+        let truncated = entries
+            .iter()
+            .find(|e| e.source_path.ends_with(&long_name))
+            .unwrap();
+        assert_eq!(
+            truncated.truncated_from.as_deref(),
+            Some(long_name.as_str())
+        );
+        let destination = truncated.destination_path.as_ref().unwrap();
+        assert!(destination.as_os_str().len() <= MAX_PATH_LEN);
+        assert!(destination.starts_with(&output_dir));
+        assert_eq!(fs::read(destination).unwrap(), b"summit photo");
+
+        // A short name is left alone
+        let short = entries
+            .iter()
+            .find(|e| e.source_path.ends_with("IMG_0002.JPG"))
+            .unwrap();
+        assert_eq!(short.truncated_from, None);
+        assert_eq!(
+            short.destination_path.as_deref(),
+            Some(output_dir.join("IMG_0002.JPG").as_path())
+        );
+    }
+
+    #[test]
+    fn test_dated_copy_is_skipped_without_reading_the_file() {
+        use crate::testdb::{MockDeviceConfig, MockFault};
+
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("dated-device", "Test iPhone", "Apple Inc.", "iPhone 15");
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::folder("dcim", "internal", "DCIM"));
+        fs.add_object(MockObject::folder("100apple", "dcim", "100APPLE"));
+        fs.add_object(MockObject::file_with_date(
+            "photo",
+            "100apple",
+            "IMG_0001.JPG",
+            vec![7; 1024],
+            "2024-02-10T08:00:00Z",
+        ));
+        // Any read of the file fails, so a skip proves it was never read
+        fs.set_config(
+            MockDeviceConfig::default().with_fault(MockFault::FailReads {
+                object_id: "photo".to_string(),
+                times: usize::MAX,
+            }),
+        );
+        manager.add_device(device, fs);
+        let content = manager.open_device("dated-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let dated_dir = temp_dir.path().join("2024/02");
+        std::fs::create_dir_all(&dated_dir).unwrap();
+        std::fs::write(dated_dir.join("IMG_0001.JPG"), vec![7; 1024]).unwrap();
+
+        let config =
+            GenericExtractionConfig::with_output_dir(temp_dir.path()).organize_by_date(true);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_skipped, 1);
+        assert_eq!(stats.errors, 0);
+    }
+
+    #[test]
+    fn test_throttle_caps_reads_of_skipped_files() {
+        use crate::testdb::MockDataGenerator;
+        use std::time::{Duration, Instant};
+
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("test-device", "Test iPhone", "Apple Inc.", "iPhone 15");
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::folder("dcim", "internal", "DCIM"));
+        fs.add_object(MockObject::folder("100apple", "dcim", "100APPLE"));
+        fs.add_object(MockObject::file(
+            "video",
+            "100apple",
+            "IMG_0001.MOV",
+            MockDataGenerator::generate_mov_header(200_000),
+        ));
+        manager.add_device(device, fs);
+        let content = manager.open_device("test-device").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut extractor =
+            GenericExtractor::new(GenericExtractionConfig::with_output_dir(temp_dir.path()));
+        assert_eq!(
+            extractor
+                .extract_from_content(&content)
+                .unwrap()
+                .files_extracted,
+            1
+        );
+
+        // The identical copy on disk is read in full to compare it, then
+        // skipped without writing anything
+        let config = GenericExtractionConfig::with_output_dir(temp_dir.path())
+            .skip_existing(false)
+            .max_bytes_per_sec(Some(400_000));
+        let mut extractor = GenericExtractor::new(config);
+
+        let start = Instant::now();
+        let stats = extractor.extract_from_content(&content).unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(stats.duplicates_found, 1);
+        assert_eq!(stats.files_extracted, 0);
+        // 200 KB at 400 KB/s, less the 40 KB burst allowance
+        assert!(
+            elapsed >= Duration::from_millis(380),
+            "read finished too fast: {:?}",
+            elapsed
+        );
+    }
 }
@@ -0,0 +1,269 @@
+//! Destination paths longer than Windows' MAX_PATH
+//!
+//! A deep `{device}/{year}/{month}` layout under an already nested output
+//! folder, plus a long original name, easily passes the 260 characters most
+//! Windows file APIs accept, and the copy then fails with an unhelpful "path
+//! not found". [`fit_path`] checks each destination before it is written and,
+//! depending on the [`LongPathStrategy`], either switches it to a `\\?\`
+//! extended-length path or shortens the file name until it fits.
+
+use crate::core::config::LongPathStrategy;
+use crate::core::extractor::PARTIAL_EXTENSION;
+use crate::duplicate::hash_to_hex;
+use log::debug;
+use sha2::{Digest, Sha256};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Longest full path, in UTF-16 units, that regular file APIs accept
+/// (`MAX_PATH` less the terminating NUL)
+pub const MAX_PATH_LEN: usize = 259;
+
+/// Longest folder path `CreateDirectoryW` accepts, which keeps room for an
+/// 8.3 file name inside it
+pub const MAX_DIR_LEN: usize = 247;
+
+/// Characters the partial-file extension adds while a file is being written
+const PARTIAL_SUFFIX_LEN: usize = 1 + PARTIAL_EXTENSION.len();
+
+/// Characters a shortened name leaves free, so the `_<n>` suffix added when
+/// the name is taken and the partial-file extension both still fit
+const RENAME_HEADROOM: usize = 4 + PARTIAL_SUFFIX_LEN;
+
+/// Hex digits of the name hash ending a shortened name
+const NAME_HASH_LEN: usize = 8;
+
+const VERBATIM_PREFIX: &str = r"\\?\";
+
+/// A destination path after fitting it within [`MAX_PATH_LEN`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FittedPath {
+    /// Where the file is written
+    pub path: PathBuf,
+    /// The file name before it was shortened, if it was
+    pub truncated_from: Option<String>,
+}
+
+/// Make `path` usable as a destination despite Windows' path length limit
+///
+/// Paths within the limit, counting the partial-file extension the file is
+/// first written under, are returned unchanged. Otherwise
+/// [`LongPathStrategy::ExtendedPrefix`] returns the absolute path with the
+/// `\\?\` prefix, and [`LongPathStrategy::Truncate`] cuts the file name down
+/// to `<start of name>~<hash>.<ext>`. The hash is of the full name, so the
+/// same file gets the same short name on every run and skip-existing still
+/// finds it. When even a short name can't fit because the folder itself is
+/// too long, the extended-length path is used after all.
+///
+/// Nothing is logged above debug level, since the same path may be fitted
+/// more than once; the caller warns about a shortened name when it writes it.
+pub fn fit_path(path: PathBuf, strategy: LongPathStrategy) -> FittedPath {
+    let absolute = std::path::absolute(&path).unwrap_or_else(|_| path.clone());
+    let dir_len = absolute.parent().map_or(0, utf16_len);
+    if utf16_len(&absolute) + PARTIAL_SUFFIX_LEN <= MAX_PATH_LEN && dir_len <= MAX_DIR_LEN {
+        return FittedPath {
+            path,
+            truncated_from: None,
+        };
+    }
+
+    if strategy == LongPathStrategy::Truncate {
+        let budget = MAX_PATH_LEN.saturating_sub(dir_len + 1 + RENAME_HEADROOM);
+        let short_name = (dir_len <= MAX_DIR_LEN)
+            .then(|| shortened_name(&path, budget))
+            .flatten();
+        if let Some(short_name) = short_name {
+            return FittedPath {
+                truncated_from: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                path: path.with_file_name(short_name),
+            };
+        }
+        debug!(
+            "Folder '{}' is too long to fit any file name",
+            absolute.parent().unwrap_or(&absolute).display()
+        );
+    }
+
+    let extended = extended_length(&absolute);
+    debug!(
+        "Path longer than {} characters, writing through '{}'",
+        MAX_PATH_LEN,
+        extended.display()
+    );
+    FittedPath {
+        path: extended,
+        truncated_from: None,
+    }
+}
+
+/// File name of `path` cut down to at most `budget` UTF-16 units
+///
+/// The extension is kept and the stem is shortened, ending in `~` and a hash
+/// of the full name. Returns `None` if the extension and hash alone don't fit.
+fn shortened_name(path: &Path, budget: usize) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy();
+    let stem = path.file_stem()?.to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let hash = hash_to_hex(&Sha256::digest(name.as_bytes()).into());
+    let suffix = format!("~{}{}", &hash[..NAME_HASH_LEN], extension);
+
+    let mut remaining = budget.checked_sub(suffix.encode_utf16().count())?;
+    let mut kept = String::new();
+    for c in stem.chars() {
+        match remaining.checked_sub(c.len_utf16()) {
+            Some(left) => remaining = left,
+            None => break,
+        }
+        kept.push(c);
+    }
+    // Windows drops trailing spaces and dots from a name, so none go before `~`
+    Some(format!("{}{}", kept.trim_end_matches([' ', '.']), suffix))
+}
+
+/// `path` (absolute) as a `\\?\` path, which bypasses MAX_PATH
+fn extended_length(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if text.starts_with(VERBATIM_PREFIX) {
+        return path.to_path_buf();
+    }
+
+    let mut extended = OsString::from(VERBATIM_PREFIX);
+    match text.strip_prefix(r"\\") {
+        // `\\server\share\...` becomes `\\?\UNC\server\share\...`
+        Some(share) => {
+            extended.push("UNC\\");
+            extended.push(share);
+        }
+        None => extended.push(path.as_os_str()),
+    }
+    PathBuf::from(extended)
+}
+
+/// Length of `path` as Windows counts it
+fn utf16_len(path: &Path) -> usize {
+    path.to_string_lossy().encode_utf16().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::extractor::partial_path;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// A folder under `root` deep enough that `name` no longer fits in it
+    fn deep_folder(root: &Path, name: &str) -> PathBuf {
+        let mut dir = root.to_path_buf();
+        while utf16_len(&dir.join(name)) <= MAX_PATH_LEN {
+            dir.push("2024-03 Holiday in the mountains");
+        }
+        dir
+    }
+
+    #[test]
+    fn test_short_paths_are_unchanged() {
+        let path = PathBuf::from("out").join("IMG_0001.JPG");
+        for strategy in [LongPathStrategy::ExtendedPrefix, LongPathStrategy::Truncate] {
+            let fitted = fit_path(path.clone(), strategy);
+            assert_eq!(fitted.path, path);
+            assert_eq!(fitted.truncated_from, None);
+        }
+    }
+
+    #[test]
+    fn test_truncate_shortens_the_name_to_fit() {
+        let temp_dir = TempDir::new().unwrap();
+        let name = format!("{}.HEIC", "Long description of the photo ".repeat(4));
+        let mut dir = temp_dir.path().to_path_buf();
+        while utf16_len(&dir) < MAX_DIR_LEN - 40 {
+            dir.push("Nested");
+        }
+        let path = dir.join(&name);
+        assert!(utf16_len(&path) > MAX_PATH_LEN);
+
+        let fitted = fit_path(path.clone(), LongPathStrategy::Truncate);
+        assert_eq!(fitted.truncated_from.as_deref(), Some(name.as_str()));
+        assert_eq!(fitted.path.parent(), Some(dir.as_path()));
+        assert!(utf16_len(&partial_path(&fitted.path)) + 4 <= MAX_PATH_LEN);
+
+        let short_name = fitted.path.file_name().unwrap().to_str().unwrap();
+        assert!(short_name.starts_with("Long description"));
+        assert!(short_name.ends_with(".HEIC"));
+        assert_eq!(short_name.matches('~').count(), 1);
+
+        // The same file gets the same name every run
+        assert_eq!(fit_path(path, LongPathStrategy::Truncate), fitted);
+
+        // ... and the shortened path is one that can be written
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(partial_path(&fitted.path), b"photo").unwrap();
+        fs::rename(partial_path(&fitted.path), &fitted.path).unwrap();
+        assert_eq!(fs::read(&fitted.path).unwrap(), b"photo");
+    }
+
+    #[test]
+    fn test_path_is_fitted_when_only_the_partial_name_is_too_long() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut dir = temp_dir.path().to_path_buf();
+        while utf16_len(&dir) < MAX_DIR_LEN - 20 {
+            dir.push("Nested");
+        }
+        let padding = MAX_PATH_LEN - utf16_len(&dir) - 1 - ".JPG".len();
+        let path = dir.join(format!("{}.JPG", "x".repeat(padding)));
+        assert_eq!(utf16_len(&path), MAX_PATH_LEN);
+        assert!(utf16_len(&partial_path(&path)) > MAX_PATH_LEN);
+
+        let fitted = fit_path(path, LongPathStrategy::Truncate);
+        assert!(fitted.truncated_from.is_some());
+        assert!(utf16_len(&partial_path(&fitted.path)) <= MAX_PATH_LEN);
+    }
+
+    #[test]
+    fn test_extended_prefix_keeps_the_full_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let name = "IMG_0001.JPG";
+        let path = deep_folder(temp_dir.path(), name).join(name);
+
+        let fitted = fit_path(path.clone(), LongPathStrategy::ExtendedPrefix);
+        assert_eq!(fitted.truncated_from, None);
+        assert!(fitted.path.to_string_lossy().starts_with(VERBATIM_PREFIX));
+        assert!(fitted.path.ends_with(name));
+
+        if cfg!(windows) {
+            fs::create_dir_all(fitted.path.parent().unwrap()).unwrap();
+            fs::write(&fitted.path, b"photo").unwrap();
+            assert_eq!(fs::read(&fitted.path).unwrap(), b"photo");
+        }
+    }
+
+    #[test]
+    fn test_truncate_falls_back_when_the_folder_is_too_long() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut dir = temp_dir.path().to_path_buf();
+        while utf16_len(&dir) <= MAX_DIR_LEN {
+            dir.push("Nested");
+        }
+
+        let fitted = fit_path(dir.join("IMG_0001.JPG"), LongPathStrategy::Truncate);
+        assert_eq!(fitted.truncated_from, None);
+        assert!(fitted.path.to_string_lossy().starts_with(VERBATIM_PREFIX));
+        assert!(fitted.path.ends_with("IMG_0001.JPG"));
+    }
+
+    #[test]
+    fn test_unc_paths_use_the_unc_prefix() {
+        assert_eq!(
+            extended_length(Path::new(r"\\nas\photos\IMG_0001.JPG")),
+            PathBuf::from(r"\\?\UNC\nas\photos\IMG_0001.JPG")
+        );
+        assert_eq!(
+            extended_length(Path::new(r"\\?\C:\photos\IMG_0001.JPG")),
+            PathBuf::from(r"\\?\C:\photos\IMG_0001.JPG")
+        );
+    }
+}
@@ -0,0 +1,808 @@
+//! Extraction manifest
+//!
+//! An optional, machine-readable record of every file an extraction touched:
+//! where it came from on the device, where it was written, its size, SHA256,
+//! capture date, the burst it is a frame of and what happened to it. The manifest is written one entry at
+//! a time and flushed after each, so a crash still leaves everything up to the
+//! last file on disk. A JSON manifest is closed (`]`) when the writer is
+//! finished or dropped; [`read_manifest`] also accepts an unterminated one.
+//!
+//! The run log ([`RunLogWriter`]) is the cumulative counterpart: every
+//! extraction appends one JSON line per file to the same file, tagged with a
+//! run ID and timestamp, so backing up to one destination again and again
+//! builds a full history there.
+
+#![allow(unused)]
+
+use crate::core::error::{ExtractionError, Result};
+use crate::core::extractor::parse_device_date;
+use crate::core::media::BurstFrame;
+use crate::core::metadata;
+use crate::duplicate::{compute_file_hash, hash_to_hex, Sha256Hash};
+use chrono::{DateTime, NaiveDate, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, LineWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Column order used for CSV manifests
+const CSV_HEADER: &str = "object_id,source_path,destination_path,size,hash,capture_date,outcome,\
+                          error,truncated_from,burst_id,burst_cover";
+
+/// Columns of CSV manifests written before the burst columns were added
+const CSV_HEADER_WITHOUT_BURSTS: &str =
+    "object_id,source_path,destination_path,size,hash,capture_date,outcome,error,truncated_from";
+
+/// Columns of CSV manifests written before `truncated_from` was added
+const CSV_HEADER_WITHOUT_TRUNCATION: &str =
+    "object_id,source_path,destination_path,size,hash,capture_date,outcome,error";
+
+/// File format of the extraction manifest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestFormat {
+    /// A JSON array of entries
+    #[default]
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+impl std::fmt::Display for ManifestFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestFormat::Json => write!(f, "json"),
+            ManifestFormat::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+/// What happened to a file during extraction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestOutcome {
+    /// Copied to the destination
+    Extracted,
+    /// Not copied because it already exists (or was extracted previously)
+    Skipped,
+    /// Not copied because identical content already exists elsewhere
+    Duplicate,
+    /// Copy failed
+    Error,
+}
+
+impl ManifestOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ManifestOutcome::Extracted => "extracted",
+            ManifestOutcome::Skipped => "skipped",
+            ManifestOutcome::Duplicate => "duplicate",
+            ManifestOutcome::Error => "error",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "extracted" => Some(ManifestOutcome::Extracted),
+            "skipped" => Some(ManifestOutcome::Skipped),
+            "duplicate" => Some(ManifestOutcome::Duplicate),
+            "error" => Some(ManifestOutcome::Error),
+            _ => None,
+        }
+    }
+}
+
+/// One file in the manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// WPD object ID on the device
+    pub object_id: String,
+    /// Path of the file on the device
+    pub source_path: String,
+    /// Where the file was written (or the existing copy it matched)
+    pub destination_path: Option<PathBuf>,
+    /// Size in bytes
+    pub size: u64,
+    /// SHA256 of the destination file, as lowercase hex
+    pub hash: Option<String>,
+    /// Capture date (EXIF, falling back to the device's modified date)
+    pub capture_date: Option<NaiveDate>,
+    /// What happened to the file
+    pub outcome: ManifestOutcome,
+    /// Error message for failed files
+    pub error: Option<String>,
+    /// Original file name, when it was shortened to fit Windows' path limit
+    #[serde(default)]
+    pub truncated_from: Option<String>,
+    /// ID of the iPhone burst the file is a frame of, if it is one
+    #[serde(default)]
+    pub burst_id: Option<String>,
+    /// Whether the file is the cover frame Photos shows for its burst
+    #[serde(default)]
+    pub burst_cover: bool,
+}
+
+impl ManifestEntry {
+    /// Create an entry with no destination details
+    pub fn new(
+        object_id: impl Into<String>,
+        source_path: impl Into<String>,
+        size: u64,
+        outcome: ManifestOutcome,
+    ) -> Self {
+        Self {
+            object_id: object_id.into(),
+            source_path: source_path.into(),
+            destination_path: None,
+            size,
+            hash: None,
+            capture_date: None,
+            outcome,
+            error: None,
+            truncated_from: None,
+            burst_id: None,
+            burst_cover: false,
+        }
+    }
+
+    /// Record the hash computed while the file was copied
+    ///
+    /// [`with_destination`](Self::with_destination) then trusts it instead of
+    /// reading the written file back to hash it.
+    pub fn with_hash(mut self, hash: Option<&Sha256Hash>) -> Self {
+        self.hash = hash.map(hash_to_hex);
+        self
+    }
+
+    /// Attach the destination file, hashing it and reading its capture date
+    ///
+    /// `date_modified` is the device timestamp used when the file carries no
+    /// EXIF date. The file is only hashed if no hash was recorded with
+    /// [`with_hash`](Self::with_hash). A missing or unreadable destination
+    /// leaves the hash empty.
+    pub fn with_destination(mut self, path: &Path, date_modified: Option<&str>) -> Self {
+        if path.is_file() {
+            if self.hash.is_none() {
+                self.hash = compute_file_hash(path).ok().map(|h| hash_to_hex(&h));
+            }
+            self.capture_date =
+                metadata::read_exif_capture_date_from_path(path).map(|taken| taken.date());
+        }
+        if self.capture_date.is_none() {
+            self.capture_date = date_modified.and_then(parse_device_date);
+        }
+        self.destination_path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Record the name the file had before it was shortened, if it was
+    pub fn with_truncated_from(mut self, name: Option<&str>) -> Self {
+        self.truncated_from = name.map(str::to_string);
+        self
+    }
+
+    /// Record the burst the file is a frame of, if it is one
+    pub fn with_burst(mut self, frame: Option<&BurstFrame>) -> Self {
+        self.burst_id = frame.map(|f| f.burst_id.clone());
+        self.burst_cover = frame.is_some_and(|f| f.cover);
+        self
+    }
+
+    /// Record the error that caused the file to fail
+    pub fn with_error(mut self, error: impl ToString) -> Self {
+        self.error = Some(error.to_string());
+        self
+    }
+
+    fn csv_row(&self) -> String {
+        let fields = [
+            self.object_id.clone(),
+            self.source_path.clone(),
+            self.destination_path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+            self.size.to_string(),
+            self.hash.clone().unwrap_or_default(),
+            self.capture_date.map(|d| d.to_string()).unwrap_or_default(),
+            self.outcome.as_str().to_string(),
+            self.error.clone().unwrap_or_default(),
+            self.truncated_from.clone().unwrap_or_default(),
+            self.burst_id.clone().unwrap_or_default(),
+            if self.burst_cover { "true" } else { "" }.to_string(),
+        ];
+        fields
+            .iter()
+            .map(|f| csv_escape(f))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn from_csv_fields(fields: &[String]) -> Option<Self> {
+        let non_empty = |s: &String| (!s.is_empty()).then(|| s.clone());
+        let (fields, burst_id, burst_cover) = match fields {
+            [fields @ .., burst_id, burst_cover] if fields.len() == 9 => {
+                (fields, non_empty(burst_id), burst_cover == "true")
+            }
+            fields => (fields, None, false),
+        };
+        let (fields, truncated_from) = match fields {
+            [fields @ .., truncated_from] if fields.len() == 8 => {
+                (fields, non_empty(truncated_from))
+            }
+            fields => (fields, None),
+        };
+        let [object_id, source_path, destination, size, hash, capture_date, outcome, error] =
+            fields
+        else {
+            return None;
+        };
+
+        Some(Self {
+            object_id: object_id.clone(),
+            source_path: source_path.clone(),
+            destination_path: non_empty(destination).map(PathBuf::from),
+            size: size.parse().ok()?,
+            hash: non_empty(hash),
+            capture_date: non_empty(capture_date).and_then(|d| d.parse().ok()),
+            outcome: ManifestOutcome::parse(outcome)?,
+            error: non_empty(error),
+            truncated_from,
+            burst_id,
+            burst_cover,
+        })
+    }
+}
+
+/// Incrementally writes manifest entries to disk
+pub struct ManifestWriter {
+    path: PathBuf,
+    format: ManifestFormat,
+    writer: BufWriter<File>,
+    entries: usize,
+    finished: bool,
+}
+
+impl ManifestWriter {
+    /// Create (or truncate) the manifest file and write its header
+    pub fn create(path: &Path, format: ManifestFormat) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to create manifest directory '{}': {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+
+        let file = File::create(path).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to create manifest '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let mut manifest = Self {
+            path: path.to_path_buf(),
+            format,
+            writer: BufWriter::new(file),
+            entries: 0,
+            finished: false,
+        };
+        let header = match format {
+            ManifestFormat::Json => "[".to_string(),
+            ManifestFormat::Csv => format!("{}\n", CSV_HEADER),
+        };
+        manifest.write_str(&header)?;
+        Ok(manifest)
+    }
+
+    /// Path of the manifest file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of entries written so far
+    pub fn entries(&self) -> usize {
+        self.entries
+    }
+
+    /// Append an entry and flush it to disk
+    pub fn record(&mut self, entry: &ManifestEntry) -> Result<()> {
+        let line = match self.format {
+            ManifestFormat::Json => {
+                let json = serde_json::to_string(entry).map_err(|e| {
+                    ExtractionError::IoError(format!("Failed to serialize manifest entry: {}", e))
+                })?;
+                let separator = if self.entries == 0 { "\n  " } else { ",\n  " };
+                format!("{}{}", separator, json)
+            }
+            ManifestFormat::Csv => format!("{}\n", entry.csv_row()),
+        };
+        self.write_str(&line)?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Close the manifest, terminating the JSON array
+    pub fn finish(mut self) -> Result<()> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        if self.format == ManifestFormat::Json {
+            let footer = if self.entries == 0 { "]\n" } else { "\n]\n" };
+            self.write_str(footer)?;
+        }
+        Ok(())
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<()> {
+        self.writer
+            .write_all(s.as_bytes())
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to write manifest '{}': {}",
+                    self.path.display(),
+                    e
+                ))
+            })
+    }
+}
+
+impl Drop for ManifestWriter {
+    fn drop(&mut self) {
+        if let Err(e) = self.close() {
+            warn!("{}", e);
+        }
+    }
+}
+
+/// One line of the run log: a manifest entry tagged with the run it came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunLogRecord {
+    /// ID shared by every record of one extraction
+    pub run_id: String,
+    /// When the file was handled
+    pub timestamp: DateTime<Utc>,
+    /// How the file was handled
+    #[serde(flatten)]
+    pub entry: ManifestEntry,
+}
+
+/// Appends manifest entries to a cumulative JSON Lines log
+///
+/// Each record is written as a single complete line and flushed straight
+/// away, so the log stays valid JSONL however the extraction ends. A torn
+/// line left by a crash is finished with a newline before the next run
+/// appends, and skipped by [`read_run_log`].
+pub struct RunLogWriter {
+    path: PathBuf,
+    run_id: String,
+    writer: LineWriter<File>,
+    records: usize,
+}
+
+impl RunLogWriter {
+    /// Open (or create) the log for appending under a new run ID
+    pub fn open(path: &Path) -> Result<Self> {
+        let io_error = |action: &str, e: std::io::Error| {
+            ExtractionError::IoError(format!(
+                "Failed to {} run log '{}': {}",
+                action,
+                path.display(),
+                e
+            ))
+        };
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to create run log directory '{}': {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(|e| io_error("open", e))?;
+
+        // Don't glue the first record onto a line torn by an earlier crash
+        let len = file.metadata().map_err(|e| io_error("read", e))?.len();
+        if len > 0 {
+            let mut last = [0u8; 1];
+            file.seek(SeekFrom::End(-1))
+                .and_then(|_| file.read_exact(&mut last))
+                .map_err(|e| io_error("read", e))?;
+            if last[0] != b'\n' {
+                file.write_all(b"\n").map_err(|e| io_error("write", e))?;
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            run_id: new_run_id(),
+            writer: LineWriter::new(file),
+            records: 0,
+        })
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// ID tagging every record of this run
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Number of records appended by this run
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Append a record for `entry` and flush it to disk
+    pub fn record(&mut self, entry: &ManifestEntry) -> Result<()> {
+        let record = RunLogRecord {
+            run_id: self.run_id.clone(),
+            timestamp: Utc::now(),
+            entry: entry.clone(),
+        };
+        let mut line = serde_json::to_string(&record).map_err(|e| {
+            ExtractionError::IoError(format!("Failed to serialize run log record: {}", e))
+        })?;
+        line.push('\n');
+
+        self.writer
+            .write_all(line.as_bytes())
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to write run log '{}': {}",
+                    self.path.display(),
+                    e
+                ))
+            })?;
+        self.records += 1;
+        Ok(())
+    }
+}
+
+/// Generate a run ID: the start time, plus a random suffix so runs started
+/// in the same millisecond stay apart
+fn new_run_id() -> String {
+    format!(
+        "{}-{:04x}",
+        Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+        rand::random::<u16>()
+    )
+}
+
+/// Read every record from a run log
+///
+/// Lines that aren't complete records, such as one torn by a crash, are
+/// skipped with a warning.
+pub fn read_run_log(path: &Path) -> Result<Vec<RunLogRecord>> {
+    let text = fs::read_to_string(path).map_err(|e| {
+        ExtractionError::IoError(format!(
+            "Failed to read run log '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    let mut records = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(e) => warn!(
+                "Skipping invalid line {} of run log '{}': {}",
+                number + 1,
+                path.display(),
+                e
+            ),
+        }
+    }
+    Ok(records)
+}
+
+/// Read a manifest back from disk
+///
+/// A JSON manifest left unterminated by a crash is still read up to its last
+/// complete entry.
+pub fn read_manifest(path: &Path, format: ManifestFormat) -> Result<Vec<ManifestEntry>> {
+    let text = fs::read_to_string(path).map_err(|e| {
+        ExtractionError::IoError(format!(
+            "Failed to read manifest '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    match format {
+        ManifestFormat::Json => parse_json_manifest(&text),
+        ManifestFormat::Csv => parse_csv_manifest(&text),
+    }
+}
+
+fn parse_json_manifest(text: &str) -> Result<Vec<ManifestEntry>> {
+    let invalid =
+        |e: serde_json::Error| ExtractionError::IoError(format!("Invalid JSON manifest: {}", e));
+
+    let trimmed = text.trim_end();
+    if trimmed.ends_with(']') {
+        return serde_json::from_str(trimmed).map_err(invalid);
+    }
+
+    // Interrupted write: keep every complete line, drop a torn last entry
+    let mut entries = Vec::new();
+    for line in trimmed.lines().skip(1) {
+        let line = line.trim().trim_end_matches(',');
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) => break,
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_csv_manifest(text: &str) -> Result<Vec<ManifestEntry>> {
+    let mut rows = split_csv_records(text).into_iter();
+    match rows.next() {
+        Some(header)
+            if [
+                CSV_HEADER,
+                CSV_HEADER_WITHOUT_BURSTS,
+                CSV_HEADER_WITHOUT_TRUNCATION,
+            ]
+            .contains(&header.join(",").as_str()) => {}
+        _ => {
+            return Err(ExtractionError::IoError(
+                "Invalid CSV manifest: missing header".to_string(),
+            ))
+        }
+    }
+
+    rows.map(|row| {
+        ManifestEntry::from_csv_fields(&row).ok_or_else(|| {
+            ExtractionError::IoError(format!("Invalid CSV manifest row: {}", row.join(",")))
+        })
+    })
+    .collect()
+}
+
+/// Quote a CSV field if it contains a separator, quote or newline
+pub(crate) fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Split CSV text into records of unquoted fields
+fn split_csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_entries() -> Vec<ManifestEntry> {
+        vec![
+            ManifestEntry {
+                object_id: "o1".to_string(),
+                source_path: "Internal Storage/DCIM/100APPLE/IMG_0001.JPG".to_string(),
+                destination_path: Some(PathBuf::from("out/IMG_0001 with~3f2a9c1e.JPG")),
+                size: 4096,
+                hash: Some("ab".repeat(32)),
+                capture_date: NaiveDate::from_ymd_opt(2024, 3, 1),
+                outcome: ManifestOutcome::Extracted,
+                error: None,
+                truncated_from: Some("IMG_0001 with a very long description.JPG".to_string()),
+                burst_id: Some("20240315120000123".to_string()),
+                burst_cover: true,
+            },
+            ManifestEntry::new("o2", "DCIM/IMG, \"odd\".JPG", 10, ManifestOutcome::Error)
+                .with_error("Device error: cable\nunplugged"),
+        ]
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("manifest.json");
+
+        let mut writer = ManifestWriter::create(&path, ManifestFormat::Json).unwrap();
+        for entry in sample_entries() {
+            writer.record(&entry).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        let parsed: Vec<ManifestEntry> = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed, sample_entries());
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("manifest.csv");
+
+        let mut writer = ManifestWriter::create(&path, ManifestFormat::Csv).unwrap();
+        for entry in sample_entries() {
+            writer.record(&entry).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let parsed = read_manifest(&path, ManifestFormat::Csv).unwrap();
+        assert_eq!(parsed, sample_entries());
+    }
+
+    #[test]
+    fn test_empty_json_manifest_is_valid() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("manifest.json");
+
+        ManifestWriter::create(&path, ManifestFormat::Json)
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let parsed: Vec<ManifestEntry> =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(parsed.is_empty());
+    }
+
+    #[test]
+    fn test_entries_are_flushed_before_finish() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("manifest.json");
+
+        let mut writer = ManifestWriter::create(&path, ManifestFormat::Json).unwrap();
+        for entry in sample_entries() {
+            writer.record(&entry).unwrap();
+        }
+
+        // Simulate a crash: read while the array is still open
+        let partial = read_manifest(&path, ManifestFormat::Json).unwrap();
+        assert_eq!(partial.len(), 2);
+
+        // Dropping the writer closes the array
+        drop(writer);
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(serde_json::from_str::<Vec<ManifestEntry>>(&text).is_ok());
+    }
+
+    #[test]
+    fn test_with_destination_hashes_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("IMG_0001.JPG");
+        fs::write(&path, b"hello").unwrap();
+
+        let entry = ManifestEntry::new("o1", "DCIM/IMG_0001.JPG", 5, ManifestOutcome::Extracted)
+            .with_destination(&path, Some("2024-02-10T08:00:00Z"));
+
+        assert_eq!(
+            entry.hash.as_deref(),
+            Some("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824")
+        );
+        assert_eq!(entry.capture_date, NaiveDate::from_ymd_opt(2024, 2, 10));
+        assert_eq!(entry.destination_path, Some(path));
+    }
+
+    #[test]
+    fn test_run_log_appends_across_runs() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("logs").join("backup_log.jsonl");
+
+        let mut first = RunLogWriter::open(&path).unwrap();
+        for entry in sample_entries() {
+            first.record(&entry).unwrap();
+        }
+        let first_id = first.run_id().to_string();
+        drop(first);
+
+        // Simulate a crash partway through writing a record
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"run_id\":\"torn").unwrap();
+        drop(file);
+
+        let mut second = RunLogWriter::open(&path).unwrap();
+        second.record(&sample_entries()[0]).unwrap();
+        assert_ne!(second.run_id(), first_id);
+        assert_eq!(second.records(), 1);
+
+        let records = read_run_log(&path).unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records[..2].iter().all(|r| r.run_id == first_id));
+        assert_eq!(records[2].run_id, second.run_id());
+        let entries: Vec<_> = records[..2].iter().map(|r| r.entry.clone()).collect();
+        assert_eq!(entries, sample_entries());
+    }
+
+    #[test]
+    fn test_csv_manifest_without_truncation_column_still_reads() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("manifest.csv");
+        fs::write(
+            &path,
+            format!(
+                "{}\no1,DCIM/IMG_0001.JPG,out/IMG_0001.JPG,4096,,2024-03-01,extracted,\n",
+                CSV_HEADER_WITHOUT_TRUNCATION
+            ),
+        )
+        .unwrap();
+
+        let parsed = read_manifest(&path, ManifestFormat::Csv).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].object_id, "o1");
+        assert_eq!(parsed[0].error, None);
+        assert_eq!(parsed[0].truncated_from, None);
+    }
+
+    #[test]
+    fn test_csv_manifest_without_burst_columns_still_reads() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("manifest.csv");
+        fs::write(
+            &path,
+            format!(
+                "{}\no1,DCIM/IMG_0001.JPG,out/IMG_0001.JPG,4096,,2024-03-01,extracted,,IMG_1.JPG\n",
+                CSV_HEADER_WITHOUT_BURSTS
+            ),
+        )
+        .unwrap();
+
+        let parsed = read_manifest(&path, ManifestFormat::Csv).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].truncated_from.as_deref(), Some("IMG_1.JPG"));
+        assert_eq!(parsed[0].burst_id, None);
+        assert!(!parsed[0].burst_cover);
+    }
+}
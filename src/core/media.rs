@@ -0,0 +1,475 @@
+//! Media file classification and filtering
+//!
+//! The photo and video extension tables here are the single source used by
+//! the extractors, the scan benchmark and device objects. [`MediaFilter`]
+//! narrows the files found on a device down to the kinds and sizes the user
+//! asked for, and [`PathFilter`] down to the device folders they asked for.
+//! [`StatsByType`] tallies what was extracted per extension and per kind.
+//! [`BurstFrame`] recognizes the frames of an iPhone burst so they can be
+//! grouped into one folder per burst.
+
+use crate::core::error::{ExtractionError, Result};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Photo file extensions (lowercase)
+pub const PHOTO_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "heic", "heif", "gif", "webp", "raw", "dng", "tiff", "tif", "bmp",
+];
+
+/// Video file extensions (lowercase)
+pub const VIDEO_EXTENSIONS: &[&str] = &["mov", "mp4", "m4v", "avi", "3gp"];
+
+/// Kind of media file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaKind {
+    Photo,
+    Video,
+}
+
+impl MediaKind {
+    /// Classify a file name by its extension (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::from_extension(&extension_of(name))
+    }
+
+    /// Classify a lowercase extension without the dot
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        if PHOTO_EXTENSIONS.contains(&extension) {
+            Some(MediaKind::Photo)
+        } else if VIDEO_EXTENSIONS.contains(&extension) {
+            Some(MediaKind::Video)
+        } else {
+            None
+        }
+    }
+
+    /// Plural name used in messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaKind::Photo => "photos",
+            MediaKind::Video => "videos",
+        }
+    }
+}
+
+/// Check if a file is a photo or video based on extension
+pub fn is_media_file(name: &str) -> bool {
+    MediaKind::from_name(name).is_some()
+}
+
+/// Lowercase extension of a file name without the dot (empty if none)
+fn extension_of(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Number of files and bytes in one group of a [`StatsByType`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeTally {
+    /// Number of files counted
+    pub files: usize,
+    /// Total size of those files in bytes
+    pub bytes: u64,
+}
+
+impl TypeTally {
+    fn add(&mut self, files: usize, bytes: u64) {
+        self.files += files;
+        self.bytes += bytes;
+    }
+}
+
+/// Extracted files broken down by extension and by photo or video
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsByType {
+    /// Tallies keyed by lowercase extension without the dot (empty if none)
+    pub by_extension: BTreeMap<String, TypeTally>,
+    /// Files with one of the [`PHOTO_EXTENSIONS`]
+    pub photos: TypeTally,
+    /// Files with one of the [`VIDEO_EXTENSIONS`]
+    pub videos: TypeTally,
+    /// Files that are neither a photo nor a video
+    pub other: TypeTally,
+}
+
+impl StatsByType {
+    /// Count one extracted file of `bytes` named `name`
+    pub fn record(&mut self, name: &str, bytes: u64) {
+        let extension = extension_of(name);
+        let kind = MediaKind::from_extension(&extension);
+        self.by_extension
+            .entry(extension)
+            .or_default()
+            .add(1, bytes);
+
+        match kind {
+            Some(MediaKind::Photo) => self.photos.add(1, bytes),
+            Some(MediaKind::Video) => self.videos.add(1, bytes),
+            None => self.other.add(1, bytes),
+        }
+    }
+
+    /// Add another breakdown, e.g. from a second device, into this one
+    pub fn merge(&mut self, other: &StatsByType) {
+        for (extension, tally) in &other.by_extension {
+            self.by_extension
+                .entry(extension.clone())
+                .or_default()
+                .add(tally.files, tally.bytes);
+        }
+        self.photos.add(other.photos.files, other.photos.bytes);
+        self.videos.add(other.videos.files, other.videos.bytes);
+        self.other.add(other.other.files, other.other.bytes);
+    }
+
+    /// Sum over all extensions
+    pub fn total(&self) -> TypeTally {
+        let mut total = TypeTally::default();
+        for tally in self.by_extension.values() {
+            total.add(tally.files, tally.bytes);
+        }
+        total
+    }
+
+    /// Whether nothing has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.by_extension.is_empty()
+    }
+
+    /// Extensions with their tallies, largest total size first
+    pub fn extensions_by_size(&self) -> Vec<(&str, TypeTally)> {
+        let mut extensions: Vec<_> = self
+            .by_extension
+            .iter()
+            .map(|(extension, tally)| (extension.as_str(), *tally))
+            .collect();
+        extensions.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then(a.0.cmp(b.0)));
+        extensions
+    }
+}
+
+/// Why a file was left out by a [`MediaFilter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exclusion {
+    /// Its kind of media is not included
+    Kind(MediaKind),
+    /// It is smaller than the minimum size
+    TooSmall,
+    /// It is larger than the maximum size
+    TooLarge,
+    /// Its path matches no include pattern
+    NotIncluded,
+    /// Its path matches an exclude pattern
+    Excluded,
+}
+
+impl fmt::Display for Exclusion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exclusion::Kind(kind) => write!(f, "{} are not included", kind.as_str()),
+            Exclusion::TooSmall => write!(f, "below the minimum size"),
+            Exclusion::TooLarge => write!(f, "above the maximum size"),
+            Exclusion::NotIncluded => write!(f, "matches no include pattern"),
+            Exclusion::Excluded => write!(f, "matches an exclude pattern"),
+        }
+    }
+}
+
+/// Which media files to extract, by kind and size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaFilter {
+    /// Extract photos
+    pub include_photos: bool,
+    /// Extract videos
+    pub include_videos: bool,
+    /// Smallest file size to extract in bytes (None = no minimum)
+    pub min_size_bytes: Option<u64>,
+    /// Largest file size to extract in bytes (None = no maximum)
+    pub max_size_bytes: Option<u64>,
+}
+
+impl Default for MediaFilter {
+    fn default() -> Self {
+        Self {
+            include_photos: true,
+            include_videos: true,
+            min_size_bytes: None,
+            max_size_bytes: None,
+        }
+    }
+}
+
+impl MediaFilter {
+    /// Whether the filter can leave out any media file
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Why the file `name` of `size` bytes is left out, if it is
+    ///
+    /// A size of 0 means the device didn't report one, so such files are
+    /// never left out for their size.
+    pub fn exclusion(&self, name: &str, size: u64) -> Option<Exclusion> {
+        match MediaKind::from_name(name) {
+            Some(kind @ MediaKind::Photo) if !self.include_photos => {
+                return Some(Exclusion::Kind(kind))
+            }
+            Some(kind @ MediaKind::Video) if !self.include_videos => {
+                return Some(Exclusion::Kind(kind))
+            }
+            _ => {}
+        }
+        if size == 0 {
+            return None;
+        }
+        if self.min_size_bytes.is_some_and(|min| size < min) {
+            Some(Exclusion::TooSmall)
+        } else if self.max_size_bytes.is_some_and(|max| size > max) {
+            Some(Exclusion::TooLarge)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the file `name` of `size` bytes passes the filter
+    pub fn accepts(&self, name: &str, size: u64) -> bool {
+        self.exclusion(name, size).is_none()
+    }
+}
+
+/// Compile a device path glob
+///
+/// Matching is case-insensitive, `*` stays within one folder and `**` spans
+/// any number of folders.
+pub fn compile_glob(pattern: &str) -> std::result::Result<Glob, globset::Error> {
+    GlobBuilder::new(pattern)
+        .case_insensitive(true)
+        .literal_separator(true)
+        .build()
+}
+
+/// Include and exclude globs matched against device-relative paths
+///
+/// Paths look like `Internal Storage/DCIM/100CLOUD/IMG_0001.JPG`, so
+/// `**/DCIM/100CLOUD/**` skips that folder on any storage. Excludes take
+/// precedence over includes, and no include patterns means every path is
+/// included.
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl PathFilter {
+    /// Compile include and exclude patterns
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        Ok(Self {
+            include: build_glob_set(include)?,
+            exclude: build_glob_set(exclude)?,
+        })
+    }
+
+    /// Whether the filter can leave out any path
+    pub fn is_active(&self) -> bool {
+        self.include.is_some() || self.exclude.is_some()
+    }
+
+    /// Why the file at device `path` is left out, if it is
+    pub fn exclusion(&self, path: &str) -> Option<Exclusion> {
+        if self.exclude.as_ref().is_some_and(|set| set.is_match(path)) {
+            Some(Exclusion::Excluded)
+        } else if self.include.as_ref().is_some_and(|set| !set.is_match(path)) {
+            Some(Exclusion::NotIncluded)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the file at device `path` passes the filter
+    pub fn accepts(&self, path: &str) -> bool {
+        self.exclusion(path).is_none()
+    }
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let invalid = |pattern: &str, e: globset::Error| ExtractionError::InvalidPattern {
+        pattern: pattern.to_string(),
+        message: e.kind().to_string(),
+    };
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(compile_glob(pattern).map_err(|e| invalid(pattern, e))?);
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| invalid(&patterns.join(", "), e))
+}
+
+/// One frame of an iPhone burst, e.g. `IMG_0001_BURST20240315120000123_COVER.JPG`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BurstFrame {
+    /// Digits after `BURST`, shared by every frame of the burst
+    pub burst_id: String,
+    /// Whether this is the frame Photos shows for the burst (`_COVER`)
+    pub cover: bool,
+}
+
+impl BurstFrame {
+    /// Recognize a burst frame from its file name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+        let stem = stem.to_ascii_uppercase();
+        let rest = &stem[stem.rfind("_BURST")? + "_BURST".len()..];
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+        let cover = match &rest[digits..] {
+            "" => false,
+            "_COVER" => true,
+            _ => return None,
+        };
+        Some(Self {
+            burst_id: rest[..digits].to_string(),
+            cover,
+        })
+    }
+
+    /// Folder the frames of this burst are grouped into
+    ///
+    /// Named after the burst ID alone, so it doesn't depend on which frame
+    /// is seen first.
+    pub fn folder_name(&self) -> String {
+        format!("BURST{}", self.burst_id)
+    }
+}
+
+/// `path` moved into its burst's folder, or `None` if it isn't a burst frame
+///
+/// The burst folder sits where the file would otherwise have gone. The cover
+/// frame keeps its `_COVER` name inside it.
+pub fn burst_grouped_path(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    let frame = BurstFrame::from_name(name.to_str()?)?;
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    Some(parent.join(frame.folder_name()).join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_kind_from_name() {
+        assert_eq!(
+            MediaKind::from_name("IMG_0001.HEIC"),
+            Some(MediaKind::Photo)
+        );
+        assert_eq!(MediaKind::from_name("clip.mov"), Some(MediaKind::Video));
+        assert_eq!(MediaKind::from_name("notes.txt"), None);
+        assert_eq!(MediaKind::from_name("no_extension"), None);
+        assert!(is_media_file("IMG_0001.jpg"));
+        assert!(!is_media_file("IMG_0001.AAE"));
+    }
+
+    #[test]
+    fn test_filter_by_kind_and_size() {
+        assert!(!MediaFilter::default().is_active());
+
+        let filter = MediaFilter {
+            include_videos: false,
+            min_size_bytes: Some(100),
+            max_size_bytes: Some(1000),
+            ..Default::default()
+        };
+        assert!(filter.is_active());
+        assert!(filter.accepts("IMG.JPG", 500));
+        assert!(filter.accepts("IMG.JPG", 100));
+        assert!(filter.accepts("IMG.JPG", 1000));
+        assert_eq!(filter.exclusion("IMG.JPG", 99), Some(Exclusion::TooSmall));
+        assert_eq!(filter.exclusion("IMG.JPG", 1001), Some(Exclusion::TooLarge));
+        assert_eq!(
+            filter.exclusion("IMG.MOV", 500),
+            Some(Exclusion::Kind(MediaKind::Video))
+        );
+
+        // Files without a reported size are kept
+        assert!(filter.accepts("IMG.JPG", 0));
+    }
+
+    #[test]
+    fn test_path_filter_excludes_take_precedence() {
+        let filter =
+            PathFilter::new(&["**/DCIM/**".to_string()], &["**/100cloud/**".to_string()]).unwrap();
+        assert!(filter.is_active());
+        assert!(filter.accepts("Internal Storage/DCIM/100APPLE/IMG_0001.JPG"));
+        assert_eq!(
+            filter.exclusion("Internal Storage/DCIM/100CLOUD/IMG_0002.JPG"),
+            Some(Exclusion::Excluded)
+        );
+        assert_eq!(
+            filter.exclusion("Internal Storage/Downloads/IMG_0003.JPG"),
+            Some(Exclusion::NotIncluded)
+        );
+
+        // `*` doesn't cross folders
+        let shallow = PathFilter::new(&["Internal Storage/*.JPG".to_string()], &[]).unwrap();
+        assert!(shallow.accepts("Internal Storage/IMG_0004.JPG"));
+        assert!(!shallow.accepts("Internal Storage/DCIM/IMG_0005.JPG"));
+
+        assert!(!PathFilter::default().is_active());
+        assert!(PathFilter::default().accepts("anything"));
+    }
+
+    #[test]
+    fn test_path_filter_rejects_invalid_glob() {
+        let err = PathFilter::new(&[], &["DCIM/[100".to_string()]).unwrap_err();
+        assert!(matches!(
+            err,
+            ExtractionError::InvalidPattern { ref pattern, .. } if pattern == "DCIM/[100"
+        ));
+    }
+
+    #[test]
+    fn test_burst_frame_from_name() {
+        let cover = BurstFrame::from_name("IMG_0001_BURST20240315120000123_COVER.JPG").unwrap();
+        assert_eq!(cover.burst_id, "20240315120000123");
+        assert!(cover.cover);
+
+        let frame = BurstFrame::from_name("img_0002_burst20240315120000123.jpg").unwrap();
+        assert_eq!(frame.burst_id, "20240315120000123");
+        assert!(!frame.cover);
+        assert_eq!(frame.folder_name(), "BURST20240315120000123");
+
+        assert_eq!(BurstFrame::from_name("IMG_0003.JPG"), None);
+        assert_eq!(BurstFrame::from_name("IMG_0004_BURST.JPG"), None);
+        assert_eq!(BurstFrame::from_name("IMG_0005_BURST123_EDITED.JPG"), None);
+    }
+
+    #[test]
+    fn test_burst_grouped_path() {
+        assert_eq!(
+            burst_grouped_path(Path::new("out/DCIM/IMG_0001_BURST42_COVER.JPG")),
+            Some(PathBuf::from("out/DCIM/BURST42/IMG_0001_BURST42_COVER.JPG"))
+        );
+        assert_eq!(
+            burst_grouped_path(Path::new("IMG_0002_BURST42.JPG")),
+            Some(PathBuf::from("BURST42/IMG_0002_BURST42.JPG"))
+        );
+        assert_eq!(burst_grouped_path(Path::new("out/IMG_0003.JPG")), None);
+    }
+}
@@ -0,0 +1,198 @@
+//! Photo metadata extraction
+//!
+//! This module reads capture dates from the EXIF data embedded in photos, so
+//! that files can be organized by when they were taken rather than when they
+//! were last written to the device. A photo that was AirDropped or synced onto
+//! an iPhone keeps its original `DateTimeOriginal`, while its WPD modified time
+//! reflects the copy.
+//!
+//! JPEG, HEIC/HEIF, TIFF, PNG and WebP containers are supported. Anything else
+//! (videos, unknown formats) falls back to the device's modified timestamp.
+
+#![allow(unused)]
+
+use crate::core::extractor::parse_device_date;
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use log::{debug, trace};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Seek};
+use std::path::{Path, PathBuf};
+
+/// Folder used by [`date_folder_path`] for files with no usable date
+pub const UNDATED_FOLDER: &str = "undated";
+
+/// Where a file's date was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateSource {
+    /// EXIF `DateTimeOriginal` embedded in the file
+    Exif,
+    /// Modified timestamp reported by the device
+    DeviceModified,
+}
+
+impl std::fmt::Display for DateSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateSource::Exif => write!(f, "EXIF DateTimeOriginal"),
+            DateSource::DeviceModified => write!(f, "device modified time"),
+        }
+    }
+}
+
+/// Read the EXIF `DateTimeOriginal` capture time from file content
+///
+/// Returns `None` if the content is not a supported container, carries no
+/// EXIF block, or the tag is missing or malformed.
+pub fn read_exif_capture_date(data: &[u8]) -> Option<NaiveDateTime> {
+    capture_date_from_reader(&mut Cursor::new(data))
+}
+
+/// Read the EXIF `DateTimeOriginal` capture time from a file on disk
+///
+/// Only the container headers are read, so this is cheap even for large files.
+pub fn read_exif_capture_date_from_path(path: &Path) -> Option<NaiveDateTime> {
+    let file = File::open(path).ok()?;
+    capture_date_from_reader(&mut BufReader::new(file))
+}
+
+fn capture_date_from_reader<R: BufRead + Seek>(reader: &mut R) -> Option<NaiveDateTime> {
+    let exif = match exif::Reader::new().read_from_container(reader) {
+        Ok(exif) => exif,
+        Err(e) => {
+            trace!("No EXIF data: {}", e);
+            return None;
+        }
+    };
+
+    let field = exif.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY)?;
+    let exif::Value::Ascii(ref values) = field.value else {
+        return None;
+    };
+    let dt = exif::DateTime::from_ascii(values.first()?).ok()?;
+
+    NaiveDate::from_ymd_opt(dt.year as i32, dt.month as u32, dt.day as u32)?.and_hms_opt(
+        dt.hour as u32,
+        dt.minute as u32,
+        dt.second as u32,
+    )
+}
+
+/// Read the EXIF `Orientation` tag (1-8) from file content
+///
+/// Returns `None` when there is no EXIF block or no orientation tag.
+pub fn read_exif_orientation(data: &[u8]) -> Option<u16> {
+    let exif = exif::Reader::new()
+        .read_from_container(&mut Cursor::new(data))
+        .ok()?;
+    let field = exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?;
+    field.value.get_uint(0).and_then(|v| u16::try_from(v).ok())
+}
+
+/// Determine the capture date of a file
+///
+/// Prefers the EXIF `DateTimeOriginal` and falls back to the device's modified
+/// timestamp when EXIF is unavailable. The chosen source is logged at debug level.
+pub fn resolve_capture_date(
+    name: &str,
+    data: &[u8],
+    date_modified: Option<&str>,
+) -> Option<(NaiveDate, DateSource)> {
+    if let Some(taken) = read_exif_capture_date(data) {
+        debug!("Date for {}: {} (EXIF DateTimeOriginal)", name, taken);
+        return Some((taken.date(), DateSource::Exif));
+    }
+
+    match date_modified.and_then(parse_device_date) {
+        Some(date) => {
+            debug!("Date for {}: {} (device modified time)", name, date);
+            Some((date, DateSource::DeviceModified))
+        }
+        None => {
+            debug!("Date for {}: unknown (no EXIF or device date)", name);
+            None
+        }
+    }
+}
+
+/// Build the `YYYY/MM/<name>` relative path used when organizing by date
+///
+/// Files without any usable date are placed under [`UNDATED_FOLDER`].
+pub fn date_folder_path(name: &str, data: &[u8], date_modified: Option<&str>) -> PathBuf {
+    match resolve_capture_date(name, data, date_modified) {
+        Some((date, _)) => PathBuf::from(format!("{:04}", date.year()))
+            .join(format!("{:02}", date.month()))
+            .join(name),
+        None => PathBuf::from(UNDATED_FOLDER).join(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdb::MockDataGenerator;
+
+    #[test]
+    fn test_read_exif_capture_date() {
+        let data = MockDataGenerator::generate_jpeg_with_exif(512, "2019:07:04 18:30:00", "iPhone");
+        let taken = read_exif_capture_date(&data).unwrap();
+        assert_eq!(
+            taken,
+            NaiveDate::from_ymd_opt(2019, 7, 4)
+                .unwrap()
+                .and_hms_opt(18, 30, 0)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_exif_capture_date_from_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("IMG_0001.JPG");
+        let data = MockDataGenerator::generate_jpeg_with_exif(512, "2019:07:04 18:30:00", "iPhone");
+        std::fs::write(&path, data).unwrap();
+
+        let taken = read_exif_capture_date_from_path(&path).unwrap();
+        assert_eq!(taken.date(), NaiveDate::from_ymd_opt(2019, 7, 4).unwrap());
+        assert!(read_exif_capture_date_from_path(&temp_dir.path().join("missing.jpg")).is_none());
+    }
+
+    #[test]
+    fn test_read_exif_capture_date_missing() {
+        let data = MockDataGenerator::generate_jpeg_header(512);
+        assert!(read_exif_capture_date(&data).is_none());
+        assert!(read_exif_capture_date(b"not an image").is_none());
+    }
+
+    #[test]
+    fn test_resolve_capture_date_prefers_exif() {
+        let data = MockDataGenerator::generate_jpeg_with_exif(512, "2019:07:04 18:30:00", "iPhone");
+        let (date, source) =
+            resolve_capture_date("IMG_0001.JPG", &data, Some("2024-02-10T08:00:00Z")).unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2019, 7, 4).unwrap());
+        assert_eq!(source, DateSource::Exif);
+    }
+
+    #[test]
+    fn test_resolve_capture_date_falls_back_to_device_date() {
+        let data = MockDataGenerator::generate_jpeg_header(512);
+        let (date, source) =
+            resolve_capture_date("IMG_0001.JPG", &data, Some("2024-02-10T08:00:00Z")).unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2024, 2, 10).unwrap());
+        assert_eq!(source, DateSource::DeviceModified);
+
+        assert!(resolve_capture_date("IMG_0001.JPG", &data, None).is_none());
+    }
+
+    #[test]
+    fn test_date_folder_path() {
+        let data = MockDataGenerator::generate_jpeg_with_exif(512, "2019:07:04 18:30:00", "iPhone");
+        assert_eq!(
+            date_folder_path("IMG_0001.JPG", &data, None),
+            PathBuf::from("2019").join("07").join("IMG_0001.JPG")
+        );
+        assert_eq!(
+            date_folder_path("IMG_0002.JPG", b"", None),
+            PathBuf::from(UNDATED_FOLDER).join("IMG_0002.JPG")
+        );
+    }
+}
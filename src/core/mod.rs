@@ -6,11 +6,27 @@
 //!
 //! # Submodules
 //!
+//! - `archive` - Extraction destinations, including ZIP archives
+//! - `checkpoint` - Periodic checkpoints for resuming an interrupted extraction
 //! - `config` - Configuration loading, saving, and management
+//! - `discovery` - Concurrent folder enumeration ahead of extraction
 //! - `error` - Error types and result aliases
 //! - `extractor` - Photo extraction logic (WPD-specific)
 //! - `generic_extractor` - Generic extraction using trait abstraction (testable)
+//! - `long_paths` - Destination paths beyond Windows' 260 character limit
+//! - `manifest` - Machine-readable record of each extracted file
+//! - `media` - Photo/video classification and size/kind filtering
+//! - `metadata` - EXIF capture date parsing for date-based organization
+//! - `names` - Case- and Unicode-insensitive comparison of destination names
+//! - `notify` - Desktop notifications and sounds when an extraction finishes
+//! - `plan` - Comparison of device files with the tracking store before extraction
+//! - `report` - Self-contained HTML report of an extraction for sharing
+//! - `retry` - Retry with backoff for transient device errors
+//! - `template` - Output path templates such as `{device}/{year}/{month}/{original_name}`
+//! - `throttle` - Transfer rate limiting for background extraction
+//! - `throughput` - Rolling transfer speed and time remaining estimates
 //! - `tracking` - Extraction state and session tracking
+//! - `util` - Helpers shared by the CLI and UI, such as opening a folder
 //!
 //! # Testing Support
 //!
@@ -32,14 +48,31 @@
 
 #![allow(unused)]
 
+pub mod archive;
+pub mod checkpoint;
 pub mod config;
+pub mod discovery;
 pub mod error;
 pub mod extractor;
 pub mod generic_extractor;
+pub mod long_paths;
+pub mod manifest;
+pub mod media;
+pub mod metadata;
+pub mod names;
+pub mod notify;
+pub mod plan;
+pub mod report;
+pub mod retry;
 pub mod setup;
+pub mod template;
+pub mod throttle;
+pub mod throughput;
 pub mod tracking;
+pub mod util;
 
 // Re-export commonly used types
+pub use checkpoint::{Checkpoint, CheckpointTracker};
 pub use config::Config;
 pub use error::{ExtractionError, Result};
 pub use extractor::{extract_photos, ExtractionConfig, ExtractionStats};
@@ -47,8 +80,19 @@ pub use generic_extractor::{
     ExtractionPhase, ExtractionStats as GenericExtractionStats, GenericExtractionConfig,
     GenericExtractor, ProgressUpdate,
 };
+pub use manifest::{ManifestEntry, ManifestFormat, ManifestOutcome, ManifestWriter};
+pub use media::{MediaFilter, MediaKind};
+pub use metadata::{read_exif_capture_date, resolve_capture_date, DateSource};
+pub use notify::{Notifier, NullNotifier, Severity};
+pub use plan::{ExtractionPlan, PlanStatus, PlannedFile};
+pub use retry::{Retrier, RetryPolicy};
 pub use setup::{
     apply_setup, is_setup_complete, normalize_path, run_setup_wizard, SetupError, SetupOptions,
     SetupResult,
 };
-pub use tracking::{scan_for_profiles, scan_for_profiles_default, ProfileSummary, StateTracker};
+pub use throttle::Throttle;
+pub use tracking::{
+    scan_for_profiles, scan_for_profiles_default, FileRecord, ProfileSummary, PruneReport,
+    SqliteStore, StateTracker, TrackedFile, TrackingStoreTrait, VerifyIssue, VerifyReport,
+};
+pub use util::reveal_in_explorer;
@@ -0,0 +1,145 @@
+//! Destination file names
+//!
+//! Windows treats `IMG_0001.JPG` and `img_0001.jpg` as the same file, and
+//! phones don't agree on whether `é` is stored as one code point (NFC) or as
+//! `e` plus a combining accent (NFD). Names that look the same are really
+//! different strings, so a plain comparison misses the clash when one file
+//! is about to land on another, and skip-existing misses a file that's
+//! already there.
+//!
+//! Names are NFC-normalized when destination paths are built
+//! ([`normalize_name`]), and compared by [`collision_key`], which also folds
+//! case. [`find_existing`] finds a file on disk under any spelling.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+/// NFC-normalize a file or folder name
+pub fn normalize_name(name: &str) -> String {
+    name.nfc().collect()
+}
+
+/// Key two paths share exactly when they name the same file on Windows
+///
+/// The path is NFC-normalized and case-folded.
+pub fn collision_key(path: &Path) -> String {
+    path.to_string_lossy()
+        .nfc()
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Find the file at `path`, whatever case or normalization form it was
+/// written with
+///
+/// The file system is asked first. Only when that misses is the parent
+/// folder listed and compared by [`collision_key`]; on Windows that is just
+/// for names with non-ASCII characters, since the file system already
+/// ignores case.
+pub fn find_existing(path: &Path) -> Option<PathBuf> {
+    if path.exists() {
+        return Some(path.to_path_buf());
+    }
+    let name = path.file_name()?.to_str()?;
+    if cfg!(windows) && name.is_ascii() {
+        return None;
+    }
+
+    let key = collision_key(Path::new(name));
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty())?;
+    fs::read_dir(parent)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .find(|entry| collision_key(Path::new(&entry.file_name())) == key)
+        .map(|entry| entry.path())
+}
+
+/// Destination paths already written by this extraction
+///
+/// Paths are compared by [`collision_key`], so a second file whose name only
+/// differs in case or normalization is seen as clashing with the first.
+#[derive(Debug, Clone, Default)]
+pub struct WrittenPaths {
+    keys: HashSet<String>,
+}
+
+impl WrittenPaths {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `path` was written
+    pub fn insert(&mut self, path: &Path) {
+        self.keys.insert(collision_key(path));
+    }
+
+    /// Whether `path`, under any spelling, was written
+    pub fn contains(&self, path: &Path) -> bool {
+        self.keys.contains(&collision_key(path))
+    }
+
+    /// Forget every path, e.g. when a new extraction starts
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const NFC: &str = "Caf\u{e9}.JPG";
+    const NFD: &str = "Cafe\u{301}.JPG";
+
+    #[test]
+    fn test_names_differing_by_case_or_form_share_a_key() {
+        assert_ne!(NFC, NFD);
+        assert_eq!(normalize_name(NFD), NFC);
+        assert_eq!(normalize_name(NFC), NFC);
+
+        let key = collision_key(Path::new("out/IMG_1.JPG"));
+        assert_eq!(collision_key(Path::new("out/img_1.jpg")), key);
+        assert_ne!(collision_key(Path::new("out/IMG_2.JPG")), key);
+        assert_eq!(
+            collision_key(&Path::new("out").join(NFC)),
+            collision_key(&Path::new("OUT").join(NFD.to_lowercase()))
+        );
+
+        let mut written = WrittenPaths::new();
+        written.insert(&Path::new("out").join(NFD));
+        written.insert(Path::new("out/IMG_1.JPG"));
+        assert!(written.contains(&Path::new("out").join(NFC)));
+        assert!(written.contains(Path::new("out/img_1.jpg")));
+        assert!(!written.contains(Path::new("other/img_1.jpg")));
+        written.clear();
+        assert!(!written.contains(Path::new("out/IMG_1.JPG")));
+    }
+
+    #[test]
+    fn test_find_existing_matches_any_spelling() {
+        let temp_dir = TempDir::new().unwrap();
+        let nfd_path = temp_dir.path().join(NFD);
+        fs::write(&nfd_path, b"photo").unwrap();
+        fs::write(temp_dir.path().join("IMG_1.JPG"), b"photo").unwrap();
+
+        assert_eq!(
+            find_existing(&temp_dir.path().join(NFC)),
+            Some(nfd_path.clone())
+        );
+        assert_eq!(find_existing(&nfd_path), Some(nfd_path));
+        let found = find_existing(&temp_dir.path().join("img_1.jpg")).unwrap();
+        assert_eq!(
+            collision_key(&found),
+            collision_key(&temp_dir.path().join("IMG_1.JPG"))
+        );
+        assert_eq!(find_existing(&temp_dir.path().join("IMG_2.JPG")), None);
+        assert_eq!(
+            find_existing(&temp_dir.path().join("missing").join(NFC)),
+            None
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Desktop notifications and sounds
+//!
+//! A long extraction usually runs in the background, so the app tells the
+//! user when it finishes or fails through a [`Notifier`]. On Windows builds
+//! with the `desktop-notifications` feature this is a system toast
+//! ([`ToastNotifier`]); otherwise, and in headless runs, [`NullNotifier`]
+//! drops everything. [`play_sound`] plays a WAV file at a given volume
+//! alongside the notification.
+
+use crate::core::error::{ExtractionError, Result};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// How important a notification is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Severity {
+    /// Informational message
+    #[default]
+    Info,
+    /// Operation succeeded
+    Success,
+    /// Something needs attention
+    Warning,
+    /// Operation failed
+    Error,
+}
+
+/// Shows notifications to the user
+pub trait Notifier: Send + Sync {
+    /// Show a notification; failures are logged, not returned
+    fn notify(&self, title: &str, body: &str, severity: Severity);
+}
+
+/// Notifier that shows nothing, for headless and CI runs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullNotifier;
+
+impl Notifier for NullNotifier {
+    fn notify(&self, _title: &str, _body: &str, _severity: Severity) {}
+}
+
+/// A notification captured by [`RecordingNotifier`]
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Title line
+    pub title: String,
+    /// Message text
+    pub body: String,
+    /// Severity
+    pub severity: Severity,
+}
+
+/// Notifier that keeps every notification instead of showing it
+///
+/// Clones share the same list, so a test can hand one clone to the code
+/// under test and inspect another.
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct RecordingNotifier {
+    notifications: Arc<Mutex<Vec<Notification>>>,
+}
+
+#[cfg(test)]
+impl RecordingNotifier {
+    /// Create a notifier with nothing recorded
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notifications received so far, oldest first
+    pub fn notifications(&self) -> Vec<Notification> {
+        self.notifications.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl Notifier for RecordingNotifier {
+    fn notify(&self, title: &str, body: &str, severity: Severity) {
+        self.notifications.lock().unwrap().push(Notification {
+            title: title.to_string(),
+            body: body.to_string(),
+            severity,
+        });
+    }
+}
+
+/// The notifier for this build: system toasts when supported, else none
+pub fn default_notifier() -> Box<dyn Notifier> {
+    #[cfg(all(windows, feature = "desktop-notifications"))]
+    {
+        Box::new(ToastNotifier::default())
+    }
+
+    #[cfg(not(all(windows, feature = "desktop-notifications")))]
+    {
+        Box::new(NullNotifier)
+    }
+}
+
+impl Default for Box<dyn Notifier> {
+    fn default() -> Self {
+        default_notifier()
+    }
+}
+
+// =============================================================================
+// Windows Toasts
+// =============================================================================
+
+/// App id toasts are shown under when none is given
+///
+/// Unpackaged apps can only raise toasts under an app user model id the shell
+/// knows about, and this tool doesn't register one of its own yet, so it
+/// borrows the id of Windows PowerShell. Known limitations:
+///
+/// - Toasts are labelled "Windows PowerShell" and show its icon, both on the
+///   banner and in the Action Center.
+/// - Notification settings are shared with PowerShell: turning its
+///   notifications off (or on Focus Assist's priority list) affects ours too.
+/// - On systems without Windows PowerShell the id is unknown and toasts are
+///   dropped without an error.
+///
+/// Pass a registered id to [`ToastNotifier::new`] to avoid these.
+pub const DEFAULT_TOAST_APP_ID: &str =
+    "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+
+/// Notifier that raises Windows toast notifications
+#[cfg(all(windows, feature = "desktop-notifications"))]
+#[derive(Debug, Clone)]
+pub struct ToastNotifier {
+    app_id: String,
+}
+
+#[cfg(all(windows, feature = "desktop-notifications"))]
+impl ToastNotifier {
+    /// Create a notifier that shows toasts under `app_id`
+    pub fn new(app_id: impl Into<String>) -> Self {
+        Self {
+            app_id: app_id.into(),
+        }
+    }
+
+    fn show(&self, title: &str, body: &str) -> windows::core::Result<()> {
+        use windows::core::HSTRING;
+        use windows::Data::Xml::Dom::XmlDocument;
+        use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+
+        let xml = XmlDocument::new()?;
+        xml.LoadXml(&HSTRING::from(toast_xml(title, body)))?;
+        let toast = ToastNotification::CreateToastNotification(&xml)?;
+        ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(&self.app_id))?
+            .Show(&toast)
+    }
+}
+
+#[cfg(all(windows, feature = "desktop-notifications"))]
+impl Default for ToastNotifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOAST_APP_ID)
+    }
+}
+
+#[cfg(all(windows, feature = "desktop-notifications"))]
+impl Notifier for ToastNotifier {
+    fn notify(&self, title: &str, body: &str, severity: Severity) {
+        if let Err(e) = self.show(title, body) {
+            log::warn!("Could not show {:?} notification: {}", severity, e);
+        }
+    }
+}
+
+/// Toast content with a title and one line of text
+fn toast_xml(title: &str, body: &str) -> String {
+    format!(
+        "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual></toast>",
+        escape_xml(title),
+        escape_xml(body)
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// =============================================================================
+// Sounds
+// =============================================================================
+
+/// Play the WAV file at `path` at `volume` (0.0 - 1.0) on a background thread
+///
+/// The file is read and scaled before returning, so a missing or unsupported
+/// file is reported here rather than failing silently later.
+pub fn play_sound(path: &Path, volume: f32) -> Result<()> {
+    let wav = std::fs::read(path).map_err(|e| {
+        ExtractionError::IoError(format!("Failed to read sound '{}': {}", path.display(), e))
+    })?;
+    let wav = scale_wav_volume(&wav, volume)?;
+
+    #[cfg(all(windows, feature = "desktop-notifications"))]
+    {
+        std::thread::spawn(move || {
+            use windows::core::PCWSTR;
+            use windows::Win32::Foundation::HMODULE;
+            use windows::Win32::Media::Audio::{PlaySoundW, SND_MEMORY, SND_NODEFAULT, SND_SYNC};
+
+            // SND_MEMORY reads the WAV from the buffer, which this thread
+            // keeps alive until playback ends
+            let played = unsafe {
+                PlaySoundW(
+                    PCWSTR(wav.as_ptr() as *const u16),
+                    HMODULE::default(),
+                    SND_MEMORY | SND_SYNC | SND_NODEFAULT,
+                )
+            };
+            if !played.as_bool() {
+                log::warn!("Could not play notification sound");
+            }
+        });
+        Ok(())
+    }
+
+    #[cfg(not(all(windows, feature = "desktop-notifications")))]
+    {
+        let _ = wav;
+        Err(ExtractionError::IoError(
+            "Sound playback needs the desktop-notifications feature on Windows".to_string(),
+        ))
+    }
+}
+
+/// Copy of a PCM WAV file with every sample multiplied by `volume`
+///
+/// Supports 8- and 16-bit PCM, which covers the system sounds Windows ships.
+pub fn scale_wav_volume(wav: &[u8], volume: f32) -> Result<Vec<u8>> {
+    let invalid =
+        |reason: &str| ExtractionError::IoError(format!("Unsupported WAV file: {}", reason));
+
+    if wav.len() < 12 || &wav[0..4] != b"RIFF" || &wav[8..12] != b"WAVE" {
+        return Err(invalid("not a RIFF/WAVE file"));
+    }
+
+    let mut bits_per_sample = None;
+    let mut data = None;
+    let mut offset = 12;
+    while offset + 8 <= wav.len() {
+        let id = &wav[offset..offset + 4];
+        let size = u32::from_le_bytes(wav[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let start = offset + 8;
+        let end = start.saturating_add(size).min(wav.len());
+
+        match id {
+            b"fmt " if end - start >= 16 => {
+                let format = u16::from_le_bytes([wav[start], wav[start + 1]]);
+                if format != 1 {
+                    return Err(invalid("not PCM"));
+                }
+                bits_per_sample = Some(u16::from_le_bytes([wav[start + 14], wav[start + 15]]));
+            }
+            b"data" => data = Some(start..end),
+            _ => {}
+        }
+
+        // Chunks are padded to an even length
+        offset = start.saturating_add(size).saturating_add(size % 2);
+    }
+
+    let (bits_per_sample, data) = match (bits_per_sample, data) {
+        (Some(bits), Some(data)) => (bits, data),
+        _ => return Err(invalid("missing fmt or data chunk")),
+    };
+
+    let volume = volume.clamp(0.0, 1.0);
+    let mut scaled = wav.to_vec();
+    match bits_per_sample {
+        // 8-bit samples are unsigned around 128
+        8 => {
+            for sample in &mut scaled[data] {
+                let centered = *sample as f32 - 128.0;
+                *sample = (centered * volume + 128.0).round() as u8;
+            }
+        }
+        16 => {
+            for sample in scaled[data].chunks_exact_mut(2) {
+                let value = i16::from_le_bytes([sample[0], sample[1]]) as f32 * volume;
+                sample.copy_from_slice(&(value.round() as i16).to_le_bytes());
+            }
+        }
+        _ => return Err(invalid("only 8- and 16-bit samples are supported")),
+    }
+
+    Ok(scaled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal 16-bit mono PCM WAV holding `samples`
+    fn wav_16(samples: &[i16]) -> Vec<u8> {
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+        wav.extend_from_slice(b"fmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+        wav
+    }
+
+    #[test]
+    fn test_recording_notifier_shares_notifications_between_clones() {
+        let recorder = RecordingNotifier::new();
+        let notifier: Box<dyn Notifier> = Box::new(recorder.clone());
+
+        notifier.notify("Extraction complete", "120 files copied", Severity::Success);
+
+        assert_eq!(
+            recorder.notifications(),
+            vec![Notification {
+                title: "Extraction complete".to_string(),
+                body: "120 files copied".to_string(),
+                severity: Severity::Success,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_toast_xml_escapes_text() {
+        let xml = toast_xml("Tom & Jerry's <iPhone>", "done");
+        assert!(xml.contains("<text>Tom &amp; Jerry&apos;s &lt;iPhone&gt;</text><text>done</text>"));
+    }
+
+    #[test]
+    fn test_scale_wav_volume() {
+        let wav = wav_16(&[1000, -2000, i16::MAX]);
+        let scaled = scale_wav_volume(&wav, 0.5).unwrap();
+
+        assert_eq!(scaled.len(), wav.len());
+        assert_eq!(scaled[..44], wav[..44]);
+        let samples: Vec<i16> = scaled[44..]
+            .chunks_exact(2)
+            .map(|s| i16::from_le_bytes([s[0], s[1]]))
+            .collect();
+        assert_eq!(samples, vec![500, -1000, 16384]);
+
+        assert!(scale_wav_volume(b"not a wav file", 1.0).is_err());
+    }
+}
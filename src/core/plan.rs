@@ -0,0 +1,232 @@
+//! Extraction plans
+//!
+//! A plan answers "what is new since my last backup?" before anything is
+//! copied. Every media file on the device is compared with the tracking store
+//! and classified as new, already extracted, or changed since it was
+//! extracted. Unlike a dry run, planning is a pure comparison pass: nothing is
+//! written, no manifest is produced and the tracking store is not updated.
+//!
+//! Sizes are always compared. Content hashes are only compared when asked for,
+//! because that means reading every previously extracted file from the device.
+
+use crate::core::error::Result;
+use crate::core::tracking::TrackingStoreTrait;
+use crate::duplicate::{compute_data_hash, hash_to_hex};
+use serde::{Deserialize, Serialize};
+
+/// How a device file compares with the tracking store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStatus {
+    /// Never extracted
+    New,
+    /// Extracted before and unchanged as far as the store can tell
+    AlreadyHave,
+    /// Extracted before, but its size or content now differs
+    Changed,
+}
+
+impl PlanStatus {
+    /// Name used in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlanStatus::New => "new",
+            PlanStatus::AlreadyHave => "already_have",
+            PlanStatus::Changed => "changed",
+        }
+    }
+}
+
+/// One device file in a plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedFile {
+    /// Device object ID
+    pub object_id: String,
+    /// Path on the device
+    pub path: String,
+    /// Size on the device in bytes
+    pub size: u64,
+    /// How the file compares with the tracking store
+    pub status: PlanStatus,
+}
+
+/// Number of files and their total size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanCount {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl PlanCount {
+    fn add(&mut self, size: u64) {
+        self.files += 1;
+        self.bytes += size;
+    }
+}
+
+/// Totals for each [`PlanStatus`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanSummary {
+    pub new: PlanCount,
+    pub already_have: PlanCount,
+    pub changed: PlanCount,
+}
+
+impl PlanSummary {
+    /// Files an extraction would copy (new and changed)
+    pub fn to_copy(&self) -> PlanCount {
+        PlanCount {
+            files: self.new.files + self.changed.files,
+            bytes: self.new.bytes + self.changed.bytes,
+        }
+    }
+}
+
+/// Comparison of a device's files with the tracking store
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractionPlan {
+    pub summary: PlanSummary,
+    /// Files in device scan order
+    pub files: Vec<PlannedFile>,
+}
+
+impl ExtractionPlan {
+    /// Create an empty plan
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a classified file, updating the summary
+    pub fn add(&mut self, file: PlannedFile) {
+        let count = match file.status {
+            PlanStatus::New => &mut self.summary.new,
+            PlanStatus::AlreadyHave => &mut self.summary.already_have,
+            PlanStatus::Changed => &mut self.summary.changed,
+        };
+        count.add(file.size);
+        self.files.push(file);
+    }
+
+    /// Files with the given status, in device scan order
+    pub fn with_status(&self, status: PlanStatus) -> impl Iterator<Item = &PlannedFile> {
+        self.files.iter().filter(move |file| file.status == status)
+    }
+}
+
+/// Classify one device file against `store`
+///
+/// A file is changed when its recorded size differs from `size`, or when
+/// `read` is given, a hash was recorded and the content read now hashes
+/// differently. Records without a size (files tracked before sizes were
+/// stored) only compare by hash.
+pub fn classify<S, F>(store: &S, object_id: &str, size: u64, read: Option<F>) -> Result<PlanStatus>
+where
+    S: TrackingStoreTrait + ?Sized,
+    F: FnOnce() -> Result<Vec<u8>>,
+{
+    let Some(record) = store.get(object_id)? else {
+        return Ok(PlanStatus::New);
+    };
+
+    if record.size != 0 && size != 0 && record.size != size {
+        return Ok(PlanStatus::Changed);
+    }
+    if let (Some(read), Some(recorded)) = (read, record.hash.as_deref()) {
+        let hash = hash_to_hex(&compute_data_hash(&read()?));
+        if !hash.eq_ignore_ascii_case(recorded) {
+            return Ok(PlanStatus::Changed);
+        }
+    }
+    Ok(PlanStatus::AlreadyHave)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::TrackingConfig;
+    use crate::core::tracking::{StateTracker, TrackedFile};
+
+    #[test]
+    fn test_classify_compares_size_and_optional_hash() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut store = StateTracker::new(&TrackingConfig::default(), temp.path());
+        let content = b"photo bytes".to_vec();
+        let hash = hash_to_hex(&compute_data_hash(&content));
+        store
+            .insert(&TrackedFile::new("o1", Some(hash), content.len() as u64).with_destination("a"))
+            .unwrap();
+        store.insert(&TrackedFile::new("legacy", None, 0)).unwrap();
+
+        let no_read = None::<fn() -> Result<Vec<u8>>>;
+        let size = content.len() as u64;
+        assert_eq!(
+            classify(&store, "o2", size, no_read).unwrap(),
+            PlanStatus::New
+        );
+        assert_eq!(
+            classify(&store, "o1", size, no_read).unwrap(),
+            PlanStatus::AlreadyHave
+        );
+        assert_eq!(
+            classify(&store, "o1", size + 1, no_read).unwrap(),
+            PlanStatus::Changed
+        );
+        assert_eq!(
+            classify(&store, "o1", size, Some(|| Ok(b"edited bytes".to_vec()))).unwrap(),
+            PlanStatus::Changed
+        );
+        assert_eq!(
+            classify(&store, "o1", size, Some(|| Ok(content.clone()))).unwrap(),
+            PlanStatus::AlreadyHave
+        );
+        assert_eq!(
+            classify(&store, "legacy", 4096, no_read).unwrap(),
+            PlanStatus::AlreadyHave
+        );
+    }
+
+    #[test]
+    fn test_plan_summary_and_json() {
+        let mut plan = ExtractionPlan::new();
+        for (id, size, status) in [
+            ("a", 10, PlanStatus::New),
+            ("b", 20, PlanStatus::AlreadyHave),
+            ("c", 30, PlanStatus::Changed),
+            ("d", 40, PlanStatus::New),
+        ] {
+            plan.add(PlannedFile {
+                object_id: id.to_string(),
+                path: format!("DCIM/{}.JPG", id),
+                size,
+                status,
+            });
+        }
+
+        assert_eq!(
+            plan.summary.new,
+            PlanCount {
+                files: 2,
+                bytes: 50
+            }
+        );
+        assert_eq!(
+            plan.summary.already_have,
+            PlanCount {
+                files: 1,
+                bytes: 20
+            }
+        );
+        assert_eq!(
+            plan.summary.to_copy(),
+            PlanCount {
+                files: 3,
+                bytes: 80
+            }
+        );
+        assert_eq!(plan.with_status(PlanStatus::Changed).count(), 1);
+
+        let json = serde_json::to_string(&plan).unwrap();
+        assert!(json.contains("\"status\":\"already_have\""));
+        assert_eq!(serde_json::from_str::<ExtractionPlan>(&json).unwrap(), plan);
+    }
+}
@@ -0,0 +1,276 @@
+//! HTML extraction report
+//!
+//! An optional, self-contained HTML page summarizing an extraction, meant for
+//! sharing: how many files were extracted and their total size, then the
+//! extracted files grouped by capture date. Styles are inlined, so the page
+//! needs nothing beside it. The report is built from the same
+//! [`ManifestEntry`] values as the manifest and written in one go when the
+//! extraction finishes.
+
+use crate::core::error::{ExtractionError, Result};
+use crate::core::extractor::format_size;
+use crate::core::manifest::{ManifestEntry, ManifestOutcome};
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Styles inlined into the report so it renders the same anywhere
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;margin:2em;color:#222;background:#fafafa}\
+h2{margin-top:2em;border-bottom:1px solid #ddd}\
+.count{color:#666}\
+.gallery{list-style:none;padding:0;display:flex;flex-wrap:wrap;gap:12px}\
+.gallery figure{margin:0}\
+.gallery figcaption{font-size:12px;overflow-wrap:anywhere}\
+.size{color:#666}";
+
+/// HTML report of one extraction, written by [`finish`](Self::finish)
+pub struct HtmlReport {
+    path: PathBuf,
+    title: String,
+    generated_at: DateTime<Local>,
+    entries: Vec<ManifestEntry>,
+}
+
+impl HtmlReport {
+    /// Start a report to be written to `path`, titled after the device
+    pub fn new(path: &Path, device_name: Option<&str>) -> Self {
+        let title = match device_name.filter(|name| !name.is_empty()) {
+            Some(name) => format!("Photos extracted from {}", name),
+            None => "Extracted photos".to_string(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            title,
+            generated_at: Local::now(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Where the report will be written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Add a processed file
+    ///
+    /// Only extracted files are listed; the others count towards the
+    /// skipped, duplicate and error totals.
+    pub fn record(&mut self, entry: &ManifestEntry) {
+        self.entries.push(entry.clone());
+    }
+
+    /// Number of files recorded with the given outcome
+    fn count(&self, outcome: ManifestOutcome) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.outcome == outcome)
+            .count()
+    }
+
+    /// Render the report as a complete HTML document
+    pub fn render(&self) -> String {
+        let extracted: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.outcome == ManifestOutcome::Extracted)
+            .collect();
+        let total_size: u64 = extracted.iter().map(|entry| entry.size).sum();
+
+        // Oldest first, with undated files at the end
+        let mut groups: BTreeMap<(bool, Option<NaiveDate>), Vec<&ManifestEntry>> = BTreeMap::new();
+        for entry in &extracted {
+            groups
+                .entry((entry.capture_date.is_none(), entry.capture_date))
+                .or_default()
+                .push(entry);
+        }
+
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n",
+            title = escape_html(&self.title),
+        );
+        let _ = writeln!(
+            html,
+            "<p class=\"summary\">{} extracted ({}); {} skipped, {}, {}. Generated {}.</p>",
+            plural(extracted.len(), "file"),
+            format_size(total_size),
+            self.count(ManifestOutcome::Skipped),
+            plural(self.count(ManifestOutcome::Duplicate), "duplicate"),
+            plural(self.count(ManifestOutcome::Error), "error"),
+            self.generated_at.format("%Y-%m-%d %H:%M"),
+        );
+
+        for ((_, date), entries) in &groups {
+            let heading = match date {
+                Some(date) => date.format("%Y-%m-%d").to_string(),
+                None => "Unknown date".to_string(),
+            };
+            let size: u64 = entries.iter().map(|entry| entry.size).sum();
+            let _ = writeln!(
+                html,
+                "<section>\n<h2>{}</h2>\n<p class=\"count\">{}, {}</p>\n<ul class=\"gallery\">",
+                heading,
+                plural(entries.len(), "file"),
+                format_size(size)
+            );
+            for entry in entries {
+                Self::render_entry(&mut html, entry);
+            }
+            html.push_str("</ul>\n</section>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Render one file of the gallery
+    fn render_entry(html: &mut String, entry: &ManifestEntry) {
+        let path = entry
+            .destination_path
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_else(|| entry.source_path.clone());
+        let name = path.rsplit(['/', '\\']).next().unwrap_or(&path);
+
+        let _ = writeln!(
+            html,
+            "<li><figure title=\"{}\"><figcaption>{}<br><span class=\"size\">{}</span>\
+             </figcaption></figure></li>",
+            escape_html(&path),
+            escape_html(name),
+            format_size(entry.size)
+        );
+    }
+
+    /// Write the report, creating its folder if needed
+    pub fn finish(self) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to create report directory '{}': {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+
+        fs::write(&self.path, self.render()).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to write HTML report '{}': {}",
+                self.path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// "1 file" or "N files" for `noun` = "file"
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(id: &str, date: Option<(i32, u32, u32)>, outcome: ManifestOutcome) -> ManifestEntry {
+        let mut entry = ManifestEntry::new(id, format!("DCIM/100APPLE/{}.JPG", id), 2048, outcome);
+        entry.destination_path = Some(PathBuf::from(format!("out/{}.JPG", id)));
+        entry.capture_date = date.and_then(|(y, m, d)| NaiveDate::from_ymd_opt(y, m, d));
+        entry
+    }
+
+    fn sample_report(path: &Path) -> HtmlReport {
+        let mut report = HtmlReport::new(path, Some("John's <iPhone>"));
+        for entry in [
+            entry("IMG_0003", Some((2024, 3, 16)), ManifestOutcome::Extracted),
+            entry("IMG_0001", Some((2024, 3, 15)), ManifestOutcome::Extracted),
+            entry("IMG_0002", Some((2024, 3, 15)), ManifestOutcome::Extracted),
+            entry("IMG_0004", None, ManifestOutcome::Extracted),
+            entry("IMG_0005", Some((2024, 3, 15)), ManifestOutcome::Skipped),
+            entry("IMG_0006", Some((2024, 3, 17)), ManifestOutcome::Error),
+        ] {
+            report.record(&entry);
+        }
+        report
+    }
+
+    #[test]
+    fn test_report_groups_extracted_files_by_date() {
+        let html = sample_report(Path::new("report.html")).render();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Photos extracted from John&#39;s &lt;iPhone&gt;</title>"));
+        assert!(html.contains("4 files extracted (8.0 KB); 1 skipped, 0 duplicates, 1 error."));
+        assert_eq!(html.matches("<figure").count(), 4);
+
+        // Date headings in order, undated last, with per-day counts
+        let headings: Vec<_> = html
+            .match_indices("<h2>")
+            .map(|(start, _)| &html[start + 4..start + 4 + html[start + 4..].find('<').unwrap()])
+            .collect();
+        assert_eq!(headings, ["2024-03-15", "2024-03-16", "Unknown date"]);
+        assert!(html.contains("<p class=\"count\">2 files, 4.0 KB</p>"));
+        assert!(html.contains("<p class=\"count\">1 file, 2.0 KB</p>"));
+
+        // Files that weren't extracted are only counted
+        assert!(!html.contains("IMG_0005"));
+        assert!(!html.contains("IMG_0006"));
+        assert!(!html.contains("2024-03-17"));
+    }
+
+    #[test]
+    fn test_summary_pluralises_counts() {
+        let mut report = HtmlReport::new(Path::new("report.html"), None);
+        for entry in [
+            entry("IMG_0001", None, ManifestOutcome::Extracted),
+            entry("IMG_0002", None, ManifestOutcome::Duplicate),
+            entry("IMG_0003", None, ManifestOutcome::Error),
+            entry("IMG_0004", None, ManifestOutcome::Error),
+        ] {
+            report.record(&entry);
+        }
+
+        let html = report.render();
+        assert!(html.contains("1 file extracted (2.0 KB); 0 skipped, 1 duplicate, 2 errors."));
+    }
+
+    #[test]
+    fn test_finish_writes_the_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("reports").join("backup.html");
+
+        sample_report(&path).finish().unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("4 files extracted"));
+        assert!(written.contains("<h2>2024-03-15</h2>"));
+    }
+}
@@ -0,0 +1,321 @@
+//! Retry support for flaky device operations
+//!
+//! WPD reads over USB occasionally fail for a moment (a cable hiccup, the
+//! device briefly busy) and succeed when tried again. This module wraps device
+//! calls in a retry loop with exponential backoff. Only errors that
+//! [`ExtractionError::is_retryable`] considers transient are retried, and the
+//! shutdown flag is checked between attempts so cancellation stays responsive.
+//!
+//! A device that drops off the bus altogether (a loose cable, the phone
+//! rebooting) can't be retried through the old handle. [`wait_for_device`]
+//! waits for it to come back so the extraction can reopen it and continue.
+
+#![allow(unused)]
+
+use crate::core::error::{ExtractionError, Result};
+use crate::device::traits::DeviceManagerTrait;
+use log::{debug, info};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Upper bound on a single backoff delay
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// How often the shutdown flag is checked while waiting to retry
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often the device list is checked while waiting for a dropped device
+const RECONNECT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Times a single file is reopened after the device drops before giving up on it
+pub const MAX_RECONNECTS_PER_FILE: usize = 3;
+
+/// Retry settings for device operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt (0 = no retries)
+    pub max_retries: usize,
+    /// Delay before the first retry; doubled for each further retry
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(250),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with the given retry count and base delay in milliseconds
+    pub fn new(max_retries: usize, base_delay_ms: u64) -> Self {
+        Self {
+            max_retries,
+            base_delay: Duration::from_millis(base_delay_ms),
+        }
+    }
+
+    /// A policy that never retries
+    pub fn none() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Delay before the given retry (1-based), capped at [`MAX_RETRY_DELAY`]
+    pub fn delay_for(&self, retry: usize) -> Duration {
+        let factor = 1u32 << (retry.saturating_sub(1).min(16) as u32);
+        self.base_delay.saturating_mul(factor).min(MAX_RETRY_DELAY)
+    }
+}
+
+/// Runs device operations under a [`RetryPolicy`] and counts the retries made
+#[derive(Debug)]
+pub struct Retrier {
+    policy: RetryPolicy,
+    shutdown_flag: Arc<AtomicBool>,
+    retries: AtomicUsize,
+}
+
+impl Retrier {
+    /// Create a retrier that stops retrying once `shutdown_flag` is set
+    pub fn new(policy: RetryPolicy, shutdown_flag: Arc<AtomicBool>) -> Self {
+        Self {
+            policy,
+            shutdown_flag,
+            retries: AtomicUsize::new(0),
+        }
+    }
+
+    /// Total number of retries made so far
+    pub fn retries(&self) -> usize {
+        self.retries.load(Ordering::SeqCst)
+    }
+
+    /// Run `op`, retrying transient failures with exponential backoff
+    ///
+    /// `what` describes the operation for log messages. Permanent errors and
+    /// the last failure after all retries are returned unchanged.
+    pub fn run<T, F>(&self, what: &str, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut attempt = 0;
+        loop {
+            let error = match op() {
+                Ok(value) => return Ok(value),
+                Err(e) => e,
+            };
+
+            if attempt >= self.policy.max_retries
+                || !error.is_retryable()
+                || self.shutdown_requested()
+            {
+                return Err(error);
+            }
+
+            attempt += 1;
+            self.retries.fetch_add(1, Ordering::SeqCst);
+            let delay = self.policy.delay_for(attempt);
+            debug!(
+                "{} failed ({}), retrying in {}ms ({}/{})",
+                what,
+                error,
+                delay.as_millis(),
+                attempt,
+                self.policy.max_retries
+            );
+
+            if !self.wait(delay) {
+                return Err(error);
+            }
+        }
+    }
+
+    fn shutdown_requested(&self) -> bool {
+        self.shutdown_flag.load(Ordering::SeqCst)
+    }
+
+    /// Sleep for `delay`, waking early on shutdown. Returns false if interrupted.
+    fn wait(&self, delay: Duration) -> bool {
+        wait_unless_shutdown(delay, &self.shutdown_flag)
+    }
+}
+
+/// Sleep for `delay`, waking early on shutdown. Returns false if interrupted.
+fn wait_unless_shutdown(delay: Duration, shutdown_flag: &AtomicBool) -> bool {
+    let mut remaining = delay;
+    while !remaining.is_zero() {
+        if shutdown_flag.load(Ordering::SeqCst) {
+            return false;
+        }
+        let step = remaining.min(SHUTDOWN_POLL_INTERVAL);
+        std::thread::sleep(step);
+        remaining -= step;
+    }
+    !shutdown_flag.load(Ordering::SeqCst)
+}
+
+/// Wait for a dropped device to come back and open it again
+///
+/// Checks `manager`'s device list for `device_id` every
+/// [`RECONNECT_POLL_INTERVAL`] and opens the device once it is listed and
+/// answers. Gives up with [`ExtractionError::DeviceDisconnected`] after
+/// `timeout`, or with [`ExtractionError::Cancelled`] once `shutdown_flag` is set.
+pub fn wait_for_device<M: DeviceManagerTrait>(
+    manager: &M,
+    device_id: &str,
+    timeout: Duration,
+    shutdown_flag: &AtomicBool,
+) -> Result<M::Content> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if shutdown_flag.load(Ordering::SeqCst) {
+            return Err(ExtractionError::Cancelled);
+        }
+
+        let listed = manager
+            .enumerate_all_devices()
+            .map(|devices| devices.iter().any(|d| d.device_id == device_id))
+            .unwrap_or(false);
+        if listed {
+            match manager.open_device(device_id) {
+                Ok(content) => {
+                    info!("Device {} reconnected", device_id);
+                    return Ok(content);
+                }
+                Err(e) => debug!(
+                    "Device {} is back but could not be opened: {}",
+                    device_id, e
+                ),
+            }
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            debug!(
+                "Device {} did not reconnect within {}s",
+                device_id,
+                timeout.as_secs()
+            );
+            return Err(ExtractionError::DeviceDisconnected);
+        }
+        if !wait_unless_shutdown(remaining.min(RECONNECT_POLL_INTERVAL), shutdown_flag) {
+            return Err(ExtractionError::Cancelled);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn transient() -> ExtractionError {
+        ExtractionError::DeviceError("cable hiccup".to_string())
+    }
+
+    #[test]
+    fn test_delay_doubles_and_caps() {
+        let policy = RetryPolicy::new(5, 100);
+        assert_eq!(policy.delay_for(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(200));
+        assert_eq!(policy.delay_for(3), Duration::from_millis(400));
+        assert_eq!(policy.delay_for(40), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_retries_transient_errors_until_success() {
+        let retrier = Retrier::new(RetryPolicy::new(3, 0), Arc::new(AtomicBool::new(false)));
+        let calls = Cell::new(0);
+
+        let result = retrier.run("read", || {
+            calls.set(calls.get() + 1);
+            if calls.get() <= 2 {
+                Err(transient())
+            } else {
+                Ok(42)
+            }
+        });
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.get(), 3);
+        assert_eq!(retrier.retries(), 2);
+    }
+
+    #[test]
+    fn test_gives_up_after_max_retries() {
+        let retrier = Retrier::new(RetryPolicy::new(2, 0), Arc::new(AtomicBool::new(false)));
+        let calls = Cell::new(0);
+
+        let result: Result<()> = retrier.run("read", || {
+            calls.set(calls.get() + 1);
+            Err(transient())
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 3);
+        assert_eq!(retrier.retries(), 2);
+    }
+
+    #[test]
+    fn test_permanent_errors_are_not_retried() {
+        let retrier = Retrier::new(RetryPolicy::new(3, 0), Arc::new(AtomicBool::new(false)));
+        let calls = Cell::new(0);
+
+        let result: Result<()> = retrier.run("read", || {
+            calls.set(calls.get() + 1);
+            Err(ExtractionError::ContentError(
+                "Object not found".to_string(),
+            ))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+        assert_eq!(retrier.retries(), 0);
+    }
+
+    #[test]
+    fn test_shutdown_stops_retrying() {
+        let flag = Arc::new(AtomicBool::new(true));
+        let retrier = Retrier::new(RetryPolicy::new(3, 0), flag);
+        let calls = Cell::new(0);
+
+        let result: Result<()> = retrier.run("read", || {
+            calls.set(calls.get() + 1);
+            Err(transient())
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_wait_for_device() {
+        use crate::device::traits::DeviceInfo;
+        use crate::testdb::{MockDeviceManager, MockFileSystem};
+
+        let mut manager = MockDeviceManager::new();
+        manager.add_device(
+            DeviceInfo::new("iphone", "iPhone", "Apple Inc.", "iPhone 15"),
+            MockFileSystem::new(),
+        );
+        let running = AtomicBool::new(false);
+
+        assert!(wait_for_device(&manager, "iphone", Duration::ZERO, &running).is_ok());
+        assert!(matches!(
+            wait_for_device(&manager, "missing", Duration::from_millis(10), &running),
+            Err(ExtractionError::DeviceDisconnected)
+        ));
+        assert!(matches!(
+            wait_for_device(
+                &manager,
+                "iphone",
+                Duration::from_secs(60),
+                &AtomicBool::new(true)
+            ),
+            Err(ExtractionError::Cancelled)
+        ));
+    }
+}
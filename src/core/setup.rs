@@ -427,8 +427,15 @@ profiles_file = "./.device_profiles.json"
 directory = "{}"
 preserve_structure = {}
 skip_existing = {}
+skip_existing_mode = "{}"
+long_path_strategy = "{}"
 organize_by_date = {}
+preserve_live_photo_pairs = {}
+group_bursts = {}
+space_safety_margin_mb = {}
+manifest_format = "{}"
 subfolder_by_device = {}
+# path_template = "{{device}}/{{year}}/{{month}}/{{original_name}}"
 
 # ┌──────────────────────────────────────────────────────────────────────────────┐
 # │                            📱 DEVICE SETTINGS                                │
@@ -443,10 +450,19 @@ apple_only = {}
 dcim_only = {}
 include_extensions = []
 exclude_extensions = []
+include_globs = []
+exclude_globs = []
 min_file_size = 0
 max_file_size = 0
 include_photos = {}
 include_videos = {}
+include_undated = {}
+verify_after_copy = {}
+verify_retries = {}
+delete_after_extract = {}
+max_retries = {}
+retry_base_delay_ms = {}
+reconnect_timeout_secs = {}
 
 # ┌──────────────────────────────────────────────────────────────────────────────┐
 # │                            📋 LOGGING SETTINGS                               │
@@ -515,7 +531,13 @@ exclude_folders = [".thumbnails", ".trash", ".cache", ".nomedia"]
         backup_dir,
         config.output.preserve_structure,
         config.output.skip_existing,
+        config.output.skip_existing_mode,
+        config.output.long_path_strategy,
         config.output.organize_by_date,
+        config.output.preserve_live_photo_pairs,
+        config.output.group_bursts,
+        config.output.space_safety_margin_mb,
+        config.output.manifest_format,
         config.output.subfolder_by_device,
         // device
         config.device.apple_only,
@@ -523,6 +545,13 @@ exclude_folders = [".thumbnails", ".trash", ".cache", ".nomedia"]
         config.extraction.dcim_only,
         config.extraction.include_photos,
         config.extraction.include_videos,
+        config.extraction.include_undated,
+        config.extraction.verify_after_copy,
+        config.extraction.verify_retries,
+        config.extraction.delete_after_extract,
+        config.extraction.max_retries,
+        config.extraction.retry_base_delay_ms,
+        config.extraction.reconnect_timeout_secs,
         // logging
         config.logging.level,
         config.logging.log_to_file,
@@ -541,7 +570,7 @@ exclude_folders = [".thumbnails", ".trash", ".cache", ".nomedia"]
             .display()
             .to_string()
             .replace('\\', "/"),
-        format!("{:?}", config.duplicate_detection.duplicate_action).to_lowercase(),
+        config.duplicate_detection.duplicate_action.as_str(),
         config.duplicate_detection.recursive,
         config.duplicate_detection.media_only,
         // tracking
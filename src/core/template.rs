@@ -0,0 +1,502 @@
+//! Output path templates
+//!
+//! A [`PathTemplate`] such as `{device}/{year}/{month}/{original_name}`
+//! describes where each extracted file goes, relative to the output folder.
+//! Templates are parsed once, so a typo in a token is reported when the
+//! configuration loads rather than half way through an extraction, and then
+//! resolved for every file.
+//!
+//! The older `preserve_structure`, `organize_by_date` and
+//! `subfolder_by_device` options map onto templates through
+//! [`PathTemplate::from_options`].
+
+use crate::core::metadata::UNDATED_FOLDER;
+use crate::core::names::normalize_name;
+use chrono::{Datelike, NaiveDate};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Tokens accepted in a path template, without braces
+pub const TEMPLATE_TOKENS: &[&str] = &[
+    "device",
+    "folder",
+    "year",
+    "month",
+    "day",
+    "original_name",
+    "stem",
+    "ext",
+];
+
+/// Folder used for `{device}` when the device name is unknown
+pub const UNKNOWN_DEVICE_FOLDER: &str = "Unknown Device";
+
+/// A value substituted into a template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    /// Device name
+    Device,
+    /// Folders above the file on the device
+    Folder,
+    /// Four-digit year of the capture date
+    Year,
+    /// Two-digit month of the capture date
+    Month,
+    /// Two-digit day of the capture date
+    Day,
+    /// File name as stored on the device
+    OriginalName,
+    /// File name without its extension
+    Stem,
+    /// Extension without the dot
+    Ext,
+}
+
+impl Token {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "device" => Token::Device,
+            "folder" => Token::Folder,
+            "year" => Token::Year,
+            "month" => Token::Month,
+            "day" => Token::Day,
+            "original_name" => Token::OriginalName,
+            "stem" => Token::Stem,
+            "ext" => Token::Ext,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Token::Device => "device",
+            Token::Folder => "folder",
+            Token::Year => "year",
+            Token::Month => "month",
+            Token::Day => "day",
+            Token::OriginalName => "original_name",
+            Token::Stem => "stem",
+            Token::Ext => "ext",
+        }
+    }
+
+    fn is_date(self) -> bool {
+        matches!(self, Token::Year | Token::Month | Token::Day)
+    }
+}
+
+/// Part of one path component
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Literal(String),
+    Token(Token),
+}
+
+/// Why a template could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// The template is blank
+    Empty,
+    /// `{name}` is not one of [`TEMPLATE_TOKENS`]
+    UnknownToken(String),
+    /// A `{` without its `}`, or a `}` without its `{`
+    UnbalancedBrace,
+    /// The template starts at a drive or root, or climbs out with `..`
+    NotRelative,
+    /// A literal part holds a character Windows doesn't allow in file names
+    InvalidCharacter(char),
+    /// Nothing in the template names the file, so every file would collide
+    MissingFileName,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Empty => write!(f, "a non-empty template"),
+            TemplateError::UnknownToken(name) => write!(f, "unknown token {{{}}}", name),
+            TemplateError::UnbalancedBrace => write!(f, "every '{{' closed by a '}}'"),
+            TemplateError::NotRelative => {
+                write!(f, "a relative path without '..' components")
+            }
+            TemplateError::InvalidCharacter(c) => {
+                write!(f, "no {:?} outside of tokens", c)
+            }
+            TemplateError::MissingFileName => {
+                write!(f, "{{original_name}} or {{stem}} in the template")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// The file a template is resolved for
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TemplateFile<'a> {
+    /// Name of the device the file comes from (empty = unknown)
+    pub device: &'a str,
+    /// Path of the file on the device, including its name
+    pub path: &'a str,
+    /// File name
+    pub name: &'a str,
+    /// Capture date (EXIF, else the device's modified date)
+    pub date: Option<NaiveDate>,
+}
+
+/// A parsed output path template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathTemplate {
+    /// Path components, each made of literals and tokens
+    components: Vec<Vec<Piece>>,
+}
+
+impl PathTemplate {
+    /// Parse a template such as `{device}/{year}/{month}/{original_name}`
+    ///
+    /// Components are separated by `/` or `\`.
+    pub fn parse(template: &str) -> Result<Self, TemplateError> {
+        let template = template.trim();
+        if template.is_empty() {
+            return Err(TemplateError::Empty);
+        }
+        if template.starts_with(['/', '\\']) || template.chars().nth(1) == Some(':') {
+            return Err(TemplateError::NotRelative);
+        }
+
+        let mut components = Vec::new();
+        for component in template.split(['/', '\\']) {
+            if component == ".." {
+                return Err(TemplateError::NotRelative);
+            }
+            let pieces = parse_component(component)?;
+            if !pieces.is_empty() && component != "." {
+                components.push(pieces);
+            }
+        }
+
+        let names_file = components.iter().flatten().any(|piece| {
+            matches!(
+                piece,
+                Piece::Token(Token::OriginalName) | Piece::Token(Token::Stem)
+            )
+        });
+        if !names_file {
+            return Err(TemplateError::MissingFileName);
+        }
+
+        Ok(Self { components })
+    }
+
+    /// The template described by the older boolean output options
+    ///
+    /// `organize_by_date` wins over `preserve_structure`, as it always has.
+    pub fn from_options(
+        preserve_structure: bool,
+        organize_by_date: bool,
+        subfolder_by_device: bool,
+    ) -> Self {
+        let mut template = String::new();
+        if subfolder_by_device {
+            template.push_str("{device}/");
+        }
+        if organize_by_date {
+            template.push_str("{year}/{month}/");
+        } else if preserve_structure {
+            template.push_str("{folder}/");
+        }
+        template.push_str("{original_name}");
+        Self::parse(&template).expect("built-in template is valid")
+    }
+
+    /// Whether resolving needs the file's capture date
+    ///
+    /// The date may come from EXIF, so the file content has to be read before
+    /// its output path is known.
+    pub fn uses_date(&self) -> bool {
+        self.components
+            .iter()
+            .flatten()
+            .any(|piece| matches!(piece, Piece::Token(token) if token.is_date()))
+    }
+
+    /// The output path for `file`, relative to the output folder
+    ///
+    /// Token values are made safe for Windows file names. Without a date,
+    /// each component holding a date token becomes a single
+    /// [`UNDATED_FOLDER`], so `{year}/{month}/{original_name}` puts the file
+    /// in `undated/`.
+    pub fn resolve(&self, file: &TemplateFile) -> PathBuf {
+        let mut path = PathBuf::new();
+        let mut undated = false;
+        for pieces in &self.components {
+            let has_date = pieces
+                .iter()
+                .any(|piece| matches!(piece, Piece::Token(token) if token.is_date()));
+            if has_date && file.date.is_none() {
+                if !undated {
+                    path.push(UNDATED_FOLDER);
+                    undated = true;
+                }
+                continue;
+            }
+            undated = false;
+
+            // `{folder}` alone expands to every folder above the file
+            if let [Piece::Token(Token::Folder)] = pieces.as_slice() {
+                path.extend(
+                    device_folders(file.path)
+                        .map(sanitize)
+                        .filter(|folder| !folder.is_empty()),
+                );
+                continue;
+            }
+
+            let component: String = pieces
+                .iter()
+                .map(|piece| match piece {
+                    Piece::Literal(text) => text.clone(),
+                    Piece::Token(token) => sanitize(&token_value(*token, file)),
+                })
+                .collect();
+            if !component.is_empty() {
+                path.push(component);
+            }
+        }
+        path
+    }
+}
+
+impl fmt::Display for PathTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, pieces) in self.components.iter().enumerate() {
+            if index > 0 {
+                f.write_str("/")?;
+            }
+            for piece in pieces {
+                match piece {
+                    Piece::Literal(text) => f.write_str(text)?,
+                    Piece::Token(token) => write!(f, "{{{}}}", token.name())?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split one path component into literals and tokens
+fn parse_component(component: &str) -> Result<Vec<Piece>, TemplateError> {
+    let mut pieces = Vec::new();
+    let mut rest = component;
+    while !rest.is_empty() {
+        match rest.find(['{', '}']) {
+            Some(start) if rest[start..].starts_with('{') => {
+                push_literal(&mut pieces, &rest[..start])?;
+                let end = rest[start..]
+                    .find('}')
+                    .ok_or(TemplateError::UnbalancedBrace)?
+                    + start;
+                let name = &rest[start + 1..end];
+                if name.contains('{') {
+                    return Err(TemplateError::UnbalancedBrace);
+                }
+                let token = Token::parse(name)
+                    .ok_or_else(|| TemplateError::UnknownToken(name.to_string()))?;
+                pieces.push(Piece::Token(token));
+                rest = &rest[end + 1..];
+            }
+            Some(_) => return Err(TemplateError::UnbalancedBrace),
+            None => {
+                push_literal(&mut pieces, rest)?;
+                rest = "";
+            }
+        }
+    }
+    Ok(pieces)
+}
+
+fn push_literal(pieces: &mut Vec<Piece>, text: &str) -> Result<(), TemplateError> {
+    if let Some(c) = text.chars().find(|c| is_reserved(*c)) {
+        return Err(TemplateError::InvalidCharacter(c));
+    }
+    if !text.is_empty() {
+        pieces.push(Piece::Literal(text.to_string()));
+    }
+    Ok(())
+}
+
+fn token_value(token: Token, file: &TemplateFile) -> String {
+    let (stem, ext) = match file.name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, ext),
+        _ => (file.name, ""),
+    };
+    match token {
+        Token::Device if file.device.trim().is_empty() => UNKNOWN_DEVICE_FOLDER.to_string(),
+        Token::Device => file.device.to_string(),
+        Token::Folder => device_folders(file.path).collect::<Vec<_>>().join("_"),
+        Token::Year => file
+            .date
+            .map_or_else(String::new, |d| format!("{:04}", d.year())),
+        Token::Month => file
+            .date
+            .map_or_else(String::new, |d| format!("{:02}", d.month())),
+        Token::Day => file
+            .date
+            .map_or_else(String::new, |d| format!("{:02}", d.day())),
+        Token::OriginalName => file.name.to_string(),
+        Token::Stem => stem.to_string(),
+        Token::Ext => ext.to_string(),
+    }
+}
+
+/// Folders above the file in a device path
+fn device_folders(path: &str) -> impl Iterator<Item = &str> {
+    let mut folders: Vec<&str> = path.split(['/', '\\']).filter(|s| !s.is_empty()).collect();
+    folders.pop();
+    folders.into_iter()
+}
+
+/// Characters Windows doesn't allow in a file or folder name
+fn is_reserved(c: char) -> bool {
+    matches!(c, ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control()
+}
+
+/// Make a token value safe to use as (part of) one path component
+fn sanitize(value: &str) -> String {
+    // The same name from two phones must give the same path
+    let cleaned: String = normalize_name(value)
+        .chars()
+        .map(|c| {
+            if is_reserved(c) || c == '/' || c == '\\' {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    // Windows drops trailing dots and spaces, and `..` would climb out
+    cleaned.trim().trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iphone_file(date: Option<NaiveDate>) -> TemplateFile<'static> {
+        TemplateFile {
+            device: "Ann's iPhone",
+            path: "Internal Storage/DCIM/100APPLE/IMG_0042.HEIC",
+            name: "IMG_0042.HEIC",
+            date,
+        }
+    }
+
+    #[test]
+    fn test_resolve_template_for_known_date_and_device() {
+        let template = PathTemplate::parse("{device}/{year}/{month}/{original_name}").unwrap();
+        let file = iphone_file(NaiveDate::from_ymd_opt(2024, 3, 9));
+        assert!(template.uses_date());
+        assert_eq!(
+            template.resolve(&file),
+            PathBuf::from("Ann's iPhone")
+                .join("2024")
+                .join("03")
+                .join("IMG_0042.HEIC")
+        );
+
+        let template = PathTemplate::parse("{year}-{month}-{day}/{stem}_copy.{ext}").unwrap();
+        assert_eq!(
+            template.resolve(&file),
+            PathBuf::from("2024-03-09").join("IMG_0042_copy.HEIC")
+        );
+
+        // Undated files share one folder however many date components there are
+        let template = PathTemplate::parse("{year}/{month}/{original_name}").unwrap();
+        assert_eq!(
+            template.resolve(&iphone_file(None)),
+            PathBuf::from(UNDATED_FOLDER).join("IMG_0042.HEIC")
+        );
+    }
+
+    #[test]
+    fn test_from_options_matches_boolean_layouts() {
+        let file = iphone_file(NaiveDate::from_ymd_opt(2024, 3, 9));
+        assert_eq!(
+            PathTemplate::from_options(true, false, false).resolve(&file),
+            PathBuf::from("Internal Storage/DCIM/100APPLE/IMG_0042.HEIC")
+        );
+        assert_eq!(
+            PathTemplate::from_options(true, true, true).to_string(),
+            "{device}/{year}/{month}/{original_name}"
+        );
+        assert_eq!(
+            PathTemplate::from_options(false, false, false).resolve(&file),
+            PathBuf::from("IMG_0042.HEIC")
+        );
+        assert!(!PathTemplate::from_options(true, false, true).uses_date());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_templates() {
+        assert_eq!(
+            PathTemplate::parse("{yaer}/{original_name}"),
+            Err(TemplateError::UnknownToken("yaer".to_string()))
+        );
+        assert_eq!(
+            PathTemplate::parse("{year/{original_name}"),
+            Err(TemplateError::UnbalancedBrace)
+        );
+        assert_eq!(
+            PathTemplate::parse("{year}/"),
+            Err(TemplateError::MissingFileName)
+        );
+        assert_eq!(
+            PathTemplate::parse("../{original_name}"),
+            Err(TemplateError::NotRelative)
+        );
+        assert_eq!(
+            PathTemplate::parse("C:/{original_name}"),
+            Err(TemplateError::NotRelative)
+        );
+        assert_eq!(
+            PathTemplate::parse("{year}?/{original_name}"),
+            Err(TemplateError::InvalidCharacter('?'))
+        );
+        assert_eq!(PathTemplate::parse("  "), Err(TemplateError::Empty));
+    }
+
+    #[test]
+    fn test_token_values_are_sanitized() {
+        let template = PathTemplate::parse("{device}/{original_name}").unwrap();
+        let file = TemplateFile {
+            device: "Work: iPhone/2",
+            path: "IMG_0001.JPG",
+            name: "IMG_0001.JPG",
+            date: None,
+        };
+        assert_eq!(
+            template.resolve(&file),
+            PathBuf::from("Work_ iPhone_2").join("IMG_0001.JPG")
+        );
+
+        let unnamed = TemplateFile { device: "", ..file };
+        assert_eq!(
+            template.resolve(&unnamed),
+            PathBuf::from(UNKNOWN_DEVICE_FOLDER).join("IMG_0001.JPG")
+        );
+    }
+
+    #[test]
+    fn test_token_values_are_nfc_normalized() {
+        let template = PathTemplate::parse("{device}/{original_name}").unwrap();
+        let file = TemplateFile {
+            device: "Rene\u{301}'s iPhone",
+            path: "Cafe\u{301}.JPG",
+            name: "Cafe\u{301}.JPG",
+            date: None,
+        };
+        assert_eq!(
+            template.resolve(&file),
+            PathBuf::from("Ren\u{e9}'s iPhone").join("Caf\u{e9}.JPG")
+        );
+    }
+}
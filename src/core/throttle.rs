@@ -0,0 +1,144 @@
+//! Transfer rate limiting
+//!
+//! A background extraction running flat out can saturate the USB bus and leave
+//! the phone sluggish. [`Throttle`] is a token bucket shared by every file
+//! copied during an extraction: each chunk read from the device spends tokens,
+//! and the reader sleeps whenever the bucket runs dry so the average rate stays
+//! under the configured cap. The shutdown flag is polled while sleeping, and a
+//! shutdown lifts the limit so the copy in progress can stop promptly.
+
+#![allow(unused)]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Longest single sleep between shutdown flag checks
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Burst allowance, as a fraction of one second's worth of bytes
+const BURST_FRACTION: f64 = 0.1;
+
+#[derive(Debug)]
+struct Bucket {
+    /// Bytes that may be written without waiting (negative = in debt)
+    tokens: f64,
+    /// When the bucket was last refilled
+    last_refill: Instant,
+}
+
+/// Token-bucket limiter for bytes written during an extraction
+#[derive(Debug)]
+pub struct Throttle {
+    max_bytes_per_sec: Option<u64>,
+    capacity: f64,
+    bucket: Mutex<Bucket>,
+    shutdown_flag: Arc<AtomicBool>,
+}
+
+impl Throttle {
+    /// Create a limiter capped at `max_bytes_per_sec` (`None` = unlimited)
+    pub fn new(max_bytes_per_sec: Option<u64>, shutdown_flag: Arc<AtomicBool>) -> Self {
+        let max_bytes_per_sec = max_bytes_per_sec.filter(|&rate| rate > 0);
+        let capacity = max_bytes_per_sec
+            .map(|rate| (rate as f64 * BURST_FRACTION).max(1.0))
+            .unwrap_or(0.0);
+
+        Self {
+            max_bytes_per_sec,
+            capacity,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+            shutdown_flag,
+        }
+    }
+
+    /// A limiter that never waits
+    pub fn unlimited() -> Self {
+        Self::new(None, Arc::new(AtomicBool::new(false)))
+    }
+
+    /// The configured cap, if any
+    pub fn max_bytes_per_sec(&self) -> Option<u64> {
+        self.max_bytes_per_sec
+    }
+
+    /// Account for `bytes` just written, sleeping as needed to respect the cap
+    ///
+    /// Returns early (without waiting out the full delay) once shutdown is
+    /// requested.
+    pub fn consume(&self, bytes: u64) {
+        let Some(rate) = self.max_bytes_per_sec else {
+            return;
+        };
+        let rate = rate as f64;
+
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        self.refill(&mut bucket, rate);
+        bucket.tokens -= bytes as f64;
+
+        while bucket.tokens < 0.0 {
+            if self.shutdown_flag.load(Ordering::SeqCst) {
+                bucket.tokens = 0.0;
+                return;
+            }
+            let wait = Duration::from_secs_f64(-bucket.tokens / rate).min(SHUTDOWN_POLL_INTERVAL);
+            std::thread::sleep(wait);
+            self.refill(&mut bucket, rate);
+        }
+    }
+
+    fn refill(&self, bucket: &mut Bucket, rate: f64) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(self.capacity);
+        bucket.last_refill = now;
+    }
+}
+
+impl Default for Throttle {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_never_waits() {
+        let throttle = Throttle::unlimited();
+        let start = Instant::now();
+        throttle.consume(1024 * 1024 * 1024);
+        assert!(start.elapsed() < Duration::from_millis(50));
+        assert_eq!(throttle.max_bytes_per_sec(), None);
+    }
+
+    #[test]
+    fn test_zero_rate_means_unlimited() {
+        let throttle = Throttle::new(Some(0), Arc::new(AtomicBool::new(false)));
+        assert_eq!(throttle.max_bytes_per_sec(), None);
+    }
+
+    #[test]
+    fn test_consume_holds_rate_under_cap() {
+        let throttle = Throttle::new(Some(100_000), Arc::new(AtomicBool::new(false)));
+        let start = Instant::now();
+        for _ in 0..5 {
+            throttle.consume(10_000);
+        }
+        // 50 KB at 100 KB/s, less the 10 KB burst allowance
+        assert!(start.elapsed() >= Duration::from_millis(380));
+    }
+
+    #[test]
+    fn test_shutdown_lifts_the_limit() {
+        let throttle = Throttle::new(Some(1), Arc::new(AtomicBool::new(true)));
+        let start = Instant::now();
+        throttle.consume(1024 * 1024);
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,130 @@
+//! Transfer speed and time remaining
+//!
+//! Files on a phone range from a few hundred kilobytes to several gigabytes,
+//! so the speed of the file being copied says little about the extraction as
+//! a whole. [`ThroughputEstimator`] keeps the running byte count over the
+//! last few seconds and reports the average over that window, which rides
+//! out a burst of small photos or a stall at the start of a large video
+//! instead of jumping with every file.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How far back the speed is averaged by default
+pub const DEFAULT_THROUGHPUT_WINDOW: Duration = Duration::from_secs(5);
+
+/// Shortest span of samples a speed is reported for
+const MIN_SPAN: Duration = Duration::from_millis(250);
+
+/// Rolling-window estimate of bytes per second
+#[derive(Debug, Clone)]
+pub struct ThroughputEstimator {
+    window: Duration,
+    /// (time, total bytes transferred so far), oldest first
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl ThroughputEstimator {
+    /// Create an estimator averaging over the last `window`
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Record that `transferred` bytes have been copied in total by now
+    pub fn record(&mut self, transferred: u64) {
+        self.record_at(Instant::now(), transferred);
+    }
+
+    /// Record that `transferred` bytes had been copied in total at `now`
+    pub fn record_at(&mut self, now: Instant, transferred: u64) {
+        self.samples.push_back((now, transferred));
+
+        // Keep one sample from before the window so it is spanned in full
+        while self.samples.len() > 2
+            && self
+                .samples
+                .get(1)
+                .is_some_and(|&(time, _)| now.duration_since(time) >= self.window)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Average speed over the window, once it spans long enough to mean anything
+    pub fn bytes_per_sec(&self) -> Option<f64> {
+        let (&(first_time, first_bytes), &(last_time, last_bytes)) =
+            (self.samples.front()?, self.samples.back()?);
+        let span = last_time.duration_since(first_time);
+        if span < MIN_SPAN {
+            return None;
+        }
+        Some(last_bytes.saturating_sub(first_bytes) as f64 / span.as_secs_f64())
+    }
+
+    /// Time left to copy `remaining` bytes at the current speed
+    pub fn eta(&self, remaining: u64) -> Option<Duration> {
+        let rate = self.bytes_per_sec().filter(|&rate| rate > 0.0)?;
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+
+    /// Forget every sample, e.g. when a new extraction starts
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
+
+impl Default for ThroughputEstimator {
+    fn default() -> Self {
+        Self::new(DEFAULT_THROUGHPUT_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_and_eta_from_timed_updates() {
+        let mut estimator = ThroughputEstimator::default();
+        let start = Instant::now();
+        assert_eq!(estimator.bytes_per_sec(), None);
+
+        // 10 s at 1 MB/s, delivered as a tiny photo then a large chunk of
+        // video in turn
+        let mut transferred = 0;
+        for tick in 1..=100u32 {
+            transferred += if tick % 2 == 0 { 190_000 } else { 10_000 };
+            estimator.record_at(
+                start + Duration::from_millis(100 * tick as u64),
+                transferred,
+            );
+        }
+        let speed = estimator.bytes_per_sec().unwrap();
+        assert!((speed - 1_000_000.0).abs() < 50_000.0, "speed {}", speed);
+        let eta = estimator.eta(30_000_000).unwrap().as_secs_f64();
+        assert!((eta - 30.0).abs() < 1.5, "eta {}", eta);
+
+        // After a window at 4 MB/s the old rate no longer counts
+        for tick in 101..=160u32 {
+            transferred += 400_000;
+            estimator.record_at(
+                start + Duration::from_millis(100 * tick as u64),
+                transferred,
+            );
+        }
+        let speed = estimator.bytes_per_sec().unwrap();
+        assert!((speed - 4_000_000.0).abs() < 100_000.0, "speed {}", speed);
+        let eta = estimator.eta(40_000_000).unwrap().as_secs_f64();
+        assert!((eta - 10.0).abs() < 0.5, "eta {}", eta);
+
+        // A stall brings the speed down and leaves no ETA
+        let stalled = start + Duration::from_millis(16_000) + DEFAULT_THROUGHPUT_WINDOW;
+        estimator.record_at(stalled, transferred);
+        estimator.record_at(stalled + Duration::from_secs(1), transferred);
+        assert_eq!(estimator.bytes_per_sec(), Some(0.0));
+        assert_eq!(estimator.eta(1), None);
+    }
+}
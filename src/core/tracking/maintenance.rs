@@ -0,0 +1,283 @@
+//! Checking tracked files against the output directory
+//!
+//! Users move, delete and edit extracted files, so over time the tracking store
+//! drifts from what is actually on disk. A deleted file stays "extracted" and is
+//! never copied again. These checks find that drift: pruning forgets files whose
+//! destination is gone, and verification re-hashes files to catch content that
+//! changed after extraction.
+
+use super::{TrackedFile, TrackingStoreTrait};
+use crate::core::error::Result;
+use crate::duplicate::{compute_file_hash, hash_to_hex};
+use log::debug;
+use std::path::{Path, PathBuf};
+
+/// Outcome of [`TrackingStoreTrait::prune_missing`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Files whose destination was checked
+    pub checked: usize,
+    /// Object IDs forgotten because their destination no longer exists
+    pub removed: Vec<String>,
+    /// Files recorded without a destination, which cannot be checked
+    pub unknown_destination: usize,
+}
+
+/// A tracked file that failed verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyIssue {
+    /// The destination file no longer exists
+    Missing { object_id: String, path: PathBuf },
+    /// The destination file exists but its content differs from what was
+    /// extracted
+    Changed {
+        object_id: String,
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+    /// The destination file exists but could not be read
+    Unreadable {
+        object_id: String,
+        path: PathBuf,
+        error: String,
+    },
+}
+
+impl VerifyIssue {
+    /// Path of the file the issue refers to
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Missing { path, .. }
+            | Self::Changed { path, .. }
+            | Self::Unreadable { path, .. } => path,
+        }
+    }
+}
+
+/// Outcome of [`TrackingStoreTrait::verify`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Files whose destination was checked
+    pub checked: usize,
+    /// Files that exist and match their recorded hash
+    pub ok: usize,
+    /// Files that exist but have no recorded hash to compare against
+    pub unhashed: usize,
+    /// Files recorded without a destination, which cannot be checked
+    pub unknown_destination: usize,
+    /// Files that are missing, changed or unreadable
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    /// Files whose destination no longer exists
+    pub fn missing(&self) -> impl Iterator<Item = &VerifyIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| matches!(issue, VerifyIssue::Missing { .. }))
+    }
+
+    /// Files whose content no longer matches the recorded hash
+    pub fn changed(&self) -> impl Iterator<Item = &VerifyIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| matches!(issue, VerifyIssue::Changed { .. }))
+    }
+
+    /// Whether every checked file was found intact
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+pub(super) fn prune_missing<S: TrackingStoreTrait + ?Sized>(
+    store: &mut S,
+    output_dir: &Path,
+) -> Result<PruneReport> {
+    let mut report = PruneReport::default();
+
+    for file in store.files()? {
+        let Some(path) = destination(&file, output_dir) else {
+            report.unknown_destination += 1;
+            continue;
+        };
+        report.checked += 1;
+
+        if !path.exists() {
+            debug!("Pruning {}: {} is gone", file.object_id, path.display());
+            store.remove(&file.object_id)?;
+            report.removed.push(file.object_id);
+        }
+    }
+
+    Ok(report)
+}
+
+pub(super) fn verify<S: TrackingStoreTrait + ?Sized>(
+    store: &S,
+    output_dir: &Path,
+    sample: Option<usize>,
+) -> Result<VerifyReport> {
+    let mut report = VerifyReport::default();
+    let files = store.files()?;
+
+    for file in sampled(&files, sample) {
+        let Some(path) = destination(file, output_dir) else {
+            report.unknown_destination += 1;
+            continue;
+        };
+        report.checked += 1;
+
+        if !path.exists() {
+            report.issues.push(VerifyIssue::Missing {
+                object_id: file.object_id.clone(),
+                path,
+            });
+            continue;
+        }
+
+        let Some(ref expected) = file.hash else {
+            report.unhashed += 1;
+            continue;
+        };
+
+        match compute_file_hash(&path) {
+            Ok(hash) => {
+                let actual = hash_to_hex(&hash);
+                if actual.eq_ignore_ascii_case(expected) {
+                    report.ok += 1;
+                } else {
+                    report.issues.push(VerifyIssue::Changed {
+                        object_id: file.object_id.clone(),
+                        path,
+                        expected: expected.clone(),
+                        actual,
+                    });
+                }
+            }
+            Err(e) => report.issues.push(VerifyIssue::Unreadable {
+                object_id: file.object_id.clone(),
+                path,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Absolute destination of a tracked file, if one was recorded
+fn destination(file: &TrackedFile, output_dir: &Path) -> Option<PathBuf> {
+    file.destination.as_ref().map(|path| output_dir.join(path))
+}
+
+/// Pick up to `sample` files spread evenly across `files`
+fn sampled(files: &[TrackedFile], sample: Option<usize>) -> Vec<&TrackedFile> {
+    match sample {
+        Some(n) if n < files.len() => {
+            if n == 0 {
+                return Vec::new();
+            }
+            (0..n).map(|i| &files[i * files.len() / n]).collect()
+        }
+        _ => files.iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::TrackingConfig;
+    use crate::core::tracking::StateTracker;
+    use crate::duplicate::compute_data_hash;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn tracked(output_dir: &Path, id: &str, name: &str, data: &[u8]) -> TrackedFile {
+        fs::write(output_dir.join(name), data).unwrap();
+        TrackedFile::new(
+            id,
+            Some(hash_to_hex(&compute_data_hash(data))),
+            data.len() as u64,
+        )
+        .with_destination(name)
+    }
+
+    #[test]
+    fn test_prune_missing_removes_deleted_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path();
+        let mut tracker = StateTracker::new(&TrackingConfig::default(), output_dir);
+
+        tracker
+            .insert(&tracked(output_dir, "kept", "IMG_0001.JPG", b"one"))
+            .unwrap();
+        tracker
+            .insert(&tracked(output_dir, "deleted", "IMG_0002.JPG", b"two"))
+            .unwrap();
+        tracker.record_extracted("legacy", 10);
+        fs::remove_file(output_dir.join("IMG_0002.JPG")).unwrap();
+
+        let report = tracker.prune_missing(output_dir).unwrap();
+
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.removed, vec!["deleted".to_string()]);
+        assert_eq!(report.unknown_destination, 1);
+        assert!(tracker.contains("kept").unwrap());
+        assert!(!tracker.contains("deleted").unwrap());
+        assert!(tracker.contains("legacy").unwrap());
+    }
+
+    #[test]
+    fn test_verify_distinguishes_missing_and_changed() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path();
+        let mut tracker = StateTracker::new(&TrackingConfig::default(), output_dir);
+
+        for (id, name) in [("a", "a.jpg"), ("b", "b.jpg"), ("c", "c.jpg")] {
+            tracker
+                .insert(&tracked(output_dir, id, name, name.as_bytes()))
+                .unwrap();
+        }
+        fs::remove_file(output_dir.join("b.jpg")).unwrap();
+        fs::write(output_dir.join("c.jpg"), b"edited").unwrap();
+
+        let report = tracker.verify(output_dir, None).unwrap();
+
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.ok, 1);
+        assert!(!report.is_clean());
+        let missing: Vec<_> = report.missing().map(|i| i.path().to_path_buf()).collect();
+        assert_eq!(missing, vec![output_dir.join("b.jpg")]);
+        let changed: Vec<_> = report.changed().map(|i| i.path().to_path_buf()).collect();
+        assert_eq!(changed, vec![output_dir.join("c.jpg")]);
+
+        // Verification never modifies the store
+        assert_eq!(tracker.len().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_verify_sample_limits_checked_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path();
+        let mut tracker = StateTracker::new(&TrackingConfig::default(), output_dir);
+
+        for i in 0..10 {
+            let name = format!("{}.jpg", i);
+            tracker
+                .insert(&tracked(
+                    output_dir,
+                    &format!("o{}", i),
+                    &name,
+                    name.as_bytes(),
+                ))
+                .unwrap();
+        }
+
+        let report = tracker.verify(output_dir, Some(3)).unwrap();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.ok, 3);
+        assert!(report.is_clean());
+    }
+}
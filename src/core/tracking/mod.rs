@@ -22,15 +22,39 @@
 //! }
 //! ```
 //!
+//! # Storage Backends
+//!
+//! "Has this file been extracted already?" lookups go through the
+//! [`TrackingStoreTrait`] trait. [`StateTracker`] keeps everything in one JSON file,
+//! which is simple but is rewritten in full on every save. For libraries with
+//! hundreds of thousands of files, [`SqliteStore`] keeps the same records in an
+//! indexed SQLite database and can import an existing tracking file. Setting
+//! `tracking.database` makes extractions use one (see [`open_database`]); the
+//! JSON file then only keeps the device and session history.
+//!
+//! # Maintenance
+//!
+//! Tracked files record where they were written, so a store can be checked
+//! against the output directory: [`TrackingStoreTrait::prune_missing`] forgets files
+//! that have since been deleted, and [`TrackingStoreTrait::verify`] re-hashes them to
+//! find files that are missing or have changed on disk.
+//!
 //! Some accessor methods are kept for API completeness and future use.
 
+mod maintenance;
+mod sqlite;
+
+pub use maintenance::{PruneReport, VerifyIssue, VerifyReport};
+pub use sqlite::SqliteStore;
+
 use crate::core::config::TrackingConfig;
 use crate::core::error::{ExtractionError, Result};
 use crate::device::DeviceInfo;
+use crate::duplicate::{hash_to_hex, Sha256Hash};
 use chrono::{DateTime, Utc};
 use log::{debug, info, trace, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufReader, BufWriter};
 use std::path::{Path, PathBuf};
@@ -58,6 +82,12 @@ pub struct ExtractionState {
     #[serde(default)]
     pub extracted_files: HashSet<String>,
 
+    /// Where each extracted file was written, keyed by identifier
+    ///
+    /// Files extracted before this was recorded have no entry.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub file_records: HashMap<String, FileRecord>,
+
     /// Extraction sessions history
     #[serde(default)]
     pub sessions: Vec<ExtractionSession>,
@@ -101,6 +131,23 @@ pub struct ExtractionStats {
     pub total_sessions: u64,
 }
 
+/// Destination details for one extracted file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRecord {
+    /// Path of the written file, relative to the output directory
+    pub path: PathBuf,
+
+    /// Size in bytes
+    pub size: u64,
+
+    /// SHA256 of the content as lowercase hex, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+
+    /// When the file was extracted
+    pub extracted_at: DateTime<Utc>,
+}
+
 /// Record of a single extraction session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractionSession {
@@ -316,6 +363,112 @@ pub fn scan_for_profiles_default(root: &Path) -> Vec<ProfileSummary> {
     scan_for_profiles(root, &config.tracking_filename)
 }
 
+/// Open the tracking database configured for `output_dir`, if any
+///
+/// A new database starts with the files recorded in the output's JSON
+/// tracking file. Returns `None` when no database is configured or files
+/// aren't tracked individually.
+pub fn open_database(config: &TrackingConfig, output_dir: &Path) -> Result<Option<SqliteStore>> {
+    let Some(path) = config.database_path(output_dir) else {
+        return Ok(None);
+    };
+    if !config.track_extracted_files {
+        return Ok(None);
+    }
+
+    let mut store = SqliteStore::open(&path)?;
+    let tracking_file = output_dir.join(&config.tracking_filename);
+    if store.is_empty()? && tracking_file.exists() {
+        let imported = store.import_tracking_file(&tracking_file)?;
+        info!(
+            "Imported {} tracked files from {} into {}",
+            imported,
+            tracking_file.display(),
+            path.display()
+        );
+    }
+    Ok(Some(store))
+}
+
+/// A file recorded as extracted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackedFile {
+    /// WPD object ID on the device
+    pub object_id: String,
+    /// SHA256 of the content as lowercase hex, if known
+    pub hash: Option<String>,
+    /// Size in bytes
+    pub size: u64,
+    /// When the file was extracted
+    pub extracted_at: DateTime<Utc>,
+    /// Where the file was written, relative to the output directory, if known
+    pub destination: Option<PathBuf>,
+}
+
+impl TrackedFile {
+    /// Create a record stamped with the current time
+    pub fn new(object_id: impl Into<String>, hash: Option<String>, size: u64) -> Self {
+        Self {
+            object_id: object_id.into(),
+            hash,
+            size,
+            extracted_at: Utc::now(),
+            destination: None,
+        }
+    }
+
+    /// Set where the file was written (relative to the output directory)
+    pub fn with_destination(mut self, destination: impl Into<PathBuf>) -> Self {
+        self.destination = Some(destination.into());
+        self
+    }
+}
+
+/// Storage for "have I extracted this file already" lookups
+pub trait TrackingStoreTrait {
+    /// Check whether a file with this object ID has been extracted
+    fn contains(&self, object_id: &str) -> Result<bool>;
+
+    /// Look up the record for a file
+    fn get(&self, object_id: &str) -> Result<Option<TrackedFile>>;
+
+    /// Record a file as extracted, replacing any previous record for it
+    fn insert(&mut self, file: &TrackedFile) -> Result<()>;
+
+    /// Forget files extracted before `cutoff`, returning how many were removed
+    fn prune_older_than(&mut self, cutoff: DateTime<Utc>) -> Result<usize>;
+
+    /// Number of files recorded
+    fn len(&self) -> Result<usize>;
+
+    /// Whether no files are recorded
+    fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// All recorded files, ordered by object ID
+    fn files(&self) -> Result<Vec<TrackedFile>>;
+
+    /// Forget a file, returning whether it was recorded
+    fn remove(&mut self, object_id: &str) -> Result<bool>;
+
+    /// Forget files whose destination under `output_dir` no longer exists
+    ///
+    /// Files recorded without a destination are left alone.
+    fn prune_missing(&mut self, output_dir: &Path) -> Result<PruneReport> {
+        maintenance::prune_missing(self, output_dir)
+    }
+
+    /// Re-hash tracked files under `output_dir` and report any that are
+    /// missing or no longer match
+    ///
+    /// `sample` limits the check to that many files spread evenly across the
+    /// store; `None` checks every file.
+    fn verify(&self, output_dir: &Path, sample: Option<usize>) -> Result<VerifyReport> {
+        maintenance::verify(self, output_dir, sample)
+    }
+}
+
 /// Tracker for managing extraction state
 pub struct StateTracker {
     /// Configuration
@@ -353,6 +506,7 @@ impl ExtractionState {
             },
             stats: ExtractionStats::default(),
             extracted_files: HashSet::new(),
+            file_records: HashMap::new(),
             sessions: Vec::new(),
         }
     }
@@ -386,6 +540,7 @@ impl StateTracker {
                 },
                 stats: ExtractionStats::default(),
                 extracted_files: HashSet::new(),
+                file_records: HashMap::new(),
                 sessions: Vec::new(),
             },
             current_session: None,
@@ -442,6 +597,21 @@ impl StateTracker {
         Ok(())
     }
 
+    /// Open the tracking file in `output_dir` without a connected device
+    ///
+    /// Used by maintenance commands; fails if there is no tracking file.
+    pub fn open_existing(config: &TrackingConfig, output_dir: &Path) -> Result<Self> {
+        let mut tracker = Self::new(config, output_dir);
+        if !tracker.tracking_file_path.exists() {
+            return Err(ExtractionError::IoError(format!(
+                "No tracking file found at {}",
+                tracker.tracking_file_path.display()
+            )));
+        }
+        tracker.state = tracker.load_state()?;
+        Ok(tracker)
+    }
+
     /// Load state from tracking file
     fn load_state(&self) -> Result<ExtractionState> {
         let file = File::open(&self.tracking_file_path).map_err(|e| {
@@ -537,6 +707,39 @@ impl StateTracker {
         self.dirty = true;
     }
 
+    /// Record a file as extracted to `path`, remembering where it went
+    ///
+    /// `path` is stored relative to the directory holding the tracking file
+    /// when it lies inside it.
+    pub fn record_extracted_file(
+        &mut self,
+        file_id: &str,
+        bytes: u64,
+        path: &Path,
+        hash: Option<&Sha256Hash>,
+    ) {
+        self.record_extracted(file_id, bytes);
+
+        if self.config.track_extracted_files {
+            let record = FileRecord {
+                path: self.relative_to_output(path),
+                size: bytes,
+                hash: hash.map(hash_to_hex),
+                extracted_at: Utc::now(),
+            };
+            self.state.file_records.insert(file_id.to_string(), record);
+        }
+    }
+
+    /// Strip the output directory prefix from `path`, if present
+    fn relative_to_output(&self, path: &Path) -> PathBuf {
+        self.tracking_file_path
+            .parent()
+            .and_then(|dir| path.strip_prefix(dir).ok())
+            .unwrap_or(path)
+            .to_path_buf()
+    }
+
     /// Record a file as skipped
     pub fn record_skipped(&mut self) {
         if let Some(ref mut session) = self.current_session {
@@ -637,8 +840,108 @@ impl StateTracker {
     #[allow(dead_code)]
     pub fn clear_tracked_files(&mut self) {
         self.state.extracted_files.clear();
+        self.state.file_records.clear();
         self.dirty = true;
     }
+
+    /// Build the record for an extracted file from its stored details, if any
+    fn tracked_file(&self, id: &str) -> TrackedFile {
+        match self.state.file_records.get(id) {
+            Some(record) => TrackedFile {
+                object_id: id.to_string(),
+                hash: record.hash.clone(),
+                size: record.size,
+                extracted_at: record.extracted_at,
+                destination: Some(record.path.clone()),
+            },
+            None => TrackedFile {
+                object_id: id.to_string(),
+                hash: None,
+                size: 0,
+                extracted_at: self.state.device.last_seen,
+                destination: None,
+            },
+        }
+    }
+}
+
+/// Files extracted before destinations were recorded have only an object ID,
+/// so they report no hash, a size of 0, the device's last-seen time, and are
+/// never pruned by age.
+impl TrackingStoreTrait for StateTracker {
+    fn contains(&self, object_id: &str) -> Result<bool> {
+        Ok(self.is_file_extracted(object_id))
+    }
+
+    fn get(&self, object_id: &str) -> Result<Option<TrackedFile>> {
+        if !self.is_file_extracted(object_id) {
+            return Ok(None);
+        }
+        Ok(Some(self.tracked_file(object_id)))
+    }
+
+    fn insert(&mut self, file: &TrackedFile) -> Result<()> {
+        if self.config.track_extracted_files {
+            self.state.extracted_files.insert(file.object_id.clone());
+            match file.destination {
+                Some(ref path) => {
+                    let record = FileRecord {
+                        path: path.clone(),
+                        size: file.size,
+                        hash: file.hash.clone(),
+                        extracted_at: file.extracted_at,
+                    };
+                    self.state
+                        .file_records
+                        .insert(file.object_id.clone(), record);
+                }
+                None => {
+                    self.state.file_records.remove(&file.object_id);
+                }
+            }
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    fn prune_older_than(&mut self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let old: Vec<String> = self
+            .state
+            .file_records
+            .iter()
+            .filter(|(_, record)| record.extracted_at < cutoff)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in &old {
+            self.remove(id)?;
+        }
+        Ok(old.len())
+    }
+
+    fn len(&self) -> Result<usize> {
+        Ok(self.tracked_files_count())
+    }
+
+    fn files(&self) -> Result<Vec<TrackedFile>> {
+        let mut files: Vec<TrackedFile> = self
+            .state
+            .extracted_files
+            .iter()
+            .map(|id| self.tracked_file(id))
+            .collect();
+        files.sort_by(|a, b| a.object_id.cmp(&b.object_id));
+        Ok(files)
+    }
+
+    fn remove(&mut self, object_id: &str) -> Result<bool> {
+        let removed = self.state.extracted_files.remove(object_id);
+        self.state.file_records.remove(object_id);
+        if removed {
+            self.dirty = true;
+        }
+        Ok(removed)
+    }
 }
 
 impl Drop for StateTracker {
@@ -665,6 +968,8 @@ mod tests {
             friendly_name: "Test iPhone".to_string(),
             manufacturer: "Apple Inc.".to_string(),
             model: "iPhone 15".to_string(),
+            storage: None,
+            battery_level: None,
         };
 
         let state = ExtractionState::new(&device_info);
@@ -681,6 +986,7 @@ mod tests {
             enabled: true,
             tracking_filename: ".test_tracking.json".to_string(),
             track_extracted_files: true,
+            database: None,
         };
 
         let mut tracker = StateTracker::new(&config, Path::new("/tmp"));
@@ -699,6 +1005,65 @@ mod tests {
         assert!(!tracker.is_file_extracted("file3"));
     }
 
+    #[test]
+    fn test_state_tracker_as_tracking_store() {
+        let config = TrackingConfig {
+            enabled: true,
+            tracking_filename: ".test_tracking.json".to_string(),
+            track_extracted_files: true,
+            database: None,
+        };
+        let temp_dir = TempDir::new().unwrap();
+        let mut tracker = StateTracker::new(&config, temp_dir.path());
+
+        TrackingStoreTrait::insert(&mut tracker, &TrackedFile::new("file1", None, 10)).unwrap();
+        tracker.record_extracted("legacy", 20);
+
+        assert!(tracker.contains("file1").unwrap());
+        assert!(!tracker.contains("file2").unwrap());
+        assert_eq!(TrackingStoreTrait::len(&tracker).unwrap(), 2);
+        // Neither file has a destination record, so neither has a timestamp
+        assert_eq!(tracker.prune_older_than(Utc::now()).unwrap(), 0);
+
+        let mut old = TrackedFile::new("file3", None, 30).with_destination("IMG_0003.JPG");
+        old.extracted_at = Utc::now() - chrono::Duration::days(30);
+        TrackingStoreTrait::insert(&mut tracker, &old).unwrap();
+        let cutoff = Utc::now() - chrono::Duration::days(1);
+        assert_eq!(tracker.prune_older_than(cutoff).unwrap(), 1);
+        assert!(!tracker.contains("file3").unwrap());
+    }
+
+    #[test]
+    fn test_record_extracted_file_stores_relative_destination() {
+        let config = TrackingConfig::default();
+        let temp_dir = TempDir::new().unwrap();
+        let mut tracker = StateTracker::new(&config, temp_dir.path());
+
+        let hash = [7u8; 32];
+        let path = temp_dir.path().join("2024").join("IMG_0001.JPG");
+        tracker.record_extracted_file("o1", 1234, &path, Some(&hash));
+
+        let files = tracker.files().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            files[0].destination,
+            Some(Path::new("2024").join("IMG_0001.JPG"))
+        );
+        assert_eq!(files[0].size, 1234);
+        assert_eq!(files[0].hash, Some(hash_to_hex(&hash)));
+
+        // Records survive a save and reload
+        tracker.save().unwrap();
+        let reopened = StateTracker::open_existing(&config, temp_dir.path()).unwrap();
+        assert_eq!(reopened.files().unwrap(), files);
+    }
+
+    #[test]
+    fn test_open_existing_requires_tracking_file() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(StateTracker::open_existing(&TrackingConfig::default(), temp_dir.path()).is_err());
+    }
+
     #[test]
     fn test_profile_summary_from_state() {
         let device_info = DeviceInfo {
@@ -706,6 +1071,8 @@ mod tests {
             friendly_name: "My iPhone".to_string(),
             manufacturer: "Apple Inc.".to_string(),
             model: "iPhone 14 Pro".to_string(),
+            storage: None,
+            battery_level: None,
         };
 
         let mut state = ExtractionState::new(&device_info);
@@ -730,6 +1097,8 @@ mod tests {
             friendly_name: "Test Device".to_string(),
             manufacturer: "Apple Inc.".to_string(),
             model: "iPhone 15".to_string(),
+            storage: None,
+            battery_level: None,
         };
 
         let mut state = ExtractionState::new(&device_info);
@@ -774,6 +1143,8 @@ mod tests {
             friendly_name: "John's iPhone".to_string(),
             manufacturer: "Apple Inc.".to_string(),
             model: "iPhone 15 Pro Max".to_string(),
+            storage: None,
+            battery_level: None,
         };
 
         let mut state = ExtractionState::new(&device_info);
@@ -825,6 +1196,8 @@ mod tests {
             friendly_name: "Root iPhone".to_string(),
             manufacturer: "Apple Inc.".to_string(),
             model: "iPhone SE".to_string(),
+            storage: None,
+            battery_level: None,
         };
 
         let state = ExtractionState::new(&device_info);
@@ -846,4 +1219,30 @@ mod tests {
         let profiles = scan_for_profiles_default(temp_dir.path());
         assert!(profiles.is_empty());
     }
+
+    #[test]
+    fn test_open_database_imports_tracking_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = TrackingConfig::default();
+        assert!(open_database(&config, temp_dir.path()).unwrap().is_none());
+
+        let device = DeviceInfo::new("device-1", "Test iPhone", "Apple Inc.", "iPhone 15");
+        let mut tracker = StateTracker::new(&config, temp_dir.path());
+        tracker.load_or_create(&device).unwrap();
+        tracker.record_extracted("o1", 10);
+        tracker.save().unwrap();
+
+        config.database = Some(PathBuf::from("tracking.db"));
+        let mut store = open_database(&config, temp_dir.path()).unwrap().unwrap();
+        assert_eq!(store.path(), temp_dir.path().join("tracking.db"));
+        assert!(store.contains("o1").unwrap());
+
+        // Only a new database is filled from the tracking file
+        store.remove("o1").unwrap();
+        store.insert(&TrackedFile::new("o2", None, 20)).unwrap();
+        drop(store);
+        let store = open_database(&config, temp_dir.path()).unwrap().unwrap();
+        assert!(!store.contains("o1").unwrap());
+        assert!(store.contains("o2").unwrap());
+    }
 }
@@ -0,0 +1,467 @@
+//! SQLite-backed tracking store
+//!
+//! Keeps one row per extracted file in an indexed table, so lookups stay fast
+//! and memory use stays flat no matter how many files have been extracted. The
+//! database runs in WAL mode, so a crash mid-extraction can lose at most the
+//! last uncommitted insert and never corrupts earlier records.
+
+use super::{ExtractionState, TrackedFile, TrackingStoreTrait};
+use crate::core::error::{ExtractionError, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use log::debug;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Schema for the tracking database
+///
+/// `object_id` is the primary key, so SQLite maintains a B-tree index on it
+/// and `contains` is a single O(log n) lookup.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS extracted_files (
+        object_id    TEXT PRIMARY KEY NOT NULL,
+        hash         TEXT,
+        size         INTEGER NOT NULL,
+        extracted_at INTEGER NOT NULL,
+        destination  TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_extracted_files_hash
+        ON extracted_files (hash);
+    CREATE INDEX IF NOT EXISTS idx_extracted_files_extracted_at
+        ON extracted_files (extracted_at);
+";
+
+/// Columns selected when reading a [`TrackedFile`] back
+const COLUMNS: &str = "object_id, hash, size, extracted_at, destination";
+
+fn db_error(e: rusqlite::Error) -> ExtractionError {
+    ExtractionError::IoError(format!("Tracking database error: {}", e))
+}
+
+/// Tracking store kept in a SQLite database
+pub struct SqliteStore {
+    conn: Connection,
+    path: PathBuf,
+}
+
+impl SqliteStore {
+    /// Open (or create) the tracking database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                ExtractionError::IoError(format!("Failed to create tracking directory: {}", e))
+            })?;
+        }
+
+        let conn = Connection::open(path).map_err(db_error)?;
+        let journal_mode: String = conn
+            .query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))
+            .map_err(db_error)?;
+        conn.pragma_update(None, "synchronous", "NORMAL")
+            .map_err(db_error)?;
+        conn.execute_batch(SCHEMA).map_err(db_error)?;
+        migrate(&conn)?;
+
+        debug!(
+            "Opened tracking database {} (journal mode: {})",
+            path.display(),
+            journal_mode
+        );
+
+        Ok(Self {
+            conn,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Path of the database file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Current SQLite journal mode (`"wal"` once opened)
+    pub fn journal_mode(&self) -> Result<String> {
+        self.conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .map_err(db_error)
+    }
+
+    /// Check whether any extracted file has this content hash
+    pub fn contains_hash(&self, hash: &str) -> Result<bool> {
+        self.conn
+            .query_row(
+                "SELECT 1 FROM extracted_files WHERE hash = ?1 LIMIT 1",
+                params![hash],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|found| found.is_some())
+            .map_err(db_error)
+    }
+
+    /// Record many files in a single transaction, returning how many were written
+    pub fn insert_many<'a, I>(&mut self, files: I) -> Result<usize>
+    where
+        I: IntoIterator<Item = &'a TrackedFile>,
+    {
+        let tx = self.conn.transaction().map_err(db_error)?;
+        let mut count = 0;
+        {
+            let mut stmt = tx.prepare(INSERT_SQL).map_err(db_error)?;
+            for file in files {
+                stmt.execute(insert_params(file)).map_err(db_error)?;
+                count += 1;
+            }
+        }
+        tx.commit().map_err(db_error)?;
+        Ok(count)
+    }
+
+    /// Import the files recorded in a JSON tracking file
+    ///
+    /// Files extracted before the JSON format recorded destinations have no
+    /// hash or destination, a size of 0, and are stamped with the time the
+    /// device was last seen. Returns the number of files imported.
+    pub fn import_tracking_file(&mut self, tracking_file: &Path) -> Result<usize> {
+        let file = File::open(tracking_file).map_err(|e| {
+            ExtractionError::IoError(format!("Failed to open tracking file: {}", e))
+        })?;
+        let state: ExtractionState =
+            serde_json::from_reader(BufReader::new(file)).map_err(|e| {
+                ExtractionError::IoError(format!("Failed to parse tracking file: {}", e))
+            })?;
+
+        let extracted_at = state.device.last_seen;
+        let mut records = state.file_records;
+        let files: Vec<TrackedFile> = state
+            .extracted_files
+            .into_iter()
+            .map(|object_id| match records.remove(&object_id) {
+                Some(record) => TrackedFile {
+                    object_id,
+                    hash: record.hash,
+                    size: record.size,
+                    extracted_at: record.extracted_at,
+                    destination: Some(record.path),
+                },
+                None => TrackedFile {
+                    object_id,
+                    hash: None,
+                    size: 0,
+                    extracted_at,
+                    destination: None,
+                },
+            })
+            .collect();
+
+        let imported = self.insert_many(&files)?;
+        debug!(
+            "Imported {} files from {} into {}",
+            imported,
+            tracking_file.display(),
+            self.path.display()
+        );
+        Ok(imported)
+    }
+}
+
+/// Bring a database created by an older version up to the current schema
+fn migrate(conn: &Connection) -> Result<()> {
+    let has_destination = conn
+        .prepare("SELECT 1 FROM pragma_table_info('extracted_files') WHERE name = 'destination'")
+        .and_then(|mut stmt| stmt.exists([]))
+        .map_err(db_error)?;
+
+    if !has_destination {
+        debug!("Adding destination column to tracking database");
+        conn.execute_batch("ALTER TABLE extracted_files ADD COLUMN destination TEXT")
+            .map_err(db_error)?;
+    }
+
+    Ok(())
+}
+
+const INSERT_SQL: &str = "INSERT OR REPLACE INTO extracted_files
+    (object_id, hash, size, extracted_at, destination) VALUES (?1, ?2, ?3, ?4, ?5)";
+
+fn insert_params(file: &TrackedFile) -> (&str, Option<&str>, i64, i64, Option<String>) {
+    (
+        &file.object_id,
+        file.hash.as_deref(),
+        file.size as i64,
+        file.extracted_at.timestamp_millis(),
+        file.destination
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned()),
+    )
+}
+
+fn tracked_file(row: &rusqlite::Row<'_>) -> rusqlite::Result<TrackedFile> {
+    Ok(TrackedFile {
+        object_id: row.get(0)?,
+        hash: row.get(1)?,
+        size: row.get::<_, i64>(2)? as u64,
+        extracted_at: from_millis(row.get(3)?),
+        destination: row.get::<_, Option<String>>(4)?.map(PathBuf::from),
+    })
+}
+
+fn from_millis(millis: i64) -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .unwrap_or(DateTime::<Utc>::UNIX_EPOCH)
+}
+
+impl TrackingStoreTrait for SqliteStore {
+    fn contains(&self, object_id: &str) -> Result<bool> {
+        self.conn
+            .query_row(
+                "SELECT 1 FROM extracted_files WHERE object_id = ?1",
+                params![object_id],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|found| found.is_some())
+            .map_err(db_error)
+    }
+
+    fn get(&self, object_id: &str) -> Result<Option<TrackedFile>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM extracted_files WHERE object_id = ?1",
+                    COLUMNS
+                ),
+                params![object_id],
+                tracked_file,
+            )
+            .optional()
+            .map_err(db_error)
+    }
+
+    fn insert(&mut self, file: &TrackedFile) -> Result<()> {
+        self.conn
+            .execute(INSERT_SQL, insert_params(file))
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    fn prune_older_than(&mut self, cutoff: DateTime<Utc>) -> Result<usize> {
+        self.conn
+            .execute(
+                "DELETE FROM extracted_files WHERE extracted_at < ?1",
+                params![cutoff.timestamp_millis()],
+            )
+            .map_err(db_error)
+    }
+
+    fn len(&self) -> Result<usize> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM extracted_files", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .map(|count| count as usize)
+            .map_err(db_error)
+    }
+
+    fn files(&self) -> Result<Vec<TrackedFile>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM extracted_files ORDER BY object_id",
+                COLUMNS
+            ))
+            .map_err(db_error)?;
+        let files = stmt
+            .query_map([], tracked_file)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(db_error)?;
+        Ok(files)
+    }
+
+    fn remove(&mut self, object_id: &str) -> Result<bool> {
+        self.conn
+            .execute(
+                "DELETE FROM extracted_files WHERE object_id = ?1",
+                params![object_id],
+            )
+            .map(|removed| removed > 0)
+            .map_err(db_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tracking::FileRecord;
+    use crate::device::DeviceInfo;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn file(i: usize) -> TrackedFile {
+        TrackedFile::new(format!("o{}", i), Some(format!("{:064x}", i)), i as u64)
+    }
+
+    #[test]
+    fn test_opens_in_wal_mode() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SqliteStore::open(&temp_dir.path().join("tracking.db")).unwrap();
+        assert_eq!(store.journal_mode().unwrap(), "wal");
+        assert!(store.is_empty().unwrap());
+    }
+
+    #[test]
+    fn test_insert_and_lookup_10k() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = SqliteStore::open(&temp_dir.path().join("tracking.db")).unwrap();
+
+        let files: Vec<TrackedFile> = (0..10_000).map(file).collect();
+        assert_eq!(store.insert_many(&files).unwrap(), 10_000);
+        store.insert(&file(10_000)).unwrap();
+
+        assert_eq!(store.len().unwrap(), 10_001);
+        for i in (0..=10_000).step_by(97) {
+            assert!(store.contains(&format!("o{}", i)).unwrap());
+        }
+        assert!(!store.contains("o10001").unwrap());
+        assert!(!store.contains("").unwrap());
+
+        let record = store.get("o1234").unwrap().unwrap();
+        assert_eq!(record.size, 1234);
+        assert_eq!(record.hash, Some(format!("{:064x}", 1234)));
+        assert!(store.contains_hash(&format!("{:064x}", 42)).unwrap());
+        assert!(!store.contains_hash("missing").unwrap());
+    }
+
+    #[test]
+    fn test_reopened_store_persists() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tracking.db");
+
+        {
+            let mut store = SqliteStore::open(&path).unwrap();
+            store.insert(&file(1)).unwrap();
+            store.insert(&file(2)).unwrap();
+        }
+
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.len().unwrap(), 2);
+        assert!(store.contains("o1").unwrap());
+        assert!(store.contains("o2").unwrap());
+    }
+
+    #[test]
+    fn test_insert_replaces_existing_record() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = SqliteStore::open(&temp_dir.path().join("tracking.db")).unwrap();
+
+        store.insert(&file(1)).unwrap();
+        store.insert(&TrackedFile::new("o1", None, 999)).unwrap();
+
+        assert_eq!(store.len().unwrap(), 1);
+        assert_eq!(store.get("o1").unwrap().unwrap().size, 999);
+    }
+
+    #[test]
+    fn test_prune_older_than() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = SqliteStore::open(&temp_dir.path().join("tracking.db")).unwrap();
+
+        let now = Utc::now();
+        let mut old = file(1);
+        old.extracted_at = now - Duration::days(400);
+        let recent = file(2);
+        store.insert_many([&old, &recent]).unwrap();
+
+        let removed = store.prune_older_than(now - Duration::days(365)).unwrap();
+        assert_eq!(removed, 1);
+        assert!(!store.contains("o1").unwrap());
+        assert!(store.contains("o2").unwrap());
+    }
+
+    #[test]
+    fn test_destination_round_trips_and_prunes() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("output");
+        fs::create_dir_all(output_dir.join("2024")).unwrap();
+        fs::write(output_dir.join("2024").join("kept.jpg"), b"kept").unwrap();
+
+        let mut store = SqliteStore::open(&temp_dir.path().join("tracking.db")).unwrap();
+        let kept = file(1).with_destination(Path::new("2024").join("kept.jpg"));
+        let gone = file(2).with_destination("gone.jpg");
+        store.insert_many([&kept, &gone, &file(3)]).unwrap();
+
+        assert_eq!(
+            store.get("o1").unwrap().unwrap().destination,
+            kept.destination
+        );
+
+        let report = store.prune_missing(&output_dir).unwrap();
+        assert_eq!(report.removed, vec!["o2".to_string()]);
+        assert_eq!(report.unknown_destination, 1);
+        assert_eq!(store.len().unwrap(), 2);
+        assert!(!store.contains("o2").unwrap());
+        assert!(!store.remove("o2").unwrap());
+    }
+
+    #[test]
+    fn test_adds_destination_column_to_old_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tracking.db");
+
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE extracted_files (
+                    object_id    TEXT PRIMARY KEY NOT NULL,
+                    hash         TEXT,
+                    size         INTEGER NOT NULL,
+                    extracted_at INTEGER NOT NULL
+                );
+                INSERT INTO extracted_files VALUES ('old', NULL, 5, 0);",
+            )
+            .unwrap();
+        }
+
+        let mut store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.get("old").unwrap().unwrap().destination, None);
+        store.insert(&file(1).with_destination("a.jpg")).unwrap();
+        assert_eq!(store.files().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_tracking_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_path = temp_dir.path().join(".photo_extraction_state.json");
+
+        let device = DeviceInfo::new("device-1", "Test iPhone", "Apple Inc.", "iPhone 15");
+        let mut state = ExtractionState::new(&device);
+        state.extracted_files = (0..250).map(|i| format!("o{}", i)).collect();
+        let record = FileRecord {
+            path: PathBuf::from("IMG_0008.JPG"),
+            size: 8,
+            hash: Some("ab".repeat(32)),
+            extracted_at: Utc::now(),
+        };
+        state.file_records.insert("o8".to_string(), record);
+        fs::write(&json_path, serde_json::to_string(&state).unwrap()).unwrap();
+
+        let mut store = SqliteStore::open(&temp_dir.path().join("tracking.db")).unwrap();
+        assert_eq!(store.import_tracking_file(&json_path).unwrap(), 250);
+        assert_eq!(store.len().unwrap(), 250);
+        assert!(store.contains("o0").unwrap());
+        assert!(store.contains("o249").unwrap());
+
+        let record = store.get("o7").unwrap().unwrap();
+        assert_eq!(record.hash, None);
+        assert_eq!(
+            record.extracted_at.timestamp_millis(),
+            state.device.last_seen.timestamp_millis()
+        );
+
+        let record = store.get("o8").unwrap().unwrap();
+        assert_eq!(record.destination, Some(PathBuf::from("IMG_0008.JPG")));
+        assert_eq!(record.size, 8);
+    }
+}
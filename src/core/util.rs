@@ -0,0 +1,127 @@
+//! Small helpers shared by the CLI and the UI
+//!
+//! [`reveal_in_explorer`] opens the system file manager at a folder, e.g. the
+//! output folder once an extraction finishes. Launching goes through a
+//! [`CommandRunner`] so tests can check the command line without spawning
+//! anything.
+
+use crate::core::error::{ExtractionError, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Starts external programs
+pub trait CommandRunner {
+    /// Launch `command` without waiting for it to exit
+    fn run(&mut self, command: &mut Command) -> std::io::Result<()>;
+}
+
+/// Runner that spawns the command as a detached child process
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&mut self, command: &mut Command) -> std::io::Result<()> {
+        command.spawn().map(|_| ())
+    }
+}
+
+/// Open the system file manager at `dir`
+pub fn reveal_in_explorer(dir: &Path) -> Result<()> {
+    reveal_in_explorer_with(dir, &mut SystemCommandRunner)
+}
+
+/// Open the file manager at `dir` through `runner`
+///
+/// The folder is passed as a single argument rather than through a shell, so
+/// spaces and `&` in the path need no quoting. Only Windows is supported;
+/// elsewhere this returns an error without running anything.
+pub fn reveal_in_explorer_with(dir: &Path, runner: &mut dyn CommandRunner) -> Result<()> {
+    if !dir.is_dir() {
+        return Err(ExtractionError::IoError(format!(
+            "Not a folder: {}",
+            dir.display()
+        )));
+    }
+
+    let mut command = explorer_command(dir)?;
+    runner.run(&mut command).map_err(|e| {
+        ExtractionError::IoError(format!(
+            "Failed to open '{}' in the file manager: {}",
+            dir.display(),
+            e
+        ))
+    })
+}
+
+#[cfg(windows)]
+fn explorer_command(dir: &Path) -> Result<Command> {
+    // Explorer doesn't understand forward slashes or the `\\?\` prefix
+    let lossy = dir.to_string_lossy();
+    let dir = lossy
+        .strip_prefix(r"\\?\")
+        .unwrap_or(&lossy)
+        .replace('/', "\\");
+
+    let mut command = Command::new("explorer.exe");
+    command.arg(dir);
+    Ok(command)
+}
+
+#[cfg(not(windows))]
+fn explorer_command(_dir: &Path) -> Result<Command> {
+    Err(ExtractionError::IoError(
+        "Opening folders is only supported on Windows".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+    use tempfile::TempDir;
+
+    /// Records the commands it is asked to run instead of spawning them
+    #[derive(Default)]
+    struct RecordingRunner {
+        commands: Vec<(OsString, Vec<OsString>)>,
+    }
+
+    impl CommandRunner for RecordingRunner {
+        fn run(&mut self, command: &mut Command) -> std::io::Result<()> {
+            self.commands.push((
+                command.get_program().to_owned(),
+                command.get_args().map(|arg| arg.to_owned()).collect(),
+            ));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_reveal_in_explorer_passes_folder_as_one_argument() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("iPhone Photos & Videos");
+        std::fs::create_dir(&dir).unwrap();
+
+        let mut runner = RecordingRunner::default();
+        let result = reveal_in_explorer_with(&dir, &mut runner);
+
+        if cfg!(windows) {
+            result.unwrap();
+            assert_eq!(
+                runner.commands,
+                vec![(
+                    OsString::from("explorer.exe"),
+                    vec![OsString::from(dir.to_string_lossy().replace('/', "\\"))],
+                )]
+            );
+        } else {
+            assert!(result.is_err());
+            assert!(runner.commands.is_empty());
+        }
+
+        // A missing folder is never handed to the file manager
+        let missing = temp_dir.path().join("missing");
+        assert!(reveal_in_explorer_with(&missing, &mut runner).is_err());
+        assert!(runner.commands.len() <= 1);
+    }
+}
@@ -7,6 +7,7 @@
 //! # Submodules
 //!
 //! - `wpd` - Windows Portable Devices API wrapper
+//! - `models` - Apple model detection from WPD model strings
 //! - `profiles` - Device profile management
 //! - `traits` - Abstraction traits for testability
 //!
@@ -26,6 +27,7 @@
 
 #![allow(unused)]
 
+pub mod models;
 pub mod profiles;
 pub mod traits;
 pub mod wpd;
@@ -33,11 +35,13 @@ pub mod wpd;
 // Re-export commonly used types from traits for convenience
 pub use traits::{
     DeviceContentTrait, DeviceInfo, DeviceManagerTrait, DeviceObject, DeviceOperationStats,
-    DeviceSimulationConfig, DeviceType,
+    DeviceSimulationConfig, DeviceType, ObjectMetadata, StorageInfo, StorageVolume,
 };
 
+pub use models::{AppleFamily, DeviceModel};
+
 // Re-export WPD-specific types
-pub use profiles::ProfileManager;
+pub use profiles::{ProfileImportSummary, ProfileManager};
 pub use wpd::{
     enumerate_all_mtp_devices, enumerate_android_devices, initialize_com, ComGuard, DeviceContent,
     DeviceManager,
@@ -0,0 +1,326 @@
+//! Apple device model detection
+//!
+//! WPD reports a device's model and friendly name as free text: "Apple
+//! iPhone", "iPhone 14 Pro", "John's iPad", and sometimes Apple's internal
+//! identifier such as "iPhone15,2". [`DeviceModel::detect`] turns those
+//! strings into a known model so the UI can show a proper label and pick an
+//! icon from the [`AppleFamily`].
+//!
+//! Detection is driven by the [`APPLE_MODELS`] table; supporting a new model
+//! only needs a new row.
+
+use std::fmt::{self, Display};
+
+/// Apple product line a model belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppleFamily {
+    /// iPhone
+    IPhone,
+    /// iPad, iPad Air, iPad mini and iPad Pro
+    IPad,
+    /// iPod touch
+    IPod,
+}
+
+impl AppleFamily {
+    /// Get a human-readable name for this family
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AppleFamily::IPhone => "iPhone",
+            AppleFamily::IPad => "iPad",
+            AppleFamily::IPod => "iPod",
+        }
+    }
+}
+
+impl Display for AppleFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+/// One row of the [`APPLE_MODELS`] table
+#[derive(Debug, PartialEq, Eq)]
+pub struct AppleModel {
+    /// Marketing name, matched against the model and friendly name
+    pub name: &'static str,
+    /// Product line
+    pub family: AppleFamily,
+    /// Apple's internal identifiers (e.g. "iPhone15,2"); empty for rows
+    /// that only name a product line
+    pub identifiers: &'static [&'static str],
+}
+
+const fn model(
+    name: &'static str,
+    family: AppleFamily,
+    identifiers: &'static [&'static str],
+) -> AppleModel {
+    AppleModel {
+        name,
+        family,
+        identifiers,
+    }
+}
+
+/// Known Apple models
+///
+/// Rows without identifiers name a whole product line ("iPad Air"), so a
+/// device reporting only that still gets a family. When several names match,
+/// the longest wins, so "iPhone 14 Pro Max" is not read as "iPhone 14".
+pub const APPLE_MODELS: &[AppleModel] = &[
+    // iPhone
+    model("iPhone", AppleFamily::IPhone, &[]),
+    model(
+        "iPhone 8",
+        AppleFamily::IPhone,
+        &["iPhone10,1", "iPhone10,4"],
+    ),
+    model(
+        "iPhone 8 Plus",
+        AppleFamily::IPhone,
+        &["iPhone10,2", "iPhone10,5"],
+    ),
+    model(
+        "iPhone X",
+        AppleFamily::IPhone,
+        &["iPhone10,3", "iPhone10,6"],
+    ),
+    model("iPhone XR", AppleFamily::IPhone, &["iPhone11,8"]),
+    model("iPhone XS", AppleFamily::IPhone, &["iPhone11,2"]),
+    model(
+        "iPhone XS Max",
+        AppleFamily::IPhone,
+        &["iPhone11,4", "iPhone11,6"],
+    ),
+    model("iPhone 11", AppleFamily::IPhone, &["iPhone12,1"]),
+    model("iPhone 11 Pro", AppleFamily::IPhone, &["iPhone12,3"]),
+    model("iPhone 11 Pro Max", AppleFamily::IPhone, &["iPhone12,5"]),
+    model(
+        "iPhone SE (2nd generation)",
+        AppleFamily::IPhone,
+        &["iPhone12,8"],
+    ),
+    model("iPhone 12 mini", AppleFamily::IPhone, &["iPhone13,1"]),
+    model("iPhone 12", AppleFamily::IPhone, &["iPhone13,2"]),
+    model("iPhone 12 Pro", AppleFamily::IPhone, &["iPhone13,3"]),
+    model("iPhone 12 Pro Max", AppleFamily::IPhone, &["iPhone13,4"]),
+    model("iPhone 13 mini", AppleFamily::IPhone, &["iPhone14,4"]),
+    model("iPhone 13", AppleFamily::IPhone, &["iPhone14,5"]),
+    model("iPhone 13 Pro", AppleFamily::IPhone, &["iPhone14,2"]),
+    model("iPhone 13 Pro Max", AppleFamily::IPhone, &["iPhone14,3"]),
+    model(
+        "iPhone SE (3rd generation)",
+        AppleFamily::IPhone,
+        &["iPhone14,6"],
+    ),
+    model("iPhone 14", AppleFamily::IPhone, &["iPhone14,7"]),
+    model("iPhone 14 Plus", AppleFamily::IPhone, &["iPhone14,8"]),
+    model("iPhone 14 Pro", AppleFamily::IPhone, &["iPhone15,2"]),
+    model("iPhone 14 Pro Max", AppleFamily::IPhone, &["iPhone15,3"]),
+    model("iPhone 15", AppleFamily::IPhone, &["iPhone15,4"]),
+    model("iPhone 15 Plus", AppleFamily::IPhone, &["iPhone15,5"]),
+    model("iPhone 15 Pro", AppleFamily::IPhone, &["iPhone16,1"]),
+    model("iPhone 15 Pro Max", AppleFamily::IPhone, &["iPhone16,2"]),
+    model("iPhone 16", AppleFamily::IPhone, &["iPhone17,3"]),
+    model("iPhone 16 Plus", AppleFamily::IPhone, &["iPhone17,4"]),
+    model("iPhone 16 Pro", AppleFamily::IPhone, &["iPhone17,1"]),
+    model("iPhone 16 Pro Max", AppleFamily::IPhone, &["iPhone17,2"]),
+    model("iPhone 16e", AppleFamily::IPhone, &["iPhone17,5"]),
+    // iPad
+    model("iPad", AppleFamily::IPad, &[]),
+    model("iPad Air", AppleFamily::IPad, &[]),
+    model("iPad mini", AppleFamily::IPad, &[]),
+    model("iPad Pro", AppleFamily::IPad, &[]),
+    model(
+        "iPad (9th generation)",
+        AppleFamily::IPad,
+        &["iPad12,1", "iPad12,2"],
+    ),
+    model(
+        "iPad (10th generation)",
+        AppleFamily::IPad,
+        &["iPad13,18", "iPad13,19"],
+    ),
+    model(
+        "iPad Air (4th generation)",
+        AppleFamily::IPad,
+        &["iPad13,1", "iPad13,2"],
+    ),
+    model(
+        "iPad Air (5th generation)",
+        AppleFamily::IPad,
+        &["iPad13,16", "iPad13,17"],
+    ),
+    model(
+        "iPad mini (6th generation)",
+        AppleFamily::IPad,
+        &["iPad14,1", "iPad14,2"],
+    ),
+    // iPod
+    model("iPod", AppleFamily::IPod, &[]),
+    model("iPod touch", AppleFamily::IPod, &[]),
+    model(
+        "iPod touch (7th generation)",
+        AppleFamily::IPod,
+        &["iPod9,1"],
+    ),
+];
+
+/// Device model parsed from the strings WPD reports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceModel {
+    /// A model from [`APPLE_MODELS`]
+    Apple(&'static AppleModel),
+    /// Not a recognized Apple model; holds the model string as reported
+    Unknown(String),
+}
+
+impl DeviceModel {
+    /// Detect the model from a device's model and friendly name strings
+    ///
+    /// An internal identifier such as "iPhone15,2" is the most precise and is
+    /// looked for first, then marketing names. The model string is checked
+    /// before the friendly name, which the user may have renamed.
+    pub fn detect(model: &str, friendly_name: &str) -> Self {
+        let fields = [model, friendly_name];
+        fields
+            .iter()
+            .find_map(|field| find_by_identifier(field))
+            .or_else(|| fields.iter().find_map(|field| find_by_name(field)))
+            .map(DeviceModel::Apple)
+            .unwrap_or_else(|| DeviceModel::Unknown(model.trim().to_string()))
+    }
+
+    /// Label to show for the device
+    pub fn label(&self) -> &str {
+        match self {
+            DeviceModel::Apple(model) => model.name,
+            DeviceModel::Unknown(model) => model,
+        }
+    }
+
+    /// Apple product line, if this is a recognized Apple model
+    pub fn family(&self) -> Option<AppleFamily> {
+        match self {
+            DeviceModel::Apple(model) => Some(model.family),
+            DeviceModel::Unknown(_) => None,
+        }
+    }
+
+    /// Check if the model wasn't recognized
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, DeviceModel::Unknown(_))
+    }
+}
+
+impl Display for DeviceModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Table row whose identifier appears as a whole word in `text`
+fn find_by_identifier(text: &str) -> Option<&'static AppleModel> {
+    text.split(|c: char| !c.is_ascii_alphanumeric() && c != ',')
+        .filter(|word| !word.is_empty())
+        .find_map(|word| {
+            APPLE_MODELS.iter().find(|row| {
+                row.identifiers
+                    .iter()
+                    .any(|identifier| identifier.eq_ignore_ascii_case(word))
+            })
+        })
+}
+
+/// Table row with the longest name found as whole words in `text`
+fn find_by_name(text: &str) -> Option<&'static AppleModel> {
+    let text = text.to_ascii_lowercase();
+    APPLE_MODELS
+        .iter()
+        .filter(|row| contains_words(&text, &row.name.to_ascii_lowercase()))
+        .max_by_key(|row| row.name.len())
+}
+
+/// Check if `needle` occurs in `haystack` not preceded or followed by a
+/// letter or digit, so "iPhone 1" doesn't match "iPhone 14"
+fn contains_words(haystack: &str, needle: &str) -> bool {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric());
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !is_word(before) && !is_word(after)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(model: &str, friendly_name: &str) -> String {
+        DeviceModel::detect(model, friendly_name)
+            .label()
+            .to_string()
+    }
+
+    #[test]
+    fn test_detects_marketing_names() {
+        assert_eq!(label("iPhone 14 Pro", "John's iPhone"), "iPhone 14 Pro");
+        assert_eq!(label("iPhone 14 Pro Max", ""), "iPhone 14 Pro Max");
+        assert_eq!(label("IPHONE 14", ""), "iPhone 14");
+        assert_eq!(label("iPhone 12 mini", ""), "iPhone 12 mini");
+
+        let air = DeviceModel::detect("iPad Air", "Family iPad");
+        assert_eq!(air.label(), "iPad Air");
+        assert_eq!(air.family(), Some(AppleFamily::IPad));
+    }
+
+    #[test]
+    fn test_detects_internal_identifiers() {
+        assert_eq!(label("iPhone15,2", ""), "iPhone 14 Pro");
+        assert_eq!(label("Apple iPhone", "iPhone16,2"), "iPhone 15 Pro Max");
+        assert_eq!(label("ipad13,17", ""), "iPad Air (5th generation)");
+
+        // An identifier beats a vaguer marketing name
+        assert_eq!(label("Apple iPhone (iPhone17,1)", ""), "iPhone 16 Pro");
+    }
+
+    #[test]
+    fn test_falls_back_to_the_product_line() {
+        let generic = DeviceModel::detect("Apple iPhone", "Apple iPhone");
+        assert_eq!(generic.label(), "iPhone");
+        assert_eq!(generic.family(), Some(AppleFamily::IPhone));
+
+        // A model string that says nothing leaves the friendly name
+        let renamed = DeviceModel::detect("MTP Device", "My iPad");
+        assert_eq!(renamed.family(), Some(AppleFamily::IPad));
+
+        // A number not in the table doesn't match a shorter one
+        assert_eq!(label("iPhone 19", ""), "iPhone");
+    }
+
+    #[test]
+    fn test_unrecognized_model_is_unknown() {
+        let galaxy = DeviceModel::detect(" Galaxy S24 ", "Samsung Galaxy");
+        assert_eq!(galaxy, DeviceModel::Unknown("Galaxy S24".to_string()));
+        assert!(galaxy.is_unknown());
+        assert_eq!(galaxy.family(), None);
+        assert_eq!(galaxy.to_string(), "Galaxy S24");
+    }
+
+    #[test]
+    fn test_table_has_no_duplicates() {
+        let mut names: Vec<_> = APPLE_MODELS.iter().map(|row| row.name).collect();
+        let mut identifiers: Vec<_> = APPLE_MODELS
+            .iter()
+            .flat_map(|row| row.identifiers.iter())
+            .collect();
+        let (name_count, identifier_count) = (names.len(), identifiers.len());
+        names.sort();
+        names.dedup();
+        identifiers.sort();
+        identifiers.dedup();
+        assert_eq!(names.len(), name_count);
+        assert_eq!(identifiers.len(), identifier_count);
+    }
+}
@@ -9,8 +9,9 @@
 //!
 //! Some accessor methods are kept for API completeness and future use.
 
-use crate::core::config::{DeviceProfile, DeviceProfilesConfig, TrackingConfig};
+use crate::core::config::{DeviceProfile, DeviceProfilesConfig, ExtractionSummary, TrackingConfig};
 use crate::core::error::{ExtractionError, Result};
+use crate::core::extractor::ExtractionStats;
 use crate::core::tracking::scan_for_profiles;
 use crate::device::DeviceInfo;
 use chrono::Utc;
@@ -43,6 +44,23 @@ pub struct DeviceProfilesDatabase {
     pub backup_location: Option<String>,
 }
 
+/// Counts from [`ProfileManager::import`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProfileImportSummary {
+    /// Profiles for devices that were not known before
+    pub added: usize,
+    /// Existing profiles replaced by the imported version
+    pub replaced: usize,
+    /// Imported profiles skipped because the device already had a profile
+    pub skipped: usize,
+}
+
+/// Whether a profiles export path should use TOML rather than JSON
+fn is_toml_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
 /// Device profiles manager
 pub struct ProfileManager {
     /// Configuration
@@ -483,6 +501,7 @@ impl ProfileManager {
             model: device.model.clone(),
             first_seen: Some(now.clone()),
             last_seen: Some(now),
+            last_extraction: None,
         };
 
         // Create the output folder
@@ -569,6 +588,15 @@ impl ProfileManager {
             if let Some(ref last) = profile.last_seen {
                 println!("  Last seen: {}", last);
             }
+            match profile.last_extraction {
+                Some(ref summary) => println!(
+                    "  Last extraction: {} ({} files, {:.1} MB)",
+                    summary.timestamp,
+                    summary.files_extracted,
+                    summary.total_bytes as f64 / (1024.0 * 1024.0)
+                ),
+                None => println!("  Last extraction: never"),
+            }
             println!("  Device ID: {}...", &id[..id.len().min(50)]);
         }
 
@@ -581,6 +609,37 @@ impl ProfileManager {
         self.config.enabled
     }
 
+    /// Remember a finished extraction as the device's last one
+    ///
+    /// Only runs that got to the end are recorded, even if some files failed.
+    /// A cancelled run leaves the previous summary in place: the summary
+    /// answers "when was this device last backed up in full", and whatever a
+    /// cancelled run did copy is skipped as existing by the next run anyway.
+    /// Runs that fail outright never reach here. Returns whether the profile
+    /// changed; call [`save`](Self::save) to persist it.
+    pub fn record_extraction(
+        &mut self,
+        device_id: &str,
+        stats: &ExtractionStats,
+        cancelled: bool,
+    ) -> bool {
+        if cancelled {
+            debug!("Extraction was cancelled; keeping the last extraction summary");
+            return false;
+        }
+        let Some(profile) = self.database.profiles.get_mut(device_id) else {
+            return false;
+        };
+
+        profile.last_extraction = Some(ExtractionSummary {
+            timestamp: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            files_extracted: stats.files_extracted,
+            total_bytes: stats.total_bytes,
+        });
+        self.dirty = true;
+        true
+    }
+
     /// Remove a profile by device ID
     pub fn remove_profile(&mut self, device_id: &str) -> Option<DeviceProfile> {
         let profile = self.database.profiles.remove(device_id);
@@ -590,6 +649,213 @@ impl ProfileManager {
         profile
     }
 
+    /// Rename a profile, optionally moving it to a new output folder
+    ///
+    /// The profile stays keyed by `device_id`, so tracking state and future
+    /// extractions keep pointing at the same device. Names and folders are
+    /// compared case-insensitively against other profiles and must be unique.
+    /// When the folder changes and the old one exists on disk, it is renamed
+    /// so the tracking file inside moves with it.
+    pub fn rename_profile(
+        &mut self,
+        device_id: &str,
+        new_name: &str,
+        new_output_folder: Option<&str>,
+    ) -> Result<()> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(ExtractionError::IoError(
+                "Profile name cannot be empty".to_string(),
+            ));
+        }
+        let new_folder = new_output_folder.map(sanitize_folder_name);
+
+        let current = self
+            .database
+            .profiles
+            .get(device_id)
+            .ok_or_else(|| ExtractionError::ProfileNotFound(device_id.to_string()))?;
+        let old_folder = current.output_folder.clone();
+
+        for (id, profile) in &self.database.profiles {
+            if id == device_id {
+                continue;
+            }
+            let folder_taken = new_folder
+                .as_deref()
+                .is_some_and(|folder| profile.output_folder.eq_ignore_ascii_case(folder));
+            if profile.name.eq_ignore_ascii_case(new_name) || folder_taken {
+                return Err(ExtractionError::ProfileNameTaken {
+                    name: new_name.to_string(),
+                    device_id: id.clone(),
+                });
+            }
+        }
+
+        if let Some(folder) = new_folder.as_deref().filter(|f| *f != old_folder) {
+            if folder.is_empty() {
+                return Err(ExtractionError::IoError(
+                    "Output folder cannot be empty".to_string(),
+                ));
+            }
+            let old_path = self.config.backup_base_folder.join(&old_folder);
+            let new_path = self.config.backup_base_folder.join(folder);
+            if new_path.exists() {
+                return Err(ExtractionError::IoError(format!(
+                    "Output folder '{}' already exists",
+                    new_path.display()
+                )));
+            }
+            if old_path.exists() {
+                fs::rename(&old_path, &new_path).map_err(|e| {
+                    ExtractionError::IoError(format!(
+                        "Failed to move '{}' to '{}': {}",
+                        old_path.display(),
+                        new_path.display(),
+                        e
+                    ))
+                })?;
+                debug!(
+                    "Moved output folder {} -> {}",
+                    old_path.display(),
+                    new_path.display()
+                );
+            }
+        }
+
+        if let Some(profile) = self.database.profiles.get_mut(device_id) {
+            profile.name = new_name.to_string();
+            if let Some(folder) = new_folder {
+                profile.output_folder = folder;
+            }
+        }
+        self.dirty = true;
+
+        Ok(())
+    }
+
+    /// Export all profiles to a portable file
+    ///
+    /// Writes TOML when the path ends in `.toml`, JSON otherwise. Output
+    /// folders are stored relative to the backup folder, so the file can be
+    /// imported on another machine with a different backup location.
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let export = DeviceProfilesDatabase {
+            version: self.database.version,
+            profiles: self.database.profiles.clone(),
+            last_updated: Some(Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+            backup_location: None,
+        };
+
+        let content = if is_toml_path(path) {
+            toml::to_string_pretty(&export).map_err(|e| {
+                ExtractionError::IoError(format!("Failed to serialize profiles: {}", e))
+            })?
+        } else {
+            serde_json::to_string_pretty(&export).map_err(|e| {
+                ExtractionError::IoError(format!("Failed to serialize profiles: {}", e))
+            })?
+        };
+
+        fs::write(path, content).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to write profiles export '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        debug!(
+            "Exported {} device profile(s) to {}",
+            export.profiles.len(),
+            path.display()
+        );
+        Ok(())
+    }
+
+    /// Import profiles from a file written by [`ProfileManager::export`]
+    ///
+    /// Without `merge`, the imported profiles replace all existing ones.
+    /// With `merge`, profiles are combined by device ID; a device that
+    /// already has a profile keeps it unless `overwrite` is set.
+    ///
+    /// Fails without changing anything if an imported profile would share
+    /// its name or output folder with another device's profile.
+    pub fn import(
+        &mut self,
+        path: &Path,
+        merge: bool,
+        overwrite: bool,
+    ) -> Result<ProfileImportSummary> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to read profiles export '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let imported: DeviceProfilesDatabase = if is_toml_path(path) {
+            toml::from_str(&content).map_err(|e| {
+                ExtractionError::IoError(format!("Failed to parse profiles export: {}", e))
+            })?
+        } else {
+            serde_json::from_str(&content).map_err(|e| {
+                ExtractionError::IoError(format!("Failed to parse profiles export: {}", e))
+            })?
+        };
+
+        let mut summary = ProfileImportSummary::default();
+        let mut profiles = if merge {
+            self.database.profiles.clone()
+        } else {
+            summary.replaced = self.database.profiles.len();
+            HashMap::new()
+        };
+
+        let mut incoming = Vec::new();
+        for (device_id, profile) in imported.profiles {
+            match profiles.get_mut(&device_id) {
+                None => {
+                    profiles.insert(device_id.clone(), profile);
+                    summary.added += 1;
+                }
+                Some(existing) if overwrite => {
+                    *existing = profile;
+                    summary.replaced += 1;
+                }
+                Some(_) => {
+                    summary.skipped += 1;
+                    continue;
+                }
+            }
+            incoming.push(device_id);
+        }
+
+        for device_id in &incoming {
+            let profile = &profiles[device_id];
+            let clash = profiles.iter().find(|(id, other)| {
+                *id != device_id
+                    && (other.name.eq_ignore_ascii_case(&profile.name)
+                        || other
+                            .output_folder
+                            .eq_ignore_ascii_case(&profile.output_folder))
+            });
+            if let Some((other_id, _)) = clash {
+                return Err(ExtractionError::ProfileNameTaken {
+                    name: profile.name.clone(),
+                    device_id: other_id.clone(),
+                });
+            }
+        }
+
+        if !merge || !incoming.is_empty() {
+            self.database.profiles = profiles;
+            self.dirty = true;
+        }
+        Ok(summary)
+    }
+
     /// Get all profiles
     pub fn get_all_profiles(&self) -> &HashMap<String, DeviceProfile> {
         &self.database.profiles
@@ -653,6 +919,7 @@ mod tests {
             output_folder: "Test_iPhone".to_string(),
             first_seen: None,
             last_seen: None,
+            last_extraction: None,
         };
         manager
             .database
@@ -674,6 +941,8 @@ mod tests {
             friendly_name: "Test iPhone".to_string(),
             manufacturer: "Apple Inc.".to_string(),
             model: "iPhone".to_string(),
+            storage: None,
+            battery_level: None,
         };
 
         // New device should need profile creation
@@ -687,6 +956,7 @@ mod tests {
             output_folder: "Test_iPhone".to_string(),
             first_seen: None,
             last_seen: None,
+            last_extraction: None,
         };
         manager
             .database
@@ -702,7 +972,272 @@ mod tests {
             friendly_name: "Other iPhone".to_string(),
             manufacturer: "Apple Inc.".to_string(),
             model: "iPhone".to_string(),
+            storage: None,
+            battery_level: None,
         };
         assert!(manager.needs_profile_creation(&other_device));
     }
+
+    fn test_profile(name: &str, folder: &str) -> DeviceProfile {
+        DeviceProfile {
+            name: name.to_string(),
+            manufacturer: "Apple Inc.".to_string(),
+            model: "iPhone".to_string(),
+            output_folder: folder.to_string(),
+            first_seen: None,
+            last_seen: None,
+            last_extraction: None,
+        }
+    }
+
+    fn temp_config(dir: &Path) -> DeviceProfilesConfig {
+        DeviceProfilesConfig {
+            backup_base_folder: dir.join("backup"),
+            profiles_file: dir.join("profiles.json"),
+            ..DeviceProfilesConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_rename_profile_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = temp_config(dir.path());
+
+        {
+            let mut manager = ProfileManager::new(&config);
+            manager.database.profiles.insert(
+                "device-123".to_string(),
+                test_profile("Old Name", "Old_Name"),
+            );
+            manager.dirty = true;
+            manager.save().unwrap();
+
+            manager
+                .rename_profile("device-123", "New Name", Some("New_Name"))
+                .unwrap();
+            manager.save().unwrap();
+        }
+
+        let mut reloaded = ProfileManager::new(&config);
+        reloaded.load().unwrap();
+        assert_eq!(reloaded.get_all_profiles().len(), 1);
+        let profile = reloaded.get_profile("device-123").unwrap();
+        assert_eq!(profile.name, "New Name");
+        assert_eq!(profile.output_folder, "New_Name");
+        assert_eq!(profile.model, "iPhone");
+    }
+
+    #[test]
+    fn test_rename_profile_moves_output_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = temp_config(dir.path());
+        let old_path = config.backup_base_folder.join("Old_Name");
+        fs::create_dir_all(&old_path).unwrap();
+        fs::write(old_path.join(".photo_extraction_state.json"), "{}").unwrap();
+
+        let mut manager = ProfileManager::new(&config);
+        manager.database.profiles.insert(
+            "device-123".to_string(),
+            test_profile("Old Name", "Old_Name"),
+        );
+
+        // Renaming without a folder keeps the existing one
+        manager
+            .rename_profile("device-123", "Work Phone", None)
+            .unwrap();
+        assert_eq!(
+            manager.get_output_path("device-123"),
+            Some(old_path.clone())
+        );
+
+        manager
+            .rename_profile("device-123", "Work Phone", Some("Work_Phone"))
+            .unwrap();
+        let new_path = config.backup_base_folder.join("Work_Phone");
+        assert!(!old_path.exists());
+        assert!(new_path.join(".photo_extraction_state.json").exists());
+        manager.dirty = false;
+    }
+
+    #[test]
+    fn test_rename_profile_rejects_collisions() {
+        let config = DeviceProfilesConfig::default();
+        let mut manager = ProfileManager::new(&config);
+        manager
+            .database
+            .profiles
+            .insert("device-1".to_string(), test_profile("Alice", "Alice"));
+        manager
+            .database
+            .profiles
+            .insert("device-2".to_string(), test_profile("Bob", "Bob"));
+
+        assert!(matches!(
+            manager.rename_profile("device-2", "alice", None),
+            Err(ExtractionError::ProfileNameTaken { ref device_id, .. }) if device_id == "device-1"
+        ));
+        assert!(matches!(
+            manager.rename_profile("device-2", "Robert", Some("Alice")),
+            Err(ExtractionError::ProfileNameTaken { .. })
+        ));
+        assert!(matches!(
+            manager.rename_profile("device-3", "Carol", None),
+            Err(ExtractionError::ProfileNotFound(_))
+        ));
+        assert_eq!(manager.get_profile("device-2").unwrap().name, "Bob");
+        assert!(!manager.dirty);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut source = ProfileManager::new(&temp_config(&dir.path().join("source")));
+        source
+            .database
+            .profiles
+            .insert("device-1".to_string(), test_profile("Alice", "Alice"));
+        source
+            .database
+            .profiles
+            .insert("device-2".to_string(), test_profile("Bob", "Bob"));
+
+        for file in ["profiles.json", "profiles.toml"] {
+            let path = dir.path().join(file);
+            source.export(&path).unwrap();
+
+            let mut target = ProfileManager::new(&temp_config(&dir.path().join("target")));
+            let summary = target.import(&path, false, false).unwrap();
+            assert_eq!(summary.added, 2);
+            assert_eq!(target.get_all_profiles().len(), 2);
+            assert_eq!(target.get_profile("device-1").unwrap().name, "Alice");
+            assert_eq!(target.get_profile("device-2").unwrap().output_folder, "Bob");
+            target.dirty = false;
+        }
+    }
+
+    #[test]
+    fn test_import_merge_keeps_existing_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+
+        let mut source = ProfileManager::new(&temp_config(&dir.path().join("source")));
+        source
+            .database
+            .profiles
+            .insert("device-1".to_string(), test_profile("Imported", "Imported"));
+        source
+            .database
+            .profiles
+            .insert("device-2".to_string(), test_profile("Bob", "Bob"));
+        source.export(&path).unwrap();
+
+        let mut target = ProfileManager::new(&temp_config(&dir.path().join("target")));
+        target
+            .database
+            .profiles
+            .insert("device-1".to_string(), test_profile("Local", "Local"));
+
+        let summary = target.import(&path, true, false).unwrap();
+        assert_eq!(
+            summary,
+            ProfileImportSummary {
+                added: 1,
+                replaced: 0,
+                skipped: 1
+            }
+        );
+        assert_eq!(target.get_profile("device-1").unwrap().name, "Local");
+        assert_eq!(target.get_profile("device-2").unwrap().name, "Bob");
+
+        // device-2 was added by the first import, so both are replaced now
+        let summary = target.import(&path, true, true).unwrap();
+        assert_eq!(summary.replaced, 2);
+        assert_eq!(target.get_profile("device-1").unwrap().name, "Imported");
+        target.dirty = false;
+    }
+
+    #[test]
+    fn test_record_extraction_keeps_last_completed_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = temp_config(dir.path());
+
+        let mut manager = ProfileManager::new(&config);
+        manager
+            .database
+            .profiles
+            .insert("device-1".to_string(), test_profile("Alice", "Alice"));
+        let completed = ExtractionStats {
+            files_extracted: 42,
+            total_bytes: 123_456,
+            errors: 1,
+            ..Default::default()
+        };
+        assert!(manager.record_extraction("device-1", &completed, false));
+        assert!(!manager.record_extraction("unknown-device", &completed, false));
+        manager.save().unwrap();
+
+        let mut reloaded = ProfileManager::new(&config);
+        reloaded.load().unwrap();
+        let summary = reloaded
+            .get_profile("device-1")
+            .unwrap()
+            .last_extraction
+            .clone()
+            .unwrap();
+        assert_eq!(summary.files_extracted, 42);
+        assert_eq!(summary.total_bytes, 123_456);
+        assert!(!summary.timestamp.is_empty());
+
+        // A cancelled run doesn't replace it
+        let cancelled = ExtractionStats {
+            files_extracted: 3,
+            total_bytes: 100,
+            ..Default::default()
+        };
+        assert!(!reloaded.record_extraction("device-1", &cancelled, true));
+        assert_eq!(
+            reloaded.get_profile("device-1").unwrap().last_extraction,
+            Some(summary)
+        );
+        assert!(!reloaded.dirty);
+    }
+
+    #[test]
+    fn test_import_rejects_clashing_names_and_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+
+        let mut target = ProfileManager::new(&temp_config(&dir.path().join("target")));
+        target
+            .database
+            .profiles
+            .insert("device-1".to_string(), test_profile("Alice", "Alice"));
+
+        for clashing in [test_profile("alice", "Other"), test_profile("Bob", "ALICE")] {
+            let mut source = ProfileManager::new(&temp_config(&dir.path().join("source")));
+            source
+                .database
+                .profiles
+                .insert("device-2".to_string(), clashing);
+            source.export(&path).unwrap();
+
+            assert!(matches!(
+                target.import(&path, true, false),
+                Err(ExtractionError::ProfileNameTaken { device_id, .. }) if device_id == "device-1"
+            ));
+            assert_eq!(target.get_all_profiles().len(), 1);
+            assert!(!target.dirty);
+        }
+
+        // Replacing the device's own profile is not a clash
+        let mut source = ProfileManager::new(&temp_config(&dir.path().join("source")));
+        source
+            .database
+            .profiles
+            .insert("device-1".to_string(), test_profile("Alice", "Alice"));
+        source.export(&path).unwrap();
+        assert_eq!(target.import(&path, true, true).unwrap().replaced, 1);
+        target.dirty = false;
+    }
 }
@@ -29,7 +29,8 @@
 //! }
 //! ```
 
-use crate::core::error::Result;
+use crate::core::error::{ExtractionError, Result};
+use crate::device::models::DeviceModel;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Debug, Display};
 
@@ -158,6 +159,73 @@ const CAMERA_MODEL_KEYWORDS: &[&str] = &[
     "osmo",
 ];
 
+/// Storage capacity of a device, summed over all of its storages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct StorageInfo {
+    /// Total capacity in bytes
+    pub capacity_bytes: u64,
+    /// Free space in bytes
+    pub free_bytes: u64,
+}
+
+impl StorageInfo {
+    /// Create storage info from total capacity and free space
+    pub fn new(capacity_bytes: u64, free_bytes: u64) -> Self {
+        Self {
+            capacity_bytes,
+            free_bytes,
+        }
+    }
+
+    /// Bytes in use
+    pub fn used_bytes(&self) -> u64 {
+        self.capacity_bytes.saturating_sub(self.free_bytes)
+    }
+
+    /// Percentage of capacity in use (0.0 when capacity is unknown)
+    pub fn used_percent(&self) -> f64 {
+        if self.capacity_bytes == 0 {
+            0.0
+        } else {
+            self.used_bytes() as f64 / self.capacity_bytes as f64 * 100.0
+        }
+    }
+}
+
+/// A storage volume on a device, such as internal storage or an SD card
+///
+/// Each volume is a separate tree at the device root; DCIM may live on any of
+/// them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageVolume {
+    /// Object ID of the storage root (pass to `enumerate_children`)
+    pub object_id: String,
+    /// Human-readable description (e.g. "Internal Storage", "SD Card")
+    pub description: String,
+    /// Total capacity in bytes, if the device reports it
+    pub capacity_bytes: Option<u64>,
+    /// Free space in bytes, if the device reports it
+    pub free_bytes: Option<u64>,
+}
+
+impl StorageVolume {
+    /// Create a volume for a root folder, with unknown capacity
+    pub fn from_root(root: &DeviceObject) -> Self {
+        Self {
+            object_id: root.object_id.clone(),
+            description: root.name.clone(),
+            capacity_bytes: None,
+            free_bytes: None,
+        }
+    }
+
+    /// Capacity and free space, if the capacity is known
+    pub fn storage_info(&self) -> Option<StorageInfo> {
+        self.capacity_bytes
+            .map(|capacity| StorageInfo::new(capacity, self.free_bytes.unwrap_or(0)))
+    }
+}
+
 /// Common device information shared between real and mock devices
 #[derive(Debug, Clone, Default)]
 pub struct DeviceInfo {
@@ -169,6 +237,13 @@ pub struct DeviceInfo {
     pub manufacturer: String,
     /// Device model (e.g., "iPhone 15 Pro", "Galaxy S24")
     pub model: String,
+    /// Storage capacity and free space (None if not queried or not reported)
+    ///
+    /// Enumeration leaves this unset because reading it means opening the
+    /// device; use [`DeviceManagerTrait::get_device_storage`] to fill it in.
+    pub storage: Option<StorageInfo>,
+    /// Battery charge as a percentage (0-100), if the device reports it
+    pub battery_level: Option<u8>,
 }
 
 impl DeviceInfo {
@@ -179,9 +254,23 @@ impl DeviceInfo {
             friendly_name: friendly_name.to_string(),
             manufacturer: manufacturer.to_string(),
             model: model.to_string(),
+            storage: None,
+            battery_level: None,
         }
     }
 
+    /// Set the storage capacity and free space
+    pub fn with_storage(mut self, storage: StorageInfo) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Set the battery level (clamped to 100)
+    pub fn with_battery_level(mut self, level: u8) -> Self {
+        self.battery_level = Some(level.min(100));
+        self
+    }
+
     /// Detect and return the device type based on manufacturer and model information
     ///
     /// This method analyzes the device's manufacturer, model, and friendly name
@@ -209,6 +298,15 @@ impl DeviceInfo {
         DeviceType::Unknown
     }
 
+    /// Detect the specific model (e.g. "iPhone 14 Pro") from the model and
+    /// friendly name
+    ///
+    /// See [`DeviceModel::detect`]; devices that aren't a known Apple model
+    /// come back as [`DeviceModel::Unknown`] with the raw model string.
+    pub fn device_model(&self) -> DeviceModel {
+        DeviceModel::detect(&self.model, &self.friendly_name)
+    }
+
     /// Check if this device is an Apple device
     fn is_apple_device(&self, manufacturer: &str, model: &str, name: &str) -> bool {
         // Check manufacturer
@@ -405,7 +503,7 @@ impl DeviceObject {
     }
 
     /// Guess content type from file extension
-    fn guess_content_type(name: &str) -> Option<String> {
+    pub(crate) fn guess_content_type(name: &str) -> Option<String> {
         let lower = name.to_lowercase();
         if lower.ends_with(".jpg") || lower.ends_with(".jpeg") {
             Some("image/jpeg".to_string())
@@ -438,23 +536,7 @@ impl DeviceObject {
 
     /// Check if this object is a media file based on extension
     pub fn is_media_file(&self) -> bool {
-        if self.is_folder {
-            return false;
-        }
-
-        let photo_extensions = [
-            "jpg", "jpeg", "png", "heic", "heif", "gif", "webp", "raw", "dng", "tiff", "tif", "bmp",
-        ];
-        let video_extensions = ["mov", "mp4", "m4v", "avi", "3gp"];
-
-        let extension = std::path::Path::new(&self.name)
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.to_lowercase())
-            .unwrap_or_default();
-
-        photo_extensions.contains(&extension.as_str())
-            || video_extensions.contains(&extension.as_str())
+        !self.is_folder && crate::core::media::is_media_file(&self.name)
     }
 }
 
@@ -472,6 +554,45 @@ impl Default for DeviceObject {
     }
 }
 
+/// Full metadata of a single object
+///
+/// Each field is `None` when the device doesn't expose the property, which
+/// is common for dimensions of videos and for dates on some Android phones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectMetadata {
+    /// Date created (ISO 8601 string)
+    pub date_created: Option<String>,
+    /// Date modified (ISO 8601 string)
+    pub date_modified: Option<String>,
+    /// Content type (e.g., "image/jpeg")
+    pub content_type: Option<String>,
+    /// File name as it was on the device's file system
+    pub original_file_name: Option<String>,
+    /// Width in pixels, for photos and videos
+    pub width: Option<u32>,
+    /// Height in pixels, for photos and videos
+    pub height: Option<u32>,
+}
+
+impl ObjectMetadata {
+    /// Metadata already known from enumerating an object
+    pub fn from_object(object: &DeviceObject) -> Self {
+        Self {
+            date_modified: object.date_modified.clone(),
+            content_type: object.content_type.clone(),
+            original_file_name: (!object.name.is_empty()).then(|| object.name.clone()),
+            ..Self::default()
+        }
+    }
+
+    /// Created date, falling back to the modified date
+    pub fn best_date(&self) -> Option<&str> {
+        self.date_created
+            .as_deref()
+            .or(self.date_modified.as_deref())
+    }
+}
+
 /// Trait for device content access (file system operations)
 ///
 /// This trait abstracts the operations needed to browse and read files from a device.
@@ -482,6 +603,20 @@ pub trait DeviceContentTrait: Send + Sync {
     /// Returns a list of root-level objects (typically "Internal Storage" on iOS devices)
     fn enumerate_objects(&self) -> Result<Vec<DeviceObject>>;
 
+    /// Enumerate the storage volumes of the device
+    ///
+    /// Devices may have more than one storage (e.g. internal storage and an SD
+    /// card), each with its own folder tree. By default every folder at the
+    /// root is treated as a storage with unknown capacity.
+    fn enumerate_storages(&self) -> Result<Vec<StorageVolume>> {
+        Ok(self
+            .enumerate_objects()?
+            .iter()
+            .filter(|object| object.is_folder)
+            .map(StorageVolume::from_root)
+            .collect())
+    }
+
     /// Enumerate children of a specific object
     ///
     /// # Arguments
@@ -497,12 +632,49 @@ pub trait DeviceContentTrait: Send + Sync {
     /// The raw bytes of the file content
     fn read_file(&self, object_id: &str) -> Result<Vec<u8>>;
 
+    /// Read the content of a file in chunks
+    ///
+    /// `on_chunk` receives each chunk as it arrives and can stop the transfer
+    /// by returning an error, which is passed back to the caller. The default
+    /// reads the whole file with [`DeviceContentTrait::read_file`] and hands
+    /// it over as a single chunk.
+    fn read_file_chunked(
+        &self,
+        object_id: &str,
+        on_chunk: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        on_chunk(&self.read_file(object_id)?)
+    }
+
+    /// Read the thumbnail the device keeps for a file
+    ///
+    /// Returns the encoded image bytes (usually JPEG) without downloading the
+    /// full file, or `None` if the device has no thumbnail for it.
+    fn read_thumbnail(&self, _object_id: &str) -> Option<Vec<u8>> {
+        None
+    }
+
     /// Get information about a specific object
     ///
     /// # Arguments
     /// * `object_id` - The object ID to look up
     fn get_object(&self, object_id: &str) -> Result<Option<DeviceObject>>;
 
+    /// Read the full metadata of a specific object
+    ///
+    /// Properties the device doesn't report are `None`. By default only
+    /// what [`DeviceContentTrait::get_object`] knows is filled in.
+    ///
+    /// # Arguments
+    /// * `object_id` - The object ID to look up
+    fn object_metadata(&self, object_id: &str) -> Result<ObjectMetadata> {
+        self.get_object(object_id)?
+            .map(|object| ObjectMetadata::from_object(&object))
+            .ok_or_else(|| {
+                ExtractionError::ContentError(format!("Object not found: {}", object_id))
+            })
+    }
+
     /// Build the full path from root to a specific object
     ///
     /// # Arguments
@@ -511,6 +683,14 @@ pub trait DeviceContentTrait: Send + Sync {
     /// # Returns
     /// The full path as a string (e.g., "Internal Storage/DCIM/100APPLE/IMG_0001.JPG")
     fn get_object_path(&self, object_id: &str) -> Option<String>;
+
+    /// Permanently delete an object from the device
+    ///
+    /// Folders are not deleted recursively, so deleting a non-empty folder fails.
+    ///
+    /// # Arguments
+    /// * `object_id` - The object ID to delete
+    fn delete_object(&self, object_id: &str) -> Result<()>;
 }
 
 /// Trait for device manager (enumeration and connection)
@@ -557,6 +737,24 @@ pub trait DeviceManagerTrait: Send + Sync {
     /// * `device_id` - The device ID to look up
     fn get_device_info(&self, device_id: &str) -> Option<DeviceInfo>;
 
+    /// Get the storage capacity and free space of a device
+    ///
+    /// Returns `None` if the device can't be opened or doesn't report its
+    /// storage.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to query
+    fn get_device_storage(&self, device_id: &str) -> Option<StorageInfo>;
+
+    /// Get the battery level of a device as a percentage (0-100)
+    ///
+    /// Returns `None` if the device can't be opened or doesn't report its
+    /// battery (e.g. it is mains powered).
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to query
+    fn battery_level(&self, device_id: &str) -> Option<u8>;
+
     /// Get the number of available devices
     fn device_count(&self) -> usize;
 }
@@ -582,6 +780,14 @@ pub struct DeviceSimulationConfig {
     pub transfer_delay_ms_per_kb: u64,
     /// Random failure rate (0-100 percentage)
     pub random_failure_rate: u8,
+    /// Number of initial file reads that return truncated content
+    /// (simulates a corrupt transfer that still "succeeds")
+    pub truncate_first_reads: usize,
+    /// Number of initial file reads that fail with a transient error
+    /// (simulates a brief cable hiccup)
+    pub fail_first_reads: usize,
+    /// Simulated latency of each folder enumeration in milliseconds
+    pub enumeration_delay_ms: u64,
 }
 
 impl DeviceSimulationConfig {
@@ -622,6 +828,18 @@ impl DeviceSimulationConfig {
         self.read_error_objects = object_ids;
         self
     }
+
+    /// Return truncated content for the first N file reads
+    pub fn with_truncated_reads(mut self, reads: usize) -> Self {
+        self.truncate_first_reads = reads;
+        self
+    }
+
+    /// Fail the first N file reads with a transient error
+    pub fn with_failed_reads(mut self, reads: usize) -> Self {
+        self.fail_first_reads = reads;
+        self
+    }
 }
 
 /// Statistics about device operations
@@ -701,6 +919,18 @@ mod tests {
         assert_eq!(info3.device_type(), DeviceType::Apple);
     }
 
+    #[test]
+    fn test_device_model_detection() {
+        let info = DeviceInfo::new("id-1", "John's iPhone", "Apple Inc.", "iPhone15,2");
+        assert_eq!(info.device_model().label(), "iPhone 14 Pro");
+
+        let info2 = DeviceInfo::new("id-2", "Pixel", "Google", "Pixel 8");
+        assert_eq!(
+            info2.device_model(),
+            DeviceModel::Unknown("Pixel 8".to_string())
+        );
+    }
+
     #[test]
     fn test_device_type_android_detection() {
         // Test Samsung
@@ -833,6 +1063,32 @@ mod tests {
         assert!(!folder.is_media_file());
     }
 
+    #[test]
+    fn test_storage_info() {
+        let storage = StorageInfo::new(128_000, 32_000);
+        assert_eq!(storage.used_bytes(), 96_000);
+        assert!((storage.used_percent() - 75.0).abs() < f64::EPSILON);
+
+        // Free space larger than capacity doesn't underflow
+        assert_eq!(StorageInfo::new(10, 20).used_bytes(), 0);
+        assert_eq!(StorageInfo::default().used_percent(), 0.0);
+
+        let device = DeviceInfo::new("id", "iPhone", "Apple Inc.", "iPhone 15");
+        assert!(device.storage.is_none());
+        assert_eq!(device.with_storage(storage).storage, Some(storage));
+    }
+
+    #[test]
+    fn test_battery_level_is_clamped() {
+        let device = DeviceInfo::new("id", "iPhone", "Apple Inc.", "iPhone 15");
+        assert!(device.battery_level.is_none());
+        assert_eq!(
+            device.clone().with_battery_level(42).battery_level,
+            Some(42)
+        );
+        assert_eq!(device.with_battery_level(250).battery_level, Some(100));
+    }
+
     #[test]
     fn test_simulation_config_builders() {
         let locked = DeviceSimulationConfig::locked();
@@ -10,29 +10,35 @@
 use crate::core::error::{ExtractionError, Result};
 use crate::device::traits::{
     DeviceContentTrait, DeviceInfo, DeviceManagerTrait, DeviceObject, DeviceSimulationConfig,
-    DeviceType,
+    DeviceType, ObjectMetadata, StorageInfo, StorageVolume,
 };
 use log::{debug, info, trace, warn};
 use std::collections::HashMap;
 use std::ptr::null_mut;
 use std::sync::{Arc, RwLock};
 use windows::{
-    core::{GUID, PCWSTR, PWSTR},
+    core::{GUID, PCWSTR, PROPVARIANT, PWSTR},
     Win32::{
         Devices::PortableDevices::{
             IEnumPortableDeviceObjectIDs, IPortableDevice, IPortableDeviceContent,
-            IPortableDeviceKeyCollection, IPortableDeviceManager, IPortableDeviceProperties,
-            IPortableDeviceValues, PortableDeviceFTM, PortableDeviceKeyCollection,
-            PortableDeviceManager, PortableDeviceValues, WPD_CLIENT_MAJOR_VERSION,
+            IPortableDeviceKeyCollection, IPortableDeviceManager,
+            IPortableDevicePropVariantCollection, IPortableDeviceProperties, IPortableDeviceValues,
+            PortableDeviceFTM, PortableDeviceKeyCollection, PortableDeviceManager,
+            PortableDevicePropVariantCollection, PortableDeviceValues,
+            PORTABLE_DEVICE_DELETE_NO_RECURSION, WPD_CLIENT_MAJOR_VERSION,
             WPD_CLIENT_MINOR_VERSION, WPD_CLIENT_NAME, WPD_CLIENT_REVISION,
-            WPD_CLIENT_SECURITY_QUALITY_OF_SERVICE, WPD_OBJECT_CONTENT_TYPE,
-            WPD_OBJECT_DATE_CREATED, WPD_OBJECT_DATE_MODIFIED, WPD_OBJECT_NAME,
-            WPD_OBJECT_ORIGINAL_FILE_NAME, WPD_OBJECT_SIZE, WPD_RESOURCE_DEFAULT,
+            WPD_CLIENT_SECURITY_QUALITY_OF_SERVICE, WPD_CONTENT_TYPE_AUDIO, WPD_CONTENT_TYPE_IMAGE,
+            WPD_CONTENT_TYPE_VIDEO, WPD_DEVICE_POWER_LEVEL, WPD_MEDIA_HEIGHT, WPD_MEDIA_WIDTH,
+            WPD_OBJECT_CONTENT_TYPE, WPD_OBJECT_DATE_CREATED, WPD_OBJECT_DATE_MODIFIED,
+            WPD_OBJECT_NAME, WPD_OBJECT_ORIGINAL_FILE_NAME, WPD_OBJECT_SIZE, WPD_RESOURCE_DEFAULT,
+            WPD_RESOURCE_THUMBNAIL, WPD_STORAGE_CAPACITY, WPD_STORAGE_FREE_SPACE_IN_BYTES,
         },
         System::Com::{
             CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, IStream,
             CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
         },
+        System::Variant::VT_LPWSTR,
+        UI::Shell::PropertiesSystem::PROPERTYKEY,
     },
 };
 
@@ -43,6 +49,16 @@ const WPD_CONTENT_TYPE_FOLDER: GUID = GUID::from_u128(0x27e2e392_a111_48e0_ab0c_
 const WPD_CONTENT_TYPE_FUNCTIONAL_OBJECT: GUID =
     GUID::from_u128(0x99ed0160_17ff_4c44_9d98_1d7a6f941921);
 
+/// Convert a failed WPD call on an open device into an [`ExtractionError`]
+///
+/// The HRESULT is kept, so callers can tell a transient failure from a
+/// permanent one, and the codes a device gives once it is unplugged become
+/// [`ExtractionError::DeviceDisconnected`]. `context` only goes to the log.
+fn wpd_error(context: &str, err: windows::core::Error) -> ExtractionError {
+    debug!("{}: {}", context, err);
+    ExtractionError::from(err)
+}
+
 /// Manager for device enumeration and connection
 ///
 /// This is the real WPD implementation that talks to actual connected devices.
@@ -315,6 +331,8 @@ impl DeviceManagerTrait for DeviceManager {
                     friendly_name,
                     manufacturer,
                     model,
+                    storage: None,
+                    battery_level: None,
                 };
 
                 // Cache the device info
@@ -361,9 +379,31 @@ impl DeviceManagerTrait for DeviceManager {
             friendly_name,
             manufacturer,
             model,
+            storage: None,
+            battery_level: None,
         })
     }
 
+    fn get_device_storage(&self, device_id: &str) -> Option<StorageInfo> {
+        match self.open_device(device_id) {
+            Ok(content) => content.storage_info(),
+            Err(e) => {
+                debug!("Could not open '{}' to read storage: {}", device_id, e);
+                None
+            }
+        }
+    }
+
+    fn battery_level(&self, device_id: &str) -> Option<u8> {
+        match self.open_device(device_id) {
+            Ok(content) => content.battery_level(),
+            Err(e) => {
+                debug!("Could not open '{}' to read battery: {}", device_id, e);
+                None
+            }
+        }
+    }
+
     fn device_count(&self) -> usize {
         self.enumerate_all_devices().map(|d| d.len()).unwrap_or(0)
     }
@@ -383,9 +423,10 @@ impl PortableDevice {
     /// to the DeviceContent, ensuring the device stays open while content is accessed.
     pub fn into_content(self) -> Result<DeviceContent> {
         unsafe {
-            let content = self.device.Content().map_err(|e| {
-                ExtractionError::ContentError(format!("Failed to get device content: {}", e))
-            })?;
+            let content = self
+                .device
+                .Content()
+                .map_err(|e| wpd_error("Failed to get device content", e))?;
 
             // Clone the device handle to pass to DeviceContent
             let device = self.device.clone();
@@ -420,8 +461,14 @@ pub struct DeviceContent {
     parent_cache: Arc<RwLock<HashMap<String, String>>>,
 }
 
-// DeviceContent is not Send/Sync due to COM, but we mark it as such
-// because we only use it from a single thread at a time in practice.
+// SAFETY: discovery shares one DeviceContent between its worker threads,
+// which list folders through it at the same time. The device is created as
+// `PortableDeviceFTM`, the free-threaded WPD class, so its interfaces (and
+// the IPortableDeviceContent taken from it) can be called from any thread
+// without marshalling; WPD serializes the requests it sends to the driver.
+// Every thread that uses it joins the multithreaded apartment first
+// (`initialize_com` uses COINIT_MULTITHREADED, and each discovery worker
+// calls it), and the caches are behind RwLocks.
 unsafe impl Send for DeviceContent {}
 unsafe impl Sync for DeviceContent {}
 
@@ -441,6 +488,162 @@ impl DeviceContent {
         &self.content
     }
 
+    /// Read the battery level (0-100) from the device object
+    ///
+    /// Returns `None` if the device doesn't report a power level.
+    pub fn battery_level(&self) -> Option<u8> {
+        unsafe {
+            let properties: IPortableDeviceProperties = self.content.Properties().ok()?;
+            let keys: IPortableDeviceKeyCollection =
+                CoCreateInstance(&PortableDeviceKeyCollection, None, CLSCTX_INPROC_SERVER).ok()?;
+            keys.Add(&WPD_DEVICE_POWER_LEVEL).ok()?;
+
+            let device_id: Vec<u16> = "DEVICE".encode_utf16().chain(std::iter::once(0)).collect();
+            let values = match properties.GetValues(PCWSTR(device_id.as_ptr()), &keys) {
+                Ok(values) => values,
+                Err(e) => {
+                    debug!("No device properties: {}", e);
+                    return None;
+                }
+            };
+            let level = values
+                .GetUnsignedIntegerValue(&WPD_DEVICE_POWER_LEVEL)
+                .ok()?;
+            trace!("Device power level: {}", level);
+
+            Some(level.min(100) as u8)
+        }
+    }
+
+    /// Sum the capacity and free space of the device's storages
+    ///
+    /// Returns `None` if no storage reports a capacity.
+    pub fn storage_info(&self) -> Option<StorageInfo> {
+        self.enumerate_storages()
+            .ok()?
+            .iter()
+            .filter_map(|volume| volume.storage_info())
+            .reduce(|total, volume| {
+                StorageInfo::new(
+                    total.capacity_bytes + volume.capacity_bytes,
+                    total.free_bytes + volume.free_bytes,
+                )
+            })
+    }
+
+    /// Read the capacity and free space of a root object
+    ///
+    /// Returns `None` for objects that are not storages (they have no capacity).
+    fn read_storage_capacity(&self, object_id: &str) -> Option<(u64, Option<u64>)> {
+        unsafe {
+            let properties: IPortableDeviceProperties = self.content.Properties().ok()?;
+            let keys: IPortableDeviceKeyCollection =
+                CoCreateInstance(&PortableDeviceKeyCollection, None, CLSCTX_INPROC_SERVER).ok()?;
+            keys.Add(&WPD_STORAGE_CAPACITY).ok()?;
+            keys.Add(&WPD_STORAGE_FREE_SPACE_IN_BYTES).ok()?;
+
+            let id_wide: Vec<u16> = object_id.encode_utf16().chain(std::iter::once(0)).collect();
+            let values = match properties.GetValues(PCWSTR(id_wide.as_ptr()), &keys) {
+                Ok(values) => values,
+                Err(e) => {
+                    debug!("No storage properties for '{}': {}", object_id, e);
+                    return None;
+                }
+            };
+
+            let capacity = values
+                .GetUnsignedLargeIntegerValue(&WPD_STORAGE_CAPACITY)
+                .ok()?;
+            let free = values
+                .GetUnsignedLargeIntegerValue(&WPD_STORAGE_FREE_SPACE_IN_BYTES)
+                .ok();
+            Some((capacity, free))
+        }
+    }
+
+    /// Read a resource of an object (the file data, its thumbnail, ...)
+    fn read_resource(&self, object_id: &str, resource: &PROPERTYKEY) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.stream_resource(object_id, resource, &mut |chunk| {
+            data.extend_from_slice(chunk);
+            Ok(())
+        })?;
+        Ok(data)
+    }
+
+    /// Read a resource stream, passing each chunk to `on_chunk` as it arrives
+    fn stream_resource(
+        &self,
+        object_id: &str,
+        resource: &PROPERTYKEY,
+        on_chunk: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        unsafe {
+            // Get the resources interface
+            let resources = self
+                .content
+                .Transfer()
+                .map_err(|e| wpd_error("Failed to get transfer interface", e))?;
+
+            // Convert object ID to wide string
+            let object_id_wide: Vec<u16> =
+                object_id.encode_utf16().chain(std::iter::once(0)).collect();
+
+            // Get the stream for reading - STGM_READ = 0
+            let mut optimal_buffer_size: u32 = 0;
+            let mut stream_opt: Option<IStream> = None;
+
+            resources
+                .GetStream(
+                    PCWSTR(object_id_wide.as_ptr()),
+                    resource,
+                    0, // STGM_READ
+                    &mut optimal_buffer_size,
+                    &mut stream_opt,
+                )
+                .map_err(|e| wpd_error(&format!("Failed to get stream of '{}'", object_id), e))?;
+
+            let stream = stream_opt.ok_or_else(|| {
+                ExtractionError::ContentError("Failed to get stream: stream is None".to_string())
+            })?;
+
+            // Use a reasonable buffer size
+            let buffer_size = if optimal_buffer_size > 0 && optimal_buffer_size <= 1048576 {
+                optimal_buffer_size as usize
+            } else {
+                262144 // 256KB default
+            };
+
+            let mut buffer = vec![0u8; buffer_size];
+
+            loop {
+                let mut bytes_read: u32 = 0;
+                // A failed read leaves the copy incomplete, whatever it returned
+                stream
+                    .Read(
+                        buffer.as_mut_ptr() as *mut _,
+                        buffer_size as u32,
+                        Some(&mut bytes_read),
+                    )
+                    .ok()
+                    .map_err(|e| wpd_error(&format!("Failed to read '{}'", object_id), e))?;
+
+                if bytes_read == 0 {
+                    break;
+                }
+
+                on_chunk(&buffer[..bytes_read as usize])?;
+
+                // A short read means the end of the stream
+                if bytes_read < buffer_size as u32 {
+                    break;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
     /// Parse object properties from IPortableDeviceValues
     fn parse_object_properties(
         &self,
@@ -620,6 +823,20 @@ impl DeviceContent {
             .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
     }
 
+    /// Generic content type for a WPD content type GUID (media types only)
+    fn content_type_category(content_type: GUID) -> Option<String> {
+        let category = if content_type == WPD_CONTENT_TYPE_IMAGE {
+            "image/*"
+        } else if content_type == WPD_CONTENT_TYPE_VIDEO {
+            "video/*"
+        } else if content_type == WPD_CONTENT_TYPE_AUDIO {
+            "audio/*"
+        } else {
+            return None;
+        };
+        Some(category.to_string())
+    }
+
     /// Cache an object and its parent relationship
     fn cache_object(&self, object: &DeviceObject) {
         if let Ok(mut cache) = self.object_cache.write() {
@@ -659,23 +876,18 @@ impl DeviceContentTrait for DeviceContent {
                 .EnumObjects(0, PCWSTR(parent_id_wide.as_ptr()), None)
                 .map_err(|e| {
                     warn!("Failed to enumerate objects in '{}': {}", parent_id, e);
-                    ExtractionError::ContentError(format!("Failed to enumerate objects: {}", e))
+                    ExtractionError::from(e)
                 })?;
 
             // Get properties interface
-            let properties: IPortableDeviceProperties = self.content.Properties().map_err(|e| {
-                ExtractionError::ContentError(format!("Failed to get properties: {}", e))
-            })?;
+            let properties: IPortableDeviceProperties = self
+                .content
+                .Properties()
+                .map_err(|e| wpd_error("Failed to get properties", e))?;
 
             // Create key collection for properties we want
             let keys_to_read: IPortableDeviceKeyCollection =
-                CoCreateInstance(&PortableDeviceKeyCollection, None, CLSCTX_INPROC_SERVER)
-                    .map_err(|e| {
-                        ExtractionError::ContentError(format!(
-                            "Failed to create key collection: {}",
-                            e
-                        ))
-                    })?;
+                CoCreateInstance(&PortableDeviceKeyCollection, None, CLSCTX_INPROC_SERVER)?;
 
             keys_to_read.Add(&WPD_OBJECT_NAME)?;
             keys_to_read.Add(&WPD_OBJECT_ORIGINAL_FILE_NAME)?;
@@ -734,9 +946,10 @@ impl DeviceContentTrait for DeviceContent {
                     CoTaskMemFree(Some(object_id_ptr.0 as *const _));
                 }
 
-                if result.is_err() {
-                    break;
-                }
+                // A failed batch would leave the listing incomplete
+                result
+                    .ok()
+                    .map_err(|e| wpd_error(&format!("Failed to list '{}'", parent_id), e))?;
             }
 
             debug!("Found {} objects in '{}'", objects.len(), parent_id);
@@ -753,67 +966,27 @@ impl DeviceContentTrait for DeviceContent {
     }
 
     fn read_file(&self, object_id: &str) -> Result<Vec<u8>> {
-        unsafe {
-            // Get the resources interface
-            let resources = self.content.Transfer().map_err(|e| {
-                ExtractionError::ContentError(format!("Failed to get transfer interface: {}", e))
-            })?;
-
-            // Convert object ID to wide string
-            let object_id_wide: Vec<u16> =
-                object_id.encode_utf16().chain(std::iter::once(0)).collect();
-
-            // Get the stream for reading - STGM_READ = 0
-            let mut optimal_buffer_size: u32 = 0;
-            let mut stream_opt: Option<IStream> = None;
-
-            resources
-                .GetStream(
-                    PCWSTR(object_id_wide.as_ptr()),
-                    &WPD_RESOURCE_DEFAULT,
-                    0, // STGM_READ
-                    &mut optimal_buffer_size,
-                    &mut stream_opt,
-                )
-                .map_err(|e| {
-                    ExtractionError::ContentError(format!("Failed to get file stream: {}", e))
-                })?;
-
-            let stream = stream_opt.ok_or_else(|| {
-                ExtractionError::ContentError("Failed to get stream: stream is None".to_string())
-            })?;
-
-            // Use a reasonable buffer size
-            let buffer_size = if optimal_buffer_size > 0 && optimal_buffer_size <= 1048576 {
-                optimal_buffer_size as usize
-            } else {
-                262144 // 256KB default
-            };
-
-            let mut data = Vec::new();
-            let mut buffer = vec![0u8; buffer_size];
-
-            loop {
-                let mut bytes_read: u32 = 0;
-                let result = stream.Read(
-                    buffer.as_mut_ptr() as *mut _,
-                    buffer_size as u32,
-                    Some(&mut bytes_read),
-                );
-
-                if bytes_read == 0 {
-                    break;
-                }
+        self.read_resource(object_id, &WPD_RESOURCE_DEFAULT)
+    }
 
-                data.extend_from_slice(&buffer[..bytes_read as usize]);
+    fn read_file_chunked(
+        &self,
+        object_id: &str,
+        on_chunk: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        self.stream_resource(object_id, &WPD_RESOURCE_DEFAULT, on_chunk)
+    }
 
-                // Check if we've read all data or encountered an error
-                if result.is_err() || bytes_read < buffer_size as u32 {
-                    break;
-                }
+    fn read_thumbnail(&self, object_id: &str) -> Option<Vec<u8>> {
+        // Not every file has one (and some devices have none); the caller
+        // falls back to decoding the full file
+        match self.read_resource(object_id, &WPD_RESOURCE_THUMBNAIL) {
+            Ok(data) if !data.is_empty() => Some(data),
+            Ok(_) => None,
+            Err(e) => {
+                trace!("No thumbnail for '{}': {}", object_id, e);
+                None
             }
-
-            Ok(data)
         }
     }
 
@@ -830,6 +1003,60 @@ impl DeviceContentTrait for DeviceContent {
         Ok(None)
     }
 
+    fn object_metadata(&self, object_id: &str) -> Result<ObjectMetadata> {
+        unsafe {
+            let properties: IPortableDeviceProperties = self
+                .content
+                .Properties()
+                .map_err(|e| wpd_error("Failed to get properties", e))?;
+
+            let keys: IPortableDeviceKeyCollection =
+                CoCreateInstance(&PortableDeviceKeyCollection, None, CLSCTX_INPROC_SERVER)?;
+            keys.Add(&WPD_OBJECT_NAME)?;
+            keys.Add(&WPD_OBJECT_ORIGINAL_FILE_NAME)?;
+            keys.Add(&WPD_OBJECT_CONTENT_TYPE)?;
+            keys.Add(&WPD_OBJECT_DATE_CREATED)?;
+            keys.Add(&WPD_OBJECT_DATE_MODIFIED)?;
+            keys.Add(&WPD_MEDIA_WIDTH)?;
+            keys.Add(&WPD_MEDIA_HEIGHT)?;
+
+            let id_wide: Vec<u16> = object_id.encode_utf16().chain(std::iter::once(0)).collect();
+            let values = properties
+                .GetValues(PCWSTR(id_wide.as_ptr()), &keys)
+                .map_err(|e| {
+                    wpd_error(&format!("Failed to get properties of '{}'", object_id), e)
+                })?;
+
+            let original_file_name = self
+                .get_string_value(&values, &WPD_OBJECT_ORIGINAL_FILE_NAME)
+                .or_else(|_| self.get_string_value(&values, &WPD_OBJECT_NAME))
+                .ok()
+                .filter(|name| !name.is_empty());
+
+            // The extension is more specific than WPD's image/video/audio
+            let content_type = original_file_name
+                .as_deref()
+                .and_then(DeviceObject::guess_content_type)
+                .or_else(|| {
+                    values
+                        .GetGuidValue(&WPD_OBJECT_CONTENT_TYPE)
+                        .ok()
+                        .and_then(Self::content_type_category)
+                });
+
+            let metadata = ObjectMetadata {
+                date_created: self.get_date_value(&values, &WPD_OBJECT_DATE_CREATED),
+                date_modified: self.get_date_value(&values, &WPD_OBJECT_DATE_MODIFIED),
+                content_type,
+                original_file_name,
+                width: values.GetUnsignedIntegerValue(&WPD_MEDIA_WIDTH).ok(),
+                height: values.GetUnsignedIntegerValue(&WPD_MEDIA_HEIGHT).ok(),
+            };
+            trace!("Metadata for '{}': {:?}", object_id, metadata);
+            Ok(metadata)
+        }
+    }
+
     fn get_object_path(&self, object_id: &str) -> Option<String> {
         let mut parts = Vec::new();
         let mut current_id = object_id.to_string();
@@ -860,6 +1087,80 @@ impl DeviceContentTrait for DeviceContent {
             Some(parts.join("/"))
         }
     }
+
+    fn enumerate_storages(&self) -> Result<Vec<StorageVolume>> {
+        let roots: Vec<DeviceObject> = self
+            .enumerate_objects()?
+            .into_iter()
+            .filter(|object| object.is_folder)
+            .collect();
+
+        let volumes: Vec<StorageVolume> = roots
+            .iter()
+            .map(|root| {
+                let mut volume = StorageVolume::from_root(root);
+                if let Some((capacity, free)) = self.read_storage_capacity(&root.object_id) {
+                    trace!(
+                        "Storage '{}': {} bytes, {:?} free",
+                        root.name,
+                        capacity,
+                        free
+                    );
+                    volume.capacity_bytes = Some(capacity);
+                    volume.free_bytes = free;
+                }
+                volume
+            })
+            .collect();
+
+        // Storages report a capacity; other root folders don't. If nothing
+        // reports one, the device hides it and every root folder is a storage.
+        if volumes.iter().any(|volume| volume.capacity_bytes.is_some()) {
+            Ok(volumes
+                .into_iter()
+                .filter(|volume| volume.capacity_bytes.is_some())
+                .collect())
+        } else {
+            Ok(volumes)
+        }
+    }
+
+    fn delete_object(&self, object_id: &str) -> Result<()> {
+        unsafe {
+            // Delete takes a collection of object IDs as VT_LPWSTR values
+            let object_ids: IPortableDevicePropVariantCollection = CoCreateInstance(
+                &PortableDevicePropVariantCollection,
+                None,
+                CLSCTX_INPROC_SERVER,
+            )
+            .map_err(|e| {
+                ExtractionError::ComError(format!("Failed to create object ID collection: {}", e))
+            })?;
+
+            let value = PROPVARIANT::from(object_id);
+            object_ids.Add(&value)?;
+            object_ids.ChangeType(VT_LPWSTR.0)?;
+
+            self.content
+                .Delete(
+                    PORTABLE_DEVICE_DELETE_NO_RECURSION.0 as u32,
+                    &object_ids,
+                    null_mut(),
+                )
+                .map_err(|e| wpd_error(&format!("Failed to delete '{}'", object_id), e))?;
+        }
+
+        // Forget the deleted object so later lookups don't return stale data
+        if let Ok(mut cache) = self.object_cache.write() {
+            cache.remove(object_id);
+        }
+        if let Ok(mut parents) = self.parent_cache.write() {
+            parents.remove(object_id);
+        }
+
+        debug!("Deleted object from device: {}", object_id);
+        Ok(())
+    }
 }
 
 impl Drop for DeviceContent {
@@ -11,7 +11,10 @@
 //! 2. **Hash Index**: Only files with matching sizes are hash-compared
 //!
 //! This avoids computing expensive SHA256 hashes for files that can't possibly
-//! be duplicates (different sizes).
+//! be duplicates (different sizes). Between the two tiers, a quick signature
+//! (SHA256 of the first [`DuplicateConfig::quick_hash_bytes`] bytes) rules out
+//! same-sized files that differ near the start, so the full hash is only
+//! computed when the quick signature collides with an indexed file.
 //!
 //! # Example
 //!
@@ -54,7 +57,10 @@ use std::time::SystemTime;
 use walkdir::WalkDir;
 
 /// Buffer size for streaming hash computation (64KB)
-const HASH_BUFFER_SIZE: usize = 64 * 1024;
+pub(crate) const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Default number of leading bytes covered by the quick signature
+pub const DEFAULT_QUICK_HASH_BYTES: usize = 4096;
 
 /// Supported media file extensions
 const MEDIA_EXTENSIONS: &[&str] = &[
@@ -95,6 +101,10 @@ pub struct DuplicateConfig {
     /// Path to the cache file
     pub cache_file: PathBuf,
 
+    /// Load an existing cache but never write it back (used by dry runs)
+    #[serde(default)]
+    pub cache_read_only: bool,
+
     /// Whether to include subdirectories when scanning
     pub recursive: bool,
 
@@ -109,6 +119,15 @@ pub struct DuplicateConfig {
 
     /// Only index media files (photos/videos)
     pub media_only: bool,
+
+    /// Leading bytes hashed for the quick signature checked before the full
+    /// hash (0 = always compute the full hash)
+    #[serde(default = "default_quick_hash_bytes")]
+    pub quick_hash_bytes: usize,
+}
+
+fn default_quick_hash_bytes() -> usize {
+    DEFAULT_QUICK_HASH_BYTES
 }
 
 impl Default for DuplicateConfig {
@@ -117,11 +136,13 @@ impl Default for DuplicateConfig {
             comparison_folders: Vec::new(),
             cache_enabled: true,
             cache_file: PathBuf::from("./.duplicate_cache.json"),
+            cache_read_only: false,
             recursive: true,
             follow_symlinks: false,
             min_file_size: 0,
             max_file_size: 0,
             media_only: true,
+            quick_hash_bytes: DEFAULT_QUICK_HASH_BYTES,
         }
     }
 }
@@ -157,6 +178,12 @@ impl DuplicateConfig {
         self
     }
 
+    /// Load the cache without saving newly computed hashes back to it
+    pub fn with_cache_read_only(mut self, read_only: bool) -> Self {
+        self.cache_read_only = read_only;
+        self
+    }
+
     /// Set whether to scan recursively
     pub fn with_recursive(mut self, recursive: bool) -> Self {
         self.recursive = recursive;
@@ -180,6 +207,12 @@ impl DuplicateConfig {
         self.media_only = media_only;
         self
     }
+
+    /// Set how many leading bytes the quick signature covers (0 = disabled)
+    pub fn with_quick_hash_bytes(mut self, bytes: usize) -> Self {
+        self.quick_hash_bytes = bytes;
+        self
+    }
 }
 
 /// Entry in the duplicate index
@@ -192,6 +225,13 @@ pub struct IndexEntry {
     /// SHA256 hash of the file
     #[serde(with = "hex_hash")]
     pub hash: Sha256Hash,
+    /// SHA256 of the first `quick_hash_bytes` bytes (None = not computed)
+    #[serde(
+        default,
+        with = "hex_hash_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub quick_hash: Option<Sha256Hash>,
     /// When this entry was indexed
     #[serde(default = "default_indexed_at")]
     pub indexed_at: u64,
@@ -231,6 +271,34 @@ mod hex_hash {
     }
 }
 
+/// Serde helper for optional hex-encoded hashes
+mod hex_hash_opt {
+    use super::Sha256Hash;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(hash: &Option<Sha256Hash>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match hash {
+            Some(hash) => super::hex_hash::serialize(hash, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Sha256Hash>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) => super::hex_to_hash(&s)
+                .map(Some)
+                .ok_or_else(|| serde::de::Error::custom("Invalid hash")),
+            None => Ok(None),
+        }
+    }
+}
+
 /// Statistics about the duplicate index
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct IndexStats {
@@ -251,7 +319,7 @@ pub struct IndexStats {
 }
 
 /// A group of duplicate files
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(dead_code)]
 pub struct DuplicateGroup {
     /// The shared hash
@@ -297,6 +365,9 @@ pub struct DuplicateIndex {
 
     /// Configuration used
     config: DuplicateConfig,
+
+    /// Number of full hashes computed by lookups (quick signature collisions)
+    full_hashes: AtomicUsize,
 }
 
 impl DuplicateIndex {
@@ -308,6 +379,7 @@ impl DuplicateIndex {
             entries: Vec::new(),
             stats: IndexStats::default(),
             config,
+            full_hashes: AtomicUsize::new(0),
         }
     }
 
@@ -328,17 +400,18 @@ impl DuplicateIndex {
         let start_time = std::time::Instant::now();
         let mut index = Self::new(config.clone());
 
-        // Try to load from cache first
+        // Hashes from the cache are reused for files unchanged since they were
+        // indexed; everything else on disk is hashed again
+        let mut cached: HashMap<PathBuf, IndexEntry> = HashMap::new();
         if config.cache_enabled && config.cache_file.exists() {
             match index.load_cache(&config.cache_file) {
-                Ok(()) => {
+                Ok(entries) => {
                     info!(
                         "Loaded {} entries from cache: {}",
-                        index.entries.len(),
+                        entries.len(),
                         config.cache_file.display()
                     );
-                    index.stats.build_time_ms = start_time.elapsed().as_millis() as u64;
-                    return Ok(index);
+                    cached = entries.into_iter().map(|e| (e.path.clone(), e)).collect();
                 }
                 Err(e) => {
                     warn!("Failed to load cache, rebuilding index: {}", e);
@@ -409,9 +482,15 @@ impl DuplicateIndex {
                     return None;
                 }
 
+                if let Some(entry) = cached.get(path).filter(|entry| {
+                    entry.size == size && unchanged_since(&metadata, entry.indexed_at)
+                }) {
+                    return Some(entry.clone());
+                }
+
                 // Compute hash
-                match compute_file_hash(path) {
-                    Ok(hash) => {
+                match hash_file(path, config.quick_hash_bytes) {
+                    Ok((hash, quick_hash)) => {
                         hashed.fetch_add(1, Ordering::Relaxed);
                         let indexed_at = SystemTime::now()
                             .duration_since(SystemTime::UNIX_EPOCH)
@@ -422,6 +501,7 @@ impl DuplicateIndex {
                             path: path.clone(),
                             size,
                             hash,
+                            quick_hash,
                             indexed_at,
                         })
                     }
@@ -444,6 +524,7 @@ impl DuplicateIndex {
         for entry in entries.into_iter().flatten() {
             index.add_entry(entry);
         }
+        let changed = hashed.load(Ordering::Relaxed) > 0 || index.entries.len() != cached.len();
 
         // Update statistics
         index.stats.errors = errors.load(Ordering::Relaxed);
@@ -460,7 +541,7 @@ impl DuplicateIndex {
         );
 
         // Save cache
-        if config.cache_enabled {
+        if config.cache_enabled && !config.cache_read_only && changed {
             if let Err(e) = index.save_cache(&config.cache_file) {
                 warn!("Failed to save cache: {}", e);
             } else {
@@ -559,20 +640,7 @@ impl DuplicateIndex {
     /// * `None` - If this is not a duplicate
     #[allow(dead_code)]
     pub fn find_duplicate(&self, data: &[u8]) -> Option<&Path> {
-        let size = data.len() as u64;
-
-        // First check: do we have any files with this size?
-        let size_matches = self.size_index.get(&size)?;
-
-        if size_matches.is_empty() {
-            return None;
-        }
-
-        // Compute hash of the input data
-        let hash = compute_data_hash(data);
-
-        // Look up in hash index
-        self.find_duplicate_by_hash(&hash)
+        self.find_duplicate_with_size(data, data.len() as u64)
     }
 
     /// Check if data with a known size is a duplicate
@@ -583,17 +651,49 @@ impl DuplicateIndex {
         // First check: do we have any files with this size?
         let size_matches = self.size_index.get(&size)?;
 
-        if size_matches.is_empty() {
+        // Second check: does the start of the data match any of them?
+        let quick_bytes = self.config.quick_hash_bytes;
+        let quick_hash =
+            (quick_bytes > 0).then(|| compute_data_hash(&data[..data.len().min(quick_bytes)]));
+        if !self.quick_signature_matches(size_matches, quick_hash) {
             return None;
         }
 
         // Compute hash of the input data
+        self.full_hashes.fetch_add(1, Ordering::Relaxed);
         let hash = compute_data_hash(data);
 
         // Look up in hash index
         self.find_duplicate_by_hash(&hash)
     }
 
+    /// Check whether any of the `candidates` could share the quick signature
+    ///
+    /// Entries indexed without a quick signature always count as possible
+    /// matches, as does a missing `quick_hash` (quick signatures disabled).
+    fn quick_signature_matches(
+        &self,
+        candidates: &[usize],
+        quick_hash: Option<Sha256Hash>,
+    ) -> bool {
+        let Some(quick_hash) = quick_hash else {
+            return !candidates.is_empty();
+        };
+        candidates.iter().any(|&idx| {
+            self.entries[idx]
+                .quick_hash
+                .is_none_or(|indexed| indexed == quick_hash)
+        })
+    }
+
+    /// Number of full hashes computed by lookups so far
+    ///
+    /// Lookups only compute a full hash when an indexed file has the same
+    /// size and quick signature.
+    pub fn full_hash_lookups(&self) -> usize {
+        self.full_hashes.load(Ordering::Relaxed)
+    }
+
     /// Find a duplicate by its pre-computed hash
     pub fn find_duplicate_by_hash(&self, hash: &Sha256Hash) -> Option<&Path> {
         let indices = self.hash_index.get(hash)?;
@@ -605,18 +705,28 @@ impl DuplicateIndex {
     #[allow(dead_code)]
     pub fn is_file_duplicate(&self, path: &Path) -> Result<Option<&Path>> {
         // Get file size first for pre-filtering
-        let metadata = fs::metadata(path).map_err(|e| {
-            ExtractionError::IoError(format!("Failed to read file metadata: {}", e))
-        })?;
+        let metadata = fs::metadata(path)?;
 
         let size = metadata.len();
 
         // Check if any indexed file has this size
-        if !self.size_index.contains_key(&size) {
+        let Some(size_matches) = self.size_index.get(&size) else {
+            return Ok(None);
+        };
+
+        // Check the quick signature before hashing the whole file
+        let quick_bytes = self.config.quick_hash_bytes;
+        let quick_hash = if quick_bytes > 0 {
+            Some(compute_file_quick_hash(path, quick_bytes)?)
+        } else {
+            None
+        };
+        if !self.quick_signature_matches(size_matches, quick_hash) {
             return Ok(None);
         }
 
         // Compute hash and look up
+        self.full_hashes.fetch_add(1, Ordering::Relaxed);
         let hash = compute_file_hash(path)?;
         Ok(self.find_duplicate_by_hash(&hash))
     }
@@ -730,8 +840,8 @@ impl DuplicateIndex {
         Ok(())
     }
 
-    /// Load the index from a cache file
-    fn load_cache(&mut self, path: &Path) -> Result<()> {
+    /// Load the entries of a cache file written for this configuration
+    fn load_cache(&self, path: &Path) -> Result<Vec<IndexEntry>> {
         let json = fs::read_to_string(path)
             .map_err(|e| ExtractionError::IoError(format!("Failed to read cache file: {}", e)))?;
 
@@ -755,14 +865,7 @@ impl DuplicateIndex {
             ));
         }
 
-        // Load entries
-        for entry in cache.entries {
-            self.add_entry(entry);
-        }
-
-        self.compute_stats();
-
-        Ok(())
+        Ok(cache.entries)
     }
 
     /// Compute a hash of the configuration for cache validation
@@ -786,38 +889,76 @@ impl DuplicateIndex {
         // Include relevant config options
         hasher.update(if self.config.recursive { b"r" } else { b"n" });
         hasher.update(if self.config.media_only { b"m" } else { b"a" });
+        hasher.update(self.config.quick_hash_bytes.to_le_bytes());
 
         let result = hasher.finalize();
         result.iter().map(|b| format!("{:02x}", b)).collect()
     }
 }
 
+/// Whether a file was last modified before `indexed_at` (seconds since the
+/// epoch), so a hash computed then still holds
+///
+/// A change in the same second as indexing counts as a change.
+fn unchanged_since(metadata: &fs::Metadata, indexed_at: u64) -> bool {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .is_some_and(|modified| modified.as_secs() < indexed_at)
+}
+
 /// Compute SHA256 hash of a file using streaming (memory-efficient)
 pub fn compute_file_hash(path: &Path) -> Result<Sha256Hash> {
-    let file = File::open(path)
-        .map_err(|e| ExtractionError::IoError(format!("Failed to open file: {}", e)))?;
+    hash_file(path, 0).map(|(hash, _)| hash)
+}
+
+/// Compute the SHA256 of a file's first `quick_bytes` bytes
+pub fn compute_file_quick_hash(path: &Path, quick_bytes: usize) -> Result<Sha256Hash> {
+    let file = File::open(path)?;
+
+    let mut head = Vec::with_capacity(quick_bytes);
+    file.take(quick_bytes as u64).read_to_end(&mut head)?;
+
+    Ok(compute_data_hash(&head))
+}
+
+/// Stream a file once, computing its full hash and (when `quick_bytes` is
+/// non-zero) the quick signature of its first `quick_bytes` bytes
+fn hash_file(path: &Path, quick_bytes: usize) -> Result<(Sha256Hash, Option<Sha256Hash>)> {
+    let file = File::open(path)?;
 
     let mut reader = BufReader::with_capacity(HASH_BUFFER_SIZE, file);
     let mut hasher = Sha256::new();
+    let mut quick_hasher = (quick_bytes > 0).then(Sha256::new);
+    let mut quick_remaining = quick_bytes;
     let mut buffer = [0u8; HASH_BUFFER_SIZE];
 
     loop {
-        let bytes_read = reader
-            .read(&mut buffer)
-            .map_err(|e| ExtractionError::IoError(format!("Failed to read file: {}", e)))?;
+        let bytes_read = reader.read(&mut buffer)?;
 
         if bytes_read == 0 {
             break;
         }
 
+        if let Some(ref mut quick_hasher) = quick_hasher {
+            let head = bytes_read.min(quick_remaining);
+            quick_hasher.update(&buffer[..head]);
+            quick_remaining -= head;
+        }
         hasher.update(&buffer[..bytes_read]);
     }
 
-    let result = hasher.finalize();
-    let mut hash = [0u8; 32];
-    hash.copy_from_slice(&result);
-
-    Ok(hash)
+    let to_hash = |digest: &[u8]| {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(digest);
+        hash
+    };
+
+    Ok((
+        to_hash(&hasher.finalize()),
+        quick_hasher.map(|h| to_hash(&h.finalize())),
+    ))
 }
 
 /// Compute SHA256 hash of in-memory data
@@ -955,6 +1096,7 @@ mod tests {
             path: PathBuf::from("/photos/original.jpg"),
             size: data.len() as u64,
             hash,
+            quick_hash: None,
             indexed_at: 0,
         };
         index.add_entry(entry);
@@ -982,6 +1124,7 @@ mod tests {
             path: PathBuf::from("/photos/original.jpg"),
             size: data.len() as u64,
             hash,
+            quick_hash: None,
             indexed_at: 0,
         };
         index.add_entry(entry);
@@ -1005,6 +1148,7 @@ mod tests {
                 path: PathBuf::from(format!("/photos/file{}.jpg", i)),
                 size: data.len() as u64,
                 hash,
+                quick_hash: None,
                 indexed_at: 0,
             };
             index.add_entry(entry);
@@ -1030,6 +1174,7 @@ mod tests {
                 path: PathBuf::from(format!("/photos/file{}.jpg", i)),
                 size: data.len() as u64,
                 hash,
+                quick_hash: None,
                 indexed_at: 0,
             };
             index.add_entry(entry);
@@ -1042,6 +1187,7 @@ mod tests {
             path: PathBuf::from("/photos/duplicate.jpg"),
             size: dup_data.len() as u64,
             hash: dup_hash,
+            quick_hash: None,
             indexed_at: 0,
         };
         index.add_entry(dup_entry);
@@ -1066,6 +1212,7 @@ mod tests {
             path: PathBuf::from("/photos/file1.jpg"),
             size: data1.len() as u64,
             hash: compute_data_hash(data1),
+            quick_hash: None,
             indexed_at: 0,
         };
         index1.add_entry(entry1);
@@ -1076,6 +1223,7 @@ mod tests {
             path: PathBuf::from("/photos/file2.jpg"),
             size: data2.len() as u64,
             hash: compute_data_hash(data2),
+            quick_hash: None,
             indexed_at: 0,
         };
         index2.add_entry(entry2);
@@ -1095,6 +1243,7 @@ mod tests {
             path: PathBuf::from("/photos/test.jpg"),
             size: data.len() as u64,
             hash: compute_data_hash(data),
+            quick_hash: None,
             indexed_at: 1234567890,
         };
 
@@ -1132,4 +1281,130 @@ mod tests {
         assert_eq!(config.min_file_size, 1024);
         assert_eq!(config.max_file_size, 10 * 1024 * 1024);
     }
+
+    /// Index entry for `data` as built from disk, with its quick signature
+    fn indexed(path: &str, data: &[u8], quick_bytes: usize) -> IndexEntry {
+        IndexEntry {
+            path: PathBuf::from(path),
+            size: data.len() as u64,
+            hash: compute_data_hash(data),
+            quick_hash: Some(compute_data_hash(&data[..data.len().min(quick_bytes)])),
+            indexed_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_quick_signature_avoids_full_hash() {
+        let mut index = DuplicateIndex::new(DuplicateConfig::new().with_quick_hash_bytes(8));
+        index.add_entry(indexed("/photos/original.jpg", b"AAAAAAAA-original", 8));
+
+        // Different sizes are rejected by the size index alone
+        assert!(index.find_duplicate(b"AAAAAAAA-longer-content").is_none());
+        assert!(index.find_duplicate(b"AAAAAAAA").is_none());
+        // Same size, but the first 8 bytes differ
+        assert!(index.find_duplicate(b"BBBBBBBB-original").is_none());
+
+        assert_eq!(index.full_hash_lookups(), 0);
+    }
+
+    #[test]
+    fn test_quick_signature_collision_falls_back_to_full_hash() {
+        let mut index = DuplicateIndex::new(DuplicateConfig::new().with_quick_hash_bytes(8));
+        index.add_entry(indexed("/photos/original.jpg", b"AAAAAAAA-original", 8));
+
+        // Same size and start, different tail: the full hash tells them apart
+        assert!(index.find_duplicate(b"AAAAAAAA-imposter").is_none());
+        assert_eq!(index.full_hash_lookups(), 1);
+
+        assert_eq!(
+            index.find_duplicate(b"AAAAAAAA-original"),
+            Some(Path::new("/photos/original.jpg"))
+        );
+        assert_eq!(index.full_hash_lookups(), 2);
+    }
+
+    #[test]
+    fn test_index_built_from_folder_stores_quick_signature() {
+        let temp = tempfile::tempdir().unwrap();
+        let original = temp.path().join("original.jpg");
+        let data = vec![7u8; 3 * HASH_BUFFER_SIZE];
+        fs::write(&original, &data).unwrap();
+
+        let config = DuplicateConfig::new()
+            .with_folder(temp.path().to_path_buf())
+            .with_cache(false)
+            .with_quick_hash_bytes(HASH_BUFFER_SIZE + 10);
+        let index =
+            DuplicateIndex::build_from_folders(&config, Arc::new(AtomicBool::new(false)), |_| {})
+                .unwrap();
+
+        let entry = &index.entries()[0];
+        assert_eq!(entry.hash, compute_data_hash(&data));
+        assert_eq!(
+            entry.quick_hash,
+            Some(compute_data_hash(&data[..HASH_BUFFER_SIZE + 10]))
+        );
+        assert_eq!(
+            compute_file_quick_hash(&original, HASH_BUFFER_SIZE + 10).unwrap(),
+            entry.quick_hash.unwrap()
+        );
+
+        assert_eq!(
+            index.is_file_duplicate(&original).unwrap(),
+            Some(original.as_path())
+        );
+        assert_eq!(index.full_hash_lookups(), 1);
+    }
+
+    #[test]
+    fn test_cached_hashes_are_reused_only_for_unchanged_files() {
+        use std::time::Duration;
+
+        let temp = tempfile::tempdir().unwrap();
+        let folder = temp.path().join("photos");
+        fs::create_dir(&folder).unwrap();
+        let kept = folder.join("kept.jpg");
+        let edited = folder.join("edited.jpg");
+        let long_ago = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let write = |path: &Path, data: &[u8], modified: Option<SystemTime>| {
+            fs::write(path, data).unwrap();
+            if let Some(modified) = modified {
+                File::options()
+                    .write(true)
+                    .open(path)
+                    .unwrap()
+                    .set_modified(modified)
+                    .unwrap();
+            }
+        };
+        write(&kept, b"kept-v1", Some(long_ago));
+        write(&edited, b"edit-v1", Some(long_ago));
+
+        let config = DuplicateConfig::new()
+            .with_folder(folder.clone())
+            .with_cache_file(temp.path().join("cache.json"));
+        let build = || {
+            DuplicateIndex::build_from_folders(&config, Arc::new(AtomicBool::new(false)), |_| {})
+                .unwrap()
+        };
+        build();
+        assert!(temp.path().join("cache.json").exists());
+
+        // Same size and an old timestamp, so the cached hash is trusted; a
+        // fresh timestamp means the file is hashed again
+        write(&kept, b"kept-v2", Some(long_ago));
+        write(&edited, b"edit-v2", None);
+        write(&folder.join("added.jpg"), b"added", None);
+
+        let index = build();
+        assert_eq!(index.len(), 3);
+        assert!(index.find_duplicate(b"kept-v1").is_some());
+        assert!(index.find_duplicate(b"edit-v1").is_none());
+        assert!(index.find_duplicate(b"edit-v2").is_some());
+        assert!(index.find_duplicate(b"added").is_some());
+
+        // Deleted files drop out of the index
+        fs::remove_file(&kept).unwrap();
+        assert_eq!(build().len(), 2);
+    }
 }
@@ -12,6 +12,9 @@
 //! - **Parallel indexing** - Multi-threaded folder scanning
 //! - **Persistent cache** - JSON cache for faster subsequent runs
 //! - **Works with all files** - Photos, videos, and any other file type
+//! - **Duplicate reports** - JSON/CSV list of each duplicate and what it matched
+//! - **Folder scans** - Cancellable search for identical files in a folder on disk
+//! - **Thread-safe run store** - Atomic check-and-insert of hashes extracted this run
 //!
 //! # Architecture
 //!
@@ -48,10 +51,18 @@
 //! ```
 
 pub mod detector;
+pub mod report;
+pub mod scan;
+pub mod store;
+pub mod strategy;
 
 // Re-export main types for convenience
 // Primary types used by the extractor
 pub use detector::{compute_data_hash, DuplicateConfig, DuplicateIndex};
+pub use report::{DuplicateReport, DuplicateReportEntry, MatchType};
+pub use scan::{scan_directory, HashAlgorithm};
+pub use store::{Claim, ClaimGuard, DuplicateStore};
+pub use strategy::{resolve_duplicate, DuplicateCandidate, DuplicateResolution};
 
 // Additional public API types (may not be used internally but are part of public interface)
 #[allow(unused_imports)]
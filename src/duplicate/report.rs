@@ -0,0 +1,350 @@
+//! Duplicate report
+//!
+//! Records every incoming file that matched content already in a comparison
+//! folder, the file it matched, how it matched and what the extractor did
+//! about it. The report is collected in memory during extraction and written
+//! once at the end as JSON (an array of entries) or CSV (with a header row).
+//! A [`scan_directory`](crate::duplicate::scan_directory) of a folder on disk
+//! fills in groups of identical files instead. A report with groups is written
+//! as a JSON object holding both `entries` and `groups`; in CSV each copy after
+//! the first in a group is a row matched to the first, with no resolution
+//! since nothing was done about it.
+
+use crate::core::error::{ExtractionError, Result};
+use crate::core::manifest::{csv_escape, ManifestFormat};
+use crate::duplicate::{hash_to_hex, DuplicateGroup, DuplicateResolution};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Column order used for CSV reports
+const CSV_HEADER: &str = "source_path,destination_path,matched_path,size,match_type,resolution";
+
+/// How an incoming file was matched to an existing one
+///
+/// Only exact matches are detected today; other kinds of match (such as
+/// perceptual similarity) get their own variant when they are implemented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchType {
+    /// Byte-identical content (same size and SHA256)
+    Exact,
+}
+
+impl MatchType {
+    /// Name used in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchType::Exact => "exact",
+        }
+    }
+}
+
+/// One duplicate found during extraction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuplicateReportEntry {
+    /// Path of the incoming file on the device
+    pub source_path: String,
+    /// Where the incoming file was written (None when it was skipped)
+    pub destination_path: Option<PathBuf>,
+    /// Existing file the incoming file matched
+    pub matched_path: PathBuf,
+    /// Size of the incoming file in bytes
+    pub size: u64,
+    /// How the files were matched
+    pub match_type: MatchType,
+    /// What was done with the incoming file
+    pub resolution: DuplicateResolution,
+}
+
+impl DuplicateReportEntry {
+    /// Create an entry for an incoming file that was not written anywhere
+    pub fn new(
+        source_path: impl Into<String>,
+        matched_path: impl Into<PathBuf>,
+        size: u64,
+        match_type: MatchType,
+        resolution: DuplicateResolution,
+    ) -> Self {
+        Self {
+            source_path: source_path.into(),
+            destination_path: None,
+            matched_path: matched_path.into(),
+            size,
+            match_type,
+            resolution,
+        }
+    }
+
+    /// Record where the incoming file was written
+    pub fn with_destination(mut self, path: impl Into<PathBuf>) -> Self {
+        self.destination_path = Some(path.into());
+        self
+    }
+
+    fn csv_row(&self) -> String {
+        let fields = [
+            self.source_path.clone(),
+            self.destination_path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+            self.matched_path.display().to_string(),
+            self.size.to_string(),
+            self.match_type.as_str().to_string(),
+            self.resolution.as_str().to_string(),
+        ];
+        fields
+            .iter()
+            .map(|f| csv_escape(f))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// A group of identical files as written to a JSON report
+#[derive(Serialize)]
+struct GroupRecord<'a> {
+    hash: String,
+    size: u64,
+    paths: &'a [PathBuf],
+}
+
+/// JSON layout of a report that has groups
+#[derive(Serialize)]
+struct GroupedReport<'a> {
+    entries: &'a [DuplicateReportEntry],
+    groups: Vec<GroupRecord<'a>>,
+}
+
+/// CSV rows for a group: every copy after the first, matched to the first
+fn group_csv_rows(group: &DuplicateGroup) -> impl Iterator<Item = String> + '_ {
+    let first = group.paths.first().map(|p| p.display().to_string());
+    group.paths.iter().skip(1).map(move |path| {
+        let fields = [
+            path.display().to_string(),
+            String::new(),
+            first.clone().unwrap_or_default(),
+            group.size.to_string(),
+            MatchType::Exact.as_str().to_string(),
+            String::new(),
+        ];
+        fields
+            .iter()
+            .map(|f| csv_escape(f))
+            .collect::<Vec<_>>()
+            .join(",")
+    })
+}
+
+/// Duplicates found during one extraction or folder scan
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DuplicateReport {
+    entries: Vec<DuplicateReportEntry>,
+    groups: Vec<DuplicateGroup>,
+}
+
+impl DuplicateReport {
+    /// Create an empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a duplicate to the report
+    pub fn record(&mut self, entry: DuplicateReportEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Add a set of identical files found by a folder scan
+    pub fn record_group(&mut self, group: DuplicateGroup) {
+        self.groups.push(group);
+    }
+
+    /// Duplicates in the order they were found
+    pub fn entries(&self) -> &[DuplicateReportEntry] {
+        &self.entries
+    }
+
+    /// Sets of identical files found by a folder scan
+    pub fn groups(&self) -> &[DuplicateGroup] {
+        &self.groups
+    }
+
+    /// Number of duplicates in the report
+    ///
+    /// Each group counts every copy but the first.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+            + self
+                .groups
+                .iter()
+                .map(|group| group.paths.len().saturating_sub(1))
+                .sum::<usize>()
+    }
+
+    /// Check if no duplicates were found
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.groups.is_empty()
+    }
+
+    /// Write the report's entries and groups to `path`, creating its parent
+    /// folder if needed
+    pub fn write_report(&self, path: &Path, format: ManifestFormat) -> Result<()> {
+        let text = match format {
+            ManifestFormat::Json => {
+                let json = if self.groups.is_empty() {
+                    serde_json::to_string_pretty(&self.entries)
+                } else {
+                    serde_json::to_string_pretty(&GroupedReport {
+                        entries: &self.entries,
+                        groups: self
+                            .groups
+                            .iter()
+                            .map(|group| GroupRecord {
+                                hash: hash_to_hex(&group.hash),
+                                size: group.size,
+                                paths: &group.paths,
+                            })
+                            .collect(),
+                    })
+                };
+                json.map_err(|e| {
+                    ExtractionError::IoError(format!("Failed to serialize duplicate report: {}", e))
+                })?
+            }
+            ManifestFormat::Csv => {
+                let mut text = format!("{}\n", CSV_HEADER);
+                for entry in &self.entries {
+                    text.push_str(&entry.csv_row());
+                    text.push('\n');
+                }
+                for row in self.groups.iter().flat_map(group_csv_rows) {
+                    text.push_str(&row);
+                    text.push('\n');
+                }
+                text
+            }
+        };
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to create directory '{}': {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+        fs::write(path, text).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to write duplicate report '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_report() -> DuplicateReport {
+        let mut report = DuplicateReport::new();
+        report.record(DuplicateReportEntry::new(
+            "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+            "D:/Photos/IMG_0001.JPG",
+            4096,
+            MatchType::Exact,
+            DuplicateResolution::Skip,
+        ));
+        report.record(
+            DuplicateReportEntry::new(
+                "DCIM/IMG, \"odd\".JPG",
+                "D:/Photos/IMG_0002.JPG",
+                10,
+                MatchType::Exact,
+                DuplicateResolution::Rename,
+            )
+            .with_destination("out/IMG_0002_1.JPG"),
+        );
+        report
+    }
+
+    #[test]
+    fn test_json_report_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("duplicates.json");
+
+        let report = sample_report();
+        report.write_report(&path, ManifestFormat::Json).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("\"match_type\": \"exact\""));
+        assert!(text.contains("\"resolution\": \"skip\""));
+        let parsed: Vec<DuplicateReportEntry> = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed, report.entries());
+    }
+
+    #[test]
+    fn test_csv_report_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("reports").join("duplicates.csv");
+
+        sample_report()
+            .write_report(&path, ManifestFormat::Csv)
+            .unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "Internal Storage/DCIM/100APPLE/IMG_0001.JPG,,D:/Photos/IMG_0001.JPG,4096,exact,skip"
+        );
+        assert_eq!(
+            lines[2],
+            "\"DCIM/IMG, \"\"odd\"\".JPG\",out/IMG_0002_1.JPG,D:/Photos/IMG_0002.JPG,10,exact,rename"
+        );
+    }
+
+    #[test]
+    fn test_report_writes_groups() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut report = DuplicateReport::new();
+        report.record_group(DuplicateGroup {
+            hash: [0xab; 32],
+            size: 10,
+            paths: vec![
+                PathBuf::from("photos/IMG_0001.JPG"),
+                PathBuf::from("photos/copy/IMG_0001.JPG"),
+                PathBuf::from("photos/IMG_0001 (1).JPG"),
+            ],
+        });
+
+        let json_path = temp_dir.path().join("duplicates.json");
+        report
+            .write_report(&json_path, ManifestFormat::Json)
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json["entries"].as_array().unwrap().len(), 0);
+        assert_eq!(json["groups"][0]["hash"], "ab".repeat(32));
+        assert_eq!(json["groups"][0]["size"], 10);
+        assert_eq!(json["groups"][0]["paths"].as_array().unwrap().len(), 3);
+
+        let csv_path = temp_dir.path().join("duplicates.csv");
+        report.write_report(&csv_path, ManifestFormat::Csv).unwrap();
+        let text = fs::read_to_string(&csv_path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[1..],
+            [
+                "photos/copy/IMG_0001.JPG,,photos/IMG_0001.JPG,10,exact,",
+                "photos/IMG_0001 (1).JPG,,photos/IMG_0001.JPG,10,exact,",
+            ]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Duplicate scan of a folder on disk
+//!
+//! [`scan_directory`] finds identical files within an already-extracted
+//! folder, without a device. Files are grouped by size first, so only files
+//! that share a size are read and hashed. Progress is reported through the
+//! same [`ProgressSink`] the extractor uses, and a shutdown request is
+//! noticed between buffer reads, so cancelling a scan of large videos
+//! doesn't wait for the current file to finish.
+
+use crate::core::error::{ExtractionError, Result};
+use crate::core::generic_extractor::{FileOutcome, ProgressSink};
+use crate::duplicate::detector::{DuplicateGroup, Sha256Hash, HASH_BUFFER_SIZE};
+use crate::duplicate::DuplicateReport;
+use log::{debug, warn};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use walkdir::WalkDir;
+
+/// Hash used to decide whether two files are identical
+///
+/// Only SHA256 is supported today; faster non-cryptographic hashes get their
+/// own variant when they are implemented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// SHA256 of the whole file
+    #[default]
+    Sha256,
+}
+
+/// Find groups of identical files anywhere below `dir`
+///
+/// Each file that has to be hashed is reported to `sink` as it is read: one
+/// matching a file hashed earlier finishes as [`FileOutcome::Duplicate`], any
+/// other as [`FileOutcome::Hashed`] with the bytes read. Files that can't be
+/// read are reported as failed and left out, and empty files are never
+/// reported as duplicates of each other. Setting `shutdown_flag`
+/// stops the scan with [`ExtractionError::Cancelled`].
+pub fn scan_directory(
+    dir: &Path,
+    algorithm: HashAlgorithm,
+    shutdown_flag: &AtomicBool,
+    sink: &mut dyn ProgressSink,
+) -> Result<DuplicateReport> {
+    if !dir.is_dir() {
+        return Err(ExtractionError::IoError(format!(
+            "Not a folder: {}",
+            dir.display()
+        )));
+    }
+
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        if shutdown_flag.load(Ordering::Relaxed) {
+            return Err(ExtractionError::Cancelled);
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        match entry.metadata() {
+            Ok(metadata) => by_size
+                .entry(metadata.len())
+                .or_default()
+                .push(entry.into_path()),
+            Err(e) => warn!("Skipping '{}': {}", entry.path().display(), e),
+        }
+    }
+
+    // A file with a size no other file has can't be a duplicate, and empty
+    // files (placeholders, lock files) all match each other trivially
+    let mut candidates: Vec<(u64, PathBuf)> = by_size
+        .into_iter()
+        .filter(|(size, paths)| *size > 0 && paths.len() > 1)
+        .flat_map(|(size, paths)| paths.into_iter().map(move |path| (size, path)))
+        .collect();
+    candidates.sort_by(|a, b| a.1.cmp(&b.1));
+    debug!(
+        "Duplicate scan of '{}': {} files share a size",
+        dir.display(),
+        candidates.len()
+    );
+
+    let total = candidates.len();
+    let mut groups: HashMap<(u64, Sha256Hash), Vec<PathBuf>> = HashMap::new();
+    for (index, (size, path)) in candidates.into_iter().enumerate() {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        sink.on_file_start(&name, index + 1, total);
+
+        let hash = match hash_file(&path, size, algorithm, shutdown_flag, sink) {
+            Ok(hash) => hash,
+            Err(ExtractionError::Cancelled) => {
+                sink.on_file_done(&FileOutcome::Cancelled);
+                return Err(ExtractionError::Cancelled);
+            }
+            Err(e) => {
+                warn!("Skipping '{}': {}", path.display(), e);
+                sink.on_file_done(&FileOutcome::Failed(e.to_string()));
+                continue;
+            }
+        };
+
+        let paths = groups.entry((size, hash)).or_default();
+        sink.on_file_done(&if paths.is_empty() {
+            FileOutcome::Hashed { bytes: size }
+        } else {
+            FileOutcome::Duplicate
+        });
+        paths.push(path);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = groups
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, hash), paths)| DuplicateGroup { hash, size, paths })
+        .collect();
+    groups.sort_by(|a, b| a.paths[0].cmp(&b.paths[0]));
+
+    let mut report = DuplicateReport::new();
+    for group in groups {
+        report.record_group(group);
+    }
+    Ok(report)
+}
+
+/// Stream `path` through `algorithm`, checking `shutdown_flag` between reads
+fn hash_file(
+    path: &Path,
+    size: u64,
+    algorithm: HashAlgorithm,
+    shutdown_flag: &AtomicBool,
+    sink: &mut dyn ProgressSink,
+) -> Result<Sha256Hash> {
+    let mut file = File::open(path)?;
+    let mut hasher = match algorithm {
+        HashAlgorithm::Sha256 => Sha256::new(),
+    };
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    let mut hashed = 0u64;
+
+    loop {
+        if shutdown_flag.load(Ordering::Relaxed) {
+            return Err(ExtractionError::Cancelled);
+        }
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+        hashed += bytes_read as u64;
+        sink.on_bytes(hashed, size);
+    }
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&hasher.finalize());
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::generic_extractor::NullSink;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scan_directory_groups_identical_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("2024/03");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp_dir.path().join("IMG_0001.JPG"), b"same photo").unwrap();
+        fs::write(nested.join("IMG_0001 copy.JPG"), b"same photo").unwrap();
+        // Same size, different content
+        fs::write(temp_dir.path().join("IMG_0002.JPG"), b"diff photo").unwrap();
+        // Empty files are not duplicates of each other
+        fs::write(temp_dir.path().join("empty1.JPG"), b"").unwrap();
+        fs::write(nested.join("empty2.JPG"), b"").unwrap();
+
+        let report = scan_directory(
+            temp_dir.path(),
+            HashAlgorithm::Sha256,
+            &AtomicBool::new(false),
+            &mut NullSink,
+        )
+        .unwrap();
+
+        assert_eq!(report.groups().len(), 1);
+        let group = &report.groups()[0];
+        assert_eq!(group.size, 10);
+        assert_eq!(
+            group.paths,
+            vec![
+                nested.join("IMG_0001 copy.JPG"),
+                temp_dir.path().join("IMG_0001.JPG"),
+            ]
+        );
+        assert_eq!(report.len(), 1);
+    }
+
+    #[test]
+    fn test_scan_directory_stops_on_shutdown() {
+        /// Requests shutdown once the first file has been hashed
+        struct CancellingSink<'a> {
+            shutdown: &'a AtomicBool,
+            outcomes: Vec<FileOutcome>,
+        }
+
+        impl ProgressSink for CancellingSink<'_> {
+            fn on_file_done(&mut self, outcome: &FileOutcome) {
+                self.outcomes.push(outcome.clone());
+                self.shutdown.store(true, Ordering::Relaxed);
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..3 {
+            fs::write(temp_dir.path().join(format!("{}.JPG", i)), b"identical").unwrap();
+        }
+
+        let shutdown = AtomicBool::new(false);
+        let mut sink = CancellingSink {
+            shutdown: &shutdown,
+            outcomes: Vec::new(),
+        };
+        let result = scan_directory(temp_dir.path(), HashAlgorithm::Sha256, &shutdown, &mut sink);
+
+        assert!(matches!(result, Err(ExtractionError::Cancelled)));
+        assert_eq!(
+            sink.outcomes,
+            vec![FileOutcome::Hashed { bytes: 9 }, FileOutcome::Cancelled]
+        );
+    }
+}
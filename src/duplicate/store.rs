@@ -0,0 +1,193 @@
+//! Hashes of files extracted during a run
+//!
+//! [`DuplicateIndex`](super::DuplicateIndex) covers files that existed before
+//! the extraction started. A [`DuplicateStore`] records what the extraction
+//! itself writes, so a second copy of the same content on the device is
+//! caught as well. It takes `&self` and locks internally, so workers copying
+//! in parallel can share one store, and [`check_and_insert`] makes sure only
+//! one of several identical files is treated as the original.
+//!
+//! [`check_and_insert`]: DuplicateStore::check_and_insert
+
+use super::detector::Sha256Hash;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Outcome of claiming a hash in a [`DuplicateStore`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Claim {
+    /// No file with this content was seen before; the caller owns it now
+    Original,
+    /// Another file with this content was claimed first, at this path
+    DuplicateOf(PathBuf),
+}
+
+/// Thread-safe map from content hash to the first output path written with it
+#[derive(Debug, Default)]
+pub struct DuplicateStore {
+    seen: Mutex<HashMap<Sha256Hash, PathBuf>>,
+}
+
+impl DuplicateStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `path` as the original for `hash` unless another path already is
+    ///
+    /// The check and the insert happen under one lock, so of several callers
+    /// racing with the same hash exactly one gets [`Claim::Original`].
+    pub fn check_and_insert(&self, hash: Sha256Hash, path: &Path) -> Claim {
+        let mut seen = self.seen.lock().unwrap();
+        match seen.get(&hash) {
+            Some(original) => Claim::DuplicateOf(original.clone()),
+            None => {
+                seen.insert(hash, path.to_path_buf());
+                Claim::Original
+            }
+        }
+    }
+
+    /// Claim `hash` for `path`, giving the claim up again if the guard is
+    /// dropped before [`ClaimGuard::keep`] is called
+    ///
+    /// Returns the original's path if the content was already claimed.
+    pub fn claim(&self, hash: Sha256Hash, path: &Path) -> Result<ClaimGuard<'_>, PathBuf> {
+        match self.check_and_insert(hash, path) {
+            Claim::Original => Ok(ClaimGuard {
+                store: self,
+                hash: Some(hash),
+            }),
+            Claim::DuplicateOf(original) => Err(original),
+        }
+    }
+
+    /// Path claimed for `hash`, if any
+    pub fn get(&self, hash: &Sha256Hash) -> Option<PathBuf> {
+        self.seen.lock().unwrap().get(hash).cloned()
+    }
+
+    /// Give up the claim on `hash`, e.g. because writing the file failed
+    pub fn release(&self, hash: &Sha256Hash) -> Option<PathBuf> {
+        self.seen.lock().unwrap().remove(hash)
+    }
+
+    /// Forget every claimed hash
+    pub fn clear(&self) {
+        self.seen.lock().unwrap().clear();
+    }
+
+    /// Number of claimed hashes
+    pub fn len(&self) -> usize {
+        self.seen.lock().unwrap().len()
+    }
+
+    /// Check if no hash is claimed
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Claim on a hash that is released when dropped unless it is kept
+#[derive(Debug)]
+pub struct ClaimGuard<'a> {
+    store: &'a DuplicateStore,
+    hash: Option<Sha256Hash>,
+}
+
+impl ClaimGuard<'_> {
+    /// Keep the claim, once the file it stands for has been written
+    pub fn keep(mut self) {
+        self.hash = None;
+    }
+}
+
+impl Drop for ClaimGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(hash) = self.hash.take() {
+            self.store.release(&hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duplicate::compute_data_hash;
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    #[test]
+    fn test_check_and_insert_keeps_first_path() {
+        let store = DuplicateStore::new();
+        let hash = compute_data_hash(b"photo");
+
+        assert_eq!(
+            store.check_and_insert(hash, Path::new("a.jpg")),
+            Claim::Original
+        );
+        assert_eq!(
+            store.check_and_insert(hash, Path::new("b.jpg")),
+            Claim::DuplicateOf(PathBuf::from("a.jpg"))
+        );
+        assert_eq!(
+            store.check_and_insert(compute_data_hash(b"other"), Path::new("c.jpg")),
+            Claim::Original
+        );
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get(&hash), Some(PathBuf::from("a.jpg")));
+    }
+
+    #[test]
+    fn test_concurrent_check_and_insert_has_one_original() {
+        const WORKERS: usize = 16;
+        const ROUNDS: usize = 200;
+
+        let store = Arc::new(DuplicateStore::new());
+        for round in 0..ROUNDS {
+            let hash = compute_data_hash(&round.to_le_bytes());
+            let barrier = Arc::new(Barrier::new(WORKERS));
+            let handles: Vec<_> = (0..WORKERS)
+                .map(|worker| {
+                    let store = Arc::clone(&store);
+                    let barrier = Arc::clone(&barrier);
+                    thread::spawn(move || {
+                        barrier.wait();
+                        let path = PathBuf::from(format!("worker_{}.jpg", worker));
+                        store.check_and_insert(hash, &path)
+                    })
+                })
+                .collect();
+
+            let claims: Vec<Claim> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+            let originals = claims.iter().filter(|c| **c == Claim::Original).count();
+            assert_eq!(originals, 1, "round {} had {} originals", round, originals);
+
+            // Every loser points at the winner's path
+            let winner = store.get(&hash).unwrap();
+            assert!(claims
+                .iter()
+                .all(|c| *c == Claim::Original || *c == Claim::DuplicateOf(winner.clone())));
+        }
+        assert_eq!(store.len(), ROUNDS);
+    }
+
+    #[test]
+    fn test_dropped_claim_is_released() {
+        let store = DuplicateStore::new();
+        let hash = compute_data_hash(b"photo");
+
+        let guard = store.claim(hash, Path::new("failed.jpg")).unwrap();
+        assert_eq!(
+            store.claim(hash, Path::new("b.jpg")).unwrap_err(),
+            PathBuf::from("failed.jpg")
+        );
+        drop(guard);
+        assert!(store.is_empty());
+
+        store.claim(hash, Path::new("b.jpg")).unwrap().keep();
+        assert_eq!(store.get(&hash), Some(PathBuf::from("b.jpg")));
+    }
+}
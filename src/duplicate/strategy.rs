@@ -0,0 +1,208 @@
+//! Duplicate resolution
+//!
+//! Decides what happens to an incoming file whose content matches a file that
+//! already exists, according to the configured [`DuplicateStrategy`].
+
+use crate::core::config::DuplicateStrategy;
+use crate::core::extractor::parse_device_datetime;
+use crate::core::metadata::{read_exif_capture_date, read_exif_capture_date_from_path};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// What to do with an incoming duplicate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateResolution {
+    /// Don't extract the incoming file
+    Skip,
+    /// Extract the incoming file over the existing one
+    Overwrite,
+    /// Extract the incoming file under a new, unique name
+    Rename,
+}
+
+impl DuplicateResolution {
+    /// Name used in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuplicateResolution::Skip => "skip",
+            DuplicateResolution::Overwrite => "overwrite",
+            DuplicateResolution::Rename => "rename",
+        }
+    }
+}
+
+/// Size and date of one side of a duplicate match
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DuplicateCandidate {
+    /// File size in bytes
+    pub size: u64,
+    /// Modified time, or the EXIF capture time when no modified time is known
+    pub date: Option<NaiveDateTime>,
+}
+
+impl DuplicateCandidate {
+    /// Describe a file read from the device
+    ///
+    /// The device's modified time is preferred because identical content
+    /// always carries identical EXIF.
+    pub fn incoming(data: &[u8], date_modified: Option<&str>) -> Self {
+        Self {
+            size: data.len() as u64,
+            date: date_modified
+                .and_then(parse_device_datetime)
+                .or_else(|| read_exif_capture_date(data)),
+        }
+    }
+
+    /// Describe a file already on disk (`None` if it can't be read)
+    pub fn existing(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let date = metadata
+            .modified()
+            .ok()
+            .map(|time| DateTime::<Utc>::from(time).naive_utc())
+            .or_else(|| read_exif_capture_date_from_path(path));
+
+        Some(Self {
+            size: metadata.len(),
+            date,
+        })
+    }
+}
+
+/// Decide how to handle `incoming` given the `existing` file it duplicates
+///
+/// `KeepNewest` and `KeepLargest` overwrite only when the incoming file wins
+/// strictly; ties and unknown dates keep the existing file.
+pub fn resolve_duplicate(
+    strategy: DuplicateStrategy,
+    incoming: &DuplicateCandidate,
+    existing: &DuplicateCandidate,
+) -> DuplicateResolution {
+    let overwrite_if = |wins: bool| {
+        if wins {
+            DuplicateResolution::Overwrite
+        } else {
+            DuplicateResolution::Skip
+        }
+    };
+
+    match strategy {
+        DuplicateStrategy::Skip => DuplicateResolution::Skip,
+        DuplicateStrategy::Overwrite => DuplicateResolution::Overwrite,
+        DuplicateStrategy::Rename => DuplicateResolution::Rename,
+        DuplicateStrategy::KeepNewest => overwrite_if(matches!(
+            (incoming.date, existing.date),
+            (Some(new), Some(old)) if new > old
+        )),
+        DuplicateStrategy::KeepLargest => overwrite_if(incoming.size > existing.size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+
+    const CONTENT: &[u8] = b"identical photo bytes";
+
+    /// Write `CONTENT` to disk with a modified time of `days` after the Unix epoch
+    fn existing_file(dir: &Path, days: u64) -> DuplicateCandidate {
+        let path = dir.join("IMG_0001.JPG");
+        fs::write(&path, CONTENT).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(days * 86_400);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        DuplicateCandidate::existing(&path).unwrap()
+    }
+
+    #[test]
+    fn test_existing_candidate_reads_size_and_mtime() {
+        let temp = tempfile::tempdir().unwrap();
+        let existing = existing_file(temp.path(), 1);
+
+        assert_eq!(existing.size, CONTENT.len() as u64);
+        assert_eq!(existing.date, parse_device_datetime("1970-01-02T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_fixed_strategies_ignore_dates() {
+        let temp = tempfile::tempdir().unwrap();
+        let existing = existing_file(temp.path(), 20_000);
+        let incoming = DuplicateCandidate::incoming(CONTENT, Some("2000-01-01T00:00:00Z"));
+
+        assert_eq!(
+            resolve_duplicate(DuplicateStrategy::Skip, &incoming, &existing),
+            DuplicateResolution::Skip
+        );
+        assert_eq!(
+            resolve_duplicate(DuplicateStrategy::Overwrite, &incoming, &existing),
+            DuplicateResolution::Overwrite
+        );
+        assert_eq!(
+            resolve_duplicate(DuplicateStrategy::Rename, &incoming, &existing),
+            DuplicateResolution::Rename
+        );
+    }
+
+    #[test]
+    fn test_keep_newest_overwrites_only_newer_files() {
+        let temp = tempfile::tempdir().unwrap();
+        // 2024-01-01
+        let existing = existing_file(temp.path(), 19_723);
+
+        let newer = DuplicateCandidate::incoming(CONTENT, Some("2024-06-01T12:00:00Z"));
+        let older = DuplicateCandidate::incoming(CONTENT, Some("2023-06-01T12:00:00Z"));
+        let same = DuplicateCandidate::incoming(CONTENT, Some("2024-01-01T00:00:00Z"));
+        let undated = DuplicateCandidate::incoming(CONTENT, None);
+
+        let resolve =
+            |incoming| resolve_duplicate(DuplicateStrategy::KeepNewest, incoming, &existing);
+        assert_eq!(resolve(&newer), DuplicateResolution::Overwrite);
+        assert_eq!(resolve(&older), DuplicateResolution::Skip);
+        assert_eq!(resolve(&same), DuplicateResolution::Skip);
+        assert_eq!(resolve(&undated), DuplicateResolution::Skip);
+    }
+
+    #[test]
+    fn test_keep_largest_overwrites_only_larger_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let existing = existing_file(temp.path(), 19_723);
+        let date = Some("2024-06-01T12:00:00Z");
+
+        let same_size = DuplicateCandidate::incoming(CONTENT, date);
+        let mut larger = same_size;
+        larger.size += 1;
+        let mut smaller = same_size;
+        smaller.size -= 1;
+
+        let resolve =
+            |incoming| resolve_duplicate(DuplicateStrategy::KeepLargest, incoming, &existing);
+        assert_eq!(resolve(&larger), DuplicateResolution::Overwrite);
+        assert_eq!(resolve(&same_size), DuplicateResolution::Skip);
+        assert_eq!(resolve(&smaller), DuplicateResolution::Skip);
+    }
+
+    #[test]
+    fn test_strategy_names_round_trip() {
+        for strategy in DuplicateStrategy::ALL {
+            assert_eq!(
+                DuplicateStrategy::from_name(strategy.as_str()),
+                Some(strategy)
+            );
+        }
+        assert_eq!(
+            DuplicateStrategy::from_name("keep-newest"),
+            Some(DuplicateStrategy::KeepNewest)
+        );
+        assert_eq!(DuplicateStrategy::from_name("newest"), None);
+    }
+}
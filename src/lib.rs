@@ -108,7 +108,7 @@
 //! - **Comprehensive Testing** - Test all features without a real device
 //! - **UI Ready** - Async controllers and event system for GUI integration
 //! - **Device Monitoring** - Hot-plug detection for device connect/disconnect
-//! - **Preview Support** - Thumbnail generation infrastructure for photo previews
+//! - **Preview Support** - Device thumbnails and preview management for photo grids
 //!
 //! # Platform Support
 //!
@@ -12,6 +12,14 @@ mod core;
 mod device;
 mod duplicate;
 mod testdb;
+// The CLI only needs the device monitor and its events (for `watch`), not
+// the rest of the UI
+mod ui {
+    #[allow(dead_code)]
+    pub mod device_monitor;
+    #[allow(dead_code)]
+    pub mod events;
+}
 
 use anyhow::Result;
 use clap::Parser;
@@ -27,42 +35,22 @@ use std::sync::Arc;
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    // Load configuration
+    // Load configuration (an explicitly given file must be valid)
     let mut config = if let Some(ref config_path) = args.config {
-        match Config::load(config_path) {
-            Ok(cfg) => cfg,
-            Err(e) => {
-                eprintln!("Warning: Failed to load config file: {}", e);
-                Config::default()
-            }
-        }
+        Config::load_strict(config_path)?
     } else {
         Config::load_default().unwrap_or_default()
     };
 
-    // Apply CLI overrides to config
-    if let Some(ref output) = args.output {
-        config.output.directory = output.clone();
-    }
-    if let Some(ref device_id) = args.device_id {
-        config.device.device_id = Some(device_id.clone());
-    }
-    if let Some(dcim_only) = args.dcim_only {
-        config.extraction.dcim_only = dcim_only;
-    }
-    if let Some(preserve) = args.preserve_structure {
-        config.output.preserve_structure = preserve;
-    }
-    if let Some(skip) = args.skip_existing {
-        config.output.skip_existing = skip;
-    }
-    if let Some(ref level) = args.log_level {
-        config.logging.level = level.clone();
-    }
-    if args.all_devices {
-        config.device.apple_only = false;
+    // Environment overrides sit between the config file and CLI flags
+    for warning in config.apply_env_overrides() {
+        eprintln!("Warning: {}", warning);
     }
 
+    // Apply CLI overrides to config, then check the merged result
+    args.apply_overrides(&mut config);
+    config.validate()?;
+
     // Set up graceful shutdown handler
     let shutdown_flag = Arc::new(AtomicBool::new(false));
     let shutdown_flag_clone = shutdown_flag.clone();
@@ -74,7 +62,7 @@ fn main() -> Result<()> {
             std::process::exit(1);
         } else {
             shutdown_flag_clone.store(true, Ordering::SeqCst);
-            eprintln!("\nGraceful shutdown requested. Finishing current file... (Press Ctrl+C again to force quit)");
+            eprintln!("\nGraceful shutdown requested. Stopping the current copy... (Press Ctrl+C again to force quit)");
         }
     })
     .expect("Failed to set Ctrl+C handler");
@@ -126,7 +114,16 @@ fn main() -> Result<()> {
     debug!("============================");
 
     // Run the command
-    cli::run_command(&args, &config, shutdown_flag)?;
+    if let Err(e) = cli::run_command(&args, &config, shutdown_flag) {
+        let hint = e
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<core::error::ExtractionError>())
+            .and_then(|error| error.recovery_hint());
+        if let Some(hint) = hint {
+            eprintln!("Hint: {}", hint);
+        }
+        return Err(e);
+    }
 
     Ok(())
 }
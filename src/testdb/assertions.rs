@@ -600,6 +600,18 @@ impl FileSystemFixtureBuilder {
         self
     }
 
+    /// Report the given storage capacity and free space (in bytes)
+    pub fn with_storage(mut self, capacity_bytes: u64, free_bytes: u64) -> Self {
+        self.config = self.config.with_storage(capacity_bytes, free_bytes);
+        self
+    }
+
+    /// Report the given battery percentage
+    pub fn with_battery(mut self, level: u8) -> Self {
+        self.config = self.config.with_battery(level);
+        self
+    }
+
     /// Build the MockFileSystem
     pub fn build(mut self) -> MockFileSystem {
         self.fs.set_config(self.config);
@@ -1103,6 +1115,7 @@ mod tests {
             errors: 0,
             bytes_processed: 1024,
             folders: 3,
+            retries: 0,
         };
 
         assert!(TestAssertions::assert_file_count(&stats, 10).passed);
@@ -0,0 +1,296 @@
+//! Replayable device fixtures
+//!
+//! A fixture captures a device's folder/file structure (names, sizes, dates
+//! and object IDs) as JSON so a user's layout can be reproduced as a mock
+//! device without their photos. File bytes are only stored when explicitly
+//! requested; otherwise content is generated on demand at the recorded size.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use photo_extraction_tool::testdb::MockDeviceManager;
+//! use photo_extraction_tool::device::traits::DeviceManagerTrait;
+//!
+//! let manager = MockDeviceManager::from_fixture("user-report.json".as_ref()).unwrap();
+//! let devices = manager.enumerate_all_devices().unwrap();
+//! let content = manager.open_device(&devices[0].device_id).unwrap();
+//! ```
+
+use super::mock_device::{MockFileSystem, MockObject};
+use crate::core::error::{ExtractionError, Result};
+use crate::device::traits::{DeviceContentTrait, DeviceInfo, StorageInfo};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Current fixture format version
+pub const FIXTURE_VERSION: u32 = 1;
+
+/// Device information stored in a fixture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureDevice {
+    pub device_id: String,
+    pub friendly_name: String,
+    pub manufacturer: String,
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<StorageInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery_level: Option<u8>,
+}
+
+impl From<&DeviceInfo> for FixtureDevice {
+    fn from(info: &DeviceInfo) -> Self {
+        Self {
+            device_id: info.device_id.clone(),
+            friendly_name: info.friendly_name.clone(),
+            manufacturer: info.manufacturer.clone(),
+            model: info.model.clone(),
+            storage: info.storage,
+            battery_level: info.battery_level,
+        }
+    }
+}
+
+impl From<&FixtureDevice> for DeviceInfo {
+    fn from(device: &FixtureDevice) -> Self {
+        Self {
+            device_id: device.device_id.clone(),
+            friendly_name: device.friendly_name.clone(),
+            manufacturer: device.manufacturer.clone(),
+            model: device.model.clone(),
+            storage: device.storage,
+            battery_level: device.battery_level,
+        }
+    }
+}
+
+/// One file or folder in a fixture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureObject {
+    pub object_id: String,
+    /// Parent object ID ("DEVICE" for root objects)
+    pub parent_id: String,
+    pub name: String,
+    pub is_folder: bool,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_modified: Option<String>,
+    /// Recorded file bytes (only present when content was captured)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<Vec<u8>>,
+}
+
+/// A recorded device tree that can be replayed as a mock device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceFixture {
+    pub version: u32,
+    /// When the fixture was recorded (RFC 3339)
+    pub recorded_at: String,
+    pub device: FixtureDevice,
+    /// Objects in depth-first order, parents before their children
+    pub objects: Vec<FixtureObject>,
+}
+
+impl DeviceFixture {
+    /// Record the full object tree of `content`
+    ///
+    /// File bytes are read and stored only when `include_content` is set.
+    pub fn record<C: DeviceContentTrait + ?Sized>(
+        device: &DeviceInfo,
+        content: &C,
+        include_content: bool,
+    ) -> Result<Self> {
+        let mut objects = Vec::new();
+        let mut stack = vec!["DEVICE".to_string()];
+
+        while let Some(parent_id) = stack.pop() {
+            let children = content.enumerate_children(&parent_id)?;
+            // Push in reverse so folders are visited in device order
+            for child in children.iter().rev().filter(|c| c.is_folder) {
+                stack.push(child.object_id.clone());
+            }
+
+            for child in children {
+                let bytes = if include_content && !child.is_folder {
+                    Some(content.read_file(&child.object_id)?)
+                } else {
+                    None
+                };
+                objects.push(FixtureObject {
+                    object_id: child.object_id,
+                    parent_id: parent_id.clone(),
+                    name: child.name,
+                    is_folder: child.is_folder,
+                    size: child.size,
+                    date_modified: child.date_modified,
+                    content: bytes,
+                });
+            }
+        }
+
+        Ok(Self {
+            version: FIXTURE_VERSION,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            device: FixtureDevice::from(device),
+            objects,
+        })
+    }
+
+    /// Write the fixture as pretty-printed JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ExtractionError::IoError(format!("Failed to serialize fixture: {}", e)))?;
+        fs::write(path, json).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to write fixture '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Load a fixture written by [`DeviceFixture::save`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to read fixture '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let fixture: Self = serde_json::from_str(&json).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to parse fixture '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        if fixture.version > FIXTURE_VERSION {
+            return Err(ExtractionError::IoError(format!(
+                "Fixture '{}' has version {}, but only version {} is supported",
+                path.display(),
+                fixture.version,
+                FIXTURE_VERSION
+            )));
+        }
+
+        Ok(fixture)
+    }
+
+    /// Device information for the recorded device
+    pub fn device_info(&self) -> DeviceInfo {
+        DeviceInfo::from(&self.device)
+    }
+
+    /// Build a mock file system reproducing the recorded tree
+    pub fn to_file_system(&self) -> MockFileSystem {
+        let mut fs = MockFileSystem::new();
+
+        for (index, object) in self.objects.iter().enumerate() {
+            let id = &object.object_id;
+            let parent = &object.parent_id;
+            let mut mock = if object.is_folder {
+                MockObject::folder(id, parent, &object.name)
+            } else if let Some(ref content) = object.content {
+                MockObject::file(id, parent, &object.name, content.clone())
+            } else {
+                MockObject::lazy_file(id, parent, &object.name, object.size as usize, index as u64)
+            };
+            mock.object.date_modified = object.date_modified.clone();
+            fs.add_object(mock);
+        }
+
+        fs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::traits::DeviceManagerTrait;
+    use crate::testdb::scenarios::{FileSpec, ScenarioBuilder};
+    use crate::testdb::MockDeviceManager;
+
+    #[test]
+    fn test_record_and_replay_fixture() {
+        let manager = ScenarioBuilder::new("recorded")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::sized(2048).modified("2024-01-15T10:00:00Z"),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.MOV",
+                FileSpec::sized(8192),
+            )
+            .folder("Internal Storage/DCIM/101APPLE")
+            .storage(128_000_000_000, 64_000_000_000)
+            .build_manager();
+        let device = manager.enumerate_all_devices().unwrap().remove(0);
+        let content = manager.open_device(&device.device_id).unwrap();
+
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("fixture.json");
+        let fixture = DeviceFixture::record(&device, &content, false).unwrap();
+        fixture.save(&path).unwrap();
+
+        // No file bytes unless asked for
+        let json = fs::read_to_string(&path).unwrap();
+        assert!(!json.contains("\"content\""));
+
+        let replayed = MockDeviceManager::from_fixture(&path).unwrap();
+        let replayed_device = replayed.enumerate_all_devices().unwrap().remove(0);
+        assert_eq!(replayed_device.device_id, device.device_id);
+        assert_eq!(replayed_device.model, device.model);
+        assert_eq!(replayed_device.storage, device.storage);
+
+        let replayed_content = replayed.open_device(&device.device_id).unwrap();
+        let rerecorded = DeviceFixture::record(&replayed_device, &replayed_content, false).unwrap();
+        assert_eq!(rerecorded.objects, fixture.objects);
+        assert_eq!(rerecorded.objects.len(), 6);
+
+        let photo = rerecorded
+            .objects
+            .iter()
+            .find(|o| o.name == "IMG_0001.JPG")
+            .unwrap();
+        assert_eq!(photo.size, 2048);
+        assert_eq!(photo.date_modified.as_deref(), Some("2024-01-15T10:00:00Z"));
+        assert_eq!(photo.parent_id, "Internal Storage/DCIM/100APPLE");
+    }
+
+    #[test]
+    fn test_fixture_with_content_replays_bytes() {
+        let manager = ScenarioBuilder::new("with_bytes")
+            .file("DCIM/IMG_0001.JPG", FileSpec::bytes(vec![0xFF, 0xD8, 0x01]))
+            .build_manager();
+        let device = manager.enumerate_all_devices().unwrap().remove(0);
+        let content = manager.open_device(&device.device_id).unwrap();
+
+        let fixture = DeviceFixture::record(&device, &content, true).unwrap();
+        let fs = fixture.to_file_system();
+
+        assert_eq!(
+            fs.read_file("DCIM/IMG_0001.JPG").unwrap(),
+            vec![0xFF, 0xD8, 0x01]
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_newer_version() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("future.json");
+        let mut fixture = DeviceFixture {
+            version: FIXTURE_VERSION,
+            recorded_at: String::new(),
+            device: FixtureDevice::from(&DeviceInfo::new("id", "Phone", "Apple Inc.", "iPhone")),
+            objects: Vec::new(),
+        };
+        fixture.version = FIXTURE_VERSION + 1;
+        fixture.save(&path).unwrap();
+
+        assert!(DeviceFixture::load(&path).is_err());
+    }
+}
@@ -122,6 +122,10 @@ impl MockDataGenerator {
     }
 
     /// Generate JPEG with EXIF data
+    ///
+    /// `date_taken` uses the EXIF format (`YYYY:MM:DD HH:MM:SS`) and is written
+    /// to both `DateTime` and `DateTimeOriginal`, so the result can be parsed by
+    /// real EXIF readers.
     pub fn generate_jpeg_with_exif(size: usize, date_taken: &str, camera_model: &str) -> Vec<u8> {
         let mut data = Vec::with_capacity(size);
 
@@ -131,6 +135,27 @@ impl MockDataGenerator {
         // APP1 marker (EXIF)
         data.extend_from_slice(&[0xFF, 0xE1]);
 
+        // ASCII values are NUL-terminated and kept longer than 4 bytes so they
+        // are always stored by offset; the stored bytes are padded to an even
+        // length to keep offsets word-aligned
+        let ascii = |value: &str| {
+            let count = (value.len() + 1).max(5) as u32;
+            let mut bytes = value.as_bytes().to_vec();
+            while bytes.len() < count as usize || bytes.len() % 2 != 0 {
+                bytes.push(0);
+            }
+            (bytes, count)
+        };
+        let (date_bytes, date_count) = ascii(date_taken);
+        let (model_bytes, model_count) = ascii(camera_model);
+
+        // Layout (offsets relative to the TIFF header):
+        // header (8) | IFD0, 3 entries (42) | Exif IFD, 1 entry (18) | values
+        const IFD0_OFFSET: u32 = 8;
+        let exif_ifd_offset = IFD0_OFFSET + 2 + 3 * 12 + 4;
+        let date_offset = exif_ifd_offset + 2 + 12 + 4;
+        let model_offset = date_offset + date_bytes.len() as u32;
+
         // Build EXIF data
         let mut exif = Vec::new();
         exif.extend_from_slice(b"Exif\0\0");
@@ -138,37 +163,23 @@ impl MockDataGenerator {
         // TIFF header (little endian)
         exif.extend_from_slice(&[0x49, 0x49]); // Little endian
         exif.extend_from_slice(&[0x2A, 0x00]); // TIFF magic
-        exif.extend_from_slice(&[0x08, 0x00, 0x00, 0x00]); // IFD0 offset
-
-        // Simplified IFD0
-        exif.extend_from_slice(&[0x02, 0x00]); // 2 entries
-
-        // DateTime tag (0x0132)
-        exif.extend_from_slice(&[0x32, 0x01]); // Tag
-        exif.extend_from_slice(&[0x02, 0x00]); // Type: ASCII
-        let date_bytes = date_taken.as_bytes();
-        let date_len = (date_bytes.len() + 1) as u32;
-        exif.extend_from_slice(&date_len.to_le_bytes());
-        if date_len <= 4 {
-            exif.extend_from_slice(date_bytes);
-            exif.push(0);
-            while exif.len() % 4 != 0 {
-                exif.push(0);
-            }
-        } else {
-            exif.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // Offset (simplified)
-        }
+        exif.extend_from_slice(&IFD0_OFFSET.to_le_bytes());
+
+        // IFD0 (entries sorted by tag)
+        exif.extend_from_slice(&3u16.to_le_bytes());
+        push_ifd_entry(&mut exif, 0x0110, 2, model_count, model_offset); // Model
+        push_ifd_entry(&mut exif, 0x0132, 2, date_count, date_offset); // DateTime
+        push_ifd_entry(&mut exif, 0x8769, 4, 1, exif_ifd_offset); // Exif IFD pointer
+        exif.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // No next IFD
 
-        // Model tag (0x0110)
-        exif.extend_from_slice(&[0x10, 0x01]); // Tag
-        exif.extend_from_slice(&[0x02, 0x00]); // Type: ASCII
-        let model_bytes = camera_model.as_bytes();
-        let model_len = (model_bytes.len() + 1) as u32;
-        exif.extend_from_slice(&model_len.to_le_bytes());
-        exif.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // Offset (simplified)
+        // Exif IFD
+        exif.extend_from_slice(&1u16.to_le_bytes());
+        push_ifd_entry(&mut exif, 0x9003, 2, date_count, date_offset); // DateTimeOriginal
+        exif.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // No next IFD
 
-        // End of IFD
-        exif.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        // Values
+        exif.extend_from_slice(&date_bytes);
+        exif.extend_from_slice(&model_bytes);
 
         // Write EXIF length
         let exif_len = (exif.len() + 2) as u16;
@@ -189,7 +200,6 @@ impl MockDataGenerator {
         // JPEG EOI marker
         data.extend_from_slice(&[0xFF, 0xD9]);
 
-        data.truncate(size);
         data
     }
 
@@ -714,6 +724,15 @@ impl MockDataGenerator {
     }
 }
 
+/// Append a little-endian TIFF IFD entry whose value is a single u32
+/// (either an inline LONG or an offset to out-of-line data)
+fn push_ifd_entry(buf: &mut Vec<u8>, tag: u16, field_type: u16, count: u32, value: u32) {
+    buf.extend_from_slice(&tag.to_le_bytes());
+    buf.extend_from_slice(&field_type.to_le_bytes());
+    buf.extend_from_slice(&count.to_le_bytes());
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
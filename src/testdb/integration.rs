@@ -257,6 +257,7 @@ impl IntegrationTestRunner {
                 errors: stats.errors,
                 bytes_processed: stats.bytes_processed,
                 folders: stats.folders_scanned,
+                retries: stats.retries,
             },
             15,
         ));
@@ -632,6 +633,7 @@ impl IntegrationTestRunner {
             progress_callback: Some(Arc::new(move |_update: ProgressUpdate| {
                 progress_count_clone.fetch_add(1, Ordering::SeqCst);
             })),
+            ..Default::default()
         };
 
         let content = match manager.open_device(&device.device_id) {
@@ -706,6 +708,7 @@ impl IntegrationTestRunner {
             write_files: true,
             max_files: 0,
             progress_callback: None,
+            ..Default::default()
         };
 
         let content = match manager.open_device(&device.device_id) {
@@ -795,6 +798,7 @@ impl IntegrationTestRunner {
             write_files: true,
             max_files: 0,
             progress_callback: None,
+            ..Default::default()
         };
 
         let content = match manager.open_device(&device.device_id) {
@@ -868,6 +872,7 @@ impl IntegrationTestRunner {
             write_files: false,
             max_files: 10, // Limit to 10 files
             progress_callback: None,
+            ..Default::default()
         };
 
         let content = match manager.open_device(&device.device_id) {
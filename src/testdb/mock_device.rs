@@ -45,13 +45,16 @@
 use crate::core::error::{ExtractionError, Result};
 use crate::device::traits::{
     DeviceContentTrait, DeviceInfo, DeviceManagerTrait, DeviceObject, DeviceSimulationConfig,
-    DeviceType,
+    DeviceType, ObjectMetadata, StorageInfo,
 };
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
+/// Chunk size used by [`MockDeviceContent`] when streaming file content
+pub const MOCK_STREAM_CHUNK_SIZE: usize = 256 * 1024;
+
 // =============================================================================
 // MockObject - File/folder representation with content
 // =============================================================================
@@ -117,6 +120,10 @@ pub struct MockObject {
     pub content: Option<Vec<u8>>,
     /// Lazy content generator for memory-efficient testing
     lazy_content: LazyContent,
+    /// Thumbnail the device keeps for this file, if any
+    pub thumbnail: Option<Vec<u8>>,
+    /// Full metadata reported for this object (`None` = derived from `object`)
+    pub metadata: Option<ObjectMetadata>,
 }
 
 impl MockObject {
@@ -126,6 +133,8 @@ impl MockObject {
             object: DeviceObject::folder(object_id, parent_id, name),
             content: None,
             lazy_content: LazyContent::None,
+            thumbnail: None,
+            metadata: None,
         }
     }
 
@@ -136,6 +145,8 @@ impl MockObject {
             object: DeviceObject::file(object_id, parent_id, name, size),
             content: Some(content),
             lazy_content: LazyContent::None,
+            thumbnail: None,
+            metadata: None,
         }
     }
 
@@ -158,6 +169,8 @@ impl MockObject {
                 size,
                 seed,
             },
+            thumbnail: None,
+            metadata: None,
         }
     }
 
@@ -182,9 +195,23 @@ impl MockObject {
             object: DeviceObject::file_with_date(object_id, parent_id, name, size, date_modified),
             content: Some(content),
             lazy_content: LazyContent::None,
+            thumbnail: None,
+            metadata: None,
         }
     }
 
+    /// Attach a device-side thumbnail to this file
+    pub fn with_thumbnail(mut self, thumbnail: Vec<u8>) -> Self {
+        self.thumbnail = Some(thumbnail);
+        self
+    }
+
+    /// Preset the metadata reported by `object_metadata`
+    pub fn with_metadata(mut self, metadata: ObjectMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
     /// Get the object ID
     pub fn object_id(&self) -> &str {
         &self.object.object_id
@@ -229,6 +256,149 @@ impl MockObject {
     }
 }
 
+// =============================================================================
+// MockFault - Deterministic fault injection
+// =============================================================================
+
+/// A deterministic failure injected into a mock device
+///
+/// Faults surface through the same `DeviceContentTrait` methods the extractor
+/// calls, so retry and error-handling paths can be exercised reproducibly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockFault {
+    /// Fail the first `times` reads of an object with a transient device error
+    FailReads { object_id: String, times: usize },
+    /// Return only the first half of an object's content for its first `times` reads
+    TruncateReads { object_id: String, times: usize },
+    /// Disconnect the device once `after` folders have been enumerated;
+    /// every later enumeration and read fails
+    DisconnectDuringEnumeration { after: usize },
+    /// Drop the connection half way through the file read that follows the
+    /// first `after_reads` reads. The device is left out of the next
+    /// `offline_listings` device listings, then comes back; handles opened
+    /// before the drop keep failing and the device has to be opened again.
+    DropConnection {
+        after_reads: usize,
+        offline_listings: usize,
+    },
+}
+
+// =============================================================================
+// RecordingDevice - Call recording and faults around a mock device
+// =============================================================================
+
+/// Wrapper around a [`MockDeviceContent`] that records which objects are read
+/// and which folders are listed, and can inject faults that [`MockFault`]
+/// can't express because they happen outside the device: a process crash
+/// part way through a run, or a shutdown request part way through a copy
+///
+/// ```ignore
+/// let device = RecordingDevice::new(content).panic_on_read(3);
+/// ```
+pub struct RecordingDevice {
+    inner: MockDeviceContent,
+    reads: Mutex<Vec<String>>,
+    listed: Mutex<Vec<String>>,
+    panic_on_read: Option<usize>,
+    raise_after_chunk: Option<Arc<AtomicBool>>,
+}
+
+impl RecordingDevice {
+    /// Wrap `inner`, recording calls without injecting any fault
+    pub fn new(inner: MockDeviceContent) -> Self {
+        Self {
+            inner,
+            reads: Mutex::new(Vec::new()),
+            listed: Mutex::new(Vec::new()),
+            panic_on_read: None,
+            raise_after_chunk: None,
+        }
+    }
+
+    /// Panic on the `read`th file read (1-based), as if the process died
+    pub fn panic_on_read(mut self, read: usize) -> Self {
+        self.panic_on_read = Some(read);
+        self
+    }
+
+    /// Set `flag` once a chunk of a file has been handed to the reader, such
+    /// as a shutdown flag to cancel a copy part way through
+    pub fn raise_after_chunk(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.raise_after_chunk = Some(flag);
+        self
+    }
+
+    /// Object IDs read so far, in order, whether whole or in chunks
+    pub fn reads(&self) -> Vec<String> {
+        self.reads.lock().unwrap().clone()
+    }
+
+    /// Folders whose children have been listed so far, in order
+    pub fn listed_folders(&self) -> Vec<String> {
+        self.listed.lock().unwrap().clone()
+    }
+
+    fn record_read(&self, object_id: &str) {
+        let mut reads = self.reads.lock().unwrap();
+        reads.push(object_id.to_string());
+        if Some(reads.len()) == self.panic_on_read {
+            drop(reads);
+            panic!("simulated crash reading {}", object_id);
+        }
+    }
+}
+
+impl DeviceContentTrait for RecordingDevice {
+    fn enumerate_objects(&self) -> Result<Vec<DeviceObject>> {
+        self.inner.enumerate_objects()
+    }
+
+    fn enumerate_children(&self, parent_id: &str) -> Result<Vec<DeviceObject>> {
+        self.listed.lock().unwrap().push(parent_id.to_string());
+        self.inner.enumerate_children(parent_id)
+    }
+
+    fn read_file(&self, object_id: &str) -> Result<Vec<u8>> {
+        self.record_read(object_id);
+        self.inner.read_file(object_id)
+    }
+
+    fn read_file_chunked(
+        &self,
+        object_id: &str,
+        on_chunk: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        self.record_read(object_id);
+        self.inner.read_file_chunked(object_id, &mut |chunk| {
+            on_chunk(chunk)?;
+            if let Some(ref flag) = self.raise_after_chunk {
+                flag.store(true, Ordering::SeqCst);
+            }
+            Ok(())
+        })
+    }
+
+    fn read_thumbnail(&self, object_id: &str) -> Option<Vec<u8>> {
+        self.inner.read_thumbnail(object_id)
+    }
+
+    fn get_object(&self, object_id: &str) -> Result<Option<DeviceObject>> {
+        self.inner.get_object(object_id)
+    }
+
+    fn object_metadata(&self, object_id: &str) -> Result<ObjectMetadata> {
+        self.inner.object_metadata(object_id)
+    }
+
+    fn get_object_path(&self, object_id: &str) -> Option<String> {
+        self.inner.get_object_path(object_id)
+    }
+
+    fn delete_object(&self, object_id: &str) -> Result<()> {
+        self.inner.delete_object(object_id)
+    }
+}
+
 // =============================================================================
 // MockDeviceConfig - Configuration for simulation behaviors
 // =============================================================================
@@ -242,6 +412,12 @@ pub struct MockDeviceConfig {
     pub simulation: DeviceSimulationConfig,
     /// Custom error message for locked device
     pub locked_message: Option<String>,
+    /// Storage capacity reported by the device (None = not reported)
+    pub storage: Option<StorageInfo>,
+    /// Battery percentage reported by the device (None = not reported)
+    pub battery_level: Option<u8>,
+    /// Deterministic faults to inject
+    pub faults: Vec<MockFault>,
 }
 
 impl MockDeviceConfig {
@@ -257,6 +433,9 @@ impl MockDeviceConfig {
             locked_message: Some(
                 "Device is locked. Please unlock and trust this computer.".to_string(),
             ),
+            storage: None,
+            battery_level: None,
+            faults: Vec::new(),
         }
     }
 
@@ -289,6 +468,42 @@ impl MockDeviceConfig {
         self.simulation.read_error_objects = object_ids;
         self
     }
+
+    /// Return truncated content for the first N file reads
+    pub fn with_truncated_reads(mut self, reads: usize) -> Self {
+        self.simulation.truncate_first_reads = reads;
+        self
+    }
+
+    /// Fail the first N file reads with a transient error
+    pub fn with_failed_reads(mut self, reads: usize) -> Self {
+        self.simulation.fail_first_reads = reads;
+        self
+    }
+
+    /// Delay every folder enumeration by `ms` milliseconds
+    pub fn with_enumeration_delay(mut self, ms: u64) -> Self {
+        self.simulation.enumeration_delay_ms = ms;
+        self
+    }
+
+    /// Report the given storage capacity and free space (in bytes)
+    pub fn with_storage(mut self, capacity_bytes: u64, free_bytes: u64) -> Self {
+        self.storage = Some(StorageInfo::new(capacity_bytes, free_bytes));
+        self
+    }
+
+    /// Report the given battery percentage (clamped to 100)
+    pub fn with_battery(mut self, level: u8) -> Self {
+        self.battery_level = Some(level.min(100));
+        self
+    }
+
+    /// Inject a deterministic fault
+    pub fn with_fault(mut self, fault: MockFault) -> Self {
+        self.faults.push(fault);
+        self
+    }
 }
 
 impl From<DeviceSimulationConfig> for MockDeviceConfig {
@@ -296,6 +511,9 @@ impl From<DeviceSimulationConfig> for MockDeviceConfig {
         Self {
             simulation: config,
             locked_message: None,
+            storage: None,
+            battery_level: None,
+            faults: Vec::new(),
         }
     }
 }
@@ -316,6 +534,22 @@ pub struct MockFileSystem {
     children_index: HashMap<String, Vec<String>>,
     /// Read counter for disconnect simulation
     read_count: AtomicUsize,
+    /// Counter of content reads for truncation simulation
+    content_reads: AtomicUsize,
+    /// Counter of read attempts for transient failure simulation
+    failure_reads: AtomicUsize,
+    /// Read attempts per object for injected faults
+    fault_reads: Mutex<HashMap<String, usize>>,
+    /// Counter of folder enumerations for injected disconnects
+    enumerations: AtomicUsize,
+    /// Set once an injected disconnect has fired
+    disconnected: AtomicBool,
+    /// Counter of file reads for injected connection drops
+    drop_reads: AtomicUsize,
+    /// Number of connection drops so far; handles from before a drop are stale
+    connection: AtomicUsize,
+    /// Device listings left out while the dropped device is away
+    offline_listings: AtomicUsize,
     /// Configuration for simulation behaviors
     config: MockDeviceConfig,
 }
@@ -326,6 +560,19 @@ impl Clone for MockFileSystem {
             objects: self.objects.clone(),
             children_index: self.children_index.clone(),
             read_count: AtomicUsize::new(self.read_count.load(Ordering::SeqCst)),
+            content_reads: AtomicUsize::new(self.content_reads.load(Ordering::SeqCst)),
+            failure_reads: AtomicUsize::new(self.failure_reads.load(Ordering::SeqCst)),
+            fault_reads: Mutex::new(
+                self.fault_reads
+                    .lock()
+                    .map(|counts| counts.clone())
+                    .unwrap_or_default(),
+            ),
+            enumerations: AtomicUsize::new(self.enumerations.load(Ordering::SeqCst)),
+            disconnected: AtomicBool::new(self.disconnected.load(Ordering::SeqCst)),
+            drop_reads: AtomicUsize::new(self.drop_reads.load(Ordering::SeqCst)),
+            connection: AtomicUsize::new(self.connection.load(Ordering::SeqCst)),
+            offline_listings: AtomicUsize::new(self.offline_listings.load(Ordering::SeqCst)),
             config: self.config.clone(),
         }
     }
@@ -338,6 +585,14 @@ impl MockFileSystem {
             objects: HashMap::new(),
             children_index: HashMap::new(),
             read_count: AtomicUsize::new(0),
+            content_reads: AtomicUsize::new(0),
+            failure_reads: AtomicUsize::new(0),
+            fault_reads: Mutex::new(HashMap::new()),
+            enumerations: AtomicUsize::new(0),
+            disconnected: AtomicBool::new(false),
+            drop_reads: AtomicUsize::new(0),
+            connection: AtomicUsize::new(0),
+            offline_listings: AtomicUsize::new(0),
             config: MockDeviceConfig::default(),
         }
     }
@@ -348,6 +603,14 @@ impl MockFileSystem {
             objects: HashMap::new(),
             children_index: HashMap::new(),
             read_count: AtomicUsize::new(0),
+            content_reads: AtomicUsize::new(0),
+            failure_reads: AtomicUsize::new(0),
+            fault_reads: Mutex::new(HashMap::new()),
+            enumerations: AtomicUsize::new(0),
+            disconnected: AtomicBool::new(false),
+            drop_reads: AtomicUsize::new(0),
+            connection: AtomicUsize::new(0),
+            offline_listings: AtomicUsize::new(0),
             config,
         }
     }
@@ -386,6 +649,17 @@ impl MockFileSystem {
         }
     }
 
+    /// Remove an object (and its entry in its parent's children)
+    ///
+    /// Returns the removed object, or `None` if it did not exist.
+    pub fn remove_object(&mut self, object_id: &str) -> Option<MockObject> {
+        let object = self.objects.remove(object_id)?;
+        if let Some(siblings) = self.children_index.get_mut(object.parent_id()) {
+            siblings.retain(|id| id != object_id);
+        }
+        Some(object)
+    }
+
     /// Get an object by ID
     pub fn get_object(&self, object_id: &str) -> Option<&MockObject> {
         self.objects.get(object_id)
@@ -434,13 +708,29 @@ impl MockFileSystem {
             return Err(ExtractionError::AccessDenied);
         }
 
+        // Check injected disconnect
+        if self.disconnected.load(Ordering::SeqCst) {
+            return Err(ExtractionError::DeviceDisconnected);
+        }
+
+        // Count this read against any per-object faults
+        let attempt = self.record_fault_read(object_id);
+        if self.config.faults.iter().any(|fault| {
+            matches!(fault, MockFault::FailReads { object_id: id, times }
+                if id == object_id && attempt < *times)
+        }) {
+            return Err(ExtractionError::DeviceError(format!(
+                "Injected read failure for {} (attempt {})",
+                object_id,
+                attempt + 1
+            )));
+        }
+
         // Check disconnect simulation
         if let Some(limit) = self.config.simulation.disconnect_after_reads {
             let count = self.read_count.fetch_add(1, Ordering::SeqCst);
             if count >= limit {
-                return Err(ExtractionError::DeviceError(
-                    "Device disconnected during transfer".to_string(),
-                ));
+                return Err(ExtractionError::DeviceDisconnected);
             }
         }
 
@@ -457,6 +747,14 @@ impl MockFileSystem {
             });
         }
 
+        // Check transient failure simulation
+        let fail = self.config.simulation.fail_first_reads;
+        if fail > 0 && self.failure_reads.fetch_add(1, Ordering::SeqCst) < fail {
+            return Err(ExtractionError::DeviceError(
+                "Simulated transient read failure".to_string(),
+            ));
+        }
+
         // Check random failure
         if self.config.simulation.random_failure_rate > 0 {
             let roll = rand::random::<u8>() % 100;
@@ -486,8 +784,106 @@ impl MockFileSystem {
         }
 
         // Use the new get_content method that supports both eager and lazy content
-        obj.get_content()
-            .ok_or_else(|| ExtractionError::ContentError("No content available".to_string()))
+        let mut content = obj
+            .get_content()
+            .ok_or_else(|| ExtractionError::ContentError("No content available".to_string()))?;
+
+        // Simulate a corrupt transfer by dropping the second half of the data
+        let truncate = self.config.simulation.truncate_first_reads;
+        if truncate > 0 && self.content_reads.fetch_add(1, Ordering::SeqCst) < truncate {
+            content.truncate(content.len() / 2);
+        }
+
+        if self.config.faults.iter().any(|fault| {
+            matches!(fault, MockFault::TruncateReads { object_id: id, times }
+                if id == object_id && attempt < *times)
+        }) {
+            content.truncate(content.len() / 2);
+        }
+
+        Ok(content)
+    }
+
+    /// Record a folder enumeration, failing if an injected disconnect has fired
+    pub fn record_enumeration(&self) -> Result<()> {
+        if !self.disconnected.load(Ordering::SeqCst) {
+            let count = self.enumerations.fetch_add(1, Ordering::SeqCst);
+            let disconnect = self.config.faults.iter().any(|fault| {
+                matches!(fault, MockFault::DisconnectDuringEnumeration { after } if count >= *after)
+            });
+            if !disconnect {
+                return Ok(());
+            }
+            self.disconnected.store(true, Ordering::SeqCst);
+        }
+
+        Err(ExtractionError::DeviceDisconnected)
+    }
+
+    /// Whether an injected disconnect has fired
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::SeqCst)
+    }
+
+    /// Count a file read, returning true if an injected connection drop fires on it
+    fn record_connection_drop(&self) -> bool {
+        let offline_listings = self.config.faults.iter().find_map(|fault| match fault {
+            MockFault::DropConnection {
+                after_reads,
+                offline_listings,
+            } => Some((*after_reads, *offline_listings)),
+            _ => None,
+        });
+        let Some((after_reads, offline_listings)) = offline_listings else {
+            return false;
+        };
+        if self.drop_reads.fetch_add(1, Ordering::SeqCst) != after_reads {
+            return false;
+        }
+        self.connection.fetch_add(1, Ordering::SeqCst);
+        self.offline_listings
+            .store(offline_listings, Ordering::SeqCst);
+        true
+    }
+
+    /// Current connection; handles opened on an earlier one are stale
+    pub fn connection(&self) -> usize {
+        self.connection.load(Ordering::SeqCst)
+    }
+
+    /// Fail with `DeviceDisconnected` if `connection` was dropped since it was opened
+    fn check_connection(&self, connection: usize) -> Result<()> {
+        if connection == self.connection() {
+            Ok(())
+        } else {
+            Err(ExtractionError::DeviceDisconnected)
+        }
+    }
+
+    /// Whether a dropped device is still away
+    fn is_offline(&self) -> bool {
+        self.offline_listings.load(Ordering::SeqCst) > 0
+    }
+
+    /// Record a device listing, returning whether the device is left out of it
+    fn record_listing(&self) -> bool {
+        self.offline_listings
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Count a read of `object_id`, returning how many reads preceded it
+    fn record_fault_read(&self, object_id: &str) -> usize {
+        if self.config.faults.is_empty() {
+            return 0;
+        }
+        let mut counts = self
+            .fault_reads
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let count = counts.entry(object_id.to_string()).or_insert(0);
+        *count += 1;
+        *count - 1
     }
 
     /// Reset read counter (for disconnect simulation)
@@ -628,6 +1024,29 @@ impl MockFileSystem {
         }
     }
 
+    /// Add a removable SD card storage next to internal storage
+    ///
+    /// Creates: SD Card/DCIM/Camera/ with photos. File names follow the same
+    /// pattern as [`add_android_dcim_structure`](Self::add_android_dcim_structure),
+    /// so the two storages hold files with identical names.
+    pub fn add_sd_card_structure(&mut self, num_files: usize) {
+        use crate::testdb::generator::{MockDataGenerator, TEST_JPEG_SIZE};
+
+        self.add_object(MockObject::folder("sdcard", "DEVICE", "SD Card"));
+        self.add_object(MockObject::folder("sd_dcim", "sdcard", "DCIM"));
+        self.add_object(MockObject::folder("sd_camera", "sd_dcim", "Camera"));
+
+        for i in 0..num_files {
+            let file_id = format!("sd_img_{:06}", i + 1);
+            let file_name = format!("IMG_20240115_{:06}.jpg", 120000 + i);
+
+            let content =
+                MockDataGenerator::generate_jpeg_with_seed(TEST_JPEG_SIZE, (1000 + i) as u64);
+
+            self.add_object(MockObject::file(&file_id, "sd_camera", &file_name, content));
+        }
+    }
+
     /// Add a full Android folder structure with multiple media folders
     ///
     /// Creates:
@@ -936,14 +1355,18 @@ pub struct MockDeviceContent {
     fs: Arc<RwLock<MockFileSystem>>,
     /// Device ID for error messages
     device_id: String,
+    /// Connection this handle was opened on
+    connection: usize,
 }
 
 impl MockDeviceContent {
     /// Create a new MockDeviceContent
     pub fn new(fs: Arc<RwLock<MockFileSystem>>, device_id: &str) -> Self {
+        let connection = fs.read().map(|fs| fs.connection()).unwrap_or_default();
         Self {
             fs,
             device_id: device_id.to_string(),
+            connection,
         }
     }
 
@@ -968,8 +1391,22 @@ impl DeviceContentTrait for MockDeviceContent {
             return Err(ExtractionError::AccessDenied);
         }
 
-        let children = fs.get_children(parent_id);
-        Ok(children.into_iter().map(|o| o.object.clone()).collect())
+        fs.check_connection(self.connection)?;
+        fs.record_enumeration()?;
+
+        let delay_ms = fs.config().simulation.enumeration_delay_ms;
+        let children: Vec<DeviceObject> = fs
+            .get_children(parent_id)
+            .into_iter()
+            .map(|o| o.object.clone())
+            .collect();
+        drop(fs);
+
+        // Simulate the WPD round-trip without holding the lock
+        if delay_ms > 0 {
+            std::thread::sleep(Duration::from_millis(delay_ms));
+        }
+        Ok(children)
     }
 
     fn read_file(&self, object_id: &str) -> Result<Vec<u8>> {
@@ -977,7 +1414,45 @@ impl DeviceContentTrait for MockDeviceContent {
             ExtractionError::DeviceError(format!("Failed to acquire read lock: {}", e))
         })?;
 
-        fs.read_file(object_id)
+        fs.check_connection(self.connection)?;
+        let data = fs.read_file(object_id)?;
+        if fs.record_connection_drop() {
+            return Err(ExtractionError::DeviceDisconnected);
+        }
+        Ok(data)
+    }
+
+    fn read_file_chunked(
+        &self,
+        object_id: &str,
+        on_chunk: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let (data, dropped) = {
+            let fs = self.fs.read().map_err(|e| {
+                ExtractionError::DeviceError(format!("Failed to acquire read lock: {}", e))
+            })?;
+            fs.check_connection(self.connection)?;
+            let data = fs.read_file(object_id)?;
+            (data, fs.record_connection_drop())
+        };
+
+        // Hand the content over in WPD-sized pieces so callers see a stream;
+        // a dropped connection delivers the first half before failing
+        if dropped {
+            data[..data.len() / 2]
+                .chunks(MOCK_STREAM_CHUNK_SIZE)
+                .try_for_each(&mut *on_chunk)?;
+            return Err(ExtractionError::DeviceDisconnected);
+        }
+        data.chunks(MOCK_STREAM_CHUNK_SIZE).try_for_each(on_chunk)
+    }
+
+    fn read_thumbnail(&self, object_id: &str) -> Option<Vec<u8>> {
+        let fs = self.fs.read().ok()?;
+        if fs.config().simulation.simulate_locked {
+            return None;
+        }
+        fs.get_object(object_id)?.thumbnail.clone()
     }
 
     fn get_object(&self, object_id: &str) -> Result<Option<DeviceObject>> {
@@ -988,10 +1463,51 @@ impl DeviceContentTrait for MockDeviceContent {
         Ok(fs.get_object(object_id).map(|o| o.object.clone()))
     }
 
+    fn object_metadata(&self, object_id: &str) -> Result<ObjectMetadata> {
+        let fs = self.fs.read().map_err(|e| {
+            ExtractionError::DeviceError(format!("Failed to acquire read lock: {}", e))
+        })?;
+
+        if fs.config().simulation.simulate_locked {
+            return Err(ExtractionError::AccessDenied);
+        }
+
+        let object = fs.get_object(object_id).ok_or_else(|| {
+            ExtractionError::ContentError(format!("Object not found: {}", object_id))
+        })?;
+        Ok(object
+            .metadata
+            .clone()
+            .unwrap_or_else(|| ObjectMetadata::from_object(&object.object)))
+    }
+
     fn get_object_path(&self, object_id: &str) -> Option<String> {
         let fs = self.fs.read().ok()?;
         fs.get_object_path(object_id)
     }
+
+    fn delete_object(&self, object_id: &str) -> Result<()> {
+        let mut fs = self.fs.write().map_err(|e| {
+            ExtractionError::DeviceError(format!("Failed to acquire write lock: {}", e))
+        })?;
+
+        if fs.config().simulation.simulate_locked {
+            return Err(ExtractionError::AccessDenied);
+        }
+        fs.check_connection(self.connection)?;
+
+        // Like WPD without recursion, refuse to delete a folder that has children
+        if !fs.get_children(object_id).is_empty() {
+            return Err(ExtractionError::ContentError(format!(
+                "Folder is not empty: {}",
+                object_id
+            )));
+        }
+
+        fs.remove_object(object_id).map(|_| ()).ok_or_else(|| {
+            ExtractionError::ContentError(format!("Object not found: {}", object_id))
+        })
+    }
 }
 
 // =============================================================================
@@ -1043,6 +1559,21 @@ impl MockDeviceManager {
         self.configs.insert(device_id, config);
     }
 
+    /// Create a manager with the single device recorded in a fixture file
+    ///
+    /// See [`DeviceFixture`](super::fixture::DeviceFixture) for the format.
+    pub fn from_fixture(path: &std::path::Path) -> Result<Self> {
+        let fixture = super::fixture::DeviceFixture::load(path)?;
+        let info = fixture.device_info();
+        let mut config = MockDeviceConfig::new();
+        config.storage = info.storage;
+        config.battery_level = info.battery_level;
+
+        let mut manager = Self::new();
+        manager.add_device_with_config(info, fixture.to_file_system(), config);
+        Ok(manager)
+    }
+
     /// Get file system for device (if exists)
     pub fn get_file_system(&self, device_id: &str) -> Option<Arc<RwLock<MockFileSystem>>> {
         self.file_systems.get(device_id).cloned()
@@ -1081,7 +1612,19 @@ impl DeviceManagerTrait for MockDeviceManager {
     }
 
     fn enumerate_all_devices(&self) -> Result<Vec<DeviceInfo>> {
-        Ok(self.devices.clone())
+        // A device that dropped its connection is missing until it comes back
+        Ok(self
+            .devices
+            .iter()
+            .filter(|device| {
+                !self
+                    .file_systems
+                    .get(&device.device_id)
+                    .and_then(|fs| fs.read().ok())
+                    .is_some_and(|fs| fs.record_listing())
+            })
+            .cloned()
+            .collect())
     }
 
     fn open_device(&self, device_id: &str) -> Result<Self::Content> {
@@ -1095,6 +1638,9 @@ impl DeviceManagerTrait for MockDeviceManager {
         let fs = self.file_systems.get(device_id).cloned().ok_or_else(|| {
             ExtractionError::DeviceError(format!("Device not found: {}", device_id))
         })?;
+        if fs.read().is_ok_and(|fs| fs.is_offline()) {
+            return Err(ExtractionError::DeviceDisconnected);
+        }
 
         Ok(MockDeviceContent::new(fs, device_id))
     }
@@ -1106,6 +1652,16 @@ impl DeviceManagerTrait for MockDeviceManager {
             .cloned()
     }
 
+    fn get_device_storage(&self, device_id: &str) -> Option<StorageInfo> {
+        let fs = self.file_systems.get(device_id)?.read().ok()?;
+        fs.config().storage
+    }
+
+    fn battery_level(&self, device_id: &str) -> Option<u8> {
+        let fs = self.file_systems.get(device_id)?.read().ok()?;
+        fs.config().battery_level
+    }
+
     fn device_count(&self) -> usize {
         self.devices.len()
     }
@@ -1193,6 +1749,8 @@ mod tests {
             friendly_name: "Test iPhone".to_string(),
             manufacturer: "Apple Inc.".to_string(),
             model: "iPhone 14".to_string(),
+            storage: None,
+            battery_level: None,
         };
 
         let fs = MockFileSystem::new();
@@ -1225,6 +1783,34 @@ mod tests {
         assert_eq!(root_objects[0].name, "Internal Storage");
     }
 
+    #[test]
+    fn test_delete_object() {
+        let mut manager = MockDeviceManager::new();
+
+        let device = DeviceInfo::new("test-001", "Test iPhone", "Apple Inc.", "iPhone 15");
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::file(
+            "photo",
+            "internal",
+            "IMG_0001.JPG",
+            vec![1],
+        ));
+
+        manager.add_device(device, fs);
+        let content = manager.open_device("test-001").unwrap();
+
+        // Non-empty folders are not deleted
+        assert!(content.delete_object("internal").is_err());
+
+        content.delete_object("photo").unwrap();
+        assert!(content.get_object("photo").unwrap().is_none());
+        assert!(content.enumerate_children("internal").unwrap().is_empty());
+
+        // Deleting again fails
+        assert!(content.delete_object("photo").is_err());
+    }
+
     #[test]
     fn test_access_denied_simulation() {
         let mut manager = MockDeviceManager::new();
@@ -1323,4 +1909,106 @@ mod tests {
         let result = fs.read_file("file2");
         assert!(matches!(result, Err(ExtractionError::TransferError { .. })));
     }
+
+    #[test]
+    fn test_injected_read_faults() {
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("internal", "DEVICE", "Internal Storage"));
+        fs.add_object(MockObject::file(
+            "file1",
+            "internal",
+            "FILE1.JPG",
+            vec![1; 8],
+        ));
+        fs.add_object(MockObject::file(
+            "file2",
+            "internal",
+            "FILE2.JPG",
+            vec![2; 8],
+        ));
+        fs.set_config(
+            MockDeviceConfig::default()
+                .with_fault(MockFault::FailReads {
+                    object_id: "file1".to_string(),
+                    times: 2,
+                })
+                .with_fault(MockFault::TruncateReads {
+                    object_id: "file2".to_string(),
+                    times: 1,
+                }),
+        );
+
+        // file1 fails twice with a retryable error, then reads normally
+        for _ in 0..2 {
+            let error = fs.read_file("file1").unwrap_err();
+            assert!(error.is_retryable());
+        }
+        assert_eq!(fs.read_file("file1").unwrap(), vec![1; 8]);
+
+        // file2 is truncated once, then complete
+        assert_eq!(fs.read_file("file2").unwrap().len(), 4);
+        assert_eq!(fs.read_file("file2").unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_injected_disconnect_during_enumeration() {
+        let mut fs = MockFileSystem::with_config(
+            MockDeviceConfig::default()
+                .with_fault(MockFault::DisconnectDuringEnumeration { after: 2 }),
+        );
+        fs.add_standard_dcim_structure(2, 1);
+        let content = MockDeviceContent::new(Arc::new(RwLock::new(fs)), "mock");
+
+        assert!(content.enumerate_children("DEVICE").is_ok());
+        assert!(content.enumerate_children("internal").is_ok());
+        assert!(content.enumerate_children("dcim").is_err());
+
+        // Once gone, the device stays gone
+        assert!(content.enumerate_children("DEVICE").is_err());
+        let fs = content.file_system();
+        let fs = fs.read().unwrap();
+        assert!(fs.is_disconnected());
+        assert!(fs.read_file("img_000001").is_err());
+    }
+
+    #[test]
+    fn test_object_metadata_maps_preset_fields() {
+        let metadata = ObjectMetadata {
+            date_created: Some("2024-06-01T09:30:00Z".to_string()),
+            date_modified: Some("2024-06-02T10:00:00Z".to_string()),
+            content_type: Some("video/quicktime".to_string()),
+            original_file_name: Some("IMG_0042.MOV".to_string()),
+            width: Some(1920),
+            height: Some(1080),
+        };
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("dcim", "DEVICE", "DCIM"));
+        fs.add_object(
+            MockObject::file("mov", "dcim", "IMG_0042.MOV", vec![0; 16])
+                .with_metadata(metadata.clone()),
+        );
+        fs.add_object(MockObject::file_with_date(
+            "jpg",
+            "dcim",
+            "IMG_0043.JPG",
+            vec![0; 16],
+            "2024-06-03T11:00:00Z",
+        ));
+        let content = MockDeviceContent::new(Arc::new(RwLock::new(fs)), "mock");
+
+        let read = content.object_metadata("mov").unwrap();
+        assert_eq!(read, metadata);
+        assert_eq!(read.best_date(), Some("2024-06-01T09:30:00Z"));
+
+        // Without a preset, only what enumeration reports is filled in
+        let read = content.object_metadata("jpg").unwrap();
+        assert_eq!(read.date_created, None);
+        assert_eq!(read.date_modified.as_deref(), Some("2024-06-03T11:00:00Z"));
+        assert_eq!(read.content_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(read.original_file_name.as_deref(), Some("IMG_0043.JPG"));
+        assert_eq!((read.width, read.height), (None, None));
+        assert_eq!(read.best_date(), Some("2024-06-03T11:00:00Z"));
+
+        assert!(content.object_metadata("missing").is_err());
+    }
 }
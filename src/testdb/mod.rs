@@ -33,6 +33,25 @@
 //! let summary = runner.run_by_names(&["single_iphone", "device_locked"]);
 //! ```
 //!
+//! # Custom Scenarios
+//!
+//! Use [`ScenarioBuilder`] to declare a device tree for a regression test and
+//! run the real extractor against it:
+//!
+//! ```rust,no_run
+//! use photo_extraction_tool::testdb::{FileSpec, ScenarioBuilder};
+//!
+//! let content = ScenarioBuilder::new("flaky_read")
+//!     .file("Internal Storage/DCIM/100APPLE/IMG_0001.JPG", FileSpec::sized(4096))
+//!     .failed_reads(1)
+//!     .build_content();
+//! ```
+//!
+//! # Recorded Fixtures
+//!
+//! `photo_extraction_tool scan --record fixture.json` captures a real device's
+//! tree (no file bytes). Replay it with [`MockDeviceManager::from_fixture`].
+//!
 //! # Available Scenarios
 //!
 //! ## Device Detection
@@ -58,6 +77,8 @@
 //! - `file_read_errors` - Specific files fail to read
 //! - `slow_transfer` - Simulated slow connection
 //! - `flaky_connection` - Random failure rate
+//! - `transient_read_failure` - A read fails twice, then succeeds on retry
+//! - `permanent_read_failure` - A read fails on every retry
 //!
 //! ## Duplicate Detection
 //! - `exact_duplicates` - Same file in multiple folders
@@ -80,6 +101,7 @@
 #![allow(unused_imports)]
 
 pub mod assertions;
+pub mod fixture;
 pub mod generator;
 pub mod integration;
 pub mod mock_device;
@@ -92,6 +114,9 @@ pub use assertions::{
     DeviceFixtureBuilder, FileSystemFixtureBuilder, ScenarioFixtureBuilder, TestAssertions,
 };
 
+// Re-export fixture recording/replay types
+pub use fixture::{DeviceFixture, FixtureDevice, FixtureObject, FIXTURE_VERSION};
+
 // Re-export commonly used types from generator
 pub use generator::{FileGeneratorConfig, MockDataGenerator};
 
@@ -109,7 +134,8 @@ pub use integration::{
 
 // Re-export commonly used types from mock_device
 pub use mock_device::{
-    MockDeviceConfig, MockDeviceContent, MockDeviceManager, MockFileSystem, MockObject,
+    MockDeviceConfig, MockDeviceContent, MockDeviceManager, MockFault, MockFileSystem, MockObject,
+    RecordingDevice,
 };
 
 // Re-export DeviceInfo from the traits module for convenience
@@ -121,7 +147,7 @@ pub use runner::{
 };
 
 // Re-export commonly used types from scenarios
-pub use scenarios::{ExpectedResults, ScenarioLibrary, TestScenario};
+pub use scenarios::{ExpectedResults, FileSpec, ScenarioBuilder, ScenarioLibrary, TestScenario};
 
 /// Prelude module for easy imports
 pub mod prelude {
@@ -132,10 +158,11 @@ pub mod prelude {
     pub use super::generator::MockDataGenerator;
     pub use super::integration::{IntegrationTestConfig, IntegrationTestRunner};
     pub use super::mock_device::{
-        MockDeviceConfig, MockDeviceContent, MockDeviceManager, MockFileSystem, MockObject,
+        MockDeviceConfig, MockDeviceContent, MockDeviceManager, MockFault, MockFileSystem,
+        MockObject,
     };
     pub use super::runner::{TestRunner, TestRunnerConfig, TestSummary};
-    pub use super::scenarios::{ScenarioLibrary, TestScenario};
+    pub use super::scenarios::{FileSpec, ScenarioBuilder, ScenarioLibrary, TestScenario};
     pub use crate::device::traits::{
         DeviceContentTrait, DeviceInfo, DeviceManagerTrait, DeviceObject,
     };
@@ -182,13 +209,7 @@ pub fn list_scenario_names() -> Vec<String> {
 
 /// Get a list of all available tags
 pub fn list_tags() -> Vec<String> {
-    let mut tags: Vec<String> = ScenarioLibrary::all_scenarios()
-        .into_iter()
-        .flat_map(|s| s.tags)
-        .collect();
-    tags.sort();
-    tags.dedup();
-    tags
+    ScenarioLibrary::tags()
 }
 
 /// Run all integration tests and print results
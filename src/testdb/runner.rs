@@ -7,17 +7,20 @@
 //! - Running all scenarios or filtered subsets
 //! - Verbose output and progress tracking
 //! - HTML and JSON report generation
+//! - A machine-readable JSON summary for CI ([`TestSummary::to_json`])
 //! - Interactive mode for browsing scenarios
 //! - Full integration with the extraction pipeline via traits
 
 use super::mock_device::{MockDeviceContent, MockDeviceManager, MockFileSystem};
 use super::scenarios::{ExpectedResults, ScenarioLibrary, TestScenario};
 use crate::core::error::{ExtractionError, Result};
+use crate::core::retry::{Retrier, RetryPolicy};
 use crate::device::traits::{DeviceContentTrait, DeviceInfo, DeviceManagerTrait, DeviceObject};
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -45,6 +48,8 @@ pub struct ScenarioResult {
     pub errors: usize,
     /// Total bytes processed
     pub bytes_processed: u64,
+    /// Number of device operations retried after a transient failure
+    pub retries: usize,
     /// Summary message
     pub message: String,
     /// Expected results for comparison
@@ -65,6 +70,7 @@ impl ScenarioResult {
             duplicates_found: 0,
             errors: 0,
             bytes_processed: 0,
+            retries: 0,
             message: "Passed".to_string(),
             expected: None,
             failure_reason: None,
@@ -82,6 +88,7 @@ impl ScenarioResult {
             duplicates_found: 0,
             errors: 0,
             bytes_processed: 0,
+            retries: 0,
             message: format!("Failed: {}", reason),
             expected: None,
             failure_reason: Some(reason.to_string()),
@@ -170,6 +177,83 @@ impl TestSummary {
             .map(|r| r.bytes_processed)
             .sum()
     }
+
+    /// The summary as pretty-printed JSON, for CI jobs to assert on
+    ///
+    /// Lists every scenario once, by name, with whether it passed, how long
+    /// it took and why it failed.
+    pub fn to_json(&self) -> String {
+        // A scenario appears under each of its tags, but is reported once
+        let mut scenarios = BTreeMap::new();
+        for result in self.results_by_tag.values().flatten() {
+            scenarios
+                .entry(result.name.as_str())
+                .or_insert_with(|| ScenarioReport::from(result));
+        }
+
+        let report = SummaryReport {
+            total: self.total,
+            passed: self.passed,
+            failed: self.failed,
+            skipped: self.skipped,
+            duration_seconds: self.total_duration.as_secs_f64(),
+            pass_rate: self.pass_rate(),
+            scenarios: scenarios.into_values().collect(),
+        };
+        serde_json::to_string_pretty(&report).expect("summary report is plain data")
+    }
+
+    /// Write [`to_json`](Self::to_json) to `path`, creating its folder
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                ExtractionError::IoError(format!(
+                    "Failed to create folder '{}': {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+        fs::write(path, self.to_json()).map_err(|e| {
+            ExtractionError::IoError(format!(
+                "Failed to write test summary '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// JSON form of a [`TestSummary`]
+#[derive(Serialize)]
+struct SummaryReport<'a> {
+    total: usize,
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    duration_seconds: f64,
+    pass_rate: f64,
+    scenarios: Vec<ScenarioReport<'a>>,
+}
+
+/// JSON form of one [`ScenarioResult`]
+#[derive(Serialize)]
+struct ScenarioReport<'a> {
+    name: &'a str,
+    passed: bool,
+    duration_seconds: f64,
+    failure_message: Option<&'a str>,
+}
+
+impl<'a> From<&'a ScenarioResult> for ScenarioReport<'a> {
+    fn from(result: &'a ScenarioResult) -> Self {
+        Self {
+            name: &result.name,
+            passed: result.passed,
+            duration_seconds: result.duration.as_secs_f64(),
+            failure_message: result.failure_reason.as_deref(),
+        }
+    }
 }
 
 // =============================================================================
@@ -199,6 +283,8 @@ pub struct TestRunnerConfig {
     pub write_files: bool,
     /// Output directory for file writes
     pub output_dir: Option<PathBuf>,
+    /// Maximum retries for transient device errors (mirrors the extractor's default)
+    pub max_retries: usize,
 }
 
 impl Default for TestRunnerConfig {
@@ -214,6 +300,7 @@ impl Default for TestRunnerConfig {
             timeout_seconds: 300,
             write_files: false,
             output_dir: None,
+            max_retries: 3,
         }
     }
 }
@@ -237,6 +324,8 @@ pub struct ExecutionStats {
     pub bytes_processed: u64,
     /// Number of folders traversed
     pub folders: usize,
+    /// Device operations retried after a transient failure
+    pub retries: usize,
 }
 
 // =============================================================================
@@ -279,6 +368,15 @@ impl TestRunner {
         self.run_scenarios_lazy(|| ScenarioLibrary::all_scenarios())
     }
 
+    /// Run all available test scenarios and write the JSON summary to `path`
+    ///
+    /// The summary is still printed as usual when running verbosely.
+    pub fn run_all_to_file(&mut self, path: &Path) -> Result<TestSummary> {
+        let summary = self.run_all();
+        summary.write_json(path)?;
+        Ok(summary)
+    }
+
     /// Run quick test scenarios only
     pub fn run_quick(&mut self) -> TestSummary {
         self.run_scenarios_lazy(|| ScenarioLibrary::quick_scenarios())
@@ -290,6 +388,21 @@ impl TestRunner {
         self.run_scenarios_lazy(move || ScenarioLibrary::scenarios_by_tag(&tag))
     }
 
+    /// Run the scenarios carrying any of `tags`
+    pub fn run_tagged(&mut self, tags: &[&str]) -> TestSummary {
+        self.run_scenarios_lazy(|| ScenarioLibrary::scenarios_tagged(tags))
+    }
+
+    /// Run those of `scenarios` that carry any of `tags`
+    pub fn run_tagged_in(&mut self, scenarios: Vec<TestScenario>, tags: &[&str]) -> TestSummary {
+        self.run_scenarios_lazy(|| {
+            scenarios
+                .into_iter()
+                .filter(|s| s.has_any_tag(tags))
+                .collect()
+        })
+    }
+
     /// Run scenarios by name
     pub fn run_by_names(&mut self, names: &[&str]) -> TestSummary {
         let all_scenarios = ScenarioLibrary::all_scenarios();
@@ -438,6 +551,7 @@ impl TestRunner {
                         stats.bytes_processed,
                     )
                     .with_expected(expected);
+                result.retries = stats.retries;
 
                 result
             }
@@ -509,8 +623,18 @@ impl TestRunner {
         // and reduce memory pressure from deep recursion
         let mut stack = vec![parent_id.to_string()];
 
+        // Retry transient failures the same way the extractor does, without the backoff delay
+        let retrier = Retrier::new(
+            RetryPolicy::new(self.config.max_retries, 0),
+            Arc::new(AtomicBool::new(false)),
+        );
+
         while let Some(current_parent) = stack.pop() {
-            let children = content.enumerate_children(&current_parent)?;
+            let children = retrier.run(&current_parent, || {
+                content.enumerate_children(&current_parent)
+            });
+            stats.retries = retrier.retries();
+            let children = children?;
 
             for child in children {
                 if child.is_folder {
@@ -521,7 +645,9 @@ impl TestRunner {
                     // Check if it's an extractable file
                     if Self::is_extractable_file(&child.name) {
                         // Try to read the file content
-                        match content.read_file(&child.object_id) {
+                        let data = retrier.run(&child.name, || content.read_file(&child.object_id));
+                        stats.retries = retrier.retries();
+                        match data {
                             Ok(data) => {
                                 stats.files_extracted += 1;
                                 stats.bytes_processed += data.len() as u64;
@@ -1099,4 +1225,113 @@ mod tests {
         // Empty device should have 0 files extracted
         assert_eq!(results[0].files_extracted, 0);
     }
+
+    #[test]
+    fn test_transient_read_failure_scenario_retries() {
+        let mut runner = TestRunner::new();
+
+        let summary = runner.run_by_names(&["transient_read_failure"]);
+        assert_eq!(summary.total, 1);
+        assert_eq!(summary.passed, 1);
+
+        let result = &runner.results[0];
+        assert_eq!(result.files_extracted, 10);
+        assert_eq!(result.errors, 0);
+        assert_eq!(result.retries, 2);
+    }
+
+    #[test]
+    fn test_permanent_read_failure_scenario_counts_error() {
+        let mut runner = TestRunner::new();
+
+        let summary = runner.run_by_names(&["permanent_read_failure"]);
+        assert_eq!(summary.total, 1);
+        assert_eq!(summary.passed, 1);
+
+        let result = &runner.results[0];
+        assert_eq!(result.files_extracted, 9);
+        assert_eq!(result.errors, 1);
+        assert_eq!(result.retries, 3);
+    }
+
+    #[test]
+    fn test_summary_json_lists_each_scenario() {
+        let mut runner = TestRunner::new();
+        let mut summary = runner.run_by_names(&["single_iphone", "device_locked"]);
+
+        // A failed scenario carries its failure message
+        summary
+            .results_by_tag
+            .entry("device".to_string())
+            .or_default()
+            .push(ScenarioResult::failed(
+                "flaky_cable",
+                Duration::from_millis(250),
+                "Expected 10 files, got 7",
+            ));
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("reports").join("summary.json");
+        summary.write_json(&path).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(json["total"], 2);
+        let scenarios = json["scenarios"].as_array().unwrap();
+        let scenario = |name: &str| {
+            scenarios
+                .iter()
+                .find(|s| s["name"] == name)
+                .unwrap_or_else(|| panic!("{} missing from summary", name))
+        };
+        assert_eq!(scenario("single_iphone")["passed"], true);
+        assert_eq!(scenario("device_locked")["passed"], true);
+        assert!(scenario("single_iphone")["failure_message"].is_null());
+        assert!(scenario("single_iphone")["duration_seconds"].is_f64());
+
+        let failed = scenario("flaky_cable");
+        assert_eq!(failed["passed"], false);
+        assert_eq!(failed["duration_seconds"], 0.25);
+        assert_eq!(failed["failure_message"], "Expected 10 files, got 7");
+    }
+
+    #[test]
+    fn test_run_tagged_runs_exactly_the_tagged_scenarios() {
+        use crate::testdb::scenarios::{FileSpec, ScenarioBuilder};
+
+        let scenario = |name: &str, tags: Vec<&str>| {
+            ScenarioBuilder::new(name)
+                .file(
+                    "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                    FileSpec::sized(64),
+                )
+                .tags(tags)
+                .build()
+        };
+        let scenarios = vec![
+            scenario("first_dup", vec!["dup"]),
+            scenario("not_a_dup", vec!["error"]),
+            scenario("second_dup", vec!["dup", "error"]),
+        ];
+
+        let mut runner = TestRunner::new();
+        let summary = runner.run_tagged_in(scenarios, &["dup"]);
+        assert_eq!(summary.total, 2);
+        let names: Vec<&str> = runner.results().iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["first_dup", "second_dup"]);
+
+        // Library scenarios are selected by any of several tags
+        let summary = runner.run_tagged(&["duplicate", "profile"]);
+        let expected = ScenarioLibrary::scenarios_tagged(&["duplicate", "profile"]);
+        assert_eq!(summary.total, expected.len());
+        for (result, scenario) in runner.results().iter().zip(&expected) {
+            assert_eq!(result.name, scenario.name);
+            assert!(scenario
+                .tags
+                .iter()
+                .any(|t| t == "duplicate" || t == "profile"));
+        }
+        assert!(ScenarioLibrary::tags().contains(&"duplicate".to_string()));
+        assert_eq!(runner.run_tagged(&["no-such-tag"]).total, 0);
+    }
 }
@@ -10,8 +10,11 @@
 //! - Tags for filtering and organization
 
 use super::generator::MockDataGenerator;
-use super::mock_device::{MockDeviceConfig, MockFileSystem, MockObject};
+use super::mock_device::{
+    MockDeviceConfig, MockDeviceContent, MockDeviceManager, MockFault, MockFileSystem, MockObject,
+};
 use crate::device::traits::DeviceInfo;
+use std::sync::{Arc, RwLock};
 
 /// A complete test scenario with device info and file system
 #[derive(Debug, Clone)]
@@ -73,6 +76,315 @@ impl TestScenario {
         self.tags = tags.into_iter().map(String::from).collect();
         self
     }
+
+    /// Whether the scenario carries any of `tags`
+    pub fn has_any_tag(&self, tags: &[&str]) -> bool {
+        self.tags.iter().any(|tag| tags.contains(&tag.as_str()))
+    }
+}
+
+/// Content and metadata for a file added through [`ScenarioBuilder`]
+#[derive(Debug, Clone)]
+pub struct FileSpec {
+    /// Canned bytes, or `None` to generate content of `size` bytes on demand
+    content: Option<Vec<u8>>,
+    /// File size in bytes
+    size: usize,
+    /// Modification date reported by the device
+    date_modified: Option<String>,
+    /// Thumbnail the device keeps for this file
+    thumbnail: Option<Vec<u8>>,
+}
+
+impl FileSpec {
+    /// A file whose content is exactly `content`
+    pub fn bytes(content: impl Into<Vec<u8>>) -> Self {
+        let content = content.into();
+        Self {
+            size: content.len(),
+            content: Some(content),
+            date_modified: None,
+            thumbnail: None,
+        }
+    }
+
+    /// A file of `size` bytes with generated content matching its extension
+    pub fn sized(size: usize) -> Self {
+        Self {
+            content: None,
+            size,
+            date_modified: None,
+            thumbnail: None,
+        }
+    }
+
+    /// Set the modification date reported by the device (e.g. `2024-01-15T10:00:00Z`)
+    pub fn modified(mut self, date: &str) -> Self {
+        self.date_modified = Some(date.to_string());
+        self
+    }
+
+    /// Attach a device-side thumbnail
+    pub fn thumbnail(mut self, thumbnail: Vec<u8>) -> Self {
+        self.thumbnail = Some(thumbnail);
+        self
+    }
+}
+
+/// Declarative builder for custom mock device trees
+///
+/// Paths are relative to the device root and use `/` as separator; missing
+/// parent folders are created automatically. Each object's ID is its full
+/// path, so `read_error("Internal Storage/DCIM/100APPLE/IMG_0001.JPG")`
+/// targets that file.
+///
+/// ```rust,no_run
+/// use photo_extraction_tool::testdb::{FileSpec, ScenarioBuilder};
+///
+/// let content = ScenarioBuilder::new("two_photos")
+///     .file("Internal Storage/DCIM/100APPLE/IMG_0001.JPG", FileSpec::sized(2048))
+///     .file("Internal Storage/DCIM/100APPLE/IMG_0002.JPG", FileSpec::bytes(vec![0xFF, 0xD8]))
+///     .read_error("Internal Storage/DCIM/100APPLE/IMG_0002.JPG")
+///     .build_content();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScenarioBuilder {
+    name: String,
+    description: String,
+    device_info: DeviceInfo,
+    file_system: MockFileSystem,
+    config: MockDeviceConfig,
+    expected: Option<ExpectedResults>,
+    tags: Vec<String>,
+    next_seed: u64,
+}
+
+impl ScenarioBuilder {
+    /// Start a scenario with an empty file system and a default iPhone
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: format!("Custom scenario '{}'", name),
+            device_info: DeviceInfo::new(
+                &format!("custom-{}", name),
+                "Test iPhone",
+                "Apple Inc.",
+                "iPhone 15",
+            ),
+            file_system: MockFileSystem::new(),
+            config: MockDeviceConfig::new(),
+            expected: None,
+            tags: vec!["custom".to_string()],
+            next_seed: 0,
+        }
+    }
+
+    /// Set the description
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Replace the mock device information
+    pub fn device(mut self, device_info: DeviceInfo) -> Self {
+        self.device_info = device_info;
+        self
+    }
+
+    /// Add a folder (and any missing parents)
+    pub fn folder(mut self, path: &str) -> Self {
+        self.ensure_folder(path);
+        self
+    }
+
+    /// Add a file (and any missing parent folders)
+    pub fn file(mut self, path: &str, spec: FileSpec) -> Self {
+        let (parent_id, name) = match path.trim_matches('/').rsplit_once('/') {
+            Some((parent, name)) => (self.ensure_folder(parent), name),
+            None => ("DEVICE".to_string(), path.trim_matches('/')),
+        };
+        let id = Self::object_id(path);
+
+        let mut object = match spec.content {
+            Some(content) => match &spec.date_modified {
+                Some(date) => MockObject::file_with_date(&id, &parent_id, name, content, date),
+                None => MockObject::file(&id, &parent_id, name, content),
+            },
+            None => {
+                self.next_seed += 1;
+                let mut object =
+                    MockObject::lazy_file(&id, &parent_id, name, spec.size, self.next_seed);
+                object.object.date_modified = spec.date_modified;
+                object
+            }
+        };
+        if let Some(thumbnail) = spec.thumbnail {
+            object = object.with_thumbnail(thumbnail);
+        }
+
+        self.file_system.add_object(object);
+        self
+    }
+
+    /// Report the given storage capacity and free space (in bytes)
+    pub fn storage(mut self, capacity_bytes: u64, free_bytes: u64) -> Self {
+        self.config = self.config.with_storage(capacity_bytes, free_bytes);
+        self
+    }
+
+    /// Report the given battery percentage
+    pub fn battery(mut self, level: u8) -> Self {
+        self.config = self.config.with_battery(level);
+        self
+    }
+
+    /// Make every read of the given object fail
+    pub fn read_error(mut self, object_id: &str) -> Self {
+        self.config
+            .simulation
+            .read_error_objects
+            .push(Self::object_id(object_id));
+        self
+    }
+
+    /// Fail the first `reads` file reads with a transient error
+    pub fn failed_reads(mut self, reads: usize) -> Self {
+        self.config = self.config.with_failed_reads(reads);
+        self
+    }
+
+    /// Return only half the content for the first `reads` file reads
+    pub fn truncated_reads(mut self, reads: usize) -> Self {
+        self.config = self.config.with_truncated_reads(reads);
+        self
+    }
+
+    /// Fail the first `times` reads of the given object with a transient error
+    pub fn fail_reads_of(mut self, object_id: &str, times: usize) -> Self {
+        self.config = self.config.with_fault(MockFault::FailReads {
+            object_id: Self::object_id(object_id),
+            times,
+        });
+        self
+    }
+
+    /// Return only half the content of the given object for its first `times` reads
+    pub fn truncate_reads_of(mut self, object_id: &str, times: usize) -> Self {
+        self.config = self.config.with_fault(MockFault::TruncateReads {
+            object_id: Self::object_id(object_id),
+            times,
+        });
+        self
+    }
+
+    /// Disconnect the device once `after` folders have been enumerated
+    pub fn disconnect_during_enumeration(mut self, after: usize) -> Self {
+        self.config = self
+            .config
+            .with_fault(MockFault::DisconnectDuringEnumeration { after });
+        self
+    }
+
+    /// Replace the device simulation config wholesale
+    ///
+    /// Call this before `storage`, `read_error` and the other simulation setters,
+    /// which adjust whatever config is current.
+    pub fn config(mut self, config: MockDeviceConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Override the expected results (by default they are derived from the tree)
+    pub fn expect(mut self, expected: ExpectedResults) -> Self {
+        self.expected = Some(expected);
+        self
+    }
+
+    /// Add tags to the scenario
+    pub fn tags(mut self, tags: Vec<&str>) -> Self {
+        self.tags.extend(tags.into_iter().map(String::from));
+        self
+    }
+
+    /// Build the scenario for use with [`TestRunner`](super::runner::TestRunner)
+    pub fn build(self) -> TestScenario {
+        let mut file_system = self.file_system;
+        let read_errors = self
+            .config
+            .simulation
+            .read_error_objects
+            .iter()
+            .filter(|id| file_system.get_object(id).is_some_and(|o| !o.is_folder()))
+            .count();
+        let expected = self.expected.unwrap_or_else(|| ExpectedResults {
+            files_to_extract: file_system.file_count() - read_errors,
+            folders: file_system.folder_count(),
+            total_size: file_system.total_size(),
+            errors: read_errors,
+            should_succeed: !self.config.simulation.simulate_locked,
+            ..Default::default()
+        });
+
+        let mut device_info = self.device_info;
+        if device_info.storage.is_none() {
+            device_info.storage = self.config.storage;
+        }
+        if device_info.battery_level.is_none() {
+            device_info.battery_level = self.config.battery_level;
+        }
+        file_system.set_config(self.config);
+
+        TestScenario {
+            name: self.name,
+            description: self.description,
+            device_info,
+            file_system,
+            expected,
+            tags: self.tags,
+        }
+    }
+
+    /// Build a device manager with this scenario's device attached
+    pub fn build_manager(self) -> MockDeviceManager {
+        let config = self.config.clone();
+        let scenario = self.build();
+        let mut manager = MockDeviceManager::new();
+        manager.add_device_with_config(scenario.device_info, scenario.file_system, config);
+        manager
+    }
+
+    /// Build the device content directly, ready to pass to an extractor
+    pub fn build_content(self) -> MockDeviceContent {
+        let scenario = self.build();
+        let device_id = scenario.device_info.device_id;
+        MockDeviceContent::new(Arc::new(RwLock::new(scenario.file_system)), &device_id)
+    }
+
+    /// Create each folder along `path`, returning the ID of the last one
+    fn ensure_folder(&mut self, path: &str) -> String {
+        let mut parent_id = "DEVICE".to_string();
+        for name in path.split('/').filter(|s| !s.is_empty()) {
+            let id = if parent_id == "DEVICE" {
+                name.to_string()
+            } else {
+                format!("{}/{}", parent_id, name)
+            };
+            if self.file_system.get_object(&id).is_none() {
+                self.file_system
+                    .add_object(MockObject::folder(&id, &parent_id, name));
+            }
+            parent_id = id;
+        }
+        parent_id
+    }
+
+    /// Normalize a path into the object ID used for it
+    fn object_id(path: &str) -> String {
+        path.split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
 }
 
 /// Collection of all predefined test scenarios
@@ -93,6 +405,8 @@ impl ScenarioLibrary {
                 friendly_name: "".to_string(),
                 manufacturer: "".to_string(),
                 model: "".to_string(),
+                storage: None,
+                battery_level: None,
             },
             MockFileSystem::new(),
             ExpectedResults {
@@ -276,6 +590,35 @@ impl ScenarioLibrary {
         .with_tags(vec!["device", "android", "samsung", "basic"])
     }
 
+    /// Scenario: Android phone with internal storage and an SD card
+    pub fn android_with_sd_card() -> TestScenario {
+        let device = DeviceInfo::new(
+            "\\\\?\\usb#vid_04e8&pid_6860#samsung_sd_serial",
+            "Galaxy A54",
+            "Samsung",
+            "SM-A546B",
+        );
+
+        let mut fs = MockFileSystem::new();
+        fs.add_android_dcim_structure(20);
+        fs.add_sd_card_structure(10);
+
+        TestScenario::new(
+            "android_with_sd_card",
+            "Android phone with photos on both internal storage and an SD card",
+            device,
+            fs,
+            ExpectedResults {
+                files_to_extract: 30,
+                folders: 6, // Internal Storage, SD Card, and DCIM/Camera on each
+                total_size: 30 * 1024 * 1024,
+                should_succeed: true,
+                ..Default::default()
+            },
+        )
+        .with_tags(vec!["device", "android", "samsung", "sd-card"])
+    }
+
     /// Scenario: Google Pixel phone
     pub fn google_pixel() -> TestScenario {
         let device = DeviceInfo::new(
@@ -1121,6 +1464,72 @@ impl ScenarioLibrary {
         .with_tags(vec!["error", "flaky", "random-failure"])
     }
 
+    /// Scenario: One file fails twice before reading successfully
+    pub fn transient_read_failure() -> TestScenario {
+        let device = DeviceInfo::new(
+            "\\\\?\\usb#vid_05ac&pid_12a8#transient_failure",
+            "Hiccuping iPhone",
+            "Apple Inc.",
+            "iPhone 15",
+        );
+
+        let mut fs = MockFileSystem::with_config(MockDeviceConfig::default().with_fault(
+            MockFault::FailReads {
+                object_id: "img_000004".to_string(),
+                times: 2,
+            },
+        ));
+        Self::add_standard_dcim_structure(&mut fs, 10, 1);
+
+        TestScenario::new(
+            "transient_read_failure",
+            "A file read fails twice with a transient error, then succeeds on retry",
+            device,
+            fs,
+            ExpectedResults {
+                files_to_extract: 10,
+                folders: 3,
+                should_succeed: true,
+                errors: 0,
+                ..Default::default()
+            },
+        )
+        .with_tags(vec!["error", "retry", "fault-injection"])
+    }
+
+    /// Scenario: One file fails on every read attempt
+    pub fn permanent_read_failure() -> TestScenario {
+        let device = DeviceInfo::new(
+            "\\\\?\\usb#vid_05ac&pid_12a8#permanent_failure",
+            "Damaged iPhone",
+            "Apple Inc.",
+            "iPhone 15",
+        );
+
+        let mut fs = MockFileSystem::with_config(MockDeviceConfig::default().with_fault(
+            MockFault::FailReads {
+                object_id: "img_000004".to_string(),
+                times: usize::MAX,
+            },
+        ));
+        Self::add_standard_dcim_structure(&mut fs, 10, 1);
+
+        TestScenario::new(
+            "permanent_read_failure",
+            "A file read keeps failing after every retry",
+            device,
+            fs,
+            ExpectedResults {
+                files_to_extract: 9,
+                folders: 3,
+                should_succeed: true,
+                errors: 1,
+                ..Default::default()
+            },
+        )
+        .with_tags(vec!["error", "retry", "fault-injection"])
+    }
+
     // =========================================================================
     // DUPLICATE DETECTION SCENARIOS
     // =========================================================================
@@ -1628,6 +2037,7 @@ impl ScenarioLibrary {
             Self::google_pixel(),
             Self::android_full_structure(),
             Self::oneplus_phone(),
+            Self::android_with_sd_card(),
             // File structure
             Self::empty_device(),
             Self::deeply_nested(),
@@ -1642,6 +2052,8 @@ impl ScenarioLibrary {
             Self::file_read_errors(),
             Self::slow_transfer(),
             Self::flaky_connection(),
+            Self::transient_read_failure(),
+            Self::permanent_read_failure(),
             // Duplicates
             Self::exact_duplicates(),
             Self::renamed_duplicates(),
@@ -1672,6 +2084,25 @@ impl ScenarioLibrary {
             .collect()
     }
 
+    /// Get scenarios carrying any of `tags`
+    pub fn scenarios_tagged(tags: &[&str]) -> Vec<TestScenario> {
+        Self::all_scenarios()
+            .into_iter()
+            .filter(|s| s.has_any_tag(tags))
+            .collect()
+    }
+
+    /// Get every tag used by a scenario, sorted
+    pub fn tags() -> Vec<String> {
+        let mut tags: Vec<String> = Self::all_scenarios()
+            .into_iter()
+            .flat_map(|s| s.tags)
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
     /// Get quick test scenarios (fast to run)
     pub fn quick_scenarios() -> Vec<TestScenario> {
         vec![
@@ -1692,6 +2123,7 @@ impl ScenarioLibrary {
             Self::google_pixel(),
             Self::android_full_structure(),
             Self::oneplus_phone(),
+            Self::android_with_sd_card(),
             Self::android_with_app_folders(),
             Self::android_whatsapp_only(),
             Self::android_privacy_apps(),
@@ -1713,6 +2145,8 @@ impl ScenarioLibrary {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::device::traits::{DeviceContentTrait, DeviceManagerTrait};
+    use crate::testdb::MockDeviceManager;
 
     #[test]
     fn test_all_scenarios_load() {
@@ -1787,6 +2221,21 @@ mod tests {
         assert!(scenario.device_info.friendly_name.contains("iPhone"));
     }
 
+    #[test]
+    fn test_android_with_sd_card_has_two_storages() {
+        let scenario = ScenarioLibrary::android_with_sd_card();
+        let mut manager = MockDeviceManager::new();
+        let device_id = scenario.device_info.device_id.clone();
+        manager.add_device(scenario.device_info, scenario.file_system);
+
+        let content = manager.open_device(&device_id).unwrap();
+        let storages = content.enumerate_storages().unwrap();
+        let names: Vec<&str> = storages.iter().map(|s| s.description.as_str()).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"Internal Storage"));
+        assert!(names.contains(&"SD Card"));
+    }
+
     #[test]
     fn test_device_locked_scenario() {
         let scenario = ScenarioLibrary::device_locked();
@@ -1971,4 +2420,137 @@ mod tests {
         assert!(scenario_names.contains(&"android_whatsapp_only"));
         assert!(scenario_names.contains(&"android_privacy_apps"));
     }
+
+    #[test]
+    fn test_scenario_builder_creates_tree() {
+        let scenario = ScenarioBuilder::new("builder_tree")
+            .folder("Internal Storage/DCIM/100APPLE")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::sized(1024).modified("2024-01-15T10:00:00Z"),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+                FileSpec::bytes(vec![0xFF, 0xD8, 0xFF, 0xE0]),
+            )
+            .storage(64_000_000_000, 8_000_000_000)
+            .read_error("Internal Storage/DCIM/100APPLE/IMG_0002.JPG")
+            .tags(vec!["regression"])
+            .build();
+
+        assert_eq!(scenario.file_system.folder_count(), 3);
+        assert_eq!(scenario.file_system.file_count(), 2);
+        assert_eq!(scenario.expected.files_to_extract, 1);
+        assert_eq!(scenario.expected.errors, 1);
+        assert_eq!(scenario.expected.total_size, 1028);
+        assert_eq!(
+            scenario.device_info.storage.map(|s| s.free_bytes),
+            Some(8_000_000_000)
+        );
+        assert!(scenario.tags.contains(&"regression".to_string()));
+
+        let dated = scenario
+            .file_system
+            .get_object("Internal Storage/DCIM/100APPLE/IMG_0001.JPG")
+            .unwrap();
+        assert_eq!(
+            dated.object.date_modified.as_deref(),
+            Some("2024-01-15T10:00:00Z")
+        );
+        assert!(scenario
+            .file_system
+            .read_file("Internal Storage/DCIM/100APPLE/IMG_0002.JPG")
+            .is_err());
+    }
+
+    #[test]
+    fn test_scenario_builder_manager_opens_device() {
+        let manager = ScenarioBuilder::new("builder_manager")
+            .file("DCIM/IMG_0001.JPG", FileSpec::sized(256))
+            .battery(42)
+            .build_manager();
+
+        let devices = manager.enumerate_all_devices().unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(manager.battery_level(&devices[0].device_id), Some(42));
+
+        let content = manager.open_device(&devices[0].device_id).unwrap();
+        let root = content.enumerate_objects().unwrap();
+        assert_eq!(root.len(), 1);
+        assert_eq!(root[0].name, "DCIM");
+    }
+
+    #[test]
+    fn test_scenario_builder_runs_extraction() {
+        use crate::core::generic_extractor::{GenericExtractionConfig, GenericExtractor};
+
+        let first = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x01, 0x02];
+        let second = vec![0xFF, 0xD8, 0xFF, 0xE1, 0x03];
+        let content = ScenarioBuilder::new("two_file_dcim")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::bytes(first.clone()),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+                FileSpec::bytes(second.clone()),
+            )
+            .build_content();
+
+        let temp = tempfile::tempdir().unwrap();
+        let config = GenericExtractionConfig {
+            output_dir: temp.path().to_path_buf(),
+            dcim_only: true,
+            preserve_structure: false,
+            skip_existing: false,
+            write_files: true,
+            retry_base_delay_ms: 0,
+            ..Default::default()
+        };
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(stats.errors, 0);
+        assert_eq!(
+            std::fs::read(temp.path().join("IMG_0001.JPG")).unwrap(),
+            first
+        );
+        assert_eq!(
+            std::fs::read(temp.path().join("IMG_0002.JPG")).unwrap(),
+            second
+        );
+    }
+
+    #[test]
+    fn test_extractor_retries_injected_faults() {
+        use crate::core::generic_extractor::{GenericExtractionConfig, GenericExtractor};
+
+        let content = ScenarioBuilder::new("retry_faults")
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+                FileSpec::sized(512),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+                FileSpec::sized(512),
+            )
+            .file(
+                "Internal Storage/DCIM/100APPLE/IMG_0003.JPG",
+                FileSpec::sized(512),
+            )
+            .fail_reads_of("Internal Storage/DCIM/100APPLE/IMG_0001.JPG", 2)
+            .fail_reads_of("Internal Storage/DCIM/100APPLE/IMG_0003.JPG", usize::MAX)
+            .build_content();
+
+        let temp = tempfile::tempdir().unwrap();
+        let config = GenericExtractionConfig::with_output_dir(temp.path()).retries(3, 0);
+        let mut extractor = GenericExtractor::new(config);
+        let stats = extractor.extract_from_content(&content).unwrap();
+
+        // IMG_0001 succeeds on its third attempt; IMG_0003 exhausts every retry
+        assert_eq!(stats.files_extracted, 2);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.retries, 2 + 3);
+    }
 }
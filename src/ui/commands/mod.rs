@@ -12,6 +12,7 @@
 //! 2. **CommandPalette** - The UI state for the palette
 //! 3. **CommandRegistry** - Storage for all available commands
 //! 4. **FuzzyMatcher** - Fast fuzzy search for command filtering
+//! 5. **PaletteSection** - Grouped results for rendering (recent, all, results)
 //!
 //! # Example Usage
 //!
@@ -37,9 +38,13 @@
 #![allow(dead_code)]
 
 use crate::ui::keybindings::{Action, ActionCategory, KeybindingRegistry};
+use log::warn;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 // =============================================================================
 // Command
@@ -66,6 +71,10 @@ pub struct Command {
     /// Keywords for search (in addition to label)
     pub keywords: Vec<String>,
 
+    /// User-defined alternative names, matched as strongly as the label
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
     /// Icon/emoji for display
     pub icon: String,
 
@@ -79,9 +88,16 @@ pub struct Command {
     #[serde(default)]
     pub use_count: u32,
 
-    /// Last time this command was used
-    #[serde(skip)]
-    pub last_used: Option<Instant>,
+    /// Last time this command was used, in seconds since the Unix epoch
+    #[serde(default)]
+    pub last_used: Option<u64>,
+}
+
+/// Current wall-clock time in seconds since the Unix epoch
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
 
 impl Command {
@@ -93,6 +109,7 @@ impl Command {
             description: None,
             category: action.category(),
             keywords: Vec::new(),
+            aliases: Vec::new(),
             icon: action.icon().to_string(),
             enabled: true,
             keybinding: None,
@@ -110,6 +127,7 @@ impl Command {
             description: None,
             category: action.category(),
             keywords: Vec::new(),
+            aliases: Vec::new(),
             icon: action.icon().to_string(),
             enabled: true,
             keybinding: None,
@@ -137,6 +155,28 @@ impl Command {
         self
     }
 
+    /// Set aliases for search
+    pub fn aliases(mut self, aliases: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.aliases = Vec::new();
+        for alias in aliases {
+            self.add_alias(alias);
+        }
+        self
+    }
+
+    /// Add an alias, ignoring blanks and case-insensitive duplicates
+    ///
+    /// Returns `true` if the alias was added.
+    pub fn add_alias(&mut self, alias: impl Into<String>) -> bool {
+        let alias = alias.into().trim().to_string();
+        if alias.is_empty() || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(&alias)) {
+            return false;
+        }
+
+        self.aliases.push(alias);
+        true
+    }
+
     /// Set the icon
     pub fn icon(mut self, icon: impl Into<String>) -> Self {
         self.icon = icon.into();
@@ -158,7 +198,7 @@ impl Command {
     /// Record that this command was used
     pub fn record_use(&mut self) {
         self.use_count += 1;
-        self.last_used = Some(Instant::now());
+        self.last_used = Some(unix_now());
     }
 
     /// Calculate frecency score (frequency + recency)
@@ -167,7 +207,8 @@ impl Command {
 
         let recency_score = match self.last_used {
             Some(last) => {
-                let seconds_ago = last.elapsed().as_secs_f64();
+                // Wall-clock time, so usage from earlier sessions still decays
+                let seconds_ago = unix_now().saturating_sub(last) as f64;
                 // Decay over time: score halves every hour
                 (-seconds_ago / 3600.0).exp()
             }
@@ -201,6 +242,7 @@ impl Default for Command {
             action: Action::None,
             category: ActionCategory::None,
             keywords: Vec::new(),
+            aliases: Vec::new(),
             icon: String::new(),
             enabled: true,
             keybinding: None,
@@ -214,6 +256,22 @@ impl Default for Command {
 // CommandMatch
 // =============================================================================
 
+/// Which part of a command a query matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchField {
+    /// The label
+    #[default]
+    Label,
+    /// The alias at this index; aliases aren't displayed, so nothing is highlighted
+    Alias(usize),
+    /// The keyword at this index
+    Keyword(usize),
+    /// The description
+    Description,
+    /// Label, keywords and description joined, as in [`Command::searchable_text`]
+    SearchableText,
+}
+
 /// A command match with its score
 #[derive(Debug, Clone)]
 pub struct CommandMatch {
@@ -223,17 +281,39 @@ pub struct CommandMatch {
     /// Match score (higher is better)
     pub score: f64,
 
-    /// Indices of matched characters in the label
+    /// Indices of matched characters in the text of `matched_field`
     pub matched_indices: Vec<usize>,
+
+    /// Which field `matched_indices` refer to
+    pub matched_field: MatchField,
 }
 
 impl CommandMatch {
-    /// Create a new command match
+    /// Create a new command match on the label
     pub fn new(command: Command, score: f64, matched_indices: Vec<usize>) -> Self {
         Self {
             command,
             score,
             matched_indices,
+            matched_field: MatchField::Label,
+        }
+    }
+
+    /// Set the field the matched indices refer to
+    pub fn in_field(mut self, field: MatchField) -> Self {
+        self.matched_field = field;
+        self
+    }
+
+    /// Text of the matched field, which `matched_indices` index by character
+    pub fn matched_text(&self) -> Cow<'_, str> {
+        let command = &self.command;
+        match self.matched_field {
+            MatchField::Label => Cow::Borrowed(&command.label),
+            MatchField::Alias(i) => Cow::Borrowed(command.aliases.get(i).map_or("", |a| a)),
+            MatchField::Keyword(i) => Cow::Borrowed(command.keywords.get(i).map_or("", |k| k)),
+            MatchField::Description => Cow::Borrowed(command.description.as_deref().unwrap_or("")),
+            MatchField::SearchableText => Cow::Owned(command.searchable_text()),
         }
     }
 
@@ -244,6 +324,74 @@ impl CommandMatch {
     }
 }
 
+// =============================================================================
+// PaletteSection
+// =============================================================================
+
+/// A titled group of results in the command palette
+#[derive(Debug, Clone)]
+pub struct PaletteSection {
+    /// Section heading (e.g., "Recent", "All Commands", "Results")
+    pub title: String,
+
+    /// Matches in display order
+    pub matches: Vec<CommandMatch>,
+}
+
+impl PaletteSection {
+    /// Create a new section
+    pub fn new(title: impl Into<String>, matches: Vec<CommandMatch>) -> Self {
+        Self {
+            title: title.into(),
+            matches,
+        }
+    }
+
+    /// Split the matches into consecutive runs of the same category
+    ///
+    /// The "All Commands" section is ordered by category, so this yields one
+    /// group per category for rendering sub-headings.
+    pub fn by_category(&self) -> Vec<(ActionCategory, Vec<&CommandMatch>)> {
+        let mut groups: Vec<(ActionCategory, Vec<&CommandMatch>)> = Vec::new();
+
+        for m in &self.matches {
+            match groups.last_mut() {
+                Some((category, group)) if *category == m.command.category => group.push(m),
+                _ => groups.push((m.command.category, vec![m])),
+            }
+        }
+
+        groups
+    }
+
+    /// Check if the section has no matches
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+}
+
+/// Persisted command usage (see [`CommandRegistry::serialize_usage`])
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CommandUsage {
+    /// Use count by command ID
+    #[serde(default)]
+    use_counts: HashMap<String, u32>,
+
+    /// Last use by command ID, in seconds since the Unix epoch
+    #[serde(default)]
+    last_used: HashMap<String, u64>,
+
+    /// Recently used command IDs, newest first
+    #[serde(default)]
+    recent: Vec<String>,
+}
+
+/// How many recently used command IDs the registry remembers
+const RECENT_HISTORY_LEN: usize = 20;
+
+/// File name of the saved command usage, next to the UI settings
+pub const COMMAND_USAGE_FILE: &str = "command_usage.json";
+
 // =============================================================================
 // FuzzyMatcher
 // =============================================================================
@@ -359,18 +507,65 @@ impl FuzzyMatcher {
             return None;
         }
 
-        // Try matching against label first (highest priority)
-        if let Some((score, indices)) = self.match_text(query, &command.label) {
-            return Some(CommandMatch::new(command.clone(), score * 2.0, indices).with_frecency());
-        }
-
-        // Try matching against searchable text (includes keywords)
-        if let Some((score, indices)) = self.match_text(query, &command.searchable_text()) {
-            // Indices won't match label exactly, but still useful for scoring
-            return Some(CommandMatch::new(command.clone(), score, indices).with_frecency());
+        // Label and aliases carry the same (highest) weight; only label
+        // matches have indices that can be highlighted
+        let label_match = self.match_text(query, &command.label);
+        let alias_match =
+            Self::best(command.aliases.iter().enumerate().filter_map(|(i, alias)| {
+                self.match_text(query, alias)
+                    .map(|(score, _)| (score, MatchField::Alias(i), Vec::new()))
+            }));
+
+        let best = match (label_match, alias_match) {
+            (Some((label_score, _)), Some(alias_match)) if alias_match.0 > label_score => {
+                Some(alias_match)
+            }
+            (Some((score, indices)), _) => Some((score, MatchField::Label, indices)),
+            (None, alias_match) => alias_match,
+        };
+        if let Some((score, field, indices)) = best {
+            return Some(
+                CommandMatch::new(command.clone(), score * 2.0, indices)
+                    .in_field(field)
+                    .with_frecency(),
+            );
         }
 
-        None
+        // Then keywords and the description, each on its own so the indices
+        // can be highlighted in that text
+        let keywords = command
+            .keywords
+            .iter()
+            .enumerate()
+            .map(|(i, keyword)| (MatchField::Keyword(i), keyword.as_str()));
+        let description = command
+            .description
+            .as_deref()
+            .map(|desc| (MatchField::Description, desc));
+        let best = Self::best(keywords.chain(description).filter_map(|(field, text)| {
+            self.match_text(query, text)
+                .map(|(score, indices)| (score, field, indices))
+        }))
+        // A query can also span fields, e.g. a label word and a keyword
+        .or_else(|| {
+            self.match_text(query, &command.searchable_text())
+                .map(|(score, indices)| (score, MatchField::SearchableText, indices))
+        });
+        best.map(|(score, field, indices)| {
+            CommandMatch::new(command.clone(), score, indices)
+                .in_field(field)
+                .with_frecency()
+        })
+    }
+
+    /// Highest-scoring match, keeping the first on ties
+    fn best(
+        matches: impl Iterator<Item = (f64, MatchField, Vec<usize>)>,
+    ) -> Option<(f64, MatchField, Vec<usize>)> {
+        matches.fold(None, |best, candidate| match best {
+            Some(best) if best.0 >= candidate.0 => Some(best),
+            _ => Some(candidate),
+        })
     }
 }
 
@@ -389,6 +584,9 @@ pub struct CommandRegistry {
 
     /// Index by action
     by_action: HashMap<String, usize>,
+
+    /// Recently used command IDs, newest first and without duplicates
+    recent: Vec<String>,
 }
 
 impl CommandRegistry {
@@ -398,6 +596,7 @@ impl CommandRegistry {
             commands: Vec::new(),
             by_id: HashMap::new(),
             by_action: HashMap::new(),
+            recent: Vec::new(),
         }
     }
 
@@ -489,9 +688,80 @@ impl CommandRegistry {
     pub fn record_use(&mut self, id: &str) {
         if let Some(command) = self.get_mut(id) {
             command.record_use();
+
+            self.recent.retain(|r| r != id);
+            self.recent.insert(0, id.to_string());
+            self.recent.truncate(RECENT_HISTORY_LEN);
         }
     }
 
+    /// Recently used commands, newest first
+    ///
+    /// Each command appears once; disabled commands are skipped.
+    pub fn recent(&self, limit: usize) -> Vec<&Command> {
+        self.recent
+            .iter()
+            .filter_map(|id| self.get(id))
+            .filter(|c| c.enabled)
+            .take(limit)
+            .collect()
+    }
+
+    /// Serialize use counts, last use times and recent history to JSON for persistence
+    pub fn serialize_usage(&self) -> String {
+        let used = || self.commands.iter().filter(|c| c.use_count > 0);
+        let usage = CommandUsage {
+            use_counts: used().map(|c| (c.id.clone(), c.use_count)).collect(),
+            last_used: used()
+                .filter_map(|c| c.last_used.map(|at| (c.id.clone(), at)))
+                .collect(),
+            recent: self.recent.clone(),
+        };
+
+        serde_json::to_string(&usage).unwrap_or_default()
+    }
+
+    /// Restore usage serialized with [`serialize_usage`](Self::serialize_usage)
+    ///
+    /// IDs that no longer match a registered command are ignored.
+    pub fn load_usage(&mut self, data: &str) -> serde_json::Result<()> {
+        let usage: CommandUsage = serde_json::from_str(data)?;
+
+        for command in &mut self.commands {
+            command.use_count = usage.use_counts.get(&command.id).copied().unwrap_or(0);
+            command.last_used = usage.last_used.get(&command.id).copied();
+        }
+
+        self.recent = usage
+            .recent
+            .into_iter()
+            .filter(|id| self.by_id.contains_key(id))
+            .take(RECENT_HISTORY_LEN)
+            .collect();
+
+        Ok(())
+    }
+
+    /// Write usage to `path`, creating its parent folder if needed
+    pub fn save_usage_file(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.serialize_usage())
+    }
+
+    /// Restore usage saved with [`save_usage_file`](Self::save_usage_file)
+    ///
+    /// A missing file leaves usage untouched, as on first launch.
+    pub fn load_usage_file(&mut self, path: &Path) -> io::Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let data = std::fs::read_to_string(path)?;
+        self.load_usage(&data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
     /// Get number of commands
     pub fn len(&self) -> usize {
         self.commands.len()
@@ -502,6 +772,25 @@ impl CommandRegistry {
         self.commands.is_empty()
     }
 
+    /// Add a search alias to a command
+    ///
+    /// Returns `false` if the command does not exist or already has the alias.
+    pub fn add_alias(&mut self, command_id: &str, alias: impl Into<String>) -> bool {
+        self.get_mut(command_id)
+            .is_some_and(|command| command.add_alias(alias))
+    }
+
+    /// Remove a search alias from a command (case-insensitive)
+    pub fn remove_alias(&mut self, command_id: &str, alias: &str) -> bool {
+        let Some(command) = self.get_mut(command_id) else {
+            return false;
+        };
+
+        let before = command.aliases.len();
+        command.aliases.retain(|a| !a.eq_ignore_ascii_case(alias));
+        command.aliases.len() != before
+    }
+
     /// Enable/disable a command
     pub fn set_enabled(&mut self, id: &str, enabled: bool) {
         if let Some(command) = self.get_mut(id) {
@@ -546,6 +835,15 @@ pub struct CommandPalette {
 
     /// Placeholder text
     placeholder: String,
+
+    /// Maximum number of commands in the "Recent" section
+    max_recent: usize,
+
+    /// Where usage is saved (None = not saved)
+    usage_path: Option<PathBuf>,
+
+    /// Whether a command has been executed since usage was last saved
+    usage_dirty: bool,
 }
 
 impl CommandPalette {
@@ -565,7 +863,43 @@ impl CommandPalette {
             show_categories: true,
             show_keybindings: true,
             placeholder: "Type a command...".to_string(),
+            max_recent: 5,
+            usage_path: None,
+            usage_dirty: false,
+        }
+    }
+
+    /// Create a command palette whose usage persists in `path`
+    ///
+    /// Usage saved there by an earlier session is loaded now. New usage is
+    /// only written by [`save_usage`](Self::save_usage), which the app calls
+    /// once on exit, so a dropped clone never overwrites newer usage.
+    pub fn with_usage_file(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let mut palette = Self::new();
+        if let Err(e) = palette.registry.load_usage_file(&path) {
+            warn!("Ignoring command usage in '{}': {}", path.display(), e);
         }
+        palette.usage_path = Some(path);
+        palette
+    }
+
+    /// Save usage to the usage file, if there is one and anything changed
+    pub fn save_usage(&mut self) {
+        if !self.usage_dirty {
+            return;
+        }
+        if let Some(ref path) = self.usage_path {
+            if let Err(e) = self.registry.save_usage_file(path) {
+                warn!(
+                    "Failed to save command usage to '{}': {}",
+                    path.display(),
+                    e
+                );
+                return;
+            }
+        }
+        self.usage_dirty = false;
     }
 
     /// Create a command palette with a custom registry
@@ -672,6 +1006,47 @@ impl CommandPalette {
         &self.matches
     }
 
+    /// Get the results grouped into sections for display
+    ///
+    /// With an empty query this is a "Recent" section (omitted when nothing
+    /// has been used yet) followed by "All Commands" ordered by category.
+    /// Otherwise it is a single ranked "Results" section.
+    pub fn sections(&self) -> Vec<PaletteSection> {
+        if !self.query.is_empty() {
+            return vec![PaletteSection::new("Results", self.matches.clone())];
+        }
+
+        let mut sections = Vec::new();
+
+        let recent: Vec<CommandMatch> = self
+            .registry
+            .recent(self.max_recent)
+            .into_iter()
+            .map(|cmd| CommandMatch::new(cmd.clone(), cmd.frecency_score(), Vec::new()))
+            .collect();
+        if !recent.is_empty() {
+            sections.push(PaletteSection::new("Recent", recent));
+        }
+
+        let category_order =
+            |category: ActionCategory| ActionCategory::all().iter().position(|c| *c == category);
+        let mut all: Vec<CommandMatch> = self
+            .registry
+            .enabled()
+            .into_iter()
+            .map(|cmd| CommandMatch::new(cmd.clone(), cmd.frecency_score(), Vec::new()))
+            .collect();
+        all.sort_by_key(|m| category_order(m.command.category));
+        sections.push(PaletteSection::new("All Commands", all));
+
+        sections
+    }
+
+    /// Set the maximum number of commands in the "Recent" section
+    pub fn set_max_recent(&mut self, max: usize) {
+        self.max_recent = max;
+    }
+
     /// Get the currently selected match
     pub fn selected(&self) -> Option<&CommandMatch> {
         self.matches.get(self.selected_index)
@@ -725,6 +1100,7 @@ impl CommandPalette {
 
         // Record usage
         self.registry.record_use(&selected.command.id);
+        self.usage_dirty = true;
 
         // Close palette
         self.close();
@@ -736,6 +1112,7 @@ impl CommandPalette {
     pub fn execute(&mut self, id: &str) -> Option<Action> {
         let command = self.registry.get(id)?.clone();
         self.registry.record_use(id);
+        self.usage_dirty = true;
         self.close();
         Some(command.action)
     }
@@ -1071,4 +1448,235 @@ mod tests {
         assert_eq!(command.id, deserialized.id);
         assert_eq!(command.label, deserialized.label);
     }
+
+    #[test]
+    fn test_palette_sections_recent() {
+        let mut palette = CommandPalette::new();
+
+        palette.execute("quit");
+        palette.execute("startextraction");
+        palette.execute("toggletheme");
+        palette.execute("startextraction");
+
+        palette.open();
+        let sections = palette.sections();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].title, "Recent");
+        assert_eq!(sections[1].title, "All Commands");
+
+        let recent: Vec<&str> = sections[0]
+            .matches
+            .iter()
+            .map(|m| m.command.id.as_str())
+            .collect();
+        assert_eq!(recent, vec!["startextraction", "toggletheme", "quit"]);
+
+        palette.set_max_recent(2);
+        assert_eq!(palette.sections()[0].matches.len(), 2);
+    }
+
+    #[test]
+    fn test_palette_sections_all_commands_by_category() {
+        let mut palette = CommandPalette::new();
+        palette.open();
+
+        // Nothing used yet, so there is no Recent section
+        let sections = palette.sections();
+        assert_eq!(sections.len(), 1);
+
+        let all = &sections[0];
+        assert_eq!(all.matches.len(), palette.registry().enabled().len());
+
+        let groups = all.by_category();
+        let categories: Vec<ActionCategory> = groups.iter().map(|(c, _)| *c).collect();
+        let mut unique = categories.clone();
+        unique.dedup();
+        assert_eq!(categories, unique, "each category should form one group");
+        assert!(groups
+            .iter()
+            .all(|(c, group)| group.iter().all(|m| m.command.category == *c)));
+    }
+
+    #[test]
+    fn test_palette_sections_results() {
+        let mut palette = CommandPalette::new();
+        palette.execute("quit");
+        palette.open();
+        palette.set_query("extract");
+
+        let sections = palette.sections();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].title, "Results");
+        assert_eq!(sections[0].matches.len(), palette.results_count());
+    }
+
+    #[test]
+    fn test_command_usage_persistence() {
+        let mut registry = CommandRegistry::new();
+        registry.register_from_actions();
+        registry.record_use("quit");
+        registry.record_use("togglesettings_missing");
+        registry.record_use("startextraction");
+        registry.record_use("startextraction");
+
+        let saved = registry.serialize_usage();
+
+        let mut restored = CommandRegistry::new();
+        restored.register_from_actions();
+        restored.load_usage(&saved).unwrap();
+
+        assert_eq!(restored.get("startextraction").unwrap().use_count, 2);
+        assert_eq!(restored.get("quit").unwrap().use_count, 1);
+        let recent: Vec<&str> = restored.recent(10).iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(recent, vec!["startextraction", "quit"]);
+
+        assert!(restored.load_usage("not json").is_err());
+    }
+
+    #[test]
+    fn test_command_alias_ranks_first() {
+        let mut registry = CommandRegistry::new();
+        registry.register_from_actions();
+
+        assert!(registry.add_alias("startextraction", "rip"));
+        assert!(registry.add_alias("startextraction", "download"));
+        assert!(!registry.add_alias("startextraction", "RIP"));
+        assert!(!registry.add_alias("no_such_command", "rip"));
+
+        let results = registry.search("rip");
+        assert_eq!(results[0].command.action, Action::StartExtraction);
+
+        let results = registry.search("download");
+        assert_eq!(results[0].command.action, Action::StartExtraction);
+    }
+
+    #[test]
+    fn test_command_alias_weighted_like_label() {
+        let matcher = FuzzyMatcher::new();
+        let labelled = Command::new("a", "Rip", Action::None);
+        let aliased = Command::new("b", "Something else", Action::None).aliases(["Rip"]);
+
+        let by_label = matcher.match_command("rip", &labelled).unwrap();
+        let by_alias = matcher.match_command("rip", &aliased).unwrap();
+        assert_eq!(by_label.score, by_alias.score);
+        assert!(by_alias.matched_indices.is_empty());
+    }
+
+    #[test]
+    fn test_command_aliases_persist() {
+        let mut registry = CommandRegistry::new();
+        registry.register(Command::from_action(Action::StartExtraction));
+        registry.add_alias("startextraction", "rip");
+
+        let json = serde_json::to_string(registry.get("startextraction").unwrap()).unwrap();
+        let restored: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.aliases, vec!["rip".to_string()]);
+
+        assert!(registry.remove_alias("startextraction", "RIP"));
+        assert!(registry.get("startextraction").unwrap().aliases.is_empty());
+    }
+
+    #[test]
+    fn test_command_match_by_keyword_reports_keyword_indices() {
+        let matcher = FuzzyMatcher::new();
+        let command = Command::new("export", "Save Report", Action::None)
+            .keywords(["csv", "spreadsheet"])
+            .description("Write the results to disk");
+
+        let matched = matcher.match_command("sheet", &command).unwrap();
+        assert_eq!(matched.matched_field, MatchField::Keyword(1));
+        assert_eq!(matched.matched_text(), "spreadsheet");
+        let text: Vec<char> = matched.matched_text().chars().collect();
+        let highlighted: String = matched.matched_indices.iter().map(|&i| text[i]).collect();
+        assert_eq!(highlighted, "sheet");
+
+        let matched = matcher.match_command("disk", &command).unwrap();
+        assert_eq!(matched.matched_field, MatchField::Description);
+        let text: Vec<char> = matched.matched_text().chars().collect();
+        assert!(matched.matched_indices.iter().all(|&i| i < text.len()));
+
+        let matched = matcher.match_command("report", &command).unwrap();
+        assert_eq!(matched.matched_field, MatchField::Label);
+        assert_eq!(matched.matched_text(), "Save Report");
+    }
+
+    #[test]
+    fn test_command_frecency_survives_restart() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("ui").join(COMMAND_USAGE_FILE);
+
+        let mut palette = CommandPalette::with_usage_file(&path);
+        palette.execute("quit");
+        palette.execute("startextraction");
+        palette.execute("startextraction");
+        // Used often, but a day ago
+        let registry = palette.registry_mut();
+        for _ in 0..3 {
+            registry.record_use("opensettings");
+        }
+        registry.get_mut("opensettings").unwrap().last_used = Some(unix_now() - 86_400);
+        registry.save_usage_file(&path).unwrap();
+
+        let by_frecency = |registry: &CommandRegistry| {
+            let mut used: Vec<&Command> =
+                registry.all().iter().filter(|c| c.use_count > 0).collect();
+            used.sort_by(|a, b| b.frecency_score().total_cmp(&a.frecency_score()));
+            used.iter().map(|c| c.id.clone()).collect::<Vec<_>>()
+        };
+        let before = by_frecency(palette.registry());
+        assert_eq!(before, vec!["startextraction", "quit", "opensettings"]);
+
+        let reopened = CommandPalette::with_usage_file(&path);
+        assert_eq!(by_frecency(reopened.registry()), before);
+        let restored = reopened.registry().get("opensettings").unwrap();
+        assert_eq!(restored.use_count, 3);
+        assert!(unix_now() - restored.last_used.unwrap() >= 86_400);
+
+        // Nothing saved yet is not an error
+        let mut fresh = CommandRegistry::new();
+        fresh.register_from_actions();
+        fresh
+            .load_usage_file(&temp_dir.path().join("missing.json"))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_command_usage_saved_on_save_usage() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(COMMAND_USAGE_FILE);
+
+        let mut palette = CommandPalette::with_usage_file(&path);
+        palette.execute("quit");
+        palette.execute("startextraction");
+        assert!(!path.exists());
+
+        palette.save_usage();
+        let reopened = CommandPalette::with_usage_file(&path);
+        assert_eq!(reopened.registry().get("quit").unwrap().use_count, 1);
+        assert_eq!(
+            reopened
+                .registry()
+                .get("startextraction")
+                .unwrap()
+                .use_count,
+            1
+        );
+    }
+
+    #[test]
+    fn test_dropped_clone_keeps_newer_usage() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(COMMAND_USAGE_FILE);
+
+        let mut palette = CommandPalette::with_usage_file(&path);
+        palette.execute("quit");
+        let stale = palette.clone();
+
+        palette.execute("quit");
+        palette.save_usage();
+        drop(stale);
+
+        let reopened = CommandPalette::with_usage_file(&path);
+        assert_eq!(reopened.registry().get("quit").unwrap().use_count, 2);
+    }
 }
@@ -20,9 +20,17 @@
 //! - [`InputState`] - Text input fields
 //! - [`ListState`] / [`ListItem`] - List/tree views
 //! - [`ProgressState`] - Progress indicators
+//! - [`TabsState`] / [`Tab`] - Tab bars
+//! - [`SliderState`] - Numeric range sliders
+//! - [`ModalState`] / [`DialogButton`] - Modal dialogs
+//! - [`NotificationQueue`] / [`Toast`] - Toast notifications
 
+use crate::ui::commands::FuzzyMatcher;
+use crate::ui::keybindings::KeyCode;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
 
 /// Unique identifier for widgets
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -240,6 +248,19 @@ pub struct FocusManager {
 
     /// Whether to show focus indicators
     show_focus_ring: bool,
+
+    /// Active focus traps, innermost last
+    traps: Vec<FocusTrap>,
+}
+
+/// Widgets that hold focus exclusively (e.g. an open modal)
+#[derive(Debug, Clone)]
+struct FocusTrap {
+    /// Focusable widgets inside the trap
+    order: Vec<WidgetId>,
+
+    /// Focus to restore when the trap is released
+    restore: Option<WidgetId>,
 }
 
 impl FocusManager {
@@ -252,6 +273,7 @@ impl FocusManager {
             max_history: 10,
             wrap_focus: true,
             show_focus_ring: true,
+            traps: Vec::new(),
         }
     }
 
@@ -280,10 +302,22 @@ impl FocusManager {
             self.focused = None;
         }
         self.focus_history.retain(|w| w != id);
+        for trap in &mut self.traps {
+            trap.order.retain(|w| w != id);
+            if trap.restore.as_ref() == Some(id) {
+                trap.restore = None;
+            }
+        }
     }
 
     /// Set focus to a specific widget
+    ///
+    /// Ignored while focus is trapped and `id` is outside the trap.
     pub fn set_focus(&mut self, id: WidgetId) {
+        if !self.in_trap(&id) {
+            return;
+        }
+
         // Save current focus to history
         if let Some(current) = self.focused.take() {
             if current != id {
@@ -318,21 +352,27 @@ impl FocusManager {
     }
 
     /// Move focus in a direction
+    ///
+    /// While focus is trapped, only widgets inside the trap are visited.
     pub fn move_focus(&mut self, direction: FocusDirection) -> Option<&WidgetId> {
-        if self.focus_order.is_empty() {
+        let order = match self.traps.last() {
+            Some(trap) => &trap.order,
+            None => &self.focus_order,
+        };
+        if order.is_empty() {
             return None;
         }
 
         let current_idx = self
             .focused
             .as_ref()
-            .and_then(|f| self.focus_order.iter().position(|w| w == f));
+            .and_then(|f| order.iter().position(|w| w == f));
 
         let new_idx = match direction {
             FocusDirection::Next | FocusDirection::Down | FocusDirection::Right => {
                 match current_idx {
                     Some(idx) => {
-                        if idx + 1 < self.focus_order.len() {
+                        if idx + 1 < order.len() {
                             Some(idx + 1)
                         } else if self.wrap_focus {
                             Some(0)
@@ -349,20 +389,20 @@ impl FocusManager {
                         if idx > 0 {
                             Some(idx - 1)
                         } else if self.wrap_focus {
-                            Some(self.focus_order.len() - 1)
+                            Some(order.len() - 1)
                         } else {
                             Some(idx)
                         }
                     }
-                    None => Some(self.focus_order.len() - 1),
+                    None => Some(order.len() - 1),
                 }
             }
             FocusDirection::First => Some(0),
-            FocusDirection::Last => Some(self.focus_order.len() - 1),
+            FocusDirection::Last => Some(order.len() - 1),
         };
 
         if let Some(idx) = new_idx {
-            let new_id = self.focus_order[idx].clone();
+            let new_id = order[idx].clone();
             self.set_focus(new_id);
         }
 
@@ -371,12 +411,50 @@ impl FocusManager {
 
     /// Go back to previously focused widget
     pub fn focus_back(&mut self) -> Option<&WidgetId> {
-        if let Some(prev) = self.focus_history.pop() {
-            self.focused = Some(prev);
+        if self
+            .focus_history
+            .last()
+            .is_some_and(|prev| self.in_trap(prev))
+        {
+            self.focused = self.focus_history.pop();
+        }
+        self.focused.as_ref()
+    }
+
+    /// Trap focus inside `ids` until [`FocusManager::release_trap`] is called
+    ///
+    /// Focus moves to the first of `ids`, and navigation stays among them.
+    /// Traps nest, so a modal opened from another modal works as expected.
+    pub fn trap_focus(&mut self, ids: Vec<WidgetId>) {
+        let restore = self.focused.clone();
+        self.traps.push(FocusTrap {
+            order: ids.clone(),
+            restore,
+        });
+        if let Some(first) = ids.into_iter().next() {
+            self.set_focus(first);
+        }
+    }
+
+    /// Release the innermost focus trap, restoring the focus it replaced
+    pub fn release_trap(&mut self) -> Option<&WidgetId> {
+        if let Some(trap) = self.traps.pop() {
+            self.focus_history.retain(|w| !trap.order.contains(w));
+            self.focused = trap.restore;
         }
         self.focused.as_ref()
     }
 
+    /// Check if focus is currently trapped
+    pub fn is_trapped(&self) -> bool {
+        !self.traps.is_empty()
+    }
+
+    /// Check if `id` may receive focus under the current trap (if any)
+    fn in_trap(&self, id: &WidgetId) -> bool {
+        self.traps.last().is_none_or(|trap| trap.order.contains(id))
+    }
+
     /// Set whether focus wraps around
     pub fn set_wrap_focus(&mut self, wrap: bool) {
         self.wrap_focus = wrap;
@@ -641,6 +719,62 @@ pub enum InputType {
     TextArea,
 }
 
+/// Maximum number of undo steps kept per input
+pub const MAX_INPUT_HISTORY: usize = 100;
+
+/// Window within which consecutive single-character inserts share an undo step
+pub const DEFAULT_COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Value and cursor captured before an edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InputSnapshot {
+    value: String,
+    cursor_position: usize,
+}
+
+/// Bounded undo/redo history for an [`InputState`]
+#[derive(Debug, Clone)]
+struct EditHistory {
+    undo: VecDeque<InputSnapshot>,
+    redo: Vec<InputSnapshot>,
+    /// Time and cursor position after the last single-character insert
+    last_insert: Option<(Instant, usize)>,
+    coalesce_window: Duration,
+}
+
+impl EditHistory {
+    /// Record the state from before an edit, merging it into the previous
+    /// step when it continues a run of typed characters
+    fn record(&mut self, before: InputSnapshot, coalescible: bool, cursor_after: usize) {
+        let now = Instant::now();
+        let merge = coalescible
+            && !self.undo.is_empty()
+            && matches!(self.last_insert, Some((at, end))
+                if end == before.cursor_position
+                    && now.duration_since(at) < self.coalesce_window);
+
+        if !merge {
+            self.undo.push_back(before);
+            if self.undo.len() > MAX_INPUT_HISTORY {
+                self.undo.pop_front();
+            }
+        }
+        self.redo.clear();
+        self.last_insert = coalescible.then_some((now, cursor_after));
+    }
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            last_insert: None,
+            coalesce_window: DEFAULT_COALESCE_WINDOW,
+        }
+    }
+}
+
 /// Text input state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InputState {
@@ -694,6 +828,10 @@ pub struct InputState {
 
     /// Whether password is visible (for password type)
     pub password_visible: bool,
+
+    /// Undo/redo history
+    #[serde(skip)]
+    history: EditHistory,
 }
 
 impl InputState {
@@ -717,6 +855,7 @@ impl InputState {
             suffix: None,
             clearable: false,
             password_visible: false,
+            history: EditHistory::default(),
         }
     }
 
@@ -762,67 +901,147 @@ impl InputState {
         self
     }
 
+    /// Set how long consecutive typed characters are merged into one undo step
+    pub fn coalesce_window(mut self, window: Duration) -> Self {
+        self.history.coalesce_window = window;
+        self
+    }
+
     /// Set the current value
     pub fn set_value(&mut self, value: impl Into<String>) {
         let value = value.into();
-        if self.max_length > 0 && value.len() > self.max_length {
-            self.value = value[..self.max_length].to_string();
-        } else {
-            self.value = value;
-        }
-        self.cursor_position = self.value.len();
-        self.clear_selection();
+        self.edit(false, |input| {
+            if input.max_length > 0 && value.len() > input.max_length {
+                input.value = value[..input.max_length].to_string();
+            } else {
+                input.value = value;
+            }
+            input.cursor_position = input.value.len();
+            input.clear_selection();
+        });
     }
 
     /// Insert text at cursor
     pub fn insert(&mut self, text: &str) {
-        // Delete selection if any
-        self.delete_selection();
-
-        let new_value = format!(
-            "{}{}{}",
-            &self.value[..self.cursor_position],
-            text,
-            &self.value[self.cursor_position..]
-        );
+        let coalescible = text.chars().count() == 1 && self.selected_text().is_none();
+        self.edit(coalescible, |input| {
+            // Delete selection if any
+            input.remove_selection();
+
+            let new_value = format!(
+                "{}{}{}",
+                &input.value[..input.cursor_position],
+                text,
+                &input.value[input.cursor_position..]
+            );
 
-        if self.max_length == 0 || new_value.len() <= self.max_length {
-            self.value = new_value;
-            self.cursor_position += text.len();
-        }
+            if input.max_length == 0 || new_value.len() <= input.max_length {
+                input.value = new_value;
+                input.cursor_position += text.len();
+            }
+        });
     }
 
     /// Delete character before cursor
     pub fn backspace(&mut self) {
-        if self.delete_selection() {
-            return;
-        }
+        self.edit(false, |input| {
+            if input.remove_selection() {
+                return;
+            }
 
-        if self.cursor_position > 0 {
-            self.value = format!(
-                "{}{}",
-                &self.value[..self.cursor_position - 1],
-                &self.value[self.cursor_position..]
-            );
-            self.cursor_position -= 1;
-        }
+            if input.cursor_position > 0 {
+                input.value = format!(
+                    "{}{}",
+                    &input.value[..input.cursor_position - 1],
+                    &input.value[input.cursor_position..]
+                );
+                input.cursor_position -= 1;
+            }
+        });
     }
 
     /// Delete character after cursor
     pub fn delete(&mut self) {
-        if self.delete_selection() {
-            return;
+        self.edit(false, |input| {
+            if input.remove_selection() {
+                return;
+            }
+
+            if input.cursor_position < input.value.len() {
+                input.value = format!(
+                    "{}{}",
+                    &input.value[..input.cursor_position],
+                    &input.value[input.cursor_position + 1..]
+                );
+            }
+        });
+    }
+
+    /// Undo the last edit, returns true if there was one
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.history.undo.pop_back() else {
+            return false;
+        };
+        let current = self.snapshot();
+        self.history.redo.push(current);
+        self.restore(snapshot);
+        true
+    }
+
+    /// Redo the last undone edit, returns true if there was one
+    pub fn redo(&mut self) -> bool {
+        let Some(snapshot) = self.history.redo.pop() else {
+            return false;
+        };
+        let current = self.snapshot();
+        self.history.undo.push_back(current);
+        self.restore(snapshot);
+        true
+    }
+
+    /// Check if there is an edit to undo
+    pub fn can_undo(&self) -> bool {
+        !self.history.undo.is_empty()
+    }
+
+    /// Check if there is an edit to redo
+    pub fn can_redo(&self) -> bool {
+        !self.history.redo.is_empty()
+    }
+
+    /// Forget all undo/redo steps, e.g. after loading a saved value
+    pub fn clear_history(&mut self) {
+        let window = self.history.coalesce_window;
+        self.history = EditHistory {
+            coalesce_window: window,
+            ..EditHistory::default()
+        };
+    }
+
+    /// Run a mutating operation, recording an undo step if the value changed
+    fn edit(&mut self, coalescible: bool, op: impl FnOnce(&mut Self)) {
+        let before = self.snapshot();
+        op(self);
+        if self.value != before.value {
+            self.history
+                .record(before, coalescible, self.cursor_position);
         }
+    }
 
-        if self.cursor_position < self.value.len() {
-            self.value = format!(
-                "{}{}",
-                &self.value[..self.cursor_position],
-                &self.value[self.cursor_position + 1..]
-            );
+    fn snapshot(&self) -> InputSnapshot {
+        InputSnapshot {
+            value: self.value.clone(),
+            cursor_position: self.cursor_position,
         }
     }
 
+    fn restore(&mut self, snapshot: InputSnapshot) {
+        self.value = snapshot.value;
+        self.cursor_position = snapshot.cursor_position;
+        self.clear_selection();
+        self.history.last_insert = None;
+    }
+
     /// Move cursor left
     pub fn move_cursor_left(&mut self, select: bool) {
         if self.cursor_position > 0 {
@@ -898,6 +1117,12 @@ impl InputState {
 
     /// Delete selected text, returns true if something was deleted
     pub fn delete_selection(&mut self) -> bool {
+        let mut deleted = false;
+        self.edit(false, |input| deleted = input.remove_selection());
+        deleted
+    }
+
+    fn remove_selection(&mut self) -> bool {
         if let (Some(start), Some(end)) = (self.selection_start, self.selection_end) {
             let (start, end) = if start < end {
                 (start, end)
@@ -929,9 +1154,11 @@ impl InputState {
 
     /// Clear the input
     pub fn clear(&mut self) {
-        self.value.clear();
-        self.cursor_position = 0;
-        self.clear_selection();
+        self.edit(false, |input| {
+            input.value.clear();
+            input.cursor_position = 0;
+            input.clear_selection();
+        });
         self.error = None;
     }
 
@@ -1165,6 +1392,32 @@ pub enum SelectionMode {
     Multiple,
 }
 
+/// How a list filter query matches item labels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ListFilterMode {
+    /// Fuzzy match, results ranked by score
+    #[default]
+    Fuzzy,
+    /// Case-insensitive substring, results in list order (cheaper for large lists)
+    Substring,
+}
+
+/// A list item that matched the filter query
+#[derive(Debug, Clone)]
+pub struct ListMatch<'a> {
+    /// Index of the item in the unfiltered list
+    pub index: usize,
+
+    /// The matching item
+    pub item: &'a ListItem,
+
+    /// Match score (higher is better; 0 for substring matches)
+    pub score: f64,
+
+    /// Character indices in the label to highlight
+    pub matched_indices: Vec<usize>,
+}
+
 /// List/tree state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListState {
@@ -1206,6 +1459,10 @@ pub struct ListState {
 
     /// Filter query
     pub filter_query: String,
+
+    /// How the filter query is matched
+    #[serde(default)]
+    pub filter_mode: ListFilterMode,
 }
 
 impl ListState {
@@ -1225,6 +1482,7 @@ impl ListState {
             show_empty_state: true,
             empty_message: "No items".to_string(),
             filter_query: String::new(),
+            filter_mode: ListFilterMode::default(),
         }
     }
 
@@ -1434,40 +1692,137 @@ impl ListState {
         self.filter_query = query.into();
     }
 
+    /// Set the filter mode
+    pub fn filter_mode(mut self, mode: ListFilterMode) -> Self {
+        self.filter_mode = mode;
+        self
+    }
+
     /// Get filtered items
+    ///
+    /// In fuzzy mode the items are ordered by match score, best first.
     pub fn filtered_items(&self) -> Vec<&ListItem> {
+        self.filtered_matches()
+            .into_iter()
+            .map(|m| m.item)
+            .collect()
+    }
+
+    /// Get filtered items with their scores and highlight indices
+    ///
+    /// An empty query matches every item in list order.
+    pub fn filtered_matches(&self) -> Vec<ListMatch<'_>> {
         if self.filter_query.is_empty() {
-            self.items.iter().collect()
-        } else {
-            let query = self.filter_query.to_lowercase();
-            self.items
+            return self
+                .items
                 .iter()
-                .filter(|item| item.label.to_lowercase().contains(&query))
-                .collect()
+                .enumerate()
+                .map(|(index, item)| ListMatch {
+                    index,
+                    item,
+                    score: 0.0,
+                    matched_indices: Vec::new(),
+                })
+                .collect();
+        }
+
+        match self.filter_mode {
+            ListFilterMode::Fuzzy => {
+                let matcher = FuzzyMatcher::new();
+                let mut matches: Vec<ListMatch<'_>> = self
+                    .items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, item)| {
+                        matcher.match_text(&self.filter_query, &item.label).map(
+                            |(score, matched_indices)| ListMatch {
+                                index,
+                                item,
+                                score,
+                                matched_indices,
+                            },
+                        )
+                    })
+                    .collect();
+
+                // Stable sort keeps list order among equal scores
+                matches.sort_by(|a, b| {
+                    b.score
+                        .partial_cmp(&a.score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                matches
+            }
+            ListFilterMode::Substring => {
+                let query = self.filter_query.to_lowercase();
+                self.items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, item)| {
+                        let label = item.label.to_lowercase();
+                        let start = label.find(&query)?;
+                        let start = label[..start].chars().count();
+                        Some(ListMatch {
+                            index,
+                            item,
+                            score: 0.0,
+                            matched_indices: (start..start + query.chars().count()).collect(),
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Number of rows shown: the filtered count while a filter is active
+    fn row_count(&self) -> usize {
+        if self.filter_query.is_empty() {
+            self.items.len()
+        } else {
+            self.filtered_matches().len()
         }
     }
 
     /// Update visible range for virtualization
+    ///
+    /// The range indexes the rows as shown, i.e. the filtered items while a
+    /// filter is active. Call again after changing the filter.
     pub fn update_visible_range(&mut self, scroll_offset: u32, viewport_height: u32) {
+        let rows = self.row_count();
         if !self.virtualized || self.item_height == 0 {
             self.visible_start = 0;
-            self.visible_end = self.items.len();
+            self.visible_end = rows;
             return;
         }
 
-        self.visible_start = (scroll_offset / self.item_height) as usize;
         self.visible_end = ((scroll_offset + viewport_height) / self.item_height + 1) as usize;
-        self.visible_end = self.visible_end.min(self.items.len());
+        self.visible_end = self.visible_end.min(rows);
+        self.visible_start = ((scroll_offset / self.item_height) as usize).min(self.visible_end);
     }
 
     /// Get visible items (for virtualized rendering)
+    ///
+    /// Slices the unfiltered items; use [`Self::visible_filtered_items`]
+    /// when a filter may be active.
     pub fn visible_items(&self) -> &[ListItem] {
-        &self.items[self.visible_start..self.visible_end.min(self.items.len())]
+        let end = self.visible_end.min(self.items.len());
+        &self.items[self.visible_start.min(end)..end]
+    }
+
+    /// Get the filtered items inside the visible range
+    ///
+    /// Each match carries the item's index in the unfiltered list, so a
+    /// click on a rendered row can be passed straight to [`Self::select`].
+    pub fn visible_filtered_items(&self) -> Vec<ListMatch<'_>> {
+        let matches = self.filtered_matches();
+        let end = self.visible_end.min(matches.len());
+        let start = self.visible_start.min(end);
+        matches.into_iter().skip(start).take(end - start).collect()
     }
 
     /// Get total content height (for scroll calculation)
     pub fn content_height(&self) -> u32 {
-        self.items.len() as u32 * self.item_height
+        self.row_count() as u32 * self.item_height
     }
 
     /// Get number of items
@@ -1847,128 +2202,865 @@ fn format_bytes(bytes: u64) -> String {
 }
 
 // =============================================================================
-// Tests
+// Tab Components
 // =============================================================================
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Single tab in a tab bar
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tab {
+    /// Tab identifier
+    pub id: String,
 
-    // Widget ID tests
-    #[test]
-    fn test_widget_id_new() {
-        let id = WidgetId::new("test");
-        assert_eq!(id.0, "test");
-    }
+    /// Tab label text
+    pub label: String,
 
-    #[test]
-    fn test_widget_id_with_prefix() {
-        let id = WidgetId::with_prefix("btn", 1);
-        assert_eq!(id.0, "btn_1");
+    /// Optional icon name
+    pub icon: Option<String>,
+
+    /// Whether the tab can be closed by the user
+    pub closable: bool,
+}
+
+impl Tab {
+    /// Create a new (non-closable) tab
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            icon: None,
+            closable: false,
+        }
     }
 
-    #[test]
-    fn test_widget_id_from_string() {
-        let id: WidgetId = "test".into();
-        assert_eq!(id.0, "test");
+    /// Set icon
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
     }
 
-    // Widget state tests
-    #[test]
-    fn test_widget_state_new() {
-        let state = WidgetState::new("widget1");
-        assert_eq!(state.id.0, "widget1");
-        assert!(state.enabled);
-        assert!(state.visible);
-        assert!(state.focusable);
+    /// Set whether the tab can be closed
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
     }
+}
 
-    #[test]
-    fn test_widget_state_builder() {
-        let state = WidgetState::new("widget1")
-            .enabled(false)
-            .visible(false)
-            .focusable(false)
-            .tab_order(5);
+/// Tab bar state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabsState {
+    /// Base widget state
+    pub widget: WidgetState,
 
-        assert!(!state.enabled);
-        assert!(!state.visible);
-        assert!(!state.focusable);
-        assert_eq!(state.tab_order, 5);
+    /// Tabs in display order
+    pub tabs: Vec<Tab>,
+
+    /// Index of the active tab (meaningless while there are no tabs)
+    pub active: usize,
+}
+
+impl TabsState {
+    /// Create a new, empty tab bar
+    pub fn new(id: impl Into<WidgetId>) -> Self {
+        Self {
+            widget: WidgetState::new(id),
+            tabs: Vec::new(),
+            active: 0,
+        }
     }
 
-    #[test]
-    fn test_widget_state_focus() {
-        let mut state = WidgetState::new("widget1");
-        assert!(!state.focused);
-        state.set_focused(true);
-        assert!(state.focused);
+    /// Add a tab (builder form of [`TabsState::add_tab`])
+    pub fn tab(mut self, tab: Tab) -> Self {
+        self.add_tab(tab);
+        self
     }
 
-    #[test]
-    fn test_widget_state_custom_data() {
-        let mut state = WidgetState::new("widget1");
-        state.set_custom("key", "value");
-        assert_eq!(state.get_custom("key"), Some("value"));
-        assert_eq!(state.get_custom("missing"), None);
+    /// Append a tab, returning its index
+    ///
+    /// The active tab doesn't change; the first tab added becomes active.
+    pub fn add_tab(&mut self, tab: Tab) -> usize {
+        self.tabs.push(tab);
+        self.tabs.len() - 1
     }
 
-    // Focus manager tests
-    #[test]
-    fn test_focus_manager_new() {
-        let fm = FocusManager::new();
-        assert!(fm.focused().is_none());
-        assert_eq!(fm.focusable_count(), 0);
+    /// Activate the tab at `index`, returning whether it exists
+    pub fn select(&mut self, index: usize) -> bool {
+        if index < self.tabs.len() {
+            self.active = index;
+            true
+        } else {
+            false
+        }
     }
 
-    #[test]
-    fn test_focus_manager_register() {
-        let mut fm = FocusManager::new();
-        fm.register(WidgetId::new("btn1"), 0);
-        fm.register(WidgetId::new("btn2"), 1);
-        assert_eq!(fm.focusable_count(), 2);
+    /// Activate the tab with the given ID, returning whether it exists
+    pub fn select_id(&mut self, id: &str) -> bool {
+        match self.index_of(id) {
+            Some(index) => self.select(index),
+            None => false,
+        }
     }
 
-    #[test]
-    fn test_focus_manager_set_focus() {
-        let mut fm = FocusManager::new();
-        fm.register(WidgetId::new("btn1"), 0);
-        fm.set_focus(WidgetId::new("btn1"));
-        assert!(fm.has_focus(&WidgetId::new("btn1")));
+    /// Activate the next tab, wrapping to the first
+    pub fn next(&mut self) {
+        if !self.tabs.is_empty() {
+            self.active = (self.active + 1) % self.tabs.len();
+        }
     }
 
-    #[test]
-    fn test_focus_manager_move_focus() {
-        let mut fm = FocusManager::new();
-        fm.register(WidgetId::new("btn1"), 0);
-        fm.register(WidgetId::new("btn2"), 1);
-        fm.register(WidgetId::new("btn3"), 2);
+    /// Activate the previous tab, wrapping to the last
+    pub fn previous(&mut self) {
+        if !self.tabs.is_empty() {
+            self.active = (self.active + self.tabs.len() - 1) % self.tabs.len();
+        }
+    }
 
-        fm.set_focus(WidgetId::new("btn1"));
-        fm.move_focus(FocusDirection::Next);
-        assert!(fm.has_focus(&WidgetId::new("btn2")));
+    /// Close the tab at `index`, returning it
+    ///
+    /// Tabs that aren't closable are left open (returns `None`). Closing the
+    /// active tab activates the tab that slides into its place, or the one
+    /// before it when it was the last tab.
+    pub fn close(&mut self, index: usize) -> Option<Tab> {
+        if !self.tabs.get(index)?.closable {
+            return None;
+        }
 
-        fm.move_focus(FocusDirection::Previous);
-        assert!(fm.has_focus(&WidgetId::new("btn1")));
+        let tab = self.tabs.remove(index);
+        if index < self.active || self.active >= self.tabs.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+        Some(tab)
     }
 
-    #[test]
-    fn test_focus_manager_wrap() {
-        let mut fm = FocusManager::new();
-        fm.set_wrap_focus(true);
-        fm.register(WidgetId::new("btn1"), 0);
-        fm.register(WidgetId::new("btn2"), 1);
+    /// Get the active tab
+    pub fn active_tab(&self) -> Option<&Tab> {
+        self.tabs.get(self.active)
+    }
 
-        fm.set_focus(WidgetId::new("btn2"));
-        fm.move_focus(FocusDirection::Next);
-        assert!(fm.has_focus(&WidgetId::new("btn1"))); // Wrapped around
+    /// Find the index of the tab with the given ID
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.id == id)
     }
 
-    #[test]
-    fn test_focus_manager_history() {
-        let mut fm = FocusManager::new();
-        fm.register(WidgetId::new("btn1"), 0);
-        fm.register(WidgetId::new("btn2"), 1);
+    /// Get number of tabs
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    /// Check if there are no tabs
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+}
+
+impl Widget for TabsState {
+    fn id(&self) -> &WidgetId {
+        &self.widget.id
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn is_visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.widget.is_focusable() && !self.tabs.is_empty()
+    }
+
+    fn tab_order(&self) -> i32 {
+        self.widget.tab_order
+    }
+}
+
+impl Default for TabsState {
+    fn default() -> Self {
+        Self::new("tabs")
+    }
+}
+
+// =============================================================================
+// Slider Components
+// =============================================================================
+
+/// Numeric range slider state
+///
+/// Values are always kept within `min..=max` and, when `step` is positive,
+/// on a multiple of `step` from `min`. Settings sliders should be built from
+/// the range the setting validates against, such as
+/// [`AppearanceSettings::UI_SCALE_RANGE`](crate::ui::settings::AppearanceSettings::UI_SCALE_RANGE).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SliderState {
+    /// Base widget state
+    pub widget: WidgetState,
+
+    /// Smallest allowed value
+    pub min: f32,
+
+    /// Largest allowed value
+    pub max: f32,
+
+    /// Increment between allowed values (0.0 = continuous)
+    pub step: f32,
+
+    /// Current value
+    pub value: f32,
+
+    /// Optional label text
+    pub label: Option<String>,
+}
+
+impl SliderState {
+    /// Create a new slider over `range`, starting at its minimum
+    pub fn new(id: impl Into<WidgetId>, range: RangeInclusive<f32>, step: f32) -> Self {
+        let (min, max) = range.into_inner();
+        Self {
+            widget: WidgetState::new(id),
+            min,
+            max,
+            step: step.max(0.0),
+            value: min,
+            label: None,
+        }
+    }
+
+    /// Set the initial value
+    pub fn value(mut self, value: f32) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    /// Set label
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set disabled state
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.widget.enabled = !disabled;
+        self
+    }
+
+    /// Set the value, clamped to the range and snapped to the nearest step
+    ///
+    /// Returns whether the value changed. NaN is ignored.
+    pub fn set_value(&mut self, value: f32) -> bool {
+        if value.is_nan() {
+            return false;
+        }
+
+        let mut value = value.clamp(self.min, self.max);
+        if self.step > 0.0 {
+            let steps = ((value - self.min) / self.step).round();
+            value = (self.min + steps * self.step).clamp(self.min, self.max);
+        }
+
+        let changed = value != self.value;
+        self.value = value;
+        changed
+    }
+
+    /// Move up by one step, returning whether the value changed
+    pub fn increment(&mut self) -> bool {
+        self.set_value(self.value + self.nudge())
+    }
+
+    /// Move down by one step, returning whether the value changed
+    pub fn decrement(&mut self) -> bool {
+        self.set_value(self.value - self.nudge())
+    }
+
+    /// Position of the value within the range (0.0 to 1.0)
+    pub fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            ((self.value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Amount moved by one increment; continuous sliders move by 1% of the range
+    fn nudge(&self) -> f32 {
+        if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / 100.0
+        }
+    }
+}
+
+impl Widget for SliderState {
+    fn id(&self) -> &WidgetId {
+        &self.widget.id
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn is_visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.widget.is_focusable()
+    }
+
+    fn tab_order(&self) -> i32 {
+        self.widget.tab_order
+    }
+}
+
+impl Default for SliderState {
+    fn default() -> Self {
+        Self::new("slider", 0.0..=1.0, 0.0)
+    }
+}
+
+// =============================================================================
+// Modal Components
+// =============================================================================
+
+/// Button shown in a modal dialog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DialogButton {
+    /// Button identifier, returned when the button is chosen
+    pub id: String,
+
+    /// Button label text
+    pub label: String,
+
+    /// Button variant for styling
+    pub variant: ButtonVariant,
+}
+
+impl DialogButton {
+    /// Create a new dialog button
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            variant: ButtonVariant::Default,
+        }
+    }
+
+    /// Set button variant
+    pub fn variant(mut self, variant: ButtonVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+}
+
+/// Modal dialog state
+///
+/// While open, focus is trapped on the dialog's buttons. Escape chooses the
+/// cancel button and Enter chooses the focused button, which is the default
+/// button when the dialog opens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModalState {
+    /// Base widget state
+    pub widget: WidgetState,
+
+    /// Dialog title
+    pub title: String,
+
+    /// Dialog message
+    pub message: String,
+
+    /// Buttons in display order
+    pub buttons: Vec<DialogButton>,
+
+    /// Button chosen by Enter
+    pub default_button: Option<String>,
+
+    /// Button chosen by Escape
+    pub cancel_button: Option<String>,
+
+    /// Whether the dialog is showing
+    pub is_open: bool,
+
+    /// Button chosen when the dialog was last closed
+    pub result: Option<String>,
+}
+
+impl ModalState {
+    /// Create a new, closed dialog without buttons
+    pub fn new(
+        id: impl Into<WidgetId>,
+        title: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            widget: WidgetState::new(id),
+            title: title.into(),
+            message: message.into(),
+            buttons: Vec::new(),
+            default_button: None,
+            cancel_button: None,
+            is_open: false,
+            result: None,
+        }
+    }
+
+    /// Create a confirmation dialog with "confirm" and "cancel" buttons
+    pub fn confirmation(
+        id: impl Into<WidgetId>,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        confirm_label: impl Into<String>,
+    ) -> Self {
+        Self::new(id, title, message)
+            .button(DialogButton::new("cancel", "Cancel"))
+            .button(DialogButton::new("confirm", confirm_label).variant(ButtonVariant::Primary))
+            .default_button("confirm")
+            .cancel_button("cancel")
+    }
+
+    /// Add a button
+    pub fn button(mut self, button: DialogButton) -> Self {
+        self.buttons.push(button);
+        self
+    }
+
+    /// Set the button chosen by Enter
+    pub fn default_button(mut self, id: impl Into<String>) -> Self {
+        self.default_button = Some(id.into());
+        self
+    }
+
+    /// Set the button chosen by Escape
+    pub fn cancel_button(mut self, id: impl Into<String>) -> Self {
+        self.cancel_button = Some(id.into());
+        self
+    }
+
+    /// Widget ID used for focusing a button
+    pub fn button_widget_id(&self, button_id: &str) -> WidgetId {
+        WidgetId::with_prefix(&self.widget.id.0, button_id)
+    }
+
+    /// Show the dialog, trapping focus on its buttons
+    pub fn open(&mut self, focus: &mut FocusManager) {
+        if self.is_open {
+            return;
+        }
+
+        self.is_open = true;
+        self.result = None;
+        focus.trap_focus(
+            self.buttons
+                .iter()
+                .map(|b| self.button_widget_id(&b.id))
+                .collect(),
+        );
+        if let Some(default) = self.default_button.as_deref() {
+            focus.set_focus(self.button_widget_id(default));
+        }
+    }
+
+    /// Close the dialog by choosing `button_id`, returning it
+    ///
+    /// Returns `None` (and stays open) if the dialog isn't open or has no
+    /// such button.
+    pub fn close(&mut self, focus: &mut FocusManager, button_id: &str) -> Option<String> {
+        if !self.is_open || !self.buttons.iter().any(|b| b.id == button_id) {
+            return None;
+        }
+        self.finish(focus, Some(button_id.to_string()))
+    }
+
+    /// Choose the default button
+    pub fn confirm(&mut self, focus: &mut FocusManager) -> Option<String> {
+        let default = self.default_button.clone()?;
+        self.close(focus, &default)
+    }
+
+    /// Dismiss the dialog, returning the cancel button (if there is one)
+    pub fn cancel(&mut self, focus: &mut FocusManager) -> Option<String> {
+        if !self.is_open {
+            return None;
+        }
+        let cancel = self
+            .cancel_button
+            .clone()
+            .filter(|id| self.buttons.iter().any(|b| b.id == *id));
+        self.finish(focus, cancel)
+    }
+
+    /// Handle a key press, returning the chosen button if the dialog closed
+    pub fn handle_key(&mut self, key: KeyCode, focus: &mut FocusManager) -> Option<String> {
+        match key {
+            KeyCode::Escape => self.cancel(focus),
+            KeyCode::Enter | KeyCode::NumpadEnter => {
+                let focused = self
+                    .buttons
+                    .iter()
+                    .find(|b| focus.has_focus(&self.button_widget_id(&b.id)))
+                    .map(|b| b.id.clone());
+                match focused {
+                    Some(id) => self.close(focus, &id),
+                    None => self.confirm(focus),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn finish(&mut self, focus: &mut FocusManager, chosen: Option<String>) -> Option<String> {
+        self.is_open = false;
+        self.result = chosen.clone();
+        focus.release_trap();
+        chosen
+    }
+}
+
+impl Widget for ModalState {
+    fn id(&self) -> &WidgetId {
+        &self.widget.id
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn is_visible(&self) -> bool {
+        self.widget.visible && self.is_open
+    }
+
+    fn is_focusable(&self) -> bool {
+        false // Focus goes to the dialog's buttons
+    }
+}
+
+impl Default for ModalState {
+    fn default() -> Self {
+        Self::new("modal", "", "")
+    }
+}
+
+// =============================================================================
+// Notification Components
+// =============================================================================
+
+/// How long a toast stays visible by default
+pub const DEFAULT_TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Number of toasts shown at once by default
+pub const DEFAULT_MAX_VISIBLE_TOASTS: usize = 3;
+
+/// Toast severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ToastSeverity {
+    /// Informational message
+    #[default]
+    Info,
+    /// Operation succeeded
+    Success,
+    /// Something needs attention
+    Warning,
+    /// Operation failed
+    Error,
+}
+
+/// A transient notification
+#[derive(Debug, Clone)]
+pub struct Toast {
+    /// Identifier assigned by the queue
+    pub id: u64,
+
+    /// Toast severity
+    pub severity: ToastSeverity,
+
+    /// Message text
+    pub message: String,
+
+    /// When the toast was created
+    pub created: Instant,
+
+    /// How long the toast stays visible (`None` = until dismissed)
+    pub duration: Option<Duration>,
+
+    /// When the toast became visible
+    shown_at: Option<Instant>,
+}
+
+impl Toast {
+    /// Create a new toast with the default duration
+    pub fn new(severity: ToastSeverity, message: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            severity,
+            message: message.into(),
+            created: Instant::now(),
+            duration: Some(DEFAULT_TOAST_DURATION),
+            shown_at: None,
+        }
+    }
+
+    /// Set how long the toast stays visible
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Keep the toast until it is dismissed
+    pub fn sticky(mut self) -> Self {
+        self.duration = None;
+        self
+    }
+
+    /// Set the creation time
+    pub fn created_at(mut self, created: Instant) -> Self {
+        self.created = created;
+        self
+    }
+
+    /// When the toast auto-dismisses, if it is visible and not sticky
+    pub fn expires_at(&self) -> Option<Instant> {
+        Some(self.shown_at? + self.duration?)
+    }
+}
+
+/// Queue of toasts, showing at most `max_visible` at a time
+///
+/// Toasts beyond the cap wait in order, and their duration only starts
+/// counting once they become visible.
+#[derive(Debug, Clone)]
+pub struct NotificationQueue {
+    visible: Vec<Toast>,
+    pending: VecDeque<Toast>,
+    max_visible: usize,
+    enabled: bool,
+    next_id: u64,
+}
+
+impl NotificationQueue {
+    /// Create an empty queue showing at most `max_visible` toasts
+    pub fn new(max_visible: usize) -> Self {
+        Self {
+            visible: Vec::new(),
+            pending: VecDeque::new(),
+            max_visible: max_visible.max(1),
+            enabled: true,
+            next_id: 1,
+        }
+    }
+
+    /// Set whether toasts are shown (see `BehaviorSettings::show_notifications`)
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.set_enabled(enabled);
+        self
+    }
+
+    /// Enable or disable toasts; disabling drops any that are queued
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.clear();
+        }
+    }
+
+    /// Add a toast, returning its ID (`None` while notifications are disabled)
+    pub fn push(&mut self, mut toast: Toast) -> Option<u64> {
+        if !self.enabled {
+            return None;
+        }
+
+        toast.id = self.next_id;
+        self.next_id += 1;
+        if self.visible.len() < self.max_visible && self.pending.is_empty() {
+            toast.shown_at = Some(toast.created);
+            self.visible.push(toast);
+        } else {
+            self.pending.push_back(toast);
+        }
+        Some(self.next_id - 1)
+    }
+
+    /// Remove a toast, returning whether it was queued
+    pub fn dismiss(&mut self, id: u64) -> bool {
+        if let Some(pos) = self.visible.iter().position(|t| t.id == id) {
+            self.visible.remove(pos);
+            true
+        } else if let Some(pos) = self.pending.iter().position(|t| t.id == id) {
+            self.pending.remove(pos);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove expired toasts and return the ones to show, oldest first
+    pub fn tick(&mut self, now: Instant) -> &[Toast] {
+        self.visible
+            .retain(|toast| toast.expires_at().is_none_or(|expiry| expiry > now));
+
+        while self.visible.len() < self.max_visible {
+            let Some(mut toast) = self.pending.pop_front() else {
+                break;
+            };
+            toast.shown_at = Some(now);
+            self.visible.push(toast);
+        }
+
+        &self.visible
+    }
+
+    /// Toasts currently shown
+    pub fn visible(&self) -> &[Toast] {
+        &self.visible
+    }
+
+    /// Number of toasts waiting for a free slot
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check if there are no toasts
+    pub fn is_empty(&self) -> bool {
+        self.visible.is_empty() && self.pending.is_empty()
+    }
+
+    /// Remove all toasts
+    pub fn clear(&mut self) {
+        self.visible.clear();
+        self.pending.clear();
+    }
+}
+
+impl Default for NotificationQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_VISIBLE_TOASTS)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::settings::{AppearanceSettings, BehaviorSettings};
+
+    // Widget ID tests
+    #[test]
+    fn test_widget_id_new() {
+        let id = WidgetId::new("test");
+        assert_eq!(id.0, "test");
+    }
+
+    #[test]
+    fn test_widget_id_with_prefix() {
+        let id = WidgetId::with_prefix("btn", 1);
+        assert_eq!(id.0, "btn_1");
+    }
+
+    #[test]
+    fn test_widget_id_from_string() {
+        let id: WidgetId = "test".into();
+        assert_eq!(id.0, "test");
+    }
+
+    // Widget state tests
+    #[test]
+    fn test_widget_state_new() {
+        let state = WidgetState::new("widget1");
+        assert_eq!(state.id.0, "widget1");
+        assert!(state.enabled);
+        assert!(state.visible);
+        assert!(state.focusable);
+    }
+
+    #[test]
+    fn test_widget_state_builder() {
+        let state = WidgetState::new("widget1")
+            .enabled(false)
+            .visible(false)
+            .focusable(false)
+            .tab_order(5);
+
+        assert!(!state.enabled);
+        assert!(!state.visible);
+        assert!(!state.focusable);
+        assert_eq!(state.tab_order, 5);
+    }
+
+    #[test]
+    fn test_widget_state_focus() {
+        let mut state = WidgetState::new("widget1");
+        assert!(!state.focused);
+        state.set_focused(true);
+        assert!(state.focused);
+    }
+
+    #[test]
+    fn test_widget_state_custom_data() {
+        let mut state = WidgetState::new("widget1");
+        state.set_custom("key", "value");
+        assert_eq!(state.get_custom("key"), Some("value"));
+        assert_eq!(state.get_custom("missing"), None);
+    }
+
+    // Focus manager tests
+    #[test]
+    fn test_focus_manager_new() {
+        let fm = FocusManager::new();
+        assert!(fm.focused().is_none());
+        assert_eq!(fm.focusable_count(), 0);
+    }
+
+    #[test]
+    fn test_focus_manager_register() {
+        let mut fm = FocusManager::new();
+        fm.register(WidgetId::new("btn1"), 0);
+        fm.register(WidgetId::new("btn2"), 1);
+        assert_eq!(fm.focusable_count(), 2);
+    }
+
+    #[test]
+    fn test_focus_manager_set_focus() {
+        let mut fm = FocusManager::new();
+        fm.register(WidgetId::new("btn1"), 0);
+        fm.set_focus(WidgetId::new("btn1"));
+        assert!(fm.has_focus(&WidgetId::new("btn1")));
+    }
+
+    #[test]
+    fn test_focus_manager_move_focus() {
+        let mut fm = FocusManager::new();
+        fm.register(WidgetId::new("btn1"), 0);
+        fm.register(WidgetId::new("btn2"), 1);
+        fm.register(WidgetId::new("btn3"), 2);
+
+        fm.set_focus(WidgetId::new("btn1"));
+        fm.move_focus(FocusDirection::Next);
+        assert!(fm.has_focus(&WidgetId::new("btn2")));
+
+        fm.move_focus(FocusDirection::Previous);
+        assert!(fm.has_focus(&WidgetId::new("btn1")));
+    }
+
+    #[test]
+    fn test_focus_manager_wrap() {
+        let mut fm = FocusManager::new();
+        fm.set_wrap_focus(true);
+        fm.register(WidgetId::new("btn1"), 0);
+        fm.register(WidgetId::new("btn2"), 1);
+
+        fm.set_focus(WidgetId::new("btn2"));
+        fm.move_focus(FocusDirection::Next);
+        assert!(fm.has_focus(&WidgetId::new("btn1"))); // Wrapped around
+    }
+
+    #[test]
+    fn test_focus_manager_history() {
+        let mut fm = FocusManager::new();
+        fm.register(WidgetId::new("btn1"), 0);
+        fm.register(WidgetId::new("btn2"), 1);
 
         fm.set_focus(WidgetId::new("btn1"));
         fm.set_focus(WidgetId::new("btn2"));
@@ -2153,6 +3245,54 @@ mod tests {
         assert_eq!(filtered.len(), 2);
     }
 
+    #[test]
+    fn test_list_state_fuzzy_filter() {
+        let mut list = ListState::new("folders");
+        list.add_item(ListItem::new("1", "DCIM 100 APPLE"));
+        list.add_item(ListItem::new("2", "DCIM 102 APPLE"));
+        list.add_item(ListItem::new("3", "Downloads"));
+
+        list.set_filter("dcim102");
+        let matches = list.filtered_matches();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].item.label, "DCIM 102 APPLE");
+        assert_eq!(matches[0].matched_indices, vec![0, 1, 2, 3, 5, 6, 7]);
+
+        // Substring mode needs the exact run of characters
+        list.filter_mode = ListFilterMode::Substring;
+        assert!(list.filtered_items().is_empty());
+        list.set_filter("102 app");
+        let matches = list.filtered_matches();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].matched_indices, (5..12).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_list_state_fuzzy_filter_is_ranked() {
+        let mut list = ListState::new("files");
+        list.add_item(ListItem::new("1", "my_photo_backup.zip"));
+        list.add_item(ListItem::new("2", "Photos"));
+        list.add_item(ListItem::new("3", "phone_export_old"));
+
+        list.set_filter("pho");
+        let matches = list.filtered_matches();
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[0].item.label, "Photos");
+        assert!(matches.windows(2).all(|w| w[0].score >= w[1].score));
+
+        // An empty query keeps list order
+        list.set_filter("");
+        let labels: Vec<&str> = list
+            .filtered_items()
+            .iter()
+            .map(|i| i.label.as_str())
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["my_photo_backup.zip", "Photos", "phone_export_old"]
+        );
+    }
+
     #[test]
     fn test_list_item_builder() {
         let item = ListItem::new("id1", "Test Item")
@@ -2304,4 +3444,321 @@ mod tests {
         assert_eq!(deserialized.label, "Test");
         assert_eq!(deserialized.description, Some("Description".to_string()));
     }
+
+    #[test]
+    fn test_input_state_undo_redo() {
+        let mut input = InputState::new("input1").coalesce_window(Duration::ZERO);
+        for c in "hello".chars() {
+            input.insert(&c.to_string());
+        }
+        assert_eq!(input.value, "hello");
+
+        assert!(input.undo());
+        assert!(input.undo());
+        assert_eq!(input.value, "hel");
+        assert_eq!(input.cursor_position, 3);
+        assert!(input.can_redo());
+
+        assert!(input.redo());
+        assert!(input.redo());
+        assert_eq!(input.value, "hello");
+        assert_eq!(input.cursor_position, 5);
+        assert!(!input.redo());
+
+        // A new edit discards the redo branch
+        input.undo();
+        input.backspace();
+        assert_eq!(input.value, "hel");
+        assert!(!input.can_redo());
+    }
+
+    #[test]
+    fn test_input_state_undo_coalesces_typing() {
+        let mut input = InputState::new("input1");
+        input.set_value("say ");
+        for c in "hello".chars() {
+            input.insert(&c.to_string());
+        }
+
+        assert!(input.undo());
+        assert_eq!(input.value, "say ");
+        assert!(input.undo());
+        assert_eq!(input.value, "");
+        assert!(!input.undo());
+    }
+
+    #[test]
+    fn test_input_state_history_is_bounded() {
+        let mut input = InputState::new("input1");
+        for i in 0..MAX_INPUT_HISTORY + 10 {
+            input.set_value(i.to_string());
+        }
+
+        let mut steps = 0;
+        while input.undo() {
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_INPUT_HISTORY);
+        assert_eq!(input.value, "9");
+    }
+
+    fn settings_tabs() -> TabsState {
+        TabsState::new("settings_tabs")
+            .tab(Tab::new("general", "General").icon("gear"))
+            .tab(Tab::new("devices", "Devices").closable(true))
+            .tab(Tab::new("advanced", "Advanced").closable(true))
+    }
+
+    #[test]
+    fn test_tabs_state_selection_wraps() {
+        let mut tabs = settings_tabs();
+        assert_eq!(tabs.active_tab().unwrap().id, "general");
+
+        tabs.previous();
+        assert_eq!(tabs.active_tab().unwrap().id, "advanced");
+        tabs.next();
+        assert_eq!(tabs.active_tab().unwrap().id, "general");
+
+        assert!(tabs.select_id("devices"));
+        assert_eq!(tabs.active, 1);
+        assert!(!tabs.select(3));
+        assert_eq!(tabs.active, 1);
+    }
+
+    #[test]
+    fn test_tabs_state_close_active_moves_to_neighbor() {
+        let mut tabs = settings_tabs();
+
+        // The tab after the closed one takes its place
+        tabs.select(1);
+        assert_eq!(tabs.close(1).unwrap().id, "devices");
+        assert_eq!(tabs.active_tab().unwrap().id, "advanced");
+
+        // Closing the last tab falls back to the one before it
+        assert_eq!(tabs.close(1).unwrap().id, "advanced");
+        assert_eq!(tabs.active_tab().unwrap().id, "general");
+
+        // Closing a tab before the active one keeps the same tab active
+        let mut tabs = settings_tabs();
+        tabs.select(2);
+        tabs.close(1);
+        assert_eq!(tabs.active_tab().unwrap().id, "advanced");
+    }
+
+    #[test]
+    fn test_tabs_state_non_closable_tab_stays_open() {
+        let mut tabs = settings_tabs();
+        assert!(tabs.close(0).is_none());
+        assert!(tabs.close(7).is_none());
+        assert_eq!(tabs.len(), 3);
+        assert_eq!(tabs.active, 0);
+    }
+
+    #[test]
+    fn test_tabs_state_serialization() {
+        let mut tabs = settings_tabs();
+        tabs.select(2);
+
+        let json = serde_json::to_string(&tabs).unwrap();
+        let deserialized: TabsState = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.tabs, tabs.tabs);
+        assert_eq!(deserialized.active, 2);
+        assert_eq!(deserialized.tabs[0].icon.as_deref(), Some("gear"));
+    }
+
+    #[test]
+    fn test_slider_clamps_above_max() {
+        let mut slider = SliderState::new("ui_scale", AppearanceSettings::UI_SCALE_RANGE, 0.25);
+
+        assert!(slider.set_value(10.0));
+        assert_eq!(slider.value, 3.0);
+        assert!(!slider.increment());
+        assert_eq!(slider.value, 3.0);
+
+        slider.set_value(-1.0);
+        assert_eq!(slider.value, 0.5);
+    }
+
+    #[test]
+    fn test_slider_snaps_to_step() {
+        let mut slider = SliderState::new("ui_scale", AppearanceSettings::UI_SCALE_RANGE, 0.25);
+
+        slider.set_value(1.1);
+        assert_eq!(slider.value, 1.0);
+        slider.set_value(1.2);
+        assert_eq!(slider.value, 1.25);
+        slider.increment();
+        assert_eq!(slider.value, 1.5);
+        slider.decrement();
+        slider.decrement();
+        assert_eq!(slider.value, 1.0);
+    }
+
+    #[test]
+    fn test_slider_fraction_at_midpoint() {
+        let slider =
+            SliderState::new("sound_volume", BehaviorSettings::SOUND_VOLUME_RANGE, 0.1).value(0.5);
+        assert!((slider.fraction() - 0.5).abs() < 1e-6);
+
+        let scale =
+            SliderState::new("ui_scale", AppearanceSettings::UI_SCALE_RANGE, 0.25).value(1.75);
+        assert_eq!(scale.fraction(), 0.5);
+
+        let json = serde_json::to_string(&scale).unwrap();
+        let parsed: SliderState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.value, 1.75);
+    }
+
+    #[test]
+    fn test_modal_escape_yields_cancel_id() {
+        let mut focus = FocusManager::new();
+        let mut modal = ModalState::confirmation(
+            "confirm_extraction",
+            "Start extraction?",
+            "Photos will be copied to the output folder.",
+            "Extract",
+        );
+
+        modal.open(&mut focus);
+        assert!(modal.is_open);
+        assert!(focus.has_focus(&modal.button_widget_id("confirm")));
+
+        assert_eq!(
+            modal.handle_key(KeyCode::Escape, &mut focus),
+            Some("cancel".to_string())
+        );
+        assert!(!modal.is_open);
+        assert_eq!(modal.result.as_deref(), Some("cancel"));
+
+        modal.open(&mut focus);
+        assert_eq!(
+            modal.handle_key(KeyCode::Enter, &mut focus),
+            Some("confirm".to_string())
+        );
+    }
+
+    #[test]
+    fn test_modal_traps_and_restores_focus() {
+        let mut focus = FocusManager::new();
+        focus.register(WidgetId::new("device_list"), 0);
+        focus.register(WidgetId::new("extract_button"), 1);
+        focus.set_focus(WidgetId::new("extract_button"));
+
+        let mut modal = ModalState::confirmation("confirm", "Cancel?", "", "Stop");
+        modal.open(&mut focus);
+        assert!(focus.is_trapped());
+
+        // Navigation and direct focus stay on the dialog's buttons
+        focus.move_focus(FocusDirection::Next);
+        assert!(focus.has_focus(&modal.button_widget_id("cancel")));
+        focus.set_focus(WidgetId::new("device_list"));
+        assert!(focus.has_focus(&modal.button_widget_id("cancel")));
+
+        // Enter picks the focused button, not the default
+        assert_eq!(
+            modal.handle_key(KeyCode::Enter, &mut focus),
+            Some("cancel".to_string())
+        );
+        assert!(!focus.is_trapped());
+        assert!(focus.has_focus(&WidgetId::new("extract_button")));
+
+        focus.move_focus(FocusDirection::Previous);
+        assert!(focus.has_focus(&WidgetId::new("device_list")));
+    }
+
+    #[test]
+    fn test_notification_queue_expires_toasts() {
+        let start = Instant::now();
+        let mut queue = NotificationQueue::new(5);
+        for (ms, severity) in [
+            (10, ToastSeverity::Info),
+            (20, ToastSeverity::Success),
+            (30, ToastSeverity::Error),
+        ] {
+            let toast = Toast::new(severity, format!("{} ms", ms))
+                .created_at(start)
+                .duration(Duration::from_millis(ms));
+            assert!(queue.push(toast).is_some());
+        }
+
+        assert_eq!(queue.tick(start).len(), 3);
+        let visible = queue.tick(start + Duration::from_millis(15));
+        assert_eq!(visible.len(), 2);
+        assert_eq!(visible[0].severity, ToastSeverity::Success);
+        assert!(queue.tick(start + Duration::from_millis(31)).is_empty());
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_notification_queue_caps_visible_toasts() {
+        let start = Instant::now();
+        let mut queue = NotificationQueue::new(2);
+        let ids: Vec<u64> = (1..=3)
+            .map(|n| {
+                let toast = Toast::new(ToastSeverity::Info, format!("toast {}", n))
+                    .created_at(start)
+                    .duration(Duration::from_millis(10));
+                queue.push(toast).unwrap()
+            })
+            .collect();
+
+        let visible: Vec<u64> = queue.tick(start).iter().map(|t| t.id).collect();
+        assert_eq!(visible, vec![ids[0], ids[1]]);
+        assert_eq!(queue.pending_len(), 1);
+
+        // The overflow toast is shown once a slot frees, with a fresh timer
+        assert!(queue.dismiss(ids[0]));
+        let later = start + Duration::from_millis(5);
+        assert_eq!(queue.tick(later).len(), 2);
+        let visible = queue.tick(start + Duration::from_millis(12));
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].id, ids[2]);
+        assert!(queue.tick(later + Duration::from_millis(10)).is_empty());
+
+        let mut disabled = NotificationQueue::default().enabled(false);
+        assert_eq!(
+            disabled.push(Toast::new(ToastSeverity::Warning, "hidden")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_list_state_virtualizes_filtered_items() {
+        let mut list = ListState::new("files").virtualized(true);
+        list.filter_mode = ListFilterMode::Substring;
+        list.add_items((0..100).map(|i| {
+            let ext = if i % 2 == 0 { "JPG" } else { "MOV" };
+            ListItem::new(i.to_string(), format!("IMG_{:04}.{}", i, ext))
+        }));
+
+        list.set_filter("jpg");
+        assert_eq!(list.content_height(), 50 * list.item_height);
+
+        // Scroll past the first 10 filtered rows with room for 4 more
+        list.update_visible_range(10 * list.item_height, 3 * list.item_height);
+        let visible = list.visible_filtered_items();
+        let labels: Vec<&str> = visible.iter().map(|m| m.item.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "IMG_0020.JPG",
+                "IMG_0022.JPG",
+                "IMG_0024.JPG",
+                "IMG_0026.JPG"
+            ]
+        );
+        let indices: Vec<usize> = visible.iter().map(|m| m.index).collect();
+        assert_eq!(indices, vec![20, 22, 24, 26]);
+
+        list.select(visible[1].index);
+        assert_eq!(list.selected_ids(), vec!["22"]);
+
+        // Scrolled beyond the end of the filtered rows
+        list.update_visible_range(80 * list.item_height, 3 * list.item_height);
+        assert!(list.visible_filtered_items().is_empty());
+
+        list.set_filter("");
+        assert_eq!(list.content_height(), 100 * list.item_height);
+    }
 }
@@ -4,21 +4,31 @@
 //! extraction operations. This controller handles background extraction, cancellation,
 //! pause/resume, and communicates with the UI through channels.
 //!
+//! Events reach the UI through a bounded queue of [`EVENT_CHANNEL_CAPACITY`]
+//! events. Progress reports are coalesced so a UI that stops polling never
+//! builds up a backlog of them; every other event is kept, and the worker
+//! waits for the UI to catch up if those alone fill the queue.
+//!
 //! No iTunes installation or additional drivers are required on Windows 10/11.
 
+use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
-use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
+use crate::core::checkpoint::{Checkpoint, DEFAULT_CHECKPOINT_INTERVAL};
+use crate::core::discovery::DEFAULT_SCAN_THREADS;
 use crate::core::error::{ExtractionError, Result};
 use crate::core::generic_extractor::{
-    ExtractionPhase, GenericExtractionConfig, GenericExtractor, ProgressUpdate,
+    ExtractionPhase, ExtractionStats, FileOutcome, GenericExtractionConfig, GenericExtractor,
+    ProgressSink, ProgressUpdate,
 };
 use crate::device::{DeviceContentTrait, DeviceInfo, DeviceManagerTrait};
 use crate::duplicate::{DuplicateConfig, DuplicateIndex};
+use crate::ui::components::{ProgressState, ProgressStatus};
 use crate::ui::events::{AppEvent, ExtractionEvent, ExtractionSummary, PauseReason, UiEvent};
 
 // =============================================================================
@@ -287,6 +297,121 @@ pub struct ProgressSnapshot {
     pub elapsed: Duration,
 }
 
+// =============================================================================
+// Event Channel
+// =============================================================================
+
+/// Events the UI may fall behind by before the worker waits for it
+///
+/// Progress events are coalesced and don't count towards this, so it only
+/// bounds events that are delivered one by one: per-file results, state
+/// changes and log lines. 1024 of those is a few seconds of a fast
+/// extraction of small files and well under a megabyte of memory.
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Bounded event queue between the extraction worker and the UI
+///
+/// A coalescable event replaces an older undelivered one of the same kind,
+/// so at most one of each is ever queued. Any other event is always
+/// delivered: while the queue is full, sending it blocks until the UI
+/// receives something or the channel is [released](Self::release).
+/// Events raised by the controller itself, often on the UI thread, are
+/// [posted](Self::post) instead and never wait.
+struct EventChannel {
+    queue: Mutex<EventQueue>,
+    capacity: usize,
+    /// Signalled when an event is queued
+    ready: Condvar,
+    /// Signalled when an event is received or the channel is released
+    space: Condvar,
+}
+
+struct EventQueue {
+    events: VecDeque<UiEvent>,
+    /// Once set, senders no longer wait for space
+    released: bool,
+}
+
+impl EventChannel {
+    fn new(capacity: usize) -> Self {
+        Self {
+            queue: Mutex::new(EventQueue {
+                events: VecDeque::new(),
+                released: false,
+            }),
+            capacity,
+            ready: Condvar::new(),
+            space: Condvar::new(),
+        }
+    }
+
+    /// Queue an event, coalescing progress and waiting for space otherwise
+    fn send(&self, event: UiEvent) {
+        let mut queue = self.queue.lock().unwrap();
+        if event.is_coalescable() {
+            queue.events.retain(|queued| !event.replaces(queued));
+        } else {
+            while !queue.released && queue.blocking_len() >= self.capacity {
+                queue = self.space.wait(queue).unwrap();
+            }
+        }
+        queue.events.push_back(event);
+        self.ready.notify_one();
+    }
+
+    /// Queue an event without waiting for space
+    ///
+    /// The queue may go over capacity by these few events; that beats
+    /// blocking the UI thread on its own queue.
+    fn post(&self, event: UiEvent) {
+        let mut queue = self.queue.lock().unwrap();
+        if event.is_coalescable() {
+            queue.events.retain(|queued| !event.replaces(queued));
+        }
+        queue.events.push_back(event);
+        self.ready.notify_one();
+    }
+
+    fn try_recv(&self) -> Option<UiEvent> {
+        let event = self.queue.lock().unwrap().events.pop_front();
+        if event.is_some() {
+            self.space.notify_all();
+        }
+        event
+    }
+
+    fn recv_timeout(&self, timeout: Duration) -> Option<UiEvent> {
+        let queue = self.queue.lock().unwrap();
+        let (mut queue, _) = self
+            .ready
+            .wait_timeout_while(queue, timeout, |queue| queue.events.is_empty())
+            .unwrap();
+        let event = queue.events.pop_front();
+        if event.is_some() {
+            self.space.notify_all();
+        }
+        event
+    }
+
+    fn len(&self) -> usize {
+        self.queue.lock().unwrap().events.len()
+    }
+
+    /// Stop making senders wait, e.g. when the UI is going away and won't
+    /// drain the queue
+    fn release(&self) {
+        self.queue.lock().unwrap().released = true;
+        self.space.notify_all();
+    }
+}
+
+impl EventQueue {
+    /// Queued events that count towards the capacity
+    fn blocking_len(&self) -> usize {
+        self.events.iter().filter(|e| !e.is_coalescable()).count()
+    }
+}
+
 // =============================================================================
 // Extraction Controller
 // =============================================================================
@@ -311,10 +436,8 @@ pub struct ExtractionController {
     progress: Arc<ProgressTracker>,
     /// Command sender
     _command_tx: Sender<ControllerCommand>,
-    /// Event receiver for UI
-    event_rx: Mutex<Receiver<UiEvent>>,
-    /// Event sender (for internal use)
-    event_tx: Sender<UiEvent>,
+    /// Events waiting for the UI
+    events: Arc<EventChannel>,
     /// Worker thread handle
     worker_handle: Mutex<Option<JoinHandle<()>>>,
     /// Current device info
@@ -329,7 +452,6 @@ impl ExtractionController {
     /// Create a new extraction controller
     pub fn new() -> Self {
         let (command_tx, _command_rx) = mpsc::channel();
-        let (event_tx, event_rx) = mpsc::channel();
 
         Self {
             state: Arc::new(AtomicU8::new(ControllerState::Idle as u8)),
@@ -337,8 +459,7 @@ impl ExtractionController {
             pause_flag: Arc::new(AtomicBool::new(false)),
             progress: Arc::new(ProgressTracker::new()),
             _command_tx: command_tx,
-            event_rx: Mutex::new(event_rx),
-            event_tx,
+            events: Arc::new(EventChannel::new(EVENT_CHANNEL_CAPACITY)),
             worker_handle: Mutex::new(None),
             current_device: RwLock::new(None),
             current_output_dir: RwLock::new(None),
@@ -386,16 +507,17 @@ impl ExtractionController {
 
     /// Try to receive the next event (non-blocking)
     pub fn try_recv_event(&self) -> Option<UiEvent> {
-        match self.event_rx.lock().unwrap().try_recv() {
-            Ok(event) => Some(event),
-            Err(TryRecvError::Empty) => None,
-            Err(TryRecvError::Disconnected) => None,
-        }
+        self.events.try_recv()
     }
 
     /// Receive events with timeout
     pub fn recv_event_timeout(&self, timeout: Duration) -> Option<UiEvent> {
-        self.event_rx.lock().unwrap().recv_timeout(timeout).ok()
+        self.events.recv_timeout(timeout)
+    }
+
+    /// Number of events waiting to be received
+    pub fn pending_events(&self) -> usize {
+        self.events.len()
     }
 
     /// Drain all pending events
@@ -418,7 +540,7 @@ impl ExtractionController {
     ) -> Result<()>
     where
         M: DeviceManagerTrait<Content = C> + Send + Sync + 'static,
-        C: DeviceContentTrait + Send + 'static,
+        C: DeviceContentTrait + Send + Sync + 'static,
     {
         // Check if already running
         if self.is_active() {
@@ -442,10 +564,10 @@ impl ExtractionController {
         let shutdown_flag = Arc::clone(&self.shutdown_flag);
         let pause_flag = Arc::clone(&self.pause_flag);
         let progress = Arc::clone(&self.progress);
-        let event_tx = self.event_tx.clone();
+        let events = Arc::clone(&self.events);
 
         // Emit started event
-        let _ = event_tx.send(UiEvent::Extraction(ExtractionEvent::Started {
+        events.post(UiEvent::Extraction(ExtractionEvent::Started {
             device: device_info.clone(),
             output_dir: config.output_dir.clone(),
             started_at: Instant::now(),
@@ -461,7 +583,7 @@ impl ExtractionController {
                 shutdown_flag,
                 pause_flag,
                 progress,
-                event_tx,
+                events,
             );
         });
 
@@ -481,9 +603,8 @@ impl ExtractionController {
         self.state
             .store(ControllerState::Paused as u8, Ordering::SeqCst);
 
-        let _ = self
-            .event_tx
-            .send(UiEvent::Extraction(ExtractionEvent::Paused {
+        self.events
+            .post(UiEvent::Extraction(ExtractionEvent::Paused {
                 reason: PauseReason::UserRequested,
             }));
 
@@ -502,9 +623,8 @@ impl ExtractionController {
         self.state
             .store(ControllerState::Extracting as u8, Ordering::SeqCst);
 
-        let _ = self
-            .event_tx
-            .send(UiEvent::Extraction(ExtractionEvent::Resumed));
+        self.events
+            .post(UiEvent::Extraction(ExtractionEvent::Resumed));
 
         Ok(())
     }
@@ -522,9 +642,8 @@ impl ExtractionController {
         self.shutdown_flag.store(true, Ordering::SeqCst);
         self.pause_flag.store(false, Ordering::SeqCst); // Unpause so thread can exit
 
-        let _ = self
-            .event_tx
-            .send(UiEvent::Extraction(ExtractionEvent::Cancelling));
+        self.events
+            .post(UiEvent::Extraction(ExtractionEvent::Cancelling));
 
         Ok(())
     }
@@ -541,6 +660,10 @@ impl ExtractionController {
 
     /// Shutdown the controller
     pub fn shutdown(&self) {
+        // The UI may not drain events any more, so don't let the worker wait
+        // on a full queue
+        self.events.release();
+
         // Cancel any running extraction
         let _ = self.cancel();
 
@@ -548,7 +671,7 @@ impl ExtractionController {
         let _ = self.wait();
 
         // Emit shutdown event
-        let _ = self.event_tx.send(UiEvent::App(AppEvent::ShuttingDown));
+        self.events.post(UiEvent::App(AppEvent::ShuttingDown));
     }
 
     /// Worker thread function that performs the actual extraction
@@ -560,10 +683,10 @@ impl ExtractionController {
         shutdown_flag: Arc<AtomicBool>,
         pause_flag: Arc<AtomicBool>,
         progress: Arc<ProgressTracker>,
-        event_tx: Sender<UiEvent>,
+        events: Arc<EventChannel>,
     ) where
         M: DeviceManagerTrait<Content = C> + Send + Sync + 'static,
-        C: DeviceContentTrait + Send + 'static,
+        C: DeviceContentTrait + Send + Sync + 'static,
     {
         let start_time = Instant::now();
 
@@ -572,7 +695,7 @@ impl ExtractionController {
             Ok(c) => c,
             Err(e) => {
                 state.store(ControllerState::Error as u8, Ordering::SeqCst);
-                let _ = event_tx.send(UiEvent::Extraction(ExtractionEvent::FatalError {
+                events.send(UiEvent::Extraction(ExtractionEvent::FatalError {
                     error: format!("Failed to open device: {}", e),
                     context: Some("Device may be locked or disconnected".to_string()),
                 }));
@@ -582,7 +705,7 @@ impl ExtractionController {
 
         // Build duplicate index if configured
         let _duplicate_index = if let Some(ref dup_config) = config.duplicate_config {
-            let _ = event_tx.send(UiEvent::App(AppEvent::Log {
+            events.send(UiEvent::App(AppEvent::Log {
                 level: crate::ui::events::LogLevel::Info,
                 message: "Building duplicate index...".to_string(),
                 source: Some("DuplicateDetector".to_string()),
@@ -592,7 +715,7 @@ impl ExtractionController {
             {
                 Ok(index) => Some(index),
                 Err(e) => {
-                    let _ = event_tx.send(UiEvent::App(AppEvent::Log {
+                    events.send(UiEvent::App(AppEvent::Log {
                         level: crate::ui::events::LogLevel::Warning,
                         message: format!("Failed to build duplicate index: {}", e),
                         source: Some("DuplicateDetector".to_string()),
@@ -605,11 +728,11 @@ impl ExtractionController {
         };
 
         // Emit scan started
-        let _ = event_tx.send(UiEvent::Extraction(ExtractionEvent::ScanStarted));
+        events.send(UiEvent::Extraction(ExtractionEvent::ScanStarted));
 
         // Create progress callback
         let progress_clone = Arc::clone(&progress);
-        let event_tx_clone = event_tx.clone();
+        let events_clone = Arc::clone(&events);
         let pause_flag_clone = Arc::clone(&pause_flag);
 
         let progress_callback: Arc<dyn Fn(ProgressUpdate) + Send + Sync> =
@@ -621,27 +744,24 @@ impl ExtractionController {
 
                 match update.phase {
                     ExtractionPhase::Scanning => {
-                        let _ = event_tx_clone.send(UiEvent::Extraction(
-                            ExtractionEvent::ScanProgress {
-                                folders_scanned: update.current_index,
-                                files_found: update.total_files,
-                                current_folder: update.current_file.clone(),
-                            },
-                        ));
+                        events_clone.send(UiEvent::Extraction(ExtractionEvent::ScanProgress {
+                            folders_scanned: update.current_index,
+                            files_found: update.total_files,
+                            current_folder: update.current_file.clone(),
+                        }));
                     }
                     ExtractionPhase::Extracting => {
                         let snapshot = progress_clone.snapshot();
-                        let _ =
-                            event_tx_clone.send(UiEvent::Extraction(ExtractionEvent::Progress {
-                                files_extracted: snapshot.files_extracted,
-                                files_skipped: snapshot.files_skipped,
-                                duplicates_found: snapshot.duplicates_found,
-                                errors: snapshot.errors,
-                                bytes_processed: snapshot.bytes_processed,
-                                eta: snapshot.eta,
-                                speed_bps: snapshot.speed_bps,
-                                percent_complete: snapshot.percent_complete,
-                            }));
+                        events_clone.send(UiEvent::Extraction(ExtractionEvent::Progress {
+                            files_extracted: snapshot.files_extracted,
+                            files_skipped: snapshot.files_skipped,
+                            duplicates_found: snapshot.duplicates_found,
+                            errors: snapshot.errors,
+                            bytes_processed: snapshot.bytes_processed,
+                            eta: snapshot.eta,
+                            speed_bps: snapshot.speed_bps,
+                            percent_complete: snapshot.percent_complete,
+                        }));
                     }
                     ExtractionPhase::Complete => {
                         // Handled separately
@@ -650,14 +770,22 @@ impl ExtractionController {
             });
 
         // Create extractor config
+        let checkpoint_path = config
+            .resume_from_checkpoint
+            .then(|| Checkpoint::default_path(&config.output_dir));
         let extractor_config = GenericExtractionConfig {
             output_dir: config.output_dir.clone(),
             dcim_only: config.dcim_only,
             preserve_structure: config.preserve_structure,
+            organize_by_date: config.organize_by_date,
             skip_existing: config.skip_existing,
             write_files: true,
             max_files: config.max_files,
+            scan_threads: DEFAULT_SCAN_THREADS,
+            checkpoint_path,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
             progress_callback: Some(progress_callback),
+            ..Default::default()
         };
 
         // Create and run extractor
@@ -680,13 +808,13 @@ impl ExtractionController {
 
                 if was_cancelled {
                     state.store(ControllerState::Idle as u8, Ordering::SeqCst);
-                    let _ = event_tx.send(UiEvent::Extraction(ExtractionEvent::Cancelled {
+                    events.send(UiEvent::Extraction(ExtractionEvent::Cancelled {
                         files_extracted: stats.files_extracted,
                         resumable: true,
                     }));
                 } else {
                     state.store(ControllerState::Completed as u8, Ordering::SeqCst);
-                    let _ = event_tx.send(UiEvent::Extraction(ExtractionEvent::Completed {
+                    events.send(UiEvent::Extraction(ExtractionEvent::Completed {
                         stats: ExtractionSummary {
                             device: device_info,
                             output_dir: config.output_dir,
@@ -709,7 +837,7 @@ impl ExtractionController {
             }
             Err(e) => {
                 state.store(ControllerState::Error as u8, Ordering::SeqCst);
-                let _ = event_tx.send(UiEvent::Extraction(ExtractionEvent::FatalError {
+                events.send(UiEvent::Extraction(ExtractionEvent::FatalError {
                     error: e.to_string(),
                     context: None,
                 }));
@@ -743,12 +871,16 @@ pub struct ExtractionConfig {
     pub duplicate_config: Option<DuplicateConfig>,
     /// Whether to preserve folder structure from device
     pub preserve_structure: bool,
+    /// Sort files into YYYY/MM folders by capture date
+    pub organize_by_date: bool,
     /// Skip files that already exist at destination
     pub skip_existing: bool,
     /// Only extract from DCIM folder
     pub dcim_only: bool,
     /// Maximum number of files to extract (0 = unlimited)
     pub max_files: usize,
+    /// Keep a checkpoint in the output directory and resume from it
+    pub resume_from_checkpoint: bool,
 }
 
 impl Default for ExtractionConfig {
@@ -757,9 +889,11 @@ impl Default for ExtractionConfig {
             output_dir: PathBuf::from("./extracted"),
             duplicate_config: None,
             preserve_structure: true,
+            organize_by_date: false,
             skip_existing: true,
             dcim_only: true,
             max_files: 0,
+            resume_from_checkpoint: false,
         }
     }
 }
@@ -785,6 +919,12 @@ impl ExtractionConfig {
         self
     }
 
+    /// Set organize by date
+    pub fn organize_by_date(mut self, organize: bool) -> Self {
+        self.organize_by_date = organize;
+        self
+    }
+
     /// Set skip existing
     pub fn skip_existing(mut self, skip: bool) -> Self {
         self.skip_existing = skip;
@@ -802,6 +942,51 @@ impl ExtractionConfig {
         self.max_files = max;
         self
     }
+
+    /// Set resume from checkpoint
+    pub fn resume_from_checkpoint(mut self, resume: bool) -> Self {
+        self.resume_from_checkpoint = resume;
+        self
+    }
+}
+
+// =============================================================================
+// Progress Sink
+// =============================================================================
+
+/// Drive a progress indicator straight from extractor events
+///
+/// Items count files; the byte fields track the file being copied.
+impl ProgressSink for ProgressState {
+    fn on_file_start(&mut self, name: &str, index: usize, total: usize) {
+        if self.status == ProgressStatus::Idle {
+            self.start();
+        }
+        self.update(index.saturating_sub(1) as u64, total as u64);
+        self.update_bytes(0, 0);
+        self.set_description(name);
+    }
+
+    fn on_bytes(&mut self, copied: u64, total: u64) {
+        self.update_bytes(copied, total);
+    }
+
+    fn on_throughput(&mut self, bytes_per_sec: f64, eta: Option<Duration>) {
+        let elapsed = self.elapsed;
+        self.update_timing(bytes_per_sec, eta.map(|eta| eta.as_secs_f64()), elapsed);
+    }
+
+    fn on_file_done(&mut self, _outcome: &FileOutcome) {
+        self.update(self.current + 1, self.total);
+    }
+
+    fn on_finished(&mut self, stats: &ExtractionStats) {
+        if stats.errors > 0 {
+            self.fail(format!("{} files failed to extract", stats.errors));
+        } else {
+            self.complete();
+        }
+    }
 }
 
 // =============================================================================
@@ -895,4 +1080,215 @@ mod tests {
         assert!(controller.resume().is_err());
         assert!(controller.cancel().is_err());
     }
+
+    #[test]
+    fn test_progress_state_as_sink() {
+        let mut progress = ProgressState::new("extraction");
+
+        progress.on_file_start("IMG_0001.JPG", 1, 2);
+        assert_eq!(progress.status, ProgressStatus::Active);
+        assert_eq!(progress.description.as_deref(), Some("IMG_0001.JPG"));
+        progress.on_bytes(512, 2048);
+        assert_eq!(progress.bytes_progress_string(), "512 B / 2.00 KB");
+        progress.on_throughput(1024.0 * 1024.0, Some(Duration::from_secs(120)));
+        assert_eq!(progress.speed_string(), "1.00 MB/s");
+        assert_eq!(progress.eta_string(), "2m 0s");
+        progress.on_file_done(&FileOutcome::Extracted { bytes: 2048 });
+        assert_eq!(progress.percentage(), 50);
+
+        progress.on_file_start("IMG_0002.JPG", 2, 2);
+        progress.on_file_done(&FileOutcome::Failed("read error".to_string()));
+        progress.on_finished(&ExtractionStats {
+            files_extracted: 1,
+            errors: 1,
+            ..Default::default()
+        });
+        assert_eq!(progress.status, ProgressStatus::Error);
+        assert_eq!(progress.items_progress_string(), "2 / 2");
+    }
+
+    fn progress_event(files_extracted: usize) -> UiEvent {
+        UiEvent::Extraction(ExtractionEvent::Progress {
+            files_extracted,
+            files_skipped: 0,
+            duplicates_found: 0,
+            errors: 0,
+            bytes_processed: files_extracted as u64 * 1024,
+            eta: None,
+            speed_bps: 0,
+            percent_complete: 0.0,
+        })
+    }
+
+    fn file_complete_event(index: usize) -> UiEvent {
+        UiEvent::Extraction(ExtractionEvent::FileComplete {
+            name: format!("IMG_{:04}.JPG", index),
+            size: 1024,
+            destination: PathBuf::from(format!("out/IMG_{:04}.JPG", index)),
+            transfer_duration: Duration::from_millis(1),
+        })
+    }
+
+    #[test]
+    fn test_progress_events_coalesce_without_consumer() {
+        let controller = ExtractionController::new();
+
+        for index in 1..=20_000 {
+            controller.events.send(progress_event(index));
+            if index % 100 == 0 {
+                controller.events.send(file_complete_event(index));
+            }
+            assert!(controller.pending_events() <= 201);
+        }
+        controller
+            .events
+            .send(UiEvent::Extraction(ExtractionEvent::Cancelled {
+                files_extracted: 20_000,
+                resumable: true,
+            }));
+
+        let events = controller.drain_events();
+        let completed: Vec<String> = events
+            .iter()
+            .filter_map(|event| match event {
+                UiEvent::Extraction(ExtractionEvent::FileComplete { name, .. }) => {
+                    Some(name.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        let expected: Vec<String> = (1..=200)
+            .map(|i| format!("IMG_{:04}.JPG", i * 100))
+            .collect();
+        assert_eq!(completed, expected);
+
+        // Only the latest progress report survives
+        let progress: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match event {
+                UiEvent::Extraction(ExtractionEvent::Progress {
+                    files_extracted, ..
+                }) => Some(*files_extracted),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(progress, vec![20_000]);
+        assert!(matches!(
+            events.last(),
+            Some(UiEvent::Extraction(ExtractionEvent::Cancelled { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_full_event_channel_waits_for_consumer() {
+        let events = Arc::new(EventChannel::new(4));
+
+        let sender = {
+            let events = Arc::clone(&events);
+            thread::spawn(move || {
+                for index in 0..10 {
+                    events.send(file_complete_event(index));
+                    events.send(progress_event(index));
+                }
+            })
+        };
+
+        // The sender fills the queue and then waits
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while events.len() < 5 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(events.len(), 5);
+
+        let mut received = 0;
+        while received < 10 {
+            match events.recv_timeout(Duration::from_secs(5)) {
+                Some(UiEvent::Extraction(ExtractionEvent::FileComplete { name, .. })) => {
+                    assert_eq!(name, format!("IMG_{:04}.JPG", received));
+                    received += 1;
+                }
+                Some(_) => {}
+                None => panic!("sender stalled after {} events", received),
+            }
+        }
+        sender.join().unwrap();
+
+        // Released, a full queue no longer holds the sender back
+        events.release();
+        for index in 0..10 {
+            events.send(file_complete_event(index));
+        }
+        assert!(events.len() >= 10);
+    }
+
+    #[test]
+    fn test_controller_events_do_not_wait_for_a_full_queue() {
+        let controller = ExtractionController::new();
+        for index in 0..EVENT_CHANNEL_CAPACITY {
+            controller.events.send(file_complete_event(index));
+        }
+        controller
+            .state
+            .store(ControllerState::Extracting as u8, Ordering::SeqCst);
+
+        // Nobody drains the queue, yet pausing, cancelling and dropping the
+        // controller all return
+        let (done_tx, done_rx) = mpsc::channel();
+        thread::spawn(move || {
+            controller.pause().unwrap();
+            controller.resume().unwrap();
+            controller.cancel().unwrap();
+            assert_eq!(controller.pending_events(), EVENT_CHANNEL_CAPACITY + 3);
+            drop(controller);
+            done_tx.send(()).unwrap();
+        });
+        done_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("controller blocked on its full event queue");
+    }
+
+    #[test]
+    fn test_extraction_resumes_from_checkpoint() {
+        use crate::testdb::{FileSpec, ScenarioBuilder};
+
+        let paths = [
+            "Internal Storage/DCIM/100APPLE/IMG_0001.JPG",
+            "Internal Storage/DCIM/100APPLE/IMG_0002.JPG",
+            "Internal Storage/DCIM/101APPLE/IMG_0003.JPG",
+        ];
+        let manager = paths
+            .iter()
+            .fold(ScenarioBuilder::new("resume"), |builder, path| {
+                builder.file(*path, FileSpec::sized(1024))
+            })
+            .build_manager();
+        let device = manager.enumerate_all_devices().unwrap().remove(0);
+
+        // An earlier run finished the first file before it was interrupted
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let checkpoint_path = Checkpoint::default_path(temp_dir.path());
+        Checkpoint {
+            completed: [paths[0].to_string()].into(),
+            position: 1,
+            total: 3,
+            ..Default::default()
+        }
+        .save(&checkpoint_path)
+        .unwrap();
+
+        let controller = ExtractionController::new();
+        let config =
+            ExtractionConfig::new(temp_dir.path().to_path_buf()).resume_from_checkpoint(true);
+        controller
+            .start_extraction(Arc::new(manager), device, config)
+            .unwrap();
+        controller.wait().unwrap();
+
+        assert_eq!(controller.state(), ControllerState::Completed);
+        assert!(!temp_dir.path().join(paths[0]).exists());
+        assert!(temp_dir.path().join(paths[1]).exists());
+        assert!(temp_dir.path().join(paths[2]).exists());
+        assert!(!checkpoint_path.exists());
+    }
 }
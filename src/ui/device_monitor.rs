@@ -114,6 +114,11 @@ pub struct MonitorConfig {
     pub apple_only: bool,
     /// Whether to automatically try to determine device state
     pub probe_device_state: bool,
+    /// How long a connect or disconnect must hold before it is reported
+    ///
+    /// A device that disconnects and comes back within this window is treated
+    /// as continuously present. Zero reports every transition immediately.
+    pub debounce: Duration,
 }
 
 impl Default for MonitorConfig {
@@ -124,6 +129,7 @@ impl Default for MonitorConfig {
             max_failures: 3,
             apple_only: true,
             probe_device_state: true,
+            debounce: Duration::ZERO,
         }
     }
 }
@@ -164,6 +170,110 @@ impl MonitorConfig {
         self.apple_only = apple_only;
         self
     }
+
+    /// Set the debounce window for connect/disconnect events
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+}
+
+// =============================================================================
+// Event Debouncing
+// =============================================================================
+
+/// A connect or disconnect waiting out the debounce window
+#[derive(Debug)]
+struct PendingTransition {
+    device_id: String,
+    connected: bool,
+    event: DeviceEvent,
+    since: Instant,
+}
+
+/// Collapses bursts of connect/disconnect events into stable transitions
+///
+/// Raw events are pushed as they are observed; [`poll`](Self::poll) returns
+/// those whose state has held for the whole window. A transition that is
+/// undone within the window is dropped, and an event that repeats the last
+/// reported state is ignored. Other device events pass straight through.
+#[derive(Debug)]
+pub struct EventDebouncer {
+    window: Duration,
+    /// Last reported state of each device (true = connected)
+    stable: HashMap<String, bool>,
+    /// Transitions not yet reported, in arrival order
+    pending: Vec<PendingTransition>,
+    /// Events that are not debounced
+    passthrough: Vec<DeviceEvent>,
+}
+
+impl EventDebouncer {
+    /// Create a debouncer with the given window
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            stable: HashMap::new(),
+            pending: Vec::new(),
+            passthrough: Vec::new(),
+        }
+    }
+
+    /// Record a raw event observed at `at`
+    pub fn push(&mut self, event: DeviceEvent, at: Instant) {
+        let (device_id, connected) = match &event {
+            DeviceEvent::Connected { device, .. } => (device.device_id.clone(), true),
+            DeviceEvent::Disconnected { device_id, .. } => (device_id.clone(), false),
+            _ => {
+                self.passthrough.push(event);
+                return;
+            }
+        };
+
+        if let Some(index) = self.pending.iter().position(|p| p.device_id == device_id) {
+            if self.pending[index].connected == connected {
+                // Same transition again: keep its start time, take the newer details
+                self.pending[index].event = event;
+                return;
+            }
+            // The device flipped back before the window passed
+            self.pending.remove(index);
+        }
+
+        let stable = self.stable.get(&device_id).copied().unwrap_or(false);
+        if connected != stable {
+            self.pending.push(PendingTransition {
+                device_id,
+                connected,
+                event,
+                since: at,
+            });
+        }
+    }
+
+    /// Take the events that are stable as of `now`
+    pub fn poll(&mut self, now: Instant) -> Vec<DeviceEvent> {
+        let mut ready = std::mem::take(&mut self.passthrough);
+
+        let window = self.window;
+        let (settled, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|p| now.saturating_duration_since(p.since) >= window);
+        self.pending = waiting;
+
+        for transition in settled {
+            self.stable
+                .insert(transition.device_id, transition.connected);
+            ready.push(transition.event);
+        }
+
+        ready
+    }
+
+    /// Check if any events are waiting to be reported
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty() || !self.passthrough.is_empty()
+    }
 }
 
 // =============================================================================
@@ -356,6 +466,7 @@ impl DeviceMonitor {
     {
         let poll_interval = Duration::from_millis(config.poll_interval_ms);
         let disconnect_timeout = Duration::from_millis(config.disconnect_timeout_ms);
+        let mut debouncer = EventDebouncer::new(config.debounce);
 
         loop {
             // Check for shutdown
@@ -374,10 +485,11 @@ impl DeviceMonitor {
             match current_devices {
                 Ok(device_list) => {
                     Self::process_device_list(
+                        device_manager.as_ref(),
                         &device_list,
                         &devices,
                         &known_device_ids,
-                        &event_tx,
+                        &mut debouncer,
                         disconnect_timeout,
                         &config,
                     );
@@ -388,20 +500,39 @@ impl DeviceMonitor {
                 }
             }
 
+            // Only transitions that have held for the debounce window go out
+            for event in debouncer.poll(Instant::now()) {
+                let _ = event_tx.send(UiEvent::Device(event));
+            }
+
             // Sleep before next poll
             thread::sleep(poll_interval);
         }
     }
 
     /// Process the list of devices and emit appropriate events
-    fn process_device_list(
+    ///
+    /// Battery levels are read for every listed device each poll, before the
+    /// device map is locked, so a slow query doesn't block readers and known
+    /// devices show a current reading. Events go to the debouncer rather than
+    /// straight to the UI.
+    fn process_device_list<M: DeviceManagerTrait + ?Sized>(
+        device_manager: &M,
         device_list: &[DeviceInfo],
         devices: &Arc<RwLock<HashMap<String, MonitoredDevice>>>,
         known_device_ids: &Arc<RwLock<Vec<String>>>,
-        event_tx: &Sender<UiEvent>,
+        debouncer: &mut EventDebouncer,
         disconnect_timeout: Duration,
         _config: &MonitorConfig,
     ) {
+        let battery_levels: HashMap<&str, Option<u8>> = device_list
+            .iter()
+            .map(|device| {
+                let id = device.device_id.as_str();
+                (id, device_manager.battery_level(id))
+            })
+            .collect();
+
         let mut devices_guard = devices.write().unwrap();
         let known = known_device_ids.read().unwrap();
 
@@ -412,36 +543,46 @@ impl DeviceMonitor {
         for device_info in device_list {
             let device_id = &device_info.device_id;
             seen_ids.push(device_id.clone());
+            let battery_level = battery_levels[device_id.as_str()];
 
             if let Some(existing) = devices_guard.get_mut(device_id) {
                 // Device already known - update it
                 existing.touch();
                 existing.info = device_info.clone();
+                existing.info.battery_level = battery_level;
 
                 // If it was disconnected, it's reconnected now
                 if existing.state == DeviceState::Disconnected {
                     existing.state = DeviceState::Connected;
                     existing.reset_failures();
 
-                    let _ = event_tx.send(UiEvent::Device(DeviceEvent::Connected {
-                        device: device_info.clone(),
-                        previously_known: existing.previously_known,
-                    }));
+                    debouncer.push(
+                        DeviceEvent::Connected {
+                            device: existing.info.clone(),
+                            previously_known: existing.previously_known,
+                        },
+                        Instant::now(),
+                    );
 
                     info!("Device reconnected: {}", device_info.friendly_name);
                 }
             } else {
                 // New device
                 let previously_known = known.contains(device_id);
-                let mut new_device = MonitoredDevice::new(device_info.clone(), previously_known);
+                let mut info = device_info.clone();
+                info.battery_level = battery_level;
+                let mut new_device = MonitoredDevice::new(info.clone(), previously_known);
                 new_device.state = DeviceState::Connected;
 
                 devices_guard.insert(device_id.clone(), new_device);
 
-                let _ = event_tx.send(UiEvent::Device(DeviceEvent::Connected {
-                    device: device_info.clone(),
-                    previously_known,
-                }));
+                debouncer.push(
+                    DeviceEvent::Connected {
+                        device: info,
+                        previously_known,
+                    },
+                    Instant::now(),
+                );
 
                 info!(
                     "New device connected: {} (previously known: {})",
@@ -457,10 +598,13 @@ impl DeviceMonitor {
                 if device.time_since_last_seen() > disconnect_timeout {
                     device.state = DeviceState::Disconnected;
 
-                    let _ = event_tx.send(UiEvent::Device(DeviceEvent::Disconnected {
-                        device_id: device_id.clone(),
-                        device_name: Some(device.info.friendly_name.clone()),
-                    }));
+                    debouncer.push(
+                        DeviceEvent::Disconnected {
+                            device_id: device_id.clone(),
+                            device_name: Some(device.info.friendly_name.clone()),
+                        },
+                        Instant::now(),
+                    );
 
                     info!("Device disconnected: {}", device.info.friendly_name);
                 }
@@ -601,6 +745,106 @@ mod tests {
         assert!(!config.apple_only);
     }
 
+    #[test]
+    fn test_monitor_config_debounce() {
+        assert_eq!(MonitorConfig::default().debounce, Duration::ZERO);
+        let config = MonitorConfig::default().with_debounce(Duration::from_millis(500));
+        assert_eq!(config.debounce, Duration::from_millis(500));
+    }
+
+    fn connected(device_id: &str) -> DeviceEvent {
+        DeviceEvent::Connected {
+            device: DeviceInfo::new(device_id, "Test iPhone", "Apple Inc.", "iPhone 15"),
+            previously_known: false,
+        }
+    }
+
+    fn disconnected(device_id: &str) -> DeviceEvent {
+        DeviceEvent::Disconnected {
+            device_id: device_id.to_string(),
+            device_name: None,
+        }
+    }
+
+    #[test]
+    fn test_debouncer_collapses_flapping_connect() {
+        let mut debouncer = EventDebouncer::new(Duration::from_millis(500));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        debouncer.push(connected("iphone-1"), at(0));
+        debouncer.push(disconnected("iphone-1"), at(100));
+        debouncer.push(connected("iphone-1"), at(200));
+        assert!(debouncer.poll(at(300)).is_empty());
+
+        let events = debouncer.poll(at(700));
+        assert_eq!(events.len(), 1);
+        assert!(
+            matches!(&events[0], DeviceEvent::Connected { device, .. } if device.device_id == "iphone-1")
+        );
+
+        assert!(debouncer.poll(at(2000)).is_empty());
+        assert!(!debouncer.has_pending());
+    }
+
+    #[test]
+    fn test_debouncer_ignores_transient_disconnect() {
+        let mut debouncer = EventDebouncer::new(Duration::from_millis(500));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        debouncer.push(connected("iphone-1"), at(0));
+        assert_eq!(debouncer.poll(at(500)).len(), 1);
+
+        // Drops out and comes back within the window: still connected
+        debouncer.push(disconnected("iphone-1"), at(1000));
+        debouncer.push(connected("iphone-1"), at(1200));
+        assert!(debouncer.poll(at(2000)).is_empty());
+
+        // A disconnect that lasts is reported
+        debouncer.push(disconnected("iphone-1"), at(3000));
+        let events = debouncer.poll(at(3500));
+        assert!(matches!(
+            events.as_slice(),
+            [DeviceEvent::Disconnected { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_debouncer_deduplicates_repeated_state() {
+        let mut debouncer = EventDebouncer::new(Duration::ZERO);
+        let now = Instant::now();
+
+        debouncer.push(connected("iphone-1"), now);
+        debouncer.push(connected("iphone-1"), now);
+        assert_eq!(debouncer.poll(now).len(), 1);
+
+        debouncer.push(connected("iphone-1"), now);
+        assert!(debouncer.poll(now).is_empty());
+
+        // Disconnecting a device that was never connected says nothing
+        debouncer.push(disconnected("ipad-1"), now);
+        assert!(debouncer.poll(now).is_empty());
+    }
+
+    #[test]
+    fn test_debouncer_passes_other_events_through() {
+        let mut debouncer = EventDebouncer::new(Duration::from_secs(60));
+        let now = Instant::now();
+
+        debouncer.push(
+            DeviceEvent::DeviceError {
+                device_id: "iphone-1".to_string(),
+                error: "timeout".to_string(),
+            },
+            now,
+        );
+        assert!(matches!(
+            debouncer.poll(now).as_slice(),
+            [DeviceEvent::DeviceError { .. }]
+        ));
+    }
+
     #[test]
     fn test_monitored_device_creation() {
         let info = DeviceInfo::new("test-id", "Test iPhone", "Apple Inc.", "iPhone 15");
@@ -691,4 +935,109 @@ mod tests {
         let known = monitor.known_device_ids.read().unwrap();
         assert_eq!(known.len(), 1);
     }
+
+    #[test]
+    fn test_connected_event_carries_battery_level() {
+        use crate::testdb::{MockDeviceConfig, MockDeviceManager, ScenarioLibrary};
+
+        let scenario = ScenarioLibrary::single_iphone();
+        let mut manager = MockDeviceManager::new();
+        manager.add_device_with_config(
+            scenario.device_info.clone(),
+            scenario.file_system,
+            MockDeviceConfig::new().with_battery(42),
+        );
+
+        let monitor = DeviceMonitor::with_config(MonitorConfig::fast());
+        monitor.start(Arc::new(manager)).unwrap();
+        let event = monitor.recv_event_timeout(Duration::from_secs(5));
+        monitor.stop();
+
+        match event {
+            Some(UiEvent::Device(DeviceEvent::Connected { device, .. })) => {
+                assert_eq!(device.device_id, scenario.device_info.device_id);
+                assert_eq!(device.battery_level, Some(42));
+            }
+            other => panic!("Expected a Connected event, got {:?}", other),
+        }
+        assert_eq!(
+            monitor
+                .get_device(&scenario.device_info.device_id)
+                .unwrap()
+                .info
+                .battery_level,
+            Some(42)
+        );
+    }
+
+    #[test]
+    fn test_device_without_battery_reports_none() {
+        use crate::testdb::{MockDeviceInfo, MockDeviceManager, MockFileSystem};
+
+        let mut manager = MockDeviceManager::new();
+        manager.add_device(
+            MockDeviceInfo::new("iphone-1", "Test iPhone", "Apple Inc.", "iPhone 15"),
+            MockFileSystem::new(),
+        );
+
+        let monitor = DeviceMonitor::with_config(MonitorConfig::fast());
+        monitor.start(Arc::new(manager)).unwrap();
+        let event = monitor.recv_event_timeout(Duration::from_secs(5));
+        monitor.stop();
+
+        match event {
+            Some(UiEvent::Device(DeviceEvent::Connected { device, .. })) => {
+                assert_eq!(device.battery_level, None);
+            }
+            other => panic!("Expected a Connected event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_battery_level_refreshed_every_poll() {
+        use crate::testdb::{MockDeviceConfig, MockDeviceManager, ScenarioLibrary};
+
+        let scenario = ScenarioLibrary::single_iphone();
+        let device_id = scenario.device_info.device_id.clone();
+        let mut manager = MockDeviceManager::new();
+        manager.add_device_with_config(
+            scenario.device_info.clone(),
+            scenario.file_system,
+            MockDeviceConfig::new().with_battery(42),
+        );
+
+        let devices = Arc::new(RwLock::new(HashMap::new()));
+        let known = Arc::new(RwLock::new(Vec::new()));
+        let mut debouncer = EventDebouncer::new(Duration::ZERO);
+        let poll = |manager: &MockDeviceManager, debouncer: &mut EventDebouncer| {
+            DeviceMonitor::process_device_list(
+                manager,
+                &manager.enumerate_all_devices().unwrap(),
+                &devices,
+                &known,
+                debouncer,
+                Duration::from_secs(5),
+                &MonitorConfig::fast(),
+            )
+        };
+
+        poll(&manager, &mut debouncer);
+        assert_eq!(
+            devices.read().unwrap()[&device_id].info.battery_level,
+            Some(42)
+        );
+
+        manager
+            .get_file_system(&device_id)
+            .unwrap()
+            .write()
+            .unwrap()
+            .config_mut()
+            .battery_level = Some(17);
+        poll(&manager, &mut debouncer);
+        assert_eq!(
+            devices.read().unwrap()[&device_id].info.battery_level,
+            Some(17)
+        );
+    }
 }
@@ -413,6 +413,35 @@ pub enum UiEvent {
     App(AppEvent),
 }
 
+impl UiEvent {
+    /// Whether a newer event of the same kind makes this one redundant
+    ///
+    /// Progress reports carry running totals, so only the latest matters;
+    /// everything else (per-file results, state changes, logs) must each be
+    /// delivered.
+    pub fn is_coalescable(&self) -> bool {
+        matches!(
+            self,
+            UiEvent::Extraction(
+                ExtractionEvent::ScanProgress { .. }
+                    | ExtractionEvent::FileProgress { .. }
+                    | ExtractionEvent::Progress { .. }
+            )
+        )
+    }
+
+    /// Whether this event supersedes `older`, a coalescable event of the
+    /// same kind that hasn't been delivered yet
+    pub(crate) fn replaces(&self, older: &UiEvent) -> bool {
+        match (self, older) {
+            (UiEvent::Extraction(new), UiEvent::Extraction(old)) => {
+                self.is_coalescable() && std::mem::discriminant(new) == std::mem::discriminant(old)
+            }
+            _ => false,
+        }
+    }
+}
+
 impl From<ExtractionEvent> for UiEvent {
     fn from(event: ExtractionEvent) -> Self {
         UiEvent::Extraction(event)
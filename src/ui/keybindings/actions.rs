@@ -711,6 +711,29 @@ impl Action {
     pub fn all() -> &'static [Action] {
         ALL_ACTIONS
     }
+
+    /// Stable name of this action, e.g. "StartExtraction"
+    ///
+    /// Custom actions are named "custom::<id>".
+    pub fn name(&self) -> String {
+        match self {
+            Action::Custom(id) => format!("custom::{}", id),
+            other => format!("{:?}", other),
+        }
+    }
+
+    /// Look up an action by its [`name`](Self::name)
+    pub fn from_name(name: &str) -> Option<Action> {
+        if let Some(id) = name.strip_prefix("custom::") {
+            return (!id.is_empty()).then(|| Action::Custom(id.to_string()));
+        }
+
+        if name == "None" {
+            return Some(Action::None);
+        }
+
+        ALL_ACTIONS.iter().find(|a| a.name() == name).cloned()
+    }
 }
 
 impl Default for Action {
@@ -1018,6 +1041,26 @@ mod tests {
         assert_eq!(custom.category(), ActionCategory::Custom);
     }
 
+    #[test]
+    fn test_action_name_roundtrip() {
+        assert_eq!(Action::StartExtraction.name(), "StartExtraction");
+        assert_eq!(
+            Action::from_name("StartExtraction"),
+            Some(Action::StartExtraction)
+        );
+
+        let custom = Action::Custom("my_action".to_string());
+        assert_eq!(custom.name(), "custom::my_action");
+        assert_eq!(Action::from_name("custom::my_action"), Some(custom));
+
+        for action in Action::all() {
+            assert_eq!(Action::from_name(&action.name()).as_ref(), Some(action));
+        }
+
+        assert_eq!(Action::from_name("NoSuchAction"), None);
+        assert_eq!(Action::from_name("custom::"), None);
+    }
+
     #[test]
     fn test_category_display_name() {
         assert_eq!(ActionCategory::Application.display_name(), "Application");
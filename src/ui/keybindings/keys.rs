@@ -5,6 +5,10 @@
 //! - Modifier flags (Ctrl, Alt, Shift, Meta)
 //! - Key combinations (key + modifiers)
 //! - Key sequences (multiple combinations for chords)
+//!
+//! Raw input from a UI framework is turned into a [`KeyCombination`] with
+//! [`KeyCombination::from_parts`] or [`KeyCombination::from_char`], or with
+//! [`KeyCombination::from_egui`] when the `egui-input` feature is enabled.
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -400,6 +404,20 @@ impl KeyCode {
         }
     }
 
+    /// Key code for a typed character
+    ///
+    /// Letters are stored lowercase, as bindings are; whitespace maps to the
+    /// named key and other control characters to [`KeyCode::Unknown`].
+    pub fn from_char(c: char) -> Self {
+        match c {
+            ' ' => KeyCode::Space,
+            '\t' => KeyCode::Tab,
+            '\n' | '\r' => KeyCode::Enter,
+            c if c.is_control() => KeyCode::Unknown,
+            c => KeyCode::Char(c.to_lowercase().next().unwrap_or(c)),
+        }
+    }
+
     /// Parse a key code from a string
     pub fn parse(s: &str) -> Self {
         let s = s.trim();
@@ -494,6 +512,28 @@ impl KeyCombination {
         }
     }
 
+    /// Create a combination from a key and the state of each modifier
+    ///
+    /// This is the common entry point for key events from any UI framework.
+    /// Character keys are normalized the same way [`parse`](Self::parse)
+    /// normalizes them, so `'P'` and `'p'` match the same binding.
+    pub fn from_parts(key: KeyCode, ctrl: bool, alt: bool, shift: bool, meta: bool) -> Self {
+        let key = match key {
+            KeyCode::Char(c) => KeyCode::from_char(c),
+            key => key,
+        };
+        Self::new(key, Modifiers::new(ctrl, alt, shift, meta))
+    }
+
+    /// Create a combination from a typed character
+    ///
+    /// An uppercase letter means Shift was held, even if `modifiers` doesn't
+    /// say so; other shifted symbols such as `?` are taken as typed.
+    pub fn from_char(c: char, modifiers: Modifiers) -> Self {
+        let shift = modifiers.shift || c.is_uppercase();
+        Self::new(KeyCode::from_char(c), Modifiers { shift, ..modifiers })
+    }
+
     /// Parse from a string like "Ctrl+Shift+P"
     pub fn parse(s: &str) -> Option<Self> {
         let parts: Vec<&str> = s.split('+').map(|p| p.trim()).collect();
@@ -548,6 +588,49 @@ impl fmt::Display for KeyCombination {
     }
 }
 
+// =============================================================================
+// egui Input
+// =============================================================================
+
+#[cfg(feature = "egui-input")]
+impl KeyCode {
+    /// Key code for a key reported by egui
+    ///
+    /// Keys with no equivalent here (F13 and up, Copy/Cut/Paste) map to
+    /// [`KeyCode::Unknown`].
+    pub fn from_egui(key: egui::Key) -> Self {
+        use egui::Key;
+
+        match key {
+            Key::ArrowUp => KeyCode::Up,
+            Key::ArrowDown => KeyCode::Down,
+            Key::ArrowLeft => KeyCode::Left,
+            Key::ArrowRight => KeyCode::Right,
+            Key::Minus => KeyCode::Char('-'),
+            Key::Quote => KeyCode::Char('\''),
+            // Letters, digits and the other punctuation are a single symbol,
+            // and the remaining names match those `parse` accepts
+            key => KeyCode::parse(key.symbol_or_name()),
+        }
+    }
+}
+
+#[cfg(feature = "egui-input")]
+impl KeyCombination {
+    /// Create a combination from a key press reported by egui
+    ///
+    /// egui reports the Command key on macOS as `mac_cmd`, which becomes Meta.
+    pub fn from_egui(key: egui::Key, modifiers: egui::Modifiers) -> Self {
+        Self::from_parts(
+            KeyCode::from_egui(key),
+            modifiers.ctrl,
+            modifiers.alt,
+            modifiers.shift,
+            modifiers.mac_cmd,
+        )
+    }
+}
+
 // =============================================================================
 // KeySequence
 // =============================================================================
@@ -915,4 +998,48 @@ mod tests {
         let deserialized: KeySequence = serde_json::from_str(&json).unwrap();
         assert_eq!(seq, deserialized);
     }
+
+    #[test]
+    fn test_key_combination_from_parts() {
+        let combo = KeyCombination::from_parts(KeyCode::Char('P'), true, false, true, false);
+        assert_eq!(combo.display(), "Ctrl+Shift+P");
+        assert_eq!(Some(combo), KeyCombination::parse("Ctrl+Shift+P"));
+
+        let combo = KeyCombination::from_parts(KeyCode::F5, false, true, false, false);
+        assert_eq!(combo.display(), "Alt+F5");
+        let combo = KeyCombination::from_parts(KeyCode::Char(' '), true, false, false, false);
+        assert_eq!(combo, KeyCombination::new(KeyCode::Space, Modifiers::CTRL));
+        assert_eq!(combo.display(), "Ctrl+Space");
+    }
+
+    #[test]
+    fn test_key_combination_from_char() {
+        assert_eq!(KeyCode::from_char('\t'), KeyCode::Tab);
+        assert_eq!(KeyCode::from_char('\u{1b}'), KeyCode::Unknown);
+        assert_eq!(KeyCode::from_char('É'), KeyCode::Char('é'));
+
+        let combo = KeyCombination::from_char('P', Modifiers::CTRL);
+        assert_eq!(combo.display(), "Ctrl+Shift+P");
+        let combo = KeyCombination::from_char('?', Modifiers::NONE);
+        assert_eq!(combo, KeyCombination::key_only(KeyCode::Char('?')));
+        assert!(combo.is_text_input());
+    }
+
+    #[cfg(feature = "egui-input")]
+    #[test]
+    fn test_key_combination_from_egui() {
+        let modifiers = egui::Modifiers {
+            ctrl: true,
+            shift: true,
+            ..Default::default()
+        };
+        let combo = KeyCombination::from_egui(egui::Key::P, modifiers);
+        assert_eq!(combo.display(), "Ctrl+Shift+P");
+
+        let combo = KeyCombination::from_egui(egui::Key::ArrowUp, egui::Modifiers::ALT);
+        assert_eq!(combo, KeyCombination::new(KeyCode::Up, Modifiers::ALT));
+        assert_eq!(KeyCode::from_egui(egui::Key::PageDown), KeyCode::PageDown);
+        assert_eq!(KeyCode::from_egui(egui::Key::Minus), KeyCode::Char('-'));
+        assert_eq!(KeyCode::from_egui(egui::Key::Copy), KeyCode::Unknown);
+    }
 }
@@ -8,6 +8,7 @@
 //! - Context-aware bindings (different bindings in different UI states)
 //! - Conflict detection and resolution
 //! - Serialization for user configuration
+//! - Import/export of Zed-style keymap JSON
 //!
 //! # Architecture
 //!
@@ -44,13 +45,17 @@
 pub mod actions;
 pub mod keys;
 pub mod registry;
+pub mod zed;
 
 pub use actions::{Action, ActionCategory};
 pub use keys::{KeyCode, KeyCombination, KeySequence, Modifiers};
 pub use registry::{
-    BindingMatch, KeybindingContext, KeybindingEntry, KeybindingRegistry, KeybindingSet,
+    BindingMatch, Conflict, ConflictKind, KeybindingContext, KeybindingEntry, KeybindingRegistry,
+    KeybindingSet, EMACS_SET, VIM_PRIORITY, VIM_SET,
 };
+pub use zed::KeymapError;
 
+use crate::ui::settings::KeyboardSettings;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -259,6 +264,28 @@ impl KeybindingManager {
         self.sequence_timeout = timeout;
     }
 
+    /// How long to wait for the next key of a multi-key sequence
+    pub fn sequence_timeout(&self) -> Duration {
+        self.sequence_timeout
+    }
+
+    /// Apply the user's keyboard settings to the live bindings
+    ///
+    /// The sequence timeout comes from `chord_timeout_ms`, and the vim and
+    /// emacs binding sets are added or removed to match their flags. Any
+    /// sequence in progress is dropped.
+    pub fn apply_keyboard_settings(&mut self, settings: &KeyboardSettings) {
+        self.sequence_timeout = Duration::from_millis(u64::from(settings.chord_timeout_ms));
+        if settings.vim_navigation {
+            self.registry.register_vim_navigation();
+        } else {
+            self.registry.remove_set(VIM_SET);
+        }
+        self.registry
+            .toggle_set(KeybindingSet::emacs(), settings.emacs_shortcuts);
+        self.clear_pending();
+    }
+
     /// Set the active context
     pub fn set_context(&mut self, context: KeybindingContext) {
         self.active_context = context;
@@ -283,6 +310,29 @@ impl KeybindingManager {
         &self.active_context
     }
 
+    /// Contexts consulted for a key, in the order they are tried
+    ///
+    /// The active context comes first, then the contexts beneath it on the
+    /// stack from the most recently pushed down, and Global last. The walk
+    /// down the stack stops at a context that
+    /// [blocks fallthrough](KeybindingContext::blocks_fallthrough), so e.g.
+    /// an open palette hands unhandled keys straight to Global.
+    pub fn lookup_order(&self) -> Vec<KeybindingContext> {
+        let mut order = Vec::new();
+        let stack = std::iter::once(&self.active_context).chain(self.context_stack.iter().rev());
+
+        for context in stack {
+            if !context.is_global() && !order.contains(context) {
+                order.push(context.clone());
+            }
+            if context.blocks_fallthrough() {
+                break;
+            }
+        }
+        order.push(KeybindingContext::Global);
+        order
+    }
+
     /// Handle a key event and return the matched action (if any)
     pub fn handle_key(&mut self, combination: KeyCombination) -> KeybindingResult {
         // Check for sequence timeout
@@ -302,7 +352,7 @@ impl KeybindingManager {
         // Check for matches
         let matches = self
             .registry
-            .find_matches(&current_sequence, &self.active_context);
+            .find_matches_in_order(&current_sequence, &self.lookup_order());
 
         match matches {
             BindingMatch::None => {
@@ -365,9 +415,20 @@ impl KeybindingManager {
         }
     }
 
-    /// Get all bindings for the current context
+    /// Get all bindings reachable from the current context
+    ///
+    /// Bindings are listed in [lookup order](Self::lookup_order).
     pub fn current_bindings(&self) -> Vec<&KeyBinding> {
-        self.registry.bindings_for_context(&self.active_context)
+        self.lookup_order()
+            .iter()
+            .flat_map(|context| {
+                self.registry
+                    .all_entries()
+                    .iter()
+                    .map(|e| &e.binding)
+                    .filter(move |b| b.enabled && &b.context == context)
+            })
+            .collect()
     }
 
     /// Get bindings grouped by category
@@ -577,4 +638,140 @@ mod tests {
         assert_eq!(binding.action, deserialized.action);
         assert_eq!(binding.description, deserialized.description);
     }
+
+    #[test]
+    fn test_apply_keyboard_settings() {
+        let mut manager = KeybindingManager::new();
+        let settings = KeyboardSettings {
+            chord_timeout_ms: 600,
+            vim_navigation: true,
+            ..Default::default()
+        };
+
+        manager.apply_keyboard_settings(&settings);
+        assert_eq!(manager.sequence_timeout(), Duration::from_millis(600));
+        assert!(manager.registry().has_set(VIM_SET));
+        assert!(!manager.registry().has_set(EMACS_SET));
+
+        manager.set_context(KeybindingContext::DeviceList);
+        let result = manager.handle_key(KeyCombination::new(KeyCode::Char('j'), Modifiers::NONE));
+        assert!(matches!(
+            result,
+            KeybindingResult::Match {
+                action: Action::SelectNextDevice,
+                ..
+            }
+        ));
+
+        manager.apply_keyboard_settings(&KeyboardSettings::default());
+        assert_eq!(manager.sequence_timeout(), Duration::from_millis(1500));
+        assert!(!manager.registry().has_set(VIM_SET));
+    }
+
+    #[test]
+    fn test_vim_navigation_gated_on_setting() {
+        let mut manager = KeybindingManager::new();
+        let j = || KeyCombination::new(KeyCode::Char('j'), Modifiers::NONE);
+        manager.set_context(KeybindingContext::Preview);
+        assert!(matches!(manager.handle_key(j()), KeybindingResult::NoMatch));
+
+        manager.apply_keyboard_settings(&KeyboardSettings {
+            vim_navigation: true,
+            ..Default::default()
+        });
+        assert!(matches!(
+            manager.handle_key(j()),
+            KeybindingResult::Match {
+                action: Action::SelectNext,
+                ..
+            }
+        ));
+
+        manager.push_context(KeybindingContext::TextInput);
+        assert!(matches!(manager.handle_key(j()), KeybindingResult::NoMatch));
+    }
+
+    #[test]
+    fn test_lookup_order_falls_through_to_parents_and_global() {
+        let mut manager = KeybindingManager::new();
+        assert_eq!(manager.lookup_order(), vec![KeybindingContext::Global]);
+
+        manager.set_context(KeybindingContext::DeviceList);
+        manager.push_context(KeybindingContext::Preview);
+        manager.push_context(KeybindingContext::Extracting);
+        assert_eq!(
+            manager.lookup_order(),
+            vec![
+                KeybindingContext::Extracting,
+                KeybindingContext::Preview,
+                KeybindingContext::DeviceList,
+                KeybindingContext::Global,
+            ]
+        );
+
+        // The palette takes over the keyboard, so only Global is left behind it
+        manager.push_context(KeybindingContext::CommandPalette);
+        assert_eq!(
+            manager.lookup_order(),
+            vec![KeybindingContext::CommandPalette, KeybindingContext::Global]
+        );
+    }
+
+    #[test]
+    fn test_global_binding_fires_in_unhandled_child_context() {
+        let mut registry = KeybindingRegistry::new();
+        registry.register(
+            KeyBinding::parse("Escape")
+                .unwrap()
+                .action(Action::CloseModal)
+                .description("Close"),
+        );
+        registry.register(
+            KeyBinding::parse("Enter")
+                .unwrap()
+                .action(Action::PaletteSelect)
+                .context(KeybindingContext::CommandPalette),
+        );
+        registry.register(
+            KeyBinding::parse("Enter")
+                .unwrap()
+                .action(Action::StartExtraction)
+                .context(KeybindingContext::DeviceList),
+        );
+        registry.register(
+            KeyBinding::parse("Delete")
+                .unwrap()
+                .action(Action::InvertSelection)
+                .context(KeybindingContext::DeviceList),
+        );
+
+        let mut manager = KeybindingManager::with_registry(registry);
+        manager.set_context(KeybindingContext::DeviceList);
+        manager.push_context(KeybindingContext::CommandPalette);
+
+        let key = |s: &str| KeyCombination::parse(s).unwrap();
+        assert_eq!(
+            manager.handle_key(key("Escape")).action(),
+            Some(&Action::CloseModal)
+        );
+        // The palette's own binding wins over the list's
+        assert_eq!(
+            manager.handle_key(key("Enter")).action(),
+            Some(&Action::PaletteSelect)
+        );
+        // The list beneath the palette is skipped
+        assert!(manager.handle_key(key("Delete")).is_no_match());
+
+        // A context with no bindings of its own passes keys to its parent
+        manager.pop_context();
+        manager.push_context(KeybindingContext::Progress);
+        assert_eq!(
+            manager.handle_key(key("Delete")).action(),
+            Some(&Action::InvertSelection)
+        );
+        assert_eq!(
+            manager.handle_key(key("Escape")).action(),
+            Some(&Action::CloseModal)
+        );
+    }
 }
@@ -90,6 +90,20 @@ impl KeybindingContext {
         matches!(self, KeybindingContext::Global)
     }
 
+    /// Whether keys unhandled here skip the contexts beneath it on the stack
+    ///
+    /// Text inputs, the command palette and modal dialogs take over the
+    /// keyboard, so a plain `j` typed into them must not reach the list
+    /// underneath. Global bindings still apply.
+    pub fn blocks_fallthrough(&self) -> bool {
+        matches!(
+            self,
+            KeybindingContext::TextInput
+                | KeybindingContext::CommandPalette
+                | KeybindingContext::Modal
+        )
+    }
+
     /// Get the display name
     pub fn display_name(&self) -> &str {
         match self {
@@ -217,6 +231,110 @@ impl Default for KeybindingSet {
     }
 }
 
+/// Name of the vim-style navigation set
+pub const VIM_SET: &str = "vim";
+
+/// Priority of vim bindings, above the defaults they share a context with
+pub const VIM_PRIORITY: i32 = 10;
+
+/// Name of the emacs-style shortcut set
+pub const EMACS_SET: &str = "emacs";
+
+impl KeybindingSet {
+    /// Vim-style navigation: `j`/`k` move through lists and `h`/`l` move
+    /// focus between panels
+    ///
+    /// The bindings only exist in list and panel contexts, so the letters
+    /// still type normally in text inputs and the command palette.
+    pub fn vim() -> Self {
+        let key = |c: char, action: Action, description: &str, context: KeybindingContext| {
+            KeyBinding::new(KeyCode::Char(c), Modifiers::NONE)
+                .action(action)
+                .description(description)
+                .context(context)
+                .priority(VIM_PRIORITY)
+        };
+
+        let mut set = Self::new(VIM_SET)
+            .description("Vim-style navigation")
+            .add(key(
+                'j',
+                Action::SelectNextDevice,
+                "Next device",
+                KeybindingContext::DeviceList,
+            ))
+            .add(key(
+                'k',
+                Action::SelectPreviousDevice,
+                "Previous device",
+                KeybindingContext::DeviceList,
+            ))
+            .add(key(
+                'j',
+                Action::SelectNext,
+                "Select next item",
+                KeybindingContext::Preview,
+            ))
+            .add(key(
+                'k',
+                Action::SelectPrevious,
+                "Select previous item",
+                KeybindingContext::Preview,
+            ));
+        for context in [
+            KeybindingContext::DeviceList,
+            KeybindingContext::Preview,
+            KeybindingContext::Progress,
+        ] {
+            set = set
+                .add(key(
+                    'h',
+                    Action::FocusPreviousPanel,
+                    "Focus previous panel",
+                    context.clone(),
+                ))
+                .add(key(
+                    'l',
+                    Action::FocusNextPanel,
+                    "Focus next panel",
+                    context,
+                ));
+        }
+        set
+    }
+
+    /// Emacs-style list movement: `Ctrl+N`/`Ctrl+P` in the device list and
+    /// command palette
+    pub fn emacs() -> Self {
+        Self::new(EMACS_SET)
+            .description("Emacs-style shortcuts")
+            .add(
+                KeyBinding::new(KeyCode::Char('n'), Modifiers::CTRL)
+                    .action(Action::SelectNextDevice)
+                    .description("Next device")
+                    .context(KeybindingContext::DeviceList),
+            )
+            .add(
+                KeyBinding::new(KeyCode::Char('p'), Modifiers::CTRL)
+                    .action(Action::SelectPreviousDevice)
+                    .description("Previous device")
+                    .context(KeybindingContext::DeviceList),
+            )
+            .add(
+                KeyBinding::new(KeyCode::Char('n'), Modifiers::CTRL)
+                    .action(Action::PaletteNext)
+                    .description("Next command")
+                    .context(KeybindingContext::CommandPalette),
+            )
+            .add(
+                KeyBinding::new(KeyCode::Char('p'), Modifiers::CTRL)
+                    .action(Action::PalettePrevious)
+                    .description("Previous command")
+                    .context(KeybindingContext::CommandPalette),
+            )
+    }
+}
+
 // =============================================================================
 // BindingMatch
 // =============================================================================
@@ -262,6 +380,70 @@ impl BindingMatch {
     }
 }
 
+// =============================================================================
+// Conflict
+// =============================================================================
+
+/// How the bindings in a [`Conflict`] compete
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    /// The same sequence is mapped to more than one action
+    Duplicate,
+
+    /// A shorter binding fires before a longer chord starting with it can complete
+    Prefix,
+}
+
+/// Bindings in one context that compete for the same keys
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    /// What kind of conflict this is
+    pub kind: ConflictKind,
+
+    /// Context shared by the competing bindings
+    pub context: KeybindingContext,
+
+    /// The contested sequence (the shorter binding for prefix conflicts)
+    pub sequence: KeySequence,
+
+    /// The competing bindings, highest priority first
+    pub bindings: Vec<KeyBinding>,
+}
+
+impl Conflict {
+    /// The competing actions and their priorities, highest priority first
+    pub fn actions(&self) -> Vec<(&Action, i32)> {
+        self.bindings
+            .iter()
+            .map(|b| (&b.action, b.priority))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let actions = self
+            .bindings
+            .iter()
+            .map(|b| format!("{:?} ({}, priority {})", b.action, b.display(), b.priority))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.kind {
+            ConflictKind::Duplicate => write!(
+                f,
+                "{} is bound more than once in {}: {}",
+                self.sequence, self.context, actions
+            ),
+            ConflictKind::Prefix => write!(
+                f,
+                "{} shadows longer chords in {}: {}",
+                self.sequence, self.context, actions
+            ),
+        }
+    }
+}
+
 // =============================================================================
 // KeybindingRegistry
 // =============================================================================
@@ -576,6 +758,12 @@ impl KeybindingRegistry {
         self.dirty = true;
     }
 
+    /// Register a prepared entry, keeping its source and user-defined flag
+    pub fn register_entry(&mut self, entry: KeybindingEntry) {
+        self.bindings.push(entry);
+        self.dirty = true;
+    }
+
     /// Register multiple bindings at once
     pub fn register_all(&mut self, bindings: impl IntoIterator<Item = KeyBinding>) {
         for binding in bindings {
@@ -583,10 +771,38 @@ impl KeybindingRegistry {
         }
     }
 
-    /// Register a keybinding set
+    /// Register a keybinding set, using the set's name as the bindings' source
     pub fn register_set(&mut self, set: KeybindingSet) {
         for binding in set.bindings {
-            self.register(binding);
+            self.register_entry(KeybindingEntry::new(binding).with_source(set.name.clone()));
+        }
+    }
+
+    /// Register the vim-style navigation set, unless it already is
+    ///
+    /// See [`KeybindingSet::vim`]; the manager registers it when
+    /// `KeyboardSettings::vim_navigation` is on.
+    pub fn register_vim_navigation(&mut self) {
+        self.toggle_set(KeybindingSet::vim(), true);
+    }
+
+    /// Check if any bindings from the set `name` are registered
+    pub fn has_set(&self, name: &str) -> bool {
+        self.bindings.iter().any(|e| e.source == name)
+    }
+
+    /// Remove every binding registered from the set `name`
+    pub fn remove_set(&mut self, name: &str) {
+        self.bindings.retain(|e| e.source != name);
+        self.dirty = true;
+    }
+
+    /// Register `set` when `enabled` (unless it already is), otherwise remove it
+    pub fn toggle_set(&mut self, set: KeybindingSet, enabled: bool) {
+        if !enabled {
+            self.remove_set(&set.name);
+        } else if !self.has_set(&set.name) {
+            self.register_set(set);
         }
     }
 
@@ -646,6 +862,39 @@ impl KeybindingRegistry {
         &mut self,
         sequence: &KeySequence,
         context: &KeybindingContext,
+    ) -> BindingMatch {
+        self.match_where(sequence, |binding_context| {
+            binding_context.is_global() || binding_context.is_compatible_with(context)
+        })
+    }
+
+    /// Find matches for a key sequence, trying each context in turn
+    ///
+    /// Only bindings registered for exactly that context are considered at
+    /// each step, so Global must be listed to be consulted. The first context
+    /// with any binding for the sequence handles it, even if that is only a
+    /// partial chord, so a chord in an inner context is never cut short by a
+    /// shorter binding further out.
+    pub fn find_matches_in_order(
+        &mut self,
+        sequence: &KeySequence,
+        contexts: &[KeybindingContext],
+    ) -> BindingMatch {
+        for context in contexts {
+            let matches = self.match_where(sequence, |binding_context| binding_context == context);
+            if !matches!(matches, BindingMatch::None) {
+                return matches;
+            }
+        }
+        BindingMatch::None
+    }
+
+    /// Match a key sequence against the enabled bindings whose context passes
+    /// `in_context`
+    fn match_where(
+        &mut self,
+        sequence: &KeySequence,
+        in_context: impl Fn(&KeybindingContext) -> bool,
     ) -> BindingMatch {
         self.rebuild_index_if_needed();
 
@@ -678,9 +927,7 @@ impl KeybindingRegistry {
                 continue;
             }
 
-            if !entry.binding.context.is_global()
-                && !entry.binding.context.is_compatible_with(context)
-            {
+            if !in_context(&entry.binding.context) {
                 continue;
             }
 
@@ -726,34 +973,78 @@ impl KeybindingRegistry {
         self.dirty = false;
     }
 
-    /// Find conflicts (multiple bindings with same sequence in compatible contexts)
-    pub fn find_conflicts(&mut self) -> Vec<(KeySequence, Vec<KeyBinding>)> {
-        self.rebuild_index_if_needed();
+    /// Find conflicting bindings within each context
+    ///
+    /// Enabled bindings are grouped by (context, sequence). A sequence mapped
+    /// to more than one distinct action is a [`ConflictKind::Duplicate`]; a
+    /// sequence that is a strict prefix of a longer chord in the same context
+    /// is a [`ConflictKind::Prefix`], since it fires before the chord can
+    /// complete. Bindings in different contexts (including Global) never
+    /// conflict with each other.
+    pub fn find_conflicts(&self) -> Vec<Conflict> {
+        let mut groups: HashMap<(KeybindingContext, KeySequence), Vec<&KeyBinding>> =
+            HashMap::new();
+
+        for entry in self.bindings.iter().filter(|e| e.binding.enabled) {
+            let key = (
+                entry.binding.context.clone(),
+                entry.binding.sequence.clone(),
+            );
+            groups.entry(key).or_default().push(&entry.binding);
+        }
+
+        let sorted = |bindings: Vec<&KeyBinding>| {
+            let mut bindings: Vec<KeyBinding> = bindings.into_iter().cloned().collect();
+            bindings.sort_by(|a, b| b.priority.cmp(&a.priority));
+            bindings
+        };
 
         let mut conflicts = Vec::new();
-        let mut seen: HashMap<(KeySequence, KeybindingContext), Vec<KeyBinding>> = HashMap::new();
 
-        for entry in &self.bindings {
-            if !entry.binding.enabled {
-                continue;
+        for ((context, sequence), bindings) in &groups {
+            let mut actions: Vec<&Action> = Vec::new();
+            for binding in bindings {
+                if !actions.contains(&&binding.action) {
+                    actions.push(&binding.action);
+                }
             }
 
-            let key = (
-                entry.binding.sequence.clone(),
-                entry.binding.context.clone(),
-            );
-
-            seen.entry(key)
-                .or_insert_with(Vec::new)
-                .push(entry.binding.clone());
-        }
+            if actions.len() > 1 {
+                conflicts.push(Conflict {
+                    kind: ConflictKind::Duplicate,
+                    context: context.clone(),
+                    sequence: sequence.clone(),
+                    bindings: sorted(bindings.clone()),
+                });
+            }
 
-        for ((seq, _ctx), bindings) in seen {
-            if bindings.len() > 1 {
-                conflicts.push((seq, bindings));
+            let shadowed: Vec<&KeyBinding> = groups
+                .iter()
+                .filter(|((ctx, seq), _)| {
+                    ctx == context
+                        && seq.len() > sequence.len()
+                        && seq.starts_with_sequence(sequence)
+                })
+                .flat_map(|(_, chords)| chords.iter().copied())
+                .collect();
+
+            if !shadowed.is_empty() {
+                conflicts.push(Conflict {
+                    kind: ConflictKind::Prefix,
+                    context: context.clone(),
+                    sequence: sequence.clone(),
+                    bindings: sorted(bindings.iter().copied().chain(shadowed).collect()),
+                });
             }
         }
 
+        conflicts.sort_by(|a, b| {
+            (a.context.display_name(), a.sequence.display())
+                .cmp(&(b.context.display_name(), b.sequence.display()))
+                .then_with(|| {
+                    (a.kind == ConflictKind::Prefix).cmp(&(b.kind == ConflictKind::Prefix))
+                })
+        });
         conflicts
     }
 
@@ -998,6 +1289,107 @@ mod tests {
         assert_eq!(registry.len(), 2);
     }
 
+    #[test]
+    fn test_find_conflicts_duplicate() {
+        let mut registry = KeybindingRegistry::new();
+
+        registry.register(
+            KeyBinding::new(KeyCode::Char('e'), Modifiers::CTRL).action(Action::StartExtraction),
+        );
+        registry.register(
+            KeyBinding::new(KeyCode::Char('e'), Modifiers::CTRL)
+                .action(Action::OpenSettings)
+                .priority(5),
+        );
+
+        let conflicts = registry.find_conflicts();
+        assert_eq!(conflicts.len(), 1);
+
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.kind, ConflictKind::Duplicate);
+        assert_eq!(conflict.context, KeybindingContext::Global);
+        assert_eq!(conflict.sequence, KeySequence::parse("Ctrl+E").unwrap());
+        assert_eq!(
+            conflict.actions(),
+            vec![(&Action::OpenSettings, 5), (&Action::StartExtraction, 0)]
+        );
+    }
+
+    #[test]
+    fn test_find_conflicts_ignores_other_contexts() {
+        let mut registry = KeybindingRegistry::new();
+
+        registry
+            .register(KeyBinding::new(KeyCode::Escape, Modifiers::NONE).action(Action::CloseModal));
+        registry.register(
+            KeyBinding::new(KeyCode::Escape, Modifiers::NONE)
+                .action(Action::StopExtraction)
+                .context(KeybindingContext::Extracting),
+        );
+        registry.register(
+            KeyBinding::new(KeyCode::Down, Modifiers::NONE)
+                .action(Action::SelectNextDevice)
+                .context(KeybindingContext::DeviceList),
+        );
+        registry.register(
+            KeyBinding::new(KeyCode::Down, Modifiers::NONE)
+                .action(Action::PaletteNext)
+                .context(KeybindingContext::CommandPalette),
+        );
+
+        // The same action twice and disabled bindings are not conflicts either
+        registry
+            .register(KeyBinding::new(KeyCode::F5, Modifiers::NONE).action(Action::RefreshDevices));
+        registry
+            .register(KeyBinding::new(KeyCode::F5, Modifiers::NONE).action(Action::RefreshDevices));
+        registry.register(
+            KeyBinding::new(KeyCode::F5, Modifiers::NONE)
+                .action(Action::Quit)
+                .enabled(false),
+        );
+
+        assert!(registry.find_conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_find_conflicts_prefix() {
+        let mut registry = KeybindingRegistry::new();
+
+        registry.register(
+            KeyBinding::new(KeyCode::Char('k'), Modifiers::CTRL).action(Action::OpenCommandPalette),
+        );
+        registry.register(
+            KeyBinding::parse("Ctrl+K Ctrl+T")
+                .unwrap()
+                .action(Action::ToggleTheme),
+        );
+        // A chord in another context is not shadowed
+        registry.register(
+            KeyBinding::parse("Ctrl+K Ctrl+S")
+                .unwrap()
+                .action(Action::OpenSettings)
+                .context(KeybindingContext::Settings),
+        );
+
+        let conflicts = registry.find_conflicts();
+        assert_eq!(conflicts.len(), 1);
+
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.kind, ConflictKind::Prefix);
+        assert_eq!(conflict.sequence.len(), 1);
+        assert_eq!(conflict.bindings.len(), 2);
+        assert!(conflict.to_string().contains("ToggleTheme"));
+    }
+
+    #[test]
+    fn test_default_bindings_have_no_conflicts() {
+        let mut registry = KeybindingRegistry::new();
+        registry.register_defaults();
+
+        let conflicts = registry.find_conflicts();
+        assert!(conflicts.is_empty(), "{:?}", conflicts);
+    }
+
     #[test]
     fn test_context_serialization() {
         let context = KeybindingContext::DeviceList;
@@ -1024,4 +1416,72 @@ mod tests {
 
         assert_eq!(entry.binding.action, deserialized.binding.action);
     }
+
+    #[test]
+    fn test_toggle_set() {
+        let mut registry = KeybindingRegistry::new();
+        registry.register_defaults();
+        let defaults = registry.len();
+
+        registry.toggle_set(KeybindingSet::emacs(), true);
+        assert!(registry.has_set(EMACS_SET));
+        assert!(registry
+            .all_entries()
+            .iter()
+            .filter(|e| e.source == EMACS_SET)
+            .all(|e| !e.is_user_defined));
+
+        // Enabling twice doesn't register the set again
+        let with_emacs = registry.len();
+        registry.toggle_set(KeybindingSet::emacs(), true);
+        assert_eq!(registry.len(), with_emacs);
+
+        let ctrl_n = KeySequence::single(KeyCode::Char('n'), Modifiers::CTRL);
+        let matched = registry.find_matches(&ctrl_n, &KeybindingContext::DeviceList);
+        assert_eq!(
+            matched.binding().map(|b| &b.action),
+            Some(&Action::SelectNextDevice)
+        );
+
+        registry.toggle_set(KeybindingSet::emacs(), false);
+        assert!(!registry.has_set(EMACS_SET));
+        assert_eq!(registry.len(), defaults);
+    }
+
+    #[test]
+    fn test_vim_navigation_in_list_context_only() {
+        let mut registry = KeybindingRegistry::new();
+        registry.register_defaults();
+        let j = KeySequence::single(KeyCode::Char('j'), Modifiers::NONE);
+        assert!(!registry
+            .find_matches(&j, &KeybindingContext::Preview)
+            .is_match());
+
+        registry.register_vim_navigation();
+        registry.register_vim_navigation();
+        assert_eq!(
+            registry
+                .all_entries()
+                .iter()
+                .filter(|e| e.source == VIM_SET)
+                .count(),
+            KeybindingSet::vim().bindings.len()
+        );
+
+        let matched = registry.find_matches(&j, &KeybindingContext::Preview);
+        assert_eq!(
+            matched.binding().map(|b| &b.action),
+            Some(&Action::SelectNext)
+        );
+        assert_eq!(matched.binding().unwrap().priority, VIM_PRIORITY);
+
+        // Letters keep typing in inputs
+        for context in [
+            KeybindingContext::TextInput,
+            KeybindingContext::CommandPalette,
+        ] {
+            assert!(!registry.find_matches(&j, &context).is_match());
+        }
+        assert!(registry.find_conflicts().is_empty());
+    }
 }
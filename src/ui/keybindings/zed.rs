@@ -0,0 +1,500 @@
+//! Zed Keymap Format - Import/Export of Zed-style Keymaps
+//!
+//! Zed stores keymaps as a JSON array of sections, each with an optional
+//! context and a map from keystrokes to action names:
+//!
+//! ```json
+//! [
+//!   { "bindings": { "ctrl-e": "StartExtraction" } },
+//!   { "context": "DeviceList", "bindings": { "enter": "ShowDeviceDetails" } }
+//! ]
+//! ```
+//!
+//! Sections without a context hold global bindings. Keystrokes use Zed's
+//! lowercase `ctrl-shift-p` notation with spaces between chord steps, and a
+//! `null` action unbinds the key. Descriptions, priorities and disabled
+//! bindings have no place in the format and are not exported.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use photo_extraction_tool::ui::keybindings::KeybindingRegistry;
+//!
+//! let mut registry = KeybindingRegistry::new();
+//! registry.register_defaults();
+//!
+//! let json = registry.to_zed_json();
+//! let imported = KeybindingRegistry::from_zed_json(&json).unwrap();
+//! assert_eq!(imported.len(), registry.len());
+//! ```
+
+use super::actions::Action;
+use super::keys::{KeyCode, KeyCombination, KeySequence, Modifiers};
+use super::registry::{KeybindingContext, KeybindingEntry, KeybindingRegistry};
+use super::KeyBinding;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Source recorded on entries imported from a Zed keymap
+const ZED_SOURCE: &str = "zed";
+
+// =============================================================================
+// KeymapError
+// =============================================================================
+
+/// Errors that can occur when importing a Zed keymap
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum KeymapError {
+    /// The document is not a valid keymap
+    #[error("Invalid keymap JSON: {0}")]
+    Json(String),
+
+    /// One or more action names are not known
+    #[error("Unknown actions in keymap: {}", .0.join(", "))]
+    UnknownActions(Vec<String>),
+
+    /// One or more keystrokes could not be parsed
+    #[error("Invalid keystrokes in keymap: {}", .0.join(", "))]
+    InvalidKeystrokes(Vec<String>),
+}
+
+// =============================================================================
+// Keymap Sections
+// =============================================================================
+
+/// One `{ "context": ..., "bindings": { ... } }` section of a Zed keymap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeymapSection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+
+    #[serde(default)]
+    bindings: Map<String, Value>,
+}
+
+impl KeybindingRegistry {
+    /// Export enabled bindings as a Zed-style keymap
+    ///
+    /// Global bindings come first in a section without a context, followed
+    /// by one section per context in registration order.
+    pub fn to_zed_json(&self) -> String {
+        let mut sections = Vec::new();
+
+        let mut contexts: Vec<&KeybindingContext> = vec![&KeybindingContext::Global];
+        for entry in self.all_entries() {
+            if !contexts.contains(&&entry.binding.context) {
+                contexts.push(&entry.binding.context);
+            }
+        }
+
+        for context in contexts {
+            let bindings: Map<String, Value> = self
+                .all_entries()
+                .iter()
+                .map(|e| &e.binding)
+                .filter(|b| b.enabled && &b.context == context)
+                .map(|b| {
+                    let action = match &b.action {
+                        Action::None => Value::Null,
+                        action => Value::String(action.name()),
+                    };
+                    (sequence_to_zed(&b.sequence), action)
+                })
+                .collect();
+
+            if bindings.is_empty() {
+                continue;
+            }
+
+            sections.push(KeymapSection {
+                context: (!context.is_global()).then(|| context_to_zed(context)),
+                bindings,
+            });
+        }
+
+        serde_json::to_string_pretty(&sections).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Build a registry from a Zed-style keymap
+    ///
+    /// Every unknown action name or unparseable keystroke is collected and
+    /// reported in a single error instead of being dropped.
+    pub fn from_zed_json(json: &str) -> Result<Self, KeymapError> {
+        let sections: Vec<KeymapSection> =
+            serde_json::from_str(json).map_err(|e| KeymapError::Json(e.to_string()))?;
+
+        let mut bindings = Vec::new();
+        let mut unknown_actions = Vec::new();
+        let mut invalid_keystrokes = Vec::new();
+
+        for section in sections {
+            let context = section
+                .context
+                .as_deref()
+                .map(context_from_zed)
+                .unwrap_or_default();
+
+            for (keystrokes, value) in section.bindings {
+                let action = match &value {
+                    Value::Null => Some(Action::None),
+                    Value::String(name) => Action::from_name(name),
+                    _ => None,
+                };
+
+                let sequence = sequence_from_zed(&keystrokes);
+                if sequence.is_none() {
+                    invalid_keystrokes.push(keystrokes.clone());
+                }
+
+                match (action, sequence) {
+                    (Some(action), Some(sequence)) => bindings.push(
+                        KeyBinding::from_sequence(sequence)
+                            .description(action.description())
+                            .action(action)
+                            .context(context.clone()),
+                    ),
+                    (None, _) => unknown_actions.push(match value {
+                        Value::String(name) => name,
+                        other => other.to_string(),
+                    }),
+                    _ => {}
+                }
+            }
+        }
+
+        if !unknown_actions.is_empty() {
+            return Err(KeymapError::UnknownActions(unknown_actions));
+        }
+        if !invalid_keystrokes.is_empty() {
+            return Err(KeymapError::InvalidKeystrokes(invalid_keystrokes));
+        }
+
+        let mut registry = KeybindingRegistry::new();
+        for binding in bindings {
+            registry.register_entry(KeybindingEntry::user_defined(binding).with_source(ZED_SOURCE));
+        }
+        Ok(registry)
+    }
+}
+
+// =============================================================================
+// Keystroke Conversion
+// =============================================================================
+
+/// Zed names for non-character keys
+const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::F1, "f1"),
+    (KeyCode::F2, "f2"),
+    (KeyCode::F3, "f3"),
+    (KeyCode::F4, "f4"),
+    (KeyCode::F5, "f5"),
+    (KeyCode::F6, "f6"),
+    (KeyCode::F7, "f7"),
+    (KeyCode::F8, "f8"),
+    (KeyCode::F9, "f9"),
+    (KeyCode::F10, "f10"),
+    (KeyCode::F11, "f11"),
+    (KeyCode::F12, "f12"),
+    (KeyCode::Up, "up"),
+    (KeyCode::Down, "down"),
+    (KeyCode::Left, "left"),
+    (KeyCode::Right, "right"),
+    (KeyCode::Home, "home"),
+    (KeyCode::End, "end"),
+    (KeyCode::PageUp, "pageup"),
+    (KeyCode::PageDown, "pagedown"),
+    (KeyCode::Backspace, "backspace"),
+    (KeyCode::Delete, "delete"),
+    (KeyCode::Insert, "insert"),
+    (KeyCode::Enter, "enter"),
+    (KeyCode::Tab, "tab"),
+    (KeyCode::Space, "space"),
+    (KeyCode::Escape, "escape"),
+    (KeyCode::NumpadEnter, "numpad_enter"),
+    (KeyCode::NumpadAdd, "numpad_add"),
+    (KeyCode::NumpadSubtract, "numpad_subtract"),
+    (KeyCode::NumpadMultiply, "numpad_multiply"),
+    (KeyCode::NumpadDivide, "numpad_divide"),
+    (KeyCode::Numpad0, "numpad0"),
+    (KeyCode::Numpad1, "numpad1"),
+    (KeyCode::Numpad2, "numpad2"),
+    (KeyCode::Numpad3, "numpad3"),
+    (KeyCode::Numpad4, "numpad4"),
+    (KeyCode::Numpad5, "numpad5"),
+    (KeyCode::Numpad6, "numpad6"),
+    (KeyCode::Numpad7, "numpad7"),
+    (KeyCode::Numpad8, "numpad8"),
+    (KeyCode::Numpad9, "numpad9"),
+    (KeyCode::MediaPlayPause, "media_play_pause"),
+    (KeyCode::MediaStop, "media_stop"),
+    (KeyCode::MediaNext, "media_next"),
+    (KeyCode::MediaPrevious, "media_previous"),
+    (KeyCode::VolumeUp, "volume_up"),
+    (KeyCode::VolumeDown, "volume_down"),
+    (KeyCode::VolumeMute, "volume_mute"),
+    (KeyCode::PrintScreen, "printscreen"),
+    (KeyCode::ScrollLock, "scrolllock"),
+    (KeyCode::Pause, "pause"),
+    (KeyCode::Menu, "menu"),
+];
+
+/// Format a combination as a Zed keystroke, e.g. "ctrl-shift-p"
+fn combination_to_zed(combination: &KeyCombination) -> String {
+    let modifiers = combination.modifiers;
+    let mut keystroke = String::new();
+
+    for (enabled, prefix) in [
+        (modifiers.ctrl, "ctrl-"),
+        (modifiers.alt, "alt-"),
+        (modifiers.shift, "shift-"),
+        (modifiers.meta, "cmd-"),
+    ] {
+        if enabled {
+            keystroke.push_str(prefix);
+        }
+    }
+
+    match &combination.key {
+        KeyCode::Char(c) => keystroke.push(c.to_ascii_lowercase()),
+        key => keystroke.push_str(
+            KEY_NAMES
+                .iter()
+                .find(|(code, _)| code == key)
+                .map(|(_, name)| *name)
+                .unwrap_or("unknown"),
+        ),
+    }
+
+    keystroke
+}
+
+/// Parse a Zed keystroke such as "ctrl-shift-p" or "ctrl--"
+fn combination_from_zed(keystroke: &str) -> Option<KeyCombination> {
+    let mut modifiers = Modifiers::NONE;
+    let mut rest = keystroke;
+
+    // A trailing "-" is the minus key, not a separator
+    while let Some((prefix, tail)) = rest.split_once('-') {
+        if tail.is_empty() {
+            break;
+        }
+        match prefix.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => modifiers.ctrl = true,
+            "alt" | "option" => modifiers.alt = true,
+            "shift" => modifiers.shift = true,
+            "cmd" | "super" | "win" | "meta" => modifiers.meta = true,
+            _ => return None,
+        }
+        rest = tail;
+    }
+
+    let mut chars = rest.chars();
+    let key = match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_whitespace() => KeyCode::Char(c.to_ascii_lowercase()),
+        _ => {
+            let name = rest.to_ascii_lowercase();
+            KEY_NAMES
+                .iter()
+                .find(|(_, n)| *n == name)
+                .map(|(code, _)| *code)?
+        }
+    };
+
+    Some(KeyCombination::new(key, modifiers))
+}
+
+fn sequence_to_zed(sequence: &KeySequence) -> String {
+    sequence
+        .iter()
+        .map(combination_to_zed)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn sequence_from_zed(keystrokes: &str) -> Option<KeySequence> {
+    let combinations = keystrokes
+        .split_whitespace()
+        .map(combination_from_zed)
+        .collect::<Option<Vec<_>>>()?;
+
+    (!combinations.is_empty()).then(|| KeySequence::new(combinations))
+}
+
+// =============================================================================
+// Context Conversion
+// =============================================================================
+
+fn context_to_zed(context: &KeybindingContext) -> String {
+    match context {
+        KeybindingContext::Custom(name) => name.clone(),
+        other => format!("{:?}", other),
+    }
+}
+
+fn context_from_zed(name: &str) -> KeybindingContext {
+    match name {
+        "Global" => KeybindingContext::Global,
+        "DeviceList" => KeybindingContext::DeviceList,
+        "Preview" => KeybindingContext::Preview,
+        "Progress" => KeybindingContext::Progress,
+        "CommandPalette" => KeybindingContext::CommandPalette,
+        "Modal" => KeybindingContext::Modal,
+        "Settings" => KeybindingContext::Settings,
+        "TextInput" => KeybindingContext::TextInput,
+        "Extracting" => KeybindingContext::Extracting,
+        other => KeybindingContext::Custom(other.to_string()),
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zed_keys(registry: &KeybindingRegistry) -> Vec<(String, KeybindingContext, Action)> {
+        let mut keys: Vec<_> = registry
+            .all_bindings()
+            .into_iter()
+            .map(|b| {
+                (
+                    sequence_to_zed(&b.sequence),
+                    b.context.clone(),
+                    b.action.clone(),
+                )
+            })
+            .collect();
+        keys.sort_by(|a, b| (&a.0, a.1.display_name()).cmp(&(&b.0, b.1.display_name())));
+        keys
+    }
+
+    #[test]
+    fn test_keystroke_conversion() {
+        let cases = [
+            ("Ctrl+E", "ctrl-e"),
+            ("Ctrl+Shift+P", "ctrl-shift-p"),
+            ("Ctrl+-", "ctrl--"),
+            ("Shift+?", "shift-?"),
+            ("F11", "f11"),
+            ("Escape", "escape"),
+            ("Ctrl+K Ctrl+C", "ctrl-k ctrl-c"),
+        ];
+
+        for (ours, zed) in cases {
+            let sequence = KeySequence::parse(ours).unwrap();
+            assert_eq!(sequence_to_zed(&sequence), zed);
+            assert_eq!(sequence_from_zed(zed), Some(sequence));
+        }
+
+        assert_eq!(sequence_from_zed("hyper-x"), None);
+        assert_eq!(sequence_from_zed("ctrl-nosuchkey"), None);
+        assert_eq!(sequence_from_zed(""), None);
+    }
+
+    #[test]
+    fn test_export_shape() {
+        let mut registry = KeybindingRegistry::new();
+        registry.register(
+            KeyBinding::new(KeyCode::Char('e'), Modifiers::CTRL).action(Action::StartExtraction),
+        );
+        registry.register(
+            KeyBinding::new(KeyCode::Enter, Modifiers::NONE)
+                .action(Action::ShowDeviceDetails)
+                .context(KeybindingContext::DeviceList),
+        );
+        registry.register(
+            KeyBinding::new(KeyCode::F9, Modifiers::NONE)
+                .action(Action::Quit)
+                .enabled(false),
+        );
+
+        let value: Value = serde_json::from_str(&registry.to_zed_json()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([
+                { "bindings": { "ctrl-e": "StartExtraction" } },
+                { "context": "DeviceList", "bindings": { "enter": "ShowDeviceDetails" } }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_defaults_roundtrip() {
+        let mut defaults = KeybindingRegistry::new();
+        defaults.register_defaults();
+
+        let imported = KeybindingRegistry::from_zed_json(&defaults.to_zed_json()).unwrap();
+
+        assert_eq!(imported.len(), defaults.len());
+        assert_eq!(imported.count_by_context(), defaults.count_by_context());
+        assert_eq!(zed_keys(&imported), zed_keys(&defaults));
+
+        let extraction = imported.bindings_for_action(&Action::StartExtraction);
+        assert_eq!(extraction.len(), 1);
+        assert_eq!(extraction[0].display(), "Ctrl+E");
+        assert!(imported
+            .all_entries()
+            .iter()
+            .all(|e| e.source == ZED_SOURCE));
+    }
+
+    #[test]
+    fn test_import_context_and_null() {
+        let json = r#"[
+            { "bindings": { "ctrl-k ctrl-t": "ToggleTheme", "f1": null } },
+            { "context": "Extracting", "bindings": { "space": "TogglePause" } },
+            { "context": "Gallery", "bindings": { "g": "custom::open_gallery" } }
+        ]"#;
+
+        let registry = KeybindingRegistry::from_zed_json(json).unwrap();
+        assert_eq!(registry.len(), 4);
+
+        let chord = registry.bindings_for_action(&Action::ToggleTheme);
+        assert_eq!(chord[0].sequence.len(), 2);
+        assert!(chord[0].context.is_global());
+
+        assert_eq!(
+            registry.bindings_for_action(&Action::TogglePause)[0].context,
+            KeybindingContext::Extracting
+        );
+        assert_eq!(registry.bindings_for_action(&Action::None).len(), 1);
+
+        let custom = Action::Custom("open_gallery".to_string());
+        assert_eq!(
+            registry.bindings_for_action(&custom)[0].context,
+            KeybindingContext::Custom("Gallery".to_string())
+        );
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let json = r#"[
+            { "bindings": { "ctrl-e": "StartExtraction", "ctrl-x": "editor::Cut" } },
+            { "context": "Preview", "bindings": { "ctrl-y": "Redo", "ctrl-z": ["Undo", {}] } }
+        ]"#;
+        let err = KeybindingRegistry::from_zed_json(json).unwrap_err();
+        assert_eq!(
+            err,
+            KeymapError::UnknownActions(vec![
+                "editor::Cut".to_string(),
+                "Redo".to_string(),
+                r#"["Undo",{}]"#.to_string(),
+            ])
+        );
+        assert!(err.to_string().contains("editor::Cut"));
+
+        let err = KeybindingRegistry::from_zed_json(r#"[{ "bindings": { "hyper-q": "Quit" } }]"#)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            KeymapError::InvalidKeystrokes(vec!["hyper-q".to_string()])
+        );
+
+        assert!(matches!(
+            KeybindingRegistry::from_zed_json("{ not json"),
+            Err(KeymapError::Json(_))
+        ));
+    }
+}
@@ -16,6 +16,7 @@
 //! - [`controller`] - Extraction controller with async operations and cancellation
 //! - [`device_monitor`] - Device hot-plug detection and state tracking
 //! - [`preview`] - Thumbnail generation and preview management
+//! - [`status`] - Status bar model fed by device and extraction events
 //!
 //! ## Zed-Style UI System
 //! - [`theme`] - Theming system with colors, typography, spacing, and presets
@@ -197,6 +198,7 @@ pub mod controller;
 pub mod device_monitor;
 pub mod events;
 pub mod preview;
+pub mod status;
 
 // Zed-style UI modules
 pub mod commands;
@@ -213,7 +215,7 @@ pub use controller::{
 };
 
 pub use device_monitor::{
-    DeviceMonitor, DeviceState, DeviceStateChecker, MonitorConfig, MonitoredDevice,
+    DeviceMonitor, DeviceState, DeviceStateChecker, EventDebouncer, MonitorConfig, MonitoredDevice,
 };
 
 pub use events::{
@@ -222,28 +224,41 @@ pub use events::{
 };
 
 pub use preview::{
-    CacheStats, PreviewItem, PreviewManager, Thumbnail, ThumbnailCache, ThumbnailConfig,
-    ThumbnailGenerator, ThumbnailResult,
+    decode_thumbnail, decode_thumbnail_unrotated, prefetch_range, video_thumbnail, CacheStats,
+    DecodeError, DecodedThumbnailCache, Orientation, PreviewItem, PreviewManager, RgbaImage,
+    Thumbnail, ThumbnailCache, ThumbnailConfig, ThumbnailDecoder, ThumbnailGenerator, ThumbnailKey,
+    ThumbnailResult, VideoSource,
 };
 
+pub use status::{ExtractionStatus, StatusModel};
+
 // Re-export main types from Zed-style modules
-pub use theme::{Theme, ThemeBuilder, ThemeManager, ThemeMode, UiStyles};
+pub use theme::{
+    ContrastIssue, Theme, ThemeBuilder, ThemeError, ThemeManager, ThemeMode, UiStyles,
+};
 
 pub use keybindings::{KeyBinding, KeybindingContext, KeybindingManager, KeybindingResult};
 
-pub use commands::{Command, CommandMatch, CommandPalette, CommandRegistry, FuzzyMatcher};
+pub use commands::{
+    Command, CommandMatch, CommandPalette, CommandRegistry, FuzzyMatcher, MatchField,
+    PaletteSection,
+};
 
 pub use panels::{
-    Panel, PanelConfig, PanelId, PanelLayout, PanelManager, PanelPosition, PanelSize,
+    FloatRect, Panel, PanelConfig, PanelId, PanelLayout, PanelManager, PanelPosition, PanelSize,
+    PanelSnapshot, SavedLayout,
 };
 
-pub use settings::{SettingsError, SettingsManager, UiSettings};
+pub use settings::{ChangedSetting, SettingsError, SettingsManager, UiSettings};
 
 pub use components::{
-    ButtonState, ButtonVariant, FocusDirection, FocusManager, InputState, ListItem, ListState,
-    ProgressState, Widget, WidgetId, WidgetState,
+    ButtonState, ButtonVariant, DialogButton, FocusDirection, FocusManager, InputState,
+    ListFilterMode, ListItem, ListMatch, ListState, ModalState, NotificationQueue, ProgressState,
+    SliderState, Tab, TabsState, Toast, ToastSeverity, Widget, WidgetId, WidgetState,
 };
 
+use crate::core::notify::{Notifier, Severity};
+
 /// UI Application state combining all UI managers
 ///
 /// This provides a convenient way to manage all UI state in one place.
@@ -266,6 +281,12 @@ pub struct UiApp {
 
     /// Focus manager for keyboard navigation
     pub focus: FocusManager,
+
+    /// Device and extraction state shown in the status panel
+    pub status: StatusModel,
+
+    /// Shows a notification when an extraction finishes or fails
+    pub notifier: Box<dyn Notifier>,
 }
 
 impl UiApp {
@@ -273,8 +294,13 @@ impl UiApp {
     pub fn new() -> Self {
         let mut app = Self::default();
 
-        // Register all commands from actions
-        app.palette.registry_mut().register_from_actions();
+        // Commands for every action, with usage from earlier sessions saved
+        // beside the settings
+        app.palette = CommandPalette::with_usage_file(
+            app.settings
+                .path()
+                .with_file_name(commands::COMMAND_USAGE_FILE),
+        );
 
         // Add keybindings to commands
         app.palette.add_keybindings(app.keybindings.registry());
@@ -341,10 +367,7 @@ impl UiApp {
         }
 
         // Apply keyboard settings
-        self.keybindings
-            .set_sequence_timeout(std::time::Duration::from_millis(
-                settings.keyboard.chord_timeout_ms as u64,
-            ));
+        self.keybindings.apply_keyboard_settings(&settings.keyboard);
 
         // Apply panel settings
         let layout = self.panels.layout_mut();
@@ -357,6 +380,93 @@ impl UiApp {
             .set_max_results(settings.behavior.palette_max_results);
     }
 
+    /// Apply an event from the controller or device monitor
+    ///
+    /// Once an extraction completes fully its output folder is opened if
+    /// `behavior.open_folder_after_extraction` is on. Finishing or failing
+    /// raises a notification and plays a sound when the behavior settings
+    /// enable them.
+    pub fn handle_event(&mut self, event: &UiEvent) {
+        self.status.update(event);
+
+        let UiEvent::Extraction(event) = event else {
+            return;
+        };
+        match event {
+            ExtractionEvent::Completed { stats } => {
+                let severity = if stats.errors > 0 || !stats.completed_fully {
+                    Severity::Warning
+                } else {
+                    Severity::Success
+                };
+                let body = format!(
+                    "{} files extracted from {}, {} errors",
+                    stats.files_extracted, stats.device.friendly_name, stats.errors
+                );
+                self.notify_finished("Extraction complete", &body, severity);
+
+                if stats.completed_fully
+                    && self
+                        .settings
+                        .settings()
+                        .behavior
+                        .open_folder_after_extraction
+                {
+                    self.perform_action(keybindings::Action::OpenOutputFolder);
+                }
+            }
+            ExtractionEvent::FatalError { error, .. } => {
+                self.notify_finished("Extraction failed", error, Severity::Error);
+            }
+            _ => {}
+        }
+    }
+
+    /// Notify and play the sound, as far as the behavior settings allow
+    fn notify_finished(&self, title: &str, body: &str, severity: Severity) {
+        let behavior = &self.settings.settings().behavior;
+        if behavior.show_notifications {
+            self.notifier.notify(title, body, severity);
+        }
+        if behavior.play_sounds {
+            if let Some(path) = &behavior.sound_path {
+                if let Err(e) = crate::core::notify::play_sound(path, behavior.sound_volume) {
+                    log::warn!("Could not play notification sound: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Run an action the app handles itself, returning whether it did
+    pub fn perform_action(&mut self, action: keybindings::Action) -> bool {
+        match action {
+            keybindings::Action::OpenOutputFolder => {
+                if let Err(e) = self.open_output_folder() {
+                    log::warn!("Could not open the output folder: {}", e);
+                }
+                true
+            }
+            keybindings::Action::ToggleTheme => {
+                self.toggle_theme_mode();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Open the most recent extraction's output folder in the file manager
+    pub fn open_output_folder(&self) -> crate::core::Result<()> {
+        let dir = self
+            .status
+            .extraction
+            .as_ref()
+            .and_then(|extraction| extraction.output_dir.as_deref())
+            .ok_or_else(|| {
+                crate::core::ExtractionError::IoError("No extraction has run yet".to_string())
+            })?;
+        crate::core::reveal_in_explorer(dir)
+    }
+
     /// Save current state to settings
     pub fn save_state(&mut self) {
         let settings = self.settings.settings_mut();
@@ -373,6 +483,9 @@ impl UiApp {
         settings.panels.default_left_width = layout.left_width;
         settings.panels.default_right_width = layout.right_width;
         settings.panels.default_bottom_height = layout.bottom_height;
+
+        // Save command usage
+        self.palette.save_usage();
     }
 }
 
@@ -423,4 +536,61 @@ mod tests {
             ThemeMode::Light
         );
     }
+
+    #[test]
+    fn test_ui_app_notifies_on_extraction_finish() {
+        use crate::core::notify::{Notification, RecordingNotifier};
+        use crate::device::DeviceInfo;
+        use std::path::PathBuf;
+        use std::time::Duration;
+
+        let recorder = RecordingNotifier::new();
+        let mut app = UiApp::new();
+        app.notifier = Box::new(recorder.clone());
+
+        let summary = ExtractionSummary {
+            device: DeviceInfo::new("dev-1", "Alice's iPhone", "Apple Inc.", "iPhone 15 Pro"),
+            output_dir: PathBuf::from("out"),
+            files_extracted: 12,
+            files_skipped: 0,
+            duplicates_found: 0,
+            errors: 0,
+            bytes_transferred: 12_000,
+            duration: Duration::from_secs(3),
+            average_speed_bps: 4000,
+            completed_fully: true,
+            resumable: false,
+        };
+        app.handle_event(&UiEvent::Extraction(ExtractionEvent::Completed {
+            stats: summary,
+        }));
+        app.handle_event(&UiEvent::Extraction(ExtractionEvent::FatalError {
+            error: "device lost".to_string(),
+            context: None,
+        }));
+
+        assert_eq!(
+            recorder.notifications(),
+            vec![
+                Notification {
+                    title: "Extraction complete".to_string(),
+                    body: "12 files extracted from Alice's iPhone, 0 errors".to_string(),
+                    severity: Severity::Success,
+                },
+                Notification {
+                    title: "Extraction failed".to_string(),
+                    body: "device lost".to_string(),
+                    severity: Severity::Error,
+                },
+            ]
+        );
+
+        // Nothing is shown once notifications are turned off
+        app.settings.settings_mut().behavior.show_notifications = false;
+        app.handle_event(&UiEvent::Extraction(ExtractionEvent::FatalError {
+            error: "device lost".to_string(),
+            context: None,
+        }));
+        assert_eq!(recorder.notifications().len(), 2);
+    }
 }
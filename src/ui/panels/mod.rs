@@ -16,6 +16,7 @@
 //! 2. **PanelPosition** - Where the panel is docked (left, right, bottom, center)
 //! 3. **PanelLayout** - The overall arrangement of panels
 //! 4. **PanelManager** - Manages panel state and layout
+//! 5. **SavedLayout** - A named, serializable snapshot of the arrangement
 //!
 //! # Example Usage
 //!
@@ -37,8 +38,8 @@
 //! let visible = manager.visible_panels();
 //! ```
 
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 // =============================================================================
 // PanelId
@@ -315,6 +316,66 @@ impl PanelSize {
     }
 }
 
+// =============================================================================
+// FloatRect
+// =============================================================================
+
+/// Location and size of a floating panel, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FloatRect {
+    /// Left edge
+    pub x: f32,
+
+    /// Top edge
+    pub y: f32,
+
+    /// Width
+    pub width: f32,
+
+    /// Height
+    pub height: f32,
+}
+
+impl FloatRect {
+    /// Offset of a newly floated panel from the window origin
+    pub const DEFAULT_OFFSET: f32 = 80.0;
+
+    /// Create a new rect
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Default rect for a panel that is being floated
+    ///
+    /// Uses the panel's preferred size; sidebar and bottom sizes use 0 for
+    /// "fill", so those dimensions fall back to the default panel size.
+    pub fn for_size(size: &PanelSize) -> Self {
+        let fallback = PanelSize::default();
+        let width = if size.width > 0.0 {
+            size.width
+        } else {
+            fallback.width
+        };
+        let height = if size.height > 0.0 {
+            size.height
+        } else {
+            fallback.height
+        };
+
+        Self::new(
+            Self::DEFAULT_OFFSET,
+            Self::DEFAULT_OFFSET,
+            size.clamp_width(width),
+            size.clamp_height(height),
+        )
+    }
+}
+
 // =============================================================================
 // Panel
 // =============================================================================
@@ -359,6 +420,10 @@ pub struct Panel {
     /// Order within the position (for stacking)
     pub order: i32,
 
+    /// Location when floating
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub float_rect: Option<FloatRect>,
+
     /// Custom data/state (for extensions)
     #[serde(default)]
     pub custom_data: HashMap<String, String>,
@@ -380,6 +445,7 @@ impl Panel {
             movable: true,
             resizable: true,
             order: 0,
+            float_rect: None,
             custom_data: HashMap::new(),
         }
     }
@@ -426,6 +492,31 @@ impl Panel {
         self
     }
 
+    /// Set the floating rect
+    pub fn float_rect(mut self, rect: FloatRect) -> Self {
+        self.float_rect = Some(rect);
+        self
+    }
+
+    /// Move the floating panel, seeding its size if it has no rect yet
+    pub fn set_float_position(&mut self, x: f32, y: f32) {
+        let rect = self
+            .float_rect
+            .get_or_insert_with(|| FloatRect::for_size(&self.size));
+        rect.x = x;
+        rect.y = y;
+    }
+
+    /// Resize the floating panel within the size constraints
+    pub fn set_float_size(&mut self, width: f32, height: f32) {
+        let (width, height) = (self.size.clamp_width(width), self.size.clamp_height(height));
+        let rect = self
+            .float_rect
+            .get_or_insert_with(|| FloatRect::for_size(&self.size));
+        rect.width = width;
+        rect.height = height;
+    }
+
     /// Toggle visibility
     pub fn toggle(&mut self) {
         self.visible = !self.visible;
@@ -486,7 +577,7 @@ impl Panel {
 // =============================================================================
 
 /// Describes the overall panel layout
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PanelLayout {
     /// Width of left sidebar
     pub left_width: f32,
@@ -581,6 +672,97 @@ impl PanelLayout {
     }
 }
 
+// =============================================================================
+// SavedLayout
+// =============================================================================
+
+/// The arrangement of a single panel within a [`SavedLayout`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PanelSnapshot {
+    /// Docked position
+    pub position: PanelPosition,
+
+    /// Size configuration
+    pub size: PanelSize,
+
+    /// Whether the panel is visible
+    pub visible: bool,
+
+    /// Whether the panel is collapsed
+    #[serde(default)]
+    pub collapsed: bool,
+
+    /// Order within the position
+    #[serde(default)]
+    pub order: i32,
+
+    /// Location when floating
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub float_rect: Option<FloatRect>,
+}
+
+impl PanelSnapshot {
+    /// Capture the arrangement of a panel
+    pub fn of(panel: &Panel) -> Self {
+        Self {
+            position: panel.position,
+            size: panel.size,
+            visible: panel.visible,
+            collapsed: panel.collapsed,
+            order: panel.order,
+            float_rect: panel.float_rect,
+        }
+    }
+
+    /// Apply this arrangement to a panel
+    pub fn apply(&self, panel: &mut Panel) {
+        panel.position = self.position;
+        panel.size = self.size;
+        panel.visible = self.visible;
+        panel.collapsed = self.collapsed;
+        panel.order = self.order;
+        panel.float_rect = self.float_rect;
+    }
+}
+
+/// A named panel arrangement that can be saved and restored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedLayout {
+    /// Layout name
+    pub name: String,
+
+    /// Sidebar and bottom panel dimensions
+    pub layout: PanelLayout,
+
+    /// Arrangement of each panel
+    ///
+    /// Entries for panel IDs this version does not know are dropped when
+    /// deserializing, so layouts saved by other versions still load.
+    #[serde(deserialize_with = "deserialize_known_panels")]
+    pub panels: HashMap<PanelId, PanelSnapshot>,
+}
+
+fn deserialize_known_panels<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<PanelId, PanelSnapshot>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::IntoDeserializer;
+
+    let raw: HashMap<String, PanelSnapshot> = HashMap::deserialize(deserializer)?;
+    Ok(raw
+        .into_iter()
+        .filter_map(|(id, snapshot)| {
+            let deserializer: serde::de::value::StringDeserializer<D::Error> =
+                id.into_deserializer();
+            PanelId::deserialize(deserializer)
+                .ok()
+                .map(|id| (id, snapshot))
+        })
+        .collect())
+}
+
 // =============================================================================
 // PanelConfig
 // =============================================================================
@@ -653,6 +835,9 @@ pub struct PanelManager {
 
     /// Focus history (for navigation)
     focus_history: Vec<PanelId>,
+
+    /// Named layouts saved by the user
+    saved_layouts: BTreeMap<String, SavedLayout>,
 }
 
 impl PanelManager {
@@ -670,6 +855,7 @@ impl PanelManager {
             layout: PanelLayout::default(),
             config: PanelConfig::default(),
             focus_history: Vec::new(),
+            saved_layouts: BTreeMap::new(),
         }
     }
 
@@ -852,13 +1038,34 @@ impl PanelManager {
     }
 
     /// Set panel position
+    ///
+    /// Floating a panel that has no rect yet gives it one based on its
+    /// current size; a previous rect is kept so the panel returns to where
+    /// it last floated.
     pub fn set_panel_position(&mut self, id: PanelId, position: PanelPosition) {
         if let Some(panel) = self.panels.get_mut(&id) {
             panel.position = position;
+            if position.is_floating() && panel.float_rect.is_none() {
+                panel.float_rect = Some(FloatRect::for_size(&panel.size));
+            }
             self.update_layout_visibility();
         }
     }
 
+    /// Move a floating panel
+    pub fn set_float_position(&mut self, id: PanelId, x: f32, y: f32) {
+        if let Some(panel) = self.panels.get_mut(&id) {
+            panel.set_float_position(x, y);
+        }
+    }
+
+    /// Resize a floating panel
+    pub fn set_float_size(&mut self, id: PanelId, width: f32, height: f32) {
+        if let Some(panel) = self.panels.get_mut(&id) {
+            panel.set_float_size(width, height);
+        }
+    }
+
     /// Set panel size
     pub fn set_panel_size(&mut self, id: PanelId, size: PanelSize) {
         if let Some(panel) = self.panels.get_mut(&id) {
@@ -943,6 +1150,71 @@ impl PanelManager {
         self.update_layout_visibility();
     }
 
+    /// Save the current arrangement under a name, replacing any existing one
+    pub fn save_layout(&mut self, name: impl Into<String>) {
+        let name = name.into();
+        let saved = SavedLayout {
+            name: name.clone(),
+            layout: self.layout.clone(),
+            panels: self
+                .panels
+                .values()
+                .map(|p| (p.id, PanelSnapshot::of(p)))
+                .collect(),
+        };
+
+        self.saved_layouts.insert(name, saved);
+    }
+
+    /// Restore a saved layout
+    ///
+    /// Panels missing from the layout keep their current state. Returns
+    /// `false` if no layout has this name.
+    pub fn load_layout(&mut self, name: &str) -> bool {
+        let Some(saved) = self.saved_layouts.get(name) else {
+            return false;
+        };
+
+        for (id, snapshot) in &saved.panels {
+            if let Some(panel) = self.panels.get_mut(id) {
+                snapshot.apply(panel);
+            }
+        }
+        self.layout = saved.layout.clone();
+
+        // Don't leave focus on a panel the layout hid
+        if let Some(id) = self.focused {
+            if !self.is_visible(id) {
+                if let Some(panel) = self.panels.get_mut(&id) {
+                    panel.unfocus();
+                }
+                self.focused = None;
+            }
+        }
+
+        true
+    }
+
+    /// Delete a saved layout, returning whether it existed
+    pub fn delete_layout(&mut self, name: &str) -> bool {
+        self.saved_layouts.remove(name).is_some()
+    }
+
+    /// Names of saved layouts, sorted
+    pub fn layout_names(&self) -> Vec<&str> {
+        self.saved_layouts.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// Get a saved layout (e.g. to persist it)
+    pub fn saved_layout(&self, name: &str) -> Option<&SavedLayout> {
+        self.saved_layouts.get(name)
+    }
+
+    /// Add a previously persisted layout, replacing one with the same name
+    pub fn insert_layout(&mut self, layout: SavedLayout) {
+        self.saved_layouts.insert(layout.name.clone(), layout);
+    }
+
     /// Update layout visibility based on panel states
     fn update_layout_visibility(&mut self) {
         self.layout.left_visible = self
@@ -1233,4 +1505,133 @@ mod tests {
 
         assert_eq!(layout.left_width, deserialized.left_width);
     }
+
+    #[test]
+    fn test_panel_manager_save_and_load_layout() {
+        let mut manager = PanelManager::new();
+
+        manager.hide_panel(PanelId::DeviceList);
+        manager.hide_panel(PanelId::Progress);
+        manager.show_panel(PanelId::Log);
+        manager.set_panel_position(PanelId::Log, PanelPosition::Right);
+        manager.resize_panel_width(PanelId::Log, 420.0);
+        manager.save_layout("minimal");
+
+        let expected: Vec<(PanelId, bool)> = PanelId::all()
+            .iter()
+            .map(|id| (*id, manager.is_visible(*id)))
+            .collect();
+        let expected_layout = manager.layout().clone();
+
+        manager.reset_layout();
+        assert!(manager.is_visible(PanelId::DeviceList));
+        manager.focus_panel(PanelId::DeviceList);
+
+        assert!(manager.load_layout("minimal"));
+        let restored: Vec<(PanelId, bool)> = PanelId::all()
+            .iter()
+            .map(|id| (*id, manager.is_visible(*id)))
+            .collect();
+        assert_eq!(restored, expected);
+        assert_eq!(manager.layout(), &expected_layout);
+
+        let log = manager.get(PanelId::Log).unwrap();
+        assert_eq!(log.position, PanelPosition::Right);
+        assert_eq!(log.size.width, 420.0);
+
+        // Focus does not stay on a panel the layout hid
+        assert_eq!(manager.focused_id(), None);
+    }
+
+    #[test]
+    fn test_panel_manager_layout_names() {
+        let mut manager = PanelManager::new();
+        assert!(manager.layout_names().is_empty());
+        assert!(!manager.load_layout("missing"));
+
+        manager.save_layout("zen");
+        manager.save_layout("review");
+        manager.save_layout("zen");
+        assert_eq!(manager.layout_names(), vec!["review", "zen"]);
+
+        assert!(manager.delete_layout("zen"));
+        assert!(!manager.delete_layout("zen"));
+        assert_eq!(manager.layout_names(), vec!["review"]);
+    }
+
+    #[test]
+    fn test_saved_layout_serialization_skips_unknown_panels() {
+        let mut manager = PanelManager::new();
+        manager.hide_panel(PanelId::Preview);
+        manager.save_layout("focus");
+
+        let json = serde_json::to_string(manager.saved_layout("focus").unwrap()).unwrap();
+        let deserialized: SavedLayout = serde_json::from_str(&json).unwrap();
+        assert_eq!(&deserialized, manager.saved_layout("focus").unwrap());
+
+        // A panel from another version is dropped instead of failing the load
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["panels"]["gallery"] = value["panels"]["preview"].clone();
+        let with_unknown: SavedLayout = serde_json::from_value(value).unwrap();
+        assert_eq!(with_unknown.panels.len(), PanelId::all().len());
+
+        let mut other = PanelManager::new();
+        other.insert_layout(with_unknown);
+        assert!(other.load_layout("focus"));
+        assert!(!other.is_visible(PanelId::Preview));
+    }
+
+    #[test]
+    fn test_floating_panel_seeds_rect() {
+        let mut manager = PanelManager::new();
+
+        // Bottom panels fill the width, so the width falls back to the default
+        manager.set_panel_size(PanelId::Log, PanelSize::bottom(180.0));
+        manager.set_panel_position(PanelId::Log, PanelPosition::Floating);
+
+        let rect = manager.get(PanelId::Log).unwrap().float_rect.unwrap();
+        assert_eq!(rect.x, FloatRect::DEFAULT_OFFSET);
+        assert_eq!(rect.width, PanelSize::default().width);
+        assert_eq!(rect.height, 180.0);
+
+        // Docking and floating again keeps the last rect
+        manager.set_float_position(PanelId::Log, 300.0, 40.0);
+        manager.set_panel_position(PanelId::Log, PanelPosition::Bottom);
+        manager.set_panel_position(PanelId::Log, PanelPosition::Floating);
+        let rect = manager.get(PanelId::Log).unwrap().float_rect.unwrap();
+        assert_eq!((rect.x, rect.y), (300.0, 40.0));
+    }
+
+    #[test]
+    fn test_floating_panel_rect_serialization() {
+        let mut manager = PanelManager::new();
+        manager.set_panel_position(PanelId::Preview, PanelPosition::Floating);
+        manager.set_float_position(PanelId::Preview, 120.0, 64.0);
+        manager.set_float_size(PanelId::Preview, 480.0, 5000.0);
+
+        let panel = manager.get(PanelId::Preview).unwrap();
+        let expected = FloatRect::new(120.0, 64.0, 480.0, panel.size.max_height);
+        assert_eq!(panel.float_rect, Some(expected));
+
+        let json = serde_json::to_string(panel).unwrap();
+        let deserialized: Panel = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.position, PanelPosition::Floating);
+        assert_eq!(deserialized.float_rect, Some(expected));
+
+        // Docked panels without a rect still deserialize from older data
+        let docked = serde_json::to_string(&Panel::new(PanelId::Log)).unwrap();
+        assert!(!docked.contains("float_rect"));
+        let deserialized: Panel = serde_json::from_str(&docked).unwrap();
+        assert_eq!(deserialized.float_rect, None);
+
+        // Saved layouts carry the rect too
+        manager.save_layout("floating");
+        manager.reset_layout();
+        manager.get_mut(PanelId::Preview).unwrap().float_rect = None;
+        manager.load_layout("floating");
+        assert_eq!(
+            manager.get(PanelId::Preview).unwrap().float_rect,
+            Some(expected)
+        );
+    }
 }
@@ -3,13 +3,21 @@
 //! Provides functionality for generating and caching thumbnails/previews
 //! of photos before extraction from iOS devices (iPhone/iPad). This enables
 //! UIs to show users what photos are available on the connected device.
+//!
+//! [`DecodedThumbnailCache`] keeps decoded pixels for the preview grid within
+//! a memory budget and decodes missing ones on background threads.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ops::Range;
 use std::path::Path;
-use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
+use crate::core::metadata::read_exif_orientation;
 use crate::device::{DeviceContentTrait, DeviceObject};
+use crate::ui::settings::PreviewSettings;
 
 // =============================================================================
 // Thumbnail Configuration
@@ -150,6 +158,458 @@ impl Thumbnail {
     }
 }
 
+/// Detect the MIME type and, where the header allows, the dimensions of
+/// encoded image bytes (JPEG or PNG)
+fn sniff_image(data: &[u8]) -> (&'static str, Option<(u32, u32)>) {
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    if data.starts_with(PNG_SIGNATURE) {
+        // IHDR is always the first chunk: width and height follow its type
+        let dimensions = data.get(16..24).map(|ihdr| {
+            (
+                u32::from_be_bytes([ihdr[0], ihdr[1], ihdr[2], ihdr[3]]),
+                u32::from_be_bytes([ihdr[4], ihdr[5], ihdr[6], ihdr[7]]),
+            )
+        });
+        return ("image/png", dimensions);
+    }
+
+    ("image/jpeg", jpeg_dimensions(data))
+}
+
+/// Read the frame dimensions from a JPEG's start-of-frame segment
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = data[pos + 1];
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+
+        // SOF0..SOF15, except DHT (C4), JPG (C8) and DAC (CC)
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let frame = data.get(pos + 5..pos + 9)?;
+            let height = u16::from_be_bytes([frame[0], frame[1]]) as u32;
+            let width = u16::from_be_bytes([frame[2], frame[3]]) as u32;
+            return Some((width, height));
+        }
+
+        pos += 2 + length;
+    }
+
+    None
+}
+
+// =============================================================================
+// Thumbnail Decoding
+// =============================================================================
+
+/// Decoded image pixels, independent of any UI framework
+///
+/// Pixels are stored row by row as 8-bit RGBA, 4 bytes per pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// RGBA bytes (`width * height * 4`)
+    pub pixels: Vec<u8>,
+    /// EXIF orientation of the source image
+    ///
+    /// [`decode_thumbnail`] has already applied it to `pixels`;
+    /// [`decode_thumbnail_unrotated`] leaves that to the caller.
+    pub orientation: Orientation,
+}
+
+/// EXIF orientation: how stored pixels are transformed to display upright
+///
+/// iPhones save photos in sensor order and record the rotation here instead
+/// of rotating the pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// 1: already upright
+    #[default]
+    Normal,
+    /// 2: mirrored left to right
+    FlipHorizontal,
+    /// 3: upside down
+    Rotate180,
+    /// 4: mirrored top to bottom
+    FlipVertical,
+    /// 5: rotate 90° clockwise, then mirror left to right
+    Rotate90FlipHorizontal,
+    /// 6: rotate 90° clockwise
+    Rotate90,
+    /// 7: rotate 270° clockwise, then mirror left to right
+    Rotate270FlipHorizontal,
+    /// 8: rotate 270° clockwise (90° counter-clockwise)
+    Rotate270,
+}
+
+impl Orientation {
+    /// Map an EXIF `Orientation` value (1-8)
+    pub fn from_exif(value: u16) -> Option<Self> {
+        Some(match value {
+            1 => Orientation::Normal,
+            2 => Orientation::FlipHorizontal,
+            3 => Orientation::Rotate180,
+            4 => Orientation::FlipVertical,
+            5 => Orientation::Rotate90FlipHorizontal,
+            6 => Orientation::Rotate90,
+            7 => Orientation::Rotate270FlipHorizontal,
+            8 => Orientation::Rotate270,
+            _ => return None,
+        })
+    }
+
+    /// EXIF `Orientation` value (1-8)
+    pub fn exif_value(&self) -> u16 {
+        match self {
+            Orientation::Normal => 1,
+            Orientation::FlipHorizontal => 2,
+            Orientation::Rotate180 => 3,
+            Orientation::FlipVertical => 4,
+            Orientation::Rotate90FlipHorizontal => 5,
+            Orientation::Rotate90 => 6,
+            Orientation::Rotate270FlipHorizontal => 7,
+            Orientation::Rotate270 => 8,
+        }
+    }
+
+    /// Whether width and height trade places when the orientation is applied
+    pub fn swaps_dimensions(&self) -> bool {
+        matches!(
+            self,
+            Orientation::Rotate90FlipHorizontal
+                | Orientation::Rotate90
+                | Orientation::Rotate270FlipHorizontal
+                | Orientation::Rotate270
+        )
+    }
+
+    /// Transform `image` so it displays upright
+    fn apply(&self, image: image::RgbaImage) -> image::RgbaImage {
+        use image::imageops::{flip_horizontal, flip_vertical, rotate180, rotate270, rotate90};
+
+        match self {
+            Orientation::Normal => image,
+            Orientation::FlipHorizontal => flip_horizontal(&image),
+            Orientation::Rotate180 => rotate180(&image),
+            Orientation::FlipVertical => flip_vertical(&image),
+            Orientation::Rotate90FlipHorizontal => flip_horizontal(&rotate90(&image)),
+            Orientation::Rotate90 => rotate90(&image),
+            Orientation::Rotate270FlipHorizontal => flip_horizontal(&rotate270(&image)),
+            Orientation::Rotate270 => rotate270(&image),
+        }
+    }
+}
+
+/// Why an image could not be decoded
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    /// The bytes are not an image format we can decode
+    #[error("Unsupported image format")]
+    UnsupportedFormat,
+
+    /// The image is HEIC but HEIC support was not compiled in
+    #[error("HEIC decoding requires the 'heic' feature")]
+    HeicNotEnabled,
+
+    /// The file is a video but video support was not compiled in
+    #[error("Video thumbnails require the 'video-thumbnails' feature")]
+    VideoNotEnabled,
+
+    /// Decoding took too long and was abandoned
+    #[error("Decoding timed out after {0:?}")]
+    Timeout(Duration),
+
+    /// The request was cancelled before decoding finished
+    #[error("Decoding was cancelled")]
+    Cancelled,
+
+    /// The image data is corrupt or truncated
+    #[error("Failed to decode image: {0}")]
+    Decode(String),
+}
+
+/// Decode an encoded image and downscale it to fit in `max_dim` x `max_dim`
+///
+/// Handles JPEG and PNG, plus HEIC when built with the `heic` feature. The
+/// aspect ratio is kept and images that already fit are not enlarged. The
+/// EXIF orientation is applied, so the result displays upright.
+pub fn decode_thumbnail(bytes: &[u8], max_dim: u32) -> Result<RgbaImage, DecodeError> {
+    decode(bytes, max_dim, true)
+}
+
+/// Like [`decode_thumbnail`], but leaves the pixels in stored order
+///
+/// For callers that rotate at render time using [`RgbaImage::orientation`].
+pub fn decode_thumbnail_unrotated(bytes: &[u8], max_dim: u32) -> Result<RgbaImage, DecodeError> {
+    decode(bytes, max_dim, false)
+}
+
+fn decode(bytes: &[u8], max_dim: u32, apply_orientation: bool) -> Result<RgbaImage, DecodeError> {
+    // libheif applies HEIC rotation while decoding, so only the EXIF tag of
+    // JPEG and PNG files is left to handle
+    let (image, orientation) = if is_heif(bytes) {
+        (decode_heif(bytes)?, Orientation::Normal)
+    } else {
+        match image::guess_format(bytes) {
+            Ok(image::ImageFormat::Jpeg | image::ImageFormat::Png) => {
+                let image = image::load_from_memory(bytes)
+                    .map_err(|e| DecodeError::Decode(e.to_string()))?
+                    .into_rgba8();
+                let orientation = read_exif_orientation(bytes)
+                    .and_then(Orientation::from_exif)
+                    .unwrap_or_default();
+                (image, orientation)
+            }
+            _ => return Err(DecodeError::UnsupportedFormat),
+        }
+    };
+
+    let image = if image.width() > max_dim || image.height() > max_dim {
+        image::DynamicImage::ImageRgba8(image)
+            .thumbnail(max_dim, max_dim)
+            .into_rgba8()
+    } else {
+        image
+    };
+    let image = if apply_orientation {
+        orientation.apply(image)
+    } else {
+        image
+    };
+
+    Ok(RgbaImage {
+        width: image.width(),
+        height: image.height(),
+        pixels: image.into_raw(),
+        orientation,
+    })
+}
+
+/// Check for an ISO-BMFF `ftyp` box with a HEIF/HEIC brand
+fn is_heif(bytes: &[u8]) -> bool {
+    const BRANDS: [&[u8]; 8] = [
+        b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"mif1", b"msf1",
+    ];
+
+    bytes.get(4..8) == Some(b"ftyp".as_slice())
+        && bytes
+            .get(8..12)
+            .is_some_and(|brand| BRANDS.contains(&brand))
+}
+
+#[cfg(feature = "heic")]
+fn decode_heif(bytes: &[u8]) -> Result<image::RgbaImage, DecodeError> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let decode_error = |e: libheif_rs::HeifError| DecodeError::Decode(e.to_string());
+
+    let context = HeifContext::read_from_bytes(bytes).map_err(decode_error)?;
+    let handle = context.primary_image_handle().map_err(decode_error)?;
+    let decoded = LibHeif::new()
+        .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+        .map_err(decode_error)?;
+
+    let planes = decoded.planes();
+    let plane = planes
+        .interleaved
+        .ok_or_else(|| DecodeError::Decode("HEIC image has no RGBA plane".to_string()))?;
+
+    // Rows may be padded; copy only the pixels of each row
+    let row_len = plane.width as usize * 4;
+    let mut pixels = Vec::with_capacity(row_len * plane.height as usize);
+    for row in plane.data.chunks(plane.stride).take(plane.height as usize) {
+        pixels.extend_from_slice(&row[..row_len]);
+    }
+
+    image::RgbaImage::from_raw(plane.width, plane.height, pixels)
+        .ok_or_else(|| DecodeError::Decode("HEIC image has a truncated plane".to_string()))
+}
+
+#[cfg(not(feature = "heic"))]
+fn decode_heif(_bytes: &[u8]) -> Result<image::RgbaImage, DecodeError> {
+    Err(DecodeError::HeicNotEnabled)
+}
+
+/// How long ffmpeg may take to produce a video frame
+pub const VIDEO_DECODE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Where to read a video from
+#[derive(Debug, Clone, Copy)]
+pub enum VideoSource<'a> {
+    /// The whole file in memory (e.g. read from the device)
+    Bytes(&'a [u8]),
+    /// A file on disk
+    Path(&'a Path),
+}
+
+/// Extract a single frame of a MOV/MP4 video as an RGBA image
+///
+/// Takes the frame at `at_seconds` (use 0.0 for the first frame) by running
+/// ffmpeg, which must be next to the executable or on `PATH`; HEVC clips
+/// from iPhones are handled by any ffmpeg build with an HEVC decoder.
+/// Gives up with [`DecodeError::Timeout`] after [`VIDEO_DECODE_TIMEOUT`].
+pub fn video_thumbnail(source: VideoSource<'_>, at_seconds: f64) -> Result<RgbaImage, DecodeError> {
+    #[cfg(feature = "video-thumbnails")]
+    {
+        video::extract_frame(source, at_seconds, VIDEO_DECODE_TIMEOUT)
+    }
+
+    #[cfg(not(feature = "video-thumbnails"))]
+    {
+        let _ = (source, at_seconds);
+        Err(DecodeError::VideoNotEnabled)
+    }
+}
+
+#[cfg(feature = "video-thumbnails")]
+mod video {
+    use super::{DecodeError, Orientation, RgbaImage, VideoSource};
+    use std::io::Read;
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    /// Run ffmpeg on `source` and decode the PNG frame it writes to stdout
+    pub(super) fn extract_frame(
+        source: VideoSource<'_>,
+        at_seconds: f64,
+        timeout: Duration,
+    ) -> Result<RgbaImage, DecodeError> {
+        // ffmpeg needs to seek, so in-memory videos go through a temp file
+        let temp_file = match source {
+            VideoSource::Bytes(bytes) => Some(TempVideo::write(bytes)?),
+            VideoSource::Path(_) => None,
+        };
+        let input = match (&temp_file, source) {
+            (Some(temp), _) => temp.path.as_path(),
+            (None, VideoSource::Path(path)) => path,
+            (None, VideoSource::Bytes(_)) => unreachable!("bytes are written to a temp file"),
+        };
+
+        let png = run_ffmpeg(input, at_seconds, timeout)?;
+        let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+            .map_err(|e| DecodeError::Decode(format!("Bad frame from ffmpeg: {}", e)))?
+            .into_rgba8();
+
+        Ok(RgbaImage {
+            width: image.width(),
+            height: image.height(),
+            pixels: image.into_raw(),
+            orientation: Orientation::Normal,
+        })
+    }
+
+    fn run_ffmpeg(
+        input: &Path,
+        at_seconds: f64,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, DecodeError> {
+        let mut child = Command::new(ffmpeg_binary())
+            .args(["-v", "error", "-ss"])
+            .arg(format!("{:.3}", at_seconds.max(0.0)))
+            .arg("-i")
+            .arg(input)
+            .args(["-frames:v", "1", "-f", "image2pipe", "-vcodec", "png", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| DecodeError::Decode(format!("Failed to run ffmpeg: {}", e)))?;
+
+        // Drain the pipes on their own threads so a full pipe can't stall ffmpeg
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let stdout_reader = std::thread::spawn(move || {
+            let mut data = Vec::new();
+            stdout.read_to_end(&mut data).map(|_| data)
+        });
+        let stderr_reader = std::thread::spawn(move || {
+            let mut message = String::new();
+            let _ = stderr.read_to_string(&mut message);
+            message
+        });
+
+        let started = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if started.elapsed() >= timeout => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(DecodeError::Timeout(timeout));
+                }
+                Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+                Err(e) => return Err(DecodeError::Decode(format!("ffmpeg failed: {}", e))),
+            }
+        };
+
+        let frame = stdout_reader
+            .join()
+            .map_err(|_| DecodeError::Decode("ffmpeg output reader panicked".to_string()))?
+            .map_err(|e| DecodeError::Decode(format!("Failed to read ffmpeg output: {}", e)))?;
+        let message = stderr_reader.join().unwrap_or_default();
+
+        if !status.success() || frame.is_empty() {
+            return Err(DecodeError::Decode(format!(
+                "ffmpeg produced no frame: {}",
+                message.trim()
+            )));
+        }
+
+        Ok(frame)
+    }
+
+    /// Prefer an ffmpeg bundled next to the executable, then fall back to `PATH`
+    fn ffmpeg_binary() -> PathBuf {
+        let name = if cfg!(windows) {
+            "ffmpeg.exe"
+        } else {
+            "ffmpeg"
+        };
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join(name)))
+            .filter(|bundled| bundled.is_file())
+            .unwrap_or_else(|| PathBuf::from(name))
+    }
+
+    /// A video written to the temp directory, removed on drop
+    struct TempVideo {
+        path: PathBuf,
+    }
+
+    impl TempVideo {
+        fn write(bytes: &[u8]) -> Result<Self, DecodeError> {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "photo-extractor-preview-{}-{}.video",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::write(&path, bytes)
+                .map_err(|e| DecodeError::Decode(format!("Failed to stage video: {}", e)))?;
+            Ok(Self { path })
+        }
+    }
+
+    impl Drop for TempVideo {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
 // =============================================================================
 // Preview Item
 // =============================================================================
@@ -364,6 +824,424 @@ impl Default for ThumbnailCache {
     }
 }
 
+// =============================================================================
+// Decoded Thumbnail Cache
+// =============================================================================
+
+/// Decode threads a [`DecodedThumbnailCache`] starts by default
+pub const DEFAULT_DECODE_WORKERS: usize = 4;
+
+/// Produces the pixels for a thumbnail on a [`DecodedThumbnailCache`] worker
+///
+/// Implemented for closures, so a caller can read the bytes however suits it
+/// (device, extracted file, stored thumbnail) and hand them to
+/// [`decode_thumbnail`].
+pub trait ThumbnailDecoder: Send + Sync {
+    /// Decode the object's image to fit in `max_dim` x `max_dim`
+    ///
+    /// `cancelled` is set once the thumbnail is no longer wanted, e.g. it was
+    /// scrolled out of view. A slow decoder should check it between steps and
+    /// return [`DecodeError::Cancelled`]; the result of a cancelled request
+    /// is thrown away either way.
+    fn decode(
+        &self,
+        object_id: &str,
+        max_dim: u32,
+        cancelled: &AtomicBool,
+    ) -> Result<RgbaImage, DecodeError>;
+}
+
+impl<F> ThumbnailDecoder for F
+where
+    F: Fn(&str, u32, &AtomicBool) -> Result<RgbaImage, DecodeError> + Send + Sync,
+{
+    fn decode(
+        &self,
+        object_id: &str,
+        max_dim: u32,
+        cancelled: &AtomicBool,
+    ) -> Result<RgbaImage, DecodeError> {
+        self(object_id, max_dim, cancelled)
+    }
+}
+
+/// A decoded thumbnail: the object and the size it was requested at
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThumbnailKey {
+    /// Device object ID
+    pub object_id: String,
+    /// Longest side the image was decoded to fit
+    pub max_dim: u32,
+}
+
+impl ThumbnailKey {
+    /// Create a key for `object_id` at `max_dim`
+    pub fn new(object_id: impl Into<String>, max_dim: u32) -> Self {
+        Self {
+            object_id: object_id.into(),
+            max_dim,
+        }
+    }
+}
+
+/// Decoded images, least recently used evicted first once over budget
+struct LruImages {
+    entries: HashMap<ThumbnailKey, (Arc<RgbaImage>, u64)>,
+    /// Keys by last use, oldest first
+    recency: BTreeMap<u64, ThumbnailKey>,
+    next_use: u64,
+    bytes: usize,
+    max_bytes: usize,
+}
+
+impl LruImages {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_use: 0,
+            bytes: 0,
+            max_bytes,
+        }
+    }
+
+    fn get(&mut self, key: &ThumbnailKey) -> Option<Arc<RgbaImage>> {
+        let (image, last_use) = self.entries.get_mut(key)?;
+        self.recency.remove(last_use);
+        *last_use = self.next_use;
+        self.recency.insert(self.next_use, key.clone());
+        self.next_use += 1;
+        Some(Arc::clone(image))
+    }
+
+    /// Store `image`, evicting older images until it fits
+    ///
+    /// An image larger than the whole budget is not kept.
+    fn insert(&mut self, key: ThumbnailKey, image: Arc<RgbaImage>) {
+        self.remove(&key);
+        let size = image.pixels.len();
+        if size > self.max_bytes {
+            return;
+        }
+        while self.bytes + size > self.max_bytes {
+            match self.recency.pop_first() {
+                Some((_, oldest)) => self.remove(&oldest),
+                None => break,
+            }
+        }
+
+        self.bytes += size;
+        self.recency.insert(self.next_use, key.clone());
+        self.entries.insert(key, (image, self.next_use));
+        self.next_use += 1;
+    }
+
+    fn remove(&mut self, key: &ThumbnailKey) {
+        if let Some((image, last_use)) = self.entries.remove(key) {
+            self.recency.remove(&last_use);
+            self.bytes -= image.pixels.len();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.bytes = 0;
+    }
+}
+
+/// A request a worker is decoding
+struct InFlight {
+    key: ThumbnailKey,
+    /// Set when the request is cancelled
+    cancelled: Arc<AtomicBool>,
+}
+
+/// State shared between a [`DecodedThumbnailCache`] and its workers
+struct DecodeState {
+    images: LruImages,
+    /// Requests waiting for a worker, next first
+    queue: VecDeque<ThumbnailKey>,
+    /// Keys in `queue`, so each is queued once
+    queued: HashSet<ThumbnailKey>,
+    /// Requests being decoded
+    in_flight: Vec<InFlight>,
+    /// Requests whose decode failed; not retried until cleared
+    failed: HashMap<ThumbnailKey, DecodeError>,
+    shutdown: bool,
+}
+
+impl DecodeState {
+    /// Queue `key` unless it's cached, already wanted or failed
+    fn request(&mut self, key: ThumbnailKey) -> bool {
+        let decoding = self
+            .in_flight
+            .iter()
+            .any(|job| job.key == key && !job.cancelled.load(Ordering::SeqCst));
+        if decoding
+            || self.queued.contains(&key)
+            || self.images.entries.contains_key(&key)
+            || self.failed.contains_key(&key)
+        {
+            return false;
+        }
+        self.queued.insert(key.clone());
+        self.queue.push_back(key);
+        true
+    }
+
+    /// Drop queued requests and cancel decodes for which `keep` is false
+    fn cancel_unless(&mut self, keep: impl Fn(&ThumbnailKey) -> bool) {
+        let queued = &mut self.queued;
+        self.queue.retain(|key| keep(key) || !queued.remove(key));
+        for job in &self.in_flight {
+            if !keep(&job.key) {
+                job.cancelled.store(true, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+struct DecodeShared {
+    state: Mutex<DecodeState>,
+    /// Signalled when a request is queued, a decode finishes or on shutdown
+    changed: Condvar,
+    decoder: Box<dyn ThumbnailDecoder>,
+}
+
+/// Decoded thumbnails for the preview grid, bounded by memory use
+///
+/// [`get`](Self::get) answers from memory and never blocks on a decode, so
+/// the grid can call it every frame. Missing thumbnails are
+/// [requested](Self::request) or [prefetched](Self::prefetch) and decoded on
+/// a pool of worker threads; once done they show up in `get`. When the
+/// decoded pixels exceed the byte budget, the least recently used images are
+/// dropped. Requests scrolled out of view are [cancelled](Self::cancel) so
+/// they don't hold up the visible ones.
+pub struct DecodedThumbnailCache {
+    shared: Arc<DecodeShared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl DecodedThumbnailCache {
+    /// Create a cache holding up to `max_bytes` of pixels, decoding on
+    /// `workers` threads (at least one)
+    pub fn new(max_bytes: usize, workers: usize, decoder: impl ThumbnailDecoder + 'static) -> Self {
+        let shared = Arc::new(DecodeShared {
+            state: Mutex::new(DecodeState {
+                images: LruImages::new(max_bytes),
+                queue: VecDeque::new(),
+                queued: HashSet::new(),
+                in_flight: Vec::new(),
+                failed: HashMap::new(),
+                shutdown: false,
+            }),
+            changed: Condvar::new(),
+            decoder: Box::new(decoder),
+        });
+
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || run_decode_worker(&shared))
+            })
+            .collect();
+
+        Self { shared, workers }
+    }
+
+    /// Create a cache sized by the preview settings' `max_cache_size_mb`
+    ///
+    /// With caching disabled nothing is kept, so every request decodes again.
+    pub fn from_settings(
+        settings: &PreviewSettings,
+        decoder: impl ThumbnailDecoder + 'static,
+    ) -> Self {
+        let max_bytes = if settings.enable_cache {
+            settings.max_cache_size_mb as usize * 1024 * 1024
+        } else {
+            0
+        };
+        Self::new(max_bytes, DEFAULT_DECODE_WORKERS, decoder)
+    }
+
+    /// Get a decoded thumbnail if it's in memory
+    pub fn get(&self, key: &ThumbnailKey) -> Option<Arc<RgbaImage>> {
+        self.shared.state.lock().unwrap().images.get(key)
+    }
+
+    /// Why the thumbnail couldn't be decoded, if it failed
+    pub fn failure(&self, key: &ThumbnailKey) -> Option<DecodeError> {
+        self.shared.state.lock().unwrap().failed.get(key).cloned()
+    }
+
+    /// Queue a thumbnail for decoding
+    ///
+    /// Does nothing if it's already cached, queued, being decoded or failed.
+    pub fn request(&self, key: ThumbnailKey) {
+        if self.shared.state.lock().unwrap().request(key) {
+            self.shared.changed.notify_one();
+        }
+    }
+
+    /// Stop working on a thumbnail that is no longer needed
+    ///
+    /// A queued request is dropped. A decode already running is told through
+    /// its cancelled flag, and whatever it produces is not cached.
+    pub fn cancel(&self, key: &ThumbnailKey) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.cancel_unless(|other| other != key);
+        self.shared.changed.notify_all();
+    }
+
+    /// Make `object_ids` at `max_dim` the only thumbnails being decoded
+    ///
+    /// Call this as the grid scrolls, with the visible items first and then
+    /// the ones to load ahead (see [`prefetch_range`]). Requests for anything
+    /// else are [cancelled](Self::cancel), and the queue is reordered so the
+    /// given items decode in order.
+    pub fn set_visible<I, S>(&self, object_ids: I, max_dim: u32)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let wanted: Vec<_> = object_ids
+            .into_iter()
+            .map(|object_id| ThumbnailKey::new(object_id, max_dim))
+            .collect();
+        let wanted_set: HashSet<_> = wanted.iter().collect();
+
+        let mut state = self.shared.state.lock().unwrap();
+        state.cancel_unless(|key| wanted_set.contains(key));
+        state.queue.clear();
+        state.queued.clear();
+        for key in wanted {
+            state.request(key);
+        }
+        self.shared.changed.notify_all();
+    }
+
+    /// Queue thumbnails of `object_ids` at `max_dim`, decoded in the given
+    /// order
+    ///
+    /// Pass the visible items first, then the ones to load ahead; see
+    /// [`prefetch_range`].
+    pub fn prefetch<I, S>(&self, object_ids: I, max_dim: u32)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for object_id in object_ids {
+            self.request(ThumbnailKey::new(object_id, max_dim));
+        }
+    }
+
+    /// Wait until nothing is queued or being decoded
+    ///
+    /// Returns `false` if that didn't happen within `timeout`.
+    pub fn wait_idle(&self, timeout: Duration) -> bool {
+        let state = self.shared.state.lock().unwrap();
+        let (_state, result) = self
+            .shared
+            .changed
+            .wait_timeout_while(state, timeout, |state| {
+                !state.queue.is_empty() || !state.in_flight.is_empty()
+            })
+            .unwrap();
+        !result.timed_out()
+    }
+
+    /// Number of decoded thumbnails in memory
+    pub fn len(&self) -> usize {
+        self.shared.state.lock().unwrap().images.entries.len()
+    }
+
+    /// Check if no decoded thumbnails are in memory
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes of pixels in memory
+    pub fn bytes(&self) -> usize {
+        self.shared.state.lock().unwrap().images.bytes
+    }
+
+    /// Drop every cached thumbnail, request and remembered failure
+    pub fn clear(&self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.cancel_unless(|_| false);
+        state.images.clear();
+        state.failed.clear();
+        self.shared.changed.notify_all();
+    }
+}
+
+impl Drop for DecodedThumbnailCache {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.shutdown = true;
+        state.cancel_unless(|_| false);
+        drop(state);
+        self.shared.changed.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Decode queued requests until the cache is dropped
+fn run_decode_worker(shared: &DecodeShared) {
+    while let Some((key, cancelled)) = next_decode(shared) {
+        let result = shared
+            .decoder
+            .decode(&key.object_id, key.max_dim, &cancelled);
+
+        let mut state = shared.state.lock().unwrap();
+        state
+            .in_flight
+            .retain(|job| !Arc::ptr_eq(&job.cancelled, &cancelled));
+        if !cancelled.load(Ordering::SeqCst) {
+            match result {
+                Ok(image) => state.images.insert(key, Arc::new(image)),
+                Err(DecodeError::Cancelled) => {}
+                Err(e) => {
+                    state.failed.insert(key, e);
+                }
+            }
+        }
+        shared.changed.notify_all();
+    }
+}
+
+/// Wait for the next request to decode, returning it with its cancelled
+/// flag; `None` once the cache is dropped
+fn next_decode(shared: &DecodeShared) -> Option<(ThumbnailKey, Arc<AtomicBool>)> {
+    let mut state = shared.state.lock().unwrap();
+    loop {
+        if state.shutdown {
+            return None;
+        }
+        if let Some(key) = state.queue.pop_front() {
+            state.queued.remove(&key);
+            let cancelled = Arc::new(AtomicBool::new(false));
+            state.in_flight.push(InFlight {
+                key: key.clone(),
+                cancelled: Arc::clone(&cancelled),
+            });
+            return Some((key, cancelled));
+        }
+        state = shared.changed.wait(state).unwrap();
+    }
+}
+
+/// Indices of the grid items to decode: the visible ones, then up to
+/// `load_ahead` past them, within `len` items
+pub fn prefetch_range(visible: Range<usize>, load_ahead: usize, len: usize) -> Range<usize> {
+    let start = visible.start.min(len);
+    let end = visible.end.saturating_add(load_ahead).min(len);
+    start..end.max(start)
+}
+
 // =============================================================================
 // Thumbnail Generator
 // =============================================================================
@@ -408,7 +1286,8 @@ impl ThumbnailGenerator {
 
     /// Generate a thumbnail for a device object
     ///
-    /// This is a stub implementation that returns raw data.
+    /// Uses the thumbnail stored on the device when there is one. Otherwise
+    /// this falls back to a stub implementation that returns raw data.
     /// Full thumbnail generation would require the `image` crate,
     /// which was removed to reduce dependencies.
     ///
@@ -424,6 +1303,13 @@ impl ThumbnailGenerator {
             return ThumbnailResult::Success(cached);
         }
 
+        // Prefer the thumbnail the device already has; it's small and
+        // doesn't need the full file to be downloaded
+        if let Some(thumb) = self.device_thumbnail(content, object) {
+            self.cache.put(object.object_id.clone(), thumb.clone());
+            return ThumbnailResult::Success(thumb);
+        }
+
         // Check if file is too large
         if object.size > self.config.max_source_size {
             return ThumbnailResult::FileTooLarge(object.size);
@@ -520,6 +1406,29 @@ impl ThumbnailGenerator {
         ThumbnailResult::NotAvailable
     }
 
+    /// Fetch the thumbnail stored on the device for an object, if any
+    fn device_thumbnail<C: DeviceContentTrait>(
+        &self,
+        content: &C,
+        object: &DeviceObject,
+    ) -> Option<Thumbnail> {
+        let data = content.read_thumbnail(&object.object_id)?;
+        let (mime_type, dimensions) = sniff_image(&data);
+        let (width, height) = dimensions.unwrap_or((0, 0));
+
+        Some(Thumbnail {
+            data,
+            // The device doesn't tell us the original dimensions
+            original_width: 0,
+            original_height: 0,
+            width,
+            height,
+            mime_type: mime_type.to_string(),
+            original_size: object.size,
+            generated_at: Instant::now(),
+        })
+    }
+
     /// Generate thumbnails for multiple objects (batch operation)
     pub fn generate_batch<C: DeviceContentTrait>(
         &self,
@@ -830,6 +1739,9 @@ impl Default for PreviewManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::device::DeviceManagerTrait;
+    use crate::testdb::{MockDeviceInfo, MockDeviceManager, MockFileSystem, MockObject};
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn test_thumbnail_config_default() {
@@ -1008,4 +1920,398 @@ mod tests {
         let ratio = thumb.original_aspect_ratio();
         assert!((ratio - 1.777).abs() < 0.01); // 16:9 ratio
     }
+
+    /// A PNG header claiming a 160x120 image
+    fn canned_png_thumbnail() -> Vec<u8> {
+        let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+        data.extend_from_slice(&13u32.to_be_bytes());
+        data.extend_from_slice(b"IHDR");
+        data.extend_from_slice(&160u32.to_be_bytes());
+        data.extend_from_slice(&120u32.to_be_bytes());
+        data
+    }
+
+    fn device_with_thumbnail() -> MockDeviceManager {
+        let mut fs = MockFileSystem::new();
+        fs.add_object(MockObject::folder("dcim", "DEVICE", "DCIM"));
+        fs.add_object(
+            MockObject::file("with-thumb", "dcim", "IMG_0001.HEIC", vec![0; 64])
+                .with_thumbnail(canned_png_thumbnail()),
+        );
+        fs.add_object(MockObject::file(
+            "no-thumb",
+            "dcim",
+            "IMG_0002.HEIC",
+            vec![0; 64],
+        ));
+
+        let mut manager = MockDeviceManager::new();
+        let device = MockDeviceInfo::new("thumb-device", "iPhone", "Apple Inc.", "iPhone 15");
+        manager.add_device(device, fs);
+        manager
+    }
+
+    #[test]
+    fn test_preview_uses_device_thumbnail() {
+        let devices = device_with_thumbnail();
+        let content = devices.open_device("thumb-device").unwrap();
+
+        let manager = PreviewManager::new();
+        let objects = content.enumerate_children("dcim").unwrap();
+        manager.add_items(
+            objects
+                .into_iter()
+                .map(|obj| (obj, "DCIM".to_string()))
+                .collect(),
+        );
+
+        let with_thumb = manager
+            .items()
+            .iter()
+            .position(|item| item.object_id == "with-thumb")
+            .unwrap();
+        assert!(manager.load_thumbnail(&content, with_thumb));
+        let thumb = manager.get(with_thumb).unwrap().thumbnail.unwrap();
+        assert_eq!(thumb.data, canned_png_thumbnail());
+        assert_eq!(thumb.mime_type, "image/png");
+        assert_eq!((thumb.width, thumb.height), (160, 120));
+        assert_eq!(manager.cache_stats().entries, 1);
+
+        // Without a device thumbnail, HEIC falls back to "not available"
+        let no_thumb = manager
+            .items()
+            .iter()
+            .position(|item| item.object_id == "no-thumb")
+            .unwrap();
+        assert!(!manager.load_thumbnail(&content, no_thumb));
+        assert!(manager.get(no_thumb).unwrap().thumbnail_error.is_some());
+    }
+
+    /// Encode a solid-colour JPEG of the given size
+    fn sample_jpeg(width: u32, height: u32) -> Vec<u8> {
+        let image = image::RgbImage::from_pixel(width, height, image::Rgb([200, 100, 50]));
+        let mut data = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new(&mut data)
+            .encode_image(&image)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_decode_thumbnail_downscales_jpeg() {
+        let decoded = decode_thumbnail(&sample_jpeg(64, 32), 16).unwrap();
+        assert_eq!((decoded.width, decoded.height), (16, 8));
+        assert_eq!(decoded.pixels.len(), 16 * 8 * 4);
+        // Opaque, roughly the source colour
+        assert_eq!(decoded.pixels[3], 255);
+        assert!(decoded.pixels[0] > 150);
+    }
+
+    #[test]
+    fn test_decode_thumbnail_does_not_enlarge() {
+        let decoded = decode_thumbnail(&sample_jpeg(10, 20), 256).unwrap();
+        assert_eq!((decoded.width, decoded.height), (10, 20));
+    }
+
+    #[test]
+    fn test_decode_thumbnail_errors() {
+        assert_eq!(
+            decode_thumbnail(b"not an image", 64),
+            Err(DecodeError::UnsupportedFormat)
+        );
+
+        let mut truncated = sample_jpeg(64, 64);
+        truncated.truncate(40);
+        assert!(matches!(
+            decode_thumbnail(&truncated, 64),
+            Err(DecodeError::Decode(_))
+        ));
+    }
+
+    /// 16x8 JPEG, red on the left and blue on the right, tagged with an EXIF
+    /// `Orientation` of `orientation`
+    fn oriented_jpeg(orientation: u16) -> Vec<u8> {
+        let image = image::RgbImage::from_fn(16, 8, |x, _| {
+            if x < 8 {
+                image::Rgb([255, 0, 0])
+            } else {
+                image::Rgb([0, 0, 255])
+            }
+        });
+        let mut encoded = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut encoded, 100)
+            .encode_image(&image)
+            .unwrap();
+
+        // Big-endian TIFF header with a single IFD entry: Orientation (SHORT)
+        let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01".to_vec();
+        exif.extend_from_slice(&orientation.to_be_bytes());
+        exif.extend_from_slice(&[0; 6]);
+
+        let mut data = encoded[..2].to_vec();
+        data.extend_from_slice(&[0xFF, 0xE1]);
+        data.extend_from_slice(&(exif.len() as u16 + 2).to_be_bytes());
+        data.extend_from_slice(&exif);
+        data.extend_from_slice(&encoded[2..]);
+        data
+    }
+
+    fn is_red(image: &RgbaImage, x: u32, y: u32) -> bool {
+        let i = ((y * image.width + x) * 4) as usize;
+        image.pixels[i] > 200 && image.pixels[i + 2] < 60
+    }
+
+    #[test]
+    fn test_decode_thumbnail_applies_exif_orientation() {
+        let normal = decode_thumbnail(&oriented_jpeg(1), 64).unwrap();
+        assert_eq!(normal.orientation, Orientation::Normal);
+        assert_eq!((normal.width, normal.height), (16, 8));
+        assert!(is_red(&normal, 0, 0));
+        assert!(!is_red(&normal, 15, 0));
+
+        let upside_down = decode_thumbnail(&oriented_jpeg(3), 64).unwrap();
+        assert_eq!(upside_down.orientation, Orientation::Rotate180);
+        assert_eq!((upside_down.width, upside_down.height), (16, 8));
+        assert!(!is_red(&upside_down, 0, 7));
+        assert!(is_red(&upside_down, 15, 7));
+
+        // Rotated a quarter turn clockwise: the red left half ends up on top
+        let clockwise = decode_thumbnail(&oriented_jpeg(6), 64).unwrap();
+        assert_eq!(clockwise.orientation, Orientation::Rotate90);
+        assert_eq!((clockwise.width, clockwise.height), (8, 16));
+        assert!(is_red(&clockwise, 0, 0));
+        assert!(!is_red(&clockwise, 0, 15));
+
+        // A quarter turn counter-clockwise puts it at the bottom
+        let counter = decode_thumbnail(&oriented_jpeg(8), 64).unwrap();
+        assert_eq!(counter.orientation, Orientation::Rotate270);
+        assert_eq!((counter.width, counter.height), (8, 16));
+        assert!(!is_red(&counter, 7, 0));
+        assert!(is_red(&counter, 7, 15));
+    }
+
+    #[test]
+    fn test_decode_thumbnail_unrotated_reports_orientation() {
+        let decoded = decode_thumbnail_unrotated(&oriented_jpeg(6), 64).unwrap();
+        assert_eq!(decoded.orientation, Orientation::Rotate90);
+        assert!(decoded.orientation.swaps_dimensions());
+        assert_eq!((decoded.width, decoded.height), (16, 8));
+        assert!(is_red(&decoded, 0, 0));
+
+        // Untagged images are upright as stored
+        let plain = decode_thumbnail(&sample_jpeg(10, 20), 64).unwrap();
+        assert_eq!(plain.orientation, Orientation::Normal);
+
+        for value in 1..=8 {
+            assert_eq!(Orientation::from_exif(value).unwrap().exif_value(), value);
+        }
+        assert_eq!(Orientation::from_exif(0), None);
+        assert_eq!(Orientation::from_exif(9), None);
+    }
+
+    #[test]
+    fn test_decode_thumbnail_heic_is_feature_gated() {
+        let mut heic = vec![0, 0, 0, 24];
+        heic.extend_from_slice(b"ftypheic");
+        heic.extend_from_slice(&[0; 12]);
+        assert!(is_heif(&heic));
+
+        let result = decode_thumbnail(&heic, 64);
+        if cfg!(feature = "heic") {
+            // Only a header, so libheif rejects it
+            assert!(matches!(result, Err(DecodeError::Decode(_))));
+        } else {
+            assert_eq!(result, Err(DecodeError::HeicNotEnabled));
+        }
+    }
+
+    #[cfg(not(feature = "video-thumbnails"))]
+    #[test]
+    fn test_video_thumbnail_needs_feature() {
+        assert_eq!(
+            video_thumbnail(VideoSource::Bytes(&[0; 16]), 0.0),
+            Err(DecodeError::VideoNotEnabled)
+        );
+    }
+
+    /// Needs ffmpeg on `PATH`, which also generates the sample clip
+    #[cfg(feature = "video-thumbnails")]
+    #[test]
+    fn test_video_thumbnail_extracts_frame() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let clip = temp_dir.path().join("sample.mp4");
+        let status = std::process::Command::new("ffmpeg")
+            .args([
+                "-v",
+                "error",
+                "-f",
+                "lavfi",
+                "-i",
+                "testsrc=size=64x48:duration=1",
+            ])
+            .arg(&clip)
+            .status()
+            .expect("ffmpeg is required for video thumbnail tests");
+        assert!(status.success());
+
+        let frame = video_thumbnail(VideoSource::Path(&clip), 0.5).unwrap();
+        assert_eq!((frame.width, frame.height), (64, 48));
+        assert_eq!(frame.pixels.len(), 64 * 48 * 4);
+
+        // In-memory videos go through a temp file
+        let bytes = std::fs::read(&clip).unwrap();
+        let frame = video_thumbnail(VideoSource::Bytes(&bytes), 0.0).unwrap();
+        assert!(frame.width > 0 && frame.height > 0);
+
+        assert!(matches!(
+            video_thumbnail(VideoSource::Bytes(b"not a video"), 0.0),
+            Err(DecodeError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn test_jpeg_dimensions() {
+        // SOI, an APP0 segment, then SOF0 for a 640x480 frame
+        let mut data = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00];
+        data.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80]);
+        assert_eq!(jpeg_dimensions(&data), Some((640, 480)));
+        assert_eq!(sniff_image(&data).0, "image/jpeg");
+
+        assert_eq!(jpeg_dimensions(b"not a jpeg"), None);
+    }
+
+    /// Solid `size` x `size` image, `size * size * 4` bytes
+    fn solid_image(size: u32) -> RgbaImage {
+        RgbaImage {
+            width: size,
+            height: size,
+            pixels: vec![0x80; (size * size * 4) as usize],
+            orientation: Orientation::Normal,
+        }
+    }
+
+    /// Decoder producing `max_dim`-sized images and counting its calls
+    fn counting_decoder(calls: Arc<AtomicUsize>) -> impl ThumbnailDecoder {
+        move |_: &str, max_dim: u32, _: &AtomicBool| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(solid_image(max_dim))
+        }
+    }
+
+    #[test]
+    fn test_decoded_cache_evicts_least_recently_used() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        // 10x10 images are 400 bytes, so two fit
+        let cache = DecodedThumbnailCache::new(1000, 2, counting_decoder(calls));
+        let key = |id: &str| ThumbnailKey::new(id, 10);
+
+        cache.prefetch(["a", "b"], 10);
+        assert!(cache.wait_idle(Duration::from_secs(5)));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.bytes(), 800);
+
+        // Using "a" leaves "b" as the least recently used
+        assert!(cache.get(&key("a")).is_some());
+        cache.prefetch(["c"], 10);
+        assert!(cache.wait_idle(Duration::from_secs(5)));
+
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("c")).is_some());
+        assert_eq!(cache.bytes(), 800);
+
+        // An image bigger than the whole budget isn't kept
+        cache.prefetch(["huge"], 20);
+        assert!(cache.wait_idle(Duration::from_secs(5)));
+        assert!(cache.get(&ThumbnailKey::new("huge", 20)).is_none());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_decoded_cache_hit_does_not_decode_again() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = DecodedThumbnailCache::new(1 << 20, 4, counting_decoder(Arc::clone(&calls)));
+        let key = ThumbnailKey::new("IMG_0001", 32);
+
+        assert!(cache.get(&key).is_none());
+        cache.prefetch(["IMG_0001", "IMG_0001"], 32);
+        assert!(cache.wait_idle(Duration::from_secs(5)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        for _ in 0..3 {
+            let image = cache.get(&key).unwrap();
+            assert_eq!((image.width, image.height), (32, 32));
+            cache.request(key.clone());
+        }
+        assert!(cache.wait_idle(Duration::from_secs(5)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Another size of the same object is a separate entry
+        cache.request(ThumbnailKey::new("IMG_0001", 64));
+        assert!(cache.wait_idle(Duration::from_secs(5)));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_decoded_cache_remembers_failures() {
+        let cache = DecodedThumbnailCache::new(1 << 20, 1, |_: &str, _: u32, _: &AtomicBool| {
+            Err(DecodeError::UnsupportedFormat)
+        });
+        let key = ThumbnailKey::new("clip.mov", 64);
+
+        cache.request(key.clone());
+        assert!(cache.wait_idle(Duration::from_secs(5)));
+        assert!(cache.get(&key).is_none());
+        assert_eq!(cache.failure(&key), Some(DecodeError::UnsupportedFormat));
+    }
+
+    #[test]
+    fn test_prefetch_range_adds_load_ahead() {
+        assert_eq!(prefetch_range(10..20, 5, 100), 10..25);
+        assert_eq!(prefetch_range(90..100, 20, 100), 90..100);
+        assert_eq!(prefetch_range(0..0, 20, 5), 0..5);
+        assert_eq!(prefetch_range(120..130, 20, 100), 100..100);
+    }
+
+    #[test]
+    fn test_decoded_cache_cancels_requests_scrolled_away() {
+        use std::sync::mpsc;
+
+        let (started_tx, started_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let decoded = Arc::new(Mutex::new(Vec::new()));
+        let decoder = {
+            let decoded = Arc::clone(&decoded);
+            let started_tx = Mutex::new(started_tx);
+            let release_rx = Mutex::new(release_rx);
+            move |object_id: &str, max_dim: u32, cancelled: &AtomicBool| {
+                decoded.lock().unwrap().push(object_id.to_string());
+                if object_id == "0" {
+                    // Hold the only worker until the test has scrolled
+                    started_tx.lock().unwrap().send(()).unwrap();
+                    release_rx.lock().unwrap().recv().unwrap();
+                    assert!(cancelled.load(Ordering::SeqCst));
+                }
+                Ok(solid_image(max_dim))
+            }
+        };
+        let cache = DecodedThumbnailCache::new(1 << 20, 1, decoder);
+        let ids: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+
+        cache.prefetch(ids.iter().cloned(), 16);
+        started_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        // Scroll so only 7 and 8 are visible, 8 first; "0" is mid-decode
+        cache.set_visible(["8", "7"], 16);
+        release_tx.send(()).unwrap();
+        assert!(cache.wait_idle(Duration::from_secs(5)));
+
+        assert_eq!(*decoded.lock().unwrap(), ["0", "8", "7"]);
+        for id in &ids {
+            let cached = cache.get(&ThumbnailKey::new(id.as_str(), 16)).is_some();
+            assert_eq!(cached, id == "7" || id == "8", "thumbnail {}", id);
+        }
+        assert_eq!(cache.failure(&ThumbnailKey::new("0", 16)), None);
+    }
 }
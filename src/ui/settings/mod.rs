@@ -33,10 +33,35 @@
 //! manager.settings_mut().appearance.font_size = 16.0;
 //! manager.save().ok();
 //! ```
+//!
+//! # Change Notifications
+//!
+//! ```rust,no_run
+//! use photo_extraction_tool::ui::settings::SettingsManager;
+//!
+//! let mut manager = SettingsManager::new();
+//!
+//! // Called with every setting that differs from the last saved state
+//! manager.subscribe(|changes| {
+//!     for change in changes {
+//!         println!("{} changed", change);
+//!     }
+//! });
+//!
+//! // `apply` reports exactly what a change touched
+//! let changes = manager.apply(|s| s.appearance.font_size = 16.0);
+//! assert_eq!(changes[0].key, "appearance.font_size");
+//! manager.save().ok();
+//! ```
 
 use crate::ui::theme::ThemeMode;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 // =============================================================================
 // UiSettings
@@ -105,19 +130,93 @@ impl UiSettings {
     }
 
     /// Merge with another settings instance (other takes precedence for non-default values)
+    ///
+    /// Merging is per field: a field of `other` that still has its default
+    /// value leaves the field of `self` alone, so a sparse override only
+    /// changes what it sets.
     pub fn merge(&mut self, other: &UiSettings) {
-        // This is a simple implementation - you could make it more sophisticated
-        // by only merging non-default values
-        self.appearance = other.appearance.clone();
-        self.behavior = other.behavior.clone();
-        self.panels = other.panels.clone();
-        self.preview = other.preview.clone();
-        self.keyboard = other.keyboard.clone();
-        self.accessibility = other.accessibility.clone();
-        self.window = other.window.clone();
+        let overrides = UiSettings::default().diff(other);
+        if overrides.is_empty() {
+            return;
+        }
+
+        let source = serde_json::to_value(other).unwrap_or_default();
+        let mut merged = serde_json::to_value(&*self).unwrap_or_default();
+        for change in &overrides {
+            let pointer = format!("/{}", change.key.replace('.', "/"));
+            if let (Some(target), Some(value)) =
+                (merged.pointer_mut(&pointer), source.pointer(&pointer))
+            {
+                *target = value.clone();
+            }
+        }
+        if let Ok(merged) = serde_json::from_value(merged) {
+            *self = merged;
+        }
+    }
+
+    /// Settings whose value differs in `other`, as dotted keys sorted by name
+    pub fn diff(&self, other: &UiSettings) -> Vec<ChangedSetting> {
+        let before = serde_json::to_value(self).unwrap_or_default();
+        let after = serde_json::to_value(other).unwrap_or_default();
+        let mut changes = Vec::new();
+        diff_values("", &before, &after, &mut changes);
+        changes
+    }
+}
+
+fn diff_values(prefix: &str, before: &Value, after: &Value, changes: &mut Vec<ChangedSetting>) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                diff_values(
+                    &path,
+                    before.get(key).unwrap_or(&Value::Null),
+                    after.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if before != after => changes.push(ChangedSetting {
+            key: prefix.to_string(),
+        }),
+        _ => {}
+    }
+}
+
+// =============================================================================
+// Change Notifications
+// =============================================================================
+
+/// A setting whose value changed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChangedSetting {
+    /// Dotted key as in the settings file, e.g. `appearance.font_size`
+    pub key: String,
+}
+
+impl ChangedSetting {
+    /// Settings group the key belongs to, e.g. `appearance`
+    pub fn group(&self) -> &str {
+        self.key.split('.').next().unwrap_or(&self.key)
     }
 }
 
+impl fmt::Display for ChangedSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.key)
+    }
+}
+
+/// Callback invoked with the settings changed by a save
+pub type SettingsListener = Arc<dyn Fn(&[ChangedSetting]) + Send + Sync>;
+
 // =============================================================================
 // Appearance Settings
 // =============================================================================
@@ -214,6 +313,9 @@ impl Default for AppearanceSettings {
 }
 
 impl AppearanceSettings {
+    /// Allowed values for `ui_scale`
+    pub const UI_SCALE_RANGE: RangeInclusive<f32> = 0.5..=3.0;
+
     /// Validate appearance settings
     pub fn validate(&self) -> Result<(), SettingsError> {
         if self.font_size < 8.0 || self.font_size > 72.0 {
@@ -223,7 +325,7 @@ impl AppearanceSettings {
             });
         }
 
-        if self.ui_scale < 0.5 || self.ui_scale > 3.0 {
+        if !Self::UI_SCALE_RANGE.contains(&self.ui_scale) {
             return Err(SettingsError::InvalidValue {
                 setting: "ui_scale".to_string(),
                 reason: "UI scale must be between 0.5 and 3.0".to_string(),
@@ -342,6 +444,10 @@ pub struct BehaviorSettings {
     /// Sound volume (0.0 - 1.0)
     pub sound_volume: f32,
 
+    /// WAV file played when an extraction finishes (`None` = no sound)
+    #[serde(default)]
+    pub sound_path: Option<PathBuf>,
+
     /// Remember window position
     pub remember_window_position: bool,
 
@@ -381,6 +487,7 @@ impl Default for BehaviorSettings {
             show_notifications: true,
             play_sounds: false,
             sound_volume: 0.5,
+            sound_path: None,
             remember_window_position: true,
             remember_panel_layout: true,
             open_folder_after_extraction: false,
@@ -394,6 +501,9 @@ impl Default for BehaviorSettings {
 }
 
 impl BehaviorSettings {
+    /// Allowed values for `sound_volume`
+    pub const SOUND_VOLUME_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+
     /// Validate behavior settings
     pub fn validate(&self) -> Result<(), SettingsError> {
         if self.animation_speed < 0.1 || self.animation_speed > 5.0 {
@@ -403,7 +513,7 @@ impl BehaviorSettings {
             });
         }
 
-        if self.sound_volume < 0.0 || self.sound_volume > 1.0 {
+        if !Self::SOUND_VOLUME_RANGE.contains(&self.sound_volume) {
             return Err(SettingsError::InvalidValue {
                 setting: "sound_volume".to_string(),
                 reason: "Sound volume must be between 0.0 and 1.0".to_string(),
@@ -631,20 +741,8 @@ pub enum ViewMode {
     Details,
 }
 
-/// Sort options
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
-#[serde(rename_all = "lowercase")]
-pub enum SortBy {
-    /// Sort by name
-    Name,
-    /// Sort by date
-    #[default]
-    Date,
-    /// Sort by size
-    Size,
-    /// Sort by type/extension
-    Type,
-}
+/// Sort options, shared with the order device folders are scanned in
+pub use crate::core::config::SortBy;
 
 // =============================================================================
 // Keyboard Settings
@@ -971,16 +1069,33 @@ pub enum SettingsError {
 // =============================================================================
 
 /// Manages loading, saving, and accessing settings
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct SettingsManager {
     /// Current settings
     settings: UiSettings,
 
+    /// Settings as last loaded or saved, to diff against on save
+    saved: UiSettings,
+
     /// Path to settings file
     settings_path: PathBuf,
 
     /// Whether settings have been modified
     dirty: bool,
+
+    /// Callbacks notified of changes on save
+    listeners: Vec<SettingsListener>,
+}
+
+impl fmt::Debug for SettingsManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SettingsManager")
+            .field("settings", &self.settings)
+            .field("settings_path", &self.settings_path)
+            .field("dirty", &self.dirty)
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
 }
 
 impl SettingsManager {
@@ -990,8 +1105,10 @@ impl SettingsManager {
 
         Self {
             settings: UiSettings::default(),
+            saved: UiSettings::default(),
             settings_path,
             dirty: false,
+            listeners: Vec::new(),
         }
     }
 
@@ -999,8 +1116,10 @@ impl SettingsManager {
     pub fn with_path(path: PathBuf) -> Self {
         Self {
             settings: UiSettings::default(),
+            saved: UiSettings::default(),
             settings_path: path,
             dirty: false,
+            listeners: Vec::new(),
         }
     }
 
@@ -1029,6 +1148,35 @@ impl SettingsManager {
         &mut self.settings
     }
 
+    /// Change settings and report which ones actually changed
+    ///
+    /// Only marks the settings dirty if something changed.
+    pub fn apply<F: FnOnce(&mut UiSettings)>(&mut self, f: F) -> Vec<ChangedSetting> {
+        let before = self.settings.clone();
+        f(&mut self.settings);
+        let changes = before.diff(&self.settings);
+        if !changes.is_empty() {
+            self.dirty = true;
+        }
+        changes
+    }
+
+    /// Settings that differ from the last loaded or saved state
+    pub fn pending_changes(&self) -> Vec<ChangedSetting> {
+        self.saved.diff(&self.settings)
+    }
+
+    /// Register a callback invoked after each save that changed something
+    ///
+    /// The callback receives every setting that differs from the previous
+    /// save, however it was changed.
+    pub fn subscribe<F>(&mut self, listener: F)
+    where
+        F: Fn(&[ChangedSetting]) + Send + Sync + 'static,
+    {
+        self.listeners.push(Arc::new(listener));
+    }
+
     /// Check if settings have been modified
     pub fn is_dirty(&self) -> bool {
         self.dirty
@@ -1050,6 +1198,7 @@ impl SettingsManager {
         // Validate loaded settings
         self.settings.validate()?;
 
+        self.saved = self.settings.clone();
         self.dirty = false;
         Ok(())
     }
@@ -1071,6 +1220,13 @@ impl SettingsManager {
             .map_err(|e| SettingsError::IoError(e.to_string()))?;
 
         self.dirty = false;
+        let changes = self.pending_changes();
+        self.saved = self.settings.clone();
+        if !changes.is_empty() {
+            for listener in &self.listeners {
+                listener(&changes);
+            }
+        }
         Ok(())
     }
 
@@ -1257,4 +1413,115 @@ mod tests {
         settings.text_spacing = 3.0;
         assert!(settings.validate().is_err());
     }
+
+    #[test]
+    fn test_apply_reports_changed_field() {
+        let mut manager = SettingsManager::new();
+
+        let changes = manager.apply(|s| s.appearance.font_size = 16.0);
+        assert_eq!(
+            changes,
+            vec![ChangedSetting {
+                key: "appearance.font_size".to_string()
+            }]
+        );
+        assert_eq!(changes[0].group(), "appearance");
+        assert!(manager.is_dirty());
+
+        // Setting a value to what it already is changes nothing
+        let mut manager = SettingsManager::new();
+        let changes = manager.apply(|s| s.appearance.font_size = 14.0);
+        assert!(changes.is_empty());
+        assert!(!manager.is_dirty());
+    }
+
+    #[test]
+    fn test_diff_lists_every_changed_key() {
+        let before = UiSettings::default();
+        let mut after = before.clone();
+        after.appearance.theme_mode = ThemeMode::Light;
+        after.window.last_x = Some(10);
+
+        let keys: Vec<String> = before.diff(&after).into_iter().map(|c| c.key).collect();
+        assert_eq!(keys, vec!["appearance.theme_mode", "window.last_x"]);
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn test_subscribers_notified_on_save() {
+        use std::sync::Mutex;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = SettingsManager::with_path(dir.path().join("ui_settings.toml"));
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&notified);
+        manager.subscribe(move |changes| sink.lock().unwrap().push(changes.to_vec()));
+
+        manager.apply(|s| s.appearance.font_size = 16.0);
+        manager.settings_mut().behavior.animation_speed = 2.0;
+        assert!(notified.lock().unwrap().is_empty());
+
+        manager.save().unwrap();
+        {
+            let notified = notified.lock().unwrap();
+            assert_eq!(notified.len(), 1);
+            let keys: Vec<&str> = notified[0].iter().map(|c| c.key.as_str()).collect();
+            assert_eq!(
+                keys,
+                vec!["appearance.font_size", "behavior.animation_speed"]
+            );
+        }
+        assert!(manager.pending_changes().is_empty());
+
+        // Saving again with nothing changed doesn't notify
+        manager.save().unwrap();
+        assert_eq!(notified.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_merge_only_applies_non_default_fields() {
+        let mut settings = UiSettings::default();
+        settings.appearance.font_size = 18.0;
+        settings.behavior.animation_speed = 2.0;
+        settings.window.last_width = Some(1600);
+
+        let mut overrides = UiSettings::default();
+        overrides.appearance.theme_mode = ThemeMode::Light;
+
+        settings.merge(&overrides);
+        assert_eq!(settings.appearance.theme_mode, ThemeMode::Light);
+        assert_eq!(settings.appearance.font_size, 18.0);
+        assert_eq!(settings.behavior.animation_speed, 2.0);
+        assert_eq!(settings.window.last_width, Some(1600));
+
+        let keys: Vec<String> = UiSettings::default()
+            .diff(&settings)
+            .into_iter()
+            .map(|c| c.key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "appearance.font_size",
+                "appearance.theme_mode",
+                "behavior.animation_speed",
+                "window.last_width"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_overrides_changed_fields() {
+        let mut settings = UiSettings::default();
+        settings.appearance.font_size = 18.0;
+
+        let mut overrides = UiSettings::default();
+        overrides.appearance.font_size = 20.0;
+        settings.merge(&overrides);
+        assert_eq!(settings.appearance.font_size, 20.0);
+
+        // Merging defaults changes nothing
+        settings.merge(&UiSettings::default());
+        assert_eq!(settings.appearance.font_size, 20.0);
+    }
 }
@@ -0,0 +1,559 @@
+//! Status Model Module
+//!
+//! Aggregates the device and extraction events a UI receives into the state
+//! shown by the [`PanelId::Status`](crate::ui::panels::PanelId::Status) panel:
+//! which device is connected and in what state, its battery and storage, how
+//! far the current extraction has got, and the last error reported.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::device::{DeviceInfo, StorageInfo};
+use crate::ui::controller::ControllerState;
+use crate::ui::device_monitor::{DeviceState, DeviceStateChecker};
+use crate::ui::events::{AppEvent, DeviceEvent, ExtractionEvent, LogLevel, UiEvent};
+
+// =============================================================================
+// Extraction Status
+// =============================================================================
+
+/// Progress of the current or most recent extraction
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractionStatus {
+    /// Phase the extraction is in
+    pub state: ControllerState,
+    /// Files extracted so far
+    pub files_extracted: usize,
+    /// Files skipped so far
+    pub files_skipped: usize,
+    /// Duplicates found so far
+    pub duplicates_found: usize,
+    /// Errors so far
+    pub errors: usize,
+    /// Files found on the device (0 until scanning reports some)
+    pub total_files: usize,
+    /// Bytes processed so far
+    pub bytes_processed: u64,
+    /// Total bytes to process (0 until scanning completes)
+    pub total_bytes: u64,
+    /// Current speed in bytes per second
+    pub speed_bps: u64,
+    /// Estimated time remaining
+    pub eta: Option<Duration>,
+    /// Percentage complete (0.0 - 100.0)
+    pub percent_complete: f64,
+    /// File being copied right now
+    pub current_file: Option<String>,
+    /// Folder the files are written to, once known
+    pub output_dir: Option<PathBuf>,
+}
+
+impl ExtractionStatus {
+    fn new() -> Self {
+        Self {
+            state: ControllerState::Scanning,
+            files_extracted: 0,
+            files_skipped: 0,
+            duplicates_found: 0,
+            errors: 0,
+            total_files: 0,
+            bytes_processed: 0,
+            total_bytes: 0,
+            speed_bps: 0,
+            eta: None,
+            percent_complete: 0.0,
+            current_file: None,
+            output_dir: None,
+        }
+    }
+
+    fn apply(&mut self, event: &ExtractionEvent) {
+        match event {
+            ExtractionEvent::ScanStarted => self.state = ControllerState::Scanning,
+            ExtractionEvent::ScanProgress { files_found, .. } => {
+                self.state = ControllerState::Scanning;
+                self.total_files = *files_found;
+            }
+            ExtractionEvent::ScanComplete {
+                total_files,
+                total_bytes,
+                ..
+            } => {
+                self.state = ControllerState::Extracting;
+                self.total_files = *total_files;
+                self.total_bytes = *total_bytes;
+            }
+            ExtractionEvent::FileStarted { name, total, .. } => {
+                self.state = ControllerState::Extracting;
+                self.total_files = *total;
+                self.current_file = Some(name.clone());
+            }
+            ExtractionEvent::Progress {
+                files_extracted,
+                files_skipped,
+                duplicates_found,
+                errors,
+                bytes_processed,
+                eta,
+                speed_bps,
+                percent_complete,
+            } => {
+                self.files_extracted = *files_extracted;
+                self.files_skipped = *files_skipped;
+                self.duplicates_found = *duplicates_found;
+                self.errors = *errors;
+                self.bytes_processed = *bytes_processed;
+                self.eta = *eta;
+                self.speed_bps = *speed_bps;
+                self.percent_complete = *percent_complete;
+            }
+            ExtractionEvent::Paused { .. } => self.state = ControllerState::Paused,
+            ExtractionEvent::Resumed => self.state = ControllerState::Extracting,
+            ExtractionEvent::Cancelling => self.state = ControllerState::Cancelling,
+            ExtractionEvent::Cancelled {
+                files_extracted, ..
+            } => {
+                self.state = ControllerState::Idle;
+                self.files_extracted = *files_extracted;
+                self.finish();
+            }
+            ExtractionEvent::Completed { stats } => {
+                self.state = ControllerState::Completed;
+                self.files_extracted = stats.files_extracted;
+                self.files_skipped = stats.files_skipped;
+                self.duplicates_found = stats.duplicates_found;
+                self.errors = stats.errors;
+                self.bytes_processed = stats.bytes_transferred;
+                self.percent_complete = 100.0;
+                self.output_dir = Some(stats.output_dir.clone());
+                self.finish();
+                self.speed_bps = stats.average_speed_bps;
+            }
+            ExtractionEvent::FatalError { .. } => {
+                self.state = ControllerState::Error;
+                self.finish();
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(&mut self) {
+        self.current_file = None;
+        self.eta = None;
+        self.speed_bps = 0;
+    }
+
+    /// Check if the extraction is still running (including paused)
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.state,
+            ControllerState::Scanning
+                | ControllerState::Extracting
+                | ControllerState::Paused
+                | ControllerState::Cancelling
+        )
+    }
+}
+
+// =============================================================================
+// Status Model
+// =============================================================================
+
+/// Everything the status bar shows, kept up to date from [`UiEvent`]s
+///
+/// Feed it every event drained from the controller and device monitor
+/// channels with [`update_from_events`](Self::update_from_events); the most
+/// recently connected device replaces the previous one.
+#[derive(Debug, Clone)]
+pub struct StatusModel {
+    /// Device shown in the status bar
+    pub device: Option<DeviceInfo>,
+    /// Connection state of that device
+    pub connection: DeviceState,
+    /// Battery charge as a percentage, if the device reports it
+    pub battery_level: Option<u8>,
+    /// Storage capacity and free space, if known
+    pub storage: Option<StorageInfo>,
+    /// Current or most recent extraction
+    pub extraction: Option<ExtractionStatus>,
+    /// Last error reported by the device, the extraction or the app
+    pub last_error: Option<String>,
+}
+
+impl Default for StatusModel {
+    fn default() -> Self {
+        Self {
+            device: None,
+            connection: DeviceState::Disconnected,
+            battery_level: None,
+            storage: None,
+            extraction: None,
+            last_error: None,
+        }
+    }
+}
+
+impl StatusModel {
+    /// Create an empty model with no device
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a batch of events in order
+    pub fn update_from_events(&mut self, events: &[UiEvent]) {
+        for event in events {
+            self.update(event);
+        }
+    }
+
+    /// Apply a single event
+    pub fn update(&mut self, event: &UiEvent) {
+        match event {
+            UiEvent::Device(event) => self.apply_device_event(event),
+            UiEvent::Extraction(event) => self.apply_extraction_event(event),
+            UiEvent::App(event) => self.apply_app_event(event),
+        }
+    }
+
+    /// Friendly name of the device shown, if any
+    pub fn device_name(&self) -> Option<&str> {
+        self.device.as_ref().map(|d| d.friendly_name.as_str())
+    }
+
+    /// User-facing description of the connection state
+    pub fn connection_message(&self) -> &'static str {
+        DeviceStateChecker::state_message(&self.connection)
+    }
+
+    /// Check if an extraction is running
+    pub fn is_extracting(&self) -> bool {
+        self.extraction.as_ref().is_some_and(|e| e.is_active())
+    }
+
+    /// Forget the last error, e.g. once the user dismissed it
+    pub fn clear_error(&mut self) {
+        self.last_error = None;
+    }
+
+    fn is_current_device(&self, device_id: &str) -> bool {
+        self.device
+            .as_ref()
+            .is_none_or(|device| device.device_id == device_id)
+    }
+
+    fn set_device(&mut self, device: &DeviceInfo, state: DeviceState) {
+        if !self.is_current_device(&device.device_id) {
+            self.battery_level = None;
+            self.storage = None;
+        }
+        self.battery_level = device.battery_level.or(self.battery_level);
+        self.storage = device.storage.or(self.storage);
+        self.device = Some(device.clone());
+        self.connection = state;
+    }
+
+    fn apply_device_event(&mut self, event: &DeviceEvent) {
+        match event {
+            DeviceEvent::Connected { device, .. } => {
+                self.set_device(device, DeviceState::Connected);
+            }
+            DeviceEvent::Disconnected { device_id, .. } => {
+                if self.is_current_device(device_id) {
+                    self.connection = DeviceState::Disconnected;
+                }
+            }
+            DeviceEvent::TrustStatusChanged { device, trusted } => {
+                let state = if *trusted {
+                    DeviceState::Connected
+                } else {
+                    DeviceState::NeedsTrust
+                };
+                self.set_device(device, state);
+            }
+            DeviceEvent::DeviceLocked { device } => {
+                self.set_device(device, DeviceState::Locked);
+            }
+            DeviceEvent::StorageInfo {
+                device,
+                total_bytes,
+                free_bytes,
+                ..
+            } => {
+                if self.is_current_device(&device.device_id) {
+                    self.storage = Some(StorageInfo::new(*total_bytes, *free_bytes));
+                }
+            }
+            DeviceEvent::DeviceError { device_id, error } => {
+                if self.is_current_device(device_id) {
+                    self.last_error = Some(error.clone());
+                }
+            }
+        }
+    }
+
+    fn apply_extraction_event(&mut self, event: &ExtractionEvent) {
+        match event {
+            ExtractionEvent::Started {
+                device, output_dir, ..
+            } => {
+                let state = match &self.device {
+                    Some(current) if current.device_id == device.device_id => {
+                        self.connection.clone()
+                    }
+                    _ => DeviceState::Connected,
+                };
+                self.set_device(device, state);
+                self.extraction = Some(ExtractionStatus {
+                    output_dir: Some(output_dir.clone()),
+                    ..ExtractionStatus::new()
+                });
+                self.last_error = None;
+                return;
+            }
+            ExtractionEvent::FileError { name, error, .. } => {
+                self.last_error = Some(format!("{}: {}", name, error));
+            }
+            ExtractionEvent::FatalError { error, context } => {
+                self.last_error = Some(match context {
+                    Some(context) => format!("{} ({})", error, context),
+                    None => error.clone(),
+                });
+            }
+            _ => {}
+        }
+
+        self.extraction
+            .get_or_insert_with(ExtractionStatus::new)
+            .apply(event);
+    }
+
+    fn apply_app_event(&mut self, event: &AppEvent) {
+        match event {
+            AppEvent::ConfigError { error } => self.last_error = Some(error.clone()),
+            AppEvent::Log {
+                level: LogLevel::Error,
+                message,
+                ..
+            } => self.last_error = Some(message.clone()),
+            _ => {}
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::events::{ExtractionSummary, PauseReason};
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    fn iphone(id: &str, name: &str) -> DeviceInfo {
+        DeviceInfo::new(id, name, "Apple Inc.", "iPhone 15 Pro")
+    }
+
+    fn progress(files_extracted: usize, percent_complete: f64) -> UiEvent {
+        UiEvent::Extraction(ExtractionEvent::Progress {
+            files_extracted,
+            files_skipped: 0,
+            duplicates_found: 0,
+            errors: 0,
+            bytes_processed: files_extracted as u64 * 1000,
+            eta: Some(Duration::from_secs(30)),
+            speed_bps: 1000,
+            percent_complete,
+        })
+    }
+
+    #[test]
+    fn test_status_model_default() {
+        let model = StatusModel::new();
+        assert!(model.device_name().is_none());
+        assert_eq!(model.connection, DeviceState::Disconnected);
+        assert!(model.extraction.is_none());
+        assert!(!model.is_extracting());
+    }
+
+    #[test]
+    fn test_status_model_tracks_latest_device() {
+        let first = iphone("dev-1", "Alice's iPhone").with_battery_level(80);
+        let second = iphone("dev-2", "Bob's iPhone");
+        let mut model = StatusModel::new();
+
+        model.update_from_events(&[
+            UiEvent::Device(DeviceEvent::Connected {
+                device: first.clone(),
+                previously_known: false,
+            }),
+            UiEvent::Device(DeviceEvent::StorageInfo {
+                device: first.clone(),
+                total_bytes: 1000,
+                used_bytes: 600,
+                free_bytes: 400,
+            }),
+        ]);
+        assert_eq!(model.device_name(), Some("Alice's iPhone"));
+        assert_eq!(model.connection, DeviceState::Connected);
+        assert_eq!(model.battery_level, Some(80));
+        assert_eq!(model.storage, Some(StorageInfo::new(1000, 400)));
+
+        model.update_from_events(&[
+            UiEvent::Device(DeviceEvent::DeviceLocked {
+                device: second.clone(),
+            }),
+            // Events about a device no longer shown are ignored
+            UiEvent::Device(DeviceEvent::Disconnected {
+                device_id: "dev-1".to_string(),
+                device_name: None,
+            }),
+            UiEvent::Device(DeviceEvent::DeviceError {
+                device_id: "dev-1".to_string(),
+                error: "stale".to_string(),
+            }),
+        ]);
+        assert_eq!(model.device_name(), Some("Bob's iPhone"));
+        assert_eq!(model.connection, DeviceState::Locked);
+        assert_eq!(model.battery_level, None);
+        assert_eq!(model.storage, None);
+        assert_eq!(model.last_error, None);
+
+        model.update_from_events(&[
+            UiEvent::Device(DeviceEvent::TrustStatusChanged {
+                device: second.clone(),
+                trusted: true,
+            }),
+            UiEvent::Device(DeviceEvent::Disconnected {
+                device_id: "dev-2".to_string(),
+                device_name: Some("Bob's iPhone".to_string()),
+            }),
+        ]);
+        assert_eq!(model.device_name(), Some("Bob's iPhone"));
+        assert_eq!(model.connection, DeviceState::Disconnected);
+        assert_eq!(model.connection_message(), "Device is disconnected");
+    }
+
+    #[test]
+    fn test_status_model_tracks_extraction_progress() {
+        let device = iphone("dev-1", "Alice's iPhone");
+        let mut model = StatusModel::new();
+
+        model.update_from_events(&[
+            UiEvent::Device(DeviceEvent::Connected {
+                device: device.clone(),
+                previously_known: true,
+            }),
+            UiEvent::Extraction(ExtractionEvent::Started {
+                device: device.clone(),
+                output_dir: PathBuf::from("out"),
+                started_at: Instant::now(),
+            }),
+            UiEvent::Extraction(ExtractionEvent::ScanComplete {
+                total_files: 10,
+                total_bytes: 10_000,
+                scan_duration: Duration::from_secs(1),
+            }),
+            UiEvent::Extraction(ExtractionEvent::FileStarted {
+                name: "IMG_0001.JPG".to_string(),
+                size: 1000,
+                index: 0,
+                total: 10,
+            }),
+            progress(1, 10.0),
+            UiEvent::Extraction(ExtractionEvent::FileError {
+                name: "IMG_0002.JPG".to_string(),
+                error: "read failed".to_string(),
+                recoverable: true,
+            }),
+            progress(4, 40.0),
+        ]);
+
+        let status = model.extraction.as_ref().unwrap();
+        assert!(model.is_extracting());
+        assert_eq!(status.state, ControllerState::Extracting);
+        assert_eq!(status.total_files, 10);
+        assert_eq!(status.total_bytes, 10_000);
+        assert_eq!(status.files_extracted, 4);
+        assert_eq!(status.percent_complete, 40.0);
+        assert_eq!(status.current_file.as_deref(), Some("IMG_0001.JPG"));
+        assert_eq!(
+            model.last_error.as_deref(),
+            Some("IMG_0002.JPG: read failed")
+        );
+        assert_eq!(model.connection, DeviceState::Connected);
+
+        model.update(&UiEvent::Extraction(ExtractionEvent::Paused {
+            reason: PauseReason::UserRequested,
+        }));
+        assert_eq!(
+            model.extraction.as_ref().unwrap().state,
+            ControllerState::Paused
+        );
+        assert!(model.is_extracting());
+
+        model.update_from_events(&[
+            UiEvent::Extraction(ExtractionEvent::Resumed),
+            UiEvent::Extraction(ExtractionEvent::Completed {
+                stats: ExtractionSummary {
+                    device: device.clone(),
+                    output_dir: PathBuf::from("out"),
+                    files_extracted: 9,
+                    files_skipped: 0,
+                    duplicates_found: 0,
+                    errors: 1,
+                    bytes_transferred: 9000,
+                    duration: Duration::from_secs(9),
+                    average_speed_bps: 1000,
+                    completed_fully: true,
+                    resumable: false,
+                },
+            }),
+        ]);
+        let status = model.extraction.as_ref().unwrap();
+        assert!(!model.is_extracting());
+        assert_eq!(status.state, ControllerState::Completed);
+        assert_eq!(status.files_extracted, 9);
+        assert_eq!(status.errors, 1);
+        assert_eq!(status.percent_complete, 100.0);
+        assert!(status.current_file.is_none());
+        assert_eq!(status.output_dir, Some(PathBuf::from("out")));
+
+        // A new run starts from a clean slate
+        model.update(&UiEvent::Extraction(ExtractionEvent::Started {
+            device,
+            output_dir: PathBuf::from("out"),
+            started_at: Instant::now(),
+        }));
+        assert_eq!(model.extraction.as_ref().unwrap().files_extracted, 0);
+        assert!(model.last_error.is_none());
+    }
+
+    #[test]
+    fn test_status_model_records_fatal_error() {
+        let mut model = StatusModel::new();
+        model.update_from_events(&[
+            UiEvent::Extraction(ExtractionEvent::ScanStarted),
+            UiEvent::Extraction(ExtractionEvent::FatalError {
+                error: "device lost".to_string(),
+                context: Some("while scanning".to_string()),
+            }),
+        ]);
+        assert_eq!(
+            model.extraction.as_ref().unwrap().state,
+            ControllerState::Error
+        );
+        assert_eq!(
+            model.last_error.as_deref(),
+            Some("device lost (while scanning)")
+        );
+
+        model.update(&UiEvent::App(AppEvent::ConfigError {
+            error: "bad config".to_string(),
+        }));
+        assert_eq!(model.last_error.as_deref(), Some("bad config"));
+        model.clear_error();
+        assert!(model.last_error.is_none());
+    }
+}
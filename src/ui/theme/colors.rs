@@ -20,6 +20,7 @@
 //! let bg = &palette.background.primary;
 //! ```
 
+use crate::ui::settings::ColorBlindMode;
 use serde::{Deserialize, Serialize};
 
 // =============================================================================
@@ -144,13 +145,39 @@ impl Color {
         }
     }
 
+    /// Create a color from HSL components
+    ///
+    /// Hue is in degrees and wraps around; saturation and lightness are 0.0-1.0.
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Self {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let s = s.clamp(0.0, 1.0);
+        let l = l.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = l - chroma / 2.0;
+
+        Self::new(r + m, g + m, b + m, 1.0)
+    }
+
     /// Convert to hex string (#RRGGBB)
+    ///
+    /// Channels are rounded to the nearest 0-255 value, so the result reads
+    /// back through [`from_hex`](Self::from_hex) as the same color.
     pub fn to_hex(&self) -> String {
         format!(
             "#{:02X}{:02X}{:02X}",
-            (self.r * 255.0) as u8,
-            (self.g * 255.0) as u8,
-            (self.b * 255.0) as u8,
+            channel_to_u8(self.r),
+            channel_to_u8(self.g),
+            channel_to_u8(self.b),
         )
     }
 
@@ -158,28 +185,28 @@ impl Color {
     pub fn to_hex_alpha(&self) -> String {
         format!(
             "#{:02X}{:02X}{:02X}{:02X}",
-            (self.r * 255.0) as u8,
-            (self.g * 255.0) as u8,
-            (self.b * 255.0) as u8,
-            (self.a * 255.0) as u8,
+            channel_to_u8(self.r),
+            channel_to_u8(self.g),
+            channel_to_u8(self.b),
+            channel_to_u8(self.a),
         )
     }
 
     /// Convert to RGB tuple (0-255)
     pub fn to_rgb(&self) -> (u8, u8, u8) {
         (
-            (self.r * 255.0) as u8,
-            (self.g * 255.0) as u8,
-            (self.b * 255.0) as u8,
+            channel_to_u8(self.r),
+            channel_to_u8(self.g),
+            channel_to_u8(self.b),
         )
     }
 
     /// Convert to RGBA tuple (0-255 for RGB, 0.0-1.0 for alpha)
     pub fn to_rgba(&self) -> (u8, u8, u8, f32) {
         (
-            (self.r * 255.0) as u8,
-            (self.g * 255.0) as u8,
-            (self.b * 255.0) as u8,
+            channel_to_u8(self.r),
+            channel_to_u8(self.g),
+            channel_to_u8(self.b),
             self.a,
         )
     }
@@ -198,7 +225,47 @@ impl Color {
         }
     }
 
+    /// Convert to HSL components (hue in degrees, saturation and lightness 0.0-1.0)
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let delta = max - min;
+        let l = (max + min) / 2.0;
+
+        if delta <= f32::EPSILON {
+            return (0.0, 0.0, l);
+        }
+
+        let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == self.r {
+            60.0 * ((self.g - self.b) / delta).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / delta + 2.0)
+        } else {
+            60.0 * ((self.r - self.g) / delta + 4.0)
+        };
+
+        (h, s.clamp(0.0, 1.0), l)
+    }
+
+    /// Return this color with a different HSL lightness, keeping hue and saturation
+    pub fn with_lightness(&self, lightness: f32) -> Self {
+        let (h, s, _) = self.to_hsl();
+        Self::from_hsl(h, s, lightness).with_alpha(self.a)
+    }
+
+    /// Rotate the hue by the given number of degrees
+    pub fn rotate_hue(&self, degrees: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h + degrees, s, l).with_alpha(self.a)
+    }
+
     /// Lighten the color by a percentage (0.0-1.0)
+    ///
+    /// Moves each channel that fraction of the way to white. Like
+    /// [`darken`](Self::darken) and [`mix`](Self::mix), this works on the
+    /// gamma-encoded sRGB values, so 50% is the mid-gray `#808080` designers
+    /// expect rather than a perceptually lighter linear-light blend.
     pub fn lighten(&self, amount: f32) -> Self {
         let amount = amount.clamp(0.0, 1.0);
         Self {
@@ -210,6 +277,8 @@ impl Color {
     }
 
     /// Darken the color by a percentage (0.0-1.0)
+    ///
+    /// Moves each channel that fraction of the way to black, in sRGB.
     pub fn darken(&self, amount: f32) -> Self {
         let amount = amount.clamp(0.0, 1.0);
         Self {
@@ -224,6 +293,7 @@ impl Color {
     ///
     /// The factor determines how much of the other color to blend in (0.0-1.0).
     /// A factor of 0.0 returns this color, 1.0 returns the other color.
+    /// Channels, alpha included, are interpolated in sRGB.
     pub fn mix(&self, other: &Color, factor: f32) -> Self {
         let factor = factor.clamp(0.0, 1.0);
         let inv = 1.0 - factor;
@@ -235,13 +305,65 @@ impl Color {
         }
     }
 
-    /// Calculate the luminance of this color
+    /// Calculate the perceived luminance of this color
     ///
-    /// Uses the relative luminance formula from WCAG 2.0
+    /// Weights the gamma-encoded channels directly; use
+    /// [`relative_luminance`](Self::relative_luminance) for contrast checks.
     pub fn luminance(&self) -> f32 {
         0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
     }
 
+    /// Calculate the relative luminance of this color (WCAG 2.1)
+    ///
+    /// Linearizes each sRGB channel before weighting them.
+    pub fn relative_luminance(&self) -> f32 {
+        0.2126 * srgb_to_linear(self.r)
+            + 0.7152 * srgb_to_linear(self.g)
+            + 0.0722 * srgb_to_linear(self.b)
+    }
+
+    /// Simulate how this color looks with a color vision deficiency
+    ///
+    /// Dichromacies project the color onto what the remaining two cone types
+    /// can distinguish in LMS space (Viénot, Brettel & Mollon 1999).
+    /// Achromatopsia keeps only the relative luminance. Alpha is unchanged.
+    pub fn simulate(&self, mode: ColorBlindMode) -> Self {
+        let linear = [
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+        ];
+
+        let [r, g, b] = match mode {
+            ColorBlindMode::None => return self.clone(),
+            ColorBlindMode::Achromatopsia => {
+                let gray = self.relative_luminance();
+                [gray, gray, gray]
+            }
+            ColorBlindMode::Protanopia
+            | ColorBlindMode::Deuteranopia
+            | ColorBlindMode::Tritanopia => {
+                let [l, m, s] = mul3(&RGB_TO_LMS, linear);
+                // Rebuild the missing cone response from the other two; the
+                // coefficients keep white and one anchor hue (blue for
+                // protan/deutan, red for tritan) unchanged
+                let lms = match mode {
+                    ColorBlindMode::Protanopia => [2.023_442 * m - 2.525_806 * s, m, s],
+                    ColorBlindMode::Deuteranopia => [l, 0.494_207 * l + 1.248_272 * s, s],
+                    _ => [l, m, -0.012_245 * l + 0.072_035 * m],
+                };
+                mul3(&LMS_TO_RGB, lms)
+            }
+        };
+
+        Self {
+            r: linear_to_srgb(r),
+            g: linear_to_srgb(g),
+            b: linear_to_srgb(b),
+            a: self.a,
+        }
+    }
+
     /// Check if this color is considered "light"
     ///
     /// Based on luminance threshold of 0.5
@@ -263,10 +385,12 @@ impl Color {
         }
     }
 
-    /// Calculate contrast ratio with another color (WCAG 2.0)
+    /// Calculate contrast ratio with another color (WCAG 2.1)
+    ///
+    /// Ranges from 1:1 (identical) to 21:1 (black on white).
     pub fn contrast_ratio(&self, other: &Color) -> f32 {
-        let l1 = self.luminance();
-        let l2 = other.luminance();
+        let l1 = self.relative_luminance();
+        let l2 = other.relative_luminance();
         let lighter = l1.max(l2);
         let darker = l1.min(l2);
         (lighter + 0.05) / (darker + 0.05)
@@ -317,6 +441,48 @@ impl Color {
     }
 }
 
+/// Linear sRGB to LMS cone responses (Hunt-Pointer-Estévez)
+const RGB_TO_LMS: [[f32; 3]; 3] = [
+    [17.8824, 43.5161, 4.11935],
+    [3.45565, 27.1554, 3.86714],
+    [0.0299566, 0.184309, 1.46709],
+];
+
+/// Inverse of [`RGB_TO_LMS`]
+const LMS_TO_RGB: [[f32; 3]; 3] = [
+    [0.080_944_45, -0.130_504_41, 0.116_721_07],
+    [-0.010_248_53, 0.054_019_33, -0.113_614_71],
+    [-0.000_365_30, -0.004_121_61, 0.693_511_4],
+];
+
+fn mul3(matrix: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+/// A 0.0-1.0 channel as the nearest 0-255 value
+fn channel_to_u8(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Undo the sRGB transfer curve
+fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Apply the sRGB transfer curve, clamping to the displayable range
+fn linear_to_srgb(channel: f32) -> f32 {
+    let channel = channel.clamp(0.0, 1.0);
+    if channel <= 0.003_130_8 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 impl Default for Color {
     fn default() -> Self {
         Self::BLACK
@@ -338,7 +504,7 @@ impl std::fmt::Display for Color {
 // =============================================================================
 
 /// Complete color palette for the application
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ColorPalette {
     /// Background colors
     pub background: BackgroundColors,
@@ -397,6 +563,28 @@ impl ColorPalette {
     }
 }
 
+impl ColorPalette {
+    /// Simulate the palette as seen with a color vision deficiency
+    ///
+    /// Every color goes through [`Color::simulate`]; `ColorBlindMode::None`
+    /// returns the palette unchanged.
+    pub fn simulate(&self, mode: ColorBlindMode) -> Self {
+        if mode == ColorBlindMode::None {
+            return self.clone();
+        }
+
+        let f = |color: &Color| color.simulate(mode);
+        Self {
+            background: self.background.map(&f),
+            foreground: self.foreground.map(&f),
+            border: self.border.map(&f),
+            semantic: self.semantic.map(&f),
+            accent: self.accent.map(&f),
+            surface: self.surface.map(&f),
+        }
+    }
+}
+
 impl Default for ColorPalette {
     fn default() -> Self {
         Self::dark()
@@ -408,7 +596,7 @@ impl Default for ColorPalette {
 // =============================================================================
 
 /// Background color hierarchy
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BackgroundColors {
     /// Primary background (main application background)
     pub primary: Color,
@@ -463,6 +651,19 @@ impl BackgroundColors {
             selected: Color::from_rgba(0, 255, 0, 0.3),
         }
     }
+
+    /// Apply `f` to every color
+    fn map(&self, f: &impl Fn(&Color) -> Color) -> Self {
+        Self {
+            primary: f(&self.primary),
+            secondary: f(&self.secondary),
+            tertiary: f(&self.tertiary),
+            elevated: f(&self.elevated),
+            hover: f(&self.hover),
+            active: f(&self.active),
+            selected: f(&self.selected),
+        }
+    }
 }
 
 // =============================================================================
@@ -470,7 +671,7 @@ impl BackgroundColors {
 // =============================================================================
 
 /// Foreground/text color hierarchy
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ForegroundColors {
     /// Primary text color (most readable)
     pub primary: Color,
@@ -525,6 +726,19 @@ impl ForegroundColors {
             link_hover: Color::from_hex("#FFFF00"),
         }
     }
+
+    /// Apply `f` to every color
+    fn map(&self, f: &impl Fn(&Color) -> Color) -> Self {
+        Self {
+            primary: f(&self.primary),
+            secondary: f(&self.secondary),
+            muted: f(&self.muted),
+            placeholder: f(&self.placeholder),
+            inverted: f(&self.inverted),
+            link: f(&self.link),
+            link_hover: f(&self.link_hover),
+        }
+    }
 }
 
 // =============================================================================
@@ -532,7 +746,7 @@ impl ForegroundColors {
 // =============================================================================
 
 /// Border color variants
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BorderColors {
     /// Default border color
     pub default: Color,
@@ -577,6 +791,17 @@ impl BorderColors {
             error: Color::from_hex("#FF0000"),
         }
     }
+
+    /// Apply `f` to every color
+    fn map(&self, f: &impl Fn(&Color) -> Color) -> Self {
+        Self {
+            default: f(&self.default),
+            subtle: f(&self.subtle),
+            strong: f(&self.strong),
+            focused: f(&self.focused),
+            error: f(&self.error),
+        }
+    }
 }
 
 // =============================================================================
@@ -584,7 +809,7 @@ impl BorderColors {
 // =============================================================================
 
 /// Semantic/status colors
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SemanticColors {
     /// Primary accent color
     pub accent: Color,
@@ -653,6 +878,21 @@ impl SemanticColors {
             info_background: Color::from_rgba(0, 255, 255, 0.2),
         }
     }
+
+    /// Apply `f` to every color
+    fn map(&self, f: &impl Fn(&Color) -> Color) -> Self {
+        Self {
+            accent: f(&self.accent),
+            success: f(&self.success),
+            warning: f(&self.warning),
+            error: f(&self.error),
+            info: f(&self.info),
+            success_background: f(&self.success_background),
+            warning_background: f(&self.warning_background),
+            error_background: f(&self.error_background),
+            info_background: f(&self.info_background),
+        }
+    }
 }
 
 // =============================================================================
@@ -660,7 +900,7 @@ impl SemanticColors {
 // =============================================================================
 
 /// Accent color variations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AccentColors {
     /// Blue
     pub blue: Color,
@@ -720,6 +960,20 @@ impl AccentColors {
             teal: Color::from_hex("#00FFFF"),
         }
     }
+
+    /// Apply `f` to every color
+    fn map(&self, f: &impl Fn(&Color) -> Color) -> Self {
+        Self {
+            blue: f(&self.blue),
+            purple: f(&self.purple),
+            pink: f(&self.pink),
+            red: f(&self.red),
+            orange: f(&self.orange),
+            yellow: f(&self.yellow),
+            green: f(&self.green),
+            teal: f(&self.teal),
+        }
+    }
 }
 
 // =============================================================================
@@ -727,7 +981,7 @@ impl AccentColors {
 // =============================================================================
 
 /// Surface colors for cards, panels, etc.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SurfaceColors {
     /// Surface level 0 (base)
     pub level0: Color,
@@ -767,6 +1021,16 @@ impl SurfaceColors {
             level3: Color::from_hex("#4D4D4D"),
         }
     }
+
+    /// Apply `f` to every color
+    fn map(&self, f: &impl Fn(&Color) -> Color) -> Self {
+        Self {
+            level0: f(&self.level0),
+            level1: f(&self.level1),
+            level2: f(&self.level2),
+            level3: f(&self.level3),
+        }
+    }
 }
 
 // =============================================================================
@@ -845,6 +1109,112 @@ mod tests {
         assert!(Color::WHITE.meets_wcag_aaa(&Color::BLACK));
     }
 
+    #[test]
+    fn test_color_contrast_known_pairs() {
+        // Black on white is the maximum, 21:1
+        let ratio = Color::BLACK.contrast_ratio(&Color::WHITE);
+        assert!((ratio - 21.0).abs() < 0.01);
+        assert_eq!(ratio, Color::WHITE.contrast_ratio(&Color::BLACK));
+
+        assert!((Color::WHITE.contrast_ratio(&Color::WHITE) - 1.0).abs() < 0.001);
+
+        // #777777 on white sits just below AA (4.48:1)
+        let gray = Color::from_hex("#777777");
+        assert!((gray.contrast_ratio(&Color::WHITE) - 4.48).abs() < 0.01);
+        assert!(!gray.meets_wcag_aa(&Color::WHITE));
+        assert!(Color::from_hex("#767676").meets_wcag_aa(&Color::WHITE));
+    }
+
+    fn assert_rgb(color: &Color, r: f32, g: f32, b: f32) {
+        assert!(
+            (color.r - r).abs() < 0.01 && (color.g - g).abs() < 0.01 && (color.b - b).abs() < 0.01,
+            "expected ({}, {}, {}), got {:?}",
+            r,
+            g,
+            b,
+            color
+        );
+    }
+
+    #[test]
+    fn test_color_hsl_roundtrip() {
+        let (h, s, l) = Color::from_hex("#FF0000").to_hsl();
+        assert!((h - 0.0).abs() < 0.01 && (s - 1.0).abs() < 0.01 && (l - 0.5).abs() < 0.01);
+
+        let (h, _, _) = Color::from_hex("#0000FF").to_hsl();
+        assert!((h - 240.0).abs() < 0.01);
+
+        for hex in ["#7C3AED", "#1E66F5", "#40A02B", "#DF8E1D", "#808080"] {
+            let color = Color::from_hex(hex);
+            let (h, s, l) = color.to_hsl();
+            let back = Color::from_hsl(h, s, l);
+            assert!(
+                (back.r - color.r).abs() < 1e-4
+                    && (back.g - color.g).abs() < 1e-4
+                    && (back.b - color.b).abs() < 1e-4,
+                "{hex} came back as {back:?}"
+            );
+        }
+
+        let cyan = Color::from_hex("#FF0000").rotate_hue(180.0);
+        assert_eq!(cyan.to_hex(), "#00FFFF");
+        assert_eq!(Color::from_hsl(-120.0, 1.0, 0.5).to_hex(), "#0000FF");
+    }
+
+    #[test]
+    fn test_color_blind_simulation() {
+        let red = Color::from_hex("#FF0000");
+
+        // Protanopes see pure red as a dark olive
+        let protan = red.simulate(ColorBlindMode::Protanopia);
+        assert_rgb(&protan, 0.369, 0.369, 0.051);
+        assert!(protan.relative_luminance() < red.relative_luminance());
+
+        // Red and green collapse onto the same hue under deuteranopia
+        let deutan = red.simulate(ColorBlindMode::Deuteranopia);
+        assert_rgb(&deutan, 0.577, 0.577, 0.0);
+
+        // Tritanopes still see red as red
+        assert_rgb(&red.simulate(ColorBlindMode::Tritanopia), 1.0, 0.0, 0.0);
+
+        let gray = red.simulate(ColorBlindMode::Achromatopsia);
+        assert!((gray.r - gray.g).abs() < 1e-6 && (gray.g - gray.b).abs() < 1e-6);
+
+        // White stays white and alpha is untouched in every mode
+        let white = Color::WHITE.with_alpha(0.5);
+        for mode in [
+            ColorBlindMode::Protanopia,
+            ColorBlindMode::Deuteranopia,
+            ColorBlindMode::Tritanopia,
+            ColorBlindMode::Achromatopsia,
+        ] {
+            let simulated = white.simulate(mode);
+            assert_rgb(&simulated, 1.0, 1.0, 1.0);
+            assert_eq!(simulated.a, 0.5);
+        }
+    }
+
+    #[test]
+    fn test_palette_simulation() {
+        let palette = ColorPalette::dark();
+
+        let unchanged = palette.simulate(ColorBlindMode::None);
+        assert_eq!(unchanged.accent.red, palette.accent.red);
+        assert_eq!(unchanged.semantic.error, palette.semantic.error);
+        assert_eq!(unchanged.surface.level3, palette.surface.level3);
+
+        let simulated = palette.simulate(ColorBlindMode::Protanopia);
+        assert_eq!(
+            simulated.accent.red,
+            palette.accent.red.simulate(ColorBlindMode::Protanopia)
+        );
+        assert_ne!(simulated.semantic.success, palette.semantic.success);
+
+        let gray = palette.simulate(ColorBlindMode::Achromatopsia);
+        let blue = &gray.accent.blue;
+        assert!((blue.r - blue.g).abs() < 1e-6 && (blue.g - blue.b).abs() < 1e-6);
+    }
+
     #[test]
     fn test_color_contrasting_text() {
         assert_eq!(Color::WHITE.contrasting_text(), Color::BLACK);
@@ -983,4 +1353,34 @@ mod tests {
         assert!(dark.level1.luminance() >= dark.level0.luminance());
         assert!(dark.level2.luminance() >= dark.level1.luminance());
     }
+
+    #[test]
+    fn test_color_math_in_srgb() {
+        let mid_gray = Color::from_hex("#808080");
+
+        // Halfway to black or between black and white is mid-gray
+        assert_eq!(Color::WHITE.darken(0.5).to_hex(), "#808080");
+        assert_eq!(Color::BLACK.mix(&Color::WHITE, 0.5).to_hex(), "#808080");
+        assert_eq!(Color::BLACK.lighten(0.5).to_hex(), "#808080");
+        assert!((Color::WHITE.darken(0.5).r - mid_gray.r).abs() < 0.005);
+
+        // Alpha is kept by lighten/darken and replaced by with_alpha
+        let accent = Color::from_hex("#3B82F680");
+        assert_eq!(accent.lighten(0.2).a, accent.a);
+        assert_eq!(accent.darken(0.2).a, accent.a);
+        assert_eq!(accent.with_alpha(0.25).to_hex_alpha(), "#3B82F640");
+    }
+
+    #[test]
+    fn test_color_to_hex_round_trips_from_hex() {
+        for value in 0..=255u8 {
+            let hex = format!("#{:02X}{:02X}{:02X}", value, 255 - value, value / 2);
+            assert_eq!(Color::from_hex(&hex).to_hex(), hex);
+
+            let with_alpha = format!("{}{:02X}", hex, value);
+            assert_eq!(Color::from_hex(&with_alpha).to_hex_alpha(), with_alpha);
+        }
+        let color = Color::from_rgb(18, 52, 86);
+        assert_eq!(Color::from_hex(&color.to_hex()), color);
+    }
 }
@@ -2,7 +2,7 @@
 //!
 //! This module provides a comprehensive typography system with:
 //! - Font family definitions (system, custom, monospace)
-//! - Font size scales
+//! - Font size scales, including a modular scale derived from the UI size
 //! - Font weight definitions
 //! - Line height and letter spacing
 //! - UI and code typography presets
@@ -244,7 +244,7 @@ impl FontStyle {
 // =============================================================================
 
 /// Complete font configuration for a text element
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FontConfig {
     /// Font family
     pub family: FontFamily,
@@ -393,8 +393,20 @@ impl Default for FontConfig {
 // Typography
 // =============================================================================
 
+/// Ratio between neighbouring steps of [`Typography::scale`] (a minor third)
+pub const TYPE_SCALE_RATIO: f32 = 1.2;
+
+/// Scale step of caption text, see [`Typography::caption_size`]
+pub const CAPTION_STEP: i32 = -2;
+
+/// Scale step of body text, see [`Typography::body_size`]
+pub const BODY_STEP: i32 = 0;
+
+/// Scale step of headings, see [`Typography::heading_size`]
+pub const HEADING_STEP: i32 = 2;
+
 /// Complete typography system for the application
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Typography {
     /// UI text font configuration
     pub ui: FontConfig,
@@ -461,6 +473,35 @@ impl Typography {
         }
     }
 
+    /// Font size `step` steps away from the UI font size on a modular scale
+    ///
+    /// Each step multiplies the size by [`TYPE_SCALE_RATIO`]: step 0 is
+    /// `ui.size` and negative steps are smaller. Components derive their
+    /// sizes from this instead of hardcoding them, so they follow the UI font.
+    pub fn scale(&self, step: i32) -> f32 {
+        self.ui.size * TYPE_SCALE_RATIO.powi(step)
+    }
+
+    /// [`scale`](Self::scale) multiplied by the appearance `ui_scale`
+    pub fn scaled(&self, step: i32, ui_scale: f32) -> f32 {
+        self.scale(step) * ui_scale
+    }
+
+    /// Font size for captions and other secondary text
+    pub fn caption_size(&self) -> f32 {
+        self.scale(CAPTION_STEP)
+    }
+
+    /// Font size for body text
+    pub fn body_size(&self) -> f32 {
+        self.scale(BODY_STEP)
+    }
+
+    /// Font size for section headings
+    pub fn heading_size(&self) -> f32 {
+        self.scale(HEADING_STEP)
+    }
+
     /// Create font config for body text
     pub fn body(&self) -> FontConfig {
         self.ui.clone()
@@ -590,7 +631,7 @@ impl Default for Typography {
 // =============================================================================
 
 /// Font size scale for consistent sizing throughout the UI
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FontScale {
     /// Extra small (10px default)
     pub xs: f32,
@@ -1050,4 +1091,30 @@ mod tests {
         assert_eq!(typography.ui.size, deserialized.ui.size);
         assert_eq!(typography.mono.size, deserialized.mono.size);
     }
+
+    #[test]
+    fn test_typography_modular_scale() {
+        let typography = Typography::default();
+        let base = typography.ui.size;
+
+        assert_eq!(typography.scale(0), base);
+        assert!((typography.scale(2) - base * TYPE_SCALE_RATIO * TYPE_SCALE_RATIO).abs() < 1e-4);
+        assert!((typography.scale(-1) - base / TYPE_SCALE_RATIO).abs() < 1e-4);
+
+        assert_eq!(typography.body_size(), base);
+        assert!(typography.caption_size() < typography.body_size());
+        assert!(typography.heading_size() > typography.body_size());
+
+        // Sizes follow the UI font and the appearance scale
+        let larger = Typography {
+            ui: FontConfig {
+                size: 16.0,
+                ..typography.ui.clone()
+            },
+            ..typography.clone()
+        };
+        assert_eq!(larger.scale(0), 16.0);
+        assert_eq!(typography.scaled(0, 1.5), base * 1.5);
+        assert!((typography.scaled(2, 2.0) - 2.0 * typography.scale(2)).abs() < 1e-4);
+    }
 }
@@ -44,6 +44,7 @@ pub use fonts::{FontConfig, FontFamily, FontWeight, Typography};
 pub use spacing::{Spacing, SpacingScale};
 
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 // =============================================================================
@@ -98,7 +99,7 @@ impl std::fmt::Display for ThemeMode {
 ///
 /// This is the main struct used throughout the application for consistent styling.
 /// Themes are immutable once created - use `ThemeBuilder` to create modified versions.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Theme {
     /// Theme name (e.g., "Zed Dark", "Zed Light")
     pub name: String,
@@ -162,6 +163,67 @@ impl Theme {
         }
     }
 
+    /// Derive a complete theme from a single accent color
+    ///
+    /// Starts from the built-in theme for `mode` and replaces every accent-tinted
+    /// color. Hover and active shades move away from the background (lighter in
+    /// dark mode, darker in light mode), primary button text is whichever of
+    /// black or white contrasts more with the accent, and the semantic colors
+    /// reuse the accent's saturation on fixed status hues, with info on the
+    /// complementary hue.
+    pub fn from_accent(accent: Color, mode: ThemeMode) -> Self {
+        let mut theme = match mode {
+            ThemeMode::Dark => Self::dark(),
+            ThemeMode::Light => Self::light(),
+        };
+        theme.name = format!("Custom {} ({})", accent.to_hex(), mode);
+
+        let (hue, saturation, lightness) = accent.to_hsl();
+        let step = if mode.is_dark() { 0.08 } else { -0.08 };
+        let hover = accent.with_lightness(lightness + step);
+        let active = accent.with_lightness(lightness + 2.0 * step);
+        let text = if accent.contrast_ratio(&Color::WHITE) >= accent.contrast_ratio(&Color::BLACK) {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        };
+
+        // Status colors need to stay readable on the theme background
+        let status_saturation = saturation.clamp(0.45, 0.85);
+        let status_lightness = if mode.is_dark() { 0.68 } else { 0.40 };
+        let status = |h: f32| Color::from_hsl(h, status_saturation, status_lightness);
+        let background_alpha = if mode.is_dark() { 0.18 } else { 0.1 };
+
+        let semantic = &mut theme.colors.semantic;
+        semantic.accent = accent.clone();
+        semantic.success = status(135.0);
+        semantic.warning = status(40.0);
+        semantic.error = status(355.0);
+        semantic.info = status(hue + 180.0);
+        semantic.success_background = semantic.success.with_alpha(background_alpha);
+        semantic.warning_background = semantic.warning.with_alpha(background_alpha);
+        semantic.error_background = semantic.error.with_alpha(background_alpha);
+        semantic.info_background = semantic.info.with_alpha(background_alpha);
+
+        theme.colors.foreground.link = accent.clone();
+        theme.colors.foreground.link_hover = hover.clone();
+        theme.colors.border.focused = accent.clone();
+
+        let ui = &mut theme.ui;
+        ui.button.primary_background = accent.clone();
+        ui.button.primary_hover = hover;
+        ui.button.primary_text = text;
+        ui.focus.color = accent.clone();
+        ui.input.border_focused = accent.clone();
+        ui.input.selection = accent.with_alpha(0.3);
+        ui.list.item_selected = accent.with_alpha(0.2);
+        ui.list.item_active = active.with_alpha(0.3);
+        ui.progress.fill = accent.clone();
+        ui.command_palette.item_selected = accent.with_alpha(0.2);
+
+        theme
+    }
+
     /// Get a builder initialized with this theme's values
     pub fn to_builder(&self) -> ThemeBuilder {
         ThemeBuilder::from_theme(self.clone())
@@ -189,6 +251,124 @@ impl Theme {
     pub fn into_arc(self) -> Arc<Theme> {
         Arc::new(self)
     }
+
+    /// Check text/background pairs against WCAG AA for normal text
+    ///
+    /// Returns one issue for every pair below [`WCAG_AA_NORMAL_TEXT`].
+    /// Disabled text, placeholders and decorative colors are not checked.
+    pub fn check_contrast(&self) -> Vec<ContrastIssue> {
+        let colors = &self.colors;
+        let ui = &self.ui;
+
+        // Lists have no background of their own and sit on a panel
+        let pairs = [
+            (
+                "text on background",
+                &colors.foreground.primary,
+                &colors.background.primary,
+            ),
+            (
+                "secondary text on secondary background",
+                &colors.foreground.secondary,
+                &colors.background.secondary,
+            ),
+            (
+                "text on panel",
+                &colors.foreground.primary,
+                &ui.panel.background,
+            ),
+            (
+                "button text on button",
+                &ui.button.text,
+                &ui.button.background,
+            ),
+            (
+                "primary button text on primary button",
+                &ui.button.primary_text,
+                &ui.button.primary_background,
+            ),
+            ("input text on input", &ui.input.text, &ui.input.background),
+            ("list text on panel", &ui.list.text, &ui.panel.background),
+            (
+                "progress text on progress track",
+                &ui.progress.text,
+                &ui.progress.track,
+            ),
+            (
+                "command palette text on command palette",
+                &ui.command_palette.text,
+                &ui.command_palette.background,
+            ),
+            (
+                "status bar text on status bar",
+                &ui.status_bar.text,
+                &ui.status_bar.background,
+            ),
+            (
+                "tooltip text on tooltip",
+                &ui.tooltip.text,
+                &ui.tooltip.background,
+            ),
+        ];
+
+        pairs
+            .into_iter()
+            .filter_map(|(pair, foreground, background)| {
+                let ratio = foreground.contrast_ratio(background);
+                (ratio < WCAG_AA_NORMAL_TEXT).then(|| ContrastIssue {
+                    pair: pair.to_string(),
+                    foreground: foreground.clone(),
+                    background: background.clone(),
+                    ratio,
+                })
+            })
+            .collect()
+    }
+
+    /// Save the theme as pretty-printed JSON
+    ///
+    /// Parent directories are created as needed. The file can be loaded back
+    /// with [`ThemeManager::load_theme_file`].
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), ThemeError> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| ThemeError::io(path, e))?;
+        }
+
+        let content = serde_json::to_string_pretty(self).map_err(|e| ThemeError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+
+        std::fs::write(path, content).map_err(|e| ThemeError::io(path, e))
+    }
+}
+
+/// Minimum WCAG AA contrast ratio for normal-size text
+pub const WCAG_AA_NORMAL_TEXT: f32 = 4.5;
+
+/// A text/background pair with too little contrast
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastIssue {
+    /// Which pair failed (e.g. "button text on button")
+    pub pair: String,
+    /// Text color
+    pub foreground: Color,
+    /// Background color
+    pub background: Color,
+    /// Measured contrast ratio
+    pub ratio: f32,
+}
+
+impl std::fmt::Display for ContrastIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} on {} is {:.2}:1 (needs {}:1)",
+            self.pair, self.foreground, self.background, self.ratio, WCAG_AA_NORMAL_TEXT
+        )
+    }
 }
 
 // =============================================================================
@@ -196,7 +376,7 @@ impl Theme {
 // =============================================================================
 
 /// UI-specific style definitions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UiStyles {
     /// Panel styles
     pub panel: PanelStyle,
@@ -302,7 +482,7 @@ impl Default for UiStyles {
 // =============================================================================
 
 /// Panel/container styling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PanelStyle {
     pub background: Color,
     pub background_secondary: Color,
@@ -350,7 +530,7 @@ impl PanelStyle {
 }
 
 /// Button styling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ButtonStyle {
     pub background: Color,
     pub background_hover: Color,
@@ -412,7 +592,7 @@ impl ButtonStyle {
 }
 
 /// Input field styling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InputStyle {
     pub background: Color,
     pub background_focused: Color,
@@ -466,7 +646,7 @@ impl InputStyle {
 }
 
 /// List/table styling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ListStyle {
     pub background: Color,
     pub item_hover: Color,
@@ -520,7 +700,7 @@ impl ListStyle {
 }
 
 /// Progress bar styling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProgressStyle {
     pub track: Color,
     pub fill: Color,
@@ -570,7 +750,7 @@ impl ProgressStyle {
 }
 
 /// Command palette styling (Zed's Ctrl+P / Cmd+K)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CommandPaletteStyle {
     pub backdrop: Color,
     pub background: Color,
@@ -644,7 +824,7 @@ impl CommandPaletteStyle {
 }
 
 /// Status bar styling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StatusBarStyle {
     pub background: Color,
     pub text: Color,
@@ -690,7 +870,7 @@ impl StatusBarStyle {
 }
 
 /// Tooltip styling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TooltipStyle {
     pub background: Color,
     pub text: Color,
@@ -742,7 +922,7 @@ impl TooltipStyle {
 }
 
 /// Focus ring styling (accessibility)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FocusStyle {
     pub color: Color,
     pub width: f32,
@@ -794,7 +974,7 @@ impl FocusStyle {
 }
 
 /// Scrollbar styling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ScrollbarStyle {
     pub track: Color,
     pub thumb: Color,
@@ -848,7 +1028,7 @@ impl ScrollbarStyle {
 // =============================================================================
 
 /// Shadow definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Shadow {
     pub color: Color,
     pub offset_x: f32,
@@ -857,7 +1037,7 @@ pub struct Shadow {
 }
 
 /// Border radius values
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BorderRadius {
     pub none: f32,
     pub small: f32,
@@ -879,7 +1059,7 @@ impl Default for BorderRadius {
 }
 
 /// Animation configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnimationConfig {
     /// Fast animations (hover states)
     pub fast_ms: u32,
@@ -1040,6 +1220,38 @@ impl Default for ThemeBuilder {
     }
 }
 
+// =============================================================================
+// Theme Files
+// =============================================================================
+
+/// Errors that can occur when loading or saving theme files
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ThemeError {
+    /// The file could not be read or written
+    #[error("IO error for theme file {}: {message}", path.display())]
+    Io { path: PathBuf, message: String },
+
+    /// The file is not a valid theme
+    #[error("Invalid theme file {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+}
+
+impl ThemeError {
+    fn io(path: &Path, error: std::io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        }
+    }
+
+    /// Path of the file the error refers to
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Io { path, .. } | Self::Parse { path, .. } => path,
+        }
+    }
+}
+
 // =============================================================================
 // Theme Manager
 // =============================================================================
@@ -1099,6 +1311,53 @@ impl ThemeManager {
     pub fn add_theme(&mut self, theme: Theme) {
         self.available.push(theme);
     }
+
+    /// Load a single theme from a JSON file
+    pub fn load_theme_file(path: impl AsRef<Path>) -> Result<Theme, ThemeError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| ThemeError::io(path, e))?;
+
+        serde_json::from_str(&content).map_err(|e| ThemeError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Register every `*.json` theme in a directory
+    ///
+    /// Files are loaded in name order. A theme with the same name as an
+    /// available one replaces it. Files that fail to load are skipped and
+    /// their errors returned; only an unreadable directory is a hard error.
+    pub fn load_themes_from_dir(
+        &mut self,
+        dir: impl AsRef<Path>,
+    ) -> Result<Vec<ThemeError>, ThemeError> {
+        let dir = dir.as_ref();
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map_err(|e| ThemeError::io(dir, e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+            })
+            .collect();
+        paths.sort();
+
+        let mut errors = Vec::new();
+        for path in paths {
+            match Self::load_theme_file(&path) {
+                Ok(theme) => {
+                    self.available.retain(|t| t.name != theme.name);
+                    self.available.push(theme);
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        Ok(errors)
+    }
 }
 
 impl Default for ThemeManager {
@@ -1143,6 +1402,34 @@ mod tests {
         assert_eq!(light.name, "Zed Light");
     }
 
+    #[test]
+    fn test_builtin_themes_pass_contrast_check() {
+        for theme in [Theme::dark(), Theme::light(), Theme::high_contrast_dark()] {
+            let issues = theme.check_contrast();
+            assert!(issues.is_empty(), "{}: {:?}", theme.name, issues);
+        }
+    }
+
+    #[test]
+    fn test_low_contrast_theme_reports_issues() {
+        let mut theme = Theme::light();
+        // Light gray text on white buttons and inputs
+        theme.ui.button.text = Color::from_hex("#AAAAAA");
+        theme.ui.button.background = Color::WHITE;
+        theme.ui.input.text = Color::from_hex("#BBBBBB");
+
+        let issues = theme.check_contrast();
+        let pairs: Vec<&str> = issues.iter().map(|i| i.pair.as_str()).collect();
+        assert_eq!(pairs, vec!["button text on button", "input text on input"]);
+
+        let button = &issues[0];
+        assert!((button.ratio - 2.32).abs() < 0.01);
+        assert_eq!(button.foreground, Color::from_hex("#AAAAAA"));
+        assert!(button
+            .to_string()
+            .starts_with("button text on button: #AAAAAA on #FFFFFF"));
+    }
+
     #[test]
     fn test_theme_toggle() {
         let dark = Theme::dark();
@@ -1263,6 +1550,117 @@ mod tests {
         assert!(theme.ui.focus.width >= 3.0);
     }
 
+    #[test]
+    fn test_theme_from_accent() {
+        for hex in ["#7C3AED", "#FACC15", "#0E7490", "#E11D48", "#22C55E"] {
+            let accent = Color::from_hex(hex);
+            let (_, _, lightness) = accent.to_hsl();
+
+            let dark = Theme::from_accent(accent.clone(), ThemeMode::Dark);
+            assert_eq!(dark.mode, ThemeMode::Dark);
+            assert_eq!(dark.colors.semantic.accent, accent);
+            assert_eq!(dark.ui.button.primary_background, accent);
+            let (_, _, hover) = dark.ui.button.primary_hover.to_hsl();
+            assert!(hover > lightness, "{hex}: dark hover should be lighter");
+
+            let light = Theme::from_accent(accent.clone(), ThemeMode::Light);
+            let (_, _, hover) = light.ui.button.primary_hover.to_hsl();
+            assert!(hover < lightness, "{hex}: light hover should be darker");
+
+            for theme in [&dark, &light] {
+                let ratio = theme
+                    .ui
+                    .button
+                    .primary_text
+                    .contrast_ratio(&theme.ui.button.primary_background);
+                assert!(ratio >= WCAG_AA_NORMAL_TEXT, "{hex}: ratio {ratio}");
+                assert!(theme.check_contrast().is_empty(), "{hex}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_theme_from_accent_semantic_colors() {
+        let theme = Theme::from_accent(Color::from_hex("#7C3AED"), ThemeMode::Dark);
+        let semantic = &theme.colors.semantic;
+
+        let (success, _, _) = semantic.success.to_hsl();
+        let (error, _, _) = semantic.error.to_hsl();
+        assert!((100.0..170.0).contains(&success));
+        assert!(!(20.0..340.0).contains(&error));
+        assert!(semantic.success_background.a < 1.0);
+
+        // Status colors stay readable on the background
+        let background = &theme.colors.background.primary;
+        assert!(semantic.success.contrast_ratio(background) >= 3.0);
+        assert!(semantic.error.contrast_ratio(background) >= 3.0);
+    }
+
+    #[test]
+    fn test_theme_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("themes").join("dark.json");
+
+        let theme = Theme::dark();
+        theme.save_to_file(&path).unwrap();
+        let loaded = ThemeManager::load_theme_file(&path).unwrap();
+        assert_eq!(loaded, theme);
+
+        // Derived colors are not round numbers and must survive as well
+        let custom = Theme::from_accent(Color::from_hex("#7C3AED"), ThemeMode::Light);
+        custom.save_to_file(&path).unwrap();
+        assert_eq!(ThemeManager::load_theme_file(&path).unwrap(), custom);
+    }
+
+    #[test]
+    fn test_load_theme_file_errors() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let missing = dir.path().join("missing.json");
+        let err = ThemeManager::load_theme_file(&missing).unwrap_err();
+        assert!(matches!(err, ThemeError::Io { .. }));
+        assert_eq!(err.path(), missing);
+
+        let bad = dir.path().join("bad.json");
+        std::fs::write(&bad, r#"{"name": "Broken", "mode": "dark"}"#).unwrap();
+        let err = ThemeManager::load_theme_file(&bad).unwrap_err();
+        assert!(matches!(err, ThemeError::Parse { .. }));
+        assert!(err.to_string().contains("bad.json"));
+    }
+
+    #[test]
+    fn test_load_themes_from_dir() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut ocean = Theme::from_accent(Color::from_hex("#0E7490"), ThemeMode::Dark);
+        ocean.name = "Ocean".to_string();
+        ocean.save_to_file(dir.path().join("ocean.json")).unwrap();
+
+        let mut light = Theme::light();
+        light.ui.focus.width = 4.0;
+        light.save_to_file(dir.path().join("light.json")).unwrap();
+
+        std::fs::write(dir.path().join("broken.json"), "not json").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let mut manager = ThemeManager::new();
+        let errors = manager.load_themes_from_dir(dir.path()).unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].path().ends_with("broken.json"));
+
+        let names = manager.available_themes();
+        assert!(names.contains(&"Ocean"));
+        assert_eq!(names.iter().filter(|n| **n == "Zed Light").count(), 1);
+
+        assert!(manager.set_theme_by_name("Zed Light"));
+        assert_eq!(manager.current().ui.focus.width, 4.0);
+
+        assert!(manager
+            .load_themes_from_dir(dir.path().join("missing"))
+            .is_err());
+    }
+
     #[test]
     fn test_focus_ring_style() {
         let style = FocusRingStyle::Solid;
@@ -28,6 +28,9 @@
 //! // Use scale for specific values
 //! let scale = SpacingScale::default();
 //! let value = scale.get(4);  // 16px (4 * 4)
+//!
+//! // Look up spacing by token name, e.g. from a layout config
+//! let md = spacing.get_token("md");  // Some(16px)
 //! ```
 
 use serde::{Deserialize, Serialize};
@@ -40,7 +43,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// Uses a base-4 scale for visual rhythm and easy calculation.
 /// All values are in pixels.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Spacing {
     /// No spacing (0px)
     pub none: f32,
@@ -121,6 +124,19 @@ impl Spacing {
         }
     }
 
+    /// Get spacing by token name (`"md"`, `"xl"`, ...), as written in
+    /// serialized layouts
+    ///
+    /// Returns `None` for an unknown token.
+    pub fn get_token(&self, token: &str) -> Option<f32> {
+        SpacingName::from_token(token).map(|name| self.get(name))
+    }
+
+    /// [`get_token`](Self::get_token) multiplied by the appearance `ui_scale`
+    pub fn scaled(&self, token: &str, ui_scale: f32) -> Option<f32> {
+        self.get_token(token).map(|value| value * ui_scale)
+    }
+
     /// Get inset padding (all sides equal)
     pub fn inset(&self, name: SpacingName) -> Inset {
         let value = self.get(name);
@@ -194,6 +210,19 @@ impl SpacingName {
         ]
     }
 
+    /// Look up a spacing name by its token, the same lowercase name it
+    /// serializes and displays as
+    ///
+    /// Surrounding whitespace and case are ignored, so `" MD "` is
+    /// [`SpacingName::Md`]. Returns `None` for an unknown token.
+    pub fn from_token(token: &str) -> Option<SpacingName> {
+        let token = token.trim();
+        Self::all()
+            .iter()
+            .copied()
+            .find(|name| name.to_string().eq_ignore_ascii_case(token))
+    }
+
     /// Get the next larger spacing
     pub fn larger(&self) -> Option<SpacingName> {
         match self {
@@ -250,7 +279,7 @@ impl std::fmt::Display for SpacingName {
 ///
 /// Allows accessing spacing values by numeric index.
 /// Value = index * base (default base is 4px).
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct SpacingScale {
     /// Base unit for spacing (default 4px)
     pub base: f32,
@@ -524,7 +553,7 @@ impl Inset {
 // =============================================================================
 
 /// Size tokens for width/height
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Size {
     /// Extra extra small (16px)
     pub xxs: f32,
@@ -648,7 +677,7 @@ pub enum SizeName {
 // =============================================================================
 
 /// Responsive breakpoints for different screen sizes
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Breakpoints {
     /// Extra small screens (mobile portrait)
     pub xs: f32,
@@ -760,7 +789,7 @@ impl BreakpointName {
 // =============================================================================
 
 /// Z-index layers for stacking context
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct ZIndex {
     /// Base layer (0)
     pub base: i32,
@@ -1200,4 +1229,38 @@ mod tests {
 
         assert_eq!(inset, deserialized);
     }
+
+    #[test]
+    fn test_spacing_lookup_by_token() {
+        let spacing = Spacing::default();
+
+        assert_eq!(spacing.get_token("none"), Some(0.0));
+        assert_eq!(spacing.get_token("sm"), Some(8.0));
+        assert_eq!(spacing.get_token("md"), Some(16.0));
+        assert_eq!(spacing.get_token("huge"), Some(64.0));
+        assert_eq!(spacing.get_token(" XL "), Some(32.0));
+
+        // Every name resolves from the token it displays as
+        for &name in SpacingName::all() {
+            assert_eq!(SpacingName::from_token(&name.to_string()), Some(name));
+            assert_eq!(
+                spacing.get_token(&name.to_string()),
+                Some(spacing.get(name))
+            );
+        }
+
+        assert_eq!(spacing.get_token("medium"), None);
+        assert_eq!(spacing.get_token(""), None);
+        assert_eq!(SpacingName::from_token("xxxl"), None);
+    }
+
+    #[test]
+    fn test_spacing_scaled_by_token() {
+        let spacing = Spacing::default();
+
+        assert_eq!(spacing.scaled("md", 1.0), Some(16.0));
+        assert_eq!(spacing.scaled("md", 1.5), Some(24.0));
+        assert_eq!(spacing.scaled("xs", 2.0), Some(8.0));
+        assert_eq!(spacing.scaled("unknown", 2.0), None);
+    }
 }